@@ -26,6 +26,7 @@ use aws_sdk_kinesis::operation::{
     get_shard_iterator::GetShardIteratorError, list_shards::ListShardsError,
     list_streams::ListStreamsError, merge_shards::MergeShardsError, split_shard::SplitShardError,
 };
+use aws_sdk_s3::error::ProvideErrorMetadata;
 use aws_sdk_s3::operation::abort_multipart_upload::AbortMultipartUploadError;
 use aws_sdk_s3::operation::complete_multipart_upload::CompleteMultipartUploadError;
 use aws_sdk_s3::operation::create_multipart_upload::CreateMultipartUploadError;
@@ -33,6 +34,7 @@ use aws_sdk_s3::operation::delete_object::DeleteObjectError;
 use aws_sdk_s3::operation::delete_objects::DeleteObjectsError;
 use aws_sdk_s3::operation::get_object::GetObjectError;
 use aws_sdk_s3::operation::head_object::HeadObjectError;
+use aws_sdk_s3::operation::list_objects_v2::ListObjectsV2Error;
 use aws_sdk_s3::operation::put_object::PutObjectError;
 use aws_sdk_s3::operation::upload_part::UploadPartError;
 use aws_smithy_client::SdkError;
@@ -108,6 +110,24 @@ impl AwsRetryable for HeadObjectError {
     }
 }
 
+impl AwsRetryable for ListObjectsV2Error {
+    fn is_retryable(&self) -> bool {
+        // S3 reports throttled requests and server errors with these error codes.
+        matches!(
+            self.code(),
+            Some(
+                "SlowDown"
+                    | "ServiceUnavailable"
+                    | "InternalError"
+                    | "RequestTimeout"
+                    | "Throttling"
+                    | "ThrottlingException"
+                    | "RequestLimitExceeded"
+            )
+        )
+    }
+}
+
 #[cfg(feature = "kinesis")]
 impl AwsRetryable for GetRecordsError {
     fn is_retryable(&self) -> bool {
@@ -71,8 +71,22 @@ pub fn build_index_command() -> Command {
                 ])
             )
         .subcommand(
-            Command::new("clear")
+            Command::new("update")
                 .display_order(2)
+                .about("Updates an index from an index config file.")
+                .long_about("Updates the config of an existing index. The doc mapping can only be extended with new fields, while the indexing settings, search settings, and retention policy can be freely modified.")
+                .args(&[
+                    arg!(--index <INDEX> "ID of the target index")
+                        .display_order(1)
+                        .required(true),
+                    arg!(--"index-config" <INDEX_CONFIG> "Location of the index config file.")
+                        .display_order(2)
+                        .required(true),
+                ])
+            )
+        .subcommand(
+            Command::new("clear")
+                .display_order(3)
                 .alias("clr")
                 .about("Clears an index: deletes all splits and resets checkpoint.")
                 .long_about("Deletes all its splits and resets its checkpoint. This operation is destructive and cannot be undone, proceed with caution.")
@@ -84,7 +98,7 @@ pub fn build_index_command() -> Command {
             )
         .subcommand(
             Command::new("delete")
-                .display_order(3)
+                .display_order(4)
                 .alias("del")
                 .about("Deletes an index.")
                 .long_about("Deletes an index. This operation is destructive and cannot be undone, proceed with caution.")
@@ -98,7 +112,7 @@ pub fn build_index_command() -> Command {
             )
         .subcommand(
             Command::new("describe")
-                .display_order(4)
+                .display_order(5)
                 .about("Displays descriptive statistics of an index.")
                 .long_about("Displays descriptive statistics of an index. Displayed statistics are: number of published splits, number of documents, splits min/max timestamps, size of splits.")
                 .args(&[
@@ -109,12 +123,12 @@ pub fn build_index_command() -> Command {
         .subcommand(
             Command::new("list")
                 .alias("ls")
-                .display_order(5)
+                .display_order(6)
                 .about("List indexes.")
             )
         .subcommand(
             Command::new("ingest")
-                .display_order(6)
+                .display_order(7)
                 .about("Ingest NDJSON documents with the ingest API.")
                 .long_about("Reads NDJSON documents from a file or streamed from stdin and sends them into ingest API.")
                 .args(&[
@@ -145,7 +159,7 @@ pub fn build_index_command() -> Command {
             )
         .subcommand(
             Command::new("search")
-                .display_order(7)
+                .display_order(8)
                 .about("Searches an index.")
                 .args(&[
                     arg!(--index <INDEX> "ID of the target index")
@@ -174,6 +188,8 @@ pub fn build_index_command() -> Command {
                         .required(false),
                     arg!(--"sort-by-score" "Sorts documents by their BM25 score.")
                         .required(false),
+                    arg!(--profile "Returns a profile of the search along with the results: the lowered query, the pruned splits, and the timings and cache hits of each split.")
+                        .required(false),
                 ])
             )
         .arg_required_else_help(true)
@@ -194,6 +210,13 @@ pub struct CreateIndexArgs {
     pub assume_yes: bool,
 }
 
+#[derive(Debug, Eq, PartialEq)]
+pub struct UpdateIndexArgs {
+    pub client_args: ClientArgs,
+    pub index_id: String,
+    pub index_config_uri: Uri,
+}
+
 #[derive(Debug, Eq, PartialEq)]
 pub struct DescribeIndexArgs {
     pub client_args: ClientArgs,
@@ -222,6 +245,7 @@ pub struct SearchIndexArgs {
     pub start_timestamp: Option<i64>,
     pub end_timestamp: Option<i64>,
     pub sort_by_score: bool,
+    pub profile: bool,
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -246,6 +270,7 @@ pub enum IndexCliCommand {
     Ingest(IngestDocsArgs),
     List(ListIndexesArgs),
     Search(SearchIndexArgs),
+    Update(UpdateIndexArgs),
 }
 
 impl IndexCliCommand {
@@ -268,6 +293,7 @@ impl IndexCliCommand {
             "ingest" => Self::parse_ingest_args(submatches),
             "list" => Self::parse_list_args(submatches),
             "search" => Self::parse_search_args(submatches),
+            "update" => Self::parse_update_args(submatches),
             _ => bail!("unknown index subcommand `{subcommand}`"),
         }
     }
@@ -302,6 +328,23 @@ impl IndexCliCommand {
         }))
     }
 
+    fn parse_update_args(mut matches: ArgMatches) -> anyhow::Result<Self> {
+        let client_args = ClientArgs::parse(&mut matches)?;
+        let index_id = matches
+            .remove_one::<String>("index")
+            .expect("`index` should be a required arg.");
+        let index_config_uri = matches
+            .remove_one::<String>("index-config")
+            .map(|uri| Uri::from_str(&uri))
+            .expect("`index-config` should be a required arg.")?;
+
+        Ok(Self::Update(UpdateIndexArgs {
+            client_args,
+            index_id,
+            index_config_uri,
+        }))
+    }
+
     fn parse_describe_args(mut matches: ArgMatches) -> anyhow::Result<Self> {
         let client_args = ClientArgs::parse(&mut matches)?;
         let index_id = matches
@@ -379,6 +422,7 @@ impl IndexCliCommand {
             .remove_many::<String>("snippet-fields")
             .map(|values| values.collect());
         let sort_by_score = matches.get_flag("sort-by-score");
+        let profile = matches.get_flag("profile");
         let start_timestamp = matches
             .remove_one::<String>("start-timestamp")
             .map(|ts| ts.parse())
@@ -400,6 +444,7 @@ impl IndexCliCommand {
             end_timestamp,
             client_args,
             sort_by_score,
+            profile,
         }))
     }
 
@@ -427,6 +472,7 @@ impl IndexCliCommand {
             Self::Ingest(args) => ingest_docs_cli(args).await,
             Self::List(args) => list_index_cli(args).await,
             Self::Search(args) => search_index_cli(args).await,
+            Self::Update(args) => update_index_cli(args).await,
         }
     }
 }
@@ -476,6 +522,22 @@ pub async fn create_index_cli(args: CreateIndexArgs) -> anyhow::Result<()> {
     Ok(())
 }
 
+pub async fn update_index_cli(args: UpdateIndexArgs) -> anyhow::Result<()> {
+    debug!(args=?args, "update-index");
+    println!("❯ Updating index...");
+    let storage_resolver = StorageResolver::unconfigured();
+    let file_content = load_file(&storage_resolver, &args.index_config_uri).await?;
+    let config_format = ConfigFormat::sniff_from_uri(&args.index_config_uri)?;
+    let qw_client = args.client_args.client();
+    let bytes = Bytes::from(file_content.to_vec());
+    qw_client
+        .indexes()
+        .update(&args.index_id, bytes, config_format)
+        .await?;
+    println!("{} Index successfully updated.", "✔".color(GREEN_COLOR));
+    Ok(())
+}
+
 pub async fn list_index_cli(args: ListIndexesArgs) -> anyhow::Result<()> {
     debug!(args=?args, "list-index");
     let qw_client = args.client_args.client();
@@ -857,6 +919,7 @@ pub async fn search_index(args: SearchIndexArgs) -> anyhow::Result<SearchRespons
         start_offset: args.start_offset as u64,
         sort_by,
         count_all: CountHits::CountAll,
+        profile: args.profile,
         ..Default::default()
     };
     let qw_client = args.client_args.search_client();
@@ -96,7 +96,7 @@ mod tests {
     use quickwit_cli::cli::{build_cli, CliCommand};
     use quickwit_cli::index::{
         ClearIndexArgs, CreateIndexArgs, DeleteIndexArgs, DescribeIndexArgs, IndexCliCommand,
-        IngestDocsArgs, SearchIndexArgs,
+        IngestDocsArgs, SearchIndexArgs, UpdateIndexArgs,
     };
     use quickwit_cli::split::{DescribeSplitArgs, SplitCliCommand};
     use quickwit_cli::tool::{
@@ -181,6 +181,32 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_parse_update_args() -> anyhow::Result<()> {
+        let app = build_cli().no_binary_name(true);
+        let matches = app.try_get_matches_from([
+            "index",
+            "update",
+            "--index",
+            "wikipedia",
+            "--index-config",
+            "index-conf.yaml",
+        ])?;
+        let command = CliCommand::parse_cli_args(matches)?;
+        let expected_index_config_uri = Uri::from_str(&format!(
+            "file://{}/index-conf.yaml",
+            std::env::current_dir().unwrap().display()
+        ))
+        .unwrap();
+        let expected_cmd = CliCommand::Index(IndexCliCommand::Update(UpdateIndexArgs {
+            client_args: ClientArgs::default(),
+            index_id: "wikipedia".to_string(),
+            index_config_uri: expected_index_config_uri,
+        }));
+        assert_eq!(command, expected_cmd);
+        Ok(())
+    }
+
     #[test]
     fn test_parse_ingest_args() -> anyhow::Result<()> {
         let app = build_cli().no_binary_name(true);
@@ -431,6 +457,7 @@ mod tests {
             "url",
             "--snippet-fields",
             "body",
+            "--profile",
         ])?;
         let command = CliCommand::parse_cli_args(matches)?;
         assert!(matches!(
@@ -447,6 +474,7 @@ mod tests {
                 start_timestamp: Some(0),
                 end_timestamp: Some(1),
                 sort_by_score: false,
+                profile: true,
             })) if &index_id == "wikipedia"
                   && query == "Barack Obama"
                   && search_field_names == vec!["title".to_string(), "url".to_string()]
@@ -26,7 +26,12 @@ use colored::Colorize;
 use itertools::Itertools;
 use quickwit_common::uri::Uri;
 use quickwit_config::{validate_identifier, ConfigFormat, SourceConfig};
+use quickwit_indexing::dead_letter::{
+    check_dead_letter_replay, parse_dead_letter_docs, DeadLetterDoc,
+};
 use quickwit_metastore::checkpoint::SourceCheckpoint;
+use quickwit_rest_client::models::IngestSource;
+use quickwit_rest_client::rest_client::CommitType;
 use quickwit_storage::{load_file, StorageResolver};
 use serde_json::Value as JsonValue;
 use tabled::{Table, Tabled};
@@ -137,6 +142,39 @@ pub fn build_source_command() -> Command {
                         .required(true),
                 ])
             )
+        .subcommand(
+            Command::new("dead-letter")
+                .about("Prints the documents rejected by a source, recorded in its dead-letter index, as NDJSON.")
+                .args(&[
+                    arg!(--index <INDEX_ID> "ID of the target index")
+                        .display_order(1)
+                        .required(true),
+                    arg!(--source <SOURCE_ID> "ID of the source.")
+                        .display_order(2)
+                        .required(true),
+                    arg!(--"max-hits" <MAX_HITS> "Maximum number of documents returned.")
+                        .default_value("20")
+                        .required(false),
+                    arg!(--"start-offset" <OFFSET> "Offset of the first document returned.")
+                        .default_value("0")
+                        .required(false),
+                ])
+            )
+        .subcommand(
+            Command::new("replay-dead-letter")
+                .about("Ingests the documents rejected by a source back into the index.")
+                .long_about("Ingests the documents rejected by a source back into the index. By default, replays the documents of the dead-letter index of the source and deletes them from it. With `--input-path`, replays the documents of a dead-letter NDJSON file instead.")
+                .args(&[
+                    arg!(--index <INDEX_ID> "ID of the target index")
+                        .display_order(1)
+                        .required(true),
+                    arg!(--source <SOURCE_ID> "ID of the source.")
+                        .display_order(2)
+                        .required(true),
+                    arg!(--"input-path" <INPUT_PATH> "Location of a dead-letter NDJSON file, such as one written under the `dead-letter` directory of the index storage.")
+                        .required(false),
+                ])
+            )
         .arg_required_else_help(true)
 }
 
@@ -184,6 +222,23 @@ pub struct ResetCheckpointArgs {
     pub assume_yes: bool,
 }
 
+#[derive(Debug, Eq, PartialEq)]
+pub struct DeadLetterArgs {
+    pub client_args: ClientArgs,
+    pub index_id: String,
+    pub source_id: String,
+    pub max_hits: u64,
+    pub start_offset: u64,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct ReplayDeadLetterArgs {
+    pub client_args: ClientArgs,
+    pub index_id: String,
+    pub source_id: String,
+    pub input_uri_opt: Option<Uri>,
+}
+
 #[derive(Debug, Eq, PartialEq)]
 pub enum SourceCliCommand {
     CreateSource(CreateSourceArgs),
@@ -192,6 +247,8 @@ pub enum SourceCliCommand {
     DescribeSource(DescribeSourceArgs),
     ListSources(ListSourcesArgs),
     ResetCheckpoint(ResetCheckpointArgs),
+    DeadLetter(DeadLetterArgs),
+    ReplayDeadLetter(ReplayDeadLetterArgs),
 }
 
 impl SourceCliCommand {
@@ -203,6 +260,8 @@ impl SourceCliCommand {
             Self::DescribeSource(args) => describe_source_cli(args).await,
             Self::ListSources(args) => list_sources_cli(args).await,
             Self::ResetCheckpoint(args) => reset_checkpoint_cli(args).await,
+            Self::DeadLetter(args) => dead_letter_cli(args).await,
+            Self::ReplayDeadLetter(args) => replay_dead_letter_cli(args).await,
         }
     }
 
@@ -224,6 +283,10 @@ impl SourceCliCommand {
             "reset-checkpoint" => {
                 Self::parse_reset_checkpoint_args(submatches).map(Self::ResetCheckpoint)
             }
+            "dead-letter" => Self::parse_dead_letter_args(submatches).map(Self::DeadLetter),
+            "replay-dead-letter" => {
+                Self::parse_replay_dead_letter_args(submatches).map(Self::ReplayDeadLetter)
+            }
             _ => bail!("unknown source subcommand `{subcommand}`"),
         }
     }
@@ -323,6 +386,53 @@ impl SourceCliCommand {
             assume_yes,
         })
     }
+
+    fn parse_dead_letter_args(mut matches: ArgMatches) -> anyhow::Result<DeadLetterArgs> {
+        let client_args = ClientArgs::parse(&mut matches)?;
+        let index_id = matches
+            .remove_one::<String>("index")
+            .expect("`index` should be a required arg.");
+        let source_id = matches
+            .remove_one::<String>("source")
+            .expect("`source` should be a required arg.");
+        let max_hits = matches
+            .remove_one::<String>("max-hits")
+            .expect("`max-hits` should have a default value.")
+            .parse()?;
+        let start_offset = matches
+            .remove_one::<String>("start-offset")
+            .expect("`start-offset` should have a default value.")
+            .parse()?;
+        Ok(DeadLetterArgs {
+            client_args,
+            index_id,
+            source_id,
+            max_hits,
+            start_offset,
+        })
+    }
+
+    fn parse_replay_dead_letter_args(
+        mut matches: ArgMatches,
+    ) -> anyhow::Result<ReplayDeadLetterArgs> {
+        let client_args = ClientArgs::parse(&mut matches)?;
+        let index_id = matches
+            .remove_one::<String>("index")
+            .expect("`index` should be a required arg.");
+        let source_id = matches
+            .remove_one::<String>("source")
+            .expect("`source` should be a required arg.");
+        let input_uri_opt = matches
+            .remove_one::<String>("input-path")
+            .map(|uri_str| Uri::from_str(&uri_str))
+            .transpose()?;
+        Ok(ReplayDeadLetterArgs {
+            client_args,
+            index_id,
+            source_id,
+            input_uri_opt,
+        })
+    }
 }
 
 async fn create_source_cli(args: CreateSourceArgs) -> anyhow::Result<()> {
@@ -520,6 +630,97 @@ async fn reset_checkpoint_cli(args: ResetCheckpointArgs) -> anyhow::Result<()> {
     Ok(())
 }
 
+async fn dead_letter_cli(args: DeadLetterArgs) -> anyhow::Result<()> {
+    debug!(args=?args, "dead-letter-source");
+    let qw_client = args.client_args.client();
+    let dead_letter_search_response = qw_client
+        .sources(&args.index_id)
+        .dead_letter_docs(&args.source_id, args.max_hits, args.start_offset)
+        .await
+        .context("failed to fetch dead-letter documents")?;
+    for dead_letter_doc in dead_letter_search_response.docs {
+        println!("{}", serde_json::to_string(&dead_letter_doc)?);
+    }
+    Ok(())
+}
+
+async fn replay_dead_letter_cli(args: ReplayDeadLetterArgs) -> anyhow::Result<()> {
+    debug!(args=?args, "replay-dead-letter-source");
+    println!("❯ Replaying dead-letter documents...");
+    let Some(input_uri) = &args.input_uri_opt else {
+        let qw_client = args.client_args.client();
+        let dead_letter_replay_response = qw_client
+            .sources(&args.index_id)
+            .replay_dead_letter(&args.source_id)
+            .await
+            .context("failed to replay dead-letter documents")?;
+        println!(
+            "{} Replayed {} documents successfully.",
+            "✔".color(GREEN_COLOR),
+            dead_letter_replay_response.num_replayed_docs
+        );
+        return Ok(());
+    };
+    let storage_resolver = StorageResolver::unconfigured();
+    let ndjson = load_file(&storage_resolver, input_uri).await?;
+    let dead_letter_docs = parse_dead_letter_docs(ndjson.as_slice())?;
+    let doc_batch = make_replay_doc_batch(&dead_letter_docs, &args.source_id)?;
+    let num_docs = doc_batch.iter().filter(|byte| **byte == b'\n').count();
+
+    if num_docs > 0 {
+        let index_metadata = args
+            .client_args
+            .client()
+            .indexes()
+            .get(&args.index_id)
+            .await
+            .context("failed to fetch index metadata")?;
+        check_dead_letter_replay(&index_metadata, &args.source_id)?;
+
+        let qw_client = args.client_args.ingest_client();
+        qw_client
+            .ingest(
+                &args.index_id,
+                IngestSource::Bytes(Bytes::from(doc_batch)),
+                None,
+                None,
+                CommitType::Auto,
+            )
+            .await
+            .context("failed to replay dead-letter documents")?;
+    }
+    println!(
+        "{} Replayed {num_docs} documents successfully.",
+        "✔".color(GREEN_COLOR)
+    );
+    Ok(())
+}
+
+/// Builds the NDJSON batch of the payloads of the dead-letter documents of a source. Payloads are
+/// re-serialized so that each document fits on a single line.
+fn make_replay_doc_batch(
+    dead_letter_docs: &[DeadLetterDoc],
+    source_id: &str,
+) -> anyhow::Result<Vec<u8>> {
+    let mut doc_batch = Vec::new();
+    for dead_letter_doc in dead_letter_docs {
+        if dead_letter_doc.source_id != source_id {
+            continue;
+        }
+        let raw_doc = dead_letter_doc.raw_doc()?;
+        let json_doc: JsonValue = serde_json::from_slice(&raw_doc).with_context(|| {
+            format!(
+                "failed to replay dead-letter document rejected with `{}`: only JSON payloads can \
+                 be replayed",
+                dead_letter_doc.error_message
+            )
+        })?;
+        serde_json::to_writer(&mut doc_batch, &json_doc)?;
+        doc_batch.push(b'\n');
+    }
+    Ok(doc_batch)
+}
+
 /// Recursively flattens a JSON object into a vector of `(path, value)` tuples where `path`
 /// represents the full path of each property in the original object. For instance, `{"root": true,
 /// "parent": {"child": 0}}` yields `[("root", true), ("parent.child", 0)]`. Arrays are not
@@ -553,6 +754,7 @@ mod tests {
     use std::str::FromStr;
 
     use quickwit_config::{SourceInputFormat, SourceParams};
+    use quickwit_indexing::dead_letter::DeadLetterErrorKind;
     use quickwit_metastore::checkpoint::PartitionId;
     use quickwit_proto::types::Position;
     use serde_json::json;
@@ -723,6 +925,62 @@ mod tests {
         assert_eq!(command, expected_command);
     }
 
+    #[test]
+    fn test_parse_replay_dead_letter_args() {
+        let app = build_cli().no_binary_name(true);
+        let matches = app
+            .try_get_matches_from(vec![
+                "source",
+                "replay-dead-letter",
+                "--index",
+                "hdfs-logs",
+                "--source",
+                "hdfs-logs-source",
+                "--input-path",
+                "s3://my-bucket/hdfs-logs/dead-letter/hdfs-logs-source/my-file.ndjson",
+            ])
+            .unwrap();
+        let command = CliCommand::parse_cli_args(matches).unwrap();
+        let expected_command =
+            CliCommand::Source(SourceCliCommand::ReplayDeadLetter(ReplayDeadLetterArgs {
+                client_args: ClientArgs::default(),
+                index_id: "hdfs-logs".to_string(),
+                source_id: "hdfs-logs-source".to_string(),
+                input_uri_opt: Some(
+                    Uri::from_str(
+                        "s3://my-bucket/hdfs-logs/dead-letter/hdfs-logs-source/my-file.ndjson",
+                    )
+                    .unwrap(),
+                ),
+            }));
+        assert_eq!(command, expected_command);
+    }
+
+    #[test]
+    fn test_make_replay_doc_batch() {
+        let dead_letter_doc = |source_id: &str, raw_doc: &[u8]| {
+            DeadLetterDoc::new(
+                "hdfs-logs".to_string(),
+                source_id.to_string(),
+                None,
+                DeadLetterErrorKind::Schema,
+                "timestamp field is missing".to_string(),
+                raw_doc,
+            )
+        };
+        let dead_letter_docs = vec![
+            dead_letter_doc("hdfs-logs-source", b"{\n  \"body\": \"foo\"\n}"),
+            dead_letter_doc("other-source", br#"{"body": "bar"}"#),
+            dead_letter_doc("hdfs-logs-source", br#"{"body": "baz"}"#),
+        ];
+        let doc_batch = make_replay_doc_batch(&dead_letter_docs, "hdfs-logs-source").unwrap();
+        assert_eq!(doc_batch, b"{\"body\":\"foo\"}\n{\"body\":\"baz\"}\n");
+
+        let dead_letter_docs = vec![dead_letter_doc("hdfs-logs-source", b"not json")];
+        let error = make_replay_doc_batch(&dead_letter_docs, "hdfs-logs-source").unwrap_err();
+        assert!(error.to_string().contains("only JSON payloads"));
+    }
+
     #[test]
     fn test_make_describe_source_tables() {
         assert!(make_describe_source_tables(
@@ -744,6 +1002,8 @@ mod tests {
             source_params: SourceParams::file("path/to/file"),
             transform_config: None,
             input_format: SourceInputFormat::Json,
+            csv_columns_opt: None,
+            dead_letter_opt: None,
         }];
         let expected_source = vec![SourceRow {
             source_id: "foo-source".to_string(),
@@ -805,6 +1065,8 @@ mod tests {
                 source_params: SourceParams::stdin(),
                 transform_config: None,
                 input_format: SourceInputFormat::Json,
+                csv_columns_opt: None,
+                dead_letter_opt: None,
             },
             SourceConfig {
                 source_id: "bar-source".to_string(),
@@ -814,6 +1076,8 @@ mod tests {
                 source_params: SourceParams::stdin(),
                 transform_config: None,
                 input_format: SourceInputFormat::Json,
+                csv_columns_opt: None,
+                dead_letter_opt: None,
             },
         ];
         let expected_sources = [
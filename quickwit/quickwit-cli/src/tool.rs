@@ -79,9 +79,9 @@ pub fn build_tool_command() -> Command {
                     arg!(--index <INDEX> "ID of the target index")
                         .display_order(1)
                         .required(true),
-                    arg!(--"input-path" <INPUT_PATH> "Location of the input file.")
+                    arg!(--"input-path" <INPUT_PATH> "Location of the input file, or glob pattern matching several input files, such as `s3://bucket/logs/*.json.gz`.")
                         .required(false),
-                    arg!(--"input-format" <INPUT_FORMAT> "Format of the input data.")
+                    arg!(--"input-format" <INPUT_FORMAT> "Format of the input data: `json`, `plain`, `csv`, `logfmt`, or `syslog`.")
                         .default_value("json")
                         .required(false),
                     arg!(--overwrite "Overwrites pre-existing index.")
@@ -252,9 +252,13 @@ impl ToolCliCommand {
             .remove_one::<String>("index")
             .expect("`index` should be a required arg.");
         let input_path_opt = if let Some(input_path) = matches.remove_one::<String>("input-path") {
-            Uri::from_str(&input_path)?
+            let input_uri = Uri::from_str(&input_path)?;
+            // Remote files are designated by their URI, which the file source resolves.
+            let input_path = input_uri
                 .filepath()
                 .map(|path| path.to_path_buf())
+                .unwrap_or_else(|| PathBuf::from(input_uri.as_str()));
+            Some(input_path)
         } else {
             None
         };
@@ -427,6 +431,8 @@ pub async fn local_ingest_docs_cli(args: LocalIngestDocsArgs) -> anyhow::Result<
         source_params,
         transform_config,
         input_format: args.input_format,
+        csv_columns_opt: None,
+        dead_letter_opt: None,
     };
     run_index_checklist(
         &mut metastore,
@@ -546,12 +552,18 @@ pub async fn local_search_cli(args: LocalSearchArgs) -> anyhow::Result<()> {
         max_hits: args.max_hits as u64,
         search_fields: args.search_fields,
         snippet_fields: args.snippet_fields,
+        snippet_fragment_size: None,
+        snippet_num_fragments: None,
+        snippet_pre_tag: None,
+        snippet_post_tag: None,
         start_timestamp: args.start_timestamp,
         end_timestamp: args.end_timestamp,
         aggs,
         format: BodyFormat::Json,
         sort_by,
         count_all: CountHits::CountAll,
+        timeout: None,
+        profile: false,
     };
     let search_request =
         search_request_from_api_request(vec![args.index_id], search_request_query_string)?;
@@ -611,6 +623,8 @@ pub async fn merge_cli(args: MergeArgs) -> anyhow::Result<()> {
                 source_params: SourceParams::Vec(VecSourceParams::default()),
                 transform_config: None,
                 input_format: SourceInputFormat::Json,
+                csv_columns_opt: None,
+                dead_letter_opt: None,
             },
             pipeline_ord: 0,
         })
@@ -383,6 +383,7 @@ async fn test_cmd_search_aggregation() {
             ..Default::default()
         },
         sort_by_score: false,
+        profile: false,
     };
     let search_response = search_index(args).await.unwrap();
 
@@ -466,6 +467,7 @@ async fn test_cmd_search_with_snippets() -> Result<()> {
             ..Default::default()
         },
         sort_by_score: false,
+        profile: false,
     };
     let search_response = search_index(args).await.unwrap();
     assert_eq!(search_response.hits.len(), 1);
@@ -505,6 +507,7 @@ async fn test_search_index_cli() {
         start_timestamp: None,
         end_timestamp: None,
         sort_by_score: false,
+        profile: false,
     };
 
     local_ingest_docs(test_env.resource_files["logs"].as_path(), &test_env)
@@ -984,6 +987,7 @@ async fn test_all_with_s3_localstack_cli() {
         start_timestamp: None,
         end_timestamp: None,
         sort_by_score: false,
+        profile: false,
     };
 
     let search_res = search_index(args).await.unwrap();
@@ -19,25 +19,27 @@
 
 pub(crate) mod serialize;
 
+use std::collections::hash_map::DefaultHasher;
 use std::collections::BTreeSet;
+use std::hash::{Hash, Hasher};
 use std::num::NonZeroU32;
 use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
 
-use anyhow::Context;
+use anyhow::{bail, Context};
 use byte_unit::Byte;
 use chrono::Utc;
 use cron::Schedule;
 use humantime::parse_duration;
 use quickwit_common::uri::Uri;
 use quickwit_doc_mapper::{
-    DefaultDocMapper, DefaultDocMapperBuilder, DocMapper, FieldMappingEntry, Mode, ModeType,
-    QuickwitJsonOptions, TokenizerEntry,
+    validate_field_mappings_update, DefaultDocMapper, DefaultDocMapperBuilder, DocMapper,
+    FieldMappingEntry, Mode, ModeType, QuickwitJsonOptions, TokenizerEntry,
 };
 use quickwit_proto::types::IndexId;
 use serde::{Deserialize, Serialize};
-pub use serialize::load_index_config_from_user_config;
+pub use serialize::{load_index_config_from_user_config, load_index_config_update};
 
 use crate::index_config::serialize::VersionedIndexConfig;
 use crate::merge_policy_config::{MergePolicyConfig, StableLogMergePolicyConfig};
@@ -306,6 +308,74 @@ pub struct IndexConfig {
 }
 
 impl IndexConfig {
+    /// Checks that `new_index_config` is a valid update of this index config.
+    ///
+    /// The index ID and URI cannot change. The doc mapping can only be extended with new fields:
+    /// the settings that determine how existing splits were built (timestamp field, tag fields,
+    /// partitioning, mode, tokenizers) must remain identical. Indexing settings, search settings,
+    /// and retention policy can be freely modified.
+    pub fn validate_update(&self, new_index_config: &IndexConfig) -> anyhow::Result<()> {
+        if self.index_id != new_index_config.index_id {
+            bail!(
+                "index ID cannot be updated: expected `{}`, got `{}`",
+                self.index_id,
+                new_index_config.index_id
+            );
+        }
+        if self.index_uri != new_index_config.index_uri {
+            bail!(
+                "index URI cannot be updated: expected `{}`, got `{}`",
+                self.index_uri,
+                new_index_config.index_uri
+            );
+        }
+        let current_doc_mapping = &self.doc_mapping;
+        let new_doc_mapping = &new_index_config.doc_mapping;
+
+        if current_doc_mapping.timestamp_field != new_doc_mapping.timestamp_field {
+            bail!("`timestamp_field` cannot be updated");
+        }
+        if current_doc_mapping.tag_fields != new_doc_mapping.tag_fields {
+            bail!("`tag_fields` cannot be updated");
+        }
+        if current_doc_mapping.partition_key != new_doc_mapping.partition_key
+            || current_doc_mapping.max_num_partitions != new_doc_mapping.max_num_partitions
+        {
+            bail!("`partition_key` and `max_num_partitions` cannot be updated");
+        }
+        if current_doc_mapping.mode != new_doc_mapping.mode {
+            bail!("`mode` cannot be updated");
+        }
+        if current_doc_mapping.store_source != new_doc_mapping.store_source
+            || current_doc_mapping.index_field_presence != new_doc_mapping.index_field_presence
+        {
+            bail!("`store_source` and `index_field_presence` cannot be updated");
+        }
+        for current_tokenizer in &current_doc_mapping.tokenizers {
+            if !new_doc_mapping.tokenizers.contains(current_tokenizer) {
+                bail!(
+                    "tokenizer `{}` cannot be removed or modified",
+                    current_tokenizer.name
+                );
+            }
+        }
+        validate_field_mappings_update(
+            &current_doc_mapping.field_mappings,
+            &new_doc_mapping.field_mappings,
+        )?;
+        Ok(())
+    }
+
+    /// Returns a fingerprint of the index config. The control plane sends it to the indexers
+    /// along with the indexing plan so that they can detect that an index config was updated.
+    pub fn fingerprint(&self) -> u64 {
+        let index_config_json =
+            serde_json::to_vec(self).expect("index config should be JSON serializable");
+        let mut hasher = DefaultHasher::new();
+        index_config_json.hash(&mut hasher);
+        hasher.finish()
+    }
+
     #[cfg(any(test, feature = "testsuite"))]
     pub fn for_test(index_id: &str, index_uri: &str) -> Self {
         let index_uri = Uri::from_str(index_uri).unwrap();
@@ -916,4 +986,51 @@ mod tests {
         schedule_test_helper_fn("monthly");
         schedule_test_helper_fn("* * * ? * ?");
     }
+
+    #[test]
+    fn test_index_config_validate_update() {
+        let index_config = IndexConfig::for_test("test-index", "s3://test-index");
+        index_config.validate_update(&index_config).unwrap();
+
+        let mut new_index_config = index_config.clone();
+        new_index_config.indexing_settings.commit_timeout_secs = 5;
+        new_index_config.search_settings.default_search_fields = vec!["owner".to_string()];
+        new_index_config.retention_policy = Some(RetentionPolicy {
+            retention_period: "1 day".to_string(),
+            evaluation_schedule: "hourly".to_string(),
+        });
+        new_index_config
+            .doc_mapping
+            .field_mappings
+            .push(serde_json::from_str(r#"{"name": "severity", "type": "text"}"#).unwrap());
+        index_config.validate_update(&new_index_config).unwrap();
+
+        let mut new_index_config = index_config.clone();
+        new_index_config.index_uri = Uri::for_test("s3://other-index");
+        let error = index_config.validate_update(&new_index_config).unwrap_err();
+        assert!(error.to_string().contains("index URI cannot be updated"));
+
+        let mut new_index_config = index_config.clone();
+        new_index_config.doc_mapping.timestamp_field = Some("response_date".to_string());
+        let error = index_config.validate_update(&new_index_config).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("`timestamp_field` cannot be updated"));
+
+        let mut new_index_config = index_config.clone();
+        new_index_config.doc_mapping.field_mappings.remove(1);
+        let error = index_config.validate_update(&new_index_config).unwrap_err();
+        assert!(error.to_string().contains("`body` cannot be removed"));
+    }
+    #[test]
+    fn test_index_config_fingerprint() {
+        let index_config = IndexConfig::for_test("test-index", "s3://test-index");
+        assert_eq!(
+            index_config.fingerprint(),
+            index_config.clone().fingerprint()
+        );
+        let mut new_index_config = index_config.clone();
+        new_index_config.indexing_settings.commit_timeout_secs = 5;
+        assert_ne!(index_config.fingerprint(), new_index_config.fingerprint());
+    }
 }
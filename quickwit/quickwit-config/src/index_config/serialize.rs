@@ -60,6 +60,23 @@ pub fn load_index_config_from_user_config(
     index_config_for_serialization.validate_and_build(Some(default_index_root_uri))
 }
 
+/// Parses and validates an update of `current_index_config` as supplied by a user with a given
+/// [`ConfigFormat`] and config content. If the update does not specify `index_uri`, the URI of
+/// the current index config is kept.
+pub fn load_index_config_update(
+    config_format: ConfigFormat,
+    config_content: &[u8],
+    current_index_config: &IndexConfig,
+) -> anyhow::Result<IndexConfig> {
+    let versioned_index_config: VersionedIndexConfig = config_format.parse(config_content)?;
+    let mut index_config_for_serialization: IndexConfigForSerialization =
+        versioned_index_config.into();
+    if index_config_for_serialization.index_uri.is_none() {
+        index_config_for_serialization.index_uri = Some(current_index_config.index_uri.clone());
+    }
+    index_config_for_serialization.validate_and_build(None)
+}
+
 impl IndexConfigForSerialization {
     fn index_uri_or_fallback_to_default(
         &self,
@@ -255,4 +272,42 @@ mod test {
             assert_eq!(index_config.index_uri.as_str(), "s3://mybucket/hdfs-logs");
         }
     }
+
+    #[test]
+    fn test_load_index_config_update_keeps_current_index_uri() {
+        let current_index_config =
+            IndexConfig::for_test("hdfs-logs", "s3://custom-bucket/custom-hdfs-logs");
+        let config_yaml = r#"
+            version: 0.6
+            index_id: hdfs-logs
+            doc_mapping: {}
+        "#;
+        let index_config = load_index_config_update(
+            ConfigFormat::Yaml,
+            config_yaml.as_bytes(),
+            &current_index_config,
+        )
+        .unwrap();
+        assert_eq!(
+            index_config.index_uri.as_str(),
+            "s3://custom-bucket/custom-hdfs-logs"
+        );
+
+        let config_yaml = r#"
+            version: 0.6
+            index_id: hdfs-logs
+            index_uri: s3://other-bucket/hdfs-logs
+            doc_mapping: {}
+        "#;
+        let index_config = load_index_config_update(
+            ConfigFormat::Yaml,
+            config_yaml.as_bytes(),
+            &current_index_config,
+        )
+        .unwrap();
+        assert_eq!(
+            index_config.index_uri.as_str(),
+            "s3://other-bucket/hdfs-logs"
+        );
+    }
 }
@@ -43,17 +43,18 @@ mod templating;
 // See #2048
 use index_config::serialize::{IndexConfigV0_6, VersionedIndexConfig};
 pub use index_config::{
-    build_doc_mapper, load_index_config_from_user_config, DocMapping, IndexConfig,
-    IndexingResources, IndexingSettings, RetentionPolicy, SearchSettings,
+    build_doc_mapper, load_index_config_from_user_config, load_index_config_update, DocMapping,
+    IndexConfig, IndexingResources, IndexingSettings, RetentionPolicy, SearchSettings,
 };
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 use serde_json::Value as JsonValue;
 pub use source_config::{
-    load_source_config_from_user_config, FileSourceParams, GcpPubSubSourceParams,
-    KafkaSourceParams, KinesisSourceParams, PulsarSourceAuth, PulsarSourceParams, RegionOrEndpoint,
-    SourceConfig, SourceInputFormat, SourceParams, TransformConfig, VecSourceParams,
-    VoidSourceParams, CLI_INGEST_SOURCE_ID, INGEST_API_SOURCE_ID, INGEST_SOURCE_ID,
+    load_source_config_from_user_config, DeadLetterConfig, FileSourceParams, GcpPubSubSourceParams,
+    KafkaSchemaParams, KafkaSourceParams, KinesisSourceParams, PulsarSourceAuth,
+    PulsarSourceParams, RegionOrEndpoint, SourceConfig, SourceInputFormat, SourceParams,
+    TransformConfig, VecSourceParams, VoidSourceParams, CLI_INGEST_SOURCE_ID, INGEST_API_SOURCE_ID,
+    INGEST_SOURCE_ID,
 };
 use tracing::warn;
 
@@ -87,8 +88,10 @@ pub use crate::storage_config::{
     IndexConfigV0_6,
     SourceInputFormat,
     SourceParams,
+    DeadLetterConfig,
     FileSourceParams,
     GcpPubSubSourceParams,
+    KafkaSchemaParams,
     KafkaSourceParams,
     KinesisSourceParams,
     PulsarSourceParams,
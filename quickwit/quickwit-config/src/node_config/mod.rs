@@ -53,6 +53,10 @@ pub struct IndexerConfig {
     /// Protocol (OTLP).
     #[serde(default = "IndexerConfig::default_enable_otlp_endpoint")]
     pub enable_otlp_endpoint: bool,
+    /// Enables the Prometheus remote-write endpoint to ingest samples pushed by Prometheus
+    /// servers and agents.
+    #[serde(default = "IndexerConfig::default_enable_prometheus_endpoint")]
+    pub enable_prometheus_endpoint: bool,
     #[serde(default = "IndexerConfig::default_enable_cooperative_indexing")]
     pub enable_cooperative_indexing: bool,
 }
@@ -73,6 +77,10 @@ impl IndexerConfig {
         }
     }
 
+    fn default_enable_prometheus_endpoint() -> bool {
+        false
+    }
+
     fn default_max_concurrent_split_uploads() -> usize {
         12
     }
@@ -90,6 +98,7 @@ impl IndexerConfig {
         let indexer_config = IndexerConfig {
             enable_cooperative_indexing: false,
             enable_otlp_endpoint: true,
+            enable_prometheus_endpoint: false,
             split_store_max_num_bytes: Byte::from_bytes(1_000_000),
             split_store_max_num_splits: 3,
             max_concurrent_split_uploads: 4,
@@ -103,6 +112,7 @@ impl Default for IndexerConfig {
         Self {
             enable_cooperative_indexing: Self::default_enable_cooperative_indexing(),
             enable_otlp_endpoint: Self::default_enable_otlp_endpoint(),
+            enable_prometheus_endpoint: Self::default_enable_prometheus_endpoint(),
             split_store_max_num_bytes: Self::default_split_store_max_num_bytes(),
             split_store_max_num_splits: Self::default_split_store_max_num_splits(),
             max_concurrent_split_uploads: Self::default_max_concurrent_split_uploads(),
@@ -243,6 +253,14 @@ pub struct JaegerConfig {
     /// The maximum number of spans that can be retrieved in a single request.
     #[serde(default = "JaegerConfig::default_max_fetch_spans")]
     pub max_fetch_spans: NonZeroU64,
+    /// Enables the periodic computation of the service dependency graph into a summary index, so
+    /// that the dependencies endpoint does not have to scan the spans.
+    #[serde(default)]
+    pub enable_dependencies_summary: bool,
+    /// Width of the time windows the dependency graph is computed over when
+    /// `enable_dependencies_summary` is set.
+    #[serde(default = "JaegerConfig::default_dependencies_summary_interval_secs")]
+    dependencies_summary_interval_secs: NonZeroU64,
 }
 
 impl JaegerConfig {
@@ -254,6 +272,10 @@ impl JaegerConfig {
         Duration::from_secs(self.max_trace_duration_secs.get())
     }
 
+    pub fn dependencies_summary_interval(&self) -> Duration {
+        Duration::from_secs(self.dependencies_summary_interval_secs.get())
+    }
+
     fn default_enable_endpoint() -> bool {
         #[cfg(any(test, feature = "testsuite"))]
         {
@@ -276,6 +298,10 @@ impl JaegerConfig {
     fn default_max_fetch_spans() -> NonZeroU64 {
         NonZeroU64::new(10_000).unwrap() // 10k spans
     }
+
+    fn default_dependencies_summary_interval_secs() -> NonZeroU64 {
+        NonZeroU64::new(600).unwrap() // 10 minutes
+    }
 }
 
 impl Default for JaegerConfig {
@@ -285,6 +311,8 @@ impl Default for JaegerConfig {
             lookback_period_hours: Self::default_lookback_period_hours(),
             max_trace_duration_secs: Self::default_max_trace_duration_secs(),
             max_fetch_spans: Self::default_max_fetch_spans(),
+            enable_dependencies_summary: false,
+            dependencies_summary_interval_secs: Self::default_dependencies_summary_interval_secs(),
         }
     }
 }
@@ -481,6 +481,7 @@ mod tests {
             config.indexer_config,
             IndexerConfig {
                 enable_otlp_endpoint: true,
+                enable_prometheus_endpoint: false,
                 split_store_max_num_bytes: Byte::from_str("1T").unwrap(),
                 split_store_max_num_splits: 10_000,
                 max_concurrent_split_uploads: 8,
@@ -514,6 +515,8 @@ mod tests {
                 lookback_period_hours: NonZeroU64::new(24).unwrap(),
                 max_trace_duration_secs: NonZeroU64::new(600).unwrap(),
                 max_fetch_spans: NonZeroU64::new(1_000).unwrap(),
+                enable_dependencies_summary: true,
+                dependencies_summary_interval_secs: NonZeroU64::new(300).unwrap(),
             }
         );
         Ok(())
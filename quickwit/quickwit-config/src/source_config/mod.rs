@@ -87,6 +87,13 @@ pub struct SourceConfig {
     // Denotes the input data format.
     #[serde(default)]
     pub input_format: SourceInputFormat,
+
+    // Denotes the names of the columns of a CSV source. If `None`, they are read from the header
+    // row of the source.
+    pub csv_columns_opt: Option<Vec<String>>,
+
+    // Denotes where the documents rejected by the doc processor are recorded.
+    pub dead_letter_opt: Option<DeadLetterConfig>,
 }
 
 impl SourceConfig {
@@ -132,6 +139,8 @@ impl SourceConfig {
             source_params: SourceParams::Ingest,
             transform_config: None,
             input_format: SourceInputFormat::Json,
+            csv_columns_opt: None,
+            dead_letter_opt: None,
         }
     }
 
@@ -145,6 +154,8 @@ impl SourceConfig {
             source_params: SourceParams::IngestApi,
             transform_config: None,
             input_format: SourceInputFormat::Json,
+            csv_columns_opt: None,
+            dead_letter_opt: None,
         }
     }
 
@@ -158,6 +169,8 @@ impl SourceConfig {
             source_params: SourceParams::IngestCli,
             transform_config: None,
             input_format: SourceInputFormat::Json,
+            csv_columns_opt: None,
+            dead_letter_opt: None,
         }
     }
 
@@ -171,6 +184,8 @@ impl SourceConfig {
             source_params,
             transform_config: None,
             input_format: SourceInputFormat::Json,
+            csv_columns_opt: None,
+            dead_letter_opt: None,
         }
     }
 }
@@ -187,12 +202,15 @@ impl TestableForRegression for SourceConfig {
                 client_log_level: None,
                 client_params: serde_json::json!({}),
                 enable_backfill_mode: false,
+                schema: None,
             }),
             transform_config: Some(TransformConfig {
                 vrl_script: ".message = downcase(string!(.message))".to_string(),
                 timezone: default_timezone(),
             }),
             input_format: SourceInputFormat::Json,
+            csv_columns_opt: None,
+            dead_letter_opt: None,
         }
     }
 
@@ -211,6 +229,11 @@ pub enum SourceInputFormat {
     OtlpTraceProtobuf,
     #[serde(alias = "plain")]
     PlainText,
+    Csv,
+    Logfmt,
+    Syslog,
+    Avro,
+    Protobuf,
 }
 
 impl FromStr for SourceInputFormat {
@@ -220,11 +243,25 @@ impl FromStr for SourceInputFormat {
         match format_str {
             "json" => Ok(Self::Json),
             "plain" => Ok(Self::PlainText),
+            "csv" => Ok(Self::Csv),
+            "logfmt" => Ok(Self::Logfmt),
+            "syslog" => Ok(Self::Syslog),
             unknown => Err(format!("unknown source input format: `{unknown}`")),
         }
     }
 }
 
+/// Destination of the documents rejected by the doc processor of a source.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DeadLetterConfig {
+    /// The rejected documents are ingested into a sibling index via the ingest API.
+    Index { index_id: String },
+    /// The rejected documents are written as NDJSON files under the storage of the index, in the
+    /// `dead-letter/<source_id>` directory.
+    Storage,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
 #[serde(tag = "source_type", content = "params", rename_all = "snake_case")]
 pub enum SourceParams {
@@ -259,7 +296,8 @@ impl SourceParams {
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
 #[serde(deny_unknown_fields)]
 pub struct FileSourceParams {
-    /// Path of the file to read. Assume stdin if None.
+    /// Path or URI of the file to read, which may contain glob patterns to read several files.
+    /// Assume stdin if None.
     #[schema(value_type = String)]
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(default)]
@@ -312,6 +350,29 @@ pub struct KafkaSourceParams {
     #[serde(default)]
     #[serde(skip_serializing_if = "is_false")]
     pub enable_backfill_mode: bool,
+    /// Writer schemas of the messages, for the `avro` and `protobuf` input formats.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema: Option<KafkaSchemaParams>,
+}
+
+/// Writer schemas of the Avro or Protobuf messages of a Kafka source. The messages must be framed
+/// with the Confluent wire format: a zero magic byte and the 4-byte schema ID, followed for
+/// Protobuf by the indexes of the message type, then the encoded message.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum KafkaSchemaParams {
+    /// The writer schemas are fetched by their ID from a Confluent schema registry, and cached.
+    Registry { url: String },
+    /// Inline Avro writer schema, in JSON. The schema ID of the messages is ignored.
+    Avro { schema: String },
+    /// Inline Protobuf descriptor set, i.e. a base64-encoded `FileDescriptorSet` including the
+    /// imported files, and the full name of the message type. The schema ID and the message
+    /// indexes of the messages are ignored.
+    DescriptorSet {
+        descriptor_set: String,
+        message_type: String,
+    },
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
@@ -579,12 +640,15 @@ mod tests {
                 client_log_level: None,
                 client_params: json! {{"bootstrap.servers": "localhost:9092"}},
                 enable_backfill_mode: false,
+                schema: None,
             }),
             transform_config: Some(TransformConfig {
                 vrl_script: ".message = downcase(string!(.message))".to_string(),
                 timezone: "local".to_string(),
             }),
             input_format: SourceInputFormat::Json,
+            csv_columns_opt: None,
+            dead_letter_opt: None,
         };
         assert_eq!(source_config, expected_source_config);
         assert_eq!(source_config.desired_num_pipelines.get(), 2);
@@ -598,6 +662,7 @@ mod tests {
                 client_log_level: None,
                 client_params: json!(null),
                 enable_backfill_mode: false,
+                schema: None,
             };
             let params_yaml = serde_yaml::to_string(&params).unwrap();
 
@@ -612,6 +677,7 @@ mod tests {
                 client_log_level: Some("info".to_string()),
                 client_params: json! {{"bootstrap.servers": "localhost:9092"}},
                 enable_backfill_mode: false,
+                schema: None,
             };
             let params_yaml = serde_yaml::to_string(&params).unwrap();
 
@@ -635,6 +701,7 @@ mod tests {
                     client_log_level: None,
                     client_params: json!(null),
                     enable_backfill_mode: false,
+                    schema: None,
                 }
             );
         }
@@ -653,11 +720,143 @@ mod tests {
                     client_log_level: Some("info".to_string()),
                     client_params: json! {{"bootstrap.servers": "localhost:9092"}},
                     enable_backfill_mode: true,
+                    schema: None,
                 }
             );
         }
     }
 
+    #[test]
+    fn test_kafka_source_params_with_schema_deserialization() {
+        {
+            let yaml = r#"
+                    topic: my-topic
+                    schema:
+                        type: registry
+                        url: http://localhost:8081
+                "#;
+            assert_eq!(
+                serde_yaml::from_str::<KafkaSourceParams>(yaml)
+                    .unwrap()
+                    .schema
+                    .unwrap(),
+                KafkaSchemaParams::Registry {
+                    url: "http://localhost:8081".to_string()
+                }
+            );
+        }
+        {
+            let yaml = r#"
+                    topic: my-topic
+                    schema:
+                        type: descriptor_set
+                        descriptor_set: Cgdmb28ucHJvdG8=
+                        message_type: acme.Log
+                "#;
+            assert_eq!(
+                serde_yaml::from_str::<KafkaSourceParams>(yaml)
+                    .unwrap()
+                    .schema
+                    .unwrap(),
+                KafkaSchemaParams::DescriptorSet {
+                    descriptor_set: "Cgdmb28ucHJvdG8=".to_string(),
+                    message_type: "acme.Log".to_string(),
+                }
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_load_kafka_source_config_with_avro_input_format() {
+        {
+            let content = r#"
+            {
+                "version": "0.6",
+                "source_id": "kafka-source",
+                "source_type": "kafka",
+                "params": {
+                    "topic": "my-topic",
+                    "schema": {"type": "registry", "url": "http://localhost:8081"}
+                },
+                "input_format": "avro"
+            }
+            "#;
+            let source_config =
+                load_source_config_from_user_config(ConfigFormat::Json, content.as_bytes())
+                    .unwrap();
+            assert_eq!(source_config.input_format, SourceInputFormat::Avro);
+        }
+        {
+            let content = r#"
+            {
+                "version": "0.6",
+                "source_id": "kafka-source",
+                "source_type": "kafka",
+                "params": {"topic": "my-topic"},
+                "input_format": "avro"
+            }
+            "#;
+            let error = load_source_config_from_user_config(ConfigFormat::Json, content.as_bytes())
+                .unwrap_err();
+            assert!(error
+                .to_string()
+                .contains("requires a `registry` or `avro` schema"));
+        }
+        {
+            let content = r#"
+            {
+                "version": "0.6",
+                "source_id": "kafka-source",
+                "source_type": "kafka",
+                "params": {
+                    "topic": "my-topic",
+                    "schema": {"type": "avro", "schema": "\"string\""}
+                },
+                "input_format": "protobuf"
+            }
+            "#;
+            let error = load_source_config_from_user_config(ConfigFormat::Json, content.as_bytes())
+                .unwrap_err();
+            assert!(error
+                .to_string()
+                .contains("requires a `registry` or `descriptor_set` schema"));
+        }
+        {
+            let content = r#"
+            {
+                "version": "0.6",
+                "source_id": "kafka-source",
+                "source_type": "kafka",
+                "params": {
+                    "topic": "my-topic",
+                    "schema": {"type": "registry", "url": "http://localhost:8081"}
+                }
+            }
+            "#;
+            let error = load_source_config_from_user_config(ConfigFormat::Json, content.as_bytes())
+                .unwrap_err();
+            assert!(error
+                .to_string()
+                .contains("requires the `avro` or `protobuf` input format"));
+        }
+        {
+            let content = r#"
+            {
+                "version": "0.6",
+                "source_id": "void-source",
+                "source_type": "void",
+                "params": {},
+                "input_format": "protobuf"
+            }
+            "#;
+            let error = load_source_config_from_user_config(ConfigFormat::Json, content.as_bytes())
+                .unwrap_err();
+            assert!(error
+                .to_string()
+                .contains("only supported for Kafka sources"));
+        }
+    }
+
     #[tokio::test]
     async fn test_load_kinesis_source_config() {
         let source_config_filepath = get_source_config_filepath("kinesis-source.yaml");
@@ -681,6 +880,8 @@ mod tests {
                 timezone: "local".to_string(),
             }),
             input_format: SourceInputFormat::Json,
+            csv_columns_opt: None,
+            dead_letter_opt: None,
         };
         assert_eq!(source_config, expected_source_config);
         assert_eq!(source_config.desired_num_pipelines.get(), 1);
@@ -754,6 +955,74 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_load_source_config_with_dead_letter() {
+        {
+            let content = r#"
+            {
+                "version": "0.6",
+                "source_id": "hdfs-logs-void-source",
+                "source_type": "void",
+                "params": {},
+                "dead_letter": {
+                    "type": "index",
+                    "index_id": "hdfs-logs-dead-letter"
+                }
+            }
+            "#;
+            let source_config =
+                load_source_config_from_user_config(ConfigFormat::Json, content.as_bytes())
+                    .unwrap();
+            assert_eq!(
+                source_config.dead_letter_opt,
+                Some(DeadLetterConfig::Index {
+                    index_id: "hdfs-logs-dead-letter".to_string()
+                })
+            );
+            let source_config_json = serde_json::to_value(&source_config).unwrap();
+            assert_eq!(
+                source_config_json["dead_letter"],
+                json!({"type": "index", "index_id": "hdfs-logs-dead-letter"})
+            );
+        }
+        {
+            let content = r#"
+            {
+                "version": "0.6",
+                "source_id": "hdfs-logs-void-source",
+                "source_type": "void",
+                "params": {},
+                "dead_letter": {
+                    "type": "storage"
+                }
+            }
+            "#;
+            let source_config =
+                load_source_config_from_user_config(ConfigFormat::Json, content.as_bytes())
+                    .unwrap();
+            assert_eq!(
+                source_config.dead_letter_opt,
+                Some(DeadLetterConfig::Storage)
+            );
+        }
+        {
+            let content = r#"
+            {
+                "version": "0.6",
+                "source_id": "hdfs-logs-void-source",
+                "source_type": "void",
+                "params": {},
+                "dead_letter": {
+                    "type": "index",
+                    "index_id": "my dead letter index"
+                }
+            }
+            "#;
+            load_source_config_from_user_config(ConfigFormat::Json, content.as_bytes())
+                .unwrap_err();
+        }
+    }
+
     #[tokio::test]
     async fn test_load_valid_distributed_source_config() {
         {
@@ -1085,6 +1354,8 @@ mod tests {
                 timezone: default_timezone(),
             }),
             input_format: SourceInputFormat::Json,
+            csv_columns_opt: None,
+            dead_letter_opt: None,
         };
         assert_eq!(source_config, expected_source_config);
         assert_eq!(source_config.desired_num_pipelines.get(), 1);
@@ -1204,4 +1475,86 @@ mod tests {
                 .unwrap();
         assert_eq!(source_config.input_format, SourceInputFormat::PlainText);
     }
+
+    #[tokio::test]
+    async fn test_source_config_csv_input_format() {
+        {
+            let file_content = r#"{
+                "version": "0.6",
+                "source_id": "logs-file-source",
+                "source_type": "file",
+                "params": {"filepath": "/test_corpus.csv"},
+                "input_format": "csv",
+                "csv_columns": ["timestamp", "severity", "message"]
+            }"#;
+            let source_config =
+                load_source_config_from_user_config(ConfigFormat::Json, file_content.as_bytes())
+                    .unwrap();
+            assert_eq!(source_config.input_format, SourceInputFormat::Csv);
+            assert_eq!(
+                source_config.csv_columns_opt.unwrap(),
+                ["timestamp", "severity", "message"]
+            );
+        }
+        {
+            let file_content = r#"{
+                "version": "0.6",
+                "source_id": "logs-file-source",
+                "source_type": "file",
+                "params": {"filepath": "/test_corpus.csv"},
+                "input_format": "csv"
+            }"#;
+            let source_config =
+                load_source_config_from_user_config(ConfigFormat::Json, file_content.as_bytes())
+                    .unwrap();
+            assert_eq!(source_config.input_format, SourceInputFormat::Csv);
+            assert!(source_config.csv_columns_opt.is_none());
+        }
+        {
+            let file_content = r#"{
+                "version": "0.6",
+                "source_id": "logs-file-source",
+                "source_type": "file",
+                "params": {"filepath": "/test_corpus.log"},
+                "input_format": "logfmt",
+                "csv_columns": ["timestamp", "message"]
+            }"#;
+            let error =
+                load_source_config_from_user_config(ConfigFormat::Json, file_content.as_bytes())
+                    .unwrap_err();
+            assert!(error
+                .to_string()
+                .contains("only supported for the `csv` input format"));
+        }
+        {
+            let file_content = r#"{
+                "version": "0.6",
+                "source_id": "logs-kafka-source",
+                "source_type": "kafka",
+                "params": {"topic": "logs", "client_params": {}},
+                "input_format": "csv"
+            }"#;
+            let error =
+                load_source_config_from_user_config(ConfigFormat::Json, file_content.as_bytes())
+                    .unwrap_err();
+            assert!(error.to_string().contains("requires `csv_columns`"));
+        }
+    }
+
+    #[test]
+    fn test_source_input_format_from_str() {
+        assert_eq!(
+            SourceInputFormat::from_str("csv").unwrap(),
+            SourceInputFormat::Csv
+        );
+        assert_eq!(
+            SourceInputFormat::from_str("logfmt").unwrap(),
+            SourceInputFormat::Logfmt
+        );
+        assert_eq!(
+            SourceInputFormat::from_str("syslog").unwrap(),
+            SourceInputFormat::Syslog
+        );
+        assert!(SourceInputFormat::from_str("xml").is_err());
+    }
 }
@@ -22,7 +22,7 @@ use std::num::NonZeroUsize;
 use anyhow::bail;
 use serde::{Deserialize, Serialize};
 
-use super::{TransformConfig, RESERVED_SOURCE_IDS};
+use super::{DeadLetterConfig, KafkaSchemaParams, TransformConfig, RESERVED_SOURCE_IDS};
 use crate::{validate_identifier, ConfigFormat, SourceConfig, SourceInputFormat, SourceParams};
 
 type SourceConfigForSerialization = SourceConfigV0_6;
@@ -116,6 +116,56 @@ impl SourceConfigForSerialization {
             }
             transform_config.validate_vrl_script()?;
         }
+        match (&self.source_params, self.input_format) {
+            (SourceParams::Kafka(kafka_params), SourceInputFormat::Avro) => {
+                match &kafka_params.schema {
+                    Some(KafkaSchemaParams::Registry { .. } | KafkaSchemaParams::Avro { .. }) => {}
+                    _ => bail!(
+                        "the `avro` input format requires a `registry` or `avro` schema in the \
+                         Kafka source parameters"
+                    ),
+                }
+            }
+            (SourceParams::Kafka(kafka_params), SourceInputFormat::Protobuf) => match &kafka_params
+                .schema
+            {
+                Some(
+                    KafkaSchemaParams::Registry { .. } | KafkaSchemaParams::DescriptorSet { .. },
+                ) => {}
+                _ => bail!(
+                    "the `protobuf` input format requires a `registry` or `descriptor_set` schema \
+                     in the Kafka source parameters"
+                ),
+            },
+            (_, SourceInputFormat::Avro | SourceInputFormat::Protobuf) => {
+                bail!(
+                    "the `avro` and `protobuf` input formats are only supported for Kafka sources"
+                )
+            }
+            (SourceParams::Kafka(kafka_params), _) if kafka_params.schema.is_some() => {
+                bail!("a Kafka source `schema` requires the `avro` or `protobuf` input format")
+            }
+            _ => {}
+        }
+        if let Some(csv_columns) = &self.csv_columns {
+            if self.input_format != SourceInputFormat::Csv {
+                bail!("`csv_columns` is only supported for the `csv` input format");
+            }
+            if csv_columns.is_empty() {
+                bail!("`csv_columns` must not be empty");
+            }
+        } else if self.input_format == SourceInputFormat::Csv
+            && !matches!(self.source_params, SourceParams::File(_))
+        {
+            // Only file sources read the header row of each file, other sources would take
+            // whichever record they happen to receive first for the header row.
+            bail!(
+                "the `csv` input format requires `csv_columns` for sources other than file sources"
+            );
+        }
+        if let Some(DeadLetterConfig::Index { index_id }) = &self.dead_letter {
+            validate_identifier("Dead letter index ID", index_id)?;
+        }
 
         Ok(SourceConfig {
             source_id: self.source_id,
@@ -125,6 +175,8 @@ impl SourceConfigForSerialization {
             source_params: self.source_params,
             transform_config: self.transform,
             input_format: self.input_format,
+            csv_columns_opt: self.csv_columns,
+            dead_letter_opt: self.dead_letter,
         })
     }
 }
@@ -139,6 +191,8 @@ impl From<SourceConfig> for SourceConfigV0_6 {
             source_params: source_config.source_params,
             transform: source_config.transform_config,
             input_format: source_config.input_format,
+            csv_columns: source_config.csv_columns_opt,
+            dead_letter: source_config.dead_letter_opt,
         }
     }
 }
@@ -196,4 +250,13 @@ pub struct SourceConfigV0_6 {
     // Denotes the input data format.
     #[serde(default)]
     pub input_format: SourceInputFormat,
+
+    // Denotes the names of the columns of a CSV source.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub csv_columns: Option<Vec<String>>,
+
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dead_letter: Option<DeadLetterConfig>,
 }
@@ -26,15 +26,16 @@ use quickwit_actors::{
 };
 use quickwit_config::SourceConfig;
 use quickwit_ingest::IngesterPool;
-use quickwit_metastore::IndexMetadata;
+use quickwit_metastore::{IndexMetadata, IndexMetadataResponseExt};
 use quickwit_proto::control_plane::{
     ControlPlaneError, ControlPlaneResult, GetOrCreateOpenShardsRequest,
     GetOrCreateOpenShardsResponse,
 };
 use quickwit_proto::metastore::{
     serde_utils as metastore_serde_utils, AddSourceRequest, CreateIndexRequest,
-    CreateIndexResponse, DeleteIndexRequest, DeleteSourceRequest, EmptyResponse, MetastoreError,
-    MetastoreService, MetastoreServiceClient, ToggleSourceRequest,
+    CreateIndexResponse, DeleteIndexRequest, DeleteSourceRequest, EmptyResponse,
+    IndexMetadataResponse, MetastoreError, MetastoreService, MetastoreServiceClient,
+    ToggleSourceRequest, UpdateIndexRequest,
 };
 use quickwit_proto::types::{IndexUid, NodeId};
 use serde::Serialize;
@@ -240,6 +241,35 @@ impl Handler<CreateIndexRequest> for ControlPlane {
     }
 }
 
+// This handler is a metastore call proxied through the control plane: we must first forward the
+// request to the metastore, and then act on the event.
+#[async_trait]
+impl Handler<UpdateIndexRequest> for ControlPlane {
+    type Reply = ControlPlaneResult<IndexMetadataResponse>;
+
+    async fn handle(
+        &mut self,
+        request: UpdateIndexRequest,
+        _ctx: &ActorContext<Self>,
+    ) -> Result<Self::Reply, ActorExitStatus> {
+        let response = match self.metastore.update_index(request).await {
+            Ok(response) => response,
+            Err(metastore_error) => return convert_metastore_error(metastore_error),
+        };
+        let index_metadata = response
+            .deserialize_index_metadata()
+            .context("failed to deserialize index metadata")?;
+
+        self.model.update_index(index_metadata);
+
+        // The plan is unchanged, but it now carries the fingerprint of the new index config: the
+        // indexers restart the pipelines of the index when they receive it.
+        self.indexing_scheduler.reapply_last_plan(&self.model);
+
+        Ok(Ok(response))
+    }
+}
+
 // This handler is a metastore call proxied through the control plane: we must first forward the
 // request to the metastore, and then act on the event.
 #[async_trait]
@@ -462,6 +492,55 @@ mod tests {
         universe.assert_quit().await;
     }
 
+    #[tokio::test]
+    async fn test_control_plane_update_index() {
+        let universe = Universe::with_accelerated_time();
+
+        let cluster_id = "test-cluster".to_string();
+        let self_node_id: NodeId = "test-node".into();
+        let indexer_pool = IndexerPool::default();
+        let ingester_pool = IngesterPool::default();
+
+        let mut mock_metastore = MetastoreServiceClient::mock();
+        mock_metastore
+            .expect_update_index()
+            .withf(|update_index_request| update_index_request.index_uid == "test-index:0")
+            .returning(|_| {
+                let index_metadata = IndexMetadata::for_test("test-index", "ram:///test-index");
+                Ok(IndexMetadataResponse::try_from_index_metadata(index_metadata).unwrap())
+            });
+        mock_metastore
+            .expect_list_indexes_metadata()
+            .returning(|_| {
+                Ok(ListIndexesMetadataResponse::try_from_indexes_metadata(Vec::new()).unwrap())
+            });
+        let replication_factor = 1;
+
+        let (control_plane_mailbox, _control_plane_handle) = ControlPlane::spawn(
+            &universe,
+            cluster_id,
+            self_node_id,
+            indexer_pool,
+            ingester_pool,
+            MetastoreServiceClient::from(mock_metastore),
+            replication_factor,
+        );
+        let index_config = IndexConfig::for_test("test-index", "ram:///test-index");
+        let update_index_request = UpdateIndexRequest {
+            index_uid: "test-index:0".to_string(),
+            index_config_json: serde_json::to_string(&index_config).unwrap(),
+        };
+        let index_metadata = control_plane_mailbox
+            .ask_for_res(update_index_request)
+            .await
+            .unwrap()
+            .deserialize_index_metadata()
+            .unwrap();
+        assert_eq!(index_metadata.index_id(), "test-index");
+
+        universe.assert_quit().await;
+    }
+
     #[tokio::test]
     async fn test_control_plane_delete_index() {
         let universe = Universe::with_accelerated_time();
@@ -221,6 +221,18 @@ impl ControlPlaneModel {
         self.index_table.insert(index_uid, index_metadata);
     }
 
+    /// Replaces the metadata of an index whose config was updated.
+    pub(crate) fn update_index(&mut self, index_metadata: IndexMetadata) {
+        let index_uid = index_metadata.index_uid.clone();
+        self.index_table.insert(index_uid, index_metadata);
+    }
+
+    /// Returns the fingerprint of the config of the given index.
+    pub(crate) fn index_config_fingerprint(&self, index_uid: &IndexUid) -> Option<u64> {
+        let index_metadata = self.index_table.get(index_uid)?;
+        Some(index_metadata.index_config.fingerprint())
+    }
+
     pub(crate) fn delete_index(&mut self, index_uid: &IndexUid) {
         // TODO: We need to let the routers and ingesters know.
         self.index_table.remove(index_uid);
@@ -20,6 +20,7 @@
 mod scheduling;
 
 use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::fmt;
 use std::num::NonZeroU32;
 use std::time::{Duration, Instant};
@@ -28,7 +29,7 @@ use fnv::{FnvHashMap, FnvHashSet};
 use itertools::Itertools;
 use quickwit_proto::indexing::{ApplyIndexingPlanRequest, IndexingService, IndexingTask};
 use quickwit_proto::metastore::SourceType;
-use quickwit_proto::types::NodeId;
+use quickwit_proto::types::{IndexUid, NodeId};
 use scheduling::{SourceToSchedule, SourceToScheduleType};
 use serde::Serialize;
 use tracing::{debug, error, info, warn};
@@ -216,10 +217,30 @@ impl IndexingScheduler {
                 return;
             }
         }
-        self.apply_physical_indexing_plan(&mut indexers, new_physical_plan);
+        self.apply_physical_indexing_plan(&mut indexers, new_physical_plan, model);
         self.state.num_schedule_indexing_plan += 1;
     }
 
+    /// Sends the last applied plan to the indexers again, along with the current index config
+    /// fingerprints, so that they restart the pipelines of the indexes whose config was updated.
+    pub(crate) fn reapply_last_plan(&mut self, model: &ControlPlaneModel) {
+        let Some(last_applied_plan) = self.state.last_applied_physical_plan.clone() else {
+            // No plan was applied yet: the indexers will receive the fingerprints with the first
+            // one.
+            return;
+        };
+        let mut indexers = self.get_indexers_from_indexer_pool();
+        let has_same_nodes = last_applied_plan
+            .indexing_tasks_per_indexer()
+            .keys()
+            .all(|node_id| indexers.iter().any(|(indexer_id, _)| indexer_id == node_id));
+        if has_same_nodes {
+            self.apply_physical_indexing_plan(&mut indexers, last_applied_plan, model);
+        } else {
+            self.schedule_indexing_plan_if_needed(model);
+        }
+    }
+
     /// Checks if the last applied plan corresponds to the running indexing tasks present in the
     /// chitchat cluster state. If true, do nothing.
     /// - If node IDs differ, schedule a new indexing plan.
@@ -260,7 +281,7 @@ impl IndexingScheduler {
         } else if !indexing_plans_diff.has_same_tasks() {
             // Some nodes may have not received their tasks, apply it again.
             info!(plans_diff=?indexing_plans_diff, "Running tasks and last applied tasks differ: reapply last plan.");
-            self.apply_physical_indexing_plan(&mut indexers, last_applied_plan.clone());
+            self.apply_physical_indexing_plan(&mut indexers, last_applied_plan.clone(), model);
         }
     }
 
@@ -272,9 +293,18 @@ impl IndexingScheduler {
         &mut self,
         indexers: &mut [(String, IndexerNodeInfo)],
         new_physical_plan: PhysicalIndexingPlan,
+        model: &ControlPlaneModel,
     ) {
         debug!("Apply physical indexing plan: {:?}", new_physical_plan);
         for (node_id, indexing_tasks) in new_physical_plan.indexing_tasks_per_indexer() {
+            let index_config_fingerprints: HashMap<String, u64> = indexing_tasks
+                .iter()
+                .flat_map(|indexing_task| {
+                    let index_uid = IndexUid::from(indexing_task.index_uid.clone());
+                    let fingerprint = model.index_config_fingerprint(&index_uid)?;
+                    Some((indexing_task.index_uid.clone(), fingerprint))
+                })
+                .collect();
             // We don't want to block on a slow indexer so we apply this change asynchronously
             // TODO not blocking is cool, but we need to make sure there is not accumulation
             // possible here.
@@ -290,7 +320,10 @@ impl IndexingScheduler {
                         .1
                         .client
                         .clone()
-                        .apply_indexing_plan(ApplyIndexingPlanRequest { indexing_tasks })
+                        .apply_indexing_plan(ApplyIndexingPlanRequest {
+                            indexing_tasks,
+                            index_config_fingerprints,
+                        })
                         .await
                     {
                         error!(indexer_node_id=%indexer.0, err=?error, "Error occurred when applying indexing plan to indexer.");
@@ -633,6 +666,7 @@ mod tests {
             client_log_level: None,
             client_params: serde_json::json!({}),
             enable_backfill_mode: false,
+            schema: None,
         };
         let index_metadata = IndexMetadata::for_test("test-index", "ram:///test-index");
         let index_uid = index_metadata.index_uid.clone();
@@ -648,6 +682,8 @@ mod tests {
                     source_params: SourceParams::Kafka(kafka_source_params.clone()),
                     transform_config: None,
                     input_format: Default::default(),
+                    csv_columns_opt: None,
+                    dead_letter_opt: None,
                 },
             )
             .unwrap();
@@ -662,6 +698,8 @@ mod tests {
                     source_params: SourceParams::Kafka(kafka_source_params.clone()),
                     transform_config: None,
                     input_format: Default::default(),
+                    csv_columns_opt: None,
+                    dead_letter_opt: None,
                 },
             )
             .unwrap();
@@ -677,6 +715,8 @@ mod tests {
                     source_params: SourceParams::IngestApi,
                     transform_config: None,
                     input_format: Default::default(),
+                    csv_columns_opt: None,
+                    dead_letter_opt: None,
                 },
             )
             .unwrap();
@@ -692,6 +732,8 @@ mod tests {
                     source_params: SourceParams::Ingest,
                     transform_config: None,
                     input_format: Default::default(),
+                    csv_columns_opt: None,
+                    dead_letter_opt: None,
                 },
             )
             .unwrap();
@@ -707,6 +749,8 @@ mod tests {
                     source_params: SourceParams::IngestCli,
                     transform_config: None,
                     input_format: Default::default(),
+                    csv_columns_opt: None,
+                    dead_letter_opt: None,
                 },
             )
             .unwrap();
@@ -817,6 +861,7 @@ mod tests {
                 "bootstrap.servers": "localhost:9092",
             }),
             enable_backfill_mode: true,
+            schema: None,
         })
     }
 
@@ -833,6 +878,8 @@ mod tests {
               source_params: kafka_source_params_for_test(),
               transform_config: None,
               input_format: SourceInputFormat::Json,
+              csv_columns_opt: None,
+              dead_letter_opt: None,
           })
       }
     }
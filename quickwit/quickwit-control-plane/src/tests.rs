@@ -61,9 +61,12 @@ fn index_metadata_for_test(
             "bootstrap.servers": "localhost:9092",
             }),
             enable_backfill_mode: true,
+            schema: None,
         }),
         transform_config: None,
         input_format: SourceInputFormat::Json,
+        csv_columns_opt: None,
+        dead_letter_opt: None,
     };
     index_metadata
         .sources
@@ -180,6 +183,14 @@ async fn test_scheduler_scheduling_and_control_loop_apply_plan_again() {
     assert_eq!(scheduler_state.num_schedule_indexing_plan, 1);
     assert!(scheduler_state.last_applied_physical_plan.is_some());
     assert_eq!(indexing_service_inbox_messages.len(), 1);
+    let apply_plan_request = &indexing_service_inbox_messages[0];
+    assert!(!apply_plan_request.indexing_tasks.is_empty());
+    assert!(apply_plan_request
+        .indexing_tasks
+        .iter()
+        .all(|indexing_task| apply_plan_request
+            .index_config_fingerprints
+            .contains_key(&indexing_task.index_uid)));
 
     // After a CONTROL_PLAN_LOOP_INTERVAL, the control loop will check if the desired plan is
     // running on the indexer. As chitchat state of the indexer is not updated (we did
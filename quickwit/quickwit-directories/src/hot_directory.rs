@@ -483,9 +483,13 @@ fn list_index_files(index: &Index) -> tantivy::Result<HashSet<PathBuf>> {
 /// Given a tantivy directory, automatically identify the parts that should be loaded on startup
 /// and writes a static cache file called hotcache in the `output`.
 ///
+/// `extra_files` are files of the directory that are not part of the index, such as split
+/// metadata files, and are cached in their entirety.
+///
 /// See [`HotDirectory`] for more information.
 pub fn write_hotcache<D: Directory>(
     directory: D,
+    extra_files: &[&Path],
     output: &mut dyn io::Write,
 ) -> tantivy::Result<()> {
     // We use the caching directory here in order to defensively ensure that
@@ -545,6 +549,17 @@ pub fn write_hotcache<D: Directory>(
             }
         }
     }
+    for file_path in extra_files {
+        let file_slice_res = debug_proxy_directory.open_read(file_path);
+        if let Err(tantivy::directory::error::OpenReadError::FileDoesNotExist(_)) = file_slice_res {
+            continue;
+        }
+        let file_slice = file_slice_res?;
+        let bytes = file_slice.read_bytes()?;
+        cache_builder
+            .add_file(file_path, file_slice.len() as u64)
+            .add_bytes(bytes.as_slice(), 0);
+    }
     cache_builder.write(output)?;
     output.flush()?;
     Ok(())
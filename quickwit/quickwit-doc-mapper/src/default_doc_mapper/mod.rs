@@ -102,6 +102,57 @@ pub fn validate_field_mapping_name(field_mapping_name: &str) -> anyhow::Result<(
     );
 }
 
+/// Checks that `new_field_mappings` is an additive update of `current_field_mappings`.
+///
+/// Existing field mappings must be left untouched, with the exception of object field mappings
+/// which may be extended with new sub-fields. New field mappings can be appended freely.
+pub fn validate_field_mappings_update(
+    current_field_mappings: &[FieldMappingEntry],
+    new_field_mappings: &[FieldMappingEntry],
+) -> anyhow::Result<()> {
+    validate_field_mappings_update_aux(current_field_mappings, new_field_mappings, "")
+}
+
+fn validate_field_mappings_update_aux(
+    current_field_mappings: &[FieldMappingEntry],
+    new_field_mappings: &[FieldMappingEntry],
+    path: &str,
+) -> anyhow::Result<()> {
+    for current_field_mapping in current_field_mappings {
+        let field_path = format!("{path}{}", current_field_mapping.name);
+        let Some(new_field_mapping) = new_field_mappings
+            .iter()
+            .find(|field_mapping| field_mapping.name == current_field_mapping.name)
+        else {
+            bail!("field `{field_path}` cannot be removed from the doc mapping");
+        };
+        match (
+            &current_field_mapping.mapping_type,
+            &new_field_mapping.mapping_type,
+        ) {
+            (
+                FieldMappingType::Object(current_object_options),
+                FieldMappingType::Object(new_object_options),
+            ) => {
+                validate_field_mappings_update_aux(
+                    &current_object_options.field_mappings,
+                    &new_object_options.field_mappings,
+                    &format!("{field_path}."),
+                )?;
+            }
+            (current_mapping_type, new_mapping_type) => {
+                if current_mapping_type != new_mapping_type {
+                    bail!(
+                        "field `{field_path}` cannot be modified. only new fields can be added to \
+                         the doc mapping"
+                    );
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
 /// Function used with serde to initialize boolean value at true if there is no value in json.
 fn default_as_true() -> bool {
     true
@@ -111,6 +162,64 @@ fn default_as_true() -> bool {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_validate_field_mappings_update() {
+        let current_field_mappings: Vec<FieldMappingEntry> = serde_json::from_str(
+            r#"[
+                {"name": "body", "type": "text"},
+                {
+                    "name": "attributes",
+                    "type": "object",
+                    "field_mappings": [{"name": "server", "type": "text"}]
+                }
+            ]"#,
+        )
+        .unwrap();
+        validate_field_mappings_update(&current_field_mappings, &current_field_mappings).unwrap();
+
+        let additive_field_mappings: Vec<FieldMappingEntry> = serde_json::from_str(
+            r#"[
+                {"name": "body", "type": "text"},
+                {
+                    "name": "attributes",
+                    "type": "object",
+                    "field_mappings": [
+                        {"name": "server", "type": "text"},
+                        {"name": "port", "type": "u64"}
+                    ]
+                },
+                {"name": "severity", "type": "text", "tokenizer": "raw"}
+            ]"#,
+        )
+        .unwrap();
+        validate_field_mappings_update(&current_field_mappings, &additive_field_mappings).unwrap();
+
+        let removed_field_mappings: Vec<FieldMappingEntry> =
+            serde_json::from_str(r#"[{"name": "body", "type": "text"}]"#).unwrap();
+        let error =
+            validate_field_mappings_update(&current_field_mappings, &removed_field_mappings)
+                .unwrap_err();
+        assert!(error.to_string().contains("`attributes` cannot be removed"));
+
+        let modified_field_mappings: Vec<FieldMappingEntry> = serde_json::from_str(
+            r#"[
+                {"name": "body", "type": "text"},
+                {
+                    "name": "attributes",
+                    "type": "object",
+                    "field_mappings": [{"name": "server", "type": "u64"}]
+                }
+            ]"#,
+        )
+        .unwrap();
+        let error =
+            validate_field_mappings_update(&current_field_mappings, &modified_field_mappings)
+                .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("`attributes.server` cannot be modified"));
+    }
+
     #[test]
     fn test_validate_field_mapping_name() {
         assert!(validate_field_mapping_name("")
@@ -24,7 +24,7 @@ use std::ops::Bound;
 
 use anyhow::Context;
 use dyn_clone::{clone_trait_object, DynClone};
-use quickwit_query::query_ast::QueryAst;
+use quickwit_query::query_ast::{QueryAst, TermExpansion};
 use serde_json::Value as JsonValue;
 use tantivy::query::Query;
 use tantivy::schema::{Field, FieldType, OwnedValue as Value, Schema};
@@ -191,6 +191,9 @@ pub struct WarmupInfo {
     pub terms_grouped_by_field: HashMap<Field, HashMap<Term, bool>>,
     /// Term ranges to warmup, and whether their position is needed too.
     pub term_ranges_grouped_by_field: HashMap<Field, HashMap<TermRange, bool>>,
+    /// Wildcard, regex and fuzzy term expansions, whose term dictionary and expanded terms
+    /// postings need to be warmed up.
+    pub term_expansions: HashSet<TermExpansion>,
 }
 
 impl WarmupInfo {
@@ -218,6 +221,8 @@ impl WarmupInfo {
                 *sub_map.entry(term_range).or_default() |= include_position;
             }
         }
+
+        self.term_expansions.extend(other.term_expansions);
     }
 }
 
@@ -226,7 +231,9 @@ mod tests {
     use std::collections::{HashMap, HashSet};
     use std::ops::Bound;
 
-    use quickwit_query::query_ast::{query_ast_from_user_text, UserInputQuery};
+    use quickwit_query::query_ast::{
+        query_ast_from_user_text, TermExpansion, TermPattern, UserInputQuery,
+    };
     use quickwit_query::BooleanOperand;
     use tantivy::schema::{Field, FieldType, Term};
 
@@ -446,6 +453,7 @@ mod tests {
                 (2, "term1", false),
                 (2, "term2", false),
             ]),
+            term_expansions: HashSet::new(),
         };
 
         // merging with default has no impact
@@ -464,6 +472,14 @@ mod tests {
                 (3, "term1", false),
                 (2, "term2", true),
             ]),
+            term_expansions: HashSet::from([TermExpansion {
+                field: Field::from_field_id(4),
+                pattern: TermPattern::Regex {
+                    term_prefix: Vec::new(),
+                    regex: "term.*".to_string(),
+                },
+                max_expansions: 10,
+            }]),
         };
         wi_base.merge(wi_2.clone());
 
@@ -480,6 +496,7 @@ mod tests {
             hashset(&["fast1", "fast2", "fast3"])
         );
         assert!(wi_base.field_norms);
+        assert_eq!(wi_base.term_expansions, wi_2.term_expansions);
 
         let expected_terms = [(1, "term1", false), (1, "term2", true), (2, "term1", false)];
         for (field, term, pos) in expected_terms {
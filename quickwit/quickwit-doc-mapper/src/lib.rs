@@ -31,12 +31,13 @@ mod error;
 mod query_builder;
 mod routing_expression;
 
+pub mod split_fields;
 /// Pruning tags manipulation.
 pub mod tag_pruning;
 
 pub use default_doc_mapper::{
-    analyze_text, DefaultDocMapper, DefaultDocMapperBuilder, FieldMappingEntry, Mode, ModeType,
-    QuickwitJsonOptions, TokenizerConfig, TokenizerEntry,
+    analyze_text, validate_field_mappings_update, DefaultDocMapper, DefaultDocMapperBuilder,
+    FieldMappingEntry, Mode, ModeType, QuickwitJsonOptions, TokenizerConfig, TokenizerEntry,
 };
 use default_doc_mapper::{
     FastFieldOptions, FieldMappingEntryForSerialization, IndexRecordOptionSchema,
@@ -22,8 +22,9 @@ use std::convert::Infallible;
 use std::ops::Bound;
 
 use quickwit_query::query_ast::{
-    FieldPresenceQuery, FullTextMode, FullTextQuery, PhrasePrefixQuery, QueryAst, QueryAstVisitor,
-    RangeQuery, TermSetQuery,
+    FieldPresenceQuery, FullTextMode, FullTextQuery, FuzzyQuery, PhrasePrefixQuery, PrefixQuery,
+    QueryAst, QueryAstVisitor, RangeQuery, RegexQuery, TermExpansion, TermPattern, TermSetQuery,
+    WildcardQuery,
 };
 use quickwit_query::{find_field_or_hit_dynamic, InvalidQuery};
 use tantivy::query::Query;
@@ -101,6 +102,7 @@ pub(crate) fn build_query(
     )?;
 
     let term_set_query_fields = extract_term_set_query_fields(query_ast);
+    let term_expansions = extract_term_expansions(query_ast, &schema);
     let term_ranges_grouped_by_field =
         extract_prefix_term_ranges(query_ast, &schema, tokenizer_manager)?;
 
@@ -119,6 +121,7 @@ pub(crate) fn build_query(
         posting_field_names: term_set_query_fields,
         terms_grouped_by_field,
         term_ranges_grouped_by_field,
+        term_expansions,
         fast_field_names,
         ..WarmupInfo::default()
     };
@@ -157,6 +160,55 @@ fn extract_term_set_query_fields(query_ast: &QueryAst) -> HashSet<String> {
     visitor.term_dict_fields_to_warm_up
 }
 
+/// Collects the term expansions of the wildcard, regex and fuzzy queries. Their matching terms
+/// are only known once the term dictionary is loaded.
+struct ExtractTermExpansions<'a> {
+    schema: &'a Schema,
+    term_expansions_to_warm_up: HashSet<TermExpansion>,
+}
+
+impl<'a> ExtractTermExpansions<'a> {
+    fn with_schema(schema: &'a Schema) -> Self {
+        ExtractTermExpansions {
+            schema,
+            term_expansions_to_warm_up: HashSet::new(),
+        }
+    }
+
+    fn add_term_expansion(&mut self, term_expansion_res: Result<TermExpansion, InvalidQuery>) {
+        // Queries on fields that do not exist match no documents and have nothing to warm up.
+        if let Ok(term_expansion) = term_expansion_res {
+            self.term_expansions_to_warm_up.insert(term_expansion);
+        }
+    }
+}
+
+impl<'a, 'b: 'a> QueryAstVisitor<'a> for ExtractTermExpansions<'b> {
+    type Err = Infallible;
+
+    fn visit_wildcard(&mut self, wildcard_query: &'a WildcardQuery) -> Result<(), Infallible> {
+        self.add_term_expansion(wildcard_query.term_expansion(self.schema));
+        Ok(())
+    }
+
+    fn visit_regex(&mut self, regex_query: &'a RegexQuery) -> Result<(), Infallible> {
+        self.add_term_expansion(regex_query.term_expansion(self.schema));
+        Ok(())
+    }
+
+    fn visit_fuzzy(&mut self, fuzzy_query: &'a FuzzyQuery) -> Result<(), Infallible> {
+        self.add_term_expansion(fuzzy_query.term_expansion(self.schema));
+        Ok(())
+    }
+}
+
+fn extract_term_expansions(query_ast: &QueryAst, schema: &Schema) -> HashSet<TermExpansion> {
+    let mut visitor = ExtractTermExpansions::with_schema(schema);
+    // This cannot fail. The error type is Infallible.
+    let _: Result<(), Infallible> = visitor.visit(query_ast);
+    visitor.term_expansions_to_warm_up
+}
+
 fn prefix_term_to_range(prefix: Term) -> (Bound<Term>, Bound<Term>) {
     let mut end_bound = prefix.serialized_term().to_vec();
     while !end_bound.is_empty() {
@@ -239,6 +291,19 @@ impl<'a, 'b: 'a> QueryAstVisitor<'a> for ExtractPrefixTermRanges<'b> {
         }
         Ok(())
     }
+
+    fn visit_prefix(&mut self, prefix_query: &'a PrefixQuery) -> Result<(), Self::Err> {
+        // The query expands to the first `max_expansions` terms of the same range.
+        if let Ok(TermExpansion {
+            pattern: TermPattern::Prefix(prefix_term),
+            max_expansions,
+            ..
+        }) = prefix_query.term_expansion(self.schema)
+        {
+            self.add_prefix_term(prefix_term, max_expansions, false);
+        }
+        Ok(())
+    }
 }
 
 fn extract_prefix_term_ranges(
@@ -253,9 +318,15 @@ fn extract_prefix_term_ranges(
 
 #[cfg(test)]
 mod test {
+    use std::ops::Bound;
+
     use quickwit_query::create_default_quickwit_tokenizer_manager;
-    use quickwit_query::query_ast::query_ast_from_user_text;
+    use quickwit_query::query_ast::{
+        query_ast_from_user_text, PrefixQuery, QueryAst, RegexQuery, TermPattern,
+        DEFAULT_AUTOMATON_MAX_EXPANSIONS,
+    };
     use tantivy::schema::{Schema, FAST, INDEXED, STORED, TEXT};
+    use tantivy::Term;
 
     use super::build_query;
     use crate::{DYNAMIC_FIELD_NAME, SOURCE_FIELD_NAME};
@@ -617,4 +688,63 @@ mod test {
         assert!(warmup_info.term_dict_field_names.is_empty());
         assert!(warmup_info.posting_field_names.is_empty());
     }
+
+    #[test]
+    fn test_build_query_warmup_info_automaton_queries() {
+        let regex_query: QueryAst = RegexQuery {
+            field: "server.name".to_string(),
+            regex: "web-[0-9]+".to_string(),
+            max_expansions: DEFAULT_AUTOMATON_MAX_EXPANSIONS,
+        }
+        .into();
+        let (_, warmup_info) = build_query(
+            &regex_query,
+            make_schema(true),
+            &create_default_quickwit_tokenizer_manager(),
+            &[],
+            true,
+        )
+        .unwrap();
+        // Only the term dictionary and the postings of the expanded terms are warmed up.
+        assert!(warmup_info.term_dict_field_names.is_empty());
+        assert!(warmup_info.posting_field_names.is_empty());
+        assert_eq!(warmup_info.term_expansions.len(), 1);
+        let term_expansion = warmup_info.term_expansions.iter().next().unwrap();
+        assert_eq!(
+            term_expansion.max_expansions,
+            DEFAULT_AUTOMATON_MAX_EXPANSIONS
+        );
+        assert!(matches!(term_expansion.pattern, TermPattern::Regex { .. }));
+
+        let prefix_query: QueryAst = PrefixQuery {
+            field: "title".to_string(),
+            prefix: "hel".to_string(),
+            max_expansions: DEFAULT_AUTOMATON_MAX_EXPANSIONS,
+        }
+        .into();
+        let (_, warmup_info) = build_query(
+            &prefix_query,
+            make_schema(true),
+            &create_default_quickwit_tokenizer_manager(),
+            &[],
+            true,
+        )
+        .unwrap();
+        assert!(warmup_info.term_dict_field_names.is_empty());
+        assert!(warmup_info.posting_field_names.is_empty());
+        assert!(warmup_info.term_expansions.is_empty());
+        let title_field = make_schema(true).get_field("title").unwrap();
+        let term_ranges = &warmup_info.term_ranges_grouped_by_field[&title_field];
+        assert_eq!(term_ranges.len(), 1);
+        let (term_range, position_needed) = term_ranges.iter().next().unwrap();
+        assert_eq!(
+            term_range.start,
+            Bound::Included(Term::from_field_text(title_field, "hel"))
+        );
+        assert_eq!(
+            term_range.limit,
+            Some(DEFAULT_AUTOMATON_MAX_EXPANSIONS as u64)
+        );
+        assert!(!position_needed);
+    }
 }
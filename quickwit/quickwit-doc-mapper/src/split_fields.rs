@@ -0,0 +1,222 @@
+// Copyright (C) 2023 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Fields of the JSON objects indexed in a split.
+//!
+//! Unlike the fields of the doc mapping, the fields of the JSON objects, including the dynamic
+//! fields, are not known from the schema. They are listed from the term dictionaries when the
+//! split is packaged, and stored in the [`SPLIT_FIELDS_FILE_NAME`] file of the split, so that they
+//! can be listed without loading the term dictionaries.
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use tantivy::schema::{FieldType, Type};
+use tantivy::termdict::TermDictionary;
+use tantivy::Searcher;
+
+use crate::DYNAMIC_FIELD_NAME;
+
+/// Name of the file of the split listing the fields of its JSON objects. The file is stored in
+/// its entirety in the hotcache of the split.
+pub const SPLIT_FIELDS_FILE_NAME: &str = "split_fields.json";
+
+/// Byte terminating the path of the JSON terms stored in the term dictionary.
+const JSON_END_OF_PATH: u8 = 0u8;
+
+/// Byte separating the segments of the JSON paths stored in the term dictionary.
+const JSON_PATH_SEGMENT_SEP: u8 = 1u8;
+
+/// A (path, type) pair of the terms of a JSON field of a split.
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
+pub struct SplitJsonField {
+    /// Name of the field, made of the name of the JSON field and the path, with the dots of the
+    /// path segments escaped. The dynamic field name is omitted.
+    pub field_name: String,
+    /// Name of the JSON field of the schema.
+    pub json_field_name: String,
+    /// Code of the [`Type`] of the terms.
+    pub type_code: u8,
+}
+
+impl SplitJsonField {
+    /// Returns the type of the terms of the field.
+    pub fn value_type(&self) -> Option<Type> {
+        Type::from_code(self.type_code)
+    }
+}
+
+/// Lists the fields of the JSON objects indexed in a split. The term dictionaries of the split must
+/// be readable synchronously, which is the case of a split being packaged.
+pub fn list_split_json_fields(searcher: &Searcher) -> anyhow::Result<Vec<SplitJsonField>> {
+    let schema = searcher.schema();
+    let mut fields = Vec::new();
+
+    for (field, field_entry) in schema.fields() {
+        if !matches!(field_entry.field_type(), FieldType::JsonObject(_))
+            || !field_entry.is_indexed()
+        {
+            continue;
+        }
+        for segment_reader in searcher.segment_readers() {
+            let inverted_index = segment_reader.inverted_index(field)?;
+            list_json_paths(inverted_index.terms(), field_entry.name(), &mut fields)?;
+        }
+    }
+    fields.sort();
+    fields.dedup();
+    Ok(fields)
+}
+
+/// Appends the JSON path stored in a term dictionary key to `field_name`, escaping the dots of
+/// the path segments.
+fn append_json_path(field_name: &mut String, json_path: &[u8]) {
+    for segment in json_path.split(|byte| *byte == JSON_PATH_SEGMENT_SEP) {
+        if !field_name.is_empty() {
+            field_name.push('.');
+        }
+        field_name.push_str(&String::from_utf8_lossy(segment).replace('.', r"\."));
+    }
+}
+
+/// Lists the (path, type) pairs of the terms of a JSON field. Instead of iterating over all the
+/// terms, we jump to the next (path, type) pair as soon as one is found.
+fn list_json_paths(
+    term_dict: &TermDictionary,
+    json_field_name: &str,
+    fields: &mut Vec<SplitJsonField>,
+) -> anyhow::Result<()> {
+    let mut lower_bound_opt: Option<Vec<u8>> = None;
+    loop {
+        let mut range = term_dict.range();
+        if let Some(lower_bound) = &lower_bound_opt {
+            range = range.ge(lower_bound);
+        }
+        let mut stream = range
+            .into_stream()
+            .context("failed to create stream over term dictionary")?;
+        if !stream.advance() {
+            return Ok(());
+        }
+        let key = stream.key();
+        let end_of_path_pos = key
+            .iter()
+            .position(|byte| *byte == JSON_END_OF_PATH)
+            .context("JSON term is missing end of path marker")?;
+        let type_code = key
+            .get(end_of_path_pos + 1)
+            .copied()
+            .context("JSON term is missing type code")?;
+
+        let mut field_name = if json_field_name == DYNAMIC_FIELD_NAME {
+            String::new()
+        } else {
+            json_field_name.to_string()
+        };
+        append_json_path(&mut field_name, &key[..end_of_path_pos]);
+        fields.push(SplitJsonField {
+            field_name,
+            json_field_name: json_field_name.to_string(),
+            type_code,
+        });
+        // The next candidate key is the smallest key greater than all the keys sharing the
+        // current path and type.
+        let mut lower_bound = key[..end_of_path_pos + 2].to_vec();
+        lower_bound[end_of_path_pos + 1] += 1;
+        lower_bound_opt = Some(lower_bound);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tantivy::schema::{Schema, STRING, TEXT};
+    use tantivy::{Index, IndexReader, ReloadPolicy, TantivyDocument};
+
+    use super::*;
+
+    #[test]
+    fn test_append_json_path() {
+        let mut field_name = String::new();
+        append_json_path(&mut field_name, b"service\x01name");
+        assert_eq!(field_name, "service.name");
+
+        let mut field_name = "attributes".to_string();
+        append_json_path(&mut field_name, b"http.method");
+        assert_eq!(field_name, r"attributes.http\.method");
+    }
+
+    #[test]
+    fn test_list_split_json_fields() {
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_text_field("body", TEXT);
+        schema_builder.add_json_field("attributes", STRING);
+        schema_builder.add_json_field(DYNAMIC_FIELD_NAME, STRING);
+        let schema = schema_builder.build();
+
+        let index = Index::create_in_ram(schema.clone());
+        let mut index_writer = index.writer(15_000_000).unwrap();
+        for doc_json in [
+            r#"{"body": "foo", "attributes": {"http.method": "GET"}, "_dynamic": {"tenant": "a"}}"#,
+            r#"{"attributes": {"http.status": 200}, "_dynamic": {"tenant": "b", "port": 80}}"#,
+        ] {
+            let doc = TantivyDocument::parse_json(&schema, doc_json).unwrap();
+            index_writer.add_document(doc).unwrap();
+        }
+        index_writer.commit().unwrap();
+
+        let reader: IndexReader = index
+            .reader_builder()
+            .reload_policy(ReloadPolicy::Manual)
+            .try_into()
+            .unwrap();
+        let searcher = reader.searcher();
+        let fields: Vec<(String, String, Type)> = list_split_json_fields(&searcher)
+            .unwrap()
+            .into_iter()
+            .map(|field| {
+                let value_type = field.value_type().unwrap();
+                (field.json_field_name, field.field_name, value_type)
+            })
+            .collect();
+        assert_eq!(
+            fields,
+            [
+                (
+                    "attributes".to_string(),
+                    r"attributes.http\.method".to_string(),
+                    Type::Str
+                ),
+                (
+                    "attributes".to_string(),
+                    r"attributes.http\.status".to_string(),
+                    Type::I64
+                ),
+                (
+                    DYNAMIC_FIELD_NAME.to_string(),
+                    "port".to_string(),
+                    Type::I64
+                ),
+                (
+                    DYNAMIC_FIELD_NAME.to_string(),
+                    "tenant".to_string(),
+                    Type::Str
+                ),
+            ]
+        );
+    }
+}
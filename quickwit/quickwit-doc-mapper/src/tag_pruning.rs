@@ -110,6 +110,9 @@ fn extract_unsimplified_tags_filter_ast(query_ast: QueryAst) -> UnsimplifiedTagF
             panic!("Extract unsimplified should only be called on AST without UserInputQuery.");
         }
         QueryAst::FieldPresence(_) => UnsimplifiedTagFilterAst::Uninformative,
+        QueryAst::Prefix(_) | QueryAst::Wildcard(_) | QueryAst::Regex(_) | QueryAst::Fuzzy(_) => {
+            UnsimplifiedTagFilterAst::Uninformative
+        }
     }
 }
 
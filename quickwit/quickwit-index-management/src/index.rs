@@ -23,15 +23,16 @@ use std::time::Duration;
 use quickwit_common::fs::{empty_dir, get_cache_directory_path};
 use quickwit_config::{validate_identifier, IndexConfig, SourceConfig};
 use quickwit_indexing::check_source_connectivity;
+use quickwit_indexing::dead_letter::delete_dead_letter_files;
 use quickwit_metastore::{
     AddSourceRequestExt, CreateIndexRequestExt, IndexMetadata, IndexMetadataResponseExt,
     ListSplitsQuery, ListSplitsRequestExt, ListSplitsResponseExt, SplitInfo, SplitMetadata,
-    SplitState,
+    SplitState, UpdateIndexRequestExt,
 };
 use quickwit_proto::metastore::{
     AddSourceRequest, CreateIndexRequest, DeleteIndexRequest, EntityKind, IndexMetadataRequest,
     ListSplitsRequest, MarkSplitsForDeletionRequest, MetastoreError, MetastoreService,
-    MetastoreServiceClient, ResetSourceCheckpointRequest,
+    MetastoreServiceClient, ResetSourceCheckpointRequest, UpdateIndexRequest,
 };
 use quickwit_proto::types::{IndexUid, SplitId};
 use quickwit_proto::{ServiceError, ServiceErrorCode};
@@ -151,6 +152,35 @@ impl IndexService {
         Ok(index_metadata)
     }
 
+    /// Updates the config of an existing index. Only additive changes to the doc mapping are
+    /// allowed, while the indexing settings, search settings, and retention policy can be
+    /// modified freely.
+    pub async fn update_index(
+        &mut self,
+        index_config: IndexConfig,
+    ) -> Result<IndexMetadata, IndexServiceError> {
+        let index_metadata_request =
+            IndexMetadataRequest::for_index_id(index_config.index_id.clone());
+        let index_metadata = self
+            .metastore
+            .index_metadata(index_metadata_request)
+            .await?
+            .deserialize_index_metadata()?;
+        index_metadata
+            .index_config
+            .validate_update(&index_config)
+            .map_err(IndexServiceError::InvalidConfig)?;
+
+        let update_index_request =
+            UpdateIndexRequest::try_from_index_config(index_metadata.index_uid, index_config)?;
+        let index_metadata = self
+            .metastore
+            .update_index(update_index_request)
+            .await?
+            .deserialize_index_metadata()?;
+        Ok(index_metadata)
+    }
+
     /// Deletes the index specified with `index_id`.
     /// This is equivalent to running `rm -rf <index path>` for a local index or
     /// `aws s3 rm --recursive <index path>` for a remote Amazon S3 index.
@@ -211,12 +241,18 @@ impl IndexService {
 
         let deleted_splits = delete_splits_from_storage_and_metastore(
             index_uid.clone(),
-            storage,
+            storage.clone(),
             self.metastore.clone(),
             splits_to_delete,
             None,
         )
         .await?;
+        // The dead-letter files of the sources are deleted before the index so that deleting the
+        // index again cleans them up if this fails.
+        delete_dead_letter_files(&*storage)
+            .await
+            .map_err(|error| IndexServiceError::Internal(format!("{error:#}")))?;
+
         let delete_index_request = DeleteIndexRequest {
             index_uid: index_uid.to_string(),
         };
@@ -462,6 +498,47 @@ mod tests {
         assert!(index_metadata_0.index_uid != index_metadata_1.index_uid);
     }
 
+    #[tokio::test]
+    async fn test_update_index() {
+        let metastore = metastore_for_test();
+        let storage_resolver = StorageResolver::for_test();
+        let mut index_service = IndexService::new(metastore, storage_resolver);
+        let index_id = "test-index";
+        let index_uri = "ram://indexes/test-index";
+        let index_config = IndexConfig::for_test(index_id, index_uri);
+        let index_metadata_0 = index_service
+            .create_index(index_config.clone(), false)
+            .await
+            .unwrap();
+
+        let mut new_index_config = index_config.clone();
+        new_index_config.indexing_settings.commit_timeout_secs = 5;
+        let index_metadata_1 = index_service
+            .update_index(new_index_config.clone())
+            .await
+            .unwrap();
+        assert_eq!(index_metadata_1.index_uid, index_metadata_0.index_uid);
+        assert_eq!(index_metadata_1.index_config, new_index_config);
+        assert_eq!(index_metadata_1.sources, index_metadata_0.sources);
+
+        let mut invalid_index_config = new_index_config;
+        invalid_index_config.doc_mapping.field_mappings.clear();
+        let error = index_service
+            .update_index(invalid_index_config)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, IndexServiceError::InvalidConfig(_)));
+
+        let error = index_service
+            .update_index(IndexConfig::for_test("index-not-found", index_uri))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            IndexServiceError::Metastore(MetastoreError::NotFound(EntityKind::Index { .. }))
+        ));
+    }
+
     #[tokio::test]
     async fn test_delete_index() {
         let mut metastore = metastore_for_test();
@@ -507,6 +584,10 @@ mod tests {
         storage.put(split_path, payload).await.unwrap();
         assert!(storage.exists(split_path).await.unwrap());
 
+        let dead_letter_file_path = Path::new("dead-letter/test-source/test-file.ndjson");
+        let payload: Box<dyn PutPayload> = Box::new(b"{}\n".to_vec());
+        storage.put(dead_letter_file_path, payload).await.unwrap();
+
         let split_infos = index_service.delete_index(index_id, false).await.unwrap();
         assert_eq!(split_infos.len(), 1);
 
@@ -518,5 +599,6 @@ mod tests {
             matches!(error, MetastoreError::NotFound(EntityKind::Index { index_id }) if index_id == index_uid.index_id())
         );
         assert!(!storage.exists(split_path).await.unwrap());
+        assert!(!storage.exists(dead_letter_file_path).await.unwrap());
     }
 }
@@ -36,6 +36,9 @@ macro_rules! bench_func {
                             docs,
                             checkpoint_delta: checkpoint_delta.clone(),
                             force_commit: false,
+                            csv_header_opt: None,
+                            invalid_docs: Vec::new(),
+                            doc_positions: Vec::new(),
                         })
                         .await
                         .unwrap();
@@ -28,19 +28,25 @@ use quickwit_actors::{Actor, ActorContext, ActorExitStatus, Handler, Mailbox, Qu
 use quickwit_common::runtimes::RuntimeType;
 use quickwit_config::{SourceInputFormat, TransformConfig};
 use quickwit_doc_mapper::{DocMapper, DocParsingError, JsonObject};
+use quickwit_metastore::checkpoint::PartitionId;
 use quickwit_opentelemetry::otlp::{
     parse_otlp_spans_json, parse_otlp_spans_protobuf, JsonSpanIterator, OtlpTraceError,
 };
+use quickwit_proto::types::Position;
 use serde::Serialize;
 use serde_json::Value as JsonValue;
 use tantivy::schema::{Field, Value};
 use tantivy::{DateTime, TantivyDocument};
 use tokio::runtime::Handle;
-use tracing::warn;
+use tracing::{error, warn};
 
+use super::input_formats::{parse_logfmt, parse_syslog, CsvParser};
 #[cfg(feature = "vrl")]
 use super::vrl_processing::*;
 use crate::actors::Indexer;
+use crate::dead_letter::{
+    DeadLetterDoc, DeadLetterErrorKind, DeadLetterSink, DEAD_LETTER_FLUSH_INTERVAL,
+};
 use crate::models::{
     NewPublishLock, NewPublishToken, ProcessedDoc, ProcessedDocBatch, PublishLock, RawDocBatch,
 };
@@ -48,8 +54,8 @@ use crate::models::{
 const PLAIN_TEXT: &str = "plain_text";
 
 pub(super) struct JsonDoc {
-    json_obj: JsonObject,
-    num_bytes: usize,
+    pub(super) json_obj: JsonObject,
+    pub(super) num_bytes: usize,
 }
 
 impl JsonDoc {
@@ -66,10 +72,18 @@ impl JsonDoc {
     ) -> Result<Self, DocProcessorError> {
         match json_value {
             JsonValue::Object(json_obj) => Ok(Self::new(json_obj, num_bytes)),
-            _ => Err(DocProcessorError::Parse),
+            _ => Err(DocProcessorError::Parse(
+                "document must be a JSON object".to_string(),
+            )),
         }
     }
 
+    #[cfg(feature = "vrl")]
+    fn try_into_vrl_doc(self) -> Result<VrlDoc, DocProcessorError> {
+        let vrl_value = serde_json::from_value::<VrlValue>(JsonValue::Object(self.json_obj))?;
+        Ok(VrlDoc::new(vrl_value, self.num_bytes))
+    }
+
     #[cfg(feature = "vrl")]
     pub fn try_from_vrl_doc(vrl_doc: VrlDoc) -> Result<Self, DocProcessorError> {
         let json_value = serde_json::to_value(vrl_doc.vrl_value)?;
@@ -79,21 +93,42 @@ impl JsonDoc {
 
 #[derive(Debug)]
 pub enum DocProcessorError {
-    Parse,
-    Schema,
+    Parse(String),
+    Schema(String),
     #[cfg(feature = "vrl")]
     Transform(VrlTerminate),
 }
 
+impl DocProcessorError {
+    fn dead_letter_error_kind(&self) -> DeadLetterErrorKind {
+        match self {
+            DocProcessorError::Parse(_) => DeadLetterErrorKind::Parse,
+            DocProcessorError::Schema(_) => DeadLetterErrorKind::Schema,
+            #[cfg(feature = "vrl")]
+            DocProcessorError::Transform(_) => DeadLetterErrorKind::Transform,
+        }
+    }
+
+    fn message(&self) -> String {
+        match self {
+            DocProcessorError::Parse(message) | DocProcessorError::Schema(message) => {
+                message.clone()
+            }
+            #[cfg(feature = "vrl")]
+            DocProcessorError::Transform(transform_error) => transform_error.to_string(),
+        }
+    }
+}
+
 impl From<serde_json::Error> for DocProcessorError {
-    fn from(_error: serde_json::Error) -> Self {
-        DocProcessorError::Parse
+    fn from(error: serde_json::Error) -> Self {
+        DocProcessorError::Parse(error.to_string())
     }
 }
 
 impl From<FromUtf8Error> for DocProcessorError {
-    fn from(_error: FromUtf8Error) -> Self {
-        DocProcessorError::Parse
+    fn from(error: FromUtf8Error) -> Self {
+        DocProcessorError::Parse(error.to_string())
     }
 }
 
@@ -104,7 +139,10 @@ fn try_into_vrl_doc(
     num_bytes: usize,
 ) -> Result<VrlDoc, DocProcessorError> {
     let vrl_value = match input_format {
-        SourceInputFormat::Json => serde_json::from_slice::<VrlValue>(&raw_doc)?,
+        // Avro and Protobuf messages are decoded into JSON docs by the source.
+        SourceInputFormat::Json | SourceInputFormat::Avro | SourceInputFormat::Protobuf => {
+            serde_json::from_slice::<VrlValue>(&raw_doc)?
+        }
         SourceInputFormat::PlainText => {
             let mut map = std::collections::BTreeMap::new();
             let key = PLAIN_TEXT.to_string();
@@ -115,6 +153,9 @@ fn try_into_vrl_doc(
         SourceInputFormat::OtlpTraceJson | SourceInputFormat::OtlpTraceProtobuf => {
             panic!("OTP log or trace data does not support VRL transforms")
         }
+        SourceInputFormat::Csv | SourceInputFormat::Logfmt | SourceInputFormat::Syslog => {
+            unreachable!("CSV, logfmt, and syslog docs are parsed into JSON docs before VRL")
+        }
     };
     let vrl_doc = VrlDoc::new(vrl_value, num_bytes);
     Ok(vrl_doc)
@@ -124,9 +165,11 @@ fn try_into_json_docs(
     input_format: SourceInputFormat,
     raw_doc: Bytes,
     num_bytes: usize,
+    csv_parser: &mut CsvParser,
 ) -> JsonDocIterator {
     match input_format {
-        SourceInputFormat::Json => {
+        // Avro and Protobuf messages are decoded into JSON docs by the source.
+        SourceInputFormat::Json | SourceInputFormat::Avro | SourceInputFormat::Protobuf => {
             let json_doc_result = serde_json::from_slice::<JsonObject>(&raw_doc)
                 .map(|json_obj| JsonDoc::new(json_obj, num_bytes));
             JsonDocIterator::from(json_doc_result)
@@ -148,6 +191,17 @@ fn try_into_json_docs(
             });
             JsonDocIterator::from(json_doc_result)
         }
+        SourceInputFormat::Csv => JsonDocIterator::from(csv_parser.parse(&raw_doc)),
+        SourceInputFormat::Logfmt => {
+            let json_doc_result =
+                parse_logfmt(&raw_doc).map(|json_obj| JsonDoc::new(json_obj, num_bytes));
+            JsonDocIterator::from(json_doc_result)
+        }
+        SourceInputFormat::Syslog => {
+            let json_doc_result =
+                parse_syslog(&raw_doc).map(|json_obj| JsonDoc::new(json_obj, num_bytes));
+            JsonDocIterator::from(json_doc_result)
+        }
     }
 }
 
@@ -156,11 +210,28 @@ fn parse_raw_doc(
     input_format: SourceInputFormat,
     raw_doc: Bytes,
     num_bytes: usize,
+    csv_parser: &mut CsvParser,
     vrl_program_opt: Option<&mut VrlProgram>,
 ) -> JsonDocIterator {
     let Some(vrl_program) = vrl_program_opt else {
-        return try_into_json_docs(input_format, raw_doc, num_bytes);
+        return try_into_json_docs(input_format, raw_doc, num_bytes, csv_parser);
     };
+    if matches!(
+        input_format,
+        SourceInputFormat::Csv | SourceInputFormat::Logfmt | SourceInputFormat::Syslog
+    ) {
+        // These formats are parsed into JSON docs first, which are then transformed.
+        let json_doc_results: Vec<Result<JsonDoc, DocProcessorError>> =
+            try_into_json_docs(input_format, raw_doc, num_bytes, csv_parser)
+                .map(|json_doc_result| {
+                    json_doc_result
+                        .and_then(JsonDoc::try_into_vrl_doc)
+                        .and_then(|vrl_doc| vrl_program.transform_doc(vrl_doc))
+                        .and_then(JsonDoc::try_from_vrl_doc)
+                })
+                .collect();
+        return JsonDocIterator::from(json_doc_results);
+    }
     let json_doc_result = try_into_vrl_doc(input_format, raw_doc, num_bytes)
         .and_then(|vrl_doc| vrl_program.transform_doc(vrl_doc))
         .and_then(JsonDoc::try_from_vrl_doc);
@@ -173,13 +244,15 @@ fn parse_raw_doc(
     input_format: SourceInputFormat,
     raw_doc: Bytes,
     num_bytes: usize,
+    csv_parser: &mut CsvParser,
     _vrl_program_opt: Option<&mut VrlProgram>,
 ) -> JsonDocIterator {
-    try_into_json_docs(input_format, raw_doc, num_bytes)
+    try_into_json_docs(input_format, raw_doc, num_bytes, csv_parser)
 }
 
 enum JsonDocIterator {
     One(Option<Result<JsonDoc, DocProcessorError>>),
+    Many(std::vec::IntoIter<Result<JsonDoc, DocProcessorError>>),
     Spans(JsonSpanIterator),
 }
 
@@ -189,6 +262,7 @@ impl Iterator for JsonDocIterator {
     fn next(&mut self) -> Option<Self::Item> {
         match self {
             Self::One(opt) => opt.take(),
+            Self::Many(json_doc_results) => json_doc_results.next(),
             Self::Spans(spans) => spans
                 .next()
                 .map(|(json_value, num_bytes)| JsonDoc::try_from_json_value(json_value, num_bytes)),
@@ -207,11 +281,17 @@ where E: Into<DocProcessorError>
     }
 }
 
+impl From<Vec<Result<JsonDoc, DocProcessorError>>> for JsonDocIterator {
+    fn from(json_doc_results: Vec<Result<JsonDoc, DocProcessorError>>) -> Self {
+        Self::Many(json_doc_results.into_iter())
+    }
+}
+
 impl From<Result<JsonSpanIterator, OtlpTraceError>> for JsonDocIterator {
     fn from(result: Result<JsonSpanIterator, OtlpTraceError>) -> Self {
         match result {
             Ok(json_doc) => Self::Spans(json_doc),
-            Err(_) => Self::One(Some(Err(DocProcessorError::Parse))),
+            Err(error) => Self::One(Some(Err(DocProcessorError::Parse(error.to_string())))),
         }
     }
 }
@@ -236,6 +316,10 @@ pub struct DocProcessorCounters {
     ///
     /// Includes both valid and invalid documents.
     pub num_bytes_total: AtomicU64,
+
+    /// Number of invalid docs that could not be recorded in the dead-letter destination of the
+    /// source.
+    pub num_dead_letter_docs_dropped: AtomicU64,
 }
 
 impl DocProcessorCounters {
@@ -248,6 +332,7 @@ impl DocProcessorCounters {
             num_schema_errors: Default::default(),
             num_valid_docs: Default::default(),
             num_bytes_total: Default::default(),
+            num_dead_letter_docs_dropped: Default::default(),
         }
     }
 
@@ -282,13 +367,13 @@ impl DocProcessorCounters {
             .inc_by(num_bytes);
     }
 
-    pub fn record_error(&self, error: DocProcessorError, num_bytes: u64) {
+    pub fn record_error(&self, error: &DocProcessorError, num_bytes: u64) {
         let label = match error {
-            DocProcessorError::Parse => {
+            DocProcessorError::Parse(_) => {
                 self.num_parse_errors.fetch_add(1, Ordering::Relaxed);
                 "parse_error"
             }
-            DocProcessorError::Schema => {
+            DocProcessorError::Schema(_) => {
                 self.num_schema_errors.fetch_add(1, Ordering::Relaxed);
                 "schema_error"
             }
@@ -310,8 +395,22 @@ impl DocProcessorCounters {
             .with_label_values([&self.index_id, &self.source_id, label])
             .inc_by(num_bytes);
     }
+
+    pub fn record_dead_letter_docs_dropped(&self, num_docs: u64) {
+        self.num_dead_letter_docs_dropped
+            .fetch_add(num_docs, Ordering::Relaxed);
+
+        crate::metrics::INDEXER_METRICS
+            .dead_letter_docs_dropped_total
+            .with_label_values([&self.index_id, &self.source_id])
+            .inc_by(num_docs);
+    }
 }
 
+/// Message the doc processor sends itself to write the dead-letter docs its sink buffers.
+#[derive(Debug)]
+struct FlushDeadLetterDocs;
+
 pub struct DocProcessor {
     doc_mapper: Arc<dyn DocMapper>,
     indexer_mailbox: Mailbox<Indexer>,
@@ -321,6 +420,9 @@ pub struct DocProcessor {
     #[cfg(feature = "vrl")]
     transform_opt: Option<VrlProgram>,
     input_format: SourceInputFormat,
+    csv_parser: CsvParser,
+    dead_letter_sink_opt: Option<DeadLetterSink>,
+    is_dead_letter_flush_scheduled: bool,
 }
 
 impl DocProcessor {
@@ -347,10 +449,26 @@ impl DocProcessor {
                 .map(VrlProgram::try_from_transform_config)
                 .transpose()?,
             input_format,
+            csv_parser: CsvParser::with_header(),
+            dead_letter_sink_opt: None,
+            is_dead_letter_flush_scheduled: false,
         };
         Ok(doc_processor)
     }
 
+    /// Parses the records of a CSV source with the given columns instead of reading them from the
+    /// header row of each file.
+    pub(crate) fn with_csv_columns(mut self, csv_columns: Vec<String>) -> Self {
+        self.csv_parser = CsvParser::with_columns(csv_columns);
+        self
+    }
+
+    /// Records the documents rejected by the doc processor into the given dead-letter sink.
+    pub(crate) fn with_dead_letter_sink(mut self, dead_letter_sink: DeadLetterSink) -> Self {
+        self.dead_letter_sink_opt = Some(dead_letter_sink);
+        self
+    }
+
     // Extract a timestamp from a tantivy document.
     //
     // If the timestamp is set up in the docmapper and the timestamp is missing,
@@ -365,19 +483,36 @@ impl DocProcessor {
         let timestamp = doc
             .get_first(timestamp_field)
             .and_then(|val| val.as_datetime())
-            .ok_or(DocProcessorError::Schema)?;
+            .ok_or_else(|| DocProcessorError::Schema("timestamp field is missing".to_string()))?;
         Ok(Some(timestamp))
     }
 
-    fn process_raw_doc(&mut self, raw_doc: Bytes, processed_docs: &mut Vec<ProcessedDoc>) {
+    fn process_raw_doc(
+        &mut self,
+        raw_doc: Bytes,
+        position_opt: Option<&(PartitionId, Position)>,
+        processed_docs: &mut Vec<ProcessedDoc>,
+        dead_letter_docs: &mut Vec<DeadLetterDoc>,
+    ) {
         let num_bytes = raw_doc.len();
+        // The raw doc is only kept around if the rejected docs are recorded. A raw doc holding
+        // several docs, such as an OTLP export request, is recorded once.
+        let mut dead_letter_raw_doc_opt =
+            self.dead_letter_sink_opt.as_ref().map(|_| raw_doc.clone());
 
         #[cfg(feature = "vrl")]
         let transform_opt = self.transform_opt.as_mut();
         #[cfg(not(feature = "vrl"))]
         let transform_opt: Option<&mut VrlProgram> = None;
 
-        for json_doc_result in parse_raw_doc(self.input_format, raw_doc, num_bytes, transform_opt) {
+        let json_doc_results = parse_raw_doc(
+            self.input_format,
+            raw_doc,
+            num_bytes,
+            &mut self.csv_parser,
+            transform_opt,
+        );
+        for json_doc_result in json_doc_results {
             let processed_doc_result =
                 json_doc_result.and_then(|json_doc| self.process_json_doc(json_doc));
 
@@ -387,12 +522,102 @@ impl DocProcessor {
                     processed_docs.push(processed_doc);
                 }
                 Err(error) => {
-                    self.counters.record_error(error, num_bytes as u64);
+                    self.record_error(
+                        error,
+                        num_bytes,
+                        dead_letter_raw_doc_opt.take().as_ref(),
+                        position_opt,
+                        dead_letter_docs,
+                    );
                 }
             }
         }
     }
 
+    /// Counts a rejected doc and, if the rejected docs are recorded, records the raw doc it comes
+    /// from. `raw_doc_opt` is `None` when that raw doc was recorded already.
+    fn record_error(
+        &self,
+        error: DocProcessorError,
+        num_bytes: usize,
+        raw_doc_opt: Option<&Bytes>,
+        position_opt: Option<&(PartitionId, Position)>,
+        dead_letter_docs: &mut Vec<DeadLetterDoc>,
+    ) {
+        self.counters.record_error(&error, num_bytes as u64);
+
+        let Some(raw_doc) = raw_doc_opt else {
+            return;
+        };
+        if self.dead_letter_sink_opt.is_some() {
+            let dead_letter_doc = DeadLetterDoc::new(
+                self.counters.index_id.clone(),
+                self.counters.source_id.clone(),
+                position_opt.cloned(),
+                error.dead_letter_error_kind(),
+                error.message(),
+                raw_doc,
+            );
+            dead_letter_docs.push(dead_letter_doc);
+        }
+    }
+
+    /// Hands the rejected docs over to the dead-letter sink, and schedules the flush of the docs
+    /// it buffers. Dead letters are recorded on a best-effort basis: the docs that cannot be
+    /// recorded are logged and counted, but do not fail the pipeline.
+    async fn write_dead_letter_docs(
+        &mut self,
+        dead_letter_docs: Vec<DeadLetterDoc>,
+        ctx: &ActorContext<Self>,
+    ) {
+        let Some(dead_letter_sink) = self.dead_letter_sink_opt.as_mut() else {
+            return;
+        };
+        if dead_letter_docs.is_empty() {
+            return;
+        }
+        let num_docs = dead_letter_sink.num_buffered_docs() + dead_letter_docs.len();
+
+        if let Err(error) = ctx
+            .protect_future(dead_letter_sink.write(&dead_letter_docs))
+            .await
+        {
+            error!(
+                index_id=%self.counters.index_id,
+                source_id=%self.counters.source_id,
+                num_docs=num_docs,
+                error=?error,
+                "failed to record dead-letter docs"
+            );
+            self.counters
+                .record_dead_letter_docs_dropped(num_docs as u64);
+        }
+        if dead_letter_sink.num_buffered_docs() > 0 && !self.is_dead_letter_flush_scheduled {
+            self.is_dead_letter_flush_scheduled = true;
+            ctx.schedule_self_msg(DEAD_LETTER_FLUSH_INTERVAL, FlushDeadLetterDocs)
+                .await;
+        }
+    }
+
+    async fn flush_dead_letter_docs(&mut self, ctx: &ActorContext<Self>) {
+        let Some(dead_letter_sink) = self.dead_letter_sink_opt.as_mut() else {
+            return;
+        };
+        let num_docs = dead_letter_sink.num_buffered_docs();
+
+        if let Err(error) = ctx.protect_future(dead_letter_sink.flush()).await {
+            error!(
+                index_id=%self.counters.index_id,
+                source_id=%self.counters.source_id,
+                num_docs=num_docs,
+                error=?error,
+                "failed to record dead-letter docs"
+            );
+            self.counters
+                .record_dead_letter_docs_dropped(num_docs as u64);
+        }
+    }
+
     fn process_json_doc(&self, json_doc: JsonDoc) -> Result<ProcessedDoc, DocProcessorError> {
         let num_bytes = json_doc.num_bytes;
 
@@ -402,8 +627,10 @@ impl DocProcessor {
             .map_err(|error| {
                 warn!(error=?error);
                 match error {
-                    DocParsingError::RequiredField(_) => DocProcessorError::Schema,
-                    _ => DocProcessorError::Parse,
+                    DocParsingError::RequiredField(_) => {
+                        DocProcessorError::Schema(error.to_string())
+                    }
+                    _ => DocProcessorError::Parse(error.to_string()),
                 }
             })?;
         let timestamp_opt = self.extract_timestamp(&doc)?;
@@ -456,6 +683,12 @@ impl Actor for DocProcessor {
         exit_status: &ActorExitStatus,
         ctx: &ActorContext<Self>,
     ) -> anyhow::Result<()> {
+        if !matches!(
+            exit_status,
+            ActorExitStatus::Killed | ActorExitStatus::Panicked
+        ) {
+            self.flush_dead_letter_docs(ctx).await;
+        }
         match exit_status {
             ActorExitStatus::DownstreamClosed
             | ActorExitStatus::Killed
@@ -482,11 +715,39 @@ impl Handler<RawDocBatch> for DocProcessor {
             return Ok(());
         }
         let mut processed_docs: Vec<ProcessedDoc> = Vec::with_capacity(raw_doc_batch.docs.len());
-        for raw_doc in raw_doc_batch.docs {
+        let mut dead_letter_docs: Vec<DeadLetterDoc> = Vec::new();
+        if let Some(csv_header) = &raw_doc_batch.csv_header_opt {
+            self.csv_parser.set_header(csv_header);
+        }
+        for invalid_doc in raw_doc_batch.invalid_docs {
+            let error = DocProcessorError::Parse(invalid_doc.error);
+            self.record_error(
+                error,
+                invalid_doc.raw_doc.len(),
+                Some(&invalid_doc.raw_doc),
+                invalid_doc.position_opt.as_ref(),
+                &mut dead_letter_docs,
+            );
+        }
+        let has_doc_positions = raw_doc_batch.doc_positions.len() == raw_doc_batch.docs.len();
+
+        for (doc_ord, raw_doc) in raw_doc_batch.docs.into_iter().enumerate() {
             let _protected_zone_guard = ctx.protect_zone();
-            self.process_raw_doc(raw_doc, &mut processed_docs);
+            let position_opt = if has_doc_positions {
+                raw_doc_batch.doc_positions.get(doc_ord)
+            } else {
+                None
+            };
+            self.process_raw_doc(
+                raw_doc,
+                position_opt,
+                &mut processed_docs,
+                &mut dead_letter_docs,
+            );
             ctx.record_progress();
         }
+        self.write_dead_letter_docs(dead_letter_docs, ctx).await;
+
         let processed_doc_batch = ProcessedDocBatch {
             docs: processed_docs,
             checkpoint_delta: raw_doc_batch.checkpoint_delta,
@@ -498,6 +759,21 @@ impl Handler<RawDocBatch> for DocProcessor {
     }
 }
 
+#[async_trait]
+impl Handler<FlushDeadLetterDocs> for DocProcessor {
+    type Reply = ();
+
+    async fn handle(
+        &mut self,
+        _message: FlushDeadLetterDocs,
+        ctx: &ActorContext<Self>,
+    ) -> Result<(), ActorExitStatus> {
+        self.is_dead_letter_flush_scheduled = false;
+        self.flush_dead_letter_docs(ctx).await;
+        Ok(())
+    }
+}
+
 #[async_trait]
 impl Handler<NewPublishLock> for DocProcessor {
     type Reply = ();
@@ -530,24 +806,27 @@ impl Handler<NewPublishToken> for DocProcessor {
 
 #[cfg(test)]
 mod tests {
+    use std::path::Path;
     use std::sync::Arc;
 
     use bytes::Bytes;
     use prost::Message;
-    use quickwit_actors::Universe;
+    use quickwit_actors::{ObservationType, Universe};
     use quickwit_common::uri::Uri;
-    use quickwit_config::{build_doc_mapper, SearchSettings};
+    use quickwit_config::{build_doc_mapper, DeadLetterConfig, SearchSettings};
     use quickwit_doc_mapper::{default_doc_mapper_for_test, DefaultDocMapper};
     use quickwit_metastore::checkpoint::SourceCheckpointDelta;
     use quickwit_opentelemetry::otlp::OtlpGrpcTracesService;
     use quickwit_proto::opentelemetry::proto::collector::trace::v1::ExportTraceServiceRequest;
     use quickwit_proto::opentelemetry::proto::trace::v1::{ResourceSpans, ScopeSpans, Span};
+    use quickwit_storage::{MockStorage, RamStorage, Storage, StorageErrorKind};
     use serde_json::Value as JsonValue;
     use tantivy::schema::NamedFieldDocument;
     use tantivy::Document;
 
     use super::*;
-    use crate::models::{PublishLock, RawDocBatch};
+    use crate::dead_letter::parse_dead_letter_docs;
+    use crate::models::{InvalidRawDoc, PublishLock, RawDocBatch};
 
     #[tokio::test]
     async fn test_doc_processor_simple() -> anyhow::Result<()> {
@@ -626,6 +905,223 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_doc_processor_dead_letter() {
+        let index_id = "my-index";
+        let source_id = "my-source";
+        let universe = Universe::with_accelerated_time();
+        let doc_mapper = Arc::new(default_doc_mapper_for_test());
+        let (indexer_mailbox, indexer_inbox) = universe.create_test_mailbox();
+        let storage = Arc::new(RamStorage::default());
+        let dead_letter_sink = DeadLetterSink::try_new(
+            &DeadLetterConfig::Storage,
+            source_id,
+            storage.clone(),
+            Path::new("/queues-not-used"),
+        )
+        .await
+        .unwrap();
+        let doc_processor = DocProcessor::try_new(
+            index_id.to_string(),
+            source_id.to_string(),
+            doc_mapper,
+            indexer_mailbox,
+            None,
+            SourceInputFormat::Json,
+        )
+        .unwrap()
+        .with_dead_letter_sink(dead_letter_sink);
+        let (doc_processor_mailbox, doc_processor_handle) =
+            universe.spawn_builder().spawn(doc_processor);
+        let mut raw_doc_batch = RawDocBatch::for_test(
+            &[
+                r#"{"body": "happy", "response_date": "2021-12-19T16:39:57+00:00", "response_time": 12, "response_payload": "YWJj"}"#, // missing timestamp
+                r#"{"body": "happy", "timestamp": 1628837062, "response_date": "2021-12-19T16:39:59+00:00", "response_time": 2, "response_payload": "YWJj"}"#, // ok
+                "{", // invalid json
+            ],
+            0..3,
+        );
+        raw_doc_batch.doc_positions = (1..=3u64)
+            .map(|offset| (PartitionId::default(), Position::from(offset)))
+            .collect();
+        doc_processor_mailbox
+            .send_message(raw_doc_batch)
+            .await
+            .unwrap();
+        doc_processor_handle.process_pending_and_observe().await;
+
+        let processed_doc_batch = indexer_inbox.drain_for_test_typed::<ProcessedDocBatch>();
+        assert_eq!(processed_doc_batch.len(), 1);
+        assert_eq!(processed_doc_batch[0].docs.len(), 1);
+
+        // The dead-letter docs are buffered until the flush interval elapses.
+        assert!(storage.list_files().await.is_empty());
+        universe.sleep(DEAD_LETTER_FLUSH_INTERVAL * 2).await;
+        doc_processor_handle.process_pending_and_observe().await;
+
+        let file_paths = storage.list_files().await;
+        assert_eq!(file_paths.len(), 1);
+        assert!(file_paths[0].starts_with("dead-letter/my-source"));
+
+        let ndjson = storage.get_all(&file_paths[0]).await.unwrap();
+        let dead_letter_docs = parse_dead_letter_docs(ndjson.as_slice()).unwrap();
+        assert_eq!(dead_letter_docs.len(), 2);
+
+        assert_eq!(dead_letter_docs[0].index_id, index_id);
+        assert_eq!(dead_letter_docs[0].source_id, source_id);
+        assert_eq!(
+            dead_letter_docs[0].partition_id,
+            Some(PartitionId::default())
+        );
+        assert_eq!(dead_letter_docs[0].position, Some(Position::from(1u64)));
+        assert_eq!(dead_letter_docs[0].error_kind, DeadLetterErrorKind::Schema);
+        assert!(dead_letter_docs[0]
+            .payload
+            .as_ref()
+            .unwrap()
+            .contains("2021-12-19T16:39:57+00:00"));
+
+        assert_eq!(dead_letter_docs[1].error_kind, DeadLetterErrorKind::Parse);
+        assert!(!dead_letter_docs[1].error_message.is_empty());
+        assert_eq!(dead_letter_docs[1].payload.as_deref(), Some("{"));
+        assert_eq!(dead_letter_docs[1].position, Some(Position::from(3u64)));
+
+        universe.assert_quit().await;
+    }
+
+    #[tokio::test]
+    async fn test_doc_processor_records_invalid_docs() {
+        let source_id = "my-source";
+        let universe = Universe::with_accelerated_time();
+        let doc_mapper = Arc::new(default_doc_mapper_for_test());
+        let (indexer_mailbox, indexer_inbox) = universe.create_test_mailbox();
+        let storage = Arc::new(RamStorage::default());
+        let dead_letter_sink = DeadLetterSink::try_new(
+            &DeadLetterConfig::Storage,
+            source_id,
+            storage.clone(),
+            Path::new("/queues-not-used"),
+        )
+        .await
+        .unwrap();
+        let doc_processor = DocProcessor::try_new(
+            "my-index".to_string(),
+            source_id.to_string(),
+            doc_mapper,
+            indexer_mailbox,
+            None,
+            SourceInputFormat::Avro,
+        )
+        .unwrap()
+        .with_dead_letter_sink(dead_letter_sink);
+        let (doc_processor_mailbox, doc_processor_handle) =
+            universe.spawn_builder().spawn(doc_processor);
+        let mut raw_doc_batch = RawDocBatch::for_test(
+            &[
+                r#"{"body": "happy", "timestamp": 1628837062, "response_date": "2021-12-19T16:39:59+00:00", "response_time": 2, "response_payload": "YWJj"}"#,
+            ],
+            0..2,
+        );
+        raw_doc_batch.invalid_docs.push(InvalidRawDoc {
+            raw_doc: Bytes::from_static(b"\x00\x00\x00\x00\x07\xff"),
+            error: "failed to decode Avro message".to_string(),
+            position_opt: Some((PartitionId::default(), Position::from(1u64))),
+        });
+        doc_processor_mailbox
+            .send_message(raw_doc_batch)
+            .await
+            .unwrap();
+        doc_processor_mailbox
+            .send_message(FlushDeadLetterDocs)
+            .await
+            .unwrap();
+        let counters = doc_processor_handle
+            .process_pending_and_observe()
+            .await
+            .state;
+        assert_eq!(counters.num_parse_errors.load(Ordering::Relaxed), 1);
+        assert_eq!(counters.num_valid_docs.load(Ordering::Relaxed), 1);
+
+        let processed_doc_batch = indexer_inbox.drain_for_test_typed::<ProcessedDocBatch>();
+        assert_eq!(processed_doc_batch.len(), 1);
+        assert_eq!(processed_doc_batch[0].docs.len(), 1);
+
+        let file_paths = storage.list_files().await;
+        assert_eq!(file_paths.len(), 1);
+
+        let ndjson = storage.get_all(&file_paths[0]).await.unwrap();
+        let dead_letter_docs = parse_dead_letter_docs(ndjson.as_slice()).unwrap();
+        assert_eq!(dead_letter_docs.len(), 1);
+        assert_eq!(dead_letter_docs[0].error_kind, DeadLetterErrorKind::Parse);
+        assert_eq!(
+            dead_letter_docs[0].error_message,
+            "failed to decode Avro message"
+        );
+        assert_eq!(
+            dead_letter_docs[0].payload_base64.as_deref(),
+            Some("AAAAAAf/")
+        );
+        assert_eq!(dead_letter_docs[0].position, Some(Position::from(1u64)));
+        universe.assert_quit().await;
+    }
+
+    #[tokio::test]
+    async fn test_doc_processor_dead_letter_sink_error() {
+        let source_id = "my-source";
+        let universe = Universe::with_accelerated_time();
+        let doc_mapper = Arc::new(default_doc_mapper_for_test());
+        let (indexer_mailbox, indexer_inbox) = universe.create_test_mailbox();
+        let mut mock_storage = MockStorage::default();
+        mock_storage
+            .expect_put()
+            .times(1)
+            .returning(|_path, _payload| {
+                Err(StorageErrorKind::Io.with_error(anyhow::anyhow!("no space left on device")))
+            });
+        let dead_letter_sink = DeadLetterSink::try_new(
+            &DeadLetterConfig::Storage,
+            source_id,
+            Arc::new(mock_storage),
+            Path::new("/queues-not-used"),
+        )
+        .await
+        .unwrap();
+        let doc_processor = DocProcessor::try_new(
+            "my-index".to_string(),
+            source_id.to_string(),
+            doc_mapper,
+            indexer_mailbox,
+            None,
+            SourceInputFormat::Json,
+        )
+        .unwrap()
+        .with_dead_letter_sink(dead_letter_sink);
+        let (doc_processor_mailbox, doc_processor_handle) =
+            universe.spawn_builder().spawn(doc_processor);
+        doc_processor_mailbox
+            .send_message(RawDocBatch::for_test(&["{", "{"], 0..2))
+            .await
+            .unwrap();
+        doc_processor_mailbox
+            .send_message(FlushDeadLetterDocs)
+            .await
+            .unwrap();
+        let observation = doc_processor_handle.process_pending_and_observe().await;
+        assert_eq!(observation.obs_type, ObservationType::Alive);
+
+        let counters = observation.state;
+        assert_eq!(counters.num_parse_errors.load(Ordering::Relaxed), 2);
+        assert_eq!(
+            counters
+                .num_dead_letter_docs_dropped
+                .load(Ordering::Relaxed),
+            2
+        );
+        let processed_doc_batch = indexer_inbox.drain_for_test_typed::<ProcessedDocBatch>();
+        assert_eq!(processed_doc_batch.len(), 1);
+        universe.assert_quit().await;
+    }
+
     const DOCMAPPER_WITH_PARTITION_JSON: &str = r#"
         {
             "tag_fields": ["tenant"],
@@ -672,6 +1168,9 @@ mod tests {
                 ],
                 checkpoint_delta: SourceCheckpointDelta::from_range(0..2),
                 force_commit: false,
+                csv_header_opt: None,
+                invalid_docs: Vec::new(),
+                doc_positions: Vec::new(),
             })
             .await?;
         universe
@@ -821,6 +1320,9 @@ mod tests {
             docs: vec![raw_doc],
             checkpoint_delta: SourceCheckpointDelta::from_range(0..2),
             force_commit: false,
+            csv_header_opt: None,
+            invalid_docs: Vec::new(),
+            doc_positions: Vec::new(),
         };
         doc_processor_mailbox
             .send_message(raw_doc_batch)
@@ -903,6 +1405,9 @@ mod tests {
             docs: vec![raw_doc],
             checkpoint_delta: SourceCheckpointDelta::from_range(0..2),
             force_commit: false,
+            csv_header_opt: None,
+            invalid_docs: Vec::new(),
+            doc_positions: Vec::new(),
         };
         doc_processor_mailbox
             .send_message(raw_doc_batch)
@@ -929,6 +1434,127 @@ mod tests {
         assert!(matches!(exit_status, ActorExitStatus::Success));
         universe.assert_quit().await;
     }
+
+    #[tokio::test]
+    async fn test_doc_processor_with_csv_input() {
+        let index_id = "my-index";
+        let source_id = "my-source";
+        let universe = Universe::with_accelerated_time();
+        let doc_mapper = Arc::new(default_doc_mapper_for_test());
+        let (indexer_mailbox, indexer_inbox) = universe.create_test_mailbox();
+        let doc_processor = DocProcessor::try_new(
+            index_id.to_string(),
+            source_id.to_string(),
+            doc_mapper.clone(),
+            indexer_mailbox,
+            None,
+            SourceInputFormat::Csv,
+        )
+        .unwrap();
+        let (doc_processor_mailbox, doc_processor_handle) =
+            universe.spawn_builder().spawn(doc_processor);
+        let mut raw_doc_batch = RawDocBatch::for_test(
+            &[
+                "happy,1628837062,2021-12-19T16:39:59+00:00,2,YWJj", // ok
+                "happy2,1628837062,2021-12-19T16:40:57+00:00,13",    // missing column
+                "happy3,,2021-12-19T16:40:57+00:00,13,YWJj",         // missing timestamp
+            ],
+            0..4,
+        );
+        raw_doc_batch.csv_header_opt = Some(Bytes::from_static(
+            b"body,timestamp,response_date,response_time,response_payload\n",
+        ));
+        doc_processor_mailbox
+            .send_message(raw_doc_batch)
+            .await
+            .unwrap();
+        let counters = doc_processor_handle
+            .process_pending_and_observe()
+            .await
+            .state;
+        assert_eq!(counters.num_parse_errors.load(Ordering::Relaxed), 1);
+        assert_eq!(counters.num_schema_errors.load(Ordering::Relaxed), 1);
+        assert_eq!(counters.num_valid_docs.load(Ordering::Relaxed), 1);
+        assert_eq!(counters.num_bytes_total.load(Ordering::Relaxed), 136);
+
+        let output_messages = indexer_inbox.drain_for_test();
+        assert_eq!(output_messages.len(), 1);
+        let batch = *(output_messages
+            .into_iter()
+            .next()
+            .unwrap()
+            .downcast::<ProcessedDocBatch>()
+            .unwrap());
+        assert_eq!(batch.docs.len(), 1);
+
+        let schema = doc_mapper.schema();
+        let NamedFieldDocument(named_field_doc_map) = batch.docs[0].doc.to_named_doc(&schema);
+        let doc_json = JsonValue::Object(doc_mapper.doc_to_json(named_field_doc_map).unwrap());
+        assert_eq!(
+            doc_json,
+            serde_json::json!({
+                "_source": {
+                    "body": "happy",
+                    "response_date": "2021-12-19T16:39:59+00:00",
+                    "response_payload": "YWJj",
+                    "response_time": "2",
+                    "timestamp": "1628837062"
+                },
+                "body": "happy",
+                "response_date": "2021-12-19T16:39:59Z",
+                "response_payload": "YWJj",
+                "response_time": 2.0,
+                "timestamp": 1628837062
+            })
+        );
+        universe.assert_quit().await;
+    }
+
+    #[tokio::test]
+    async fn test_doc_processor_with_csv_columns() {
+        let universe = Universe::with_accelerated_time();
+        let doc_mapper = Arc::new(default_doc_mapper_for_test());
+        let (indexer_mailbox, indexer_inbox) = universe.create_test_mailbox();
+        let csv_columns = ["timestamp", "body"]
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        let doc_processor = DocProcessor::try_new(
+            "my-index".to_string(),
+            "my-source".to_string(),
+            doc_mapper,
+            indexer_mailbox,
+            None,
+            SourceInputFormat::Csv,
+        )
+        .unwrap()
+        .with_csv_columns(csv_columns);
+        let (doc_processor_mailbox, doc_processor_handle) =
+            universe.spawn_builder().spawn(doc_processor);
+        doc_processor_mailbox
+            .send_message(RawDocBatch::for_test(
+                &["1628837062,happy", "1628837063,happy2"],
+                0..2,
+            ))
+            .await
+            .unwrap();
+        let counters = doc_processor_handle
+            .process_pending_and_observe()
+            .await
+            .state;
+        assert_eq!(counters.num_valid_docs.load(Ordering::Relaxed), 2);
+        assert_eq!(counters.num_invalid_docs(), 0);
+
+        let output_messages = indexer_inbox.drain_for_test();
+        let batch = *(output_messages
+            .into_iter()
+            .next()
+            .unwrap()
+            .downcast::<ProcessedDocBatch>()
+            .unwrap());
+        assert_eq!(batch.docs.len(), 2);
+        universe.assert_quit().await;
+    }
 }
 
 #[cfg(feature = "vrl")]
@@ -1110,4 +1736,64 @@ mod tests_vrl {
         );
         universe.assert_quit().await;
     }
+
+    #[tokio::test]
+    async fn test_doc_processor_with_logfmt_input() {
+        let universe = Universe::with_accelerated_time();
+        let (indexer_mailbox, indexer_inbox) = universe.create_test_mailbox();
+        let doc_mapper = Arc::new(default_doc_mapper_for_test());
+        let vrl_script = r#"
+            .body = upcase(string!(.msg))
+            .timestamp = to_int!(.ts)
+            del(.msg)
+            del(.ts)
+        "#;
+        let transform_config = TransformConfig::for_test(vrl_script);
+        let doc_processor = DocProcessor::try_new(
+            "my-index".to_string(),
+            "my-source".to_string(),
+            doc_mapper.clone(),
+            indexer_mailbox,
+            Some(transform_config),
+            SourceInputFormat::Logfmt,
+        )
+        .unwrap();
+        let (doc_processor_mailbox, doc_processor_handle) =
+            universe.spawn_builder().spawn(doc_processor);
+        doc_processor_mailbox
+            .send_message(RawDocBatch::for_test(
+                &[
+                    "msg=happy ts=1628837062 response_date=2021-12-19T16:39:59+00:00 \
+                     response_time=2 response_payload=YWJj",
+                    r#"msg="unterminated"#,
+                ],
+                0..2,
+            ))
+            .await
+            .unwrap();
+        let counters = doc_processor_handle
+            .process_pending_and_observe()
+            .await
+            .state;
+        assert_eq!(counters.num_parse_errors.load(Ordering::Relaxed), 1);
+        assert_eq!(counters.num_transform_errors.load(Ordering::Relaxed), 0);
+        assert_eq!(counters.num_valid_docs.load(Ordering::Relaxed), 1);
+        assert_eq!(counters.num_bytes_total.load(Ordering::Relaxed), 118);
+
+        let output_messages = indexer_inbox.drain_for_test();
+        let batch = *(output_messages
+            .into_iter()
+            .next()
+            .unwrap()
+            .downcast::<ProcessedDocBatch>()
+            .unwrap());
+        assert_eq!(batch.docs.len(), 1);
+
+        let schema = doc_mapper.schema();
+        let NamedFieldDocument(named_field_doc_map) = batch.docs[0].doc.to_named_doc(&schema);
+        let doc_json = JsonValue::Object(doc_mapper.doc_to_json(named_field_doc_map).unwrap());
+        assert_eq!(doc_json["body"], "HAPPY");
+        assert_eq!(doc_json["timestamp"], 1628837062);
+        universe.assert_quit().await;
+    }
 }
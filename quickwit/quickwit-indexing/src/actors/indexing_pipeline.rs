@@ -48,9 +48,12 @@ use crate::actors::publisher::PublisherType;
 use crate::actors::sequencer::Sequencer;
 use crate::actors::uploader::UploaderType;
 use crate::actors::{Indexer, Packager, Publisher, Uploader};
+use crate::dead_letter::DeadLetterSink;
 use crate::merge_policy::MergePolicy;
 use crate::models::IndexingStatistics;
-use crate::source::{quickwit_supported_sources, AssignShards, SourceActor, SourceRuntimeArgs};
+use crate::source::{
+    quickwit_supported_sources, AssignShards, DrainSource, SourceActor, SourceRuntimeArgs,
+};
 use crate::split_store::IndexingSplitStore;
 use crate::SplitsUpdateMailbox;
 
@@ -112,6 +115,11 @@ pub struct Spawn {
     retry_count: usize,
 }
 
+/// Asks the pipeline to stop reading from its source, index and publish the documents already
+/// read along with their checkpoint, and exit.
+#[derive(Clone, Copy, Debug)]
+pub struct DrainPipeline;
+
 pub struct IndexingPipeline {
     params: IndexingPipelineParams,
     previous_generations_statistics: IndexingStatistics,
@@ -119,6 +127,8 @@ pub struct IndexingPipeline {
     handles_opt: Option<IndexingPipelineHandles>,
     // Killswitch used for the actors in the pipeline. This is not the supervisor killswitch.
     kill_switch: KillSwitch,
+    // Set when the pipeline is draining: it must exit instead of respawning its actors.
+    draining: bool,
 }
 
 #[async_trait]
@@ -159,6 +169,7 @@ impl IndexingPipeline {
             handles_opt: None,
             kill_switch: KillSwitch::default(),
             statistics: IndexingStatistics::default(),
+            draining: false,
         }
     }
 
@@ -279,6 +290,12 @@ impl IndexingPipeline {
             Health::Healthy => {}
             Health::FailureOrUnhealthy => {
                 self.terminate().await;
+                if self.draining {
+                    // The documents that were not published will be read again from the last
+                    // published checkpoint by the next pipeline.
+                    info!("indexing pipeline failed while draining, exiting");
+                    return Err(ActorExitStatus::Success);
+                }
                 let first_retry_delay = wait_duration_before_retry(0);
                 ctx.schedule_self_msg(first_retry_delay, Spawn { retry_count: 0 })
                     .await;
@@ -401,7 +418,7 @@ impl IndexingPipeline {
             .set_kill_switch(self.kill_switch.clone())
             .spawn(indexer);
 
-        let doc_processor = DocProcessor::try_new(
+        let mut doc_processor = DocProcessor::try_new(
             index_id.to_string(),
             source_id.to_string(),
             self.params.doc_mapper.clone(),
@@ -409,6 +426,19 @@ impl IndexingPipeline {
             self.params.source_config.transform_config.clone(),
             self.params.source_config.input_format,
         )?;
+        if let Some(csv_columns) = &self.params.source_config.csv_columns_opt {
+            doc_processor = doc_processor.with_csv_columns(csv_columns.clone());
+        }
+        if let Some(dead_letter_config) = &self.params.source_config.dead_letter_opt {
+            let dead_letter_sink = DeadLetterSink::try_new(
+                dead_letter_config,
+                source_id,
+                self.params.storage.clone(),
+                &self.params.queues_dir_path,
+            )
+            .await?;
+            doc_processor = doc_processor.with_dead_letter_sink(dead_letter_sink);
+        }
         let (doc_processor_mailbox, doc_processor_handle) = ctx
             .spawn_actor()
             .set_backpressure_micros_counter(
@@ -535,6 +565,36 @@ impl Handler<Spawn> for IndexingPipeline {
     }
 }
 
+#[async_trait]
+impl Handler<DrainPipeline> for IndexingPipeline {
+    type Reply = ();
+
+    async fn handle(
+        &mut self,
+        _message: DrainPipeline,
+        _ctx: &ActorContext<Self>,
+    ) -> Result<(), ActorExitStatus> {
+        self.draining = true;
+
+        let Some(handles) = &self.handles_opt else {
+            // The actors are not running, so there are no pending documents to publish.
+            return Err(ActorExitStatus::Success);
+        };
+        info!("draining indexing pipeline");
+        // The pipeline exits once all its actors have exited successfully (see
+        // `perform_health_check`).
+        if handles
+            .source_mailbox
+            .send_message(DrainSource)
+            .await
+            .is_err()
+        {
+            debug!("source already exited");
+        }
+        Ok(())
+    }
+}
+
 #[async_trait]
 impl Handler<AssignShards> for IndexingPipeline {
     type Reply = ();
@@ -679,6 +739,8 @@ mod tests {
             source_params: SourceParams::file(PathBuf::from("data/test_corpus.json")),
             transform_config: None,
             input_format: SourceInputFormat::Json,
+            csv_columns_opt: None,
+            dead_letter_opt: None,
         };
         let storage = Arc::new(RamStorage::default());
         let split_store = IndexingSplitStore::create_without_local_store_for_test(storage.clone());
@@ -779,6 +841,8 @@ mod tests {
             source_params: SourceParams::file(PathBuf::from("data/test_corpus.json")),
             transform_config: None,
             input_format: SourceInputFormat::Json,
+            csv_columns_opt: None,
+            dead_letter_opt: None,
         };
         let storage = Arc::new(RamStorage::default());
         let split_store = IndexingSplitStore::create_without_local_store_for_test(storage.clone());
@@ -846,6 +910,8 @@ mod tests {
             source_params: SourceParams::Void(VoidSourceParams),
             transform_config: None,
             input_format: SourceInputFormat::Json,
+            csv_columns_opt: None,
+            dead_letter_opt: None,
         };
         let metastore = MetastoreServiceClient::from(mock_metastore);
         let storage = Arc::new(RamStorage::default());
@@ -964,6 +1030,8 @@ mod tests {
             source_params: SourceParams::file(PathBuf::from("data/test_corpus.json")),
             transform_config: None,
             input_format: SourceInputFormat::Json,
+            csv_columns_opt: None,
+            dead_letter_opt: None,
         };
         let storage = Arc::new(RamStorage::default());
         let split_store = IndexingSplitStore::create_without_local_store_for_test(storage.clone());
@@ -54,6 +54,7 @@ use serde::{Deserialize, Serialize};
 use tokio::sync::Semaphore;
 use tracing::{debug, error, info, warn};
 
+use super::indexing_pipeline::DrainPipeline;
 use super::merge_pipeline::{MergePipeline, MergePipelineParams};
 use super::MergePlanner;
 use crate::models::{DetachIndexingPipeline, DetachMergePipeline, ObservePipeline, SpawnPipeline};
@@ -115,6 +116,11 @@ pub struct IndexingService {
     local_split_store: Arc<LocalSplitStore>,
     max_concurrent_split_uploads: usize,
     merge_pipeline_handles: HashMap<MergePipelineId, MergePipelineHandle>,
+    // Fingerprints of the index configs with which the running indexing pipelines were spawned.
+    index_config_fingerprints: HashMap<IndexingPipelineId, u64>,
+    // Pipelines draining before being respawned with an updated index config. The value tells
+    // whether the pipeline is still part of the indexing plan and must be respawned.
+    draining_pipelines: HashMap<IndexingPipelineId, bool>,
     cooperative_indexing_permits: Option<Arc<Semaphore>>,
     event_broker: EventBroker,
 }
@@ -173,6 +179,8 @@ impl IndexingService {
             counters: Default::default(),
             max_concurrent_split_uploads: indexer_config.max_concurrent_split_uploads,
             merge_pipeline_handles: HashMap::new(),
+            index_config_fingerprints: HashMap::new(),
+            draining_pipelines: HashMap::new(),
             cooperative_indexing_permits,
             event_broker,
         })
@@ -327,6 +335,8 @@ impl IndexingService {
         };
         let pipeline = IndexingPipeline::new(pipeline_params);
         let (pipeline_mailbox, pipeline_handle) = ctx.spawn_actor().spawn(pipeline);
+        self.index_config_fingerprints
+            .insert(pipeline_id.clone(), index_config.fingerprint());
         self.indexing_pipelines
             .insert(pipeline_id, (pipeline_mailbox, pipeline_handle));
         self.counters.num_running_pipelines += 1;
@@ -413,6 +423,8 @@ impl IndexingService {
                 merge_pipeline_mailbox_handle.handle.state().is_running()
             });
         self.counters.num_running_merge_pipelines = self.merge_pipeline_handles.len();
+        self.index_config_fingerprints
+            .retain(|pipeline_id, _| self.indexing_pipelines.contains_key(pipeline_id));
         self.update_cluster_running_indexing_tasks().await;
 
         let pipeline_metrics: HashMap<&IndexingPipelineId, PipelineMetrics> = self
@@ -455,6 +467,7 @@ impl IndexingService {
     }
 
     /// Applies the indexing plan by:
+    /// - Draining and restarting the pipelines whose index config fingerprint changed.
     /// - Stopping the running pipelines not present in the provided plan.
     /// - Starting the pipelines that are not running.
     /// Note: the indexing is a list of `IndexingTask` and has no ordinal
@@ -479,18 +492,36 @@ impl IndexingService {
             updated_pipeline_ids.insert(pipeline_id);
         }
 
+        // Drain the pipelines spawned with an outdated index config. They are respawned once they
+        // have published their pending checkpoint.
+        let outdated_pipeline_ids: Vec<IndexingPipelineId> = self
+            .index_config_fingerprints
+            .iter()
+            .filter(|(pipeline_id, fingerprint)| {
+                updated_pipeline_ids.contains(*pipeline_id)
+                    && physical_indexing_plan_request
+                        .index_config_fingerprints
+                        .get(pipeline_id.index_uid.as_str())
+                        .map(|new_fingerprint| new_fingerprint != *fingerprint)
+                        .unwrap_or(false)
+            })
+            .map(|(pipeline_id, _)| pipeline_id.clone())
+            .collect();
+        self.drain_pipelines(ctx, outdated_pipeline_ids).await;
+
+        // Draining pipelines are respawned only if they are still part of the plan.
+        for (pipeline_id, should_respawn) in self.draining_pipelines.iter_mut() {
+            *should_respawn = updated_pipeline_ids.contains(pipeline_id);
+        }
         let running_pipeline_ids: HashSet<IndexingPipelineId> =
             self.indexing_pipelines.keys().cloned().collect();
 
         // Spawn new pipeline in the new plan that are not currently running
-        let failed_spawning_pipeline_ids = self
-            .spawn_pipelines(
-                ctx,
-                updated_pipeline_ids
-                    .difference(&running_pipeline_ids)
-                    .collect(),
-            )
-            .await?;
+        let pipeline_ids_to_spawn: Vec<&IndexingPipelineId> = updated_pipeline_ids
+            .difference(&running_pipeline_ids)
+            .filter(|pipeline_id| !self.draining_pipelines.contains_key(*pipeline_id))
+            .collect();
+        let failed_spawning_pipeline_ids = self.spawn_pipelines(ctx, pipeline_ids_to_spawn).await?;
 
         // TODO: Temporary hack to assign shards to pipelines.
         for indexing_task in &physical_indexing_plan_request.indexing_tasks {
@@ -627,6 +658,71 @@ impl IndexingService {
         }
     }
 
+    /// Drains the pipelines spawned with an outdated index config: their sources stop reading, and
+    /// the documents already read are indexed and published along with their checkpoint. The
+    /// merge pipelines are then quit gracefully and the pipelines are respawned with the new index
+    /// config (see `RespawnDrainedPipelines`).
+    async fn drain_pipelines(
+        &mut self,
+        ctx: &ActorContext<Self>,
+        pipeline_ids: Vec<IndexingPipelineId>,
+    ) {
+        let mut drained_pipelines: HashMap<
+            MergePipelineId,
+            Vec<(IndexingPipelineId, ActorHandle<IndexingPipeline>)>,
+        > = HashMap::new();
+
+        for pipeline_id in pipeline_ids {
+            let Some((pipeline_mailbox, pipeline_handle)) =
+                self.indexing_pipelines.remove(&pipeline_id)
+            else {
+                continue;
+            };
+            self.counters.num_running_pipelines -= 1;
+            self.index_config_fingerprints.remove(&pipeline_id);
+
+            info!(
+                index_id=%pipeline_id.index_uid.index_id(),
+                source_id=%pipeline_id.source_id,
+                pipeline_ord=%pipeline_id.pipeline_ord,
+                "Index config was updated, draining indexing pipeline before restarting it."
+            );
+            if let Err(error) = pipeline_mailbox.send_message(DrainPipeline).await {
+                warn!(pipeline_id=?pipeline_id, err=?error, "Failed to drain pipeline.");
+            }
+            self.draining_pipelines.insert(pipeline_id.clone(), true);
+            drained_pipelines
+                .entry(MergePipelineId::from(&pipeline_id))
+                .or_default()
+                .push((pipeline_id, pipeline_handle));
+        }
+        for (merge_pipeline_id, pipelines) in drained_pipelines {
+            // The merge pipeline also depends on the index config (doc mapping, merge policy...).
+            // It keeps receiving the splits published by the draining pipelines, so we quit it
+            // only once they have exited.
+            let merge_pipeline_handle_opt =
+                self.detach_merge_pipeline(&merge_pipeline_id).await.ok();
+            let indexing_service_mailbox = ctx.mailbox().clone();
+
+            tokio::spawn(async move {
+                let mut pipeline_ids = Vec::with_capacity(pipelines.len());
+
+                for (pipeline_id, pipeline_handle) in pipelines {
+                    pipeline_handle.join().await;
+                    pipeline_ids.push(pipeline_id);
+                }
+                if let Some(merge_pipeline_handle) = merge_pipeline_handle_opt {
+                    merge_pipeline_handle.quit().await;
+                }
+                let respawn_message = RespawnDrainedPipelines { pipeline_ids };
+
+                if let Err(error) = indexing_service_mailbox.send_message(respawn_message).await {
+                    error!(err=?error, "Failed to respawn drained pipelines.");
+                }
+            });
+        }
+    }
+
     /// Updates running indexing tasks in chitchat cluster state.
     async fn update_cluster_running_indexing_tasks(&self) {
         let indexing_tasks = self
@@ -760,6 +856,47 @@ impl Handler<SuperviseLoop> for IndexingService {
     }
 }
 
+#[derive(Debug)]
+struct RespawnDrainedPipelines {
+    pipeline_ids: Vec<IndexingPipelineId>,
+}
+
+#[async_trait]
+impl Handler<RespawnDrainedPipelines> for IndexingService {
+    type Reply = ();
+
+    async fn handle(
+        &mut self,
+        message: RespawnDrainedPipelines,
+        ctx: &ActorContext<Self>,
+    ) -> Result<(), ActorExitStatus> {
+        let pipeline_ids: Vec<IndexingPipelineId> = message
+            .pipeline_ids
+            .into_iter()
+            .filter(|pipeline_id| self.draining_pipelines.remove(pipeline_id).unwrap_or(false))
+            .collect();
+        if pipeline_ids.is_empty() {
+            return Ok(());
+        }
+        // Pipelines that fail to respawn are reported as missing in the cluster state, so the
+        // control plane applies the plan again.
+        match self
+            .spawn_pipelines(ctx, pipeline_ids.iter().collect())
+            .await
+        {
+            Ok(failed_spawning_pipeline_ids) if !failed_spawning_pipeline_ids.is_empty() => {
+                error!(pipeline_ids=?failed_spawning_pipeline_ids, "Failed to respawn pipelines.");
+            }
+            Ok(_) => {}
+            Err(error) => {
+                error!(err=?error, "Failed to respawn pipelines.");
+            }
+        }
+        self.update_cluster_running_indexing_tasks().await;
+        Ok(())
+    }
+}
+
 #[async_trait]
 impl Actor for IndexingService {
     type ObservableState = IndexingServiceCounters;
@@ -837,12 +974,12 @@ mod tests {
     use quickwit_ingest::{init_ingest_api, CreateQueueIfNotExistsRequest};
     use quickwit_metastore::{
         metastore_for_test, AddSourceRequestExt, CreateIndexRequestExt,
-        ListIndexesMetadataResponseExt, ListSplitsResponseExt,
+        ListIndexesMetadataResponseExt, ListSplitsResponseExt, UpdateIndexRequestExt,
     };
     use quickwit_proto::indexing::IndexingTask;
     use quickwit_proto::metastore::{
         AddSourceRequest, CreateIndexRequest, DeleteIndexRequest, IndexMetadataResponse,
-        ListIndexesMetadataResponse, ListSplitsResponse,
+        ListIndexesMetadataResponse, ListSplitsResponse, UpdateIndexRequest,
     };
 
     use super::*;
@@ -922,6 +1059,8 @@ mod tests {
             source_params: SourceParams::void(),
             transform_config: None,
             input_format: SourceInputFormat::Json,
+            csv_columns_opt: None,
+            dead_letter_opt: None,
         };
         let spawn_pipeline_msg = SpawnPipeline {
             index_id: index_id.clone(),
@@ -979,6 +1118,124 @@ mod tests {
         universe.assert_quit().await;
     }
 
+    #[tokio::test]
+    async fn test_indexing_service_restarts_pipelines_on_index_config_update() {
+        quickwit_common::setup_logging_for_tests();
+        let transport = ChannelTransport::default();
+        let cluster = create_cluster_for_test(Vec::new(), &["indexer"], &transport, true)
+            .await
+            .unwrap();
+        let mut metastore = metastore_for_test();
+
+        let index_id = append_random_suffix("test-indexing-service-update-index");
+        let index_uri = format!("ram:///indexes/{index_id}");
+        let index_config = IndexConfig::for_test(&index_id, &index_uri);
+
+        let create_index_request =
+            CreateIndexRequest::try_from_index_config(index_config.clone()).unwrap();
+        let index_uid: IndexUid = metastore
+            .create_index(create_index_request)
+            .await
+            .unwrap()
+            .index_uid
+            .into();
+        let source_config = SourceConfig {
+            source_id: "test-indexing-service--source".to_string(),
+            max_num_pipelines_per_indexer: NonZeroUsize::new(1).unwrap(),
+            desired_num_pipelines: NonZeroUsize::new(1).unwrap(),
+            enabled: true,
+            source_params: SourceParams::void(),
+            transform_config: None,
+            input_format: SourceInputFormat::Json,
+            csv_columns_opt: None,
+            dead_letter_opt: None,
+        };
+        let add_source_request =
+            AddSourceRequest::try_from_source_config(index_uid.clone(), source_config.clone())
+                .unwrap();
+        metastore.add_source(add_source_request).await.unwrap();
+
+        let universe = Universe::with_accelerated_time();
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (indexing_service, indexing_service_handle) =
+            spawn_indexing_service_for_test(temp_dir.path(), &universe, metastore.clone(), cluster)
+                .await;
+        let indexing_tasks = vec![IndexingTask {
+            index_uid: index_uid.to_string(),
+            source_id: source_config.source_id.clone(),
+            shard_ids: Vec::new(),
+        }];
+        let index_config_fingerprints =
+            HashMap::from_iter([(index_uid.to_string(), index_config.fingerprint())]);
+        indexing_service
+            .ask_for_res(ApplyIndexingPlanRequest {
+                indexing_tasks: indexing_tasks.clone(),
+                index_config_fingerprints,
+            })
+            .await
+            .unwrap();
+        let indexing_pipeline_id_before = universe
+            .get_one::<IndexingPipeline>()
+            .unwrap()
+            .actor_instance_id()
+            .to_string();
+
+        // The pipeline is not restarted when the index config is unchanged.
+        let index_config_fingerprints =
+            HashMap::from_iter([(index_uid.to_string(), index_config.fingerprint())]);
+        indexing_service
+            .ask_for_res(ApplyIndexingPlanRequest {
+                indexing_tasks: indexing_tasks.clone(),
+                index_config_fingerprints,
+            })
+            .await
+            .unwrap();
+        let indexing_pipelines = universe.get::<IndexingPipeline>();
+        assert_eq!(indexing_pipelines.len(), 1);
+        assert_eq!(
+            indexing_pipelines[0].actor_instance_id(),
+            indexing_pipeline_id_before
+        );
+
+        let mut new_index_config = index_config;
+        new_index_config.indexing_settings.commit_timeout_secs = 5;
+        let new_index_config_fingerprint = new_index_config.fingerprint();
+        let update_index_request =
+            UpdateIndexRequest::try_from_index_config(index_uid.clone(), new_index_config).unwrap();
+        metastore.update_index(update_index_request).await.unwrap();
+
+        let index_config_fingerprints =
+            HashMap::from_iter([(index_uid.to_string(), new_index_config_fingerprint)]);
+        indexing_service
+            .ask_for_res(ApplyIndexingPlanRequest {
+                indexing_tasks,
+                index_config_fingerprints,
+            })
+            .await
+            .unwrap();
+
+        // The pipeline drains, then is respawned with the new index config.
+        let mut is_pipeline_respawned = false;
+
+        for _ in 0..30 {
+            universe.sleep(*HEARTBEAT).await;
+            let indexing_pipelines = universe.get::<IndexingPipeline>();
+
+            if indexing_pipelines.len() == 1
+                && indexing_pipelines[0].actor_instance_id() != indexing_pipeline_id_before
+            {
+                is_pipeline_respawned = true;
+                break;
+            }
+        }
+        assert!(is_pipeline_respawned);
+
+        let observation = indexing_service_handle.process_pending_and_observe().await;
+        assert_eq!(observation.num_running_pipelines, 1);
+        assert_eq!(observation.num_running_merge_pipelines, 1);
+        universe.assert_quit().await;
+    }
+
     #[tokio::test]
     async fn test_indexing_service_supervise_pipelines() {
         quickwit_common::setup_logging_for_tests();
@@ -1013,6 +1270,8 @@ mod tests {
             }),
             transform_config: None,
             input_format: SourceInputFormat::Json,
+            csv_columns_opt: None,
+            dead_letter_opt: None,
         };
         indexing_service
             .ask_for_res(SpawnPipeline {
@@ -1079,6 +1338,8 @@ mod tests {
             source_params: SourceParams::void(),
             transform_config: None,
             input_format: SourceInputFormat::Json,
+            csv_columns_opt: None,
+            dead_letter_opt: None,
         };
         let add_source_request =
             AddSourceRequest::try_from_source_config(index_uid.clone(), source_config_1.clone())
@@ -1103,7 +1364,10 @@ mod tests {
             },
         ];
         indexing_service
-            .ask_for_res(ApplyIndexingPlanRequest { indexing_tasks })
+            .ask_for_res(ApplyIndexingPlanRequest {
+                indexing_tasks,
+                index_config_fingerprints: HashMap::new(),
+            })
             .await
             .unwrap();
         assert_eq!(
@@ -1119,6 +1383,7 @@ mod tests {
             client_log_level: None,
             client_params: serde_json::Value::Null,
             enable_backfill_mode: false,
+            schema: None,
         };
         let source_config_2 = SourceConfig {
             source_id: "test-indexing-service--source-2".to_string(),
@@ -1128,6 +1393,8 @@ mod tests {
             source_params: SourceParams::Kafka(kafka_params),
             transform_config: None,
             input_format: SourceInputFormat::Json,
+            csv_columns_opt: None,
+            dead_letter_opt: None,
         };
         let add_source_request_2 =
             AddSourceRequest::try_from_source_config(index_uid.clone(), source_config_2.clone())
@@ -1159,6 +1426,7 @@ mod tests {
         indexing_service
             .ask_for_res(ApplyIndexingPlanRequest {
                 indexing_tasks: indexing_tasks.clone(),
+                index_config_fingerprints: HashMap::new(),
             })
             .await
             .unwrap();
@@ -1208,6 +1476,7 @@ mod tests {
         indexing_service
             .ask_for_res(ApplyIndexingPlanRequest {
                 indexing_tasks: indexing_tasks.clone(),
+                index_config_fingerprints: HashMap::new(),
             })
             .await
             .unwrap();
@@ -1247,6 +1516,7 @@ mod tests {
         indexing_service
             .ask_for_res(ApplyIndexingPlanRequest {
                 indexing_tasks: Vec::new(),
+                index_config_fingerprints: HashMap::new(),
             })
             .await
             .unwrap();
@@ -1279,6 +1549,8 @@ mod tests {
             source_params: SourceParams::void(),
             transform_config: None,
             input_format: SourceInputFormat::Json,
+            csv_columns_opt: None,
+            dead_letter_opt: None,
         };
         let create_index_request = CreateIndexRequest::try_from_index_config(index_config).unwrap();
         let index_uid: IndexUid = metastore
@@ -1407,6 +1679,8 @@ mod tests {
             source_params: SourceParams::void(),
             transform_config: None,
             input_format: SourceInputFormat::Json,
+            csv_columns_opt: None,
+            dead_letter_opt: None,
         };
         index_metadata
             .sources
@@ -0,0 +1,680 @@
+// Copyright (C) 2023 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Parsers turning the raw documents of the CSV, logfmt, and syslog input formats into the JSON
+//! documents fed to the optional VRL transform and the doc mapper.
+
+use bytes::Bytes;
+use csv::StringRecord;
+use quickwit_doc_mapper::JsonObject;
+use serde_json::Value as JsonValue;
+use time::format_description::well_known::Rfc3339;
+use time::{Date, Month, OffsetDateTime, PrimitiveDateTime, Time};
+
+use super::doc_processor::{DocProcessorError, JsonDoc};
+
+/// Parses the records of a CSV source. Each record is turned into a JSON object mapping the
+/// column names to the values of the record. Empty values are omitted.
+pub(super) struct CsvParser {
+    columns_opt: Option<Vec<String>>,
+    // Header row the columns were read from, `None` if the columns are set in the source config.
+    header_opt: Option<Bytes>,
+    has_header: bool,
+}
+
+impl CsvParser {
+    /// Creates a parser reading the column names from the header rows handed over by the
+    /// source, see [`CsvParser::set_header`].
+    pub fn with_header() -> Self {
+        Self {
+            columns_opt: None,
+            header_opt: None,
+            has_header: true,
+        }
+    }
+
+    /// Creates a parser using the given column names, for sources without a header row.
+    pub fn with_columns(columns: Vec<String>) -> Self {
+        Self {
+            columns_opt: Some(columns),
+            header_opt: None,
+            has_header: false,
+        }
+    }
+
+    /// Reads the column names from the header row of the file the next records come from. This
+    /// is a no-op if the columns are set in the source config.
+    pub fn set_header(&mut self, header: &Bytes) {
+        if !self.has_header || self.header_opt.as_ref() == Some(header) {
+            return;
+        }
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .from_reader(&header[..]);
+        let mut record = StringRecord::new();
+
+        self.columns_opt = match reader.read_record(&mut record) {
+            Ok(true) => Some(record.iter().map(str::to_string).collect()),
+            Ok(false) | Err(_) => None,
+        };
+        self.header_opt = Some(header.clone());
+    }
+
+    /// Parses the records of a raw document, which usually holds a single record.
+    pub fn parse(&mut self, raw_doc: &[u8]) -> Vec<Result<JsonDoc, DocProcessorError>> {
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .from_reader(raw_doc);
+        let mut record = StringRecord::new();
+        let mut json_doc_results = Vec::new();
+        let mut previous_position = 0;
+
+        loop {
+            match reader.read_record(&mut record) {
+                Ok(true) => {}
+                Ok(false) => break,
+                Err(error) => {
+                    let error =
+                        DocProcessorError::Parse(format!("failed to parse CSV record: {error}"));
+                    json_doc_results.push(Err(error));
+                    break;
+                }
+            }
+            let position = reader.position().byte();
+            let num_bytes = (position - previous_position) as usize;
+            previous_position = position;
+
+            let Some(columns) = &self.columns_opt else {
+                let error = DocProcessorError::Parse(
+                    "CSV columns are unknown: the header row of the file is missing or invalid"
+                        .to_string(),
+                );
+                json_doc_results.push(Err(error));
+                continue;
+            };
+            if record.len() != columns.len() {
+                let error = DocProcessorError::Parse(format!(
+                    "CSV record has {} fields, but {} columns are expected",
+                    record.len(),
+                    columns.len()
+                ));
+                json_doc_results.push(Err(error));
+                continue;
+            }
+            let json_obj: JsonObject = columns
+                .iter()
+                .zip(record.iter())
+                .filter(|(_, value)| !value.is_empty())
+                .map(|(column, value)| (column.clone(), JsonValue::String(value.to_string())))
+                .collect();
+            json_doc_results.push(Ok(JsonDoc::new(json_obj, num_bytes)));
+        }
+        json_doc_results
+    }
+}
+
+/// Parses a logfmt line, such as `level=info msg="request served" duration=12ms`, into a JSON
+/// object. Values are kept as strings, and keys without a value are set to `true`.
+pub(super) fn parse_logfmt(raw_doc: &[u8]) -> Result<JsonObject, DocProcessorError> {
+    let line = std::str::from_utf8(raw_doc)
+        .map_err(|error| DocProcessorError::Parse(error.to_string()))?;
+    let mut json_obj = JsonObject::new();
+    let mut rest = line.trim_start();
+
+    while !rest.is_empty() {
+        let key_len = rest
+            .find(|ch: char| ch.is_whitespace() || ch == '=' || ch == '"')
+            .unwrap_or(rest.len());
+        if key_len == 0 {
+            return Err(logfmt_error(line, rest));
+        }
+        let key = rest[..key_len].to_string();
+        rest = &rest[key_len..];
+
+        let value = if let Some(value_str) = rest.strip_prefix('=') {
+            if let Some(quoted_str) = value_str.strip_prefix('"') {
+                let (value, after_value) = parse_quoted_string(quoted_str, unescape_logfmt_char)
+                    .ok_or_else(|| logfmt_error(line, value_str))?;
+                rest = after_value;
+                value
+            } else {
+                let value_len = value_str
+                    .find(char::is_whitespace)
+                    .unwrap_or(value_str.len());
+                rest = &value_str[value_len..];
+                value_str[..value_len].to_string()
+            };
+            JsonValue::String(value)
+        } else {
+            JsonValue::Bool(true)
+        };
+        if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+            return Err(logfmt_error(line, rest));
+        }
+        json_obj.insert(key, value);
+        rest = rest.trim_start();
+    }
+    if json_obj.is_empty() {
+        return Err(DocProcessorError::Parse(
+            "logfmt document must contain at least one key".to_string(),
+        ));
+    }
+    Ok(json_obj)
+}
+
+fn logfmt_error(line: &str, rest: &str) -> DocProcessorError {
+    DocProcessorError::Parse(format!(
+        "failed to parse logfmt document at position {}",
+        line.len() - rest.len()
+    ))
+}
+
+fn unescape_logfmt_char(ch: char) -> Option<char> {
+    match ch {
+        '"' | '\\' => Some(ch),
+        'n' => Some('\n'),
+        'r' => Some('\r'),
+        't' => Some('\t'),
+        _ => None,
+    }
+}
+
+fn unescape_syslog_char(ch: char) -> Option<char> {
+    match ch {
+        '"' | '\\' | ']' => Some(ch),
+        _ => None,
+    }
+}
+
+/// Parses a string following an opening double quote, up to the closing double quote. Returns
+/// the unescaped string and the input remaining after the closing quote.
+fn parse_quoted_string(
+    input: &str,
+    unescape_char: fn(char) -> Option<char>,
+) -> Option<(String, &str)> {
+    let mut value = String::new();
+    let mut chars = input.char_indices();
+
+    while let Some((idx, ch)) = chars.next() {
+        match ch {
+            '"' => return Some((value, &input[idx + 1..])),
+            '\\' => {
+                let (_, escaped_ch) = chars.next()?;
+                if let Some(unescaped_ch) = unescape_char(escaped_ch) {
+                    value.push(unescaped_ch);
+                } else {
+                    value.push('\\');
+                    value.push(escaped_ch);
+                }
+            }
+            _ => value.push(ch),
+        }
+    }
+    None
+}
+
+const SYSLOG_FACILITIES: [&str; 24] = [
+    "kern", "user", "mail", "daemon", "auth", "syslog", "lpr", "news", "uucp", "cron", "authpriv",
+    "ftp", "ntp", "audit", "alert", "clockd", "local0", "local1", "local2", "local3", "local4",
+    "local5", "local6", "local7",
+];
+
+const SYSLOG_SEVERITIES: [&str; 8] = [
+    "emerg", "alert", "crit", "err", "warning", "notice", "info", "debug",
+];
+
+const RFC3164_MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// Parses an RFC 5424 or RFC 3164 syslog message into a JSON object with the `facility`,
+/// `severity`, `version`, `timestamp`, `hostname`, `appname`, `procid`, `msgid`,
+/// `structured_data`, and `message` fields. The fields missing from the message are omitted.
+pub(super) fn parse_syslog(raw_doc: &[u8]) -> Result<JsonObject, DocProcessorError> {
+    parse_syslog_at(raw_doc, OffsetDateTime::now_utc())
+}
+
+fn parse_syslog_at(raw_doc: &[u8], now: OffsetDateTime) -> Result<JsonObject, DocProcessorError> {
+    let line = std::str::from_utf8(raw_doc)
+        .map_err(|error| DocProcessorError::Parse(error.to_string()))?
+        .trim_end_matches(['\r', '\n']);
+    let (priority_str, rest) = line
+        .strip_prefix('<')
+        .and_then(|rest| rest.split_once('>'))
+        .ok_or_else(|| syslog_error("missing priority"))?;
+    let priority: usize = priority_str
+        .parse()
+        .ok()
+        .filter(|priority| priority_str.len() <= 3 && *priority < 192)
+        .ok_or_else(|| syslog_error("invalid priority"))?;
+
+    let mut json_obj = JsonObject::new();
+    json_obj.insert(
+        "facility".to_string(),
+        JsonValue::String(SYSLOG_FACILITIES[priority / 8].to_string()),
+    );
+    json_obj.insert(
+        "severity".to_string(),
+        JsonValue::String(SYSLOG_SEVERITIES[priority % 8].to_string()),
+    );
+    if rest.starts_with(|ch: char| ch.is_ascii_digit()) {
+        parse_rfc5424(rest, &mut json_obj)?;
+    } else {
+        parse_rfc3164(rest, now, &mut json_obj);
+    }
+    Ok(json_obj)
+}
+
+fn syslog_error(message: &str) -> DocProcessorError {
+    DocProcessorError::Parse(format!("failed to parse syslog message: {message}"))
+}
+
+fn insert_string(json_obj: &mut JsonObject, key: &str, value: &str) {
+    json_obj.insert(key.to_string(), JsonValue::String(value.to_string()));
+}
+
+/// Parses the part of an RFC 5424 message following its priority:
+/// `VERSION TIMESTAMP HOSTNAME APP-NAME PROCID MSGID STRUCTURED-DATA [MSG]`.
+fn parse_rfc5424(input: &str, json_obj: &mut JsonObject) -> Result<(), DocProcessorError> {
+    let mut fields = input.splitn(7, ' ');
+    let version: u64 = fields
+        .next()
+        .and_then(|version_str| version_str.parse().ok())
+        .ok_or_else(|| syslog_error("invalid version"))?;
+    json_obj.insert("version".to_string(), JsonValue::from(version));
+
+    for key in ["timestamp", "hostname", "appname", "procid", "msgid"] {
+        let value = fields
+            .next()
+            .filter(|value| !value.is_empty())
+            .ok_or_else(|| syslog_error("missing header field"))?;
+        // `-` denotes a nil value.
+        if value != "-" {
+            insert_string(json_obj, key, value);
+        }
+    }
+    let rest = fields
+        .next()
+        .ok_or_else(|| syslog_error("missing structured data"))?;
+    let rest = if let Some(rest) = rest.strip_prefix('-') {
+        rest
+    } else {
+        let (structured_data, rest) = parse_structured_data(rest)?;
+        json_obj.insert(
+            "structured_data".to_string(),
+            JsonValue::Object(structured_data),
+        );
+        rest
+    };
+    if rest.is_empty() {
+        return Ok(());
+    }
+    let message = rest
+        .strip_prefix(' ')
+        .ok_or_else(|| syslog_error("invalid structured data"))?;
+    let message = message.strip_prefix('\u{feff}').unwrap_or(message);
+    insert_string(json_obj, "message", message);
+    Ok(())
+}
+
+/// Parses the structured data elements of an RFC 5424 message, such as
+/// `[exampleSDID@32473 iut="3" eventSource="Application"]`, into a JSON object mapping the
+/// element IDs to their parameters. Returns the object and the input remaining after the last
+/// element.
+fn parse_structured_data(input: &str) -> Result<(JsonObject, &str), DocProcessorError> {
+    let mut structured_data = JsonObject::new();
+    let mut rest = input;
+
+    if !rest.starts_with('[') {
+        return Err(syslog_error("invalid structured data"));
+    }
+    while let Some(element) = rest.strip_prefix('[') {
+        let id_len = element
+            .find([' ', ']'])
+            .ok_or_else(|| syslog_error("unterminated structured data element"))?;
+        let element_id = &element[..id_len];
+        let mut params = JsonObject::new();
+        rest = &element[id_len..];
+
+        loop {
+            rest = rest.trim_start_matches(' ');
+            if let Some(after_element) = rest.strip_prefix(']') {
+                rest = after_element;
+                break;
+            }
+            let (param_name, quoted_str) = rest
+                .split_once("=\"")
+                .ok_or_else(|| syslog_error("invalid structured data parameter"))?;
+            let (param_value, after_value) = parse_quoted_string(quoted_str, unescape_syslog_char)
+                .ok_or_else(|| syslog_error("unterminated structured data parameter"))?;
+            params.insert(param_name.to_string(), JsonValue::String(param_value));
+            rest = after_value;
+        }
+        structured_data.insert(element_id.to_string(), JsonValue::Object(params));
+    }
+    Ok((structured_data, rest))
+}
+
+/// Parses the part of an RFC 3164 message following its priority:
+/// `TIMESTAMP HOSTNAME TAG[PID]: MSG`. The format is loosely defined, so the parts that cannot be
+/// recognized are kept in the message.
+fn parse_rfc3164(input: &str, now: OffsetDateTime, json_obj: &mut JsonObject) {
+    let mut rest = input;
+
+    if let Some((timestamp, after_timestamp)) = parse_rfc3164_timestamp(rest, now) {
+        insert_string(json_obj, "timestamp", &timestamp);
+        rest = after_timestamp.trim_start_matches(' ');
+
+        if let Some((hostname, after_hostname)) = rest.split_once(' ') {
+            if !hostname.is_empty() && !hostname.ends_with(':') && !hostname.contains('[') {
+                insert_string(json_obj, "hostname", hostname);
+                rest = after_hostname;
+            }
+        }
+    }
+    if let Some((tag, message)) = rest.split_once(':') {
+        if !tag.is_empty() && tag.len() <= 48 && !tag.contains(' ') {
+            let (appname, procid_opt) =
+                match tag.strip_suffix(']').and_then(|tag| tag.split_once('[')) {
+                    Some((appname, procid)) => (appname, Some(procid)),
+                    None => (tag, None),
+                };
+            insert_string(json_obj, "appname", appname);
+            if let Some(procid) = procid_opt {
+                insert_string(json_obj, "procid", procid);
+            }
+            rest = message.strip_prefix(' ').unwrap_or(message);
+        }
+    }
+    insert_string(json_obj, "message", rest);
+}
+
+/// Parses an RFC 3164 timestamp, such as `Oct 11 22:14:15`, into an RFC 3339 timestamp. As the
+/// year and the time zone are not specified, the timestamp is assumed to be in UTC and in the last
+/// twelve months.
+fn parse_rfc3164_timestamp(input: &str, now: OffsetDateTime) -> Option<(String, &str)> {
+    let timestamp_str = input
+        .get(..15)
+        .filter(|timestamp_str| timestamp_str.is_ascii())?;
+    let rest = &input[15..];
+
+    if !rest.is_empty() && !rest.starts_with(' ') {
+        return None;
+    }
+    let month_idx = RFC3164_MONTHS
+        .iter()
+        .position(|month| *month == &timestamp_str[..3])?;
+    let month = Month::try_from(month_idx as u8 + 1).ok()?;
+    if &timestamp_str[3..4] != " " || &timestamp_str[6..7] != " " {
+        return None;
+    }
+    let day: u8 = timestamp_str[4..6].trim_start().parse().ok()?;
+    let mut time_parts = timestamp_str[7..].split(':');
+    let mut next_time_part = || -> Option<u8> {
+        time_parts
+            .next()
+            .filter(|time_part| time_part.len() == 2)?
+            .parse()
+            .ok()
+    };
+    let time = Time::from_hms(next_time_part()?, next_time_part()?, next_time_part()?).ok()?;
+
+    let date = Date::from_calendar_date(now.year(), month, day).ok()?;
+    let mut date_time = PrimitiveDateTime::new(date, time).assume_utc();
+    if date_time > now + time::Duration::days(1) {
+        date_time = date_time.replace_year(now.year() - 1).ok()?;
+    }
+    let timestamp = date_time.format(&Rfc3339).ok()?;
+    Some((timestamp, rest))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use time::macros::datetime;
+
+    use super::*;
+
+    fn parse_csv(csv_parser: &mut CsvParser, raw_doc: &str) -> Vec<Result<JsonValue, String>> {
+        csv_parser
+            .parse(raw_doc.as_bytes())
+            .into_iter()
+            .map(|json_doc_result| {
+                json_doc_result
+                    .map(|json_doc| JsonValue::Object(json_doc.json_obj))
+                    .map_err(|error| format!("{error:?}"))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_csv_parser_with_header() {
+        let mut csv_parser = CsvParser::with_header();
+        let json_docs = parse_csv(&mut csv_parser, "1628837061,INFO,hello\n");
+        assert_eq!(json_docs.len(), 1);
+        assert!(json_docs[0]
+            .as_ref()
+            .unwrap_err()
+            .contains("CSV columns are unknown"));
+
+        csv_parser.set_header(&Bytes::from_static(b"timestamp,severity,message\n"));
+        let json_docs = parse_csv(&mut csv_parser, "1628837062,INFO,\"hello, world\"\n");
+        assert_eq!(
+            json_docs,
+            [Ok(json!({
+                "timestamp": "1628837062",
+                "severity": "INFO",
+                "message": "hello, world"
+            }))]
+        );
+        let json_docs = parse_csv(
+            &mut csv_parser,
+            "1628837063,,bye\n1628837064,WARN,\"\"\"quoted\"\"\"",
+        );
+        assert_eq!(
+            json_docs,
+            [
+                Ok(json!({"timestamp": "1628837063", "message": "bye"})),
+                Ok(json!({
+                    "timestamp": "1628837064",
+                    "severity": "WARN",
+                    "message": "\"quoted\""
+                }))
+            ]
+        );
+        let json_docs = parse_csv(&mut csv_parser, "1628837065,ERROR");
+        assert_eq!(json_docs.len(), 1);
+        assert!(json_docs[0]
+            .as_ref()
+            .unwrap_err()
+            .contains("CSV record has 2 fields, but 3 columns are expected"));
+
+        // The next file has a different header row.
+        csv_parser.set_header(&Bytes::from_static(b"message,timestamp\n"));
+        let json_docs = parse_csv(&mut csv_parser, "hello,1628837066\n");
+        assert_eq!(
+            json_docs,
+            [Ok(json!({"timestamp": "1628837066", "message": "hello"}))]
+        );
+    }
+
+    #[test]
+    fn test_csv_parser_with_columns() {
+        let columns = vec!["timestamp".to_string(), "message".to_string()];
+        let mut csv_parser = CsvParser::with_columns(columns);
+        csv_parser.set_header(&Bytes::from_static(b"severity,message\n"));
+
+        let json_docs = parse_csv(&mut csv_parser, "timestamp,message");
+        assert_eq!(
+            json_docs,
+            [Ok(json!({"timestamp": "timestamp", "message": "message"}))]
+        );
+        let json_doc = csv_parser
+            .parse(b"1628837062,hello\n")
+            .pop()
+            .unwrap()
+            .unwrap();
+        assert_eq!(json_doc.num_bytes, 17);
+
+        let json_docs = csv_parser.parse(b"1628837062,\xff");
+        assert!(json_docs[0].is_err());
+    }
+
+    #[test]
+    fn test_parse_logfmt() {
+        let json_obj = parse_logfmt(
+            br#"level=info msg="request \"served\"\n" duration=12ms  empty= cached path=/a=b"#,
+        )
+        .unwrap();
+        assert_eq!(
+            JsonValue::Object(json_obj),
+            json!({
+                "level": "info",
+                "msg": "request \"served\"\n",
+                "duration": "12ms",
+                "empty": "",
+                "cached": true,
+                "path": "/a=b"
+            })
+        );
+        let json_obj = parse_logfmt(b"level=info level=warn\n").unwrap();
+        assert_eq!(JsonValue::Object(json_obj), json!({"level": "warn"}));
+
+        for invalid_line in [
+            "",
+            "  ",
+            "=info",
+            "msg=\"unterminated",
+            "msg=\"quoted\"suffix",
+            "key\"=value",
+        ] {
+            parse_logfmt(invalid_line.as_bytes()).unwrap_err();
+        }
+    }
+
+    #[test]
+    fn test_parse_syslog_rfc5424() {
+        let json_obj = parse_syslog(
+            br#"<165>1 2003-10-11T22:14:15.003Z mymachine.example.com evntslog - ID47 [exampleSDID@32473 iut="3" eventSource="Appli\"cation\]"][examplePriority@32473 class="high"] An application event"#,
+        )
+        .unwrap();
+        assert_eq!(
+            JsonValue::Object(json_obj),
+            json!({
+                "facility": "local4",
+                "severity": "notice",
+                "version": 1,
+                "timestamp": "2003-10-11T22:14:15.003Z",
+                "hostname": "mymachine.example.com",
+                "appname": "evntslog",
+                "msgid": "ID47",
+                "structured_data": {
+                    "exampleSDID@32473": {"iut": "3", "eventSource": "Appli\"cation]"},
+                    "examplePriority@32473": {"class": "high"}
+                },
+                "message": "An application event"
+            })
+        );
+        let json_obj =
+            parse_syslog("<34>1 - - su 1234 - - \u{feff}'su root' failed\n".as_bytes()).unwrap();
+        assert_eq!(
+            JsonValue::Object(json_obj),
+            json!({
+                "facility": "auth",
+                "severity": "crit",
+                "version": 1,
+                "appname": "su",
+                "procid": "1234",
+                "message": "'su root' failed"
+            })
+        );
+        let json_obj = parse_syslog(b"<0>1 - - - - - -").unwrap();
+        assert_eq!(
+            JsonValue::Object(json_obj),
+            json!({"facility": "kern", "severity": "emerg", "version": 1})
+        );
+        for invalid_line in [
+            "no priority",
+            "<192>1 - - - - - -",
+            "<abc>1 - - - - - -",
+            "<13>1 - - - -",
+            "<13>1 - - - - - [unterminated",
+            "<13>1 - - - - - [id key=\"value\"]message",
+        ] {
+            parse_syslog(invalid_line.as_bytes()).unwrap_err();
+        }
+    }
+
+    #[test]
+    fn test_parse_syslog_rfc3164() {
+        let now = datetime!(2023-12-01 10:00:00 UTC);
+        let json_obj = parse_syslog_at(
+            b"<34>Oct 11 22:14:15 mymachine su[230]: 'su root' failed for lonvick on /dev/pts/8",
+            now,
+        )
+        .unwrap();
+        assert_eq!(
+            JsonValue::Object(json_obj),
+            json!({
+                "facility": "auth",
+                "severity": "crit",
+                "timestamp": "2023-10-11T22:14:15Z",
+                "hostname": "mymachine",
+                "appname": "su",
+                "procid": "230",
+                "message": "'su root' failed for lonvick on /dev/pts/8"
+            })
+        );
+        // Timestamps later than now are assumed to be from the previous year.
+        let json_obj = parse_syslog_at(b"<13>Dec 31 23:59:59 host cron: job done", now).unwrap();
+        assert_eq!(
+            JsonValue::Object(json_obj),
+            json!({
+                "facility": "user",
+                "severity": "notice",
+                "timestamp": "2022-12-31T23:59:59Z",
+                "hostname": "host",
+                "appname": "cron",
+                "message": "job done"
+            })
+        );
+        let json_obj = parse_syslog_at(b"<13>Nov  2 03:04:05 kernel: boot", now).unwrap();
+        assert_eq!(
+            JsonValue::Object(json_obj),
+            json!({
+                "facility": "user",
+                "severity": "notice",
+                "timestamp": "2023-11-02T03:04:05Z",
+                "appname": "kernel",
+                "message": "boot"
+            })
+        );
+        let json_obj = parse_syslog_at(b"<13>an unstructured message: hello", now).unwrap();
+        assert_eq!(
+            JsonValue::Object(json_obj),
+            json!({
+                "facility": "user",
+                "severity": "notice",
+                "message": "an unstructured message: hello"
+            })
+        );
+    }
+}
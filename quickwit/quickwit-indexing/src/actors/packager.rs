@@ -30,6 +30,7 @@ use quickwit_actors::{Actor, ActorContext, ActorExitStatus, Handler, Mailbox, Qu
 use quickwit_common::runtimes::RuntimeType;
 use quickwit_common::temp_dir::TempDirectory;
 use quickwit_directories::write_hotcache;
+use quickwit_doc_mapper::split_fields::{list_split_json_fields, SPLIT_FIELDS_FILE_NAME};
 use quickwit_doc_mapper::tag_pruning::append_to_tag_set;
 use quickwit_doc_mapper::NamedField;
 use tantivy::schema::FieldType;
@@ -211,7 +212,7 @@ fn list_split_files(
 
 fn build_hotcache<W: io::Write>(split_path: &Path, out: &mut W) -> anyhow::Result<()> {
     let mmap_directory = tantivy::directory::MmapDirectory::open(split_path)?;
-    write_hotcache(mmap_directory, out)?;
+    write_hotcache(mmap_directory, &[Path::new(SPLIT_FIELDS_FILE_NAME)], out)?;
     Ok(())
 }
 
@@ -277,7 +278,7 @@ fn create_packaged_split(
     ctx: &ActorContext<Packager>,
 ) -> anyhow::Result<PackagedSplit> {
     info!(split_id = split.split_id(), "create-packaged-split");
-    let split_files = list_split_files(segment_metas, &split.split_scratch_directory)?;
+    let mut split_files = list_split_files(segment_metas, &split.split_scratch_directory)?;
 
     // Extracts tag values from inverted indexes only when a field cardinality is less
     // than `MAX_VALUES_PER_TAG_FIELD`.
@@ -308,6 +309,18 @@ fn create_packaged_split(
 
     ctx.record_progress();
 
+    // Lists the fields of the JSON objects now that the term dictionaries are local, so that
+    // listing the fields of the split does not require loading them.
+    debug!(split_id = split.split_id(), "list-split-fields");
+    let split_json_fields = list_split_json_fields(&index_reader.searcher())?;
+    let split_fields_path = split
+        .split_scratch_directory
+        .path()
+        .join(SPLIT_FIELDS_FILE_NAME);
+    std::fs::write(&split_fields_path, serde_json::to_vec(&split_json_fields)?)?;
+    split_files.push(split_fields_path);
+    ctx.record_progress();
+
     debug!(split_id = split.split_id(), "build-hotcache");
     let mut hotcache_bytes = Vec::new();
     build_hotcache(split.split_scratch_directory.path(), &mut hotcache_bytes)?;
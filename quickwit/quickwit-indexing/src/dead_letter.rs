@@ -0,0 +1,475 @@
+// Copyright (C) 2023 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Dead-letter destinations recording the documents rejected by the doc processor, so that they
+//! can be inspected and replayed once the doc mapping or the transform is fixed.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{bail, Context};
+use base64::prelude::{Engine, BASE64_STANDARD};
+use bytes::Bytes;
+use quickwit_config::{DeadLetterConfig, SourceConfig, INGEST_API_SOURCE_ID};
+use quickwit_ingest::{
+    get_ingest_api_service, CommitType, DocBatchBuilder, IngestRequest, IngestService,
+    IngestServiceClient,
+};
+use quickwit_metastore::checkpoint::PartitionId;
+use quickwit_metastore::IndexMetadata;
+use quickwit_proto::types::Position;
+use quickwit_storage::Storage;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+/// Directory of the index storage under which the [`DeadLetterConfig::Storage`] destination
+/// writes its NDJSON files, one subdirectory per source.
+pub const DEAD_LETTER_DIR: &str = "dead-letter";
+
+/// Number of bytes of dead-letter documents above which the [`DeadLetterConfig::Storage`]
+/// destination writes them to a file.
+const DEAD_LETTER_FILE_TARGET_NUM_BYTES: usize = 8 * 1024 * 1024;
+
+/// Maximum amount of time the [`DeadLetterConfig::Storage`] destination buffers dead-letter
+/// documents before writing them to a file.
+pub(crate) const DEAD_LETTER_FLUSH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Kind of error that caused a document to be rejected.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DeadLetterErrorKind {
+    /// The document could not be parsed into a JSON object, or into a document of the index.
+    Parse,
+    /// The document is missing a field required by the doc mapping.
+    Schema,
+    /// The VRL transform of the source failed.
+    Transform,
+}
+
+/// A document rejected by the doc processor, as recorded in a dead-letter destination.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct DeadLetterDoc {
+    /// Time at which the document was rejected, in seconds since the Unix epoch.
+    pub timestamp: i64,
+    pub index_id: String,
+    pub source_id: String,
+    /// Partition the document was read from, if the source records it.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    pub partition_id: Option<PartitionId>,
+    /// Checkpoint position of the partition right after the document, if the source records it.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    pub position: Option<Position>,
+    pub error_kind: DeadLetterErrorKind,
+    pub error_message: String,
+    /// Raw payload of the document, if it is valid UTF-8.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payload: Option<String>,
+    /// Base64-encoded raw payload of the document, if it is not valid UTF-8.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payload_base64: Option<String>,
+}
+
+impl DeadLetterDoc {
+    pub fn new(
+        index_id: String,
+        source_id: String,
+        position_opt: Option<(PartitionId, Position)>,
+        error_kind: DeadLetterErrorKind,
+        error_message: String,
+        raw_doc: &[u8],
+    ) -> Self {
+        let (payload, payload_base64) = match std::str::from_utf8(raw_doc) {
+            Ok(payload) => (Some(payload.to_string()), None),
+            Err(_) => (None, Some(BASE64_STANDARD.encode(raw_doc))),
+        };
+        let (partition_id, position) = position_opt.unzip();
+        Self {
+            timestamp: OffsetDateTime::now_utc().unix_timestamp(),
+            index_id,
+            source_id,
+            partition_id,
+            position,
+            error_kind,
+            error_message,
+            payload,
+            payload_base64,
+        }
+    }
+
+    /// Returns the raw payload of the document, as read from the source.
+    pub fn raw_doc(&self) -> anyhow::Result<Bytes> {
+        if let Some(payload) = &self.payload {
+            return Ok(Bytes::from(payload.clone()));
+        }
+        let payload_base64 = self
+            .payload_base64
+            .as_ref()
+            .context("dead-letter document has no payload")?;
+        let raw_doc = BASE64_STANDARD
+            .decode(payload_base64)
+            .context("failed to decode base64 payload of dead-letter document")?;
+        Ok(Bytes::from(raw_doc))
+    }
+}
+
+/// Parses the dead-letter documents of an NDJSON file written by the
+/// [`DeadLetterConfig::Storage`] destination.
+pub fn parse_dead_letter_docs(ndjson: &[u8]) -> anyhow::Result<Vec<DeadLetterDoc>> {
+    serde_json::Deserializer::from_slice(ndjson)
+        .into_iter::<DeadLetterDoc>()
+        .map(|dead_letter_doc_res| {
+            dead_letter_doc_res.context("failed to parse dead-letter document")
+        })
+        .collect()
+}
+
+/// Checks that the dead-letter documents of a source can be replayed through the ingest API.
+///
+/// Replayed documents are processed by the ingest API source of the index, so the source must
+/// parse and transform its documents the same way: otherwise, the documents would be rejected
+/// again, or indexed without their transform.
+pub fn check_dead_letter_replay(
+    index_metadata: &IndexMetadata,
+    source_id: &str,
+) -> anyhow::Result<()> {
+    let Some(source_config) = index_metadata.sources.get(source_id) else {
+        bail!(
+            "source `{source_id}` of index `{}` does not exist",
+            index_metadata.index_id()
+        );
+    };
+    let ingest_api_source_config = index_metadata
+        .sources
+        .get(INGEST_API_SOURCE_ID)
+        .cloned()
+        .unwrap_or_else(SourceConfig::ingest_api_default);
+
+    if source_config.input_format != ingest_api_source_config.input_format {
+        bail!(
+            "source `{source_id}` reads documents in the `{:?}` format, but replayed documents \
+             are ingested in the `{:?}` format of the ingest API source",
+            source_config.input_format,
+            ingest_api_source_config.input_format
+        );
+    }
+    if source_config.transform_config != ingest_api_source_config.transform_config {
+        bail!(
+            "source `{source_id}` and the ingest API source have different transforms: replayed \
+             documents would not go through the transform of the source"
+        );
+    }
+    Ok(())
+}
+
+/// Returns the directory, relative to the index storage, holding the dead-letter files of a
+/// source.
+pub fn dead_letter_dir_path(source_id: &str) -> PathBuf {
+    Path::new(DEAD_LETTER_DIR).join(source_id)
+}
+
+/// Deletes the dead-letter files written under the index storage by the
+/// [`DeadLetterConfig::Storage`] destinations of the sources of an index.
+pub async fn delete_dead_letter_files(index_storage: &dyn Storage) -> anyhow::Result<()> {
+    let dead_letter_prefix = format!("{DEAD_LETTER_DIR}/");
+    let file_paths = index_storage
+        .list(Path::new(&dead_letter_prefix))
+        .await
+        .context("failed to list dead-letter files")?;
+    if file_paths.is_empty() {
+        return Ok(());
+    }
+    let file_paths: Vec<&Path> = file_paths.iter().map(PathBuf::as_path).collect();
+    index_storage
+        .bulk_delete(&file_paths)
+        .await
+        .context("failed to delete dead-letter files")?;
+    Ok(())
+}
+
+/// Writes the dead-letter documents of a source to its configured destination.
+///
+/// The [`DeadLetterConfig::Storage`] destination buffers the documents and writes them to a file
+/// once [`DEAD_LETTER_FILE_TARGET_NUM_BYTES`] are buffered, or when flushed.
+pub(crate) enum DeadLetterSink {
+    Index {
+        index_id: String,
+        ingest_service: IngestServiceClient,
+    },
+    Storage {
+        storage: Arc<dyn Storage>,
+        source_id: String,
+        ndjson_buffer: Vec<u8>,
+        num_buffered_docs: usize,
+    },
+}
+
+impl DeadLetterSink {
+    pub async fn try_new(
+        dead_letter_config: &DeadLetterConfig,
+        source_id: &str,
+        index_storage: Arc<dyn Storage>,
+        queues_dir_path: &Path,
+    ) -> anyhow::Result<Self> {
+        let dead_letter_sink = match dead_letter_config {
+            DeadLetterConfig::Index { index_id } => {
+                let ingest_api_service = get_ingest_api_service(queues_dir_path).await?;
+                Self::Index {
+                    index_id: index_id.clone(),
+                    ingest_service: IngestServiceClient::from_mailbox(ingest_api_service),
+                }
+            }
+            DeadLetterConfig::Storage => Self::Storage {
+                storage: index_storage,
+                source_id: source_id.to_string(),
+                ndjson_buffer: Vec::new(),
+                num_buffered_docs: 0,
+            },
+        };
+        Ok(dead_letter_sink)
+    }
+
+    /// Returns the number of documents written but not flushed yet.
+    pub fn num_buffered_docs(&self) -> usize {
+        match self {
+            Self::Index { .. } => 0,
+            Self::Storage {
+                num_buffered_docs, ..
+            } => *num_buffered_docs,
+        }
+    }
+
+    /// Writes the dead-letter documents, or buffers them for the [`DeadLetterConfig::Storage`]
+    /// destination. On error, the documents passed and the buffered documents are lost.
+    pub async fn write(&mut self, dead_letter_docs: &[DeadLetterDoc]) -> anyhow::Result<()> {
+        match self {
+            Self::Index {
+                index_id,
+                ingest_service,
+            } => {
+                let mut doc_batch_builder = DocBatchBuilder::new(index_id.clone()).json_writer();
+                for dead_letter_doc in dead_letter_docs {
+                    doc_batch_builder.ingest_doc(dead_letter_doc)?;
+                }
+                let ingest_request = IngestRequest {
+                    doc_batches: vec![doc_batch_builder.build()],
+                    commit: CommitType::Auto.into(),
+                };
+                ingest_service
+                    .ingest(ingest_request)
+                    .await
+                    .with_context(|| {
+                        format!("failed to ingest dead-letter documents into index `{index_id}`")
+                    })?;
+            }
+            Self::Storage {
+                ndjson_buffer,
+                num_buffered_docs,
+                ..
+            } => {
+                for dead_letter_doc in dead_letter_docs {
+                    serde_json::to_writer(&mut *ndjson_buffer, dead_letter_doc)?;
+                    ndjson_buffer.push(b'\n');
+                    *num_buffered_docs += 1;
+                }
+                if ndjson_buffer.len() >= DEAD_LETTER_FILE_TARGET_NUM_BYTES {
+                    self.flush().await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the buffered documents of the [`DeadLetterConfig::Storage`] destination to a file.
+    /// On error, the buffered documents are lost.
+    pub async fn flush(&mut self) -> anyhow::Result<()> {
+        let Self::Storage {
+            storage,
+            source_id,
+            ndjson_buffer,
+            num_buffered_docs,
+        } = self
+        else {
+            return Ok(());
+        };
+        if *num_buffered_docs == 0 {
+            return Ok(());
+        }
+        let ndjson = std::mem::take(ndjson_buffer);
+        *num_buffered_docs = 0;
+
+        let file_path =
+            dead_letter_dir_path(source_id).join(format!("{}.ndjson", ulid::Ulid::new()));
+        storage
+            .put(&file_path, Box::new(ndjson))
+            .await
+            .with_context(|| {
+                format!("failed to write dead-letter file `{}`", file_path.display())
+            })?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quickwit_config::{SourceInputFormat, TransformConfig};
+    use quickwit_storage::RamStorage;
+
+    use super::*;
+
+    fn dead_letter_doc_for_test(raw_doc: &[u8]) -> DeadLetterDoc {
+        DeadLetterDoc::new(
+            "test-index".to_string(),
+            "test-source".to_string(),
+            Some((PartitionId::from(0u64), Position::from(2u64))),
+            DeadLetterErrorKind::Parse,
+            "expected value at line 1 column 1".to_string(),
+            raw_doc,
+        )
+    }
+
+    #[test]
+    fn test_dead_letter_doc_payload() {
+        let dead_letter_doc = dead_letter_doc_for_test(br#"{"body": "foo""#);
+        assert_eq!(
+            dead_letter_doc.payload.as_deref(),
+            Some(r#"{"body": "foo""#)
+        );
+        assert!(dead_letter_doc.payload_base64.is_none());
+        assert_eq!(
+            dead_letter_doc.raw_doc().unwrap(),
+            &br#"{"body": "foo""#[..]
+        );
+
+        let dead_letter_doc = dead_letter_doc_for_test(&[0xff, 0xfe]);
+        assert!(dead_letter_doc.payload.is_none());
+        assert_eq!(dead_letter_doc.payload_base64.as_deref(), Some("//4="));
+        assert_eq!(dead_letter_doc.raw_doc().unwrap(), &[0xff, 0xfe][..]);
+    }
+
+    #[tokio::test]
+    async fn test_dead_letter_sink_storage() {
+        let storage = Arc::new(RamStorage::default());
+        let mut dead_letter_sink = DeadLetterSink::try_new(
+            &DeadLetterConfig::Storage,
+            "test-source",
+            storage.clone(),
+            Path::new("/queues-not-used"),
+        )
+        .await
+        .unwrap();
+        let dead_letter_docs = vec![
+            dead_letter_doc_for_test(b"foo"),
+            dead_letter_doc_for_test(b"bar"),
+        ];
+        dead_letter_sink.write(&dead_letter_docs).await.unwrap();
+        assert_eq!(dead_letter_sink.num_buffered_docs(), 2);
+        assert!(storage.list_files().await.is_empty());
+
+        dead_letter_sink.flush().await.unwrap();
+        assert_eq!(dead_letter_sink.num_buffered_docs(), 0);
+
+        let file_paths = storage.list_files().await;
+        assert_eq!(file_paths.len(), 1);
+        assert!(file_paths[0].starts_with("dead-letter/test-source"));
+
+        let ndjson = storage.get_all(&file_paths[0]).await.unwrap();
+        let parsed_dead_letter_docs = parse_dead_letter_docs(ndjson.as_slice()).unwrap();
+        assert_eq!(parsed_dead_letter_docs, dead_letter_docs);
+
+        // Flushing without buffered docs does not write an empty file.
+        dead_letter_sink.flush().await.unwrap();
+        assert_eq!(storage.list_files().await.len(), 1);
+
+        // Docs are written as soon as the buffer reaches its target size.
+        let large_dead_letter_doc =
+            dead_letter_doc_for_test(&vec![b'a'; DEAD_LETTER_FILE_TARGET_NUM_BYTES]);
+        dead_letter_sink
+            .write(&[large_dead_letter_doc])
+            .await
+            .unwrap();
+        assert_eq!(dead_letter_sink.num_buffered_docs(), 0);
+        assert_eq!(storage.list_files().await.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_delete_dead_letter_files() {
+        let storage = RamStorage::default();
+        delete_dead_letter_files(&storage).await.unwrap();
+
+        for file_path in [
+            "dead-letter/source-1/foo.ndjson",
+            "dead-letter/source-2/bar.ndjson",
+            "dead-letter-split.split",
+        ] {
+            storage
+                .put(Path::new(file_path), Box::new(b"{}\n".to_vec()))
+                .await
+                .unwrap();
+        }
+        delete_dead_letter_files(&storage).await.unwrap();
+
+        let file_paths = storage.list_files().await;
+        assert_eq!(file_paths, [PathBuf::from("dead-letter-split.split")]);
+    }
+
+    #[test]
+    fn test_check_dead_letter_replay() {
+        let mut index_metadata = IndexMetadata::for_test("test-index", "ram:///test-index");
+        index_metadata
+            .add_source(SourceConfig::ingest_api_default())
+            .unwrap();
+
+        let mut json_source_config = SourceConfig::ingest_api_default();
+        json_source_config.source_id = "json-source".to_string();
+        index_metadata
+            .add_source(json_source_config.clone())
+            .unwrap();
+
+        let mut csv_source_config = json_source_config.clone();
+        csv_source_config.source_id = "csv-source".to_string();
+        csv_source_config.input_format = SourceInputFormat::Csv;
+        index_metadata.add_source(csv_source_config).unwrap();
+
+        let mut transform_source_config = json_source_config;
+        transform_source_config.source_id = "transform-source".to_string();
+        transform_source_config.transform_config = Some(TransformConfig::new(
+            ".message = downcase(string!(.message))".to_string(),
+            None,
+        ));
+        index_metadata.add_source(transform_source_config).unwrap();
+
+        check_dead_letter_replay(&index_metadata, INGEST_API_SOURCE_ID).unwrap();
+        check_dead_letter_replay(&index_metadata, "json-source").unwrap();
+
+        let error = check_dead_letter_replay(&index_metadata, "csv-source").unwrap_err();
+        assert!(error.to_string().contains("`Csv` format"));
+
+        let error = check_dead_letter_replay(&index_metadata, "transform-source").unwrap_err();
+        assert!(error.to_string().contains("different transforms"));
+
+        check_dead_letter_replay(&index_metadata, "unknown-source").unwrap_err();
+    }
+}
@@ -39,6 +39,7 @@ pub use crate::split_store::{get_tantivy_directory_from_split_bundle, IndexingSp
 
 pub mod actors;
 mod controlled_directory;
+pub mod dead_letter;
 pub mod merge_policy;
 mod metrics;
 pub mod models;
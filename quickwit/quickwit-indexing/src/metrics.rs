@@ -23,6 +23,7 @@ use quickwit_common::metrics::{new_counter_vec, new_gauge_vec, IntCounterVec, In
 pub struct IndexerMetrics {
     pub processed_docs_total: IntCounterVec<3>,
     pub processed_bytes: IntCounterVec<3>,
+    pub dead_letter_docs_dropped_total: IntCounterVec<2>,
     pub backpressure_micros: IntCounterVec<2>,
     pub available_concurrent_upload_permits: IntGaugeVec<1>,
     pub ongoing_merge_operations: IntGaugeVec<2>,
@@ -45,6 +46,13 @@ impl Default for IndexerMetrics {
                 "quickwit_indexing",
                 ["index", "source", "docs_processed_status"],
             ),
+            dead_letter_docs_dropped_total: new_counter_vec(
+                "dead_letter_docs_dropped_total",
+                "Number of invalid docs that could not be recorded in the dead-letter destination \
+                 of their source, by index and source",
+                "quickwit_indexing",
+                ["index", "source"],
+            ),
             backpressure_micros: new_counter_vec(
                 "backpressure_micros",
                 "Amount of time spent in backpressure (in micros). This time only includes the \
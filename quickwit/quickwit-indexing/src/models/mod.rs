@@ -48,7 +48,7 @@ pub use processed_doc::{ProcessedDoc, ProcessedDocBatch};
 pub use publish_lock::{NewPublishLock, PublishLock};
 pub use publisher_message::SplitsUpdate;
 use quickwit_proto::types::PublishToken;
-pub use raw_doc_batch::RawDocBatch;
+pub use raw_doc_batch::{InvalidRawDoc, RawDocBatch};
 pub use split_attrs::{create_split_metadata, SplitAttrs};
 
 #[derive(Debug)]
@@ -20,13 +20,31 @@
 use std::fmt;
 
 use bytes::Bytes;
-use quickwit_metastore::checkpoint::SourceCheckpointDelta;
+use quickwit_metastore::checkpoint::{PartitionId, SourceCheckpointDelta};
+use quickwit_proto::types::Position;
+
+/// A raw document the source failed to decode, such as a Kafka message that does not match its
+/// writer schema. The doc processor counts and records it like the docs it fails to parse.
+#[derive(Debug)]
+pub struct InvalidRawDoc {
+    pub raw_doc: Bytes,
+    pub error: String,
+    /// Partition of the doc and checkpoint position right after it, if the source records them.
+    pub position_opt: Option<(PartitionId, Position)>,
+}
 
 #[derive(Default)]
 pub struct RawDocBatch {
     pub docs: Vec<Bytes>,
     pub checkpoint_delta: SourceCheckpointDelta,
     pub force_commit: bool,
+    /// Header row of the CSV file the documents were read from, for CSV sources reading their
+    /// columns from the header row of each file.
+    pub csv_header_opt: Option<Bytes>,
+    pub invalid_docs: Vec<InvalidRawDoc>,
+    /// Partition of each doc and checkpoint position right after it. Empty if the source does not
+    /// record them, otherwise as long as `docs`.
+    pub doc_positions: Vec<(PartitionId, Position)>,
 }
 
 impl RawDocBatch {
@@ -39,6 +57,9 @@ impl RawDocBatch {
             docs,
             checkpoint_delta,
             force_commit,
+            csv_header_opt: None,
+            invalid_docs: Vec::new(),
+            doc_positions: Vec::new(),
         }
     }
 
@@ -47,6 +68,9 @@ impl RawDocBatch {
             docs: Vec::with_capacity(capacity),
             checkpoint_delta: SourceCheckpointDelta::default(),
             force_commit: false,
+            csv_header_opt: None,
+            invalid_docs: Vec::new(),
+            doc_positions: Vec::new(),
         }
     }
 
@@ -66,6 +90,9 @@ impl RawDocBatch {
             docs,
             checkpoint_delta,
             force_commit: false,
+            csv_header_opt: None,
+            invalid_docs: Vec::new(),
+            doc_positions: Vec::new(),
         }
     }
 }
@@ -77,6 +104,8 @@ impl fmt::Debug for RawDocBatch {
             .field("num_docs", &self.num_docs())
             .field("checkpoint_delta", &self.checkpoint_delta)
             .field("force_commit", &self.force_commit)
+            .field("has_csv_header", &self.csv_header_opt.is_some())
+            .field("num_invalid_docs", &self.invalid_docs.len())
             .finish()
     }
 }
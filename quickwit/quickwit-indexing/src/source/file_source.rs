@@ -17,22 +17,28 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
-use std::fmt;
+use std::collections::VecDeque;
 use std::ops::Range;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
+use std::{fmt, io};
 
-use anyhow::Context;
+use anyhow::{bail, Context};
+use async_compression::tokio::bufread::{GzipDecoder, ZstdDecoder};
 use async_trait::async_trait;
 use bytes::Bytes;
+use csv_core::ReadRecordResult;
+use glob::{MatchOptions, Pattern};
 use quickwit_actors::{ActorExitStatus, Mailbox};
 use quickwit_common::uri::Uri;
-use quickwit_config::FileSourceParams;
+use quickwit_config::{FileSourceParams, SourceInputFormat};
 use quickwit_metastore::checkpoint::{PartitionId, SourceCheckpoint};
 use quickwit_proto::types::Position;
+use quickwit_storage::{Storage, StorageResolver};
 use serde::Serialize;
-use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncRead, AsyncReadExt, BufReader};
 use tracing::info;
 
 use crate::actors::DocProcessor;
@@ -42,18 +48,283 @@ use crate::source::{Source, SourceContext, SourceRuntimeArgs, TypedSourceFactory
 /// Number of bytes after which a new batch is cut.
 pub(crate) const BATCH_NUM_BYTES_LIMIT: u64 = 500_000u64;
 
+/// Characters marking the start of a glob pattern in a file path.
+const GLOB_PATTERN_CHARS: [char; 3] = ['*', '?', '['];
+
+const GLOB_MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
 #[derive(Default, Clone, Debug, Eq, PartialEq, Serialize)]
 pub struct FileSourceCounters {
     pub previous_offset: u64,
     pub current_offset: u64,
     pub num_lines_processed: u64,
+    pub num_files_processed: u64,
+}
+
+/// Compression format of a file, inferred from its extension.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum FileCompression {
+    Gzip,
+    Zstd,
+}
+
+impl FileCompression {
+    fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "gz" => Some(Self::Gzip),
+            "zst" | "zstd" => Some(Self::Zstd),
+            _ => None,
+        }
+    }
+
+    fn decoder(
+        self,
+        reader: Box<dyn AsyncRead + Send + Unpin>,
+    ) -> Box<dyn AsyncRead + Send + Unpin> {
+        let buf_reader = BufReader::new(reader);
+        match self {
+            Self::Gzip => {
+                let mut decoder = GzipDecoder::new(buf_reader);
+                decoder.multiple_members(true);
+                Box::new(decoder)
+            }
+            Self::Zstd => {
+                let mut decoder = ZstdDecoder::new(buf_reader);
+                decoder.multiple_members(true);
+                Box::new(decoder)
+            }
+        }
+    }
+}
+
+/// A file that remains to be read, along with the offset to resume reading from.
+struct PendingFile {
+    storage: Arc<dyn Storage>,
+    path: PathBuf,
+    partition_id: PartitionId,
+    offset: u64,
+    // Whether the records of the file are CSV records, which may span several lines.
+    is_csv: bool,
+    read_csv_header: bool,
+}
+
+impl PendingFile {
+    /// Opens the file at its offset. Returns `None` if the file was read entirely already.
+    ///
+    /// Compressed files cannot be read from an offset: they are decompressed again from offset 0
+    /// and the bytes up to the offset are discarded.
+    async fn open(self) -> anyhow::Result<Option<FileReader>> {
+        let file_num_bytes = self.storage.file_num_bytes(&self.path).await? as usize;
+
+        if file_num_bytes == 0 {
+            return Ok(None);
+        }
+        let compression_opt = FileCompression::from_path(&self.path);
+        // When resuming from an offset, the header row of the file is read again. Otherwise, it
+        // is read as the first line of the file.
+        let mut csv_header_opt = None;
+
+        let reader = if let Some(compression) = compression_opt {
+            let stream = self
+                .storage
+                .get_slice_stream(&self.path, 0..file_num_bytes)
+                .await?;
+            let mut decoder = BufReader::new(compression.decoder(stream));
+            let mut num_bytes_to_skip = self.offset;
+
+            if self.read_csv_header && self.offset > 0 {
+                let csv_header = read_csv_header(&mut decoder, &self.path).await?;
+                num_bytes_to_skip = self
+                    .offset
+                    .checked_sub(csv_header.len() as u64)
+                    .with_context(|| {
+                        format!(
+                            "checkpointed offset {} of file `{}` is within its header row",
+                            self.offset,
+                            self.path.display()
+                        )
+                    })?;
+                csv_header_opt = Some(csv_header);
+            }
+            // The offset of a compressed file refers to its decompressed content, so we have to
+            // decompress the file from the start and skip the bytes that were read already.
+            let num_skipped_bytes = tokio::io::copy(
+                &mut (&mut decoder).take(num_bytes_to_skip),
+                &mut tokio::io::sink(),
+            )
+            .await
+            .with_context(|| format!("failed to decompress file `{}`", self.path.display()))?;
+            if num_skipped_bytes < num_bytes_to_skip {
+                bail!(
+                    "file `{}` is shorter than its checkpointed offset {}",
+                    self.path.display(),
+                    self.offset
+                );
+            }
+            decoder
+        } else {
+            if self.offset as usize >= file_num_bytes {
+                return Ok(None);
+            }
+            if self.read_csv_header && self.offset > 0 {
+                // The header row lies before the checkpointed offset, so only the bytes up to the
+                // offset are fetched.
+                let stream = self
+                    .storage
+                    .get_slice_stream(&self.path, 0..self.offset as usize)
+                    .await?;
+                let csv_header = read_csv_header(&mut BufReader::new(stream), &self.path).await?;
+
+                if !csv_header.ends_with(b"\n") {
+                    bail!(
+                        "checkpointed offset {} of file `{}` is within its header row",
+                        self.offset,
+                        self.path.display()
+                    );
+                }
+                csv_header_opt = Some(csv_header);
+            }
+            let stream = self
+                .storage
+                .get_slice_stream(
+                    &self.path,
+                    Range {
+                        start: self.offset as usize,
+                        end: file_num_bytes,
+                    },
+                )
+                .await?;
+            BufReader::new(stream)
+        };
+        let file_reader = FileReader {
+            partition_id_opt: Some(self.partition_id),
+            compression_opt,
+            reader,
+            csv_record_splitter_opt: self.is_csv.then(CsvRecordSplitter::default),
+            csv_header_opt,
+        };
+        Ok(Some(file_reader))
+    }
+}
+
+/// Reads the header row of a CSV file, which is its first record.
+async fn read_csv_header(
+    reader: &mut (impl AsyncBufRead + Unpin),
+    path: &Path,
+) -> anyhow::Result<Bytes> {
+    let mut csv_header = String::new();
+    read_csv_record(reader, &mut CsvRecordSplitter::default(), &mut csv_header)
+        .await
+        .with_context(|| format!("failed to read header row of file `{}`", path.display()))?;
+    if csv_header.is_empty() {
+        bail!("file `{}` has no header row", path.display());
+    }
+    Ok(Bytes::from(csv_header))
+}
+
+/// Reads the next CSV record, which spans several lines when its quoted fields contain line
+/// breaks, and appends it to `record`. Returns the number of bytes read, 0 at EOF.
+async fn read_csv_record(
+    reader: &mut (impl AsyncBufRead + Unpin),
+    csv_record_splitter: &mut CsvRecordSplitter,
+    record: &mut String,
+) -> io::Result<usize> {
+    let mut num_bytes = 0;
+    loop {
+        let line_start = record.len();
+        let num_line_bytes = reader.read_line(record).await?;
+        num_bytes += num_line_bytes;
+
+        if num_line_bytes == 0 || csv_record_splitter.ends_record(&record.as_bytes()[line_start..])
+        {
+            return Ok(num_bytes);
+        }
+    }
+}
+
+/// Finds where the records of a CSV file end by feeding its lines to a CSV reader, so that line
+/// breaks within quoted fields (RFC 4180) do not split records.
+struct CsvRecordSplitter {
+    reader: csv_core::Reader,
+    // Scratch buffers for the fields of the records, which are discarded.
+    fields: [u8; 1024],
+    field_ends: [usize; 64],
+}
+
+impl Default for CsvRecordSplitter {
+    fn default() -> Self {
+        Self {
+            reader: csv_core::Reader::new(),
+            fields: [0; 1024],
+            field_ends: [0; 64],
+        }
+    }
+}
+
+impl CsvRecordSplitter {
+    /// Feeds the next line of the file to the reader. Returns whether the line ends a record.
+    fn ends_record(&mut self, line: &[u8]) -> bool {
+        let mut input = line;
+        let mut ends_record = false;
+
+        while !input.is_empty() {
+            let (result, num_bytes_read, _, _) =
+                self.reader
+                    .read_record(input, &mut self.fields, &mut self.field_ends);
+            let (bytes_read, remaining_input) = input.split_at(num_bytes_read);
+            input = remaining_input;
+
+            match result {
+                ReadRecordResult::Record => ends_record = true,
+                // The reader skips the line terminators following a record, such as the `\n` of
+                // `\r\n`. Any other byte starts a new record.
+                ReadRecordResult::InputEmpty => {
+                    ends_record &= bytes_read.iter().all(|byte| matches!(byte, b'\r' | b'\n'));
+                }
+                ReadRecordResult::OutputFull
+                | ReadRecordResult::OutputEndsFull
+                | ReadRecordResult::End => {}
+            }
+        }
+        ends_record
+    }
+}
+
+struct FileReader {
+    /// `None` when reading from stdin, which cannot be checkpointed.
+    partition_id_opt: Option<PartitionId>,
+    compression_opt: Option<FileCompression>,
+    reader: BufReader<Box<dyn AsyncRead + Send + Unpin>>,
+    /// Set for CSV sources, whose records may span several lines.
+    csv_record_splitter_opt: Option<CsvRecordSplitter>,
+    /// Header row of the file, for CSV sources reading their columns from it. It is handed over
+    /// to the doc processor along with each batch of the file.
+    csv_header_opt: Option<Bytes>,
+}
+
+impl FileReader {
+    /// Reads the next document of the file, a line or a CSV record, and appends it to `doc`.
+    /// Returns the number of bytes read, 0 at EOF.
+    async fn read_doc(&mut self, doc: &mut String) -> io::Result<usize> {
+        if let Some(csv_record_splitter) = &mut self.csv_record_splitter_opt {
+            read_csv_record(&mut self.reader, csv_record_splitter, doc).await
+        } else {
+            self.reader.read_line(doc).await
+        }
+    }
 }
 
 pub struct FileSource {
     source_id: String,
-    params: FileSourceParams,
     counters: FileSourceCounters,
-    reader: BufReader<Box<dyn AsyncRead + Send + Unpin>>,
+    pending_files: VecDeque<PendingFile>,
+    file_reader_opt: Option<FileReader>,
+    // Whether the first line of each file is the header row of a CSV source.
+    read_csv_header: bool,
 }
 
 impl fmt::Debug for FileSource {
@@ -62,6 +333,25 @@ impl fmt::Debug for FileSource {
     }
 }
 
+impl FileSource {
+    /// Opens the next file that has not been read entirely yet.
+    async fn open_next_file(&mut self, ctx: &SourceContext) -> anyhow::Result<()> {
+        while let Some(pending_file) = self.pending_files.pop_front() {
+            let offset = pending_file.offset;
+            let partition_id = pending_file.partition_id.clone();
+
+            if let Some(file_reader) = ctx.protect_future(pending_file.open()).await? {
+                info!(partition_id=%partition_id, offset=%offset, "reading file");
+                self.counters.previous_offset = offset;
+                self.counters.current_offset = offset;
+                self.file_reader_opt = Some(file_reader);
+                return Ok(());
+            }
+        }
+        Ok(())
+    }
+}
+
 #[async_trait]
 impl Source for FileSource {
     async fn emit_batches(
@@ -69,49 +359,77 @@ impl Source for FileSource {
         doc_processor_mailbox: &Mailbox<DocProcessor>,
         ctx: &SourceContext,
     ) -> Result<Duration, ActorExitStatus> {
+        if self.file_reader_opt.is_none() {
+            self.open_next_file(ctx).await?;
+        }
+        let Some(file_reader) = self.file_reader_opt.as_mut() else {
+            info!("EOF");
+            ctx.send_exit_with_success(doc_processor_mailbox).await?;
+            return Err(ActorExitStatus::Success);
+        };
         // We collect batches of documents before sending them to the indexer.
         let limit_num_bytes = self.counters.previous_offset + BATCH_NUM_BYTES_LIMIT;
         let mut reached_eof = false;
         let mut doc_batch = RawDocBatch::default();
         while self.counters.current_offset < limit_num_bytes {
-            let mut doc_line = String::new();
+            let mut doc = String::new();
             // guard the zone in case of slow read, such as reading from someone
             // typing to stdin
             let num_bytes = ctx
-                .protect_future(self.reader.read_line(&mut doc_line))
+                .protect_future(file_reader.read_doc(&mut doc))
                 .await
                 .map_err(anyhow::Error::from)?;
             if num_bytes == 0 {
                 reached_eof = true;
                 break;
             }
-            doc_batch.docs.push(Bytes::from(doc_line));
             self.counters.current_offset += num_bytes as u64;
+
+            // The header row is handed over with the batches of the file instead of indexed.
+            if self.read_csv_header && file_reader.csv_header_opt.is_none() {
+                file_reader.csv_header_opt = Some(Bytes::from(doc));
+                continue;
+            }
+            if let Some(partition_id) = &file_reader.partition_id_opt {
+                let position = Position::from(self.counters.current_offset);
+                doc_batch
+                    .doc_positions
+                    .push((partition_id.clone(), position));
+            }
+            doc_batch.docs.push(Bytes::from(doc));
             self.counters.num_lines_processed += 1;
         }
-        if !doc_batch.docs.is_empty() {
-            if let Some(filepath) = &self.params.filepath {
-                let filepath_str = filepath
-                    .to_str()
-                    .context("path is invalid utf-8")?
-                    .to_string();
-                let partition_id = PartitionId::from(filepath_str);
+        doc_batch.csv_header_opt = file_reader.csv_header_opt.clone();
+
+        if let Some(partition_id) = &file_reader.partition_id_opt {
+            let from_position = Position::from(self.counters.previous_offset);
+            // Compressed files cannot be read from an offset without being decompressed from the
+            // start, so they are marked as read entirely to be skipped when the source restarts.
+            let to_position = if reached_eof && file_reader.compression_opt.is_some() {
+                Position::Eof
+            } else {
+                Position::from(self.counters.current_offset)
+            };
+            if from_position < to_position {
                 doc_batch
                     .checkpoint_delta
-                    .record_partition_delta(
-                        partition_id,
-                        Position::from(self.counters.previous_offset),
-                        Position::from(self.counters.current_offset),
-                    )
+                    .record_partition_delta(partition_id.clone(), from_position, to_position)
                     .unwrap();
             }
+        }
+        if !doc_batch.docs.is_empty() || !doc_batch.checkpoint_delta.is_empty() {
             self.counters.previous_offset = self.counters.current_offset;
             ctx.send_message(doc_processor_mailbox, doc_batch).await?;
         }
         if reached_eof {
-            info!("EOF");
-            ctx.send_exit_with_success(doc_processor_mailbox).await?;
-            return Err(ActorExitStatus::Success);
+            self.file_reader_opt = None;
+            self.counters.num_files_processed += 1;
+
+            if self.pending_files.is_empty() {
+                info!("EOF");
+                ctx.send_exit_with_success(doc_processor_mailbox).await?;
+                return Err(ActorExitStatus::Success);
+            }
         }
         Ok(Duration::default())
     }
@@ -132,53 +450,123 @@ impl TypedSourceFactory for FileSourceFactory {
     type Source = FileSource;
     type Params = FileSourceParams;
 
-    // TODO handle checkpoint for files.
     async fn typed_create_source(
         ctx: Arc<SourceRuntimeArgs>,
         params: FileSourceParams,
         checkpoint: SourceCheckpoint,
     ) -> anyhow::Result<FileSource> {
-        let mut offset = 0;
-        let reader: Box<dyn AsyncRead + Send + Unpin> = if let Some(filepath) = &params.filepath {
-            let partition_id = PartitionId::from(filepath.to_string_lossy().to_string());
-            offset = checkpoint
-                .position_for_partition(&partition_id)
-                .map(|position| {
-                    position
-                        .as_usize()
-                        .expect("file offset should be stored as usize")
-                })
-                .unwrap_or(0);
-            let (dir_uri, file_name) = dir_and_filename(filepath)?;
-            let storage = ctx.storage_resolver.resolve(&dir_uri).await?;
-            let file_size = storage.file_num_bytes(file_name).await?.try_into().unwrap();
-            storage
-                .get_slice_stream(
-                    file_name,
-                    Range {
-                        start: offset,
-                        end: file_size,
-                    },
-                )
-                .await?
+        let mut pending_files = VecDeque::new();
+        let is_csv = ctx.source_config.input_format == SourceInputFormat::Csv;
+        let read_csv_header = is_csv && ctx.source_config.csv_columns_opt.is_none();
+
+        let file_reader_opt = if let Some(filepath) = &params.filepath {
+            let source_files = resolve_source_files(&ctx.storage_resolver, filepath).await?;
+
+            for (path, partition_id) in source_files.files {
+                let offset = match checkpoint.position_for_partition(&partition_id) {
+                    None | Some(Position::Beginning) => 0,
+                    Some(Position::Eof) => continue,
+                    Some(position) => position
+                        .as_u64()
+                        .expect("file offset should be stored as u64"),
+                };
+                pending_files.push_back(PendingFile {
+                    storage: source_files.storage.clone(),
+                    path,
+                    partition_id,
+                    offset,
+                    is_csv,
+                    read_csv_header,
+                });
+            }
+            None
         } else {
             // We cannot use the checkpoint.
-            Box::new(tokio::io::stdin())
+            let stdin: Box<dyn AsyncRead + Send + Unpin> = Box::new(tokio::io::stdin());
+            Some(FileReader {
+                partition_id_opt: None,
+                compression_opt: None,
+                reader: BufReader::new(stdin),
+                csv_record_splitter_opt: is_csv.then(CsvRecordSplitter::default),
+                csv_header_opt: None,
+            })
         };
         let file_source = FileSource {
             source_id: ctx.source_id().to_string(),
-            counters: FileSourceCounters {
-                previous_offset: offset as u64,
-                current_offset: offset as u64,
-                num_lines_processed: 0,
-            },
-            reader: BufReader::new(reader),
-            params,
+            counters: FileSourceCounters::default(),
+            pending_files,
+            file_reader_opt,
+            read_csv_header,
         };
         Ok(file_source)
     }
 }
 
+/// The files read by a file source.
+pub(crate) struct SourceFiles {
+    pub storage: Arc<dyn Storage>,
+    /// Paths of the files relative to `storage`, in the order they are read, along with their
+    /// partition ID.
+    pub files: Vec<(PathBuf, PartitionId)>,
+}
+
+/// Resolves the files designated by the `filepath` parameter of a file source: a single file, or
+/// the files matching a glob pattern such as `s3://bucket/logs/2023-*/*.json.gz`.
+pub(crate) async fn resolve_source_files(
+    storage_resolver: &StorageResolver,
+    filepath: &Path,
+) -> anyhow::Result<SourceFiles> {
+    let filepath_str = filepath.to_str().context("path is invalid utf-8")?;
+
+    let Some(pattern_start) = filepath_str.find(GLOB_PATTERN_CHARS) else {
+        let (dir_uri, file_name) = dir_and_filename(filepath)?;
+        let storage = storage_resolver.resolve(&dir_uri).await?;
+        let partition_id = PartitionId::from(filepath_str.to_string());
+        let source_files = SourceFiles {
+            storage,
+            files: vec![(file_name.to_path_buf(), partition_id)],
+        };
+        return Ok(source_files);
+    };
+    let dir_end = filepath_str[..pattern_start]
+        .rfind('/')
+        .context("glob pattern should be preceded by a directory")?;
+    let dir_uri = Uri::from_str(&filepath_str[..dir_end])?;
+    let pattern_str = &filepath_str[dir_end + 1..];
+    let pattern = Pattern::new(pattern_str)
+        .with_context(|| format!("failed to parse glob pattern `{pattern_str}`"))?;
+    // The part of the pattern before the first special character narrows down the listing.
+    let list_prefix = &filepath_str[dir_end + 1..pattern_start];
+
+    let storage = storage_resolver.resolve(&dir_uri).await?;
+    let mut files = Vec::new();
+
+    for path in storage.list(Path::new(list_prefix)).await? {
+        if pattern.matches_path_with(&path, GLOB_MATCH_OPTIONS) {
+            let partition_id = PartitionId::from(dir_uri.join(&path)?.as_str().to_string());
+            files.push((path, partition_id));
+        }
+    }
+    let source_files = SourceFiles { storage, files };
+    Ok(source_files)
+}
+
+/// Checks that the file, or at least one of the files matching the glob pattern, exists.
+pub(crate) async fn check_connectivity(
+    storage_resolver: &StorageResolver,
+    filepath: &Path,
+) -> anyhow::Result<()> {
+    let source_files = resolve_source_files(storage_resolver, filepath).await?;
+
+    match source_files.files.first() {
+        Some((path, _)) => {
+            source_files.storage.file_num_bytes(path).await?;
+        }
+        None => bail!("no file matches `{}`", filepath.display()),
+    }
+    Ok(())
+}
+
 pub(crate) fn dir_and_filename(filepath: &Path) -> anyhow::Result<(Uri, &Path)> {
     let dir_uri: Uri = filepath
         .parent()
@@ -220,6 +608,8 @@ mod tests {
             source_params: SourceParams::File(params.clone()),
             transform_config: None,
             input_format: SourceInputFormat::Json,
+            csv_columns_opt: None,
+            dead_letter_opt: None,
         };
         let metastore = metastore_for_test();
         let file_source = FileSourceFactory::typed_create_source(
@@ -246,7 +636,8 @@ mod tests {
             serde_json::json!({
                 "previous_offset": 1030u64,
                 "current_offset": 1030u64,
-                "num_lines_processed": 4u32
+                "num_lines_processed": 4u32,
+                "num_files_processed": 1u64
             })
         );
         let batch = indexer_inbox.drain_for_test();
@@ -287,6 +678,8 @@ mod tests {
             source_params: SourceParams::File(params.clone()),
             transform_config: None,
             input_format: SourceInputFormat::Json,
+            csv_columns_opt: None,
+            dead_letter_opt: None,
         };
         let metastore = metastore_for_test();
         let source = FileSourceFactory::typed_create_source(
@@ -313,7 +706,8 @@ mod tests {
             serde_json::json!({
                 "previous_offset": 700_000u64,
                 "current_offset": 700_000u64,
-                "num_lines_processed": 20_000u64
+                "num_lines_processed": 20_000u64,
+                "num_files_processed": 1u64
             })
         );
         let indexer_msgs = doc_processor_inbox.drain_for_test();
@@ -378,6 +772,8 @@ mod tests {
             source_params: SourceParams::File(params.clone()),
             transform_config: None,
             input_format: SourceInputFormat::Json,
+            csv_columns_opt: None,
+            dead_letter_opt: None,
         };
         let metastore = metastore_for_test();
         let source = FileSourceFactory::typed_create_source(
@@ -405,10 +801,349 @@ mod tests {
             serde_json::json!({
                 "previous_offset": 290u64,
                 "current_offset": 290u64,
-                "num_lines_processed": 98u64
+                "num_lines_processed": 98u64,
+                "num_files_processed": 1u64
             })
         );
         let indexer_messages: Vec<RawDocBatch> = doc_processor_inbox.drain_for_test_typed();
         assert!(&indexer_messages[0].docs[0].starts_with(b"2\n"));
+        assert_eq!(
+            indexer_messages[0].doc_positions[0],
+            (
+                PartitionId::from(temp_file_path.to_string_lossy().to_string()),
+                Position::from(6u64)
+            )
+        );
+    }
+
+    async fn run_file_source(
+        params: FileSourceParams,
+        input_format: SourceInputFormat,
+        checkpoint: SourceCheckpoint,
+    ) -> (serde_json::Value, Vec<RawDocBatch>) {
+        let universe = Universe::with_accelerated_time();
+        let (doc_processor_mailbox, doc_processor_inbox) = universe.create_test_mailbox();
+        let source_config = SourceConfig {
+            source_id: "test-file-source".to_string(),
+            desired_num_pipelines: NonZeroUsize::new(1).unwrap(),
+            max_num_pipelines_per_indexer: NonZeroUsize::new(1).unwrap(),
+            enabled: true,
+            source_params: SourceParams::File(params.clone()),
+            transform_config: None,
+            input_format,
+            csv_columns_opt: None,
+            dead_letter_opt: None,
+        };
+        let source = FileSourceFactory::typed_create_source(
+            SourceRuntimeArgs::for_test(
+                IndexUid::new_with_random_ulid("test-index"),
+                source_config,
+                metastore_for_test(),
+                PathBuf::from("./queues"),
+            ),
+            params,
+            checkpoint,
+        )
+        .await
+        .unwrap();
+        let file_source_actor = SourceActor {
+            source: Box::new(source),
+            doc_processor_mailbox,
+        };
+        let (_file_source_mailbox, file_source_handle) =
+            universe.spawn_builder().spawn(file_source_actor);
+        let (actor_termination, counters) = file_source_handle.join().await;
+        assert!(actor_termination.is_success());
+        let batches: Vec<RawDocBatch> = doc_processor_inbox.drain_for_test_typed();
+        universe.assert_quit().await;
+        (counters, batches)
+    }
+
+    fn write_test_files(dir_path: &Path) {
+        std::fs::write(dir_path.join("a.json"), "{\"id\": 1}\n{\"id\": 2}\n").unwrap();
+
+        let mut gzip_encoder = flate2::write::GzEncoder::new(
+            std::fs::File::create(dir_path.join("b.json.gz")).unwrap(),
+            flate2::Compression::default(),
+        );
+        gzip_encoder
+            .write_all(b"{\"id\": 3}\n{\"id\": 4}\n")
+            .unwrap();
+        gzip_encoder.finish().unwrap();
+
+        let zstd_payload = zstd::encode_all(&b"{\"id\": 5}\n{\"id\": 6}\n"[..], 0).unwrap();
+        std::fs::write(dir_path.join("c.json.zst"), zstd_payload).unwrap();
+
+        std::fs::write(dir_path.join("d.txt"), "not a json file\n").unwrap();
+        std::fs::create_dir(dir_path.join("sub")).unwrap();
+        std::fs::write(dir_path.join("sub").join("e.json"), "{\"id\": 7}\n").unwrap();
+    }
+
+    fn partition_id(dir_path: &Path, file_name: &str) -> PartitionId {
+        let uri = Uri::from_str(&dir_path.join(file_name).to_string_lossy()).unwrap();
+        PartitionId::from(uri.as_str().to_string())
+    }
+
+    #[tokio::test]
+    async fn test_file_source_glob_pattern_with_compressed_files() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir_path = temp_dir.path().canonicalize().unwrap();
+        write_test_files(&dir_path);
+
+        let params = FileSourceParams::file(dir_path.join("*.json*"));
+        let (counters, batches) =
+            run_file_source(params, SourceInputFormat::Json, SourceCheckpoint::default()).await;
+        assert_eq!(counters["num_lines_processed"], 6);
+        assert_eq!(counters["num_files_processed"], 3);
+
+        let docs: Vec<Bytes> = batches
+            .iter()
+            .flat_map(|batch| batch.docs.iter().cloned())
+            .collect();
+        assert_eq!(docs.len(), 6);
+        assert_eq!(docs[0], "{\"id\": 1}\n");
+        assert_eq!(docs[2], "{\"id\": 3}\n");
+        assert_eq!(docs[5], "{\"id\": 6}\n");
+
+        let mut checkpoint = SourceCheckpoint::default();
+        for batch in batches {
+            checkpoint.try_apply_delta(batch.checkpoint_delta).unwrap();
+        }
+        assert_eq!(
+            checkpoint.position_for_partition(&partition_id(&dir_path, "a.json")),
+            Some(&Position::from(20u64))
+        );
+        assert_eq!(
+            checkpoint.position_for_partition(&partition_id(&dir_path, "b.json.gz")),
+            Some(&Position::Eof)
+        );
+        assert_eq!(
+            checkpoint.position_for_partition(&partition_id(&dir_path, "c.json.zst")),
+            Some(&Position::Eof)
+        );
+        assert_eq!(checkpoint.num_partitions(), 3);
+
+        // Files in subdirectories match `**`.
+        let params = FileSourceParams::file(dir_path.join("**/*.json"));
+        let (counters, _batches) =
+            run_file_source(params, SourceInputFormat::Json, SourceCheckpoint::default()).await;
+        assert_eq!(counters["num_lines_processed"], 3);
+        assert_eq!(counters["num_files_processed"], 2);
+    }
+
+    #[tokio::test]
+    async fn test_file_source_glob_pattern_resume_from_checkpoint() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir_path = temp_dir.path().canonicalize().unwrap();
+        write_test_files(&dir_path);
+
+        let mut checkpoint = SourceCheckpoint::default();
+        let checkpoint_deltas = [
+            // `a.json` was read entirely.
+            ("a.json", Position::from(20u64)),
+            // `b.json.gz` was read entirely.
+            ("b.json.gz", Position::Eof),
+            // The first line of `c.json.zst` was read.
+            ("c.json.zst", Position::from(10u64)),
+        ];
+        for (file_name, position) in checkpoint_deltas {
+            let checkpoint_delta = SourceCheckpointDelta::from_partition_delta(
+                partition_id(&dir_path, file_name),
+                Position::from(0u64),
+                position,
+            )
+            .unwrap();
+            checkpoint.try_apply_delta(checkpoint_delta).unwrap();
+        }
+        let params = FileSourceParams::file(dir_path.join("*.json*"));
+        let (counters, batches) =
+            run_file_source(params, SourceInputFormat::Json, checkpoint).await;
+        assert_eq!(counters["num_lines_processed"], 1);
+        assert_eq!(counters["num_files_processed"], 1);
+
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].docs, vec![Bytes::from("{\"id\": 6}\n")]);
+        assert_eq!(
+            format!("{:?}", batches[0].checkpoint_delta),
+            format!(
+                "∆({}:(00000000000000000010..~eof])",
+                partition_id(&dir_path, "c.json.zst")
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn test_file_source_csv_header() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir_path = temp_dir.path().canonicalize().unwrap();
+        std::fs::write(dir_path.join("a.csv"), "id,message\n1,hello\n2,world\n").unwrap();
+
+        let mut gzip_encoder = flate2::write::GzEncoder::new(
+            std::fs::File::create(dir_path.join("b.csv.gz")).unwrap(),
+            flate2::Compression::default(),
+        );
+        gzip_encoder
+            .write_all(b"message,id\nbonjour,3\nmonde,4\n")
+            .unwrap();
+        gzip_encoder.finish().unwrap();
+
+        let params = FileSourceParams::file(dir_path.join("*.csv*"));
+        let (counters, batches) = run_file_source(
+            params.clone(),
+            SourceInputFormat::Csv,
+            SourceCheckpoint::default(),
+        )
+        .await;
+        assert_eq!(counters["num_lines_processed"], 4);
+        assert_eq!(counters["num_files_processed"], 2);
+
+        // The header row of each file is handed over with its batches instead of being indexed.
+        assert_eq!(batches.len(), 2);
+        assert_eq!(
+            batches[0].csv_header_opt.as_deref(),
+            Some(&b"id,message\n"[..])
+        );
+        assert_eq!(
+            batches[0].docs,
+            vec![Bytes::from("1,hello\n"), Bytes::from("2,world\n")]
+        );
+        assert_eq!(
+            batches[1].csv_header_opt.as_deref(),
+            Some(&b"message,id\n"[..])
+        );
+        assert_eq!(
+            batches[1].docs,
+            vec![Bytes::from("bonjour,3\n"), Bytes::from("monde,4\n")]
+        );
+
+        // The header row of the file covers the start of its first batch.
+        assert_eq!(
+            format!("{:?}", batches[0].checkpoint_delta),
+            format!(
+                "∆({}:(00000000000000000000..00000000000000000027])",
+                partition_id(&dir_path, "a.csv")
+            )
+        );
+        // The header row is read again when resuming from an offset.
+        let mut checkpoint = SourceCheckpoint::default();
+        let checkpoint_deltas = [
+            ("a.csv", Position::from(19u64)),
+            ("b.csv.gz", Position::from(21u64)),
+        ];
+        for (file_name, position) in checkpoint_deltas {
+            let checkpoint_delta = SourceCheckpointDelta::from_partition_delta(
+                partition_id(&dir_path, file_name),
+                Position::from(0u64),
+                position,
+            )
+            .unwrap();
+            checkpoint.try_apply_delta(checkpoint_delta).unwrap();
+        }
+        let (counters, batches) = run_file_source(params, SourceInputFormat::Csv, checkpoint).await;
+        assert_eq!(counters["num_lines_processed"], 2);
+
+        assert_eq!(batches.len(), 2);
+        assert_eq!(
+            batches[0].csv_header_opt.as_deref(),
+            Some(&b"id,message\n"[..])
+        );
+        assert_eq!(batches[0].docs, vec![Bytes::from("2,world\n")]);
+        assert_eq!(
+            batches[1].csv_header_opt.as_deref(),
+            Some(&b"message,id\n"[..])
+        );
+        assert_eq!(batches[1].docs, vec![Bytes::from("monde,4\n")]);
+    }
+
+    #[tokio::test]
+    async fn test_file_source_csv_quoted_line_breaks() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir_path = temp_dir.path().canonicalize().unwrap();
+        std::fs::write(
+            dir_path.join("a.csv"),
+            "id,\"long\nmessage\"\n1,\"hello\nworld\"\r\n2,\"say \"\"hi\"\"\"\n3,bye\n",
+        )
+        .unwrap();
+
+        let params = FileSourceParams::file(dir_path.join("a.csv"));
+        let (counters, batches) = run_file_source(
+            params.clone(),
+            SourceInputFormat::Csv,
+            SourceCheckpoint::default(),
+        )
+        .await;
+        assert_eq!(counters["num_lines_processed"], 3);
+
+        assert_eq!(batches.len(), 1);
+        assert_eq!(
+            batches[0].csv_header_opt.as_deref(),
+            Some(&b"id,\"long\nmessage\"\n"[..])
+        );
+        assert_eq!(
+            batches[0].docs,
+            vec![
+                Bytes::from("1,\"hello\nworld\"\r\n"),
+                Bytes::from("2,\"say \"\"hi\"\"\"\n"),
+                Bytes::from("3,bye\n"),
+            ]
+        );
+        // The header row spanning several lines is read again when resuming from an offset.
+        let checkpoint_delta = SourceCheckpointDelta::from_partition_delta(
+            partition_id(&dir_path, "a.csv"),
+            Position::from(0u64),
+            Position::from(35u64),
+        )
+        .unwrap();
+        let mut checkpoint = SourceCheckpoint::default();
+        checkpoint.try_apply_delta(checkpoint_delta).unwrap();
+
+        let (counters, batches) = run_file_source(params, SourceInputFormat::Csv, checkpoint).await;
+        assert_eq!(counters["num_lines_processed"], 2);
+
+        assert_eq!(batches.len(), 1);
+        assert_eq!(
+            batches[0].csv_header_opt.as_deref(),
+            Some(&b"id,\"long\nmessage\"\n"[..])
+        );
+        assert_eq!(
+            batches[0].docs,
+            vec![
+                Bytes::from("2,\"say \"\"hi\"\"\"\n"),
+                Bytes::from("3,bye\n")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_csv_record_splitter() {
+        let mut csv_record_splitter = CsvRecordSplitter::default();
+        assert!(csv_record_splitter.ends_record(b"1,hello\n"));
+        assert!(!csv_record_splitter.ends_record(b"2,\"hello\n"));
+        assert!(csv_record_splitter.ends_record(b"world\"\r\n"));
+        assert!(!csv_record_splitter.ends_record(b"3,\"say \"\"hi\"\"\n"));
+        assert!(csv_record_splitter.ends_record(b"\"\n"));
+        // Quotes within unquoted fields are regular characters.
+        assert!(csv_record_splitter.ends_record(b"4,say \"hi\n"));
+        // The last line of a file may lack a line break.
+        assert!(!csv_record_splitter.ends_record(b"5,bye"));
+    }
+
+    #[tokio::test]
+    async fn test_file_source_check_connectivity() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir_path = temp_dir.path().canonicalize().unwrap();
+        write_test_files(&dir_path);
+        let storage_resolver = StorageResolver::for_test();
+
+        check_connectivity(&storage_resolver, &dir_path.join("*.gz"))
+            .await
+            .unwrap();
+        check_connectivity(&storage_resolver, &dir_path.join("a.json"))
+            .await
+            .unwrap();
+        let error = check_connectivity(&storage_resolver, &dir_path.join("*.csv"))
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("no file matches"));
     }
 }
@@ -326,6 +326,8 @@ mod gcp_pubsub_emulator_tests {
             }),
             transform_config: None,
             input_format: SourceInputFormat::Json,
+            csv_columns_opt: None,
+            dead_letter_opt: None,
         }
     }
 
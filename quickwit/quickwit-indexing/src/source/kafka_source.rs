@@ -47,6 +47,7 @@ use tokio::task::{spawn_blocking, JoinHandle};
 use tokio::time;
 use tracing::{debug, info, warn};
 
+use super::schema_registry::{self, DecodeError, MessageDecoder};
 use crate::actors::DocProcessor;
 use crate::models::{NewPublishLock, PublishLock};
 use crate::source::{BatchBuilder, Source, SourceContext, SourceRuntimeArgs, TypedSourceFactory};
@@ -227,6 +228,7 @@ pub struct KafkaSource {
     truncate_tx: watch::Sender<SourceCheckpoint>,
     poll_loop_jh: JoinHandle<()>,
     publish_lock: PublishLock,
+    message_decoder_opt: Option<MessageDecoder>,
 }
 
 impl fmt::Debug for KafkaSource {
@@ -249,6 +251,8 @@ impl KafkaSource {
     ) -> anyhow::Result<Self> {
         let topic = params.topic.clone();
         let backfill_mode_enabled = params.enable_backfill_mode;
+        let message_decoder_opt =
+            MessageDecoder::try_new(ctx.source_config.input_format, params.schema.as_ref())?;
 
         let (events_tx, events_rx) = mpsc::channel(100);
         let (truncate_tx, truncate_rx) = watch::channel(SourceCheckpoint::default());
@@ -292,6 +296,7 @@ impl KafkaSource {
             truncate_tx,
             poll_loop_jh,
             publish_lock,
+            message_decoder_opt,
         })
     }
 
@@ -308,14 +313,6 @@ impl KafkaSource {
             ..
         } = message;
 
-        if let Some(doc) = doc_opt {
-            batch.add_doc(doc);
-        } else {
-            self.state.num_invalid_messages += 1;
-        }
-        self.state.num_bytes_processed += payload_len;
-        self.state.num_messages_processed += 1;
-
         let partition_id = self
             .state
             .assigned_partitions
@@ -330,6 +327,45 @@ impl KafkaSource {
             })?
             .clone();
         let current_position = Position::from(offset);
+
+        let doc_opt = match (doc_opt, self.message_decoder_opt.as_mut()) {
+            (Some(doc), Some(message_decoder)) => match message_decoder.decode(&doc).await {
+                Ok(json_doc) => Some(json_doc),
+                Err(DecodeError::InvalidMessage(error)) => {
+                    warn!(
+                        topic=%self.topic,
+                        partition=%partition,
+                        offset=%offset,
+                        error=%error,
+                        "Failed to decode message."
+                    );
+                    // The doc processor counts the message as a parse error and records it in
+                    // the dead-letter destination of the source, if any.
+                    batch.add_invalid_doc(
+                        doc,
+                        error,
+                        partition_id.clone(),
+                        current_position.clone(),
+                    );
+                    self.state.num_invalid_messages += 1;
+                    None
+                }
+                Err(DecodeError::Schema(error)) => {
+                    return Err(error.context("failed to resolve writer schema of message"));
+                }
+            },
+            (None, _) => {
+                self.state.num_invalid_messages += 1;
+                None
+            }
+            (doc_opt, _) => doc_opt,
+        };
+        if let Some(doc) = doc_opt {
+            batch.add_doc_with_position(doc, partition_id.clone(), current_position.clone());
+        }
+        self.state.num_bytes_processed += payload_len;
+        self.state.num_messages_processed += 1;
+
         let previous_position = self
             .state
             .current_positions
@@ -648,6 +684,9 @@ fn previous_position_for_offset(offset: i64) -> Position {
 
 /// Checks whether we can establish a connection to the Kafka broker.
 pub(super) async fn check_connectivity(params: KafkaSourceParams) -> anyhow::Result<()> {
+    if let Some(schema_params) = &params.schema {
+        schema_registry::check_connectivity(schema_params).await?;
+    }
     let mut client_config = parse_client_params(params.client_params)?;
 
     let consumer: BaseConsumer<DefaultConsumerContext> = client_config
@@ -786,7 +825,9 @@ mod kafka_broker_tests {
 
     use quickwit_actors::{ActorContext, Universe};
     use quickwit_common::rand::append_random_suffix;
-    use quickwit_config::{IndexConfig, SourceConfig, SourceInputFormat, SourceParams};
+    use quickwit_config::{
+        IndexConfig, KafkaSchemaParams, SourceConfig, SourceInputFormat, SourceParams,
+    };
     use quickwit_metastore::checkpoint::{IndexCheckpointDelta, SourceCheckpointDelta};
     use quickwit_metastore::{
         metastore_for_test, CreateIndexRequestExt, SplitMetadata, StageSplitsRequestExt,
@@ -915,9 +956,12 @@ mod kafka_broker_tests {
                     "bootstrap.servers": "localhost:9092",
                 }),
                 enable_backfill_mode: true,
+                schema: None,
             }),
             transform_config: None,
             input_format: SourceInputFormat::Json,
+            csv_columns_opt: None,
+            dead_letter_opt: None,
         };
         (source_id, source_config)
     }
@@ -1088,6 +1132,13 @@ mod kafka_broker_tests {
 
         assert_eq!(batch.docs.len(), 2);
         assert_eq!(batch.docs[1], "test-doc");
+        assert_eq!(
+            batch.doc_positions,
+            [
+                (partition_id_1.clone(), Position::from(1u64)),
+                (partition_id_2.clone(), Position::from(42u64)),
+            ]
+        );
         assert_eq!(batch.num_bytes, 16);
         assert_eq!(
             kafka_source.state.current_positions.get(&2).unwrap(),
@@ -1106,6 +1157,39 @@ mod kafka_broker_tests {
             .unwrap();
         assert_eq!(batch.checkpoint_delta, expected_checkpoint_delta);
 
+        // Messages that fail to decode are handed over to the doc processor as invalid docs.
+        let schema_params = KafkaSchemaParams::Avro {
+            schema: r#""long""#.to_string(),
+        };
+        kafka_source.message_decoder_opt =
+            MessageDecoder::try_new(SourceInputFormat::Avro, Some(&schema_params)).unwrap();
+
+        let message = KafkaMessage {
+            doc_opt: Some(Bytes::from_static(b"not-framed")),
+            payload_len: 10,
+            partition: 2,
+            offset: 43,
+        };
+        kafka_source
+            .process_message(message, &mut batch)
+            .await
+            .unwrap();
+
+        assert_eq!(batch.docs.len(), 2);
+        assert_eq!(batch.invalid_docs.len(), 1);
+        assert_eq!(batch.invalid_docs[0].raw_doc, "not-framed");
+        assert_eq!(
+            batch.invalid_docs[0].position_opt,
+            Some((PartitionId::from(2u64), Position::from(43u64)))
+        );
+        assert_eq!(batch.num_bytes, 26);
+        assert_eq!(
+            kafka_source.state.current_positions.get(&2).unwrap(),
+            &Position::from(43u64)
+        );
+        assert_eq!(kafka_source.state.num_messages_processed, 4);
+        assert_eq!(kafka_source.state.num_invalid_messages, 2);
+
         // Message from unassigned partition
         let message = KafkaMessage {
             doc_opt: Some(Bytes::from_static(b"test-doc")),
@@ -1573,6 +1657,7 @@ mod kafka_broker_tests {
             client_log_level: None,
             client_params: json!({ "bootstrap.servers": bootstrap_servers }),
             enable_backfill_mode: true,
+            schema: None,
         })
         .await
         .unwrap();
@@ -1584,6 +1669,7 @@ mod kafka_broker_tests {
             client_log_level: None,
             client_params: json!({ "bootstrap.servers": bootstrap_servers }),
             enable_backfill_mode: true,
+            schema: None,
         })
         .await
         .unwrap_err();
@@ -1596,6 +1682,7 @@ mod kafka_broker_tests {
                 "bootstrap.servers": "192.0.2.10:9092"
             }),
             enable_backfill_mode: true,
+            schema: None,
         })
         .await
         .unwrap_err();
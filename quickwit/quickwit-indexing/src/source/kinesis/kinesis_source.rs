@@ -314,6 +314,9 @@ impl Source for KinesisSource {
                 docs,
                 checkpoint_delta,
                 force_commit: false,
+                csv_header_opt: None,
+                invalid_docs: Vec::new(),
+                doc_positions: Vec::new(),
             };
             ctx.send_message(indexer_mailbox, batch).await?;
         }
@@ -54,7 +54,7 @@
 //!
 //! Right now two sources are implemented in quickwit.
 //! - the file source: there partition here is a filepath, and the position is a byte-offset within
-//!   that file.
+//!   that file, or `Eof` once a compressed file has been read entirely.
 //! - the kafka source: the partition id is a kafka topic partition id, and the position is a kafka
 //!   offset.
 mod file_source;
@@ -68,6 +68,8 @@ mod kafka_source;
 mod kinesis;
 #[cfg(feature = "pulsar")]
 mod pulsar_source;
+#[cfg(feature = "kafka")]
+mod schema_registry;
 mod source_factory;
 mod vec_source;
 mod void_source;
@@ -93,10 +95,10 @@ use quickwit_actors::{Actor, ActorContext, ActorExitStatus, Handler, Mailbox};
 use quickwit_common::runtimes::RuntimeType;
 use quickwit_config::{SourceConfig, SourceParams};
 use quickwit_ingest::IngesterPool;
-use quickwit_metastore::checkpoint::{SourceCheckpoint, SourceCheckpointDelta};
+use quickwit_metastore::checkpoint::{PartitionId, SourceCheckpoint, SourceCheckpointDelta};
 use quickwit_proto::indexing::IndexingPipelineId;
 use quickwit_proto::metastore::MetastoreServiceClient;
-use quickwit_proto::types::{IndexUid, ShardId};
+use quickwit_proto::types::{IndexUid, Position, ShardId};
 use quickwit_storage::StorageResolver;
 use serde_json::Value as JsonValue;
 pub use source_factory::{SourceFactory, SourceLoader, TypedSourceFactory};
@@ -105,9 +107,8 @@ use tracing::error;
 pub use vec_source::{VecSource, VecSourceFactory};
 pub use void_source::{VoidSource, VoidSourceFactory};
 
-use self::file_source::dir_and_filename;
 use crate::actors::DocProcessor;
-use crate::models::RawDocBatch;
+use crate::models::{InvalidRawDoc, RawDocBatch};
 use crate::source::ingest::IngestSourceFactory;
 use crate::source::ingest_api_source::IngestApiSourceFactory;
 
@@ -288,6 +289,11 @@ pub struct Assignment {
 #[derive(Debug)]
 pub struct AssignShards(pub Assignment);
 
+/// Asks the source to stop emitting batches. The downstream actors index and publish the batches
+/// already emitted before exiting in turn.
+#[derive(Debug)]
+pub struct DrainSource;
+
 #[async_trait]
 impl Actor for SourceActor {
     type ObservableState = JsonValue;
@@ -361,6 +367,21 @@ impl Handler<AssignShards> for SourceActor {
     }
 }
 
+#[async_trait]
+impl Handler<DrainSource> for SourceActor {
+    type Reply = ();
+
+    async fn handle(
+        &mut self,
+        _message: DrainSource,
+        ctx: &SourceContext,
+    ) -> Result<(), ActorExitStatus> {
+        ctx.send_exit_with_success(&self.doc_processor_mailbox)
+            .await?;
+        Err(ActorExitStatus::Success)
+    }
+}
+
 pub fn quickwit_supported_sources() -> &'static SourceLoader {
     static SOURCE_LOADER: OnceCell<SourceLoader> = OnceCell::new();
     SOURCE_LOADER.get_or_init(|| {
@@ -389,9 +410,7 @@ pub async fn check_source_connectivity(
     match &source_config.source_params {
         SourceParams::File(params) => {
             if let Some(filepath) = &params.filepath {
-                let (dir_uri, file_name) = dir_and_filename(filepath)?;
-                let storage = storage_resolver.resolve(&dir_uri).await?;
-                storage.file_num_bytes(file_name).await?;
+                file_source::check_connectivity(storage_resolver, filepath).await?;
             }
             Ok(())
         }
@@ -457,6 +476,8 @@ impl Handler<SuggestTruncate> for SourceActor {
 #[derive(Debug, Default)]
 pub(crate) struct BatchBuilder {
     docs: Vec<Bytes>,
+    invalid_docs: Vec<InvalidRawDoc>,
+    doc_positions: Vec<(PartitionId, Position)>,
     num_bytes: u64,
     checkpoint_delta: SourceCheckpointDelta,
     force_commit: bool,
@@ -468,6 +489,37 @@ impl BatchBuilder {
         self.docs.push(doc);
     }
 
+    /// Adds a doc along with its partition and the checkpoint position right after it, which the
+    /// doc processor records if it rejects the doc. A source must add all its docs either with or
+    /// without a position.
+    #[cfg(any(feature = "kafka", feature = "pulsar"))]
+    pub fn add_doc_with_position(
+        &mut self,
+        doc: Bytes,
+        partition_id: PartitionId,
+        position: Position,
+    ) {
+        self.add_doc(doc);
+        self.doc_positions.push((partition_id, position));
+    }
+
+    /// Adds a doc the source failed to decode, which the doc processor records as a parse error.
+    #[cfg(feature = "kafka")]
+    pub fn add_invalid_doc(
+        &mut self,
+        raw_doc: Bytes,
+        error: String,
+        partition_id: PartitionId,
+        position: Position,
+    ) {
+        self.num_bytes += raw_doc.len() as u64;
+        self.invalid_docs.push(InvalidRawDoc {
+            raw_doc,
+            error,
+            position_opt: Some((partition_id, position)),
+        });
+    }
+
     pub fn force_commit(&mut self) {
         self.force_commit = true;
     }
@@ -477,12 +529,17 @@ impl BatchBuilder {
             docs: self.docs,
             checkpoint_delta: self.checkpoint_delta,
             force_commit: self.force_commit,
+            csv_header_opt: None,
+            invalid_docs: self.invalid_docs,
+            doc_positions: self.doc_positions,
         }
     }
 
     #[cfg(feature = "kafka")]
     pub fn clear(&mut self) {
         self.docs.clear();
+        self.invalid_docs.clear();
+        self.doc_positions.clear();
         self.num_bytes = 0;
         self.checkpoint_delta = SourceCheckpointDelta::default();
     }
@@ -508,6 +565,8 @@ mod tests {
                 source_params: SourceParams::void(),
                 transform_config: None,
                 input_format: SourceInputFormat::Json,
+                csv_columns_opt: None,
+                dead_letter_opt: None,
             };
             check_source_connectivity(&StorageResolver::for_test(), &source_config).await?;
         }
@@ -520,6 +579,8 @@ mod tests {
                 source_params: SourceParams::Vec(VecSourceParams::default()),
                 transform_config: None,
                 input_format: SourceInputFormat::Json,
+                csv_columns_opt: None,
+                dead_letter_opt: None,
             };
             check_source_connectivity(&StorageResolver::for_test(), &source_config).await?;
         }
@@ -532,6 +593,8 @@ mod tests {
                 source_params: SourceParams::file("file-does-not-exist.json"),
                 transform_config: None,
                 input_format: SourceInputFormat::Json,
+                csv_columns_opt: None,
+                dead_letter_opt: None,
             };
             assert!(
                 check_source_connectivity(&StorageResolver::for_test(), &source_config)
@@ -548,6 +611,8 @@ mod tests {
                 source_params: SourceParams::file("data/test_corpus.json"),
                 transform_config: None,
                 input_format: SourceInputFormat::Json,
+                csv_columns_opt: None,
+                dead_letter_opt: None,
             };
             assert!(
                 check_source_connectivity(&StorageResolver::for_test(), &source_config)
@@ -193,9 +193,9 @@ impl PulsarSource {
 
         batch
             .checkpoint_delta
-            .record_partition_delta(partition, current_position, msg_position)
+            .record_partition_delta(partition.clone(), current_position, msg_position.clone())
             .context("failed to record partition delta")?;
-        batch.add_doc(doc);
+        batch.add_doc_with_position(doc, partition, msg_position);
 
         self.state.num_bytes_processed += num_bytes;
         self.state.num_messages_processed += 1;
@@ -555,6 +555,8 @@ mod pulsar_broker_tests {
             }),
             transform_config: None,
             input_format: SourceInputFormat::Json,
+            csv_columns_opt: None,
+            dead_letter_opt: None,
         };
         (source_id, source_config)
     }
@@ -879,6 +881,10 @@ mod pulsar_broker_tests {
         );
         assert_eq!(batch.num_bytes, 16);
         assert_eq!(batch.docs.len(), 1);
+        assert_eq!(
+            batch.doc_positions,
+            [(PartitionId::from(topic.as_str()), Position::from(4u64))]
+        );
 
         let mut expected_checkpoint_delta = SourceCheckpointDelta::default();
         expected_checkpoint_delta
@@ -0,0 +1,841 @@
+// Copyright (C) 2023 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Decoding of the Avro and Protobuf messages of a Kafka source into JSON docs, using the writer
+//! schemas of a Confluent schema registry or an inline schema.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{bail, Context};
+use apache_avro::types::Value as AvroValue;
+use apache_avro::Schema as AvroSchema;
+use base64::prelude::{Engine, BASE64_STANDARD};
+use bytes::Bytes;
+use prost::Message;
+use prost_reflect::{
+    DescriptorPool, DynamicMessage, FileDescriptor, MessageDescriptor, SerializeOptions,
+};
+use prost_types::FileDescriptorProto;
+use quickwit_config::{KafkaSchemaParams, SourceInputFormat};
+use reqwest::{StatusCode, Url};
+use serde::Deserialize;
+use serde_json::Value as JsonValue;
+
+/// Magic byte starting the messages framed with the Confluent wire format. It is followed by the
+/// 4-byte big-endian ID of the writer schema.
+const CONFLUENT_MAGIC_BYTE: u8 = 0;
+
+const CONFLUENT_HEADER_LEN: usize = 5;
+
+const SCHEMA_REGISTRY_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug)]
+pub(super) enum DecodeError {
+    /// The message cannot be decoded and is skipped.
+    InvalidMessage(String),
+    /// The writer schema of the message cannot be resolved. The error may be transient, so the
+    /// message must not be skipped.
+    Schema(anyhow::Error),
+}
+
+impl DecodeError {
+    fn invalid(message: impl Into<String>) -> Self {
+        DecodeError::InvalidMessage(message.into())
+    }
+}
+
+enum WriterSchemas {
+    Registry(SchemaRegistryClient),
+    Avro(AvroSchema),
+    Protobuf(MessageDescriptor),
+}
+
+/// Decodes the Avro or Protobuf messages of a Kafka source into JSON docs.
+pub(super) struct MessageDecoder {
+    input_format: SourceInputFormat,
+    writer_schemas: WriterSchemas,
+}
+
+impl MessageDecoder {
+    /// Creates a decoder for the `avro` and `protobuf` input formats, or returns `None` for the
+    /// other formats, whose messages are forwarded as is.
+    pub fn try_new(
+        input_format: SourceInputFormat,
+        schema_params_opt: Option<&KafkaSchemaParams>,
+    ) -> anyhow::Result<Option<Self>> {
+        if !matches!(
+            input_format,
+            SourceInputFormat::Avro | SourceInputFormat::Protobuf
+        ) {
+            return Ok(None);
+        }
+        let schema_params = schema_params_opt
+            .context("the `avro` and `protobuf` input formats require a Kafka source `schema`")?;
+        let writer_schemas = match (schema_params, input_format) {
+            (KafkaSchemaParams::Registry { url }, _) => {
+                WriterSchemas::Registry(SchemaRegistryClient::try_new(url)?)
+            }
+            (KafkaSchemaParams::Avro { schema }, SourceInputFormat::Avro) => {
+                let avro_schema =
+                    AvroSchema::parse_str(schema).context("failed to parse Avro schema")?;
+                WriterSchemas::Avro(avro_schema)
+            }
+            (
+                KafkaSchemaParams::DescriptorSet {
+                    descriptor_set,
+                    message_type,
+                },
+                SourceInputFormat::Protobuf,
+            ) => {
+                let descriptor_set_bytes = BASE64_STANDARD
+                    .decode(descriptor_set)
+                    .context("failed to decode base64 Protobuf descriptor set")?;
+                let descriptor_pool = DescriptorPool::decode(descriptor_set_bytes.as_slice())
+                    .context("failed to parse Protobuf descriptor set")?;
+                let message_descriptor = descriptor_pool
+                    .get_message_by_name(message_type)
+                    .with_context(|| {
+                        format!("message type `{message_type}` not found in descriptor set")
+                    })?;
+                WriterSchemas::Protobuf(message_descriptor)
+            }
+            _ => bail!("Kafka source `schema` does not match the input format"),
+        };
+        let message_decoder = Self {
+            input_format,
+            writer_schemas,
+        };
+        Ok(Some(message_decoder))
+    }
+
+    /// Decodes a message framed with the Confluent wire format into a JSON doc.
+    pub async fn decode(&mut self, payload: &[u8]) -> Result<Bytes, DecodeError> {
+        let (schema_id, body) = split_confluent_header(payload)?;
+
+        let json_doc = match &mut self.writer_schemas {
+            WriterSchemas::Avro(avro_schema) => decode_avro(avro_schema, body)?,
+            WriterSchemas::Protobuf(message_descriptor) => {
+                let (_message_indexes, body) = split_message_indexes(body)?;
+                decode_protobuf(message_descriptor.clone(), body)?
+            }
+            WriterSchemas::Registry(schema_registry) => {
+                if self.input_format == SourceInputFormat::Avro {
+                    let avro_schema = schema_registry.avro_schema(schema_id).await?;
+                    decode_avro(&avro_schema, body)?
+                } else {
+                    let (message_indexes, body) = split_message_indexes(body)?;
+                    let file_descriptor = schema_registry.protobuf_file(schema_id).await?;
+                    let message_descriptor = find_message_descriptor(
+                        &file_descriptor,
+                        &message_indexes,
+                    )
+                    .ok_or_else(|| {
+                        DecodeError::invalid(format!(
+                            "message indexes {message_indexes:?} not found in schema `{schema_id}`"
+                        ))
+                    })?;
+                    decode_protobuf(message_descriptor, body)?
+                }
+            }
+        };
+        Ok(Bytes::from(json_doc))
+    }
+}
+
+/// Splits a message framed with the Confluent wire format into its schema ID and its body.
+fn split_confluent_header(payload: &[u8]) -> Result<(u32, &[u8]), DecodeError> {
+    if payload.len() < CONFLUENT_HEADER_LEN || payload[0] != CONFLUENT_MAGIC_BYTE {
+        return Err(DecodeError::invalid(
+            "message is not framed with the Confluent wire format",
+        ));
+    }
+    let schema_id_bytes: [u8; 4] = payload[1..CONFLUENT_HEADER_LEN]
+        .try_into()
+        .expect("slice should be 4 bytes long");
+    let schema_id = u32::from_be_bytes(schema_id_bytes);
+    Ok((schema_id, &payload[CONFLUENT_HEADER_LEN..]))
+}
+
+/// Splits the body of a Protobuf message into the indexes of its message type in the schema and
+/// the encoded message. The indexes are encoded as an array of zigzag varints, and the array `[0]`
+/// designating the first message type as a single `0`.
+fn split_message_indexes(body: &[u8]) -> Result<(Vec<usize>, &[u8]), DecodeError> {
+    let mut rest = body;
+    let num_indexes = read_zigzag_varint(&mut rest)?;
+
+    if num_indexes == 0 {
+        return Ok((vec![0], rest));
+    }
+    if num_indexes < 0 || num_indexes as usize > rest.len() {
+        return Err(DecodeError::invalid("invalid Protobuf message indexes"));
+    }
+    let mut message_indexes = Vec::with_capacity(num_indexes as usize);
+
+    for _ in 0..num_indexes {
+        let message_index = usize::try_from(read_zigzag_varint(&mut rest)?)
+            .map_err(|_| DecodeError::invalid("invalid Protobuf message indexes"))?;
+        message_indexes.push(message_index);
+    }
+    Ok((message_indexes, rest))
+}
+
+fn read_zigzag_varint(input: &mut &[u8]) -> Result<i64, DecodeError> {
+    let mut value: u64 = 0;
+
+    for shift in (0..64).step_by(7) {
+        let (&byte, rest) = input
+            .split_first()
+            .ok_or_else(|| DecodeError::invalid("truncated Protobuf message indexes"))?;
+        *input = rest;
+        value |= u64::from(byte & 0x7F) << shift;
+
+        if byte & 0x80 == 0 {
+            return Ok((value >> 1) as i64 ^ -((value & 1) as i64));
+        }
+    }
+    Err(DecodeError::invalid("invalid Protobuf message indexes"))
+}
+
+/// Returns the message type designated by the given indexes: the index of a top-level message
+/// type of the file, followed by the indexes of the nested message types.
+fn find_message_descriptor(
+    file_descriptor: &FileDescriptor,
+    message_indexes: &[usize],
+) -> Option<MessageDescriptor> {
+    let (first_index, nested_indexes) = message_indexes.split_first()?;
+    let mut message_descriptor = file_descriptor.messages().nth(*first_index)?;
+
+    for nested_index in nested_indexes {
+        message_descriptor = message_descriptor.child_messages().nth(*nested_index)?;
+    }
+    Some(message_descriptor)
+}
+
+fn decode_avro(avro_schema: &AvroSchema, mut body: &[u8]) -> Result<Vec<u8>, DecodeError> {
+    let avro_value = apache_avro::from_avro_datum(avro_schema, &mut body, None)
+        .map_err(|error| DecodeError::invalid(format!("failed to decode Avro message: {error}")))?;
+    let json_value = avro_value_to_json(avro_value).map_err(|error| {
+        DecodeError::invalid(format!("failed to convert Avro message to JSON: {error}"))
+    })?;
+    let json_doc = serde_json::to_vec(&json_value).expect("JSON value should be serializable");
+    Ok(json_doc)
+}
+
+/// Converts an Avro value into JSON. Unions are replaced with their value, enums with their
+/// symbol, and bytes are base64-encoded.
+fn avro_value_to_json(avro_value: AvroValue) -> anyhow::Result<JsonValue> {
+    let json_value = match avro_value {
+        AvroValue::Bytes(bytes) | AvroValue::Fixed(_, bytes) => {
+            JsonValue::String(BASE64_STANDARD.encode(bytes))
+        }
+        AvroValue::Enum(_, symbol) => JsonValue::String(symbol),
+        AvroValue::Union(_, avro_value) => avro_value_to_json(*avro_value)?,
+        AvroValue::Array(avro_values) => JsonValue::Array(
+            avro_values
+                .into_iter()
+                .map(avro_value_to_json)
+                .collect::<anyhow::Result<_>>()?,
+        ),
+        AvroValue::Map(avro_entries) => JsonValue::Object(
+            avro_entries
+                .into_iter()
+                .map(|(key, avro_value)| Ok((key, avro_value_to_json(avro_value)?)))
+                .collect::<anyhow::Result<_>>()?,
+        ),
+        AvroValue::Record(avro_fields) => JsonValue::Object(
+            avro_fields
+                .into_iter()
+                .map(|(name, avro_value)| Ok((name, avro_value_to_json(avro_value)?)))
+                .collect::<anyhow::Result<_>>()?,
+        ),
+        avro_value => JsonValue::try_from(avro_value)?,
+    };
+    Ok(json_value)
+}
+
+/// Decodes a Protobuf message into JSON, following the canonical JSON mapping of Protobuf except
+/// that the field names are kept as is and the 64-bit integers are not stringified.
+fn decode_protobuf(
+    message_descriptor: MessageDescriptor,
+    body: &[u8],
+) -> Result<Vec<u8>, DecodeError> {
+    let message = DynamicMessage::decode(message_descriptor, body).map_err(|error| {
+        DecodeError::invalid(format!("failed to decode Protobuf message: {error}"))
+    })?;
+    let options = SerializeOptions::new()
+        .stringify_64_bit_integers(false)
+        .use_proto_field_name(true);
+    let mut json_doc = Vec::new();
+    let mut serializer = serde_json::Serializer::new(&mut json_doc);
+    message
+        .serialize_with_options(&mut serializer, &options)
+        .map_err(|error| {
+            DecodeError::invalid(format!(
+                "failed to convert Protobuf message to JSON: {error}"
+            ))
+        })?;
+    Ok(json_doc)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RegisteredSchema {
+    schema: String,
+    // Absent for Avro schemas.
+    #[serde(default)]
+    schema_type: Option<String>,
+    #[serde(default)]
+    references: Vec<SchemaReference>,
+}
+
+#[derive(Deserialize)]
+struct SchemaReference {
+    name: String,
+    subject: String,
+    version: i32,
+}
+
+/// Client of a Confluent schema registry, caching the schemas by ID. The schemas registered under
+/// an ID are immutable, so the cache never needs to be invalidated.
+struct SchemaRegistryClient {
+    url: Url,
+    http_client: reqwest::Client,
+    avro_schemas: HashMap<u32, Arc<AvroSchema>>,
+    protobuf_files: HashMap<u32, FileDescriptor>,
+}
+
+impl SchemaRegistryClient {
+    fn try_new(url: &str) -> anyhow::Result<Self> {
+        let url = Url::parse(url)
+            .with_context(|| format!("failed to parse schema registry URL `{url}`"))?;
+        let http_client = reqwest::Client::builder()
+            .timeout(SCHEMA_REGISTRY_TIMEOUT)
+            .build()
+            .context("failed to create schema registry HTTP client")?;
+        Ok(Self {
+            url,
+            http_client,
+            avro_schemas: HashMap::new(),
+            protobuf_files: HashMap::new(),
+        })
+    }
+
+    fn endpoint_url(&self, path_segments: &[&str], serialized: bool) -> Url {
+        let mut url = self.url.clone();
+        url.path_segments_mut()
+            .expect("schema registry URL should be a base URL")
+            .pop_if_empty()
+            .extend(path_segments);
+        if serialized {
+            url.query_pairs_mut().append_pair("format", "serialized");
+        }
+        url
+    }
+
+    async fn fetch_schema(&self, url: Url) -> Result<RegisteredSchema, DecodeError> {
+        let response = self
+            .http_client
+            .get(url.clone())
+            .send()
+            .await
+            .with_context(|| format!("failed to fetch schema from `{url}`"))
+            .map_err(DecodeError::Schema)?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Err(DecodeError::invalid(format!(
+                "schema `{url}` not found in schema registry"
+            )));
+        }
+        response
+            .error_for_status()
+            .with_context(|| format!("failed to fetch schema from `{url}`"))
+            .map_err(DecodeError::Schema)?
+            .json::<RegisteredSchema>()
+            .await
+            .with_context(|| format!("failed to parse schema fetched from `{url}`"))
+            .map_err(DecodeError::Schema)
+    }
+
+    async fn avro_schema(&mut self, schema_id: u32) -> Result<Arc<AvroSchema>, DecodeError> {
+        if let Some(avro_schema) = self.avro_schemas.get(&schema_id) {
+            return Ok(avro_schema.clone());
+        }
+        let url = self.endpoint_url(&["schemas", "ids", &schema_id.to_string()], false);
+        let registered_schema = self.fetch_schema(url).await?;
+
+        if !matches!(
+            registered_schema.schema_type.as_deref(),
+            None | Some("AVRO")
+        ) {
+            return Err(DecodeError::invalid(format!(
+                "schema `{schema_id}` is not an Avro schema"
+            )));
+        }
+        if !registered_schema.references.is_empty() {
+            return Err(DecodeError::Schema(anyhow::anyhow!(
+                "Avro schema `{schema_id}` has references, which are not supported"
+            )));
+        }
+        let avro_schema = AvroSchema::parse_str(&registered_schema.schema)
+            .with_context(|| format!("failed to parse Avro schema `{schema_id}`"))
+            .map_err(DecodeError::Schema)?;
+        let avro_schema = Arc::new(avro_schema);
+        self.avro_schemas.insert(schema_id, avro_schema.clone());
+        Ok(avro_schema)
+    }
+
+    /// Returns the Protobuf file registered under the given ID, after fetching the files it
+    /// references, and the files they reference in turn. The well-known types of the
+    /// `google/protobuf` files are built in.
+    async fn protobuf_file(&mut self, schema_id: u32) -> Result<FileDescriptor, DecodeError> {
+        if let Some(file_descriptor) = self.protobuf_files.get(&schema_id) {
+            return Ok(file_descriptor.clone());
+        }
+        let url = self.endpoint_url(&["schemas", "ids", &schema_id.to_string()], true);
+        let registered_schema = self.fetch_schema(url).await?;
+
+        if registered_schema.schema_type.as_deref() != Some("PROTOBUF") {
+            return Err(DecodeError::invalid(format!(
+                "schema `{schema_id}` is not a Protobuf schema"
+            )));
+        }
+        let file_proto = decode_file_descriptor_proto(&registered_schema.schema)?;
+        let file_name = file_proto.name().to_string();
+        let mut file_protos = vec![file_proto];
+
+        let mut pending_references = registered_schema.references;
+        let mut visited_file_names = HashSet::new();
+        // The well-known types are taken from the global pool, which holds them by default.
+        let well_known_descriptor_pool = DescriptorPool::global();
+
+        while let Some(reference) = pending_references.pop() {
+            if !visited_file_names.insert(reference.name.clone()) {
+                continue;
+            }
+            if reference.name.starts_with("google/protobuf/") {
+                if let Some(well_known_file) =
+                    well_known_descriptor_pool.get_file_by_name(&reference.name)
+                {
+                    file_protos.push(well_known_file.file_descriptor_proto().clone());
+                }
+                continue;
+            }
+            let version = reference.version.to_string();
+            let url = self.endpoint_url(
+                &["subjects", &reference.subject, "versions", &version],
+                true,
+            );
+            let referenced_schema = self.fetch_schema(url).await?;
+            file_protos.push(decode_file_descriptor_proto(&referenced_schema.schema)?);
+            pending_references.extend(referenced_schema.references);
+        }
+        // Each schema gets its own pool: the versions of a schema usually share the same file
+        // name, which a shared pool would reject as conflicting files.
+        let mut descriptor_pool = DescriptorPool::new();
+        descriptor_pool
+            .add_file_descriptor_protos(file_protos)
+            .with_context(|| format!("failed to build Protobuf schema `{schema_id}`"))
+            .map_err(DecodeError::Schema)?;
+        let file_descriptor = descriptor_pool
+            .get_file_by_name(&file_name)
+            .expect("file should have been added to the descriptor pool");
+        self.protobuf_files
+            .insert(schema_id, file_descriptor.clone());
+        Ok(file_descriptor)
+    }
+}
+
+fn decode_file_descriptor_proto(schema: &str) -> Result<FileDescriptorProto, DecodeError> {
+    BASE64_STANDARD
+        .decode(schema)
+        .context("failed to decode base64 Protobuf schema")
+        .and_then(|schema_bytes| {
+            FileDescriptorProto::decode(schema_bytes.as_slice())
+                .context("failed to parse Protobuf schema")
+        })
+        .map_err(DecodeError::Schema)
+}
+
+/// Checks whether we can reach the schema registry of a Kafka source.
+pub(super) async fn check_connectivity(schema_params: &KafkaSchemaParams) -> anyhow::Result<()> {
+    let KafkaSchemaParams::Registry { url } = schema_params else {
+        return Ok(());
+    };
+    let schema_registry = SchemaRegistryClient::try_new(url)?;
+    let url = schema_registry.endpoint_url(&["subjects"], false);
+    schema_registry
+        .http_client
+        .get(url.clone())
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .with_context(|| format!("failed to reach schema registry at `{url}`"))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use apache_avro::types::Record;
+    use prost_reflect::Value as ProtobufValue;
+    use prost_types::field_descriptor_proto::{Label, Type};
+    use prost_types::{DescriptorProto, FieldDescriptorProto, FileDescriptorSet};
+    use serde_json::json;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+
+    const AVRO_SCHEMA: &str = r#"{
+        "type": "record",
+        "name": "Log",
+        "fields": [
+            {"name": "message", "type": "string"},
+            {"name": "severity", "type": {"type": "enum", "name": "Severity", "symbols": ["INFO", "WARN"]}},
+            {"name": "payload", "type": ["null", "bytes"]},
+            {"name": "timestamp", "type": "long"}
+        ]
+    }"#;
+
+    fn frame_message(schema_id: u32, message_indexes: &[u8], body: &[u8]) -> Vec<u8> {
+        let mut payload = vec![CONFLUENT_MAGIC_BYTE];
+        payload.extend_from_slice(&schema_id.to_be_bytes());
+        payload.extend_from_slice(message_indexes);
+        payload.extend_from_slice(body);
+        payload
+    }
+
+    fn avro_body() -> Vec<u8> {
+        let avro_schema = AvroSchema::parse_str(AVRO_SCHEMA).unwrap();
+        let mut record = Record::new(&avro_schema).unwrap();
+        record.put("message", "hello");
+        record.put("severity", AvroValue::Enum(1, "WARN".to_string()));
+        record.put(
+            "payload",
+            AvroValue::Union(1, Box::new(AvroValue::Bytes(b"abc".to_vec()))),
+        );
+        record.put("timestamp", 1628837062i64);
+        apache_avro::to_avro_datum(&avro_schema, record).unwrap()
+    }
+
+    fn field_proto(name: &str, number: i32, field_type: Type) -> FieldDescriptorProto {
+        FieldDescriptorProto {
+            name: Some(name.to_string()),
+            number: Some(number),
+            label: Some(Label::Optional as i32),
+            r#type: Some(field_type as i32),
+            ..Default::default()
+        }
+    }
+
+    fn log_file_proto() -> FileDescriptorProto {
+        FileDescriptorProto {
+            name: Some("log.proto".to_string()),
+            package: Some("acme".to_string()),
+            message_type: vec![
+                DescriptorProto {
+                    name: Some("Metric".to_string()),
+                    field: vec![field_proto("value", 1, Type::Double)],
+                    ..Default::default()
+                },
+                DescriptorProto {
+                    name: Some("Log".to_string()),
+                    field: vec![
+                        field_proto("message", 1, Type::String),
+                        field_proto("timestamp", 2, Type::Int64),
+                    ],
+                    ..Default::default()
+                },
+            ],
+            syntax: Some("proto3".to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn protobuf_body() -> Vec<u8> {
+        let mut descriptor_pool = DescriptorPool::new();
+        descriptor_pool
+            .add_file_descriptor_proto(log_file_proto())
+            .unwrap();
+        let message_descriptor = descriptor_pool.get_message_by_name("acme.Log").unwrap();
+        let mut message = DynamicMessage::new(message_descriptor);
+        message.set_field_by_name("message", ProtobufValue::String("hello".to_string()));
+        message.set_field_by_name("timestamp", ProtobufValue::I64(1628837062));
+        message.encode_to_vec()
+    }
+
+    fn parse_json_doc(json_doc: Bytes) -> JsonValue {
+        serde_json::from_slice(&json_doc).unwrap()
+    }
+
+    #[test]
+    fn test_split_message_indexes() {
+        assert_eq!(
+            split_message_indexes(&[0, 42]).unwrap(),
+            (vec![0], &[42u8][..])
+        );
+        assert_eq!(
+            split_message_indexes(&[2, 2, 42]).unwrap(),
+            (vec![1], &[42u8][..])
+        );
+        assert_eq!(
+            split_message_indexes(&[4, 2, 0]).unwrap(),
+            (vec![1, 0], &[][..])
+        );
+        assert_eq!(
+            split_message_indexes(&[4, 0x80, 0x01, 6]).unwrap(),
+            (vec![64, 3], &[][..])
+        );
+        // Truncated indexes.
+        split_message_indexes(&[4, 2]).unwrap_err();
+        // Negative number of indexes.
+        split_message_indexes(&[1, 2]).unwrap_err();
+    }
+
+    #[test]
+    fn test_message_decoder_is_only_created_for_avro_and_protobuf() {
+        let schema_params = KafkaSchemaParams::Registry {
+            url: "http://localhost:8081".to_string(),
+        };
+        assert!(
+            MessageDecoder::try_new(SourceInputFormat::Json, Some(&schema_params))
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            MessageDecoder::try_new(SourceInputFormat::Avro, Some(&schema_params))
+                .unwrap()
+                .is_some()
+        );
+        MessageDecoder::try_new(SourceInputFormat::Protobuf, None).unwrap_err();
+
+        let schema_params = KafkaSchemaParams::Avro {
+            schema: AVRO_SCHEMA.to_string(),
+        };
+        MessageDecoder::try_new(SourceInputFormat::Protobuf, Some(&schema_params)).unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn test_message_decoder_avro_with_schema_registry() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/schemas/ids/7"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "schema": AVRO_SCHEMA
+            })))
+            // The schema is fetched once, then cached.
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let schema_params = KafkaSchemaParams::Registry {
+            url: mock_server.uri(),
+        };
+        let mut message_decoder =
+            MessageDecoder::try_new(SourceInputFormat::Avro, Some(&schema_params))
+                .unwrap()
+                .unwrap();
+        let payload = frame_message(7, &[], &avro_body());
+
+        for _ in 0..2 {
+            let json_doc = message_decoder.decode(&payload).await.unwrap();
+            assert_eq!(
+                parse_json_doc(json_doc),
+                json!({
+                    "message": "hello",
+                    "severity": "WARN",
+                    "payload": "YWJj",
+                    "timestamp": 1628837062
+                })
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_message_decoder_protobuf_with_schema_registry() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/registry/schemas/ids/3"))
+            .and(query_param("format", "serialized"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "schemaType": "PROTOBUF",
+                "schema": BASE64_STANDARD.encode(log_file_proto().encode_to_vec())
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let schema_params = KafkaSchemaParams::Registry {
+            url: format!("{}/registry/", mock_server.uri()),
+        };
+        let mut message_decoder =
+            MessageDecoder::try_new(SourceInputFormat::Protobuf, Some(&schema_params))
+                .unwrap()
+                .unwrap();
+        // The message indexes `[1]` designate the second message type of the file, `acme.Log`.
+        let payload = frame_message(3, &[2, 2], &protobuf_body());
+
+        for _ in 0..2 {
+            let json_doc = message_decoder.decode(&payload).await.unwrap();
+            assert_eq!(
+                parse_json_doc(json_doc),
+                json!({"message": "hello", "timestamp": 1628837062})
+            );
+        }
+        let payload = frame_message(3, &[2, 4], &protobuf_body());
+        let error = message_decoder.decode(&payload).await.unwrap_err();
+        assert!(matches!(error, DecodeError::InvalidMessage(_)));
+    }
+
+    #[tokio::test]
+    async fn test_message_decoder_protobuf_schema_versions() {
+        let mut log_file_proto_v2 = log_file_proto();
+        log_file_proto_v2.message_type[1]
+            .field
+            .push(field_proto("severity", 3, Type::String));
+
+        let mock_server = MockServer::start().await;
+        for (schema_id, file_proto) in [(3, log_file_proto()), (4, log_file_proto_v2.clone())] {
+            Mock::given(method("GET"))
+                .and(path(format!("/schemas/ids/{schema_id}")))
+                .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                    "schemaType": "PROTOBUF",
+                    "schema": BASE64_STANDARD.encode(file_proto.encode_to_vec())
+                })))
+                .expect(1)
+                .mount(&mock_server)
+                .await;
+        }
+        let schema_params = KafkaSchemaParams::Registry {
+            url: mock_server.uri(),
+        };
+        let mut message_decoder =
+            MessageDecoder::try_new(SourceInputFormat::Protobuf, Some(&schema_params))
+                .unwrap()
+                .unwrap();
+        let payload = frame_message(3, &[2, 2], &protobuf_body());
+        let json_doc = message_decoder.decode(&payload).await.unwrap();
+        assert_eq!(
+            parse_json_doc(json_doc),
+            json!({"message": "hello", "timestamp": 1628837062})
+        );
+        // Both versions of `log.proto` are registered, under different schema IDs.
+        let mut descriptor_pool = DescriptorPool::new();
+        descriptor_pool
+            .add_file_descriptor_proto(log_file_proto_v2)
+            .unwrap();
+        let message_descriptor = descriptor_pool.get_message_by_name("acme.Log").unwrap();
+        let mut message = DynamicMessage::new(message_descriptor);
+        message.set_field_by_name("message", ProtobufValue::String("bye".to_string()));
+        message.set_field_by_name("severity", ProtobufValue::String("WARN".to_string()));
+        let payload = frame_message(4, &[2, 2], &message.encode_to_vec());
+
+        let json_doc = message_decoder.decode(&payload).await.unwrap();
+        assert_eq!(
+            parse_json_doc(json_doc),
+            json!({"message": "bye", "severity": "WARN"})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_message_decoder_with_inline_schemas() {
+        let schema_params = KafkaSchemaParams::Avro {
+            schema: AVRO_SCHEMA.to_string(),
+        };
+        let mut message_decoder =
+            MessageDecoder::try_new(SourceInputFormat::Avro, Some(&schema_params))
+                .unwrap()
+                .unwrap();
+        let payload = frame_message(1, &[], &avro_body());
+        let json_doc = message_decoder.decode(&payload).await.unwrap();
+        assert_eq!(parse_json_doc(json_doc)["message"], "hello");
+
+        let descriptor_set = FileDescriptorSet {
+            file: vec![log_file_proto()],
+        };
+        let schema_params = KafkaSchemaParams::DescriptorSet {
+            descriptor_set: BASE64_STANDARD.encode(descriptor_set.encode_to_vec()),
+            message_type: "acme.Log".to_string(),
+        };
+        let mut message_decoder =
+            MessageDecoder::try_new(SourceInputFormat::Protobuf, Some(&schema_params))
+                .unwrap()
+                .unwrap();
+        let payload = frame_message(1, &[0], &protobuf_body());
+        let json_doc = message_decoder.decode(&payload).await.unwrap();
+        assert_eq!(
+            parse_json_doc(json_doc),
+            json!({"message": "hello", "timestamp": 1628837062})
+        );
+        let schema_params = KafkaSchemaParams::DescriptorSet {
+            descriptor_set: BASE64_STANDARD.encode(descriptor_set.encode_to_vec()),
+            message_type: "acme.Unknown".to_string(),
+        };
+        MessageDecoder::try_new(SourceInputFormat::Protobuf, Some(&schema_params)).unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn test_message_decoder_errors() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/schemas/ids/5"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&mock_server)
+            .await;
+        // Other schemas are not found.
+        let schema_params = KafkaSchemaParams::Registry {
+            url: mock_server.uri(),
+        };
+        let mut message_decoder =
+            MessageDecoder::try_new(SourceInputFormat::Avro, Some(&schema_params))
+                .unwrap()
+                .unwrap();
+
+        let error = message_decoder.decode(&avro_body()).await.unwrap_err();
+        assert!(matches!(error, DecodeError::InvalidMessage(_)));
+
+        let payload = frame_message(4, &[], &avro_body());
+        let error = message_decoder.decode(&payload).await.unwrap_err();
+        assert!(matches!(error, DecodeError::InvalidMessage(_)));
+
+        let payload = frame_message(5, &[], &avro_body());
+        let error = message_decoder.decode(&payload).await.unwrap_err();
+        assert!(matches!(error, DecodeError::Schema(_)));
+    }
+
+    #[tokio::test]
+    async fn test_check_connectivity() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/subjects"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!(["logs-value"])))
+            .mount(&mock_server)
+            .await;
+        let schema_params = KafkaSchemaParams::Registry {
+            url: mock_server.uri(),
+        };
+        check_connectivity(&schema_params).await.unwrap();
+
+        let schema_params = KafkaSchemaParams::Registry {
+            url: format!("{}/unknown", mock_server.uri()),
+        };
+        check_connectivity(&schema_params).await.unwrap_err();
+    }
+}
@@ -164,6 +164,8 @@ mod tests {
             source_params: SourceParams::Vec(params.clone()),
             transform_config: None,
             input_format: SourceInputFormat::Json,
+            csv_columns_opt: None,
+            dead_letter_opt: None,
         };
         let metastore = metastore_for_test();
         let vec_source = VecSourceFactory::typed_create_source(
@@ -225,6 +227,8 @@ mod tests {
             source_params: SourceParams::Vec(params.clone()),
             transform_config: None,
             input_format: SourceInputFormat::Json,
+            csv_columns_opt: None,
+            dead_letter_opt: None,
         };
         let metastore = metastore_for_test();
         let vec_source = VecSourceFactory::typed_create_source(
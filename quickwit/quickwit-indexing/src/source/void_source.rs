@@ -94,6 +94,8 @@ mod tests {
             source_params: SourceParams::void(),
             transform_config: None,
             input_format: SourceInputFormat::Json,
+            csv_columns_opt: None,
+            dead_letter_opt: None,
         };
         let metastore = metastore_for_test();
         let ctx = SourceRuntimeArgs::for_test(
@@ -120,6 +122,8 @@ mod tests {
             source_params: SourceParams::void(),
             transform_config: None,
             input_format: SourceInputFormat::Json,
+            csv_columns_opt: None,
+            dead_letter_opt: None,
         };
         let metastore = metastore_for_test();
         let void_source = VoidSourceFactory::typed_create_source(
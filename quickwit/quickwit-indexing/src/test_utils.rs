@@ -167,6 +167,8 @@ impl TestSandbox {
             }),
             transform_config: None,
             input_format: SourceInputFormat::Json,
+            csv_columns_opt: None,
+            dead_letter_opt: None,
         };
         let pipeline_id = self
             .indexing_service
@@ -0,0 +1,827 @@
+// Copyright (C) 2023 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Service dependency graph, served to the "System Architecture" tab of the Jaeger UI.
+//!
+//! The graph counts the calls between services: a span whose parent span belongs to another
+//! service is a call from the parent's service to the span's service. It is either computed on
+//! the fly from the spans of the requested time range, or read from a summary index into which
+//! the graph of each time window is periodically stored.
+
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Range;
+use std::sync::Arc;
+use std::time::Duration;
+
+use quickwit_common::retry::RetryParams;
+use quickwit_common::uri::Uri;
+use quickwit_config::{
+    load_index_config_from_user_config, ConfigFormat, IndexConfig, JaegerConfig,
+};
+use quickwit_ingest::{
+    CommitType, DocBatchBuilder, IngestRequest, IngestService, IngestServiceClient,
+};
+use quickwit_opentelemetry::otlp::{SpanId, TraceId, OTEL_TRACES_INDEX_ID};
+use quickwit_proto::jaeger::api_v2::DependencyLink;
+use quickwit_proto::jaeger::storage::v1::{GetDependenciesRequest, GetDependenciesResponse};
+use quickwit_proto::search::{CountHits, SearchRequest, SortField, SortOrder};
+use quickwit_query::query_ast::QueryAst;
+use quickwit_search::SearchService;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+use time::OffsetDateTime;
+use tonic::Status;
+use tracing::{debug, info, instrument, warn};
+
+use crate::{json_deserialize, JaegerResult, JaegerService};
+
+pub const OTEL_DEPENDENCIES_INDEX_ID: &str = "otel-dependencies-v0_6";
+
+const OTEL_DEPENDENCIES_INDEX_CONFIG: &str = r#"
+version: 0.6
+
+index_id: ${INDEX_ID}
+
+doc_mapping:
+  mode: strict
+  field_mappings:
+    - name: timestamp
+      type: datetime
+      input_formats: [unix_timestamp]
+      output_format: unix_timestamp_secs
+      indexed: false
+      fast: true
+      fast_precision: seconds
+    - name: parent
+      type: text
+      tokenizer: raw
+      fast: true
+    - name: child
+      type: text
+      tokenizer: raw
+      fast: true
+    - name: call_count
+      type: u64
+      indexed: false
+      fast: true
+  timestamp_field: timestamp
+
+indexing_settings:
+  commit_timeout_secs: 10
+"#;
+
+/// Maximum number of parent services, and of child services of each parent service, read from
+/// the summary index.
+const MAX_NUM_SERVICES: u64 = 10_000;
+
+/// Maximum number of spans scanned to compute a dependency graph. Beyond, the graph is truncated.
+const MAX_DEPENDENCIES_SPANS: u64 = 1_000_000;
+
+/// Delay after the end of a time window before its dependency graph is computed, leaving time for
+/// the spans of the window to be committed.
+const DEPENDENCIES_SUMMARY_DELAY_SECS: i64 = 60;
+
+/// Maximum age of the time windows missed while the summary task was not running that are
+/// summarized when it starts.
+const DEPENDENCIES_SUMMARY_MAX_CATCH_UP_SECS: i64 = 7 * 24 * 60 * 60;
+
+/// Backoff between the attempts to summarize a time window. The task retries a window until it
+/// succeeds, so `max_attempts` is not used.
+const DEPENDENCIES_SUMMARY_RETRY_PARAMS: RetryParams = RetryParams {
+    base_delay: Duration::from_secs(5),
+    max_delay: Duration::from_secs(5 * 60),
+    max_attempts: usize::MAX,
+};
+
+/// Fields of the spans read to compute a dependency graph.
+const SPAN_EDGE_FIELDS: [&str; 4] = ["trace_id", "span_id", "parent_span_id", "service_name"];
+
+pub fn dependencies_index_config(default_index_root_uri: &Uri) -> anyhow::Result<IndexConfig> {
+    let index_config_str =
+        OTEL_DEPENDENCIES_INDEX_CONFIG.replace("${INDEX_ID}", OTEL_DEPENDENCIES_INDEX_ID);
+    let index_config = load_index_config_from_user_config(
+        ConfigFormat::Yaml,
+        index_config_str.as_bytes(),
+        default_index_root_uri,
+    )?;
+    Ok(index_config)
+}
+
+/// Fields of a span needed to find the service of its parent span.
+#[derive(Debug, Deserialize)]
+struct SpanEdge {
+    trace_id: TraceId,
+    span_id: SpanId,
+    #[serde(default)]
+    parent_span_id: Option<SpanId>,
+    service_name: String,
+}
+
+/// Document of the dependencies summary index, counting the calls between two services over a
+/// time window.
+#[derive(Debug, Serialize)]
+struct DependencySummaryDoc<'a> {
+    timestamp: i64,
+    parent: &'a str,
+    child: &'a str,
+    call_count: u64,
+}
+
+/// Builds the dependency graph of a set of spans, added one page of spans at a time.
+#[derive(Debug, Default)]
+struct DependencyGraphBuilder {
+    service_names: Vec<String>,
+    service_ords: HashMap<String, usize>,
+    /// Service of each span, as an index into `service_names`.
+    span_services: HashMap<(TraceId, SpanId), usize>,
+    /// Trace ID, parent span ID and service of the spans with a parent span. Their parent span
+    /// may only be added later, so they are resolved once all the spans are added.
+    child_spans: Vec<(TraceId, SpanId, usize)>,
+}
+
+impl DependencyGraphBuilder {
+    fn add_span(&mut self, span: SpanEdge) {
+        let service_ord = match self.service_ords.get(&span.service_name) {
+            Some(service_ord) => *service_ord,
+            None => {
+                let service_ord = self.service_names.len();
+                self.service_ords
+                    .insert(span.service_name.clone(), service_ord);
+                self.service_names.push(span.service_name);
+                service_ord
+            }
+        };
+        self.span_services
+            .insert((span.trace_id, span.span_id), service_ord);
+
+        if let Some(parent_span_id) = span.parent_span_id {
+            self.child_spans
+                .push((span.trace_id, parent_span_id, service_ord));
+        }
+    }
+
+    /// Calls within a service are not counted, and neither are the spans whose parent span was
+    /// not added.
+    fn build(self) -> DependencyGraph {
+        let mut dependency_graph = DependencyGraph::default();
+
+        for (trace_id, parent_span_id, service_ord) in self.child_spans {
+            let Some(parent_service_ord) = self.span_services.get(&(trace_id, parent_span_id))
+            else {
+                continue;
+            };
+            if *parent_service_ord != service_ord {
+                dependency_graph.add_calls(
+                    &self.service_names[*parent_service_ord],
+                    &self.service_names[service_ord],
+                    1,
+                );
+            }
+        }
+        dependency_graph
+    }
+}
+
+/// Number of calls between services, keyed by parent service and child service.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct DependencyGraph {
+    call_counts: BTreeMap<(String, String), u64>,
+}
+
+impl DependencyGraph {
+    /// Builds the graph from the nested `parent` and `child` terms aggregations run over the
+    /// summary index.
+    fn from_summary_aggregation(aggregation: &JsonValue) -> Self {
+        let mut dependency_graph = Self::default();
+
+        for parent_bucket in aggregation["parent"]["buckets"]
+            .as_array()
+            .into_iter()
+            .flatten()
+        {
+            let Some(parent) = parent_bucket["key"].as_str() else {
+                continue;
+            };
+            for child_bucket in parent_bucket["child"]["buckets"]
+                .as_array()
+                .into_iter()
+                .flatten()
+            {
+                let Some(child) = child_bucket["key"].as_str() else {
+                    continue;
+                };
+                let call_count = child_bucket["call_count"]["value"].as_f64().unwrap_or(0.0);
+                dependency_graph.add_calls(parent, child, call_count as u64);
+            }
+        }
+        dependency_graph
+    }
+
+    fn add_calls(&mut self, parent: &str, child: &str, call_count: u64) {
+        if call_count == 0 {
+            return;
+        }
+        *self
+            .call_counts
+            .entry((parent.to_string(), child.to_string()))
+            .or_default() += call_count;
+    }
+
+    fn is_empty(&self) -> bool {
+        self.call_counts.is_empty()
+    }
+
+    fn into_dependency_links(self) -> Vec<DependencyLink> {
+        self.call_counts
+            .into_iter()
+            .map(|((parent, child), call_count)| DependencyLink {
+                parent,
+                child,
+                call_count,
+                source: String::new(),
+            })
+            .collect()
+    }
+}
+
+impl JaegerService {
+    #[instrument("get_dependencies", skip_all)]
+    pub(crate) async fn get_dependencies_inner(
+        &self,
+        request: GetDependenciesRequest,
+    ) -> JaegerResult<GetDependenciesResponse> {
+        debug!(request=?request, "`get_dependencies` request");
+
+        let end = request
+            .end_time
+            .map(|timestamp| timestamp.seconds)
+            .unwrap_or_else(|| OffsetDateTime::now_utc().unix_timestamp());
+        let start = request
+            .start_time
+            .map(|timestamp| timestamp.seconds)
+            .unwrap_or(end - self.lookback_period_secs);
+        // The end of the search time range is excluded.
+        let time_range = start..end + 1;
+
+        let dependency_graph = if self.enable_dependencies_summary {
+            self.read_dependencies_summary(time_range).await?
+        } else {
+            self.scan_dependencies(time_range).await?
+        };
+        let dependencies = dependency_graph.into_dependency_links();
+        debug!(dependencies=?dependencies, "`get_dependencies` response");
+        let response = GetDependenciesResponse { dependencies };
+        Ok(response)
+    }
+
+    /// Computes the dependency graph of the spans started in the time range. The spans are read
+    /// page by page, `max_fetch_spans` at a time, up to `MAX_DEPENDENCIES_SPANS` spans, and only
+    /// the fields linking a span to its parent span are fetched.
+    async fn scan_dependencies(&self, time_range: Range<i64>) -> JaegerResult<DependencyGraph> {
+        let query_ast = serde_json::to_string(&QueryAst::MatchAll)
+            .map_err(|err| Status::internal(err.to_string()))?;
+        let mut search_request = SearchRequest {
+            index_id_patterns: vec![OTEL_TRACES_INDEX_ID.to_string()],
+            query_ast,
+            start_timestamp: Some(time_range.start),
+            end_timestamp: Some(time_range.end),
+            max_hits: self.max_fetch_spans,
+            sort_fields: vec![SortField {
+                field_name: "span_start_timestamp_nanos".to_string(),
+                sort_order: SortOrder::Asc.into(),
+            }],
+            count_hits: CountHits::Underestimate.into(),
+            fetch_fields: SPAN_EDGE_FIELDS
+                .iter()
+                .map(|field| field.to_string())
+                .collect(),
+            ..Default::default()
+        };
+        let mut dependency_graph_builder = DependencyGraphBuilder::default();
+        let mut num_spans: u64 = 0;
+
+        loop {
+            let search_response = self
+                .search_service
+                .root_search(search_request.clone())
+                .await?;
+            let num_hits = search_response.hits.len() as u64;
+            num_spans += num_hits;
+
+            for hit in &search_response.hits {
+                let span = json_deserialize::<SpanEdge>(&hit.json, "span")?;
+                dependency_graph_builder.add_span(span);
+            }
+            if num_hits < search_request.max_hits {
+                break;
+            }
+            if num_spans >= MAX_DEPENDENCIES_SPANS {
+                warn!(
+                    num_spans=%num_spans,
+                    start_timestamp=%time_range.start,
+                    end_timestamp=%time_range.end,
+                    "dependency graph is truncated: too many spans in time range"
+                );
+                break;
+            }
+            // The next page starts after the last span of this page.
+            search_request.search_after = search_response
+                .hits
+                .last()
+                .and_then(|hit| hit.partial_hit.clone());
+
+            if search_request.search_after.is_none() {
+                break;
+            }
+        }
+        Ok(dependency_graph_builder.build())
+    }
+
+    /// Sums the call counts stored in the summary index for the time windows starting in the
+    /// time range.
+    async fn read_dependencies_summary(
+        &self,
+        time_range: Range<i64>,
+    ) -> JaegerResult<DependencyGraph> {
+        let query_ast = serde_json::to_string(&QueryAst::MatchAll)
+            .map_err(|err| Status::internal(err.to_string()))?;
+        let aggregation_query = json!({
+            "parent": {
+                "terms": {"field": "parent", "size": MAX_NUM_SERVICES},
+                "aggs": {
+                    "child": {
+                        "terms": {"field": "child", "size": MAX_NUM_SERVICES},
+                        "aggs": {
+                            "call_count": {"sum": {"field": "call_count"}}
+                        }
+                    }
+                }
+            }
+        });
+        let search_request = SearchRequest {
+            index_id_patterns: vec![OTEL_DEPENDENCIES_INDEX_ID.to_string()],
+            query_ast,
+            aggregation_request: Some(aggregation_query.to_string()),
+            max_hits: 0,
+            start_timestamp: Some(time_range.start),
+            end_timestamp: Some(time_range.end),
+            count_hits: CountHits::Underestimate.into(),
+            ..Default::default()
+        };
+        let search_response = self.search_service.root_search(search_request).await?;
+
+        let Some(aggregation_json) = search_response.aggregation else {
+            return Ok(DependencyGraph::default());
+        };
+        let aggregation: JsonValue =
+            json_deserialize(&aggregation_json, "dependencies aggregation")?;
+        Ok(DependencyGraph::from_summary_aggregation(&aggregation))
+    }
+
+    /// Computes the dependency graph of a time window and stores it into the summary index.
+    async fn summarize_dependencies(
+        &self,
+        time_range: Range<i64>,
+        ingest_service: &mut IngestServiceClient,
+    ) -> anyhow::Result<()> {
+        let dependency_graph = self.scan_dependencies(time_range.clone()).await?;
+
+        if dependency_graph.is_empty() {
+            return Ok(());
+        }
+        let num_dependencies = dependency_graph.call_counts.len();
+        let mut doc_batch_builder =
+            DocBatchBuilder::new(OTEL_DEPENDENCIES_INDEX_ID.to_string()).json_writer();
+
+        for ((parent, child), call_count) in &dependency_graph.call_counts {
+            let summary_doc = DependencySummaryDoc {
+                timestamp: time_range.start,
+                parent,
+                child,
+                call_count: *call_count,
+            };
+            doc_batch_builder.ingest_doc(summary_doc)?;
+        }
+        // The summary index is the record of the windows already summarized, so the request waits
+        // for the documents to be searchable.
+        let ingest_request = IngestRequest {
+            doc_batches: vec![doc_batch_builder.build()],
+            commit: CommitType::WaitFor.into(),
+        };
+        ingest_service.ingest(ingest_request).await?;
+        info!(
+            num_dependencies=%num_dependencies,
+            start_timestamp=%time_range.start,
+            "stored dependency graph summary"
+        );
+        Ok(())
+    }
+
+    /// Returns the start of the last time window stored into the summary index, if any.
+    async fn last_summarized_window_start(&self) -> JaegerResult<Option<i64>> {
+        let query_ast = serde_json::to_string(&QueryAst::MatchAll)
+            .map_err(|err| Status::internal(err.to_string()))?;
+        let search_request = SearchRequest {
+            index_id_patterns: vec![OTEL_DEPENDENCIES_INDEX_ID.to_string()],
+            query_ast,
+            max_hits: 1,
+            sort_fields: vec![SortField {
+                field_name: "timestamp".to_string(),
+                sort_order: SortOrder::Desc.into(),
+            }],
+            count_hits: CountHits::Underestimate.into(),
+            ..Default::default()
+        };
+        let search_response = self.search_service.root_search(search_request).await?;
+
+        let Some(hit) = search_response.hits.first() else {
+            return Ok(None);
+        };
+        let summary_doc: JsonValue = json_deserialize(&hit.json, "dependencies summary")?;
+        Ok(summary_doc["timestamp"].as_i64())
+    }
+}
+
+/// Returns the start of the first time window the summary task computes the graph of: the window
+/// following the last one summarized, unless it is older than the catch-up period, or the current
+/// window if none was summarized yet.
+fn first_window_start(last_window_start_opt: Option<i64>, now: i64, interval_secs: i64) -> i64 {
+    let current_window_start = now - now.rem_euclid(interval_secs);
+
+    let Some(last_window_start) = last_window_start_opt else {
+        return current_window_start;
+    };
+    let catch_up_start = now - DEPENDENCIES_SUMMARY_MAX_CATCH_UP_SECS;
+    let oldest_window_start = catch_up_start - catch_up_start.rem_euclid(interval_secs);
+    (last_window_start + interval_secs).clamp(oldest_window_start, current_window_start)
+}
+
+/// Computes the dependency graph of the spans of each time window, as wide as the configured
+/// interval, once it has elapsed, and stores it into the dependencies summary index.
+///
+/// The task is meant to run on a single node of the cluster. When it starts, it resumes from the
+/// last window stored into the summary index, so that the windows missed while it was not running
+/// are summarized as well. A window that fails to be summarized is retried with backoff, and the
+/// task only moves on to the next window once it succeeds.
+pub async fn dependencies_summary_task(
+    jaeger_config: JaegerConfig,
+    search_service: Arc<dyn SearchService>,
+    mut ingest_service: IngestServiceClient,
+) {
+    let interval_secs = jaeger_config.dependencies_summary_interval().as_secs() as i64;
+    let jaeger_service = JaegerService::new(jaeger_config, search_service);
+
+    let last_window_start_opt = match jaeger_service.last_summarized_window_start().await {
+        Ok(last_window_start_opt) => last_window_start_opt,
+        Err(error) => {
+            warn!(error=?error, "failed to read last dependency graph summary");
+            None
+        }
+    };
+    let now = OffsetDateTime::now_utc().unix_timestamp();
+    let mut window_start = first_window_start(last_window_start_opt, now, interval_secs);
+
+    loop {
+        let window_end = window_start + interval_secs;
+        let wait_secs = window_end + DEPENDENCIES_SUMMARY_DELAY_SECS
+            - OffsetDateTime::now_utc().unix_timestamp();
+
+        if wait_secs > 0 {
+            tokio::time::sleep(Duration::from_secs(wait_secs as u64)).await;
+        }
+        let mut num_attempts = 0;
+
+        while let Err(error) = jaeger_service
+            .summarize_dependencies(window_start..window_end, &mut ingest_service)
+            .await
+        {
+            // Past a few attempts, the delay is capped anyway.
+            num_attempts = (num_attempts + 1).min(16);
+            let delay = DEPENDENCIES_SUMMARY_RETRY_PARAMS.compute_delay(num_attempts);
+            warn!(
+                error=?error,
+                start_timestamp=%window_start,
+                "failed to summarize dependency graph, retrying in {delay:?}"
+            );
+            tokio::time::sleep(delay).await;
+        }
+        window_start = window_end;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU64;
+
+    use prost_types::Timestamp as WellKnownTimestamp;
+    use quickwit_proto::search::{Hit, PartialHit, SearchResponse};
+    use quickwit_search::MockSearchService;
+
+    use super::*;
+
+    fn span_json(
+        trace_id: u8,
+        span_id: u8,
+        parent_span_id_opt: Option<u8>,
+        service: &str,
+    ) -> String {
+        let mut span = json!({
+            "trace_id": TraceId::new([trace_id; 16]),
+            "span_id": SpanId::new([span_id; 8]),
+            "service_name": service,
+        });
+        if let Some(parent_span_id) = parent_span_id_opt {
+            span["parent_span_id"] = json!(SpanId::new([parent_span_id; 8]));
+        }
+        span.to_string()
+    }
+
+    #[test]
+    fn test_dependencies_index_config_is_valid() {
+        let index_config = dependencies_index_config(&Uri::for_test("ram:///indexes")).unwrap();
+        assert_eq!(index_config.index_id, OTEL_DEPENDENCIES_INDEX_ID);
+    }
+
+    #[test]
+    fn test_dependency_graph_builder() {
+        let spans: Vec<SpanEdge> = [
+            // The child span is added before its parent span.
+            span_json(1, 2, Some(1), "api"),
+            span_json(1, 1, None, "frontend"),
+            span_json(1, 3, Some(2), "api"),
+            span_json(1, 4, Some(3), "db"),
+            span_json(1, 5, Some(1), "api"),
+            // The parent span is not part of the set.
+            span_json(2, 1, Some(9), "db"),
+            // Same span IDs, but another trace.
+            span_json(3, 4, Some(3), "cache"),
+        ]
+        .iter()
+        .map(|span| serde_json::from_str(span).unwrap())
+        .collect();
+
+        let mut dependency_graph_builder = DependencyGraphBuilder::default();
+
+        for span in spans {
+            dependency_graph_builder.add_span(span);
+        }
+        let dependency_links = dependency_graph_builder.build().into_dependency_links();
+        assert_eq!(
+            dependency_links,
+            [
+                DependencyLink {
+                    parent: "api".to_string(),
+                    child: "db".to_string(),
+                    call_count: 1,
+                    source: String::new(),
+                },
+                DependencyLink {
+                    parent: "frontend".to_string(),
+                    child: "api".to_string(),
+                    call_count: 2,
+                    source: String::new(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_first_window_start() {
+        let interval_secs = 600;
+        let now = 1_700_000_123;
+        let current_window_start = 1_699_999_800;
+        assert_eq!(
+            first_window_start(None, now, interval_secs),
+            current_window_start
+        );
+        assert_eq!(
+            first_window_start(Some(current_window_start - 3 * 600), now, interval_secs),
+            current_window_start - 2 * 600
+        );
+        assert_eq!(
+            first_window_start(Some(current_window_start - 600), now, interval_secs),
+            current_window_start
+        );
+        // The windows older than the catch-up period are skipped.
+        let oldest_window_start = first_window_start(Some(0), now, interval_secs);
+        assert_eq!(oldest_window_start.rem_euclid(interval_secs), 0);
+        assert!(oldest_window_start <= now - DEPENDENCIES_SUMMARY_MAX_CATCH_UP_SECS);
+        assert!(oldest_window_start > now - DEPENDENCIES_SUMMARY_MAX_CATCH_UP_SECS - interval_secs);
+    }
+
+    #[test]
+    fn test_dependency_graph_from_summary_aggregation() {
+        let aggregation = json!({
+            "parent": {
+                "buckets": [
+                    {
+                        "key": "api",
+                        "doc_count": 2,
+                        "child": {
+                            "buckets": [
+                                {"key": "cache", "doc_count": 1, "call_count": {"value": 0.0}},
+                                {"key": "db", "doc_count": 2, "call_count": {"value": 42.0}},
+                            ]
+                        }
+                    },
+                ]
+            }
+        });
+        let dependency_graph = DependencyGraph::from_summary_aggregation(&aggregation);
+        assert_eq!(
+            dependency_graph.call_counts,
+            BTreeMap::from([(("api".to_string(), "db".to_string()), 42)])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_dependencies() {
+        let mut mock_search_service = MockSearchService::new();
+        mock_search_service
+            .expect_root_search()
+            .withf(|search_request| {
+                search_request.index_id_patterns == [OTEL_TRACES_INDEX_ID]
+                    && search_request.start_timestamp == Some(1_000)
+                    && search_request.end_timestamp == Some(2_001)
+                    && search_request.aggregation_request.is_none()
+                    && search_request.fetch_fields == SPAN_EDGE_FIELDS
+            })
+            .return_once(|_| {
+                Ok(SearchResponse {
+                    num_hits: 2,
+                    hits: vec![
+                        Hit {
+                            json: span_json(1, 1, None, "frontend"),
+                            ..Default::default()
+                        },
+                        Hit {
+                            json: span_json(1, 2, Some(1), "api"),
+                            ..Default::default()
+                        },
+                    ],
+                    ..Default::default()
+                })
+            });
+        let jaeger_service =
+            JaegerService::new(JaegerConfig::default(), Arc::new(mock_search_service));
+        let request = GetDependenciesRequest {
+            start_time: Some(WellKnownTimestamp {
+                seconds: 1_000,
+                nanos: 0,
+            }),
+            end_time: Some(WellKnownTimestamp {
+                seconds: 2_000,
+                nanos: 0,
+            }),
+        };
+        let response = jaeger_service
+            .get_dependencies_inner(request)
+            .await
+            .unwrap();
+        assert_eq!(
+            response.dependencies,
+            [DependencyLink {
+                parent: "frontend".to_string(),
+                child: "api".to_string(),
+                call_count: 1,
+                source: String::new(),
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_dependencies_pages_through_spans() {
+        let mut mock_search_service = MockSearchService::new();
+        let last_hit_of_first_page = PartialHit {
+            split_id: "split-1".to_string(),
+            doc_id: 2,
+            ..Default::default()
+        };
+        let expected_search_after = last_hit_of_first_page.clone();
+        mock_search_service
+            .expect_root_search()
+            .withf(|search_request| {
+                search_request.max_hits == 2
+                    && search_request.search_after.is_none()
+                    && search_request.sort_fields[0].field_name == "span_start_timestamp_nanos"
+            })
+            .times(1)
+            .return_once(move |_| {
+                Ok(SearchResponse {
+                    num_hits: 3,
+                    hits: vec![
+                        Hit {
+                            json: span_json(1, 1, None, "frontend"),
+                            ..Default::default()
+                        },
+                        Hit {
+                            json: span_json(1, 2, Some(1), "api"),
+                            partial_hit: Some(last_hit_of_first_page),
+                            ..Default::default()
+                        },
+                    ],
+                    ..Default::default()
+                })
+            });
+        mock_search_service
+            .expect_root_search()
+            .withf(move |search_request| {
+                search_request.search_after.as_ref() == Some(&expected_search_after)
+            })
+            .times(1)
+            .return_once(|_| {
+                Ok(SearchResponse {
+                    num_hits: 3,
+                    hits: vec![Hit {
+                        json: span_json(1, 3, Some(2), "db"),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                })
+            });
+        let jaeger_config = JaegerConfig {
+            max_fetch_spans: NonZeroU64::new(2).unwrap(),
+            ..Default::default()
+        };
+        let jaeger_service = JaegerService::new(jaeger_config, Arc::new(mock_search_service));
+        let request = GetDependenciesRequest {
+            start_time: Some(WellKnownTimestamp {
+                seconds: 1_000,
+                nanos: 0,
+            }),
+            end_time: Some(WellKnownTimestamp {
+                seconds: 2_000,
+                nanos: 0,
+            }),
+        };
+        let response = jaeger_service
+            .get_dependencies_inner(request)
+            .await
+            .unwrap();
+        assert_eq!(
+            response.dependencies,
+            [
+                DependencyLink {
+                    parent: "api".to_string(),
+                    child: "db".to_string(),
+                    call_count: 1,
+                    source: String::new(),
+                },
+                DependencyLink {
+                    parent: "frontend".to_string(),
+                    child: "api".to_string(),
+                    call_count: 1,
+                    source: String::new(),
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_last_summarized_window_start() {
+        let mut mock_search_service = MockSearchService::new();
+        mock_search_service
+            .expect_root_search()
+            .withf(|search_request| {
+                search_request.index_id_patterns == [OTEL_DEPENDENCIES_INDEX_ID]
+                    && search_request.max_hits == 1
+                    && search_request.sort_fields[0].field_name == "timestamp"
+            })
+            .return_once(|_| {
+                Ok(SearchResponse {
+                    num_hits: 1,
+                    hits: vec![Hit {
+                        json: json!({
+                            "timestamp": 1_700_000_400,
+                            "parent": "api",
+                            "child": "db",
+                            "call_count": 3,
+                        })
+                        .to_string(),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                })
+            });
+        let jaeger_service =
+            JaegerService::new(JaegerConfig::default(), Arc::new(mock_search_service));
+        let last_window_start_opt = jaeger_service.last_summarized_window_start().await.unwrap();
+        assert_eq!(last_window_start_opt, Some(1_700_000_400));
+    }
+}
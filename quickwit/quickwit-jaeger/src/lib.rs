@@ -36,11 +36,12 @@ use quickwit_proto::jaeger::api_v2::{
     KeyValue as JaegerKeyValue, Log as JaegerLog, Process as JaegerProcess, Span as JaegerSpan,
     SpanRef as JaegerSpanRef, SpanRefType as JaegerSpanRefType, ValueType,
 };
+use quickwit_proto::jaeger::storage::v1::dependencies_reader_plugin_server::DependenciesReaderPlugin;
 use quickwit_proto::jaeger::storage::v1::span_reader_plugin_server::SpanReaderPlugin;
 use quickwit_proto::jaeger::storage::v1::{
-    FindTraceIDsRequest, FindTraceIDsResponse, FindTracesRequest, GetOperationsRequest,
-    GetOperationsResponse, GetServicesRequest, GetServicesResponse, GetTraceRequest, Operation,
-    SpansResponseChunk, TraceQueryParameters,
+    FindTraceIDsRequest, FindTraceIDsResponse, FindTracesRequest, GetDependenciesRequest,
+    GetDependenciesResponse, GetOperationsRequest, GetOperationsResponse, GetServicesRequest,
+    GetServicesResponse, GetTraceRequest, Operation, SpansResponseChunk, TraceQueryParameters,
 };
 use quickwit_proto::opentelemetry::proto::trace::v1::status::StatusCode as OtlpStatusCode;
 use quickwit_proto::search::{CountHits, ListTermsRequest, SearchRequest};
@@ -59,10 +60,15 @@ use tracing::{debug, error, instrument, warn, Span as RuntimeSpan};
 
 use crate::metrics::JAEGER_SERVICE_METRICS;
 
+mod dependencies;
 #[cfg(test)]
 mod integration_tests;
 mod metrics;
 
+pub use dependencies::{
+    dependencies_index_config, dependencies_summary_task, OTEL_DEPENDENCIES_INDEX_ID,
+};
+
 // OpenTelemetry to Jaeger Transformation
 // <https://opentelemetry.io/docs/reference/specification/trace/sdk_exporters/jaeger/>
 
@@ -77,6 +83,7 @@ pub struct JaegerService {
     lookback_period_secs: i64,
     max_trace_duration_secs: i64,
     max_fetch_spans: u64,
+    enable_dependencies_summary: bool,
 }
 
 impl JaegerService {
@@ -86,6 +93,7 @@ impl JaegerService {
             lookback_period_secs: config.lookback_period().as_secs() as i64,
             max_trace_duration_secs: config.max_trace_duration().as_secs() as i64,
             max_fetch_spans: config.max_fetch_spans.get(),
+            enable_dependencies_summary: config.enable_dependencies_summary,
         }
     }
 
@@ -504,6 +512,19 @@ impl SpanReaderPlugin for JaegerService {
     }
 }
 
+#[async_trait]
+impl DependenciesReaderPlugin for JaegerService {
+    async fn get_dependencies(
+        &self,
+        request: Request<GetDependenciesRequest>,
+    ) -> Result<Response<GetDependenciesResponse>, Status> {
+        metrics!(
+            self.get_dependencies_inner(request.into_inner()).await,
+            [get_dependencies, OTEL_TRACES_INDEX_ID]
+        );
+    }
+}
+
 fn extract_term(term_bytes: &[u8]) -> String {
     tantivy::Term::wrap(term_bytes)
         .value()
@@ -321,6 +321,9 @@ impl DeleteTaskPlanner {
                 IndexMetasForLeafSearch {
                     doc_mapper_str: doc_mapper_str.to_string(),
                     index_uri,
+                    query_ast_resolved: None,
+                    start_timestamp: None,
+                    end_timestamp: None,
                 },
             );
             let leaf_search_request = jobs_to_leaf_requests(
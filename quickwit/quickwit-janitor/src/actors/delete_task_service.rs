@@ -59,6 +59,9 @@ pub struct DeleteTaskService {
     storage_resolver: StorageResolver,
     delete_service_task_dir: PathBuf,
     pipeline_handles_by_index_uid: HashMap<IndexUid, ActorHandle<DeleteTaskPipeline>>,
+    // Index configs the running pipelines were spawned with. Used to restart a pipeline when the
+    // config of its index is updated.
+    index_config_by_index_uid: HashMap<IndexUid, IndexConfig>,
     max_concurrent_split_uploads: usize,
     event_broker: EventBroker,
 }
@@ -81,6 +84,7 @@ impl DeleteTaskService {
             storage_resolver,
             delete_service_task_dir,
             pipeline_handles_by_index_uid: Default::default(),
+            index_config_by_index_uid: Default::default(),
             max_concurrent_split_uploads,
             event_broker,
         })
@@ -139,11 +143,32 @@ impl DeleteTaskService {
                 .pipeline_handles_by_index_uid
                 .remove(deleted_index_uid)
                 .expect("Handle must be present.");
+            self.index_config_by_index_uid.remove(deleted_index_uid);
             // Kill the pipeline, this avoids to wait a long time for a delete operation to finish.
             pipeline_handle.kill().await;
         }
 
+        // Restart pipelines on updated indexes so that they pick up the new index config.
+        for index_uid in index_uids.intersection(&pipeline_index_uids) {
+            let index_config = &index_config_by_index_id[index_uid];
+            if self.index_config_by_index_uid.get(index_uid) == Some(index_config) {
+                continue;
+            }
+            info!(
+                index_id = index_uid.index_id(),
+                "Restart delete task pipeline on updated index."
+            );
+            let pipeline_handle = self
+                .pipeline_handles_by_index_uid
+                .remove(index_uid)
+                .expect("Handle must be present.");
+            self.index_config_by_index_uid.remove(index_uid);
+            pipeline_handle.kill().await;
+        }
+
         // Start new pipelines and add them to the handles hashmap.
+        let pipeline_index_uids: HashSet<IndexUid> =
+            self.pipeline_handles_by_index_uid.keys().cloned().collect();
         for index_uid in index_uids.difference(&pipeline_index_uids) {
             let index_config = index_config_by_index_id
                 .remove(index_uid)
@@ -173,6 +198,8 @@ impl DeleteTaskService {
             .index_metadata(index_metadata_request)
             .await?
             .deserialize_index_metadata()?;
+        self.index_config_by_index_uid
+            .insert(index_metadata.index_uid.clone(), index_config);
         let pipeline = DeleteTaskPipeline::new(
             index_metadata.index_uid.clone(),
             self.metastore.clone(),
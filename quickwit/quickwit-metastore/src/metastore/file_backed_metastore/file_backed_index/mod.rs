@@ -30,7 +30,7 @@ use std::ops::Bound;
 
 use itertools::Itertools;
 use quickwit_common::PrettySample;
-use quickwit_config::{SourceConfig, INGEST_SOURCE_ID};
+use quickwit_config::{IndexConfig, SourceConfig, INGEST_SOURCE_ID};
 use quickwit_proto::metastore::{
     AcquireShardsSubrequest, AcquireShardsSubresponse, DeleteQuery, DeleteShardsSubrequest,
     DeleteTask, EntityKind, ListShardsSubrequest, ListShardsSubresponse, MetastoreError,
@@ -469,6 +469,14 @@ impl FileBackedIndex {
         Ok(())
     }
 
+    /// Updates the index config. Returns whether a mutation occurred.
+    pub(crate) fn update_index_config(
+        &mut self,
+        index_config: IndexConfig,
+    ) -> MetastoreResult<bool> {
+        self.metadata.update_index_config(index_config)
+    }
+
     /// Adds a source.
     pub(crate) fn add_source(&mut self, source_config: SourceConfig) -> MetastoreResult<()> {
         let index_uid = self.index_uid().clone();
@@ -46,7 +46,7 @@ use quickwit_proto::metastore::{
     ListStaleSplitsRequest, MarkSplitsForDeletionRequest, MetastoreError, MetastoreResult,
     MetastoreService, OpenShardsRequest, OpenShardsResponse, OpenShardsSubrequest,
     PublishSplitsRequest, ResetSourceCheckpointRequest, StageSplitsRequest, ToggleSourceRequest,
-    UpdateSplitsDeleteOpstampRequest, UpdateSplitsDeleteOpstampResponse,
+    UpdateIndexRequest, UpdateSplitsDeleteOpstampRequest, UpdateSplitsDeleteOpstampResponse,
 };
 use quickwit_proto::types::IndexUid;
 use quickwit_storage::Storage;
@@ -64,7 +64,7 @@ use self::store_operations::{
 use super::{
     AddSourceRequestExt, CreateIndexRequestExt, IndexMetadataResponseExt,
     ListIndexesMetadataResponseExt, ListSplitsRequestExt, ListSplitsResponseExt,
-    PublishSplitsRequestExt, StageSplitsRequestExt,
+    PublishSplitsRequestExt, StageSplitsRequestExt, UpdateIndexRequestExt,
 };
 use crate::checkpoint::IndexCheckpointDelta;
 use crate::{IndexMetadata, ListSplitsQuery, MetastoreServiceExt, SplitState};
@@ -404,6 +404,29 @@ impl MetastoreService for FileBackedMetastore {
         Ok(response)
     }
 
+    async fn update_index(
+        &mut self,
+        request: UpdateIndexRequest,
+    ) -> MetastoreResult<IndexMetadataResponse> {
+        let index_config = request.deserialize_index_config()?;
+        let index_uid: IndexUid = request.index_uid.into();
+
+        let index_metadata = self
+            .mutate(index_uid, |index| {
+                let mutation_occurred = index.update_index_config(index_config)?;
+                let index_metadata = index.metadata().clone();
+
+                if mutation_occurred {
+                    Ok(MutationOccurred::Yes(index_metadata))
+                } else {
+                    Ok(MutationOccurred::No(index_metadata))
+                }
+            })
+            .await?;
+        let response = IndexMetadataResponse::try_from_index_metadata(index_metadata)?;
+        Ok(response)
+    }
+
     async fn delete_index(
         &mut self,
         request: DeleteIndexRequest,
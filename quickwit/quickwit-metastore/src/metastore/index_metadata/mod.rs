@@ -99,6 +99,24 @@ impl IndexMetadata {
         &self.index_config().index_uri
     }
 
+    /// Replaces the index config with `index_config`. Returns an error if the new config is not a
+    /// valid update of the current one, and whether the index was modified otherwise.
+    pub(crate) fn update_index_config(
+        &mut self,
+        index_config: IndexConfig,
+    ) -> MetastoreResult<bool> {
+        self.index_config
+            .validate_update(&index_config)
+            .map_err(|error| MetastoreError::InvalidArgument {
+                message: format!("failed to update index `{}`: {error:#}", self.index_id()),
+            })?;
+        if self.index_config == index_config {
+            return Ok(false);
+        }
+        self.index_config = index_config;
+        Ok(true)
+    }
+
     /// Adds a source to the index. Returns an error if the source_id already exists.
     pub fn add_source(&mut self, source_config: SourceConfig) -> MetastoreResult<()> {
         match self.sources.entry(source_config.source_id.clone()) {
@@ -39,7 +39,7 @@ use quickwit_proto::metastore::{
     serde_utils, AddSourceRequest, CreateIndexRequest, DeleteTask, IndexMetadataRequest,
     IndexMetadataResponse, ListIndexesMetadataResponse, ListSplitsRequest, ListSplitsResponse,
     MetastoreError, MetastoreResult, MetastoreService, MetastoreServiceClient,
-    PublishSplitsRequest, StageSplitsRequest,
+    PublishSplitsRequest, StageSplitsRequest, UpdateIndexRequest,
 };
 use quickwit_proto::types::{IndexUid, SplitId};
 use time::OffsetDateTime;
@@ -96,6 +96,37 @@ impl CreateIndexRequestExt for CreateIndexRequest {
     }
 }
 
+/// Helper trait to build a [`UpdateIndexRequest`] and deserialize its payload.
+pub trait UpdateIndexRequestExt {
+    /// Creates a new [`UpdateIndexRequest`] from an [`IndexConfig`].
+    fn try_from_index_config(
+        index_uid: impl Into<IndexUid>,
+        index_config: IndexConfig,
+    ) -> MetastoreResult<UpdateIndexRequest>;
+
+    /// Deserializes the `index_config_json` field of a [`UpdateIndexRequest`] into an
+    /// [`IndexConfig`].
+    fn deserialize_index_config(&self) -> MetastoreResult<IndexConfig>;
+}
+
+impl UpdateIndexRequestExt for UpdateIndexRequest {
+    fn try_from_index_config(
+        index_uid: impl Into<IndexUid>,
+        index_config: IndexConfig,
+    ) -> MetastoreResult<UpdateIndexRequest> {
+        let index_config_json = serde_utils::to_json_str(&index_config)?;
+        let request = Self {
+            index_uid: index_uid.into().into(),
+            index_config_json,
+        };
+        Ok(request)
+    }
+
+    fn deserialize_index_config(&self) -> MetastoreResult<IndexConfig> {
+        serde_utils::from_json_str(&self.index_config_json)
+    }
+}
+
 /// Helper trait to build a [`IndexMetadataResponse`] and deserialize its payload.
 pub trait IndexMetadataResponseExt {
     /// Creates a new [`IndexMetadataResponse`] from an [`IndexMetadata`].
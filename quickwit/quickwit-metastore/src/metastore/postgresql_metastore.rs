@@ -41,7 +41,8 @@ use quickwit_proto::metastore::{
     ListSplitsRequest, ListSplitsResponse, ListStaleSplitsRequest, MarkSplitsForDeletionRequest,
     MetastoreError, MetastoreResult, MetastoreService, MetastoreServiceClient, OpenShardsRequest,
     OpenShardsResponse, PublishSplitsRequest, ResetSourceCheckpointRequest, StageSplitsRequest,
-    ToggleSourceRequest, UpdateSplitsDeleteOpstampRequest, UpdateSplitsDeleteOpstampResponse,
+    ToggleSourceRequest, UpdateIndexRequest, UpdateSplitsDeleteOpstampRequest,
+    UpdateSplitsDeleteOpstampResponse,
 };
 use quickwit_proto::types::IndexUid;
 use sea_query::{
@@ -62,7 +63,7 @@ use crate::{
     AddSourceRequestExt, CreateIndexRequestExt, IndexMetadata, IndexMetadataResponseExt,
     ListIndexesMetadataResponseExt, ListSplitsQuery, ListSplitsRequestExt, ListSplitsResponseExt,
     MetastoreFactory, MetastoreResolverError, MetastoreServiceExt, Split, SplitMaturity,
-    SplitMetadata, SplitState, StageSplitsRequestExt,
+    SplitMetadata, SplitState, StageSplitsRequestExt, UpdateIndexRequestExt,
 };
 
 static MIGRATOR: Migrator = sqlx::migrate!("migrations/postgresql");
@@ -529,6 +530,24 @@ impl MetastoreService for PostgresqlMetastore {
         })
     }
 
+    #[instrument(skip_all, fields(index_id=request.index_uid))]
+    async fn update_index(
+        &mut self,
+        request: UpdateIndexRequest,
+    ) -> MetastoreResult<IndexMetadataResponse> {
+        let index_config = request.deserialize_index_config()?;
+        let index_uid: IndexUid = request.index_uid.into();
+        let index_metadata = run_with_tx!(self.connection_pool, tx, {
+            mutate_index_metadata(tx, index_uid.clone(), |index_metadata| {
+                index_metadata.update_index_config(index_config)
+            })
+            .await?;
+            index_metadata(tx, index_uid.index_id()).await
+        })?;
+        let response = IndexMetadataResponse::try_from_index_metadata(index_metadata)?;
+        Ok(response)
+    }
+
     #[instrument(skip_all, fields(index_id=request.index_uid))]
     async fn delete_index(
         &mut self,
@@ -25,7 +25,9 @@ use async_trait::async_trait;
 use futures::future::try_join_all;
 use itertools::Itertools;
 use quickwit_common::rand::append_random_suffix;
-use quickwit_config::{IndexConfig, SourceConfig, SourceInputFormat, SourceParams};
+use quickwit_config::{
+    IndexConfig, RetentionPolicy, SourceConfig, SourceInputFormat, SourceParams,
+};
 use quickwit_doc_mapper::tag_pruning::{no_tag, tag, TagFilterAst};
 use quickwit_proto::metastore::{
     AddSourceRequest, CreateIndexRequest, DeleteIndexRequest, DeleteQuery, DeleteSourceRequest,
@@ -33,7 +35,7 @@ use quickwit_proto::metastore::{
     ListDeleteTasksRequest, ListIndexesMetadataRequest, ListSplitsRequest, ListStaleSplitsRequest,
     MarkSplitsForDeletionRequest, MetastoreError, MetastoreService, PublishSplitsRequest,
     ResetSourceCheckpointRequest, SourceType, StageSplitsRequest, ToggleSourceRequest,
-    UpdateSplitsDeleteOpstampRequest,
+    UpdateIndexRequest, UpdateSplitsDeleteOpstampRequest,
 };
 use quickwit_proto::types::{IndexUid, Position};
 use quickwit_query::query_ast::qast_json_helper;
@@ -50,6 +52,7 @@ use crate::{
     AddSourceRequestExt, CreateIndexRequestExt, IndexMetadataResponseExt,
     ListIndexesMetadataResponseExt, ListSplitsQuery, ListSplitsRequestExt, ListSplitsResponseExt,
     MetastoreServiceExt, Split, SplitMaturity, SplitMetadata, SplitState, StageSplitsRequestExt,
+    UpdateIndexRequestExt,
 };
 
 #[async_trait]
@@ -449,6 +452,87 @@ pub async fn test_metastore_delete_index<
     cleanup_index(&mut metastore, index_uid).await;
 }
 
+pub async fn test_metastore_update_index<MetastoreToTest: MetastoreService + DefaultForTest>() {
+    let mut metastore = MetastoreToTest::default_for_test().await;
+
+    let index_id = append_random_suffix("test-update-index");
+    let index_uri = format!("ram:///indexes/{index_id}");
+    let index_config = IndexConfig::for_test(&index_id, &index_uri);
+
+    let index_uid_not_existing = IndexUid::new_with_random_ulid(&index_id);
+    let update_index_request =
+        UpdateIndexRequest::try_from_index_config(index_uid_not_existing, index_config.clone())
+            .unwrap();
+    let error = metastore
+        .update_index(update_index_request)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        MetastoreError::NotFound(EntityKind::Index { .. })
+    ));
+
+    let create_index_request =
+        CreateIndexRequest::try_from_index_config(index_config.clone()).unwrap();
+    let index_uid: IndexUid = metastore
+        .create_index(create_index_request)
+        .await
+        .unwrap()
+        .index_uid
+        .into();
+
+    let mut new_index_config = index_config.clone();
+    new_index_config.indexing_settings.commit_timeout_secs = 5;
+    new_index_config.search_settings.default_search_fields = vec!["owner".to_string()];
+    new_index_config.retention_policy = Some(RetentionPolicy::new(
+        "1 day".to_string(),
+        "hourly".to_string(),
+    ));
+    new_index_config
+        .doc_mapping
+        .field_mappings
+        .push(serde_json::from_str(r#"{"name": "severity", "type": "text"}"#).unwrap());
+
+    let update_index_request =
+        UpdateIndexRequest::try_from_index_config(index_uid.clone(), new_index_config.clone())
+            .unwrap();
+    let index_metadata = metastore
+        .update_index(update_index_request)
+        .await
+        .unwrap()
+        .deserialize_index_metadata()
+        .unwrap();
+    assert_eq!(index_metadata.index_uid, index_uid);
+    assert_eq!(index_metadata.index_config, new_index_config);
+
+    let index_metadata = metastore
+        .index_metadata(IndexMetadataRequest::for_index_id(index_id.to_string()))
+        .await
+        .unwrap()
+        .deserialize_index_metadata()
+        .unwrap();
+    assert_eq!(index_metadata.index_config, new_index_config);
+
+    // Removing a field from the doc mapping is not allowed.
+    let update_index_request =
+        UpdateIndexRequest::try_from_index_config(index_uid.clone(), index_config).unwrap();
+    let error = metastore
+        .update_index(update_index_request)
+        .await
+        .unwrap_err();
+    assert!(matches!(error, MetastoreError::InvalidArgument { .. }));
+
+    let index_metadata = metastore
+        .index_metadata(IndexMetadataRequest::for_index_id(index_id.to_string()))
+        .await
+        .unwrap()
+        .deserialize_index_metadata()
+        .unwrap();
+    assert_eq!(index_metadata.index_config, new_index_config);
+
+    cleanup_index(&mut metastore, index_uid).await;
+}
+
 pub async fn test_metastore_add_source<MetastoreToTest: MetastoreService + DefaultForTest>() {
     let mut metastore = MetastoreToTest::default_for_test().await;
 
@@ -475,6 +559,8 @@ pub async fn test_metastore_add_source<MetastoreToTest: MetastoreService + Defau
         source_params: SourceParams::void(),
         transform_config: None,
         input_format: SourceInputFormat::Json,
+        csv_columns_opt: None,
+        dead_letter_opt: None,
     };
 
     assert_eq!(
@@ -577,6 +663,8 @@ pub async fn test_metastore_toggle_source<MetastoreToTest: MetastoreService + De
         source_params: SourceParams::void(),
         transform_config: None,
         input_format: SourceInputFormat::Json,
+        csv_columns_opt: None,
+        dead_letter_opt: None,
     };
     let add_source_request =
         AddSourceRequest::try_from_source_config(index_uid.clone(), source.clone()).unwrap();
@@ -644,6 +732,8 @@ pub async fn test_metastore_delete_source<MetastoreToTest: MetastoreService + De
         source_params: SourceParams::void(),
         transform_config: None,
         input_format: SourceInputFormat::Json,
+        csv_columns_opt: None,
+        dead_letter_opt: None,
     };
 
     let index_config = IndexConfig::for_test(&index_id, index_uri.as_str());
@@ -768,6 +858,8 @@ pub async fn test_metastore_reset_checkpoint<MetastoreToTest: MetastoreService +
             source_params: SourceParams::void(),
             transform_config: None,
             input_format: SourceInputFormat::Json,
+            csv_columns_opt: None,
+            dead_letter_opt: None,
         };
         metastore
             .add_source(
@@ -3905,6 +3997,12 @@ macro_rules! metastore_test_suite {
                 $crate::tests::test_metastore_delete_index::<$metastore_type>().await;
             }
 
+            #[tokio::test]
+            async fn test_metastore_update_index() {
+                let _ = tracing_subscriber::fmt::try_init();
+                $crate::tests::test_metastore_update_index::<$metastore_type>().await;
+            }
+
             // Split API tests
             //
             //  - stage_splits
@@ -0,0 +1,441 @@
+// Copyright (C) 2023 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+
+//! Decoding of the OTLP/HTTP JSON export requests.
+//!
+//! OTLP/HTTP JSON payloads follow the proto3 JSON mapping, implemented by the serde impls that
+//! `pbjson` generates for the OpenTelemetry protos, with one exception: trace and span IDs are
+//! encoded in hex instead of base64. They are converted to base64 before decoding the payload.
+//!
+//! <https://opentelemetry.io/docs/specs/otlp/#json-protobuf-encoding>
+
+use base64::prelude::{Engine, BASE64_STANDARD};
+use serde::de::DeserializeOwned;
+use serde_json::Value as JsonValue;
+
+/// Names of the fields holding trace and span IDs, in lowerCamelCase and with their original
+/// name. Attribute keys are held by `key` fields, so no other field can have these names.
+const HEX_ID_FIELD_NAMES: [&str; 6] = [
+    "traceId",
+    "trace_id",
+    "spanId",
+    "span_id",
+    "parentSpanId",
+    "parent_span_id",
+];
+
+#[derive(Debug, thiserror::Error)]
+#[error("{0}")]
+pub struct OtlpJsonError(String);
+
+/// Decodes an OTLP/HTTP JSON export request.
+pub fn decode_otlp_json<T: DeserializeOwned>(payload: &[u8]) -> Result<T, OtlpJsonError> {
+    let mut json_value: JsonValue = serde_json::from_slice(payload)
+        .map_err(|error| OtlpJsonError(format!("failed to parse JSON payload: {error}")))?;
+    prepare_otlp_json(&mut json_value)?;
+    serde_json::from_value(json_value)
+        .map_err(|error| OtlpJsonError(format!("failed to decode OTLP request: {error}")))
+}
+
+/// Converts the hex-encoded trace and span IDs to base64 and removes the null fields, which the
+/// proto3 JSON mapping treats as absent.
+fn prepare_otlp_json(json_value: &mut JsonValue) -> Result<(), OtlpJsonError> {
+    match json_value {
+        JsonValue::Object(json_map) => {
+            json_map.retain(|_, field_value| !field_value.is_null());
+
+            for (field_name, field_value) in json_map.iter_mut() {
+                if HEX_ID_FIELD_NAMES.contains(&field_name.as_str()) {
+                    hex_id_to_base64(field_name, field_value)?;
+                } else {
+                    prepare_otlp_json(field_value)?;
+                }
+            }
+        }
+        JsonValue::Array(json_values) => {
+            for json_value in json_values {
+                prepare_otlp_json(json_value)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn hex_id_to_base64(field_name: &str, json_value: &mut JsonValue) -> Result<(), OtlpJsonError> {
+    let id_opt = json_value
+        .as_str()
+        .and_then(|hex_id| hex::decode(hex_id).ok());
+    let Some(id) = id_opt else {
+        return Err(OtlpJsonError(format!(
+            "field `{field_name}` must be a hex-encoded ID"
+        )));
+    };
+    *json_value = JsonValue::String(BASE64_STANDARD.encode(id));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use quickwit_proto::opentelemetry::proto::collector::logs::v1::{
+        ExportLogsPartialSuccess, ExportLogsServiceRequest, ExportLogsServiceResponse,
+    };
+    use quickwit_proto::opentelemetry::proto::collector::metrics::v1::ExportMetricsServiceRequest;
+    use quickwit_proto::opentelemetry::proto::collector::trace::v1::ExportTraceServiceRequest;
+    use quickwit_proto::opentelemetry::proto::common::v1::any_value::Value as OtlpValue;
+    use quickwit_proto::opentelemetry::proto::logs::v1::SeverityNumber;
+    use quickwit_proto::opentelemetry::proto::metrics::v1::metric::Data as MetricData;
+    use quickwit_proto::opentelemetry::proto::metrics::v1::{
+        number_data_point, AggregationTemporality,
+    };
+    use quickwit_proto::opentelemetry::proto::trace::v1::span::SpanKind;
+    use quickwit_proto::opentelemetry::proto::trace::v1::status::StatusCode;
+    use serde_json::json;
+
+    // Examples from <https://github.com/open-telemetry/opentelemetry-proto/tree/main/examples>.
+    const LOGS_EXAMPLE: &str = r#"{
+        "resourceLogs": [
+            {
+                "resource": {
+                    "attributes": [
+                        {"key": "service.name", "value": {"stringValue": "my.service"}}
+                    ]
+                },
+                "scopeLogs": [
+                    {
+                        "scope": {
+                            "name": "my.library",
+                            "version": "1.0.0",
+                            "attributes": [
+                                {"key": "my.scope.attribute", "value": {"stringValue": "some scope attribute"}}
+                            ]
+                        },
+                        "logRecords": [
+                            {
+                                "timeUnixNano": "1544712660300000000",
+                                "observedTimeUnixNano": "1544712660300000000",
+                                "severityNumber": 10,
+                                "severityText": "Information",
+                                "traceId": "5B8EFFF798038103D269B633813FC60C",
+                                "spanId": "EEE19B7EC3C1B174",
+                                "body": {"stringValue": "Example log record"},
+                                "attributes": [
+                                    {"key": "string.attribute", "value": {"stringValue": "some string"}},
+                                    {"key": "boolean.attribute", "value": {"boolValue": true}},
+                                    {"key": "int.attribute", "value": {"intValue": "10"}},
+                                    {"key": "double.attribute", "value": {"doubleValue": 637.704}},
+                                    {"key": "array.attribute", "value": {"arrayValue": {"values": [{"stringValue": "many"}, {"stringValue": "values"}]}}},
+                                    {"key": "map.attribute", "value": {"kvlistValue": {"values": [{"key": "some.map.key", "value": {"stringValue": "some value"}}]}}}
+                                ]
+                            }
+                        ]
+                    }
+                ]
+            }
+        ]
+    }"#;
+
+    const TRACES_EXAMPLE: &str = r#"{
+        "resourceSpans": [
+            {
+                "resource": {
+                    "attributes": [
+                        {"key": "service.name", "value": {"stringValue": "my.service"}}
+                    ]
+                },
+                "scopeSpans": [
+                    {
+                        "scope": {
+                            "name": "my.library",
+                            "version": "1.0.0",
+                            "attributes": [
+                                {"key": "my.scope.attribute", "value": {"stringValue": "some scope attribute"}}
+                            ]
+                        },
+                        "spans": [
+                            {
+                                "traceId": "5B8EFFF798038103D269B633813FC60C",
+                                "spanId": "EEE19B7EC3C1B174",
+                                "parentSpanId": "EEE19B7EC3C1B173",
+                                "name": "I'm a server span",
+                                "startTimeUnixNano": "1544712660000000000",
+                                "endTimeUnixNano": "1544712661000000000",
+                                "kind": 2,
+                                "attributes": [
+                                    {"key": "my.span.attr", "value": {"stringValue": "some value"}}
+                                ]
+                            }
+                        ]
+                    }
+                ]
+            }
+        ]
+    }"#;
+
+    const METRICS_EXAMPLE: &str = r#"{
+        "resourceMetrics": [
+            {
+                "resource": {
+                    "attributes": [
+                        {"key": "service.name", "value": {"stringValue": "my.service"}}
+                    ]
+                },
+                "scopeMetrics": [
+                    {
+                        "scope": {
+                            "name": "my.library",
+                            "version": "1.0.0"
+                        },
+                        "metrics": [
+                            {
+                                "name": "my.counter",
+                                "unit": "1",
+                                "description": "I am a Counter",
+                                "sum": {
+                                    "aggregationTemporality": 1,
+                                    "isMonotonic": true,
+                                    "dataPoints": [
+                                        {
+                                            "asDouble": 5,
+                                            "startTimeUnixNano": "1544712660300000000",
+                                            "timeUnixNano": "1544712660300000000",
+                                            "attributes": [
+                                                {"key": "my.counter.attr", "value": {"stringValue": "some value"}}
+                                            ]
+                                        }
+                                    ]
+                                }
+                            },
+                            {
+                                "name": "my.gauge",
+                                "unit": "1",
+                                "description": "I am a Gauge",
+                                "gauge": {
+                                    "dataPoints": [
+                                        {
+                                            "asDouble": 10,
+                                            "timeUnixNano": "1544712660300000000"
+                                        }
+                                    ]
+                                }
+                            },
+                            {
+                                "name": "my.histogram",
+                                "unit": "1",
+                                "description": "I am a Histogram",
+                                "histogram": {
+                                    "aggregationTemporality": 1,
+                                    "dataPoints": [
+                                        {
+                                            "startTimeUnixNano": "1544712660300000000",
+                                            "timeUnixNano": "1544712660300000000",
+                                            "count": 2,
+                                            "sum": 2,
+                                            "bucketCounts": [1, 1],
+                                            "explicitBounds": [1],
+                                            "min": 0,
+                                            "max": 2
+                                        }
+                                    ]
+                                }
+                            }
+                        ]
+                    }
+                ]
+            }
+        ]
+    }"#;
+
+    #[test]
+    fn test_decode_otlp_json_logs() {
+        let request: ExportLogsServiceRequest = decode_otlp_json(LOGS_EXAMPLE.as_bytes()).unwrap();
+        let resource_logs = &request.resource_logs[0];
+        let resource_attribute = &resource_logs.resource.as_ref().unwrap().attributes[0];
+        assert_eq!(resource_attribute.key, "service.name");
+
+        let scope_logs = &resource_logs.scope_logs[0];
+        assert_eq!(scope_logs.scope.as_ref().unwrap().version, "1.0.0");
+
+        let log_record = &scope_logs.log_records[0];
+        assert_eq!(log_record.time_unix_nano, 1544712660300000000);
+        assert_eq!(log_record.observed_time_unix_nano, 1544712660300000000);
+        assert_eq!(log_record.severity_number, SeverityNumber::Info2 as i32);
+        assert_eq!(log_record.severity_text, "Information");
+        assert_eq!(
+            log_record.trace_id,
+            hex::decode("5b8efff798038103d269b633813fc60c").unwrap()
+        );
+        assert_eq!(log_record.span_id, hex::decode("eee19b7ec3c1b174").unwrap());
+        assert_eq!(
+            log_record.body.as_ref().unwrap().value,
+            Some(OtlpValue::StringValue("Example log record".to_string()))
+        );
+        let attribute_values: Vec<OtlpValue> = log_record
+            .attributes
+            .iter()
+            .map(|attribute| attribute.value.clone().unwrap().value.unwrap())
+            .collect();
+        assert_eq!(
+            attribute_values[..4],
+            [
+                OtlpValue::StringValue("some string".to_string()),
+                OtlpValue::BoolValue(true),
+                OtlpValue::IntValue(10),
+                OtlpValue::DoubleValue(637.704),
+            ]
+        );
+        let OtlpValue::ArrayValue(array_value) = &attribute_values[4] else {
+            panic!("expected array value");
+        };
+        assert_eq!(array_value.values.len(), 2);
+
+        let OtlpValue::KvlistValue(kvlist_value) = &attribute_values[5] else {
+            panic!("expected kvlist value");
+        };
+        assert_eq!(kvlist_value.values[0].key, "some.map.key");
+    }
+
+    #[test]
+    fn test_decode_otlp_json_traces() {
+        let request: ExportTraceServiceRequest =
+            decode_otlp_json(TRACES_EXAMPLE.as_bytes()).unwrap();
+        let span = &request.resource_spans[0].scope_spans[0].spans[0];
+        assert_eq!(
+            span.trace_id,
+            hex::decode("5b8efff798038103d269b633813fc60c").unwrap()
+        );
+        assert_eq!(span.span_id, hex::decode("eee19b7ec3c1b174").unwrap());
+        assert_eq!(
+            span.parent_span_id,
+            hex::decode("eee19b7ec3c1b173").unwrap()
+        );
+        assert_eq!(span.name, "I'm a server span");
+        assert_eq!(span.kind, SpanKind::Server as i32);
+        assert_eq!(span.start_time_unix_nano, 1544712660000000000);
+        assert_eq!(span.end_time_unix_nano, 1544712661000000000);
+        assert_eq!(span.attributes[0].key, "my.span.attr");
+        assert!(span.status.is_none());
+    }
+
+    #[test]
+    fn test_decode_otlp_json_metrics() {
+        let request: ExportMetricsServiceRequest =
+            decode_otlp_json(METRICS_EXAMPLE.as_bytes()).unwrap();
+        let metrics = &request.resource_metrics[0].scope_metrics[0].metrics;
+        assert_eq!(metrics.len(), 3);
+
+        let Some(MetricData::Sum(sum)) = &metrics[0].data else {
+            panic!("expected sum");
+        };
+        assert_eq!(
+            sum.aggregation_temporality,
+            AggregationTemporality::Delta as i32
+        );
+        assert!(sum.is_monotonic);
+        assert_eq!(sum.data_points[0].start_time_unix_nano, 1544712660300000000);
+        assert_eq!(
+            sum.data_points[0].value,
+            Some(number_data_point::Value::AsDouble(5.0))
+        );
+        let Some(MetricData::Gauge(gauge)) = &metrics[1].data else {
+            panic!("expected gauge");
+        };
+        assert_eq!(
+            gauge.data_points[0].value,
+            Some(number_data_point::Value::AsDouble(10.0))
+        );
+        let Some(MetricData::Histogram(histogram)) = &metrics[2].data else {
+            panic!("expected histogram");
+        };
+        let data_point = &histogram.data_points[0];
+        assert_eq!(data_point.count, 2);
+        assert_eq!(data_point.sum, Some(2.0));
+        assert_eq!(data_point.bucket_counts, [1, 1]);
+        assert_eq!(data_point.explicit_bounds, [1.0]);
+        assert_eq!(data_point.min, Some(0.0));
+        assert_eq!(data_point.max, Some(2.0));
+    }
+
+    #[test]
+    fn test_decode_otlp_json_accepts_original_field_names_and_enum_names() {
+        let payload = json!({
+            "resource_spans": [{
+                "scope_spans": [{
+                    "spans": [{
+                        "trace_id": "5b8efff798038103d269b633813fc60c",
+                        "kind": "SPAN_KIND_CLIENT",
+                        "start_time_unix_nano": 1544712660000000000u64,
+                        "status": {"code": "STATUS_CODE_ERROR", "message": null}
+                    }]
+                }]
+            }]
+        });
+        let request: ExportTraceServiceRequest =
+            decode_otlp_json(payload.to_string().as_bytes()).unwrap();
+        let span = &request.resource_spans[0].scope_spans[0].spans[0];
+        assert_eq!(span.trace_id.len(), 16);
+        assert_eq!(span.kind, SpanKind::Client as i32);
+        assert_eq!(span.start_time_unix_nano, 1544712660000000000);
+        assert_eq!(span.status.as_ref().unwrap().code, StatusCode::Error as i32);
+    }
+
+    #[test]
+    fn test_decode_otlp_json_errors() {
+        decode_otlp_json::<ExportTraceServiceRequest>(b"[]").unwrap_err();
+
+        let payload =
+            json!({"resourceSpans": [{"scopeSpans": [{"spans": [{"traceId": "AAECAw=="}]}]}]});
+        let error = decode_otlp_json::<ExportTraceServiceRequest>(payload.to_string().as_bytes())
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "field `traceId` must be a hex-encoded ID"
+        );
+
+        let payload =
+            json!({"resourceLogs": [{"scopeLogs": [{"logRecords": [{"timeUnixNano": "-1"}]}]}]});
+        let error = decode_otlp_json::<ExportLogsServiceRequest>(payload.to_string().as_bytes())
+            .unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("failed to decode OTLP request"));
+    }
+
+    #[test]
+    fn test_encode_otlp_json_export_response() {
+        let response = ExportLogsServiceResponse {
+            partial_success: Some(ExportLogsPartialSuccess {
+                rejected_log_records: 2,
+                error_message: "invalid log records".to_string(),
+            }),
+        };
+        assert_eq!(
+            serde_json::to_value(response).unwrap(),
+            json!({
+                "partialSuccess": {
+                    "rejectedLogRecords": "2",
+                    "errorMessage": "invalid log records"
+                }
+            })
+        );
+        let response = ExportLogsServiceResponse {
+            partial_success: None,
+        };
+        assert_eq!(serde_json::to_value(response).unwrap(), json!({}));
+    }
+}
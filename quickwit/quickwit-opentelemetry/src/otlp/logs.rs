@@ -30,15 +30,16 @@ use quickwit_proto::opentelemetry::proto::collector::logs::v1::logs_service_serv
 use quickwit_proto::opentelemetry::proto::collector::logs::v1::{
     ExportLogsPartialSuccess, ExportLogsServiceRequest, ExportLogsServiceResponse,
 };
+use quickwit_proto::types::IndexId;
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 use tonic::{Request, Response, Status};
 use tracing::field::Empty;
 use tracing::{error, instrument, warn, Span as RuntimeSpan};
 
-use super::{is_zero, parse_log_record_body, SpanId, TraceId};
+use super::{is_zero, parse_log_record_body, OtlpEncoding, SpanId, TraceId};
 use crate::otlp::extract_attributes;
-use crate::otlp::metrics::OTLP_SERVICE_METRICS;
+use crate::otlp::metrics::{index_label, OTLP_SERVICE_METRICS};
 
 pub const OTEL_LOGS_INDEX_ID: &str = "otel-logs-v0_6";
 
@@ -228,10 +229,24 @@ impl OtlpGrpcLogsService {
         Ok(index_config)
     }
 
+    /// Ingests the log records of an OTLP/HTTP export request into the index `index_id`.
+    #[instrument(name = "ingest_logs", skip_all, fields(index_id = %index_id))]
+    pub async fn export_http(
+        &self,
+        index_id: IndexId,
+        request: ExportLogsServiceRequest,
+        encoding: OtlpEncoding,
+    ) -> Result<ExportLogsServiceResponse, Status> {
+        self.clone()
+            .export_instrumented(&index_id, request, "http", encoding)
+            .await
+    }
+
     async fn export_inner(
         &mut self,
+        index_id: &str,
         request: ExportLogsServiceRequest,
-        labels: [&'static str; 4],
+        labels: [&str; 4],
     ) -> Result<ExportLogsServiceResponse, Status> {
         let ParsedLogRecords {
             doc_batch,
@@ -239,8 +254,9 @@ impl OtlpGrpcLogsService {
             num_parse_errors,
             error_message,
         } = tokio::task::spawn_blocking({
+            let index_id = index_id.to_string();
             let parent_span = RuntimeSpan::current();
-            || Self::parse_logs(request, parent_span)
+            || Self::parse_logs(request, index_id, parent_span)
         })
         .await
         .map_err(|join_error| {
@@ -275,6 +291,7 @@ impl OtlpGrpcLogsService {
     #[instrument(skip_all, parent = parent_span, fields(num_spans = Empty, num_bytes = Empty, num_parse_errors = Empty))]
     fn parse_logs(
         request: ExportLogsServiceRequest,
+        index_id: IndexId,
         parent_span: RuntimeSpan,
     ) -> Result<ParsedLogRecords, Status> {
         let mut log_records = BTreeSet::new();
@@ -382,7 +399,7 @@ impl OtlpGrpcLogsService {
                 }
             }
         }
-        let mut doc_batch = DocBatchBuilder::new(OTEL_LOGS_INDEX_ID.to_string()).json_writer();
+        let mut doc_batch = DocBatchBuilder::new(index_id).json_writer();
         for log_record in log_records {
             if let Err(error) = doc_batch.ingest_doc(&log_record.0) {
                 error!(error=?error, "Failed to JSON serialize span.");
@@ -417,17 +434,21 @@ impl OtlpGrpcLogsService {
 
     async fn export_instrumented(
         &mut self,
+        index_id: &str,
         request: ExportLogsServiceRequest,
+        transport: &'static str,
+        encoding: OtlpEncoding,
     ) -> Result<ExportLogsServiceResponse, Status> {
         let start = std::time::Instant::now();
 
-        let labels = ["logs", OTEL_LOGS_INDEX_ID, "grpc", "protobuf"];
+        let index_label = index_label(index_id);
+        let labels = ["logs", index_label, transport, encoding.as_str()];
 
         OTLP_SERVICE_METRICS
             .requests_total
             .with_label_values(labels)
             .inc();
-        let (export_res, is_error) = match self.export_inner(request, labels).await {
+        let (export_res, is_error) = match self.export_inner(index_id, request, labels).await {
             ok @ Ok(_) => (ok, "false"),
             err @ Err(_) => {
                 OTLP_SERVICE_METRICS
@@ -438,7 +459,7 @@ impl OtlpGrpcLogsService {
             }
         };
         let elapsed = start.elapsed().as_secs_f64();
-        let labels = ["logs", OTEL_LOGS_INDEX_ID, "grpc", "protobuf", is_error];
+        let labels = ["logs", index_label, transport, encoding.as_str(), is_error];
         OTLP_SERVICE_METRICS
             .request_duration_seconds
             .with_label_values(labels)
@@ -457,7 +478,7 @@ impl LogsService for OtlpGrpcLogsService {
     ) -> Result<Response<ExportLogsServiceResponse>, Status> {
         let request = request.into_inner();
         self.clone()
-            .export_instrumented(request)
+            .export_instrumented(OTEL_LOGS_INDEX_ID, request, "grpc", OtlpEncoding::Protobuf)
             .await
             .map(Response::new)
     }
@@ -20,12 +20,19 @@
 use once_cell::sync::Lazy;
 use quickwit_common::metrics::{new_counter_vec, new_histogram_vec, HistogramVec, IntCounterVec};
 
+use super::{OTEL_LOGS_INDEX_ID, OTEL_METRICS_INDEX_ID, OTEL_TRACES_INDEX_ID};
+
+/// Value of the `index` label for the requests targeting an index other than the default OTEL
+/// indexes.
+const OTHER_INDEX_LABEL: &str = "other";
+
 pub struct OtlpServiceMetrics {
     pub requests_total: IntCounterVec<4>,
     pub request_errors_total: IntCounterVec<4>,
     pub request_duration_seconds: HistogramVec<5>,
     pub ingested_log_records_total: IntCounterVec<4>,
     pub ingested_spans_total: IntCounterVec<4>,
+    pub ingested_data_points_total: IntCounterVec<4>,
     pub ingested_bytes_total: IntCounterVec<4>,
 }
 
@@ -62,6 +69,12 @@ impl Default for OtlpServiceMetrics {
                 "quickwit_otlp",
                 ["service", "index", "transport", "format"],
             ),
+            ingested_data_points_total: new_counter_vec(
+                "ingested_data_points_total",
+                "Number of metric data points ingested",
+                "quickwit_otlp",
+                ["service", "index", "transport", "format"],
+            ),
             ingested_bytes_total: new_counter_vec(
                 "ingested_bytes_total",
                 "Number of bytes ingested",
@@ -72,5 +85,28 @@ impl Default for OtlpServiceMetrics {
     }
 }
 
+/// Returns the value of the `index` label for the requests targeting `index_id`. OTLP/HTTP
+/// requests can target any index, so only the default OTEL indexes get their own label value to
+/// bound the cardinality of the metrics.
+pub(crate) fn index_label(index_id: &str) -> &str {
+    match index_id {
+        OTEL_LOGS_INDEX_ID | OTEL_METRICS_INDEX_ID | OTEL_TRACES_INDEX_ID => index_id,
+        _ => OTHER_INDEX_LABEL,
+    }
+}
+
 /// `OTLP_SERVICE_METRICS` exposes metrics for each OTLP service.
 pub static OTLP_SERVICE_METRICS: Lazy<OtlpServiceMetrics> = Lazy::new(OtlpServiceMetrics::default);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_index_label() {
+        assert_eq!(index_label(OTEL_LOGS_INDEX_ID), OTEL_LOGS_INDEX_ID);
+        assert_eq!(index_label(OTEL_METRICS_INDEX_ID), OTEL_METRICS_INDEX_ID);
+        assert_eq!(index_label(OTEL_TRACES_INDEX_ID), OTEL_TRACES_INDEX_ID);
+        assert_eq!(index_label("my-logs"), "other");
+    }
+}
@@ -25,13 +25,19 @@ use quickwit_proto::opentelemetry::proto::common::v1::{
 };
 use serde_json::{Number as JsonNumber, Value as JsonValue};
 
+mod json;
 mod logs;
 mod metrics;
+mod otel_metrics;
 mod span_id;
 mod trace_id;
 mod traces;
 
+pub use json::{decode_otlp_json, OtlpJsonError};
 pub use logs::{OtlpGrpcLogsService, OTEL_LOGS_INDEX_ID};
+pub use otel_metrics::{
+    MetricDataPoint, MetricType, OtlpGrpcMetricsService, OTEL_METRICS_INDEX_ID,
+};
 pub use span_id::{SpanId, TryFromSpanIdError};
 pub use trace_id::{TraceId, TryFromTraceIdError};
 pub use traces::{
@@ -40,6 +46,22 @@ pub use traces::{
     OTEL_TRACES_INDEX_ID,
 };
 
+/// Encoding of the payload of an OTLP export request.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum OtlpEncoding {
+    Json,
+    Protobuf,
+}
+
+impl OtlpEncoding {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Protobuf => "protobuf",
+        }
+    }
+}
+
 impl From<OtlpTraceError> for tonic::Status {
     fn from(error: OtlpTraceError) -> Self {
         tonic::Status::invalid_argument(error.to_string())
@@ -0,0 +1,975 @@
+// Copyright (C) 2023 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use quickwit_common::uri::Uri;
+use quickwit_config::{load_index_config_from_user_config, ConfigFormat, IndexConfig};
+use quickwit_ingest::{
+    CommitType, DocBatch, DocBatchBuilder, IngestRequest, IngestService, IngestServiceClient,
+};
+use quickwit_proto::opentelemetry::proto::collector::metrics::v1::metrics_service_server::MetricsService;
+use quickwit_proto::opentelemetry::proto::collector::metrics::v1::{
+    ExportMetricsPartialSuccess, ExportMetricsServiceRequest, ExportMetricsServiceResponse,
+};
+use quickwit_proto::opentelemetry::proto::common::v1::KeyValue as OtlpKeyValue;
+use quickwit_proto::opentelemetry::proto::metrics::v1::exponential_histogram_data_point::Buckets as OtlpBuckets;
+use quickwit_proto::opentelemetry::proto::metrics::v1::metric::Data as OtlpMetricData;
+use quickwit_proto::opentelemetry::proto::metrics::v1::number_data_point::Value as OtlpNumberValue;
+use quickwit_proto::opentelemetry::proto::metrics::v1::{
+    AggregationTemporality as OtlpAggregationTemporality, NumberDataPoint as OtlpNumberDataPoint,
+};
+use quickwit_proto::types::IndexId;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use tonic::{Request, Response, Status};
+use tracing::field::Empty;
+use tracing::{error, instrument, Span as RuntimeSpan};
+
+use super::{extract_attributes, is_zero, OtlpEncoding};
+use crate::otlp::metrics::{index_label, OTLP_SERVICE_METRICS};
+
+pub const OTEL_METRICS_INDEX_ID: &str = "otel-metrics-v0_6";
+
+const OTEL_METRICS_INDEX_CONFIG: &str = r#"
+version: 0.6
+
+index_id: ${INDEX_ID}
+
+doc_mapping:
+  mode: strict
+  field_mappings:
+    - name: timestamp_nanos
+      type: datetime
+      input_formats: [unix_timestamp]
+      output_format: unix_timestamp_nanos
+      indexed: false
+      fast: true
+      fast_precision: milliseconds
+    - name: start_timestamp_nanos
+      type: datetime
+      input_formats: [unix_timestamp]
+      output_format: unix_timestamp_nanos
+      indexed: false
+    - name: service_name
+      type: text
+      tokenizer: raw
+      fast: true
+    - name: metric_name
+      type: text
+      tokenizer: raw
+      fast: true
+    - name: metric_description
+      type: text
+      indexed: false
+    - name: metric_unit
+      type: text
+      tokenizer: raw
+    - name: metric_type
+      type: text
+      tokenizer: raw
+      fast: true
+    - name: aggregation_temporality
+      type: text
+      tokenizer: raw
+    - name: is_monotonic
+      type: bool
+    - name: attributes
+      type: json
+      tokenizer: raw
+      fast: true
+    - name: flags
+      type: u64
+      indexed: false
+    - name: value
+      type: f64
+      indexed: false
+      fast: true
+    - name: count
+      type: u64
+      indexed: false
+      fast: true
+    - name: sum
+      type: f64
+      indexed: false
+      fast: true
+    - name: min
+      type: f64
+      indexed: false
+      fast: true
+    - name: max
+      type: f64
+      indexed: false
+      fast: true
+    - name: bucket_counts
+      type: array<u64>
+      indexed: false
+    - name: explicit_bounds
+      type: array<f64>
+      indexed: false
+    - name: scale
+      type: i64
+      indexed: false
+    - name: zero_count
+      type: u64
+      indexed: false
+    - name: positive_offset
+      type: i64
+      indexed: false
+    - name: positive_bucket_counts
+      type: array<u64>
+      indexed: false
+    - name: negative_offset
+      type: i64
+      indexed: false
+    - name: negative_bucket_counts
+      type: array<u64>
+      indexed: false
+    - name: resource_attributes
+      type: json
+      tokenizer: raw
+      fast: true
+    - name: resource_dropped_attributes_count
+      type: u64
+      indexed: false
+    - name: scope_name
+      type: text
+      indexed: false
+    - name: scope_version
+      type: text
+      indexed: false
+    - name: scope_attributes
+      type: json
+      indexed: false
+    - name: scope_dropped_attributes_count
+      type: u64
+      indexed: false
+
+  timestamp_field: timestamp_nanos
+
+indexing_settings:
+  commit_timeout_secs: 5
+
+search_settings:
+  default_search_fields: [metric_name]
+"#;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MetricType {
+    Gauge,
+    Sum,
+    Histogram,
+    ExponentialHistogram,
+}
+
+/// A metric data point flattened into a document. Gauge and sum data points populate the `value`
+/// field, histogram data points populate the `count`, `sum`, `min`, `max`, and bucket fields.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricDataPoint {
+    pub timestamp_nanos: u64,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_timestamp_nanos: Option<u64>,
+    pub service_name: String,
+    pub metric_name: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metric_description: Option<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metric_unit: Option<String>,
+    pub metric_type: MetricType,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aggregation_temporality: Option<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_monotonic: Option<bool>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub attributes: HashMap<String, JsonValue>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_zero")]
+    pub flags: u32,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<f64>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<u64>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sum: Option<f64>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min: Option<f64>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max: Option<f64>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub bucket_counts: Vec<u64>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub explicit_bounds: Vec<f64>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scale: Option<i32>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub zero_count: Option<u64>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub positive_offset: Option<i32>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub positive_bucket_counts: Vec<u64>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub negative_offset: Option<i32>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub negative_bucket_counts: Vec<u64>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub resource_attributes: HashMap<String, JsonValue>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_zero")]
+    pub resource_dropped_attributes_count: u32,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope_name: Option<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope_version: Option<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub scope_attributes: HashMap<String, JsonValue>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_zero")]
+    pub scope_dropped_attributes_count: u32,
+}
+
+/// The resource, scope, and metric fields shared by all the data points of a metric.
+struct MetricContext<'a> {
+    service_name: &'a str,
+    resource_attributes: &'a HashMap<String, JsonValue>,
+    resource_dropped_attributes_count: u32,
+    scope_name: Option<&'a String>,
+    scope_version: Option<&'a String>,
+    scope_attributes: &'a HashMap<String, JsonValue>,
+    scope_dropped_attributes_count: u32,
+    metric_name: &'a str,
+    metric_description: Option<&'a String>,
+    metric_unit: Option<&'a String>,
+}
+
+impl<'a> MetricContext<'a> {
+    fn data_point(
+        &self,
+        metric_type: MetricType,
+        time_unix_nano: u64,
+        start_time_unix_nano: u64,
+        attributes: Vec<OtlpKeyValue>,
+        flags: u32,
+    ) -> MetricDataPoint {
+        MetricDataPoint {
+            timestamp_nanos: time_unix_nano,
+            start_timestamp_nanos: Some(start_time_unix_nano).filter(|nanos| *nanos != 0),
+            service_name: self.service_name.to_string(),
+            metric_name: self.metric_name.to_string(),
+            metric_description: self.metric_description.cloned(),
+            metric_unit: self.metric_unit.cloned(),
+            metric_type,
+            aggregation_temporality: None,
+            is_monotonic: None,
+            attributes: extract_attributes(attributes),
+            flags,
+            value: None,
+            count: None,
+            sum: None,
+            min: None,
+            max: None,
+            bucket_counts: Vec::new(),
+            explicit_bounds: Vec::new(),
+            scale: None,
+            zero_count: None,
+            positive_offset: None,
+            positive_bucket_counts: Vec::new(),
+            negative_offset: None,
+            negative_bucket_counts: Vec::new(),
+            resource_attributes: self.resource_attributes.clone(),
+            resource_dropped_attributes_count: self.resource_dropped_attributes_count,
+            scope_name: self.scope_name.cloned(),
+            scope_version: self.scope_version.cloned(),
+            scope_attributes: self.scope_attributes.clone(),
+            scope_dropped_attributes_count: self.scope_dropped_attributes_count,
+        }
+    }
+
+    fn number_data_point(
+        &self,
+        metric_type: MetricType,
+        data_point: OtlpNumberDataPoint,
+    ) -> MetricDataPoint {
+        let value = match data_point.value {
+            Some(OtlpNumberValue::AsDouble(value)) => Some(value),
+            Some(OtlpNumberValue::AsInt(value)) => Some(value as f64),
+            None => None,
+        };
+        let mut metric_data_point = self.data_point(
+            metric_type,
+            data_point.time_unix_nano,
+            data_point.start_time_unix_nano,
+            data_point.attributes,
+            data_point.flags,
+        );
+        metric_data_point.value = finite(value);
+        metric_data_point
+    }
+}
+
+/// NaN and infinite values cannot be serialized to JSON.
+fn finite(value_opt: Option<f64>) -> Option<f64> {
+    value_opt.filter(|value| value.is_finite())
+}
+
+fn aggregation_temporality_name(aggregation_temporality: i32) -> Option<String> {
+    match OtlpAggregationTemporality::from_i32(aggregation_temporality)? {
+        OtlpAggregationTemporality::Delta => Some("delta".to_string()),
+        OtlpAggregationTemporality::Cumulative => Some("cumulative".to_string()),
+        OtlpAggregationTemporality::Unspecified => None,
+    }
+}
+
+fn split_buckets(buckets_opt: Option<OtlpBuckets>) -> (Option<i32>, Vec<u64>) {
+    match buckets_opt {
+        Some(buckets) => (Some(buckets.offset), buckets.bucket_counts),
+        None => (None, Vec::new()),
+    }
+}
+
+struct ParsedMetrics {
+    doc_batch: DocBatch,
+    num_data_points: u64,
+    num_parse_errors: u64,
+    error_message: String,
+}
+
+/// Flattens the data points of an OTLP metrics export request. Data points without a timestamp
+/// and summary data points, which are not supported, are counted as parse errors.
+fn parse_otlp_metrics(
+    request: ExportMetricsServiceRequest,
+) -> (Vec<MetricDataPoint>, u64, u64, String) {
+    let mut data_points = Vec::new();
+    let mut num_data_points = 0;
+    let mut num_parse_errors = 0;
+    let mut error_message = String::new();
+
+    for resource_metrics in request.resource_metrics {
+        let mut resource_attributes = extract_attributes(
+            resource_metrics
+                .resource
+                .clone()
+                .map(|rsrc| rsrc.attributes)
+                .unwrap_or_else(Vec::new),
+        );
+        let resource_dropped_attributes_count = resource_metrics
+            .resource
+            .map(|rsrc| rsrc.dropped_attributes_count)
+            .unwrap_or(0);
+
+        let service_name = match resource_attributes.remove("service.name") {
+            Some(JsonValue::String(value)) => value.to_string(),
+            _ => "unknown_service".to_string(),
+        };
+        for scope_metrics in resource_metrics.scope_metrics {
+            let scope_name = scope_metrics
+                .scope
+                .as_ref()
+                .map(|scope| &scope.name)
+                .filter(|name| !name.is_empty());
+            let scope_version = scope_metrics
+                .scope
+                .as_ref()
+                .map(|scope| &scope.version)
+                .filter(|version| !version.is_empty());
+            let scope_attributes = extract_attributes(
+                scope_metrics
+                    .scope
+                    .clone()
+                    .map(|scope| scope.attributes)
+                    .unwrap_or_else(Vec::new),
+            );
+            let scope_dropped_attributes_count = scope_metrics
+                .scope
+                .as_ref()
+                .map(|scope| scope.dropped_attributes_count)
+                .unwrap_or(0);
+
+            for metric in scope_metrics.metrics {
+                let metric_description =
+                    Some(&metric.description).filter(|description| !description.is_empty());
+                let metric_unit = Some(&metric.unit).filter(|unit| !unit.is_empty());
+                let context = MetricContext {
+                    service_name: &service_name,
+                    resource_attributes: &resource_attributes,
+                    resource_dropped_attributes_count,
+                    scope_name,
+                    scope_version,
+                    scope_attributes: &scope_attributes,
+                    scope_dropped_attributes_count,
+                    metric_name: &metric.name,
+                    metric_description,
+                    metric_unit,
+                };
+                let metric_data_points: Vec<MetricDataPoint> = match metric.data {
+                    Some(OtlpMetricData::Gauge(gauge)) => gauge
+                        .data_points
+                        .into_iter()
+                        .map(|data_point| context.number_data_point(MetricType::Gauge, data_point))
+                        .collect(),
+                    Some(OtlpMetricData::Sum(sum)) => {
+                        let aggregation_temporality =
+                            aggregation_temporality_name(sum.aggregation_temporality);
+                        sum.data_points
+                            .into_iter()
+                            .map(|data_point| {
+                                let mut metric_data_point =
+                                    context.number_data_point(MetricType::Sum, data_point);
+                                metric_data_point.aggregation_temporality =
+                                    aggregation_temporality.clone();
+                                metric_data_point.is_monotonic = Some(sum.is_monotonic);
+                                metric_data_point
+                            })
+                            .collect()
+                    }
+                    Some(OtlpMetricData::Histogram(histogram)) => {
+                        let aggregation_temporality =
+                            aggregation_temporality_name(histogram.aggregation_temporality);
+                        histogram
+                            .data_points
+                            .into_iter()
+                            .map(|data_point| {
+                                let mut metric_data_point = context.data_point(
+                                    MetricType::Histogram,
+                                    data_point.time_unix_nano,
+                                    data_point.start_time_unix_nano,
+                                    data_point.attributes,
+                                    data_point.flags,
+                                );
+                                metric_data_point.aggregation_temporality =
+                                    aggregation_temporality.clone();
+                                metric_data_point.count = Some(data_point.count);
+                                metric_data_point.sum = finite(data_point.sum);
+                                metric_data_point.min = finite(data_point.min);
+                                metric_data_point.max = finite(data_point.max);
+                                metric_data_point.bucket_counts = data_point.bucket_counts;
+                                metric_data_point.explicit_bounds = data_point.explicit_bounds;
+                                metric_data_point
+                            })
+                            .collect()
+                    }
+                    Some(OtlpMetricData::ExponentialHistogram(exponential_histogram)) => {
+                        let aggregation_temporality = aggregation_temporality_name(
+                            exponential_histogram.aggregation_temporality,
+                        );
+                        exponential_histogram
+                            .data_points
+                            .into_iter()
+                            .map(|data_point| {
+                                let mut metric_data_point = context.data_point(
+                                    MetricType::ExponentialHistogram,
+                                    data_point.time_unix_nano,
+                                    data_point.start_time_unix_nano,
+                                    data_point.attributes,
+                                    data_point.flags,
+                                );
+                                let (positive_offset, positive_bucket_counts) =
+                                    split_buckets(data_point.positive);
+                                let (negative_offset, negative_bucket_counts) =
+                                    split_buckets(data_point.negative);
+                                metric_data_point.aggregation_temporality =
+                                    aggregation_temporality.clone();
+                                metric_data_point.count = Some(data_point.count);
+                                metric_data_point.sum = finite(data_point.sum);
+                                metric_data_point.min = finite(data_point.min);
+                                metric_data_point.max = finite(data_point.max);
+                                metric_data_point.scale = Some(data_point.scale);
+                                metric_data_point.zero_count = Some(data_point.zero_count);
+                                metric_data_point.positive_offset = positive_offset;
+                                metric_data_point.positive_bucket_counts = positive_bucket_counts;
+                                metric_data_point.negative_offset = negative_offset;
+                                metric_data_point.negative_bucket_counts = negative_bucket_counts;
+                                metric_data_point
+                            })
+                            .collect()
+                    }
+                    Some(OtlpMetricData::Summary(summary)) => {
+                        num_data_points += summary.data_points.len() as u64;
+                        num_parse_errors += summary.data_points.len() as u64;
+                        error_message = format!(
+                            "summary metrics are not supported: metric `{}` was rejected",
+                            metric.name
+                        );
+                        continue;
+                    }
+                    None => continue,
+                };
+                for metric_data_point in metric_data_points {
+                    num_data_points += 1;
+
+                    if metric_data_point.timestamp_nanos == 0 {
+                        num_parse_errors += 1;
+                        error_message = format!(
+                            "data points without a timestamp are not supported: a data point of \
+                             metric `{}` was rejected",
+                            metric.name
+                        );
+                        continue;
+                    }
+                    data_points.push(metric_data_point);
+                }
+            }
+        }
+    }
+    // Sorting the data points by service, metric, and timestamp improves compression.
+    data_points.sort_unstable_by(|left, right| {
+        left.service_name
+            .cmp(&right.service_name)
+            .then_with(|| left.metric_name.cmp(&right.metric_name))
+            .then_with(|| left.timestamp_nanos.cmp(&right.timestamp_nanos))
+    });
+    (
+        data_points,
+        num_data_points,
+        num_parse_errors,
+        error_message,
+    )
+}
+
+#[derive(Clone)]
+pub struct OtlpGrpcMetricsService {
+    ingest_service: IngestServiceClient,
+}
+
+impl OtlpGrpcMetricsService {
+    pub fn new(ingest_service: IngestServiceClient) -> Self {
+        Self { ingest_service }
+    }
+
+    pub fn index_config(default_index_root_uri: &Uri) -> anyhow::Result<IndexConfig> {
+        let index_config_str =
+            OTEL_METRICS_INDEX_CONFIG.replace("${INDEX_ID}", OTEL_METRICS_INDEX_ID);
+        let index_config = load_index_config_from_user_config(
+            ConfigFormat::Yaml,
+            index_config_str.as_bytes(),
+            default_index_root_uri,
+        )?;
+        Ok(index_config)
+    }
+
+    /// Ingests the data points of an OTLP/HTTP export request into the index `index_id`.
+    #[instrument(name = "ingest_metrics", skip_all, fields(index_id = %index_id))]
+    pub async fn export_http(
+        &self,
+        index_id: IndexId,
+        request: ExportMetricsServiceRequest,
+        encoding: OtlpEncoding,
+    ) -> Result<ExportMetricsServiceResponse, Status> {
+        self.clone()
+            .export_instrumented(&index_id, request, "http", encoding)
+            .await
+    }
+
+    async fn export_inner(
+        &mut self,
+        index_id: &str,
+        request: ExportMetricsServiceRequest,
+        labels: [&str; 4],
+    ) -> Result<ExportMetricsServiceResponse, Status> {
+        let ParsedMetrics {
+            doc_batch,
+            num_data_points,
+            num_parse_errors,
+            error_message,
+        } = tokio::task::spawn_blocking({
+            let index_id = index_id.to_string();
+            let parent_span = RuntimeSpan::current();
+            || Self::parse_metrics(request, index_id, parent_span)
+        })
+        .await
+        .map_err(|join_error| {
+            error!("Failed to parse metrics: {join_error:?}");
+            Status::internal("failed to parse metrics")
+        })?;
+        if num_data_points == 0 {
+            return Err(tonic::Status::invalid_argument("the request is empty"));
+        }
+        if num_data_points == num_parse_errors {
+            return Err(tonic::Status::invalid_argument(error_message));
+        }
+        let num_bytes = doc_batch.num_bytes() as u64;
+        self.store_metrics(doc_batch).await?;
+
+        OTLP_SERVICE_METRICS
+            .ingested_data_points_total
+            .with_label_values(labels)
+            .inc_by(num_data_points - num_parse_errors);
+        OTLP_SERVICE_METRICS
+            .ingested_bytes_total
+            .with_label_values(labels)
+            .inc_by(num_bytes);
+
+        let response = ExportMetricsServiceResponse {
+            // `rejected_data_points=0` and `error_message=""` is consided a "full" success.
+            partial_success: Some(ExportMetricsPartialSuccess {
+                rejected_data_points: num_parse_errors as i64,
+                error_message,
+            }),
+        };
+        Ok(response)
+    }
+
+    #[instrument(skip_all, parent = parent_span, fields(num_data_points = Empty, num_bytes = Empty, num_parse_errors = Empty))]
+    fn parse_metrics(
+        request: ExportMetricsServiceRequest,
+        index_id: IndexId,
+        parent_span: RuntimeSpan,
+    ) -> ParsedMetrics {
+        let (data_points, num_data_points, mut num_parse_errors, mut error_message) =
+            parse_otlp_metrics(request);
+
+        let mut doc_batch = DocBatchBuilder::new(index_id).json_writer();
+        for data_point in data_points {
+            if let Err(error) = doc_batch.ingest_doc(&data_point) {
+                error!(error=?error, "Failed to JSON serialize data point.");
+                error_message = format!("Failed to JSON serialize data point: {error:?}");
+                num_parse_errors += 1;
+            }
+        }
+        let doc_batch = doc_batch.build();
+        let current_span = RuntimeSpan::current();
+        current_span.record("num_data_points", num_data_points);
+        current_span.record("num_bytes", doc_batch.num_bytes());
+        current_span.record("num_parse_errors", num_parse_errors);
+
+        ParsedMetrics {
+            doc_batch,
+            num_data_points,
+            num_parse_errors,
+            error_message,
+        }
+    }
+
+    #[instrument(skip_all, fields(num_bytes = doc_batch.num_bytes()))]
+    async fn store_metrics(&mut self, doc_batch: DocBatch) -> Result<(), tonic::Status> {
+        let ingest_request = IngestRequest {
+            doc_batches: vec![doc_batch],
+            commit: CommitType::Auto.into(),
+        };
+        self.ingest_service.ingest(ingest_request).await?;
+        Ok(())
+    }
+
+    async fn export_instrumented(
+        &mut self,
+        index_id: &str,
+        request: ExportMetricsServiceRequest,
+        transport: &'static str,
+        encoding: OtlpEncoding,
+    ) -> Result<ExportMetricsServiceResponse, Status> {
+        let start = std::time::Instant::now();
+
+        let index_label = index_label(index_id);
+        let labels = ["metrics", index_label, transport, encoding.as_str()];
+
+        OTLP_SERVICE_METRICS
+            .requests_total
+            .with_label_values(labels)
+            .inc();
+        let (export_res, is_error) = match self.export_inner(index_id, request, labels).await {
+            ok @ Ok(_) => (ok, "false"),
+            err @ Err(_) => {
+                OTLP_SERVICE_METRICS
+                    .request_errors_total
+                    .with_label_values(labels)
+                    .inc();
+                (err, "true")
+            }
+        };
+        let elapsed = start.elapsed().as_secs_f64();
+        let labels = [
+            "metrics",
+            index_label,
+            transport,
+            encoding.as_str(),
+            is_error,
+        ];
+        OTLP_SERVICE_METRICS
+            .request_duration_seconds
+            .with_label_values(labels)
+            .observe(elapsed);
+
+        export_res
+    }
+}
+
+#[async_trait]
+impl MetricsService for OtlpGrpcMetricsService {
+    #[instrument(name = "ingest_metrics", skip_all)]
+    async fn export(
+        &self,
+        request: Request<ExportMetricsServiceRequest>,
+    ) -> Result<Response<ExportMetricsServiceResponse>, Status> {
+        let request = request.into_inner();
+        self.clone()
+            .export_instrumented(
+                OTEL_METRICS_INDEX_ID,
+                request,
+                "grpc",
+                OtlpEncoding::Protobuf,
+            )
+            .await
+            .map(Response::new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quickwit_metastore::{metastore_for_test, CreateIndexRequestExt};
+    use quickwit_proto::metastore::{CreateIndexRequest, MetastoreService};
+    use quickwit_proto::opentelemetry::proto::common::v1::any_value::Value as OtlpAnyValueValue;
+    use quickwit_proto::opentelemetry::proto::common::v1::AnyValue as OtlpAnyValue;
+    use quickwit_proto::opentelemetry::proto::metrics::v1::{
+        ExponentialHistogram as OtlpExponentialHistogram,
+        ExponentialHistogramDataPoint as OtlpExponentialHistogramDataPoint, Gauge as OtlpGauge,
+        Histogram as OtlpHistogram, HistogramDataPoint as OtlpHistogramDataPoint,
+        Metric as OtlpMetric, ResourceMetrics, ScopeMetrics, Sum as OtlpSum,
+        Summary as OtlpSummary, SummaryDataPoint as OtlpSummaryDataPoint,
+    };
+    use quickwit_proto::opentelemetry::proto::resource::v1::Resource as OtlpResource;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_index_config_is_valid() {
+        let index_config =
+            OtlpGrpcMetricsService::index_config(&Uri::for_test("ram:///indexes")).unwrap();
+        assert_eq!(index_config.index_id, OTEL_METRICS_INDEX_ID);
+        assert_eq!(
+            index_config.doc_mapping.timestamp_field.unwrap(),
+            "timestamp_nanos"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_index() {
+        let mut metastore = metastore_for_test();
+        let index_config =
+            OtlpGrpcMetricsService::index_config(&Uri::for_test("ram:///indexes")).unwrap();
+        let create_index_request = CreateIndexRequest::try_from_index_config(index_config).unwrap();
+        metastore.create_index(create_index_request).await.unwrap();
+    }
+
+    fn metric(name: &str, data: OtlpMetricData) -> OtlpMetric {
+        OtlpMetric {
+            name: name.to_string(),
+            description: String::new(),
+            unit: "ms".to_string(),
+            data: Some(data),
+        }
+    }
+
+    #[test]
+    fn test_parse_otlp_metrics() {
+        let gauge = OtlpMetricData::Gauge(OtlpGauge {
+            data_points: vec![
+                OtlpNumberDataPoint {
+                    time_unix_nano: 2,
+                    value: Some(OtlpNumberValue::AsInt(42)),
+                    ..Default::default()
+                },
+                OtlpNumberDataPoint {
+                    time_unix_nano: 0,
+                    value: Some(OtlpNumberValue::AsDouble(1.5)),
+                    ..Default::default()
+                },
+            ],
+        });
+        let sum = OtlpMetricData::Sum(OtlpSum {
+            data_points: vec![OtlpNumberDataPoint {
+                time_unix_nano: 1,
+                start_time_unix_nano: 0,
+                attributes: vec![OtlpKeyValue {
+                    key: "method".to_string(),
+                    value: Some(OtlpAnyValue {
+                        value: Some(OtlpAnyValueValue::StringValue("GET".to_string())),
+                    }),
+                }],
+                value: Some(OtlpNumberValue::AsDouble(f64::NAN)),
+                ..Default::default()
+            }],
+            aggregation_temporality: OtlpAggregationTemporality::Cumulative as i32,
+            is_monotonic: true,
+        });
+        let histogram = OtlpMetricData::Histogram(OtlpHistogram {
+            data_points: vec![OtlpHistogramDataPoint {
+                time_unix_nano: 3,
+                start_time_unix_nano: 1,
+                count: 3,
+                sum: Some(6.0),
+                bucket_counts: vec![1, 2],
+                explicit_bounds: vec![2.5],
+                min: Some(1.0),
+                max: Some(3.0),
+                ..Default::default()
+            }],
+            aggregation_temporality: OtlpAggregationTemporality::Delta as i32,
+        });
+        let exponential_histogram =
+            OtlpMetricData::ExponentialHistogram(OtlpExponentialHistogram {
+                data_points: vec![OtlpExponentialHistogramDataPoint {
+                    time_unix_nano: 4,
+                    count: 2,
+                    scale: 3,
+                    zero_count: 1,
+                    positive: Some(OtlpBuckets {
+                        offset: -1,
+                        bucket_counts: vec![1],
+                    }),
+                    ..Default::default()
+                }],
+                aggregation_temporality: OtlpAggregationTemporality::Delta as i32,
+            });
+        let summary = OtlpMetricData::Summary(OtlpSummary {
+            data_points: vec![OtlpSummaryDataPoint {
+                time_unix_nano: 5,
+                ..Default::default()
+            }],
+        });
+        let request = ExportMetricsServiceRequest {
+            resource_metrics: vec![ResourceMetrics {
+                resource: Some(OtlpResource {
+                    attributes: vec![OtlpKeyValue {
+                        key: "service.name".to_string(),
+                        value: Some(OtlpAnyValue {
+                            value: Some(OtlpAnyValueValue::StringValue("quickwit".to_string())),
+                        }),
+                    }],
+                    dropped_attributes_count: 0,
+                }),
+                scope_metrics: vec![ScopeMetrics {
+                    scope: None,
+                    metrics: vec![
+                        metric("a_gauge", gauge),
+                        metric("b_sum", sum),
+                        metric("c_histogram", histogram),
+                        metric("d_exponential_histogram", exponential_histogram),
+                        metric("e_summary", summary),
+                    ],
+                    schema_url: String::new(),
+                }],
+                schema_url: String::new(),
+            }],
+        };
+        let (data_points, num_data_points, num_parse_errors, error_message) =
+            parse_otlp_metrics(request);
+        assert_eq!(num_data_points, 6);
+        assert_eq!(num_parse_errors, 2);
+        assert!(error_message.contains("e_summary"));
+        assert_eq!(data_points.len(), 4);
+
+        let gauge_json = serde_json::to_value(&data_points[0]).unwrap();
+        assert_eq!(
+            gauge_json,
+            json!({
+                "timestamp_nanos": 2,
+                "service_name": "quickwit",
+                "metric_name": "a_gauge",
+                "metric_unit": "ms",
+                "metric_type": "gauge",
+                "value": 42.0,
+            })
+        );
+        let sum_data_point = &data_points[1];
+        assert_eq!(sum_data_point.metric_type, MetricType::Sum);
+        assert_eq!(
+            sum_data_point.aggregation_temporality.as_deref(),
+            Some("cumulative")
+        );
+        assert_eq!(sum_data_point.is_monotonic, Some(true));
+        assert_eq!(sum_data_point.value, None);
+        assert_eq!(sum_data_point.attributes["method"], json!("GET"));
+
+        let histogram_data_point = &data_points[2];
+        assert_eq!(histogram_data_point.metric_type, MetricType::Histogram);
+        assert_eq!(histogram_data_point.start_timestamp_nanos, Some(1));
+        assert_eq!(histogram_data_point.count, Some(3));
+        assert_eq!(histogram_data_point.sum, Some(6.0));
+        assert_eq!(histogram_data_point.bucket_counts, [1, 2]);
+        assert_eq!(histogram_data_point.explicit_bounds, [2.5]);
+
+        let exponential_histogram_data_point = &data_points[3];
+        assert_eq!(
+            exponential_histogram_data_point.metric_type,
+            MetricType::ExponentialHistogram
+        );
+        assert_eq!(exponential_histogram_data_point.scale, Some(3));
+        assert_eq!(exponential_histogram_data_point.zero_count, Some(1));
+        assert_eq!(exponential_histogram_data_point.positive_offset, Some(-1));
+        assert_eq!(exponential_histogram_data_point.positive_bucket_counts, [1]);
+        assert_eq!(exponential_histogram_data_point.negative_offset, None);
+    }
+
+    #[test]
+    fn test_parse_otlp_metrics_without_timestamp() {
+        let gauge = OtlpMetricData::Gauge(OtlpGauge {
+            data_points: vec![OtlpNumberDataPoint {
+                time_unix_nano: 0,
+                value: Some(OtlpNumberValue::AsInt(42)),
+                ..Default::default()
+            }],
+        });
+        let request = ExportMetricsServiceRequest {
+            resource_metrics: vec![ResourceMetrics {
+                resource: None,
+                scope_metrics: vec![ScopeMetrics {
+                    scope: None,
+                    metrics: vec![metric("a_gauge", gauge)],
+                    schema_url: String::new(),
+                }],
+                schema_url: String::new(),
+            }],
+        };
+        let (data_points, num_data_points, num_parse_errors, error_message) =
+            parse_otlp_metrics(request);
+        assert!(data_points.is_empty());
+        assert_eq!(num_data_points, 1);
+        assert_eq!(num_parse_errors, 1);
+        assert_eq!(
+            error_message,
+            "data points without a timestamp are not supported: a data point of metric `a_gauge` \
+             was rejected"
+        );
+    }
+}
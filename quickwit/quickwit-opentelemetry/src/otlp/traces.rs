@@ -37,14 +37,15 @@ use quickwit_proto::opentelemetry::proto::resource::v1::Resource as OtlpResource
 use quickwit_proto::opentelemetry::proto::trace::v1::span::Link as OtlpLink;
 use quickwit_proto::opentelemetry::proto::trace::v1::status::StatusCode as OtlpStatusCode;
 use quickwit_proto::opentelemetry::proto::trace::v1::{Span as OtlpSpan, Status as OtlpStatus};
+use quickwit_proto::types::IndexId;
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 use tonic::{Request, Response, Status};
 use tracing::field::Empty;
 use tracing::{error, instrument, warn, Span as RuntimeSpan};
 
-use super::{is_zero, TryFromSpanIdError, TryFromTraceIdError};
-use crate::otlp::metrics::OTLP_SERVICE_METRICS;
+use super::{is_zero, OtlpEncoding, TryFromSpanIdError, TryFromTraceIdError};
+use crate::otlp::metrics::{index_label, OTLP_SERVICE_METRICS};
 use crate::otlp::{extract_attributes, SpanId, TraceId};
 
 pub const OTEL_TRACES_INDEX_ID: &str = "otel-traces-v0_6";
@@ -490,6 +491,7 @@ impl SpanFingerprint {
 
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub struct SpanStatus {
+    #[serde(with = "serde_status_code")]
     pub code: OtlpStatusCode,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message: Option<String>,
@@ -531,6 +533,37 @@ impl Default for SpanStatus {
     }
 }
 
+/// Status codes are stored in snake case in the OTEL traces index: `unset`, `ok`, or `error`.
+mod serde_status_code {
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use super::OtlpStatusCode;
+
+    const STATUS_CODES: [&str; 3] = ["unset", "ok", "error"];
+
+    pub fn serialize<S>(status_code: &OtlpStatusCode, serializer: S) -> Result<S::Ok, S::Error>
+    where S: Serializer {
+        let status_code_str = match status_code {
+            OtlpStatusCode::Unset => "unset",
+            OtlpStatusCode::Ok => "ok",
+            OtlpStatusCode::Error => "error",
+        };
+        serializer.serialize_str(status_code_str)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<OtlpStatusCode, D::Error>
+    where D: Deserializer<'de> {
+        let status_code_str = String::deserialize(deserializer)?;
+        match status_code_str.as_str() {
+            "unset" => Ok(OtlpStatusCode::Unset),
+            "ok" => Ok(OtlpStatusCode::Ok),
+            "error" => Ok(OtlpStatusCode::Error),
+            _ => Err(D::Error::unknown_variant(&status_code_str, &STATUS_CODES)),
+        }
+    }
+}
+
 const UNKNOWN_SERVICE: &str = "unknown_service";
 
 const SERVICE_NAME_KEY: &str = "service.name";
@@ -686,10 +719,24 @@ impl OtlpGrpcTracesService {
         Ok(index_config)
     }
 
+    /// Ingests the spans of an OTLP/HTTP export request into the index `index_id`.
+    #[instrument(name = "ingest_spans", skip_all, fields(index_id = %index_id))]
+    pub async fn export_http(
+        &self,
+        index_id: IndexId,
+        request: ExportTraceServiceRequest,
+        encoding: OtlpEncoding,
+    ) -> Result<ExportTraceServiceResponse, Status> {
+        self.clone()
+            .export_instrumented(&index_id, request, "http", encoding)
+            .await
+    }
+
     pub async fn export_inner(
         &mut self,
+        index_id: &str,
         request: ExportTraceServiceRequest,
-        labels: [&'static str; 4],
+        labels: [&str; 4],
     ) -> Result<ExportTraceServiceResponse, Status> {
         let ParsedSpans {
             doc_batch,
@@ -697,8 +744,9 @@ impl OtlpGrpcTracesService {
             num_parse_errors,
             error_message,
         } = tokio::task::spawn_blocking({
+            let index_id = index_id.to_string();
             let parent_span = RuntimeSpan::current();
-            || Self::parse_spans(request, parent_span)
+            || Self::parse_spans(request, index_id, parent_span)
         })
         .await
         .map_err(|join_error| {
@@ -736,6 +784,7 @@ impl OtlpGrpcTracesService {
     #[instrument(skip_all, parent = parent_span, fields(num_spans = Empty, num_bytes = Empty, num_parse_errors = Empty))]
     fn parse_spans(
         request: ExportTraceServiceRequest,
+        index_id: IndexId,
         parent_span: RuntimeSpan,
     ) -> tonic::Result<ParsedSpans> {
         let spans = parse_otlp_spans(request)?;
@@ -743,8 +792,7 @@ impl OtlpGrpcTracesService {
         let mut num_parse_errors = 0;
         let mut error_message = String::new();
 
-        let mut doc_batch_builder =
-            DocBatchBuilder::new(OTEL_TRACES_INDEX_ID.to_string()).json_writer();
+        let mut doc_batch_builder = DocBatchBuilder::new(index_id).json_writer();
         for span in spans {
             if let Err(error) = doc_batch_builder.ingest_doc(&span.0) {
                 error!(error=?error, "failed to JSON serialize span.");
@@ -779,17 +827,21 @@ impl OtlpGrpcTracesService {
 
     async fn export_instrumented(
         &mut self,
+        index_id: &str,
         request: ExportTraceServiceRequest,
+        transport: &'static str,
+        encoding: OtlpEncoding,
     ) -> Result<ExportTraceServiceResponse, Status> {
         let start = std::time::Instant::now();
 
-        let labels = ["trace", OTEL_TRACES_INDEX_ID, "grpc", "protobuf"];
+        let index_label = index_label(index_id);
+        let labels = ["trace", index_label, transport, encoding.as_str()];
 
         OTLP_SERVICE_METRICS
             .requests_total
             .with_label_values(labels)
             .inc();
-        let (export_res, is_error) = match self.export_inner(request, labels).await {
+        let (export_res, is_error) = match self.export_inner(index_id, request, labels).await {
             ok @ Ok(_) => (ok, "false"),
             err @ Err(_) => {
                 OTLP_SERVICE_METRICS
@@ -800,7 +852,7 @@ impl OtlpGrpcTracesService {
             }
         };
         let elapsed = start.elapsed().as_secs_f64();
-        let labels = ["trace", OTEL_TRACES_INDEX_ID, "grpc", "protobuf", is_error];
+        let labels = ["trace", index_label, transport, encoding.as_str(), is_error];
         OTLP_SERVICE_METRICS
             .request_duration_seconds
             .with_label_values(labels)
@@ -819,7 +871,12 @@ impl TraceService for OtlpGrpcTracesService {
     ) -> Result<Response<ExportTraceServiceResponse>, Status> {
         let request = request.into_inner();
         self.clone()
-            .export_instrumented(request)
+            .export_instrumented(
+                OTEL_TRACES_INDEX_ID,
+                request,
+                "grpc",
+                OtlpEncoding::Protobuf,
+            )
             .await
             .map(Response::new)
     }
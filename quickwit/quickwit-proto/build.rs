@@ -17,6 +17,7 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
+use std::env;
 use std::path::PathBuf;
 
 use glob::glob;
@@ -119,8 +120,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         .enum_attribute(".", "#[serde(rename_all=\"snake_case\")]")
         .type_attribute(".", "#[derive(Serialize, Deserialize, utoipa::ToSchema)]")
         .type_attribute("PartialHit", "#[derive(Eq, Hash)]")
-        .type_attribute("PartialHit.sort_value", "#[derive(Copy)]")
         .type_attribute("SearchRequest", "#[derive(Eq, Hash)]")
+        .type_attribute("SnippetConfig", "#[derive(Eq, Hash)]")
         .type_attribute("SortByValue", "#[derive(Ord, PartialOrd)]")
         .type_attribute("SortField", "#[derive(Eq, Hash)]")
         .out_dir("src/codegen/quickwit")
@@ -141,15 +142,32 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         )?;
 
     // OTEL proto
+    let otel_descriptor_set_path =
+        PathBuf::from(env::var("OUT_DIR")?).join("opentelemetry_descriptor_set.bin");
+
     let mut prost_config = prost_build::Config::default();
-    prost_config.protoc_arg("--experimental_allow_proto3_optional");
+    prost_config
+        .protoc_arg("--experimental_allow_proto3_optional")
+        .file_descriptor_set_path(&otel_descriptor_set_path);
 
     let protos = find_protos("protos/third-party/opentelemetry");
     tonic_build::configure()
-        .type_attribute(".", "#[derive(Serialize, Deserialize)]")
-        .type_attribute("StatusCode", r#"#[serde(rename_all = "snake_case")]"#)
         .out_dir("src/codegen/opentelemetry")
         .compile_with_config(prost_config, &protos, &["protos/third-party"])?;
+
+    // Serde impls following the proto3 JSON mapping, used to decode OTLP/HTTP JSON payloads. OTLP
+    // encodes trace and span IDs in hex rather than base64, see `quickwit-opentelemetry`.
+    let otel_descriptor_set = std::fs::read(&otel_descriptor_set_path)?;
+    pbjson_build::Builder::new()
+        .register_descriptors(&otel_descriptor_set)?
+        .ignore_unknown_fields()
+        .build(&[".opentelemetry"])?;
+
+    // Prometheus proto
+    let protos = find_protos("protos/third-party/prometheus");
+    prost_build::Config::new()
+        .out_dir("src/codegen/prometheus")
+        .compile_protos(&protos, &["protos/third-party"])?;
     Ok(())
 }
 
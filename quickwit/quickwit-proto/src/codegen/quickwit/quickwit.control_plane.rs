@@ -121,6 +121,11 @@ pub trait ControlPlaneService: std::fmt::Debug + dyn_clone::DynClone + Send + Sy
         &mut self,
         request: super::metastore::CreateIndexRequest,
     ) -> crate::control_plane::ControlPlaneResult<super::metastore::CreateIndexResponse>;
+    /// Updates an index.
+    async fn update_index(
+        &mut self,
+        request: super::metastore::UpdateIndexRequest,
+    ) -> crate::control_plane::ControlPlaneResult<super::metastore::IndexMetadataResponse>;
     /// Deletes an index.
     async fn delete_index(
         &mut self,
@@ -237,6 +242,12 @@ impl ControlPlaneService for ControlPlaneServiceClient {
     > {
         self.inner.create_index(request).await
     }
+    async fn update_index(
+        &mut self,
+        request: super::metastore::UpdateIndexRequest,
+    ) -> crate::control_plane::ControlPlaneResult<super::metastore::IndexMetadataResponse> {
+        self.inner.update_index(request).await
+    }
     async fn delete_index(
         &mut self,
         request: super::metastore::DeleteIndexRequest,
@@ -285,6 +296,14 @@ pub mod control_plane_service_mock {
         > {
             self.inner.lock().await.create_index(request).await
         }
+        async fn update_index(
+            &mut self,
+            request: super::super::metastore::UpdateIndexRequest,
+        ) -> crate::control_plane::ControlPlaneResult<
+            super::super::metastore::IndexMetadataResponse,
+        > {
+            self.inner.lock().await.update_index(request).await
+        }
         async fn delete_index(
             &mut self,
             request: super::super::metastore::DeleteIndexRequest,
@@ -355,6 +374,23 @@ for Box<dyn ControlPlaneService> {
         Box::pin(fut)
     }
 }
+impl tower::Service<super::metastore::UpdateIndexRequest>
+for Box<dyn ControlPlaneService> {
+    type Response = super::metastore::IndexMetadataResponse;
+    type Error = crate::control_plane::ControlPlaneError;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+    fn poll_ready(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+    fn call(&mut self, request: super::metastore::UpdateIndexRequest) -> Self::Future {
+        let mut svc = self.clone();
+        let fut = async move { svc.update_index(request).await };
+        Box::pin(fut)
+    }
+}
 impl tower::Service<super::metastore::DeleteIndexRequest>
 for Box<dyn ControlPlaneService> {
     type Response = super::metastore::EmptyResponse;
@@ -448,6 +484,11 @@ struct ControlPlaneServiceTowerBlock {
         super::metastore::CreateIndexResponse,
         crate::control_plane::ControlPlaneError,
     >,
+    update_index_svc: quickwit_common::tower::BoxService<
+        super::metastore::UpdateIndexRequest,
+        super::metastore::IndexMetadataResponse,
+        crate::control_plane::ControlPlaneError,
+    >,
     delete_index_svc: quickwit_common::tower::BoxService<
         super::metastore::DeleteIndexRequest,
         super::metastore::EmptyResponse,
@@ -479,6 +520,7 @@ impl Clone for ControlPlaneServiceTowerBlock {
         Self {
             inner: self.inner.clone(),
             create_index_svc: self.create_index_svc.clone(),
+            update_index_svc: self.update_index_svc.clone(),
             delete_index_svc: self.delete_index_svc.clone(),
             add_source_svc: self.add_source_svc.clone(),
             toggle_source_svc: self.toggle_source_svc.clone(),
@@ -497,6 +539,12 @@ impl ControlPlaneService for ControlPlaneServiceTowerBlock {
     > {
         self.create_index_svc.ready().await?.call(request).await
     }
+    async fn update_index(
+        &mut self,
+        request: super::metastore::UpdateIndexRequest,
+    ) -> crate::control_plane::ControlPlaneResult<super::metastore::IndexMetadataResponse> {
+        self.update_index_svc.ready().await?.call(request).await
+    }
     async fn delete_index(
         &mut self,
         request: super::metastore::DeleteIndexRequest,
@@ -540,6 +588,15 @@ pub struct ControlPlaneServiceTowerBlockBuilder {
         >,
     >,
     #[allow(clippy::type_complexity)]
+    update_index_layer: Option<
+        quickwit_common::tower::BoxLayer<
+            Box<dyn ControlPlaneService>,
+            super::metastore::UpdateIndexRequest,
+            super::metastore::IndexMetadataResponse,
+            crate::control_plane::ControlPlaneError,
+        >,
+    >,
+    #[allow(clippy::type_complexity)]
     delete_index_layer: Option<
         quickwit_common::tower::BoxLayer<
             Box<dyn ControlPlaneService>,
@@ -597,6 +654,14 @@ impl ControlPlaneServiceTowerBlockBuilder {
         <L::Service as tower::Service<
             super::metastore::CreateIndexRequest,
         >>::Future: Send + 'static,
+        L::Service: tower::Service<
+                super::metastore::UpdateIndexRequest,
+                Response = super::metastore::IndexMetadataResponse,
+                Error = crate::control_plane::ControlPlaneError,
+            > + Clone + Send + Sync + 'static,
+        <L::Service as tower::Service<
+            super::metastore::UpdateIndexRequest,
+        >>::Future: Send + 'static,
         L::Service: tower::Service<
                 super::metastore::DeleteIndexRequest,
                 Response = super::metastore::EmptyResponse,
@@ -642,6 +707,10 @@ impl ControlPlaneServiceTowerBlockBuilder {
             .create_index_layer = Some(
             quickwit_common::tower::BoxLayer::new(layer.clone()),
         );
+        self
+            .update_index_layer = Some(
+            quickwit_common::tower::BoxLayer::new(layer.clone()),
+        );
         self
             .delete_index_layer = Some(
             quickwit_common::tower::BoxLayer::new(layer.clone()),
@@ -679,6 +748,21 @@ impl ControlPlaneServiceTowerBlockBuilder {
         self.create_index_layer = Some(quickwit_common::tower::BoxLayer::new(layer));
         self
     }
+    pub fn update_index_layer<L>(mut self, layer: L) -> Self
+    where
+        L: tower::Layer<Box<dyn ControlPlaneService>> + Send + Sync + 'static,
+        L::Service: tower::Service<
+                super::metastore::UpdateIndexRequest,
+                Response = super::metastore::IndexMetadataResponse,
+                Error = crate::control_plane::ControlPlaneError,
+            > + Clone + Send + Sync + 'static,
+        <L::Service as tower::Service<
+            super::metastore::UpdateIndexRequest,
+        >>::Future: Send + 'static,
+    {
+        self.update_index_layer = Some(quickwit_common::tower::BoxLayer::new(layer));
+        self
+    }
     pub fn delete_index_layer<L>(mut self, layer: L) -> Self
     where
         L: tower::Layer<Box<dyn ControlPlaneService>> + Send + Sync + 'static,
@@ -799,6 +883,11 @@ impl ControlPlaneServiceTowerBlockBuilder {
         } else {
             quickwit_common::tower::BoxService::new(boxed_instance.clone())
         };
+        let update_index_svc = if let Some(layer) = self.update_index_layer {
+            layer.layer(boxed_instance.clone())
+        } else {
+            quickwit_common::tower::BoxService::new(boxed_instance.clone())
+        };
         let delete_index_svc = if let Some(layer) = self.delete_index_layer {
             layer.layer(boxed_instance.clone())
         } else {
@@ -829,6 +918,7 @@ impl ControlPlaneServiceTowerBlockBuilder {
         let tower_block = ControlPlaneServiceTowerBlock {
             inner: boxed_instance.clone(),
             create_index_svc,
+            update_index_svc,
             delete_index_svc,
             add_source_svc,
             toggle_source_svc,
@@ -919,6 +1009,15 @@ where
                 crate::control_plane::ControlPlaneError,
             >,
         >
+        + tower::Service<
+            super::metastore::UpdateIndexRequest,
+            Response = super::metastore::IndexMetadataResponse,
+            Error = crate::control_plane::ControlPlaneError,
+            Future = BoxFuture<
+                super::metastore::IndexMetadataResponse,
+                crate::control_plane::ControlPlaneError,
+            >,
+        >
         + tower::Service<
             super::metastore::DeleteIndexRequest,
             Response = super::metastore::EmptyResponse,
@@ -973,6 +1072,12 @@ where
     > {
         self.call(request).await
     }
+    async fn update_index(
+        &mut self,
+        request: super::metastore::UpdateIndexRequest,
+    ) -> crate::control_plane::ControlPlaneResult<super::metastore::IndexMetadataResponse> {
+        self.call(request).await
+    }
     async fn delete_index(
         &mut self,
         request: super::metastore::DeleteIndexRequest,
@@ -1050,6 +1155,16 @@ where
             .map(|response| response.into_inner())
             .map_err(|error| error.into())
     }
+    async fn update_index(
+        &mut self,
+        request: super::metastore::UpdateIndexRequest,
+    ) -> crate::control_plane::ControlPlaneResult<super::metastore::IndexMetadataResponse> {
+        self.inner
+            .update_index(request)
+            .await
+            .map(|response| response.into_inner())
+            .map_err(|error| error.into())
+    }
     async fn delete_index(
         &mut self,
         request: super::metastore::DeleteIndexRequest,
@@ -1127,6 +1242,17 @@ for ControlPlaneServiceGrpcServerAdapter {
             .map(tonic::Response::new)
             .map_err(|error| error.into())
     }
+    async fn update_index(
+        &self,
+        request: tonic::Request<super::metastore::UpdateIndexRequest>,
+    ) -> Result<tonic::Response<super::metastore::IndexMetadataResponse>, tonic::Status> {
+        self.inner
+            .clone()
+            .update_index(request.into_inner())
+            .await
+            .map(tonic::Response::new)
+            .map_err(|error| error.into())
+    }
     async fn delete_index(
         &self,
         request: tonic::Request<super::metastore::DeleteIndexRequest>,
@@ -1301,6 +1427,37 @@ pub mod control_plane_service_grpc_client {
                 );
             self.inner.unary(req, path, codec).await
         }
+        /// Updates an index.
+        pub async fn update_index(
+            &mut self,
+            request: impl tonic::IntoRequest<super::super::metastore::UpdateIndexRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::metastore::IndexMetadataResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/quickwit.control_plane.ControlPlaneService/UpdateIndex",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "quickwit.control_plane.ControlPlaneService",
+                        "UpdateIndex",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
         /// Deletes an index.
         pub async fn delete_index(
             &mut self,
@@ -1478,6 +1635,14 @@ pub mod control_plane_service_grpc_server {
             tonic::Response<super::super::metastore::CreateIndexResponse>,
             tonic::Status,
         >;
+        /// Updates an index.
+        async fn update_index(
+            &self,
+            request: tonic::Request<super::super::metastore::UpdateIndexRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::metastore::IndexMetadataResponse>,
+            tonic::Status,
+        >;
         /// Deletes an index.
         async fn delete_index(
             &self,
@@ -1649,6 +1814,55 @@ pub mod control_plane_service_grpc_server {
                     };
                     Box::pin(fut)
                 }
+                "/quickwit.control_plane.ControlPlaneService/UpdateIndex" => {
+                    #[allow(non_camel_case_types)]
+                    struct UpdateIndexSvc<T: ControlPlaneServiceGrpc>(pub Arc<T>);
+                    impl<
+                        T: ControlPlaneServiceGrpc,
+                    > tonic::server::UnaryService<
+                        super::super::metastore::UpdateIndexRequest,
+                    > for UpdateIndexSvc<T> {
+                        type Response = super::super::metastore::IndexMetadataResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::metastore::UpdateIndexRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                (*inner).update_index(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = UpdateIndexSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 "/quickwit.control_plane.ControlPlaneService/DeleteIndex" => {
                     #[allow(non_camel_case_types)]
                     struct DeleteIndexSvc<T: ControlPlaneServiceGrpc>(pub Arc<T>);
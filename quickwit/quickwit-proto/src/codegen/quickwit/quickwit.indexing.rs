@@ -4,6 +4,13 @@
 pub struct ApplyIndexingPlanRequest {
     #[prost(message, repeated, tag = "1")]
     pub indexing_tasks: ::prost::alloc::vec::Vec<IndexingTask>,
+    /// Fingerprints of the index configs the tasks were planned with, keyed by index UID. Indexers
+    /// restart the pipelines of an index when its fingerprint changes.
+    #[prost(map = "string, uint64", tag = "2")]
+    pub index_config_fingerprints: ::std::collections::HashMap<
+        ::prost::alloc::string::String,
+        u64,
+    >,
 }
 #[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -19,6 +19,15 @@ pub struct CreateIndexResponse {
 #[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UpdateIndexRequest {
+    #[prost(string, tag = "1")]
+    pub index_uid: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub index_config_json: ::prost::alloc::string::String,
+}
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ListIndexesMetadataRequest {
     #[prost(string, repeated, tag = "2")]
     pub index_id_patterns: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
@@ -452,6 +461,11 @@ impl PrometheusLabels<1> for CreateIndexRequest {
         OwnedPrometheusLabels::new([std::borrow::Cow::Borrowed("create_index")])
     }
 }
+impl PrometheusLabels<1> for UpdateIndexRequest {
+    fn labels(&self) -> OwnedPrometheusLabels<1usize> {
+        OwnedPrometheusLabels::new([std::borrow::Cow::Borrowed("update_index")])
+    }
+}
 impl PrometheusLabels<1> for IndexMetadataRequest {
     fn labels(&self) -> OwnedPrometheusLabels<1usize> {
         OwnedPrometheusLabels::new([std::borrow::Cow::Borrowed("index_metadata")])
@@ -571,6 +585,11 @@ pub trait MetastoreService: std::fmt::Debug + dyn_clone::DynClone + Send + Sync
         &mut self,
         request: CreateIndexRequest,
     ) -> crate::metastore::MetastoreResult<CreateIndexResponse>;
+    /// Updates the configuration of an index.
+    async fn update_index(
+        &mut self,
+        request: UpdateIndexRequest,
+    ) -> crate::metastore::MetastoreResult<IndexMetadataResponse>;
     /// Gets an index metadata.
     async fn index_metadata(
         &mut self,
@@ -763,6 +782,12 @@ impl MetastoreService for MetastoreServiceClient {
     ) -> crate::metastore::MetastoreResult<CreateIndexResponse> {
         self.inner.create_index(request).await
     }
+    async fn update_index(
+        &mut self,
+        request: UpdateIndexRequest,
+    ) -> crate::metastore::MetastoreResult<IndexMetadataResponse> {
+        self.inner.update_index(request).await
+    }
     async fn index_metadata(
         &mut self,
         request: IndexMetadataRequest,
@@ -911,6 +936,12 @@ pub mod metastore_service_mock {
         ) -> crate::metastore::MetastoreResult<super::CreateIndexResponse> {
             self.inner.lock().await.create_index(request).await
         }
+        async fn update_index(
+            &mut self,
+            request: super::UpdateIndexRequest,
+        ) -> crate::metastore::MetastoreResult<super::IndexMetadataResponse> {
+            self.inner.lock().await.update_index(request).await
+        }
         async fn index_metadata(
             &mut self,
             request: super::IndexMetadataRequest,
@@ -1074,6 +1105,22 @@ impl tower::Service<CreateIndexRequest> for Box<dyn MetastoreService> {
         Box::pin(fut)
     }
 }
+impl tower::Service<UpdateIndexRequest> for Box<dyn MetastoreService> {
+    type Response = IndexMetadataResponse;
+    type Error = crate::metastore::MetastoreError;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+    fn poll_ready(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+    fn call(&mut self, request: UpdateIndexRequest) -> Self::Future {
+        let mut svc = self.clone();
+        let fut = async move { svc.update_index(request).await };
+        Box::pin(fut)
+    }
+}
 impl tower::Service<IndexMetadataRequest> for Box<dyn MetastoreService> {
     type Response = IndexMetadataResponse;
     type Error = crate::metastore::MetastoreError;
@@ -1419,6 +1466,11 @@ struct MetastoreServiceTowerBlock {
         CreateIndexResponse,
         crate::metastore::MetastoreError,
     >,
+    update_index_svc: quickwit_common::tower::BoxService<
+        UpdateIndexRequest,
+        IndexMetadataResponse,
+        crate::metastore::MetastoreError,
+    >,
     index_metadata_svc: quickwit_common::tower::BoxService<
         IndexMetadataRequest,
         IndexMetadataResponse,
@@ -1530,6 +1582,7 @@ impl Clone for MetastoreServiceTowerBlock {
         Self {
             inner: self.inner.clone(),
             create_index_svc: self.create_index_svc.clone(),
+            update_index_svc: self.update_index_svc.clone(),
             index_metadata_svc: self.index_metadata_svc.clone(),
             list_indexes_metadata_svc: self.list_indexes_metadata_svc.clone(),
             delete_index_svc: self.delete_index_svc.clone(),
@@ -1564,6 +1617,12 @@ impl MetastoreService for MetastoreServiceTowerBlock {
     ) -> crate::metastore::MetastoreResult<CreateIndexResponse> {
         self.create_index_svc.ready().await?.call(request).await
     }
+    async fn update_index(
+        &mut self,
+        request: UpdateIndexRequest,
+    ) -> crate::metastore::MetastoreResult<IndexMetadataResponse> {
+        self.update_index_svc.ready().await?.call(request).await
+    }
     async fn index_metadata(
         &mut self,
         request: IndexMetadataRequest,
@@ -1709,6 +1768,15 @@ pub struct MetastoreServiceTowerBlockBuilder {
         >,
     >,
     #[allow(clippy::type_complexity)]
+    update_index_layer: Option<
+        quickwit_common::tower::BoxLayer<
+            Box<dyn MetastoreService>,
+            UpdateIndexRequest,
+            IndexMetadataResponse,
+            crate::metastore::MetastoreError,
+        >,
+    >,
+    #[allow(clippy::type_complexity)]
     index_metadata_layer: Option<
         quickwit_common::tower::BoxLayer<
             Box<dyn MetastoreService>,
@@ -1908,6 +1976,12 @@ impl MetastoreServiceTowerBlockBuilder {
                 Error = crate::metastore::MetastoreError,
             > + Clone + Send + Sync + 'static,
         <L::Service as tower::Service<CreateIndexRequest>>::Future: Send + 'static,
+        L::Service: tower::Service<
+                UpdateIndexRequest,
+                Response = IndexMetadataResponse,
+                Error = crate::metastore::MetastoreError,
+            > + Clone + Send + Sync + 'static,
+        <L::Service as tower::Service<UpdateIndexRequest>>::Future: Send + 'static,
         L::Service: tower::Service<
                 IndexMetadataRequest,
                 Response = IndexMetadataResponse,
@@ -2047,6 +2121,10 @@ impl MetastoreServiceTowerBlockBuilder {
             .create_index_layer = Some(
             quickwit_common::tower::BoxLayer::new(layer.clone()),
         );
+        self
+            .update_index_layer = Some(
+            quickwit_common::tower::BoxLayer::new(layer.clone()),
+        );
         self
             .index_metadata_layer = Some(
             quickwit_common::tower::BoxLayer::new(layer.clone()),
@@ -2143,6 +2221,19 @@ impl MetastoreServiceTowerBlockBuilder {
         self.create_index_layer = Some(quickwit_common::tower::BoxLayer::new(layer));
         self
     }
+    pub fn update_index_layer<L>(mut self, layer: L) -> Self
+    where
+        L: tower::Layer<Box<dyn MetastoreService>> + Send + Sync + 'static,
+        L::Service: tower::Service<
+                UpdateIndexRequest,
+                Response = IndexMetadataResponse,
+                Error = crate::metastore::MetastoreError,
+            > + Clone + Send + Sync + 'static,
+        <L::Service as tower::Service<UpdateIndexRequest>>::Future: Send + 'static,
+    {
+        self.update_index_layer = Some(quickwit_common::tower::BoxLayer::new(layer));
+        self
+    }
     pub fn index_metadata_layer<L>(mut self, layer: L) -> Self
     where
         L: tower::Layer<Box<dyn MetastoreService>> + Send + Sync + 'static,
@@ -2490,6 +2581,11 @@ impl MetastoreServiceTowerBlockBuilder {
         } else {
             quickwit_common::tower::BoxService::new(boxed_instance.clone())
         };
+        let update_index_svc = if let Some(layer) = self.update_index_layer {
+            layer.layer(boxed_instance.clone())
+        } else {
+            quickwit_common::tower::BoxService::new(boxed_instance.clone())
+        };
         let index_metadata_svc = if let Some(layer) = self.index_metadata_layer {
             layer.layer(boxed_instance.clone())
         } else {
@@ -2607,6 +2703,7 @@ impl MetastoreServiceTowerBlockBuilder {
         let tower_block = MetastoreServiceTowerBlock {
             inner: boxed_instance.clone(),
             create_index_svc,
+            update_index_svc,
             index_metadata_svc,
             list_indexes_metadata_svc,
             delete_index_svc,
@@ -2710,6 +2807,12 @@ where
             Error = crate::metastore::MetastoreError,
             Future = BoxFuture<CreateIndexResponse, crate::metastore::MetastoreError>,
         >
+        + tower::Service<
+            UpdateIndexRequest,
+            Response = IndexMetadataResponse,
+            Error = crate::metastore::MetastoreError,
+            Future = BoxFuture<IndexMetadataResponse, crate::metastore::MetastoreError>,
+        >
         + tower::Service<
             IndexMetadataRequest,
             Response = IndexMetadataResponse,
@@ -2852,6 +2955,12 @@ where
     ) -> crate::metastore::MetastoreResult<CreateIndexResponse> {
         self.call(request).await
     }
+    async fn update_index(
+        &mut self,
+        request: UpdateIndexRequest,
+    ) -> crate::metastore::MetastoreResult<IndexMetadataResponse> {
+        self.call(request).await
+    }
     async fn index_metadata(
         &mut self,
         request: IndexMetadataRequest,
@@ -3035,6 +3144,16 @@ where
             .map(|response| response.into_inner())
             .map_err(|error| error.into())
     }
+    async fn update_index(
+        &mut self,
+        request: UpdateIndexRequest,
+    ) -> crate::metastore::MetastoreResult<IndexMetadataResponse> {
+        self.inner
+            .update_index(request)
+            .await
+            .map(|response| response.into_inner())
+            .map_err(|error| error.into())
+    }
     async fn index_metadata(
         &mut self,
         request: IndexMetadataRequest,
@@ -3287,6 +3406,17 @@ for MetastoreServiceGrpcServerAdapter {
             .map(tonic::Response::new)
             .map_err(|error| error.into())
     }
+    async fn update_index(
+        &self,
+        request: tonic::Request<UpdateIndexRequest>,
+    ) -> Result<tonic::Response<IndexMetadataResponse>, tonic::Status> {
+        self.inner
+            .clone()
+            .update_index(request.into_inner())
+            .await
+            .map(tonic::Response::new)
+            .map_err(|error| error.into())
+    }
     async fn index_metadata(
         &self,
         request: tonic::Request<IndexMetadataRequest>,
@@ -3632,6 +3762,34 @@ pub mod metastore_service_grpc_client {
                 );
             self.inner.unary(req, path, codec).await
         }
+        /// Updates the configuration of an index.
+        pub async fn update_index(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UpdateIndexRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::IndexMetadataResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/quickwit.metastore.MetastoreService/UpdateIndex",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("quickwit.metastore.MetastoreService", "UpdateIndex"),
+                );
+            self.inner.unary(req, path, codec).await
+        }
         /// Gets an index metadata.
         pub async fn index_metadata(
             &mut self,
@@ -4251,6 +4409,14 @@ pub mod metastore_service_grpc_server {
             tonic::Response<super::CreateIndexResponse>,
             tonic::Status,
         >;
+        /// Updates the configuration of an index.
+        async fn update_index(
+            &self,
+            request: tonic::Request<super::UpdateIndexRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::IndexMetadataResponse>,
+            tonic::Status,
+        >;
         /// Gets an index metadata.
         async fn index_metadata(
             &self,
@@ -4515,6 +4681,52 @@ pub mod metastore_service_grpc_server {
                     };
                     Box::pin(fut)
                 }
+                "/quickwit.metastore.MetastoreService/UpdateIndex" => {
+                    #[allow(non_camel_case_types)]
+                    struct UpdateIndexSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
+                    impl<
+                        T: MetastoreServiceGrpc,
+                    > tonic::server::UnaryService<super::UpdateIndexRequest>
+                    for UpdateIndexSvc<T> {
+                        type Response = super::IndexMetadataResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::UpdateIndexRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                (*inner).update_index(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = UpdateIndexSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 "/quickwit.metastore.MetastoreService/IndexMetadata" => {
                     #[allow(non_camel_case_types)]
                     struct IndexMetadataSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
@@ -94,7 +94,7 @@ pub struct SearchRequest {
     /// Fields to extract snippet on
     #[prost(string, repeated, tag = "12")]
     pub snippet_fields: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
-    /// Optional sort by one or more fields (limited to 2 at the moment).
+    /// Optional sort by one or more fields.
     #[prost(message, repeated, tag = "14")]
     pub sort_fields: ::prost::alloc::vec::Vec<SortField>,
     /// If set, the search response will include a search id
@@ -109,6 +109,41 @@ pub struct SearchRequest {
     pub search_after: ::core::option::Option<PartialHit>,
     #[prost(enumeration = "CountHits", tag = "17")]
     pub count_hits: i32,
+    /// Settings of the snippets extracted on `snippet_fields`.
+    #[prost(message, optional, tag = "18")]
+    pub snippet_config: ::core::option::Option<SnippetConfig>,
+    /// Maximum duration of the search, in milliseconds.
+    /// When it is reached, the splits that have not been searched yet are skipped,
+    /// and the hits gathered so far are returned.
+    #[prost(uint64, optional, tag = "19")]
+    pub timeout_millis: ::core::option::Option<u64>,
+    /// If set, the search response includes a profile of the search: the query
+    /// lowered for each index, the splits pruned by the root and, for each split
+    /// searched, the time spent and the caches hit.
+    #[prost(bool, tag = "20")]
+    pub profile: bool,
+    /// If not empty, the hits only hold these top-level fields of the documents.
+    #[prost(string, repeated, tag = "21")]
+    pub fetch_fields: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+/// Settings of the snippets, also called highlights.
+#[derive(Serialize, Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SnippetConfig {
+    /// Maximum number of characters of a fragment. Defaults to 150.
+    #[prost(uint32, optional, tag = "1")]
+    pub fragment_size: ::core::option::Option<u32>,
+    /// Maximum number of fragments returned for each field value. Defaults to 1.
+    /// If set to 0, the whole field value is returned.
+    #[prost(uint32, optional, tag = "2")]
+    pub num_fragments: ::core::option::Option<u32>,
+    /// Markup inserted before each highlighted term. Defaults to `<b>`.
+    #[prost(string, optional, tag = "3")]
+    pub pre_tag: ::core::option::Option<::prost::alloc::string::String>,
+    /// Markup inserted after each highlighted term. Defaults to `</b>`.
+    #[prost(string, optional, tag = "4")]
+    pub post_tag: ::core::option::Option<::prost::alloc::string::String>,
 }
 #[derive(Serialize, Deserialize, utoipa::ToSchema)]
 #[derive(Eq, Hash)]
@@ -143,6 +178,90 @@ pub struct SearchResponse {
     /// Scroll Id (only set if scroll_secs was set in the request)
     #[prost(string, optional, tag = "6")]
     pub scroll_id: ::core::option::Option<::prost::alloc::string::String>,
+    /// Whether the search timed out. If true, the hits are those gathered
+    /// before the timeout, and the skipped splits are listed in `errors`.
+    #[prost(bool, tag = "7")]
+    pub timed_out: bool,
+    /// Profile of the search (only set if `profile` was set in the request).
+    #[prost(message, optional, tag = "8")]
+    pub profile: ::core::option::Option<SearchProfile>,
+}
+#[derive(Serialize, Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SearchProfile {
+    /// Query lowered into a tantivy query, for each index searched.
+    #[prost(message, repeated, tag = "1")]
+    pub queries: ::prost::alloc::vec::Vec<IndexQueryProfile>,
+    /// Splits not searched because their time range does not overlap the time
+    /// range of the query.
+    #[prost(message, repeated, tag = "2")]
+    pub splits_pruned_by_time_range: ::prost::alloc::vec::Vec<PrunedSplit>,
+    /// Splits not searched because their tags cannot match the query.
+    #[prost(message, repeated, tag = "3")]
+    pub splits_pruned_by_tags: ::prost::alloc::vec::Vec<PrunedSplit>,
+    /// Profile of each split searched.
+    #[prost(message, repeated, tag = "4")]
+    pub split_profiles: ::prost::alloc::vec::Vec<SplitSearchProfile>,
+    /// Whether only part of the pruned splits are reported, the splits of the
+    /// indexes being listed up to a limit to find them.
+    #[prost(bool, tag = "5")]
+    pub pruned_splits_truncated: bool,
+}
+#[derive(Serialize, Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct IndexQueryProfile {
+    #[prost(string, tag = "1")]
+    pub index_id: ::prost::alloc::string::String,
+    /// Debug representation of the tantivy query built for the index.
+    #[prost(string, tag = "2")]
+    pub lowered_query: ::prost::alloc::string::String,
+}
+#[derive(Serialize, Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PrunedSplit {
+    #[prost(string, tag = "1")]
+    pub index_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub split_id: ::prost::alloc::string::String,
+}
+#[derive(Serialize, Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SplitSearchProfile {
+    #[prost(string, tag = "1")]
+    pub split_id: ::prost::alloc::string::String,
+    /// Whether the response was served by the partial request cache. If true,
+    /// the other timings and counters are not relevant.
+    #[prost(bool, tag = "2")]
+    pub partial_request_cache_hit: bool,
+    /// Whether the split footer was served by the footer cache.
+    #[prost(bool, tag = "3")]
+    pub footer_cache_hit: bool,
+    /// Approximate number of reads served by the fast field cache: the reads of
+    /// the split minus the reads that went past the cache, which may be split or
+    /// merged differently.
+    #[prost(uint64, tag = "4")]
+    pub num_fast_field_cache_hits: u64,
+    /// Approximate number of reads served by the split cache: the reads past the
+    /// fast field cache minus the reads that hit the storage, which may be split
+    /// or merged differently.
+    #[prost(uint64, tag = "5")]
+    pub num_split_cache_hits: u64,
+    /// Time spent warming up the split, in microseconds.
+    #[prost(uint64, tag = "6")]
+    pub warmup_micros: u64,
+    /// Number of bytes fetched from the storage during the warmup.
+    #[prost(uint64, tag = "7")]
+    pub warmup_num_bytes_fetched: u64,
+    /// Number of documents matched by the query in the split.
+    #[prost(uint64, tag = "8")]
+    pub num_docs_matched: u64,
+    /// Time spent collecting the matching documents, in microseconds.
+    #[prost(uint64, tag = "9")]
+    pub collection_micros: u64,
 }
 #[derive(Serialize, Deserialize, utoipa::ToSchema)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -157,6 +276,9 @@ pub struct SplitSearchError {
     /// Flag to indicate if the error can be considered a retryable error
     #[prost(bool, tag = "3")]
     pub retryable_error: bool,
+    /// Flag to indicate that the split was skipped because the search timed out
+    #[prost(bool, tag = "4")]
+    pub timed_out: bool,
 }
 #[derive(Serialize, Deserialize, utoipa::ToSchema)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -267,6 +389,9 @@ pub struct PartialHit {
     pub sort_value: ::core::option::Option<SortByValue>,
     #[prost(message, optional, tag = "11")]
     pub sort_value2: ::core::option::Option<SortByValue>,
+    /// Values of the sort keys following the second one, in order.
+    #[prost(message, repeated, tag = "12")]
+    pub extra_sort_values: ::prost::alloc::vec::Vec<SortByValue>,
     #[prost(string, tag = "2")]
     pub split_id: ::prost::alloc::string::String,
     /// (segment_ord, doc) form a tantivy DocAddress, which is sufficient to identify a document
@@ -282,7 +407,7 @@ pub struct PartialHit {
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SortByValue {
-    #[prost(oneof = "sort_by_value::SortValue", tags = "1, 2, 3, 4")]
+    #[prost(oneof = "sort_by_value::SortValue", tags = "1, 2, 3, 4, 5")]
     pub sort_value: ::core::option::Option<sort_by_value::SortValue>,
 }
 /// Nested message and enum types in `SortByValue`.
@@ -300,6 +425,8 @@ pub mod sort_by_value {
         F64(f64),
         #[prost(bool, tag = "4")]
         Boolean(bool),
+        #[prost(string, tag = "5")]
+        Str(::prost::alloc::string::String),
     }
 }
 #[derive(Serialize, Deserialize, utoipa::ToSchema)]
@@ -324,6 +451,9 @@ pub struct LeafSearchResponse {
     pub intermediate_aggregation_result: ::core::option::Option<
         ::prost::alloc::vec::Vec<u8>,
     >,
+    /// Profile of the splits searched (only set if `profile` was set in the request).
+    #[prost(message, repeated, tag = "7")]
+    pub split_profiles: ::prost::alloc::vec::Vec<SplitSearchProfile>,
 }
 #[derive(Serialize, Deserialize, utoipa::ToSchema)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -333,6 +463,8 @@ pub struct SnippetRequest {
     pub snippet_fields: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
     #[prost(string, tag = "2")]
     pub query_ast_resolved: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "3")]
+    pub snippet_config: ::core::option::Option<SnippetConfig>,
 }
 #[derive(Serialize, Deserialize, utoipa::ToSchema)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -355,6 +487,9 @@ pub struct FetchDocsRequest {
     /// `DocMapper` as json serialized trait.
     #[prost(string, tag = "6")]
     pub doc_mapper: ::prost::alloc::string::String,
+    /// If not empty, the hits only hold these top-level fields of the documents.
+    #[prost(string, repeated, tag = "8")]
+    pub fetch_fields: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
 }
 #[derive(Serialize, Deserialize, utoipa::ToSchema)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -443,6 +578,70 @@ pub struct LeafListTermsResponse {
 #[derive(Serialize, Deserialize, utoipa::ToSchema)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListFieldsRequest {
+    /// Index ID patterns
+    #[prost(string, repeated, tag = "1")]
+    pub index_id_patterns: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[derive(Serialize, Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListFieldsEntry {
+    /// Full path of the field. The segments of object paths are separated by a dot.
+    #[prost(string, tag = "1")]
+    pub field_name: ::prost::alloc::string::String,
+    #[prost(enumeration = "ListFieldType", tag = "2")]
+    pub field_type: i32,
+    /// Whether the field is indexed.
+    #[prost(bool, tag = "3")]
+    pub searchable: bool,
+    /// Whether the field is a fast field.
+    #[prost(bool, tag = "4")]
+    pub aggregatable: bool,
+    /// IDs of the indexes in which the field exists. Empty in leaf responses.
+    #[prost(string, repeated, tag = "5")]
+    pub index_ids: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[derive(Serialize, Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListFieldsResponse {
+    /// Fields sorted by name and type.
+    #[prost(message, repeated, tag = "1")]
+    pub fields: ::prost::alloc::vec::Vec<ListFieldsEntry>,
+}
+#[derive(Serialize, Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct LeafListFieldsRequest {
+    /// Index ID
+    #[prost(string, tag = "1")]
+    pub index_id: ::prost::alloc::string::String,
+    /// Index URI. The index URI defines the location of the storage that contains the
+    /// split files.
+    #[prost(string, tag = "2")]
+    pub index_uri: ::prost::alloc::string::String,
+    /// Splits to list the fields of.
+    #[prost(message, repeated, tag = "3")]
+    pub split_offsets: ::prost::alloc::vec::Vec<SplitIdAndFooterOffsets>,
+}
+#[derive(Serialize, Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct LeafListFieldsResponse {
+    /// Fields of the JSON objects seen in the splits, sorted by name and type.
+    #[prost(message, repeated, tag = "1")]
+    pub fields: ::prost::alloc::vec::Vec<ListFieldsEntry>,
+    /// The list of splits that failed.
+    #[prost(message, repeated, tag = "2")]
+    pub failed_splits: ::prost::alloc::vec::Vec<SplitSearchError>,
+    /// Total number of splits the leaf(s) were in charge of.
+    #[prost(uint64, tag = "3")]
+    pub num_attempted_splits: u64,
+}
+#[derive(Serialize, Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SearchStreamRequest {
     /// Index ID
     #[prost(string, tag = "1")]
@@ -458,6 +657,10 @@ pub struct SearchStreamRequest {
     /// Name of the fast field to extract
     #[prost(string, tag = "6")]
     pub fast_field: ::prost::alloc::string::String,
+    /// Names of the fast fields to extract, one column per fast field.
+    /// When not empty, it takes precedence over `fast_field`.
+    #[prost(string, repeated, tag = "12")]
+    pub fast_fields: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
     /// The output format
     #[prost(enumeration = "OutputFormat", tag = "7")]
     pub output_format: i32,
@@ -566,6 +769,58 @@ impl SortOrder {
 #[serde(rename_all = "snake_case")]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
+pub enum ListFieldType {
+    Str = 0,
+    U64 = 1,
+    I64 = 2,
+    F64 = 3,
+    Bool = 4,
+    Date = 5,
+    Facet = 6,
+    Bytes = 7,
+    IpAddr = 8,
+    Json = 9,
+}
+impl ListFieldType {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            ListFieldType::Str => "STR",
+            ListFieldType::U64 => "U64",
+            ListFieldType::I64 => "I64",
+            ListFieldType::F64 => "F64",
+            ListFieldType::Bool => "BOOL",
+            ListFieldType::Date => "DATE",
+            ListFieldType::Facet => "FACET",
+            ListFieldType::Bytes => "BYTES",
+            ListFieldType::IpAddr => "IP_ADDR",
+            ListFieldType::Json => "JSON",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "STR" => Some(Self::Str),
+            "U64" => Some(Self::U64),
+            "I64" => Some(Self::I64),
+            "F64" => Some(Self::F64),
+            "BOOL" => Some(Self::Bool),
+            "DATE" => Some(Self::Date),
+            "FACET" => Some(Self::Facet),
+            "BYTES" => Some(Self::Bytes),
+            "IP_ADDR" => Some(Self::IpAddr),
+            "JSON" => Some(Self::Json),
+            _ => None,
+        }
+    }
+}
+#[derive(Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
 pub enum OutputFormat {
     /// Comma Separated Values format (<https://datatracker.ietf.org/doc/html/rfc4180>).
     /// The delimiter is `,`.
@@ -575,6 +830,12 @@ pub enum OutputFormat {
     /// Format data by row in ClickHouse binary format.
     /// <https://clickhouse.tech/docs/en/interfaces/formats/#rowbinary>
     ClickHouseRowBinary = 1,
+    /// Newline delimited JSON format (<https://github.com/ndjson/ndjson-spec>).
+    /// Each row is serialized as a JSON object keyed by fast field name.
+    Ndjson = 2,
+    /// Arrow IPC streaming format.
+    /// <https://arrow.apache.org/docs/format/Columnar.html#ipc-streaming-format>
+    ArrowIpc = 3,
 }
 impl OutputFormat {
     /// String value of the enum field names used in the ProtoBuf definition.
@@ -585,6 +846,8 @@ impl OutputFormat {
         match self {
             OutputFormat::Csv => "CSV",
             OutputFormat::ClickHouseRowBinary => "CLICK_HOUSE_ROW_BINARY",
+            OutputFormat::Ndjson => "NDJSON",
+            OutputFormat::ArrowIpc => "ARROW_IPC",
         }
     }
     /// Creates an enum from field names used in the ProtoBuf definition.
@@ -592,6 +855,8 @@ impl OutputFormat {
         match value {
             "CSV" => Some(Self::Csv),
             "CLICK_HOUSE_ROW_BINARY" => Some(Self::ClickHouseRowBinary),
+            "NDJSON" => Some(Self::Ndjson),
+            "ARROW_IPC" => Some(Self::ArrowIpc),
             _ => None,
         }
     }
@@ -860,6 +1125,67 @@ pub mod search_service_client {
                 );
             self.inner.unary(req, path, codec).await
         }
+        /// Root list fields API.
+        /// This RPC lists the fields of the doc mappings of the targeted indexes, and
+        /// dispatches the several calls to `LeafListFields` to discover the fields of
+        /// JSON objects.
+        ///
+        /// It is also in charge of merging back the results.
+        pub async fn root_list_fields(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListFieldsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListFieldsResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/quickwit.search.SearchService/RootListFields",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("quickwit.search.SearchService", "RootListFields"),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// Lists the JSON object fields seen in the given set of splits.
+        pub async fn leaf_list_fields(
+            &mut self,
+            request: impl tonic::IntoRequest<super::LeafListFieldsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::LeafListFieldsResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/quickwit.search.SearchService/LeafListFields",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("quickwit.search.SearchService", "LeafListFields"),
+                );
+            self.inner.unary(req, path, codec).await
+        }
         /// Performs a scroll request.
         pub async fn scroll(
             &mut self,
@@ -1041,6 +1367,27 @@ pub mod search_service_server {
             tonic::Response<super::LeafListTermsResponse>,
             tonic::Status,
         >;
+        /// Root list fields API.
+        /// This RPC lists the fields of the doc mappings of the targeted indexes, and
+        /// dispatches the several calls to `LeafListFields` to discover the fields of
+        /// JSON objects.
+        ///
+        /// It is also in charge of merging back the results.
+        async fn root_list_fields(
+            &self,
+            request: tonic::Request<super::ListFieldsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListFieldsResponse>,
+            tonic::Status,
+        >;
+        /// Lists the JSON object fields seen in the given set of splits.
+        async fn leaf_list_fields(
+            &self,
+            request: tonic::Request<super::LeafListFieldsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::LeafListFieldsResponse>,
+            tonic::Status,
+        >;
         /// Performs a scroll request.
         async fn scroll(
             &self,
@@ -1417,6 +1764,98 @@ pub mod search_service_server {
                     };
                     Box::pin(fut)
                 }
+                "/quickwit.search.SearchService/RootListFields" => {
+                    #[allow(non_camel_case_types)]
+                    struct RootListFieldsSvc<T: SearchService>(pub Arc<T>);
+                    impl<
+                        T: SearchService,
+                    > tonic::server::UnaryService<super::ListFieldsRequest>
+                    for RootListFieldsSvc<T> {
+                        type Response = super::ListFieldsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListFieldsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                (*inner).root_list_fields(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = RootListFieldsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/quickwit.search.SearchService/LeafListFields" => {
+                    #[allow(non_camel_case_types)]
+                    struct LeafListFieldsSvc<T: SearchService>(pub Arc<T>);
+                    impl<
+                        T: SearchService,
+                    > tonic::server::UnaryService<super::LeafListFieldsRequest>
+                    for LeafListFieldsSvc<T> {
+                        type Response = super::LeafListFieldsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::LeafListFieldsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                (*inner).leaf_list_fields(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = LeafListFieldsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 "/quickwit.search.SearchService/Scroll" => {
                     #[allow(non_camel_case_types)]
                     struct ScrollSvc<T: SearchService>(pub Arc<T>);
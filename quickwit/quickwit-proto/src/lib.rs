@@ -55,6 +55,10 @@ pub mod jaeger {
     }
 }
 
+pub mod prometheus {
+    include!("codegen/prometheus/prometheus.rs");
+}
+
 pub mod opentelemetry {
     #[cfg(not(doctest))]
     pub mod proto {
@@ -63,45 +67,59 @@ pub mod opentelemetry {
             pub mod logs {
                 pub mod v1 {
                     include!("codegen/opentelemetry/opentelemetry.proto.collector.logs.v1.rs");
+                    include!(concat!(
+                        env!("OUT_DIR"),
+                        "/opentelemetry.proto.collector.logs.v1.serde.rs"
+                    ));
+                }
+            }
+            pub mod metrics {
+                pub mod v1 {
+                    include!("codegen/opentelemetry/opentelemetry.proto.collector.metrics.v1.rs");
+                    include!(concat!(
+                        env!("OUT_DIR"),
+                        "/opentelemetry.proto.collector.metrics.v1.serde.rs"
+                    ));
                 }
             }
-            // pub mod metrics {
-            //     pub mod v1 {
-            //         include!("codegen/opentelemetry/opentelemetry.proto.collector.metrics.v1.rs"
-            // );     }
-            // }
             pub mod trace {
                 pub mod v1 {
                     include!("codegen/opentelemetry/opentelemetry.proto.collector.trace.v1.rs");
+                    include!(concat!(
+                        env!("OUT_DIR"),
+                        "/opentelemetry.proto.collector.trace.v1.serde.rs"
+                    ));
                 }
             }
         }
         pub mod common {
             pub mod v1 {
                 include!("codegen/opentelemetry/opentelemetry.proto.common.v1.rs");
+                include!(concat!(env!("OUT_DIR"), "/opentelemetry.proto.common.v1.serde.rs"));
             }
         }
         pub mod logs {
             pub mod v1 {
                 include!("codegen/opentelemetry/opentelemetry.proto.logs.v1.rs");
+                include!(concat!(env!("OUT_DIR"), "/opentelemetry.proto.logs.v1.serde.rs"));
+            }
+        }
+        pub mod metrics {
+            pub mod v1 {
+                include!("codegen/opentelemetry/opentelemetry.proto.metrics.v1.rs");
+                include!(concat!(env!("OUT_DIR"), "/opentelemetry.proto.metrics.v1.serde.rs"));
             }
         }
-        // pub mod metrics {
-        //     pub mod experimental {
-        //         include!("codegen/opentelemetry/opentelemetry.proto.metrics.experimental.rs");
-        //     }
-        //     pub mod v1 {
-        //         tonic::include_proto!("codegen/opentelemetry/opentelemetry.proto.metrics.v1");
-        //     }
-        // }
         pub mod resource {
             pub mod v1 {
                 include!("codegen/opentelemetry/opentelemetry.proto.resource.v1.rs");
+                include!(concat!(env!("OUT_DIR"), "/opentelemetry.proto.resource.v1.serde.rs"));
             }
         }
         pub mod trace {
             pub mod v1 {
                 include!("codegen/opentelemetry/opentelemetry.proto.trace.v1.rs");
+                include!(concat!(env!("OUT_DIR"), "/opentelemetry.proto.trace.v1.serde.rs"));
             }
         }
     }
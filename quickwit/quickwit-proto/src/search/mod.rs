@@ -52,8 +52,19 @@ impl fmt::Display for SplitSearchError {
     }
 }
 
+impl SplitSearchError {
+    /// Creates the error reported for a split skipped because the search timed out.
+    pub fn for_timed_out_split(split_id: String) -> Self {
+        SplitSearchError {
+            error: "search timed out".to_string(),
+            split_id,
+            retryable_error: false,
+            timed_out: true,
+        }
+    }
+}
+
 impl Eq for SortByValue {}
-impl Copy for SortByValue {}
 impl From<SortValue> for SortByValue {
     fn from(sort_value: SortValue) -> Self {
         SortByValue {
@@ -83,6 +94,7 @@ impl SortByValue {
                 }
             }
             Some(SortValue::Boolean(b)) => Bool(b),
+            Some(SortValue::Str(s)) => String(s),
             None => Null,
         }
     }
@@ -104,7 +116,8 @@ impl SortByValue {
                     return None;
                 }
             }
-            String(_) | Array(_) | Object(_) => return None,
+            String(s) => Some(SortValue::Str(s)),
+            Array(_) | Object(_) => return None,
         };
         Some(SortByValue { sort_value })
     }
@@ -116,15 +129,14 @@ impl SortByValue {
 // This is terrible because this means Eq, PartialEq are not really in line with Ord's
 // implementation. if in presence of NaN.
 impl Eq for SortValue {}
-impl Copy for SortValue {}
 
 impl Ord for SortValue {
     fn cmp(&self, other: &Self) -> Ordering {
         // We make sure to end up with a total order.
-        match (*self, *other) {
+        match (self, other) {
             // Same types.
-            (SortValue::U64(left), SortValue::U64(right)) => left.cmp(&right),
-            (SortValue::I64(left), SortValue::I64(right)) => left.cmp(&right),
+            (SortValue::U64(left), SortValue::U64(right)) => left.cmp(right),
+            (SortValue::I64(left), SortValue::I64(right)) => left.cmp(right),
             (SortValue::F64(left), SortValue::F64(right)) => {
                 if left.is_nan() {
                     if right.is_nan() {
@@ -135,27 +147,30 @@ impl Ord for SortValue {
                 } else if right.is_nan() {
                     Ordering::Greater
                 } else {
-                    left.partial_cmp(&right).unwrap_or(Ordering::Less)
+                    left.partial_cmp(right).unwrap_or(Ordering::Less)
                 }
             }
-            (SortValue::Boolean(left), SortValue::Boolean(right)) => left.cmp(&right),
+            (SortValue::Boolean(left), SortValue::Boolean(right)) => left.cmp(right),
+            (SortValue::Str(left), SortValue::Str(right)) => left.cmp(right),
+            // Strings are greater than any other type.
+            (SortValue::Str(_), _) => Ordering::Greater,
             // We half the logic by making sure we keep
             // the "stronger" type on the left.
             (SortValue::U64(left), SortValue::I64(right)) => {
-                if left > i64::MAX as u64 {
+                if *left > i64::MAX as u64 {
                     return Ordering::Greater;
                 }
-                (left as i64).cmp(&right)
+                (*left as i64).cmp(right)
             }
             (SortValue::F64(left), _) if left.is_nan() => Ordering::Less,
             (SortValue::F64(left), SortValue::U64(right)) => {
-                left.partial_cmp(&(right as f64)).unwrap_or(Ordering::Less)
+                left.partial_cmp(&(*right as f64)).unwrap_or(Ordering::Less)
             }
             (SortValue::F64(left), SortValue::I64(right)) => {
-                left.partial_cmp(&(right as f64)).unwrap_or(Ordering::Less)
+                left.partial_cmp(&(*right as f64)).unwrap_or(Ordering::Less)
             }
-            (SortValue::Boolean(left), right) => SortValue::U64(left as u64).cmp(&right),
-            (left, right) => right.cmp(&left).reverse(),
+            (SortValue::Boolean(left), right) => SortValue::U64(*left as u64).cmp(right),
+            (left, right) => right.cmp(left).reverse(),
         }
     }
 }
@@ -186,6 +201,10 @@ impl std::hash::Hash for SortValue {
                 3u8.hash(state);
                 b.hash(state);
             }
+            SortValue::Str(s) => {
+                4u8.hash(state);
+                s.hash(state);
+            }
         }
     }
 }
@@ -196,13 +215,14 @@ impl SortValue {
     /// For number, we prefer to represent them, in order, as i64, then as u64 and finaly as f64.
     pub fn normalize(&self) -> Self {
         match self {
-            SortValue::I64(_) => *self,
-            SortValue::Boolean(_) => *self,
+            SortValue::I64(_) => self.clone(),
+            SortValue::Boolean(_) => self.clone(),
+            SortValue::Str(_) => self.clone(),
             SortValue::U64(number) => {
                 if let Ok(number) = (*number).try_into() {
                     SortValue::I64(number)
                 } else {
-                    *self
+                    self.clone()
                 }
             }
             SortValue::F64(number) => {
@@ -215,7 +235,7 @@ impl SortValue {
                         return SortValue::U64(number as u64);
                     }
                 }
-                *self
+                self.clone()
             }
         }
     }
@@ -224,10 +244,78 @@ impl SortValue {
 impl PartialHit {
     /// Helper to get access to the 1st sort value
     pub fn sort_value(&self) -> Option<SortValue> {
-        if let Some(sort_value) = self.sort_value {
-            sort_value.sort_value
+        if let Some(sort_value) = &self.sort_value {
+            sort_value.sort_value.clone()
         } else {
             None
         }
     }
+
+    /// Returns the values of the sort keys, in order.
+    ///
+    /// A missing `sort_value` or `sort_value2` followed by other values is returned as a null
+    /// `SortByValue`, so that the values stay aligned with the sort keys.
+    pub fn sort_by_values(&self) -> impl Iterator<Item = SortByValue> + '_ {
+        let num_sort_values = if !self.extra_sort_values.is_empty() {
+            2 + self.extra_sort_values.len()
+        } else if self.sort_value2.is_some() {
+            2
+        } else {
+            usize::from(self.sort_value.is_some())
+        };
+        [&self.sort_value, &self.sort_value2]
+            .into_iter()
+            .map(|sort_by_value_opt| {
+                sort_by_value_opt
+                    .clone()
+                    .unwrap_or(SortByValue { sort_value: None })
+            })
+            .chain(self.extra_sort_values.iter().cloned())
+            .take(num_sort_values)
+    }
+
+    /// Sets the values of the sort keys, in order.
+    ///
+    /// The first two values are stored in `sort_value` and `sort_value2`, the following ones in
+    /// `extra_sort_values`.
+    pub fn set_sort_by_values(&mut self, sort_by_values: impl IntoIterator<Item = SortByValue>) {
+        let mut sort_by_values_iter = sort_by_values.into_iter();
+        self.sort_value = sort_by_values_iter.next();
+        self.sort_value2 = sort_by_values_iter.next();
+        self.extra_sort_values = sort_by_values_iter.collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partial_hit_sort_by_values() {
+        let partial_hit = PartialHit::default();
+        assert_eq!(partial_hit.sort_by_values().count(), 0);
+
+        let mut partial_hit = PartialHit::default();
+        partial_hit.set_sort_by_values([SortValue::I64(1).into(), SortValue::U64(2).into()]);
+        let sort_values: Vec<Option<SortValue>> = partial_hit
+            .sort_by_values()
+            .map(|sort_by_value| sort_by_value.sort_value)
+            .collect();
+        assert_eq!(
+            sort_values,
+            [Some(SortValue::I64(1)), Some(SortValue::U64(2))]
+        );
+
+        let partial_hit = PartialHit {
+            sort_value: None,
+            sort_value2: None,
+            extra_sort_values: vec![SortValue::Boolean(true).into()],
+            ..Default::default()
+        };
+        let sort_values: Vec<Option<SortValue>> = partial_hit
+            .sort_by_values()
+            .map(|sort_by_value| sort_by_value.sort_value)
+            .collect();
+        assert_eq!(sort_values, [None, None, Some(SortValue::Boolean(true))]);
+    }
 }
@@ -0,0 +1,104 @@
+// Copyright (C) 2023 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use serde::Deserialize;
+
+use crate::elastic_query_dsl::{ConvertableToQueryAst, ElasticQueryDslInner};
+use crate::not_nan_f32::NotNaNf32;
+use crate::query_ast::{self, QueryAst};
+
+/// Matches the documents matching the `positive` query. The score of the documents also
+/// matching the `negative` query is multiplied by `negative_boost`.
+#[derive(Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct BoostingQuery {
+    positive: Box<ElasticQueryDslInner>,
+    negative: Box<ElasticQueryDslInner>,
+    negative_boost: NotNaNf32,
+    #[serde(default)]
+    boost: Option<NotNaNf32>,
+}
+
+impl From<BoostingQuery> for ElasticQueryDslInner {
+    fn from(boosting_query: BoostingQuery) -> Self {
+        Self::Boosting(boosting_query)
+    }
+}
+
+impl ConvertableToQueryAst for BoostingQuery {
+    fn convert_to_query_ast(self) -> anyhow::Result<QueryAst> {
+        let positive_ast = self.positive.convert_to_query_ast()?;
+        let negative_ast = self.negative.convert_to_query_ast()?;
+        // The two clauses below match disjoint sets of documents.
+        let positive_only_ast: QueryAst = query_ast::BoolQuery {
+            must: vec![positive_ast.clone()],
+            must_not: vec![negative_ast.clone()],
+            ..Default::default()
+        }
+        .into();
+        let positive_and_negative_ast: QueryAst = query_ast::BoolQuery {
+            must: vec![positive_ast],
+            filter: vec![negative_ast],
+            ..Default::default()
+        }
+        .into();
+        let boosting_ast: QueryAst = query_ast::BoolQuery {
+            should: vec![
+                positive_only_ast,
+                positive_and_negative_ast.boost(Some(self.negative_boost)),
+            ],
+            ..Default::default()
+        }
+        .into();
+        Ok(boosting_ast.boost(self.boost))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_boosting_query_convert_to_query_ast() {
+        let boosting_query_json = r#"{
+            "positive": { "term": { "text": "apple" } },
+            "negative": { "term": { "text": "pie" } },
+            "negative_boost": 0.5
+        }"#;
+        let boosting_query: BoostingQuery = serde_json::from_str(boosting_query_json).unwrap();
+        let query_ast = boosting_query.convert_to_query_ast().unwrap();
+        let QueryAst::Bool(bool_query) = query_ast else {
+            panic!()
+        };
+        assert_eq!(bool_query.should.len(), 2);
+        let QueryAst::Boost { boost, .. } = &bool_query.should[1] else {
+            panic!()
+        };
+        assert_eq!(*boost, NotNaNf32::try_from(0.5).unwrap());
+    }
+
+    #[test]
+    fn test_boosting_query_missing_negative_boost() {
+        let boosting_query_json = r#"{
+            "positive": { "term": { "text": "apple" } },
+            "negative": { "term": { "text": "pie" } }
+        }"#;
+        serde_json::from_str::<BoostingQuery>(boosting_query_json).unwrap_err();
+    }
+}
@@ -0,0 +1,85 @@
+// Copyright (C) 2023 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use serde::Deserialize;
+
+use crate::elastic_query_dsl::{ConvertableToQueryAst, ElasticQueryDslInner};
+use crate::not_nan_f32::NotNaNf32;
+use crate::query_ast::{self, QueryAst};
+
+/// Wraps a filter query. All the matching documents get a score equal to `boost`.
+#[derive(Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct ConstantScoreQuery {
+    filter: Box<ElasticQueryDslInner>,
+    #[serde(default)]
+    boost: Option<NotNaNf32>,
+}
+
+impl From<ConstantScoreQuery> for ElasticQueryDslInner {
+    fn from(constant_score_query: ConstantScoreQuery) -> Self {
+        Self::ConstantScore(constant_score_query)
+    }
+}
+
+impl ConvertableToQueryAst for ConstantScoreQuery {
+    fn convert_to_query_ast(self) -> anyhow::Result<QueryAst> {
+        let filter_ast = self.filter.convert_to_query_ast()?;
+        // The filter clause does not contribute to the score, so the score of the matching
+        // documents is the one of `MatchAll`, that is 1.
+        let constant_score_ast: QueryAst = query_ast::BoolQuery {
+            must: vec![QueryAst::MatchAll],
+            filter: vec![filter_ast],
+            ..Default::default()
+        }
+        .into();
+        Ok(constant_score_ast.boost(self.boost))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constant_score_query_convert_to_query_ast() {
+        let constant_score_query_json = r#"{
+            "filter": { "term": { "user.id": "kimchy" } },
+            "boost": 1.2
+        }"#;
+        let constant_score_query: ConstantScoreQuery =
+            serde_json::from_str(constant_score_query_json).unwrap();
+        let query_ast = constant_score_query.convert_to_query_ast().unwrap();
+        let QueryAst::Boost { underlying, boost } = query_ast else {
+            panic!()
+        };
+        assert_eq!(boost, NotNaNf32::try_from(1.2).unwrap());
+        let QueryAst::Bool(bool_query) = *underlying else {
+            panic!()
+        };
+        assert_eq!(bool_query.must, vec![QueryAst::MatchAll]);
+        assert_eq!(
+            bool_query.filter,
+            vec![QueryAst::Term(query_ast::TermQuery {
+                field: "user.id".to_string(),
+                value: "kimchy".to_string(),
+            })]
+        );
+    }
+}
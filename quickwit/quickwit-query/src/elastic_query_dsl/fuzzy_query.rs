@@ -0,0 +1,253 @@
+// Copyright (C) 2023 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use anyhow::Context;
+use serde::Deserialize;
+
+use crate::elastic_query_dsl::one_field_map::OneFieldMap;
+use crate::elastic_query_dsl::{
+    default_max_expansions, ConvertableToQueryAst, ElasticQueryDslInner,
+    StringOrStructForSerialization,
+};
+use crate::not_nan_f32::NotNaNf32;
+use crate::query_ast::{self, QueryAst};
+
+#[derive(Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(from = "OneFieldMap<StringOrStructForSerialization<FuzzyQueryParams>>")]
+pub(crate) struct FuzzyQuery {
+    pub field: String,
+    pub params: FuzzyQueryParams,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct FuzzyQueryParams {
+    value: String,
+    #[serde(default)]
+    fuzziness: Fuzziness,
+    #[serde(default = "default_max_expansions")]
+    max_expansions: u32,
+    #[serde(default)]
+    prefix_length: u32,
+    #[serde(default = "default_transpositions")]
+    transpositions: bool,
+    #[serde(default)]
+    boost: Option<NotNaNf32>,
+}
+
+fn default_transpositions() -> bool {
+    true
+}
+
+impl From<String> for FuzzyQueryParams {
+    fn from(value: String) -> FuzzyQueryParams {
+        FuzzyQueryParams {
+            value,
+            fuzziness: Fuzziness::default(),
+            max_expansions: default_max_expansions(),
+            prefix_length: 0,
+            transpositions: default_transpositions(),
+            boost: None,
+        }
+    }
+}
+
+/// Maximum edit distance allowed, either as an explicit number or as `AUTO` or
+/// `AUTO:[low],[high]`, in which case the distance depends on the length of the value.
+#[derive(Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(try_from = "FuzzinessForSerialization")]
+enum Fuzziness {
+    Distance(u8),
+    Auto { low: usize, high: usize },
+}
+
+impl Default for Fuzziness {
+    fn default() -> Self {
+        Fuzziness::Auto { low: 3, high: 6 }
+    }
+}
+
+impl Fuzziness {
+    fn distance(&self, value: &str) -> u8 {
+        match *self {
+            Fuzziness::Distance(distance) => distance,
+            Fuzziness::Auto { low, high } => {
+                let num_chars = value.chars().count();
+                if num_chars < low {
+                    0
+                } else if num_chars < high {
+                    1
+                } else {
+                    2
+                }
+            }
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum FuzzinessForSerialization {
+    Number(u8),
+    Str(String),
+}
+
+impl TryFrom<FuzzinessForSerialization> for Fuzziness {
+    type Error = anyhow::Error;
+
+    fn try_from(fuzziness: FuzzinessForSerialization) -> anyhow::Result<Fuzziness> {
+        let fuzziness_str = match fuzziness {
+            FuzzinessForSerialization::Number(distance) => {
+                return Ok(Fuzziness::Distance(distance));
+            }
+            FuzzinessForSerialization::Str(fuzziness_str) => fuzziness_str,
+        };
+        let Some(auto_params) = fuzziness_str.strip_prefix("AUTO") else {
+            let distance: u8 = fuzziness_str
+                .parse()
+                .with_context(|| format!("invalid fuzziness `{fuzziness_str}`"))?;
+            return Ok(Fuzziness::Distance(distance));
+        };
+        if auto_params.is_empty() {
+            return Ok(Fuzziness::default());
+        }
+        let (low_str, high_str) = auto_params
+            .strip_prefix(':')
+            .and_then(|low_high| low_high.split_once(','))
+            .with_context(|| format!("invalid fuzziness `{fuzziness_str}`"))?;
+        let low: usize = low_str
+            .parse()
+            .with_context(|| format!("invalid fuzziness `{fuzziness_str}`"))?;
+        let high: usize = high_str
+            .parse()
+            .with_context(|| format!("invalid fuzziness `{fuzziness_str}`"))?;
+        Ok(Fuzziness::Auto { low, high })
+    }
+}
+
+impl From<OneFieldMap<StringOrStructForSerialization<FuzzyQueryParams>>> for FuzzyQuery {
+    fn from(one_field_map: OneFieldMap<StringOrStructForSerialization<FuzzyQueryParams>>) -> Self {
+        FuzzyQuery {
+            field: one_field_map.field,
+            params: one_field_map.value.inner,
+        }
+    }
+}
+
+impl From<FuzzyQuery> for ElasticQueryDslInner {
+    fn from(fuzzy_query: FuzzyQuery) -> Self {
+        Self::Fuzzy(fuzzy_query)
+    }
+}
+
+impl ConvertableToQueryAst for FuzzyQuery {
+    fn convert_to_query_ast(self) -> anyhow::Result<QueryAst> {
+        let FuzzyQueryParams {
+            value,
+            fuzziness,
+            max_expansions,
+            prefix_length,
+            transpositions,
+            boost,
+        } = self.params;
+        if prefix_length > 0 {
+            anyhow::bail!("`prefix_length` is not supported in fuzzy queries");
+        }
+        let distance = fuzziness.distance(&value);
+        let fuzzy_ast: QueryAst = query_ast::FuzzyQuery {
+            field: self.field,
+            value,
+            distance,
+            transposition_cost_one: transpositions,
+            prefix: false,
+            max_expansions,
+        }
+        .into();
+        Ok(fuzzy_ast.boost(boost))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_query_convert_to_query_ast() {
+        let fuzzy_query_json = r#"{
+            "user.id": {
+                "value": "ki",
+                "fuzziness": 1,
+                "transpositions": false,
+                "max_expansions": 10
+            }
+        }"#;
+        let fuzzy_query: FuzzyQuery = serde_json::from_str(fuzzy_query_json).unwrap();
+        let query_ast = fuzzy_query.convert_to_query_ast().unwrap();
+        assert_eq!(
+            query_ast,
+            QueryAst::Fuzzy(query_ast::FuzzyQuery {
+                field: "user.id".to_string(),
+                value: "ki".to_string(),
+                distance: 1,
+                transposition_cost_one: false,
+                prefix: false,
+                max_expansions: 10,
+            })
+        );
+    }
+
+    #[test]
+    fn test_fuzzy_query_prefix_length_unsupported() {
+        let fuzzy_query_json = r#"{ "user.id": { "value": "kimchy", "prefix_length": 2 } }"#;
+        let fuzzy_query: FuzzyQuery = serde_json::from_str(fuzzy_query_json).unwrap();
+        fuzzy_query.convert_to_query_ast().unwrap_err();
+    }
+
+    #[test]
+    fn test_fuzziness() {
+        let parse_fuzziness =
+            |fuzziness_json: &str| -> Fuzziness { serde_json::from_str(fuzziness_json).unwrap() };
+        assert_eq!(parse_fuzziness("2"), Fuzziness::Distance(2));
+        assert_eq!(parse_fuzziness(r#""1""#), Fuzziness::Distance(1));
+        assert_eq!(parse_fuzziness(r#""AUTO""#), Fuzziness::default());
+        assert_eq!(
+            parse_fuzziness(r#""AUTO:2,4""#),
+            Fuzziness::Auto { low: 2, high: 4 }
+        );
+        assert!(serde_json::from_str::<Fuzziness>(r#""AUTO:2""#).is_err());
+        assert!(serde_json::from_str::<Fuzziness>(r#""abc""#).is_err());
+
+        let auto_fuzziness = Fuzziness::default();
+        assert_eq!(auto_fuzziness.distance("ab"), 0);
+        assert_eq!(auto_fuzziness.distance("abc"), 1);
+        assert_eq!(auto_fuzziness.distance("abcde"), 1);
+        assert_eq!(auto_fuzziness.distance("abcdef"), 2);
+    }
+
+    #[test]
+    fn test_fuzzy_query_short_format() {
+        let fuzzy_query: FuzzyQuery = serde_json::from_str(r#"{ "user.id": "kimchy" }"#).unwrap();
+        let query_ast = fuzzy_query.convert_to_query_ast().unwrap();
+        let QueryAst::Fuzzy(fuzzy_query_ast) = query_ast else {
+            panic!()
+        };
+        assert_eq!(fuzzy_query_ast.distance, 2);
+        assert!(fuzzy_query_ast.transposition_cost_one);
+    }
+}
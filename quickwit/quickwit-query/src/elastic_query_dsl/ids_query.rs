@@ -0,0 +1,82 @@
+// Copyright (C) 2023 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::{BTreeSet, HashMap};
+
+use serde::Deserialize;
+
+use crate::elastic_query_dsl::{ConvertableToQueryAst, ElasticQueryDslInner};
+use crate::not_nan_f32::NotNaNf32;
+use crate::query_ast::{QueryAst, TermSetQuery};
+
+/// Quickwit does not assign IDs to documents, so the `ids` query matches the values of the
+/// `_id` field of the documents, if any.
+pub(crate) const ID_FIELD_NAME: &str = "_id";
+
+#[derive(Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct IdsQuery {
+    values: Vec<String>,
+    #[serde(default)]
+    boost: Option<NotNaNf32>,
+}
+
+impl From<IdsQuery> for ElasticQueryDslInner {
+    fn from(ids_query: IdsQuery) -> Self {
+        Self::Ids(ids_query)
+    }
+}
+
+impl ConvertableToQueryAst for IdsQuery {
+    fn convert_to_query_ast(self) -> anyhow::Result<QueryAst> {
+        if self.values.is_empty() {
+            return Ok(QueryAst::MatchNone);
+        }
+        let values: BTreeSet<String> = self.values.into_iter().collect();
+        let terms_per_field = HashMap::from([(ID_FIELD_NAME.to_string(), values)]);
+        let term_set_ast: QueryAst = TermSetQuery { terms_per_field }.into();
+        Ok(term_set_ast.boost(self.boost))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ids_query_convert_to_query_ast() {
+        let ids_query: IdsQuery = serde_json::from_str(r#"{ "values": ["1", "4"] }"#).unwrap();
+        let query_ast = ids_query.convert_to_query_ast().unwrap();
+        let QueryAst::TermSet(term_set_query) = query_ast else {
+            panic!()
+        };
+        let values: Vec<&str> = term_set_query.terms_per_field[ID_FIELD_NAME]
+            .iter()
+            .map(String::as_str)
+            .collect();
+        assert_eq!(values, ["1", "4"]);
+    }
+
+    #[test]
+    fn test_ids_query_empty() {
+        let ids_query: IdsQuery = serde_json::from_str(r#"{ "values": [] }"#).unwrap();
+        let query_ast = ids_query.convert_to_query_ast().unwrap();
+        assert_eq!(query_ast, QueryAst::MatchNone);
+    }
+}
@@ -0,0 +1,144 @@
+// Copyright (C) 2023 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use serde::Deserialize;
+
+use crate::elastic_query_dsl::one_field_map::OneFieldMap;
+use crate::elastic_query_dsl::{
+    ConvertableToQueryAst, ElasticQueryDslInner, StringOrStructForSerialization,
+};
+use crate::not_nan_f32::NotNaNf32;
+use crate::query_ast::{self, QueryAst, DEFAULT_AUTOMATON_MAX_EXPANSIONS};
+
+#[derive(Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(from = "OneFieldMap<StringOrStructForSerialization<PrefixQueryParams>>")]
+pub(crate) struct PrefixQuery {
+    pub field: String,
+    pub params: PrefixQueryParams,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct PrefixQueryParams {
+    value: String,
+    #[serde(default)]
+    case_insensitive: bool,
+    #[serde(default)]
+    boost: Option<NotNaNf32>,
+}
+
+impl From<String> for PrefixQueryParams {
+    fn from(value: String) -> PrefixQueryParams {
+        PrefixQueryParams {
+            value,
+            case_insensitive: false,
+            boost: None,
+        }
+    }
+}
+
+impl From<OneFieldMap<StringOrStructForSerialization<PrefixQueryParams>>> for PrefixQuery {
+    fn from(one_field_map: OneFieldMap<StringOrStructForSerialization<PrefixQueryParams>>) -> Self {
+        PrefixQuery {
+            field: one_field_map.field,
+            params: one_field_map.value.inner,
+        }
+    }
+}
+
+impl From<PrefixQuery> for ElasticQueryDslInner {
+    fn from(prefix_query: PrefixQuery) -> Self {
+        Self::Prefix(prefix_query)
+    }
+}
+
+impl ConvertableToQueryAst for PrefixQuery {
+    fn convert_to_query_ast(self) -> anyhow::Result<QueryAst> {
+        let PrefixQueryParams {
+            value,
+            case_insensitive,
+            boost,
+        } = self.params;
+        let prefix_query = query_ast::PrefixQuery {
+            field: self.field,
+            prefix: value,
+            max_expansions: DEFAULT_AUTOMATON_MAX_EXPANSIONS,
+        };
+        let prefix_ast: QueryAst = if case_insensitive {
+            query_ast::RegexQuery {
+                field: prefix_query.field.clone(),
+                regex: format!("(?i){}", prefix_query.to_regex()),
+                max_expansions: DEFAULT_AUTOMATON_MAX_EXPANSIONS,
+            }
+            .into()
+        } else {
+            prefix_query.into()
+        };
+        Ok(prefix_ast.boost(boost))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prefix_query_convert_to_query_ast() {
+        let prefix_query_json = r#"{ "user.id": { "value": "ki", "boost": 2.0 } }"#;
+        let prefix_query: PrefixQuery = serde_json::from_str(prefix_query_json).unwrap();
+        let query_ast = prefix_query.convert_to_query_ast().unwrap();
+        let QueryAst::Boost { underlying, boost } = query_ast else {
+            panic!()
+        };
+        assert_eq!(boost, NotNaNf32::try_from(2.0).unwrap());
+        assert_eq!(
+            *underlying,
+            QueryAst::Prefix(query_ast::PrefixQuery {
+                field: "user.id".to_string(),
+                prefix: "ki".to_string(),
+                max_expansions: DEFAULT_AUTOMATON_MAX_EXPANSIONS,
+            })
+        );
+    }
+
+    #[test]
+    fn test_prefix_query_case_insensitive() {
+        let prefix_query_json = r#"{ "user.id": { "value": "Ki", "case_insensitive": true } }"#;
+        let prefix_query: PrefixQuery = serde_json::from_str(prefix_query_json).unwrap();
+        let query_ast = prefix_query.convert_to_query_ast().unwrap();
+        assert_eq!(
+            query_ast,
+            QueryAst::Regex(query_ast::RegexQuery {
+                field: "user.id".to_string(),
+                regex: "(?i)Ki(?s:.*)".to_string(),
+                max_expansions: DEFAULT_AUTOMATON_MAX_EXPANSIONS,
+            })
+        );
+    }
+
+    #[test]
+    fn test_prefix_query_short_format() {
+        let prefix_query: PrefixQuery = serde_json::from_str(r#"{ "user.id": "ki" }"#).unwrap();
+        assert_eq!(prefix_query.field, "user.id");
+        assert_eq!(
+            prefix_query.params,
+            PrefixQueryParams::from("ki".to_string())
+        );
+    }
+}
@@ -0,0 +1,131 @@
+// Copyright (C) 2023 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use serde::Deserialize;
+
+use crate::elastic_query_dsl::one_field_map::OneFieldMap;
+use crate::elastic_query_dsl::{
+    ConvertableToQueryAst, ElasticQueryDslInner, StringOrStructForSerialization,
+};
+use crate::not_nan_f32::NotNaNf32;
+use crate::query_ast::{self, QueryAst, DEFAULT_AUTOMATON_MAX_EXPANSIONS};
+
+#[derive(Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(from = "OneFieldMap<StringOrStructForSerialization<RegexpQueryParams>>")]
+pub(crate) struct RegexpQuery {
+    pub field: String,
+    pub params: RegexpQueryParams,
+}
+
+/// The regular expression follows the syntax of the Rust `regex` crate, which does not support
+/// the Lucene optional operators (`@`, `&`, `~` and `<>` intervals).
+#[derive(Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct RegexpQueryParams {
+    value: String,
+    // The optional operators are not supported, we include these properties here just to accept
+    // user queries containing them.
+    #[serde(default, rename = "flags")]
+    _flags: Option<String>,
+    #[serde(default, rename = "max_determinized_states")]
+    _max_determinized_states: Option<u64>,
+    #[serde(default)]
+    case_insensitive: bool,
+    #[serde(default)]
+    boost: Option<NotNaNf32>,
+}
+
+impl From<String> for RegexpQueryParams {
+    fn from(value: String) -> RegexpQueryParams {
+        RegexpQueryParams {
+            value,
+            _flags: None,
+            _max_determinized_states: None,
+            case_insensitive: false,
+            boost: None,
+        }
+    }
+}
+
+impl From<OneFieldMap<StringOrStructForSerialization<RegexpQueryParams>>> for RegexpQuery {
+    fn from(one_field_map: OneFieldMap<StringOrStructForSerialization<RegexpQueryParams>>) -> Self {
+        RegexpQuery {
+            field: one_field_map.field,
+            params: one_field_map.value.inner,
+        }
+    }
+}
+
+impl From<RegexpQuery> for ElasticQueryDslInner {
+    fn from(regexp_query: RegexpQuery) -> Self {
+        Self::Regexp(regexp_query)
+    }
+}
+
+impl ConvertableToQueryAst for RegexpQuery {
+    fn convert_to_query_ast(self) -> anyhow::Result<QueryAst> {
+        let regex = if self.params.case_insensitive {
+            format!("(?i){}", self.params.value)
+        } else {
+            self.params.value
+        };
+        let regex_ast: QueryAst = query_ast::RegexQuery {
+            field: self.field,
+            regex,
+            max_expansions: DEFAULT_AUTOMATON_MAX_EXPANSIONS,
+        }
+        .into();
+        Ok(regex_ast.boost(self.params.boost))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_regexp_query_convert_to_query_ast() {
+        let regexp_query_json = r#"{ "user.id": { "value": "k.*y", "flags": "ALL" } }"#;
+        let regexp_query: RegexpQuery = serde_json::from_str(regexp_query_json).unwrap();
+        let query_ast = regexp_query.convert_to_query_ast().unwrap();
+        assert_eq!(
+            query_ast,
+            QueryAst::Regex(query_ast::RegexQuery {
+                field: "user.id".to_string(),
+                regex: "k.*y".to_string(),
+                max_expansions: DEFAULT_AUTOMATON_MAX_EXPANSIONS,
+            })
+        );
+    }
+
+    #[test]
+    fn test_regexp_query_case_insensitive() {
+        let regexp_query_json = r#"{ "user.id": { "value": "k.*y", "case_insensitive": true } }"#;
+        let regexp_query: RegexpQuery = serde_json::from_str(regexp_query_json).unwrap();
+        let query_ast = regexp_query.convert_to_query_ast().unwrap();
+        assert_eq!(
+            query_ast,
+            QueryAst::Regex(query_ast::RegexQuery {
+                field: "user.id".to_string(),
+                regex: "(?i)k.*y".to_string(),
+                max_expansions: DEFAULT_AUTOMATON_MAX_EXPANSIONS,
+            })
+        );
+    }
+}
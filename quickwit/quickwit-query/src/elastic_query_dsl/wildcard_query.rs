@@ -0,0 +1,134 @@
+// Copyright (C) 2023 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use serde::Deserialize;
+
+use crate::elastic_query_dsl::one_field_map::OneFieldMap;
+use crate::elastic_query_dsl::{
+    ConvertableToQueryAst, ElasticQueryDslInner, StringOrStructForSerialization,
+};
+use crate::not_nan_f32::NotNaNf32;
+use crate::query_ast::{self, QueryAst, DEFAULT_AUTOMATON_MAX_EXPANSIONS};
+
+#[derive(Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(from = "OneFieldMap<StringOrStructForSerialization<WildcardQueryParams>>")]
+pub(crate) struct WildcardQuery {
+    pub field: String,
+    pub params: WildcardQueryParams,
+}
+
+/// The pattern can be passed either as `value` or as `wildcard`.
+#[derive(Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct WildcardQueryParams {
+    #[serde(alias = "wildcard")]
+    value: String,
+    #[serde(default)]
+    case_insensitive: bool,
+    #[serde(default)]
+    boost: Option<NotNaNf32>,
+}
+
+impl From<String> for WildcardQueryParams {
+    fn from(value: String) -> WildcardQueryParams {
+        WildcardQueryParams {
+            value,
+            case_insensitive: false,
+            boost: None,
+        }
+    }
+}
+
+impl From<OneFieldMap<StringOrStructForSerialization<WildcardQueryParams>>> for WildcardQuery {
+    fn from(
+        one_field_map: OneFieldMap<StringOrStructForSerialization<WildcardQueryParams>>,
+    ) -> Self {
+        WildcardQuery {
+            field: one_field_map.field,
+            params: one_field_map.value.inner,
+        }
+    }
+}
+
+impl From<WildcardQuery> for ElasticQueryDslInner {
+    fn from(wildcard_query: WildcardQuery) -> Self {
+        Self::Wildcard(wildcard_query)
+    }
+}
+
+impl ConvertableToQueryAst for WildcardQuery {
+    fn convert_to_query_ast(self) -> anyhow::Result<QueryAst> {
+        let WildcardQueryParams {
+            value,
+            case_insensitive,
+            boost,
+        } = self.params;
+        let wildcard_query = query_ast::WildcardQuery {
+            field: self.field,
+            value,
+            max_expansions: DEFAULT_AUTOMATON_MAX_EXPANSIONS,
+        };
+        let wildcard_ast: QueryAst = if case_insensitive {
+            query_ast::RegexQuery {
+                field: wildcard_query.field.clone(),
+                regex: format!("(?i){}", wildcard_query.to_regex()),
+                max_expansions: DEFAULT_AUTOMATON_MAX_EXPANSIONS,
+            }
+            .into()
+        } else {
+            wildcard_query.into()
+        };
+        Ok(wildcard_ast.boost(boost))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wildcard_query_convert_to_query_ast() {
+        let wildcard_query_json = r#"{ "user.id": { "wildcard": "ki*y" } }"#;
+        let wildcard_query: WildcardQuery = serde_json::from_str(wildcard_query_json).unwrap();
+        let query_ast = wildcard_query.convert_to_query_ast().unwrap();
+        assert_eq!(
+            query_ast,
+            QueryAst::Wildcard(query_ast::WildcardQuery {
+                field: "user.id".to_string(),
+                value: "ki*y".to_string(),
+                max_expansions: DEFAULT_AUTOMATON_MAX_EXPANSIONS,
+            })
+        );
+    }
+
+    #[test]
+    fn test_wildcard_query_case_insensitive() {
+        let wildcard_query_json = r#"{ "user.id": { "value": "Ki?y", "case_insensitive": true } }"#;
+        let wildcard_query: WildcardQuery = serde_json::from_str(wildcard_query_json).unwrap();
+        let query_ast = wildcard_query.convert_to_query_ast().unwrap();
+        assert_eq!(
+            query_ast,
+            QueryAst::Regex(query_ast::RegexQuery {
+                field: "user.id".to_string(),
+                regex: "(?i)(?s)Ki.y".to_string(),
+                max_expansions: DEFAULT_AUTOMATON_MAX_EXPANSIONS,
+            })
+        );
+    }
+}
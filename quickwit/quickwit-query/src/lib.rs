@@ -33,6 +33,7 @@ mod error;
 mod json_literal;
 mod not_nan_f32;
 pub mod query_ast;
+pub mod sql;
 pub mod tokenizers;
 
 pub use elastic_query_dsl::{ElasticQueryDsl, OneFieldMap};
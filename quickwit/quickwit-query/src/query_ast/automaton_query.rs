@@ -0,0 +1,507 @@
+// Copyright (C) 2023 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::{fmt, io};
+
+use levenshtein_automata::{Distance, LevenshteinAutomatonBuilder, DFA, SINK_STATE};
+use once_cell::sync::Lazy;
+use tantivy::query::{EnableScoring, Explanation, Query, Scorer, TermSetQuery, Weight};
+use tantivy::schema::Field;
+use tantivy::{DocId, InvertedIndexReader, Score, SegmentReader, Term};
+use tantivy_fst::{Automaton, Regex};
+
+use crate::query_ast::MAX_FUZZY_DISTANCE;
+use crate::InvalidQuery;
+
+/// Default maximum number of terms a prefix, wildcard, regex or fuzzy query expands to in a
+/// segment.
+pub const DEFAULT_AUTOMATON_MAX_EXPANSIONS: u32 = 10_000;
+
+pub(crate) fn default_automaton_max_expansions() -> u32 {
+    DEFAULT_AUTOMATON_MAX_EXPANSIONS
+}
+
+// Building a Levenshtein automaton builder is expensive, so we build them once and for all.
+static LEVENSHTEIN_BUILDERS: Lazy<HashMap<(u8, bool), LevenshteinAutomatonBuilder>> =
+    Lazy::new(|| {
+        let mut levenshtein_builders = HashMap::new();
+        for distance in 0..=MAX_FUZZY_DISTANCE {
+            for transposition_cost_one in [false, true] {
+                let levenshtein_builder =
+                    LevenshteinAutomatonBuilder::new(distance, transposition_cost_one);
+                levenshtein_builders
+                    .insert((distance, transposition_cost_one), levenshtein_builder);
+            }
+        }
+        levenshtein_builders
+    });
+
+/// Automaton matching the terms accepted by a regular expression or within a Levenshtein
+/// distance of a value.
+enum TermAutomaton {
+    Regex(Regex),
+    Levenshtein(DFA),
+}
+
+#[derive(Clone)]
+enum TermAutomatonState {
+    Regex(Option<usize>),
+    Levenshtein(u32),
+}
+
+impl TermAutomaton {
+    pub fn regex(regex: &str) -> Result<TermAutomaton, InvalidQuery> {
+        let regex = Regex::new(regex).map_err(|error| InvalidQuery::InvalidRegex {
+            regex: regex.to_string(),
+            error: error.to_string(),
+        })?;
+        Ok(TermAutomaton::Regex(regex))
+    }
+
+    /// Returns an automaton matching the terms within `distance` edits of `value` or, if
+    /// `prefix` is true, the terms starting with a sequence within `distance` edits of `value`.
+    ///
+    /// The distance must be lower or equal to [`MAX_FUZZY_DISTANCE`].
+    pub fn levenshtein(
+        value: &str,
+        distance: u8,
+        transposition_cost_one: bool,
+        prefix: bool,
+    ) -> TermAutomaton {
+        let levenshtein_builder = &LEVENSHTEIN_BUILDERS[&(distance, transposition_cost_one)];
+        let dfa = if prefix {
+            levenshtein_builder.build_prefix_dfa(value)
+        } else {
+            levenshtein_builder.build_dfa(value)
+        };
+        TermAutomaton::Levenshtein(dfa)
+    }
+}
+
+impl Automaton for TermAutomaton {
+    type State = TermAutomatonState;
+
+    fn start(&self) -> TermAutomatonState {
+        match self {
+            TermAutomaton::Regex(regex) => TermAutomatonState::Regex(regex.start()),
+            TermAutomaton::Levenshtein(dfa) => TermAutomatonState::Levenshtein(dfa.initial_state()),
+        }
+    }
+
+    fn is_match(&self, state: &TermAutomatonState) -> bool {
+        match (self, state) {
+            (TermAutomaton::Regex(regex), TermAutomatonState::Regex(regex_state)) => {
+                regex.is_match(regex_state)
+            }
+            (TermAutomaton::Levenshtein(dfa), TermAutomatonState::Levenshtein(dfa_state)) => {
+                matches!(dfa.distance(*dfa_state), Distance::Exact(_))
+            }
+            _ => false,
+        }
+    }
+
+    fn can_match(&self, state: &TermAutomatonState) -> bool {
+        match (self, state) {
+            (TermAutomaton::Regex(regex), TermAutomatonState::Regex(regex_state)) => {
+                regex.can_match(regex_state)
+            }
+            (TermAutomaton::Levenshtein(_), TermAutomatonState::Levenshtein(dfa_state)) => {
+                *dfa_state != SINK_STATE
+            }
+            _ => false,
+        }
+    }
+
+    fn accept(&self, state: &TermAutomatonState, byte: u8) -> TermAutomatonState {
+        match (self, state) {
+            (TermAutomaton::Regex(regex), TermAutomatonState::Regex(regex_state)) => {
+                TermAutomatonState::Regex(regex.accept(regex_state, byte))
+            }
+            (TermAutomaton::Levenshtein(dfa), TermAutomatonState::Levenshtein(dfa_state)) => {
+                TermAutomatonState::Levenshtein(dfa.transition(*dfa_state, byte))
+            }
+            _ => state.clone(),
+        }
+    }
+}
+
+/// Automaton matching the terms made of `prefix` followed by a sequence accepted by the inner
+/// automaton.
+///
+/// This is used to run an automaton against the values of a JSON path, which are stored in the
+/// term dictionary behind the path and value type, and to match the terms starting with a
+/// prefix.
+struct PrefixedAutomaton {
+    prefix: Vec<u8>,
+    automaton: TermAutomaton,
+}
+
+#[derive(Clone)]
+enum PrefixedAutomatonState {
+    Prefix(usize),
+    Inner(TermAutomatonState),
+    Dead,
+}
+
+impl Automaton for PrefixedAutomaton {
+    type State = PrefixedAutomatonState;
+
+    fn start(&self) -> PrefixedAutomatonState {
+        if self.prefix.is_empty() {
+            PrefixedAutomatonState::Inner(self.automaton.start())
+        } else {
+            PrefixedAutomatonState::Prefix(0)
+        }
+    }
+
+    fn is_match(&self, state: &PrefixedAutomatonState) -> bool {
+        match state {
+            PrefixedAutomatonState::Inner(inner_state) => self.automaton.is_match(inner_state),
+            PrefixedAutomatonState::Prefix(_) | PrefixedAutomatonState::Dead => false,
+        }
+    }
+
+    fn can_match(&self, state: &PrefixedAutomatonState) -> bool {
+        match state {
+            PrefixedAutomatonState::Prefix(_) => true,
+            PrefixedAutomatonState::Inner(inner_state) => self.automaton.can_match(inner_state),
+            PrefixedAutomatonState::Dead => false,
+        }
+    }
+
+    fn accept(&self, state: &PrefixedAutomatonState, byte: u8) -> PrefixedAutomatonState {
+        match state {
+            PrefixedAutomatonState::Prefix(num_matched_bytes) => {
+                if self.prefix[*num_matched_bytes] != byte {
+                    PrefixedAutomatonState::Dead
+                } else if num_matched_bytes + 1 == self.prefix.len() {
+                    PrefixedAutomatonState::Inner(self.automaton.start())
+                } else {
+                    PrefixedAutomatonState::Prefix(num_matched_bytes + 1)
+                }
+            }
+            PrefixedAutomatonState::Inner(inner_state) => {
+                PrefixedAutomatonState::Inner(self.automaton.accept(inner_state, byte))
+            }
+            PrefixedAutomatonState::Dead => PrefixedAutomatonState::Dead,
+        }
+    }
+}
+
+/// Terms a prefix, wildcard, regex or fuzzy query expands to in a segment.
+///
+/// Unlike tantivy's regex and fuzzy term queries, the number of terms is capped: only the first
+/// `max_expansions` matching terms in lexicographic order are searched. This lets the search
+/// warm up the postings of exactly these terms before running the query.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TermExpansion {
+    pub field: Field,
+    pub pattern: TermPattern,
+    pub max_expansions: u32,
+}
+
+/// Pattern of the terms a [`TermExpansion`] expands to.
+///
+/// The values of a JSON path are stored in the term dictionary behind the path and value type:
+/// for JSON fields, `term_prefix` holds these bytes, and the prefix term includes them.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TermPattern {
+    /// The terms starting with the value of the term.
+    Prefix(Term),
+    /// The terms made of `term_prefix` followed by a sequence matching `regex`.
+    Regex { term_prefix: Vec<u8>, regex: String },
+    /// The terms made of `term_prefix` followed by a sequence within `distance` edits of
+    /// `value` or, if `prefix` is true, starting with such a sequence.
+    Fuzzy {
+        term_prefix: Vec<u8>,
+        value: String,
+        distance: u8,
+        transposition_cost_one: bool,
+        prefix: bool,
+    },
+}
+
+impl TermExpansion {
+    fn automaton(&self) -> Result<PrefixedAutomaton, InvalidQuery> {
+        let (prefix, automaton) = match &self.pattern {
+            TermPattern::Prefix(term) => (
+                term.serialized_value_bytes().to_vec(),
+                TermAutomaton::regex("(?s:.*)")?,
+            ),
+            TermPattern::Regex { term_prefix, regex } => {
+                (term_prefix.clone(), TermAutomaton::regex(regex)?)
+            }
+            TermPattern::Fuzzy {
+                term_prefix,
+                value,
+                distance,
+                transposition_cost_one,
+                prefix,
+            } => {
+                if *distance > MAX_FUZZY_DISTANCE {
+                    return Err(InvalidQuery::Other(anyhow::anyhow!(
+                        "fuzzy distance must be lower or equal to {MAX_FUZZY_DISTANCE}, got \
+                         {distance}"
+                    )));
+                }
+                let automaton =
+                    TermAutomaton::levenshtein(value, *distance, *transposition_cost_one, *prefix);
+                (term_prefix.clone(), automaton)
+            }
+        };
+        Ok(PrefixedAutomaton { prefix, automaton })
+    }
+
+    /// Returns the terms of the segment matching the pattern, up to `max_expansions`.
+    ///
+    /// With a split index, the term dictionary of the field (or the term range of a prefix
+    /// pattern) has to be warmed up first.
+    pub fn expand(&self, inverted_index: &InvertedIndexReader) -> io::Result<Vec<Term>> {
+        let automaton = self
+            .automaton()
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error.to_string()))?;
+        self.expand_with_automaton(&automaton, inverted_index)
+    }
+
+    fn expand_with_automaton(
+        &self,
+        automaton: &PrefixedAutomaton,
+        inverted_index: &InvertedIndexReader,
+    ) -> io::Result<Vec<Term>> {
+        let term_dict = inverted_index.terms();
+        let mut term_stream_builder = term_dict.search(automaton);
+        // Restricting the stream to the terms starting with the prefix avoids reading the
+        // dictionary blocks of the other terms.
+        if !automaton.prefix.is_empty() {
+            term_stream_builder = term_stream_builder.ge(&automaton.prefix);
+            if let Some(prefix_end) = prefix_end_bound(&automaton.prefix) {
+                term_stream_builder = term_stream_builder.lt(prefix_end);
+            }
+        }
+        let mut term_stream = term_stream_builder.into_stream()?;
+        let mut terms = Vec::new();
+        while terms.len() < self.max_expansions as usize && term_stream.advance() {
+            terms.push(Term::from_field_bytes(self.field, term_stream.key()));
+        }
+        Ok(terms)
+    }
+}
+
+/// Returns the smallest key greater than all the keys starting with `prefix`, if any.
+fn prefix_end_bound(prefix: &[u8]) -> Option<Vec<u8>> {
+    let mut end_bound = prefix.to_vec();
+    while let Some(last_byte) = end_bound.pop() {
+        if last_byte != u8::MAX {
+            end_bound.push(last_byte + 1);
+            return Some(end_bound);
+        }
+    }
+    None
+}
+
+/// Query matching the documents containing one of the terms a [`TermExpansion`] expands to.
+///
+/// In each segment, the terms are expanded and searched with tantivy's term set query.
+#[derive(Clone)]
+pub(crate) struct AutomatonQuery {
+    term_expansion: TermExpansion,
+    automaton: Arc<PrefixedAutomaton>,
+}
+
+impl AutomatonQuery {
+    pub fn new(term_expansion: TermExpansion) -> Result<AutomatonQuery, InvalidQuery> {
+        let automaton = term_expansion.automaton()?;
+        Ok(AutomatonQuery {
+            term_expansion,
+            automaton: Arc::new(automaton),
+        })
+    }
+
+    fn expanded_weight(&self, reader: &SegmentReader) -> tantivy::Result<Box<dyn Weight>> {
+        let inverted_index = reader.inverted_index(self.term_expansion.field)?;
+        let terms = self
+            .term_expansion
+            .expand_with_automaton(&self.automaton, &inverted_index)?;
+        TermSetQuery::new(terms).weight(EnableScoring::disabled_from_schema(reader.schema()))
+    }
+}
+
+impl fmt::Debug for AutomatonQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AutomatonQuery")
+            .field("term_expansion", &self.term_expansion)
+            .finish()
+    }
+}
+
+impl Query for AutomatonQuery {
+    fn weight(&self, _enable_scoring: EnableScoring<'_>) -> tantivy::Result<Box<dyn Weight>> {
+        Ok(Box::new(self.clone()))
+    }
+}
+
+impl Weight for AutomatonQuery {
+    fn scorer(&self, reader: &SegmentReader, boost: Score) -> tantivy::Result<Box<dyn Scorer>> {
+        self.expanded_weight(reader)?.scorer(reader, boost)
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> tantivy::Result<Explanation> {
+        self.expanded_weight(reader)?.explain(reader, doc)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tantivy::collector::Count;
+    use tantivy::schema::{Schema, TEXT};
+    use tantivy::{doc, Index, IndexWriter};
+
+    use super::*;
+
+    fn count_matching_docs(texts: &[&str], pattern: TermPattern, max_expansions: u32) -> usize {
+        let mut schema_builder = Schema::builder();
+        let body = schema_builder.add_text_field("body", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_with_num_threads(1, 15_000_000).unwrap();
+        for text in texts {
+            index_writer.add_document(doc!(body => *text)).unwrap();
+        }
+        index_writer.commit().unwrap();
+        let searcher = index.reader().unwrap().searcher();
+        let term_expansion = TermExpansion {
+            field: body,
+            pattern,
+            max_expansions,
+        };
+        let query = AutomatonQuery::new(term_expansion).unwrap();
+        searcher.search(&query, &Count).unwrap()
+    }
+
+    fn regex_pattern(regex: &str) -> TermPattern {
+        TermPattern::Regex {
+            term_prefix: Vec::new(),
+            regex: regex.to_string(),
+        }
+    }
+
+    fn fuzzy_pattern(
+        value: &str,
+        distance: u8,
+        transposition_cost_one: bool,
+        prefix: bool,
+    ) -> TermPattern {
+        TermPattern::Fuzzy {
+            term_prefix: Vec::new(),
+            value: value.to_string(),
+            distance,
+            transposition_cost_one,
+            prefix,
+        }
+    }
+
+    #[test]
+    fn test_automaton_query_regex() {
+        let texts = ["quick fox", "quack duck", "quiet cat", "slow dog"];
+        assert_eq!(
+            count_matching_docs(&texts, regex_pattern("qu[a-z]+k"), 10),
+            2
+        );
+    }
+
+    #[test]
+    fn test_automaton_query_levenshtein() {
+        let texts = ["quick fox", "quikc duck", "quiet cat", "slow dog"];
+        let pattern = fuzzy_pattern("quick", 1, true, false);
+        assert_eq!(count_matching_docs(&texts, pattern, 10), 2);
+        let pattern = fuzzy_pattern("quick", 1, false, false);
+        assert_eq!(count_matching_docs(&texts, pattern, 10), 1);
+        let pattern = fuzzy_pattern("qui", 0, false, true);
+        assert_eq!(count_matching_docs(&texts, pattern, 10), 3);
+    }
+
+    #[test]
+    fn test_automaton_query_prefix() {
+        let texts = ["quick fox", "quack duck", "quiet cat", "slow dog"];
+        let body = Field::from_field_id(0);
+        let pattern = TermPattern::Prefix(Term::from_field_text(body, "qui"));
+        assert_eq!(count_matching_docs(&texts, pattern, 10), 2);
+        let pattern = TermPattern::Prefix(Term::from_field_text(body, "qui"));
+        assert_eq!(count_matching_docs(&texts, pattern, 1), 1);
+    }
+
+    #[test]
+    fn test_automaton_query_max_expansions() {
+        let texts = ["aa", "ab", "ac", "ad", "ab ac"];
+        assert_eq!(count_matching_docs(&texts, regex_pattern("a."), 10), 5);
+        // Only `aa` and `ab` are searched.
+        assert_eq!(count_matching_docs(&texts, regex_pattern("a."), 2), 3);
+        assert_eq!(count_matching_docs(&texts, regex_pattern("a."), 0), 0);
+    }
+
+    #[test]
+    fn test_automaton_query_invalid_pattern() {
+        let term_expansion = TermExpansion {
+            field: Field::from_field_id(0),
+            pattern: regex_pattern("qu[a-z"),
+            max_expansions: 10,
+        };
+        let error = AutomatonQuery::new(term_expansion).unwrap_err();
+        assert!(matches!(error, InvalidQuery::InvalidRegex { .. }));
+
+        let term_expansion = TermExpansion {
+            field: Field::from_field_id(0),
+            pattern: fuzzy_pattern("quick", MAX_FUZZY_DISTANCE + 1, true, false),
+            max_expansions: 10,
+        };
+        AutomatonQuery::new(term_expansion).unwrap_err();
+    }
+
+    #[test]
+    fn test_prefix_end_bound() {
+        assert_eq!(prefix_end_bound(b"ab"), Some(b"ac".to_vec()));
+        assert_eq!(prefix_end_bound(&[b'a', u8::MAX]), Some(b"b".to_vec()));
+        assert_eq!(prefix_end_bound(&[u8::MAX, u8::MAX]), None);
+        assert_eq!(prefix_end_bound(b""), None);
+    }
+
+    #[test]
+    fn test_prefixed_automaton() {
+        let automaton = PrefixedAutomaton {
+            prefix: b"ab".to_vec(),
+            automaton: TermAutomaton::regex("c+").unwrap(),
+        };
+        let matches = |term: &[u8]| {
+            let mut state = automaton.start();
+            for byte in term {
+                if !automaton.can_match(&state) {
+                    return false;
+                }
+                state = automaton.accept(&state, *byte);
+            }
+            automaton.is_match(&state)
+        };
+        assert!(matches(b"abc"));
+        assert!(matches(b"abccc"));
+        assert!(!matches(b"ab"));
+        assert!(!matches(b"ac"));
+        assert!(!matches(b"abcd"));
+    }
+}
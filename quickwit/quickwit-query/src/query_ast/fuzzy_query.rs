@@ -0,0 +1,149 @@
+// Copyright (C) 2023 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use serde::{Deserialize, Serialize};
+use tantivy::schema::Schema as TantivySchema;
+use tantivy::tokenizer::TokenizerManager;
+
+use crate::query_ast::automaton_query::{
+    default_automaton_max_expansions, AutomatonQuery, TermExpansion, TermPattern,
+};
+use crate::query_ast::tantivy_query_ast::TantivyQueryAst;
+use crate::query_ast::utils::untokenized_text_term;
+use crate::query_ast::{BuildTantivyAst, QueryAst};
+use crate::InvalidQuery;
+
+/// Maximum Levenshtein distance supported by fuzzy queries.
+pub const MAX_FUZZY_DISTANCE: u8 = 2;
+
+/// Matches documents containing a term within a Levenshtein distance of `value`.
+///
+/// The value is not tokenized.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyQuery {
+    pub field: String,
+    pub value: String,
+    pub distance: u8,
+    /// If true, a transposition of two adjacent characters counts as one edit instead of two.
+    #[serde(default = "default_transposition_cost_one")]
+    pub transposition_cost_one: bool,
+    /// If true, `value` is matched as a fuzzy prefix of the terms.
+    #[serde(default)]
+    pub prefix: bool,
+    /// Maximum number of terms the query expands to in each segment.
+    #[serde(default = "default_automaton_max_expansions")]
+    pub max_expansions: u32,
+}
+
+fn default_transposition_cost_one() -> bool {
+    true
+}
+
+impl From<FuzzyQuery> for QueryAst {
+    fn from(fuzzy_query: FuzzyQuery) -> Self {
+        QueryAst::Fuzzy(fuzzy_query)
+    }
+}
+
+impl FuzzyQuery {
+    /// Returns the terms the query expands to in each segment.
+    pub fn term_expansion(&self, schema: &TantivySchema) -> Result<TermExpansion, InvalidQuery> {
+        let term = untokenized_text_term(&self.field, schema, "fuzzy", "")?;
+        Ok(TermExpansion {
+            field: term.field(),
+            pattern: TermPattern::Fuzzy {
+                term_prefix: term.serialized_value_bytes().to_vec(),
+                value: self.value.clone(),
+                distance: self.distance,
+                transposition_cost_one: self.transposition_cost_one,
+                prefix: self.prefix,
+            },
+            max_expansions: self.max_expansions,
+        })
+    }
+}
+
+impl BuildTantivyAst for FuzzyQuery {
+    fn build_tantivy_ast_impl(
+        &self,
+        schema: &TantivySchema,
+        _tokenizer_manager: &TokenizerManager,
+        _search_fields: &[String],
+        _with_validation: bool,
+    ) -> Result<TantivyQueryAst, InvalidQuery> {
+        let term_expansion = self.term_expansion(schema)?;
+        Ok(AutomatonQuery::new(term_expansion)?.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tantivy::schema::{Schema, TEXT};
+
+    use super::FuzzyQuery;
+    use crate::create_default_quickwit_tokenizer_manager;
+    use crate::query_ast::automaton_query::DEFAULT_AUTOMATON_MAX_EXPANSIONS;
+    use crate::query_ast::{BuildTantivyAst, QueryAst};
+
+    #[test]
+    fn test_fuzzy_query_deserialization_defaults() {
+        let query_ast: QueryAst = serde_json::from_str(
+            r#"{"type": "fuzzy", "field": "body", "value": "quikc", "distance": 1}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            query_ast,
+            QueryAst::Fuzzy(FuzzyQuery {
+                field: "body".to_string(),
+                value: "quikc".to_string(),
+                distance: 1,
+                transposition_cost_one: true,
+                prefix: false,
+                max_expansions: DEFAULT_AUTOMATON_MAX_EXPANSIONS,
+            })
+        );
+    }
+
+    #[test]
+    fn test_fuzzy_query_max_distance() {
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_text_field("body", TEXT);
+        let schema = schema_builder.build();
+        let tokenizer_manager = create_default_quickwit_tokenizer_manager();
+        let mut fuzzy_query = FuzzyQuery {
+            field: "body".to_string(),
+            value: "quikc".to_string(),
+            distance: 2,
+            transposition_cost_one: true,
+            prefix: false,
+            max_expansions: 50,
+        };
+        let tantivy_query_ast = fuzzy_query
+            .build_tantivy_ast_call(&schema, &tokenizer_manager, &[], true)
+            .unwrap();
+        let leaf = tantivy_query_ast.as_leaf().unwrap();
+        assert!(format!("{leaf:?}").starts_with("AutomatonQuery"));
+
+        fuzzy_query.distance = 3;
+        let error = fuzzy_query
+            .build_tantivy_ast_call(&schema, &tokenizer_manager, &[], true)
+            .unwrap_err();
+        assert!(error.to_string().contains("fuzzy distance"));
+    }
+}
@@ -22,28 +22,38 @@ use tantivy::query::BoostQuery as TantivyBoostQuery;
 use tantivy::schema::Schema as TantivySchema;
 use tantivy::tokenizer::TokenizerManager;
 
+mod automaton_query;
 mod bool_query;
 mod field_presence;
 mod full_text_query;
+mod fuzzy_query;
 mod phrase_prefix_query;
+mod prefix_query;
 mod range_query;
+mod regex_query;
 mod tantivy_query_ast;
 mod term_query;
 mod term_set_query;
 mod user_input_query;
 pub(crate) mod utils;
 mod visitor;
+mod wildcard_query;
 
+pub use automaton_query::{TermExpansion, TermPattern, DEFAULT_AUTOMATON_MAX_EXPANSIONS};
 pub use bool_query::BoolQuery;
 pub use field_presence::FieldPresenceQuery;
 pub use full_text_query::{FullTextMode, FullTextParams, FullTextQuery};
+pub use fuzzy_query::{FuzzyQuery, MAX_FUZZY_DISTANCE};
 pub use phrase_prefix_query::PhrasePrefixQuery;
+pub use prefix_query::PrefixQuery;
 pub use range_query::RangeQuery;
+pub use regex_query::RegexQuery;
 use tantivy_query_ast::TantivyQueryAst;
 pub use term_query::TermQuery;
 pub use term_set_query::TermSetQuery;
 pub use user_input_query::UserInputQuery;
 pub use visitor::QueryAstVisitor;
+pub use wildcard_query::WildcardQuery;
 
 use crate::{BooleanOperand, InvalidQuery, NotNaNf32};
 
@@ -57,6 +67,10 @@ pub enum QueryAst {
     FieldPresence(FieldPresenceQuery),
     FullText(FullTextQuery),
     PhrasePrefix(PhrasePrefixQuery),
+    Prefix(PrefixQuery),
+    Wildcard(WildcardQuery),
+    Regex(RegexQuery),
+    Fuzzy(FuzzyQuery),
     Range(RangeQuery),
     UserInput(UserInputQuery),
     MatchAll,
@@ -95,6 +109,10 @@ impl QueryAst {
             | ast @ QueryAst::TermSet(_)
             | ast @ QueryAst::FullText(_)
             | ast @ QueryAst::PhrasePrefix(_)
+            | ast @ QueryAst::Prefix(_)
+            | ast @ QueryAst::Wildcard(_)
+            | ast @ QueryAst::Regex(_)
+            | ast @ QueryAst::Fuzzy(_)
             | ast @ QueryAst::MatchAll
             | ast @ QueryAst::MatchNone
             | ast @ QueryAst::FieldPresence(_)
@@ -224,6 +242,30 @@ impl BuildTantivyAst for QueryAst {
             ),
             QueryAst::PhrasePrefix(phrase_prefix_query) => phrase_prefix_query
                 .build_tantivy_ast_call(schema, tokenizer_manager, search_fields, with_validation),
+            QueryAst::Prefix(prefix_query) => prefix_query.build_tantivy_ast_call(
+                schema,
+                tokenizer_manager,
+                search_fields,
+                with_validation,
+            ),
+            QueryAst::Wildcard(wildcard_query) => wildcard_query.build_tantivy_ast_call(
+                schema,
+                tokenizer_manager,
+                search_fields,
+                with_validation,
+            ),
+            QueryAst::Regex(regex_query) => regex_query.build_tantivy_ast_call(
+                schema,
+                tokenizer_manager,
+                search_fields,
+                with_validation,
+            ),
+            QueryAst::Fuzzy(fuzzy_query) => fuzzy_query.build_tantivy_ast_call(
+                schema,
+                tokenizer_manager,
+                search_fields,
+                with_validation,
+            ),
             QueryAst::UserInput(user_text_query) => user_text_query.build_tantivy_ast_call(
                 schema,
                 tokenizer_manager,
@@ -0,0 +1,122 @@
+// Copyright (C) 2023 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use serde::{Deserialize, Serialize};
+use tantivy::schema::Schema as TantivySchema;
+use tantivy::tokenizer::TokenizerManager;
+
+use crate::query_ast::automaton_query::{
+    default_automaton_max_expansions, AutomatonQuery, TermExpansion, TermPattern,
+};
+use crate::query_ast::tantivy_query_ast::TantivyQueryAst;
+use crate::query_ast::utils::{escape_regex, untokenized_text_term};
+use crate::query_ast::{BuildTantivyAst, QueryAst};
+use crate::InvalidQuery;
+
+/// Matches documents containing a term starting with `prefix`.
+///
+/// The prefix is not tokenized: it is matched against the terms as they were indexed.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PrefixQuery {
+    pub field: String,
+    pub prefix: String,
+    /// Maximum number of terms the query expands to in each segment.
+    #[serde(default = "default_automaton_max_expansions")]
+    pub max_expansions: u32,
+}
+
+impl From<PrefixQuery> for QueryAst {
+    fn from(prefix_query: PrefixQuery) -> Self {
+        QueryAst::Prefix(prefix_query)
+    }
+}
+
+impl PrefixQuery {
+    pub(crate) fn to_regex(&self) -> String {
+        format!("{}(?s:.*)", escape_regex(&self.prefix))
+    }
+
+    /// Returns the terms the query expands to in each segment.
+    pub fn term_expansion(&self, schema: &TantivySchema) -> Result<TermExpansion, InvalidQuery> {
+        let prefix_term = untokenized_text_term(&self.field, schema, "prefix", &self.prefix)?;
+        Ok(TermExpansion {
+            field: prefix_term.field(),
+            pattern: TermPattern::Prefix(prefix_term),
+            max_expansions: self.max_expansions,
+        })
+    }
+}
+
+impl BuildTantivyAst for PrefixQuery {
+    fn build_tantivy_ast_impl(
+        &self,
+        schema: &TantivySchema,
+        _tokenizer_manager: &TokenizerManager,
+        _search_fields: &[String],
+        _with_validation: bool,
+    ) -> Result<TantivyQueryAst, InvalidQuery> {
+        let term_expansion = self.term_expansion(schema)?;
+        Ok(AutomatonQuery::new(term_expansion)?.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tantivy::schema::{Schema, TEXT};
+
+    use super::PrefixQuery;
+    use crate::create_default_quickwit_tokenizer_manager;
+    use crate::query_ast::{BuildTantivyAst, DEFAULT_AUTOMATON_MAX_EXPANSIONS};
+
+    #[test]
+    fn test_prefix_query_to_regex() {
+        let prefix_query = PrefixQuery {
+            field: "body".to_string(),
+            prefix: "quick.wit".to_string(),
+            max_expansions: DEFAULT_AUTOMATON_MAX_EXPANSIONS,
+        };
+        assert_eq!(prefix_query.to_regex(), r"quick\.wit(?s:.*)");
+    }
+
+    #[test]
+    fn test_prefix_query_on_non_text_field() {
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_text_field("body", TEXT);
+        schema_builder.add_u64_field("count", tantivy::schema::INDEXED);
+        let schema = schema_builder.build();
+        let tokenizer_manager = create_default_quickwit_tokenizer_manager();
+        let prefix_query = PrefixQuery {
+            field: "body".to_string(),
+            prefix: "quick".to_string(),
+            max_expansions: DEFAULT_AUTOMATON_MAX_EXPANSIONS,
+        };
+        prefix_query
+            .build_tantivy_ast_call(&schema, &tokenizer_manager, &[], true)
+            .unwrap();
+        let prefix_query = PrefixQuery {
+            field: "count".to_string(),
+            prefix: "1".to_string(),
+            max_expansions: DEFAULT_AUTOMATON_MAX_EXPANSIONS,
+        };
+        let error = prefix_query
+            .build_tantivy_ast_call(&schema, &tokenizer_manager, &[], true)
+            .unwrap_err();
+        assert!(error.to_string().contains("non-text field"));
+    }
+}
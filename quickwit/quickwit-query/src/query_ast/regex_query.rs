@@ -0,0 +1,145 @@
+// Copyright (C) 2023 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use serde::{Deserialize, Serialize};
+use tantivy::schema::Schema as TantivySchema;
+use tantivy::tokenizer::TokenizerManager;
+
+use crate::query_ast::automaton_query::{
+    default_automaton_max_expansions, AutomatonQuery, TermExpansion, TermPattern,
+};
+use crate::query_ast::tantivy_query_ast::TantivyQueryAst;
+use crate::query_ast::utils::untokenized_text_term;
+use crate::query_ast::{BuildTantivyAst, QueryAst};
+use crate::InvalidQuery;
+
+/// Matches documents containing a term matching the regular expression `regex`.
+///
+/// The regular expression has to match the whole term. It is not tokenized.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RegexQuery {
+    pub field: String,
+    pub regex: String,
+    /// Maximum number of terms the query expands to in each segment.
+    #[serde(default = "default_automaton_max_expansions")]
+    pub max_expansions: u32,
+}
+
+impl From<RegexQuery> for QueryAst {
+    fn from(regex_query: RegexQuery) -> Self {
+        QueryAst::Regex(regex_query)
+    }
+}
+
+impl RegexQuery {
+    /// Returns the terms the query expands to in each segment.
+    pub fn term_expansion(&self, schema: &TantivySchema) -> Result<TermExpansion, InvalidQuery> {
+        let term = untokenized_text_term(&self.field, schema, "regex", "")?;
+        Ok(TermExpansion {
+            field: term.field(),
+            pattern: TermPattern::Regex {
+                term_prefix: term.serialized_value_bytes().to_vec(),
+                regex: self.regex.clone(),
+            },
+            max_expansions: self.max_expansions,
+        })
+    }
+}
+
+impl BuildTantivyAst for RegexQuery {
+    fn build_tantivy_ast_impl(
+        &self,
+        schema: &TantivySchema,
+        _tokenizer_manager: &TokenizerManager,
+        _search_fields: &[String],
+        _with_validation: bool,
+    ) -> Result<TantivyQueryAst, InvalidQuery> {
+        let term_expansion = self.term_expansion(schema)?;
+        Ok(AutomatonQuery::new(term_expansion)?.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tantivy::schema::{JsonObjectOptions, Schema, TEXT};
+
+    use super::RegexQuery;
+    use crate::query_ast::{BuildTantivyAst, TermPattern, DEFAULT_AUTOMATON_MAX_EXPANSIONS};
+    use crate::{create_default_quickwit_tokenizer_manager, InvalidQuery};
+
+    #[test]
+    fn test_regex_query_invalid_regex() {
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_text_field("body", TEXT);
+        let schema = schema_builder.build();
+        let tokenizer_manager = create_default_quickwit_tokenizer_manager();
+        let regex_query = RegexQuery {
+            field: "body".to_string(),
+            regex: "qu[a-z]+k".to_string(),
+            max_expansions: DEFAULT_AUTOMATON_MAX_EXPANSIONS,
+        };
+        regex_query
+            .build_tantivy_ast_call(&schema, &tokenizer_manager, &[], true)
+            .unwrap();
+        let regex_query = RegexQuery {
+            field: "body".to_string(),
+            regex: "qu[a-z+k".to_string(),
+            max_expansions: DEFAULT_AUTOMATON_MAX_EXPANSIONS,
+        };
+        let error = regex_query
+            .build_tantivy_ast_call(&schema, &tokenizer_manager, &[], true)
+            .unwrap_err();
+        assert!(matches!(error, InvalidQuery::InvalidRegex { .. }));
+    }
+
+    #[test]
+    fn test_regex_query_on_json_field() {
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_json_field("attributes", TEXT);
+        schema_builder.add_json_field("_dynamic", JsonObjectOptions::default());
+        let schema = schema_builder.build();
+        let tokenizer_manager = create_default_quickwit_tokenizer_manager();
+        let regex_query = RegexQuery {
+            field: "attributes.color".to_string(),
+            regex: "re.*".to_string(),
+            max_expansions: DEFAULT_AUTOMATON_MAX_EXPANSIONS,
+        };
+        regex_query
+            .build_tantivy_ast_call(&schema, &tokenizer_manager, &[], true)
+            .unwrap();
+        let term_expansion = regex_query.term_expansion(&schema).unwrap();
+        let TermPattern::Regex { term_prefix, regex } = term_expansion.pattern else {
+            panic!("expected a regex pattern");
+        };
+        // The path, the end of path marker and the string type code.
+        assert_eq!(term_prefix, b"color\x00s");
+        assert_eq!(regex, "re.*");
+
+        // The dynamic field is not indexed.
+        let regex_query = RegexQuery {
+            field: "color".to_string(),
+            regex: "re.*".to_string(),
+            max_expansions: DEFAULT_AUTOMATON_MAX_EXPANSIONS,
+        };
+        let error = regex_query
+            .build_tantivy_ast_call(&schema, &tokenizer_manager, &[], true)
+            .unwrap_err();
+        assert!(error.to_string().contains("not full-text searchable"));
+    }
+}
@@ -32,11 +32,21 @@ use crate::not_nan_f32::NotNaNf32;
 use crate::query_ast::tantivy_query_ast::TantivyQueryAst;
 use crate::query_ast::{
     self, BuildTantivyAst, FieldPresenceQuery, FullTextMode, FullTextParams, QueryAst,
+    DEFAULT_AUTOMATON_MAX_EXPANSIONS,
 };
 use crate::{BooleanOperand, InvalidQuery, JsonLiteral};
 
 const DEFAULT_PHRASE_QUERY_MAX_EXPANSION: u32 = 50;
 
+/// Edit distance of the fuzzy terms that do not specify one, e.g. `quikc~`.
+const DEFAULT_FUZZY_DISTANCE: u8 = 2;
+
+/// Characters with a special meaning in tantivy's query grammar, which have to be escaped to
+/// appear in a term.
+const GRAMMAR_SPECIAL_CHARS: [char; 14] = [
+    '+', '^', '`', ':', '{', '}', '"', '[', ']', '(', ')', '!', '\\', '*',
+];
+
 /// A query expressed in the tantivy query grammar DSL.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct UserInputQuery {
@@ -67,7 +77,8 @@ impl UserInputQuery {
             .as_ref()
             .map(|search_fields| &search_fields[..])
             .unwrap_or(default_search_fields);
-        let user_input_ast = tantivy::query_grammar::parse_query(&self.user_text)
+        let user_text = escape_term_patterns(&self.user_text);
+        let user_input_ast = tantivy::query_grammar::parse_query(&user_text)
             .map_err(|_| anyhow::anyhow!("failed to parse query: `{}`", &self.user_text))?;
         let default_occur = match self.default_operator {
             BooleanOperand::And => Occur::Must,
@@ -95,6 +106,231 @@ impl BuildTantivyAst for UserInputQuery {
     }
 }
 
+/// Term syntaxes that tantivy's query grammar does not support.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TermPattern {
+    /// `/qu[a-z]+k/`
+    Regex(String),
+    /// `qu*k` or `qu?ck`
+    Wildcard(String),
+    /// `quikc~` or `quikc~1`
+    Fuzzy { value: String, distance: u8 },
+}
+
+impl TermPattern {
+    fn to_query_ast(&self, field: String) -> QueryAst {
+        match self {
+            TermPattern::Regex(regex) => query_ast::RegexQuery {
+                field,
+                regex: regex.clone(),
+                max_expansions: DEFAULT_AUTOMATON_MAX_EXPANSIONS,
+            }
+            .into(),
+            TermPattern::Wildcard(value) => query_ast::WildcardQuery {
+                field,
+                value: value.clone(),
+                max_expansions: DEFAULT_AUTOMATON_MAX_EXPANSIONS,
+            }
+            .into(),
+            TermPattern::Fuzzy { value, distance } => query_ast::FuzzyQuery {
+                field,
+                value: value.clone(),
+                distance: *distance,
+                transposition_cost_one: true,
+                prefix: false,
+                max_expansions: DEFAULT_AUTOMATON_MAX_EXPANSIONS,
+            }
+            .into(),
+        }
+    }
+}
+
+fn is_term_delimiter(c: char) -> bool {
+    c.is_whitespace() || matches!(c, '(' | ')' | '"' | '\'' | '[' | ']' | '{' | '}' | '^')
+}
+
+/// Returns the position following the first unescaped occurrence of one of `closing_chars`,
+/// starting from `pos`.
+fn find_closing_char(chars: &[char], mut pos: usize, closing_chars: &[char]) -> Option<usize> {
+    while pos < chars.len() {
+        let c = chars[pos];
+        pos += 1;
+        if c == '\\' {
+            pos += 1;
+        } else if closing_chars.contains(&c) {
+            return Some(pos);
+        }
+    }
+    None
+}
+
+/// Returns the position following the term starting at `pos`.
+fn find_term_end(chars: &[char], mut pos: usize) -> usize {
+    while pos < chars.len() && !is_term_delimiter(chars[pos]) {
+        pos += if chars[pos] == '\\' { 2 } else { 1 };
+    }
+    pos.min(chars.len())
+}
+
+/// Escapes the terms of the user query that tantivy's query grammar does not parse as a single
+/// literal, such as regex, wildcard and fuzzy terms, or the terms holding escaped characters.
+///
+/// The grammar then hands over the raw text of each unquoted term, which is parsed by
+/// [`parse_term_pattern`]. Quoted phrases, ranges and sets are left untouched, as well as the terms
+/// with a single trailing `*`, which the grammar parses as prefix queries.
+fn escape_term_patterns(user_text: &str) -> String {
+    let chars: Vec<char> = user_text.chars().collect();
+    let mut escaped_text = String::with_capacity(user_text.len());
+    let mut pos = 0;
+
+    while pos < chars.len() {
+        let end = match chars[pos] {
+            quote @ ('"' | '\'') => {
+                find_closing_char(&chars, pos + 1, &[quote]).unwrap_or(chars.len())
+            }
+            '[' | '{' => find_closing_char(&chars, pos + 1, &[']', '}']).unwrap_or(chars.len()),
+            c if is_term_delimiter(c) => pos + 1,
+            _ => {
+                pos = escape_term(&chars, pos, &mut escaped_text);
+                continue;
+            }
+        };
+        escaped_text.extend(&chars[pos..end]);
+        pos = end;
+    }
+    escaped_text
+}
+
+/// Escapes the term starting at `start`, optionally prefixed by an occur sign and a field name,
+/// and returns the position following it.
+fn escape_term(chars: &[char], start: usize, escaped_text: &mut String) -> usize {
+    let mut value_start = start;
+    if matches!(chars[value_start], '+' | '-') {
+        value_start += 1;
+    }
+    let mut pos = value_start;
+    while pos < chars.len() && !is_term_delimiter(chars[pos]) {
+        match chars[pos] {
+            '\\' => pos += 2,
+            ':' => {
+                value_start = pos + 1;
+                break;
+            }
+            _ => pos += 1,
+        }
+    }
+    escaped_text.extend(&chars[start..value_start]);
+
+    let value_end =
+        find_regex_end(chars, value_start).unwrap_or_else(|| find_term_end(chars, value_start));
+    let value = &chars[value_start..value_end];
+
+    let is_prefix = !value.is_empty() && unescaped_positions(value, '*') == [value.len() - 1];
+    if parse_term_pattern(value).is_some() {
+        escape_grammar_chars(value, escaped_text);
+    } else if value.contains(&'\\') {
+        // The trailing `*` of a prefix term is left for the grammar to parse.
+        let escaped_value_len = if is_prefix {
+            value.len() - 1
+        } else {
+            value.len()
+        };
+        escape_grammar_chars(&value[..escaped_value_len], escaped_text);
+        escaped_text.extend(&value[escaped_value_len..]);
+    } else {
+        escaped_text.extend(value);
+    }
+    value_end
+}
+
+/// Returns the position following the regex starting at `pos`, if any. The closing slash has to
+/// end the term, so that paths like `/var/log` are not mistaken for regexes.
+fn find_regex_end(chars: &[char], pos: usize) -> Option<usize> {
+    if chars.get(pos) != Some(&'/') {
+        return None;
+    }
+    find_closing_char(chars, pos + 1, &['/'])
+        .filter(|&regex_end| find_term_end(chars, regex_end) == regex_end)
+}
+
+fn escape_grammar_chars(value: &[char], escaped_text: &mut String) {
+    for &c in value {
+        if GRAMMAR_SPECIAL_CHARS.contains(&c) || c.is_whitespace() {
+            escaped_text.push('\\');
+        }
+        escaped_text.push(c);
+    }
+}
+
+/// Returns the positions of the unescaped occurrences of `special_char` in `value`.
+fn unescaped_positions(value: &[char], special_char: char) -> Vec<usize> {
+    let mut positions = Vec::new();
+    let mut pos = 0;
+    while pos < value.len() {
+        if value[pos] == '\\' {
+            pos += 2;
+            continue;
+        }
+        if value[pos] == special_char {
+            positions.push(pos);
+        }
+        pos += 1;
+    }
+    positions
+}
+
+fn unescape(value: &[char]) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.iter();
+    while let Some(&c) = chars.next() {
+        if c == '\\' {
+            unescaped.push(chars.next().copied().unwrap_or('\\'));
+        } else {
+            unescaped.push(c);
+        }
+    }
+    unescaped
+}
+
+/// Parses the raw text of an unquoted term into a regex, wildcard or fuzzy term pattern. Returns
+/// `None` for the other terms.
+fn parse_term_pattern(value: &[char]) -> Option<TermPattern> {
+    if find_regex_end(value, 0) == Some(value.len()) {
+        let regex: String = value[1..value.len() - 1]
+            .iter()
+            .collect::<String>()
+            .replace("\\/", "/");
+        return Some(TermPattern::Regex(regex));
+    }
+    let star_positions = unescaped_positions(value, '*');
+    let has_wildcards = !star_positions.is_empty() || !unescaped_positions(value, '?').is_empty();
+
+    if let Some(&tilde_pos) = unescaped_positions(value, '~').last() {
+        let distance_str: String = value[tilde_pos + 1..].iter().collect();
+        if tilde_pos > 0 && !has_wildcards && distance_str.chars().all(|c| c.is_ascii_digit()) {
+            let distance = if distance_str.is_empty() {
+                DEFAULT_FUZZY_DISTANCE
+            } else {
+                distance_str.parse().ok()?
+            };
+            return Some(TermPattern::Fuzzy {
+                value: unescape(&value[..tilde_pos]),
+                distance,
+            });
+        }
+    }
+    if !has_wildcards {
+        return None;
+    }
+    // A single trailing `*` is a prefix query, handled by the grammar.
+    let is_prefix =
+        star_positions == [value.len() - 1] && unescaped_positions(value, '?').is_empty();
+    if is_prefix {
+        return None;
+    }
+    Some(TermPattern::Wildcard(value.iter().collect()))
+}
+
 fn convert_user_input_ast_to_query_ast(
     user_input_ast: UserInputAst,
     default_occur: Occur,
@@ -204,6 +440,18 @@ fn convert_user_input_literal(
     if field_names.is_empty() {
         anyhow::bail!("query requires a default search field and none was supplied");
     }
+    // The grammar hands over the raw text of the unquoted terms, see `escape_term_patterns`.
+    let mut term_pattern_opt: Option<TermPattern> = None;
+    let mut phrase = phrase;
+    if matches!(delimiter, Delimiter::None) {
+        let raw_term: Vec<char> = phrase.chars().collect();
+        if !prefix {
+            term_pattern_opt = parse_term_pattern(&raw_term);
+        }
+        if term_pattern_opt.is_none() {
+            phrase = unescape(&raw_term);
+        }
+    }
     let mode = match delimiter {
         Delimiter::None => FullTextMode::PhraseFallbackToIntersection,
         Delimiter::SingleQuotes => FullTextMode::Bool {
@@ -219,6 +467,9 @@ fn convert_user_input_literal(
     let mut phrase_queries: Vec<QueryAst> = field_names
         .into_iter()
         .map(|field_name| {
+            if let Some(term_pattern) = &term_pattern_opt {
+                return term_pattern.to_query_ast(field_name);
+            }
             if prefix {
                 return query_ast::PhrasePrefixQuery {
                     field: field_name,
@@ -252,7 +503,8 @@ fn convert_user_input_literal(
 #[cfg(test)]
 mod tests {
     use crate::query_ast::{
-        BoolQuery, BuildTantivyAst, FullTextMode, FullTextQuery, QueryAst, UserInputQuery,
+        BoolQuery, BuildTantivyAst, FullTextMode, FullTextQuery, FuzzyQuery, QueryAst, RegexQuery,
+        UserInputQuery, WildcardQuery, DEFAULT_AUTOMATON_MAX_EXPANSIONS,
     };
     use crate::{create_default_quickwit_tokenizer_manager, BooleanOperand, InvalidQuery};
 
@@ -472,4 +724,136 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_user_input_query_term_patterns() {
+        let parse_user_query = |user_text: &str| {
+            UserInputQuery {
+                user_text: user_text.to_string(),
+                default_fields: None,
+                default_operator: BooleanOperand::And,
+            }
+            .parse_user_query(&["body".to_string()])
+            .unwrap()
+        };
+        assert_eq!(
+            parse_user_query("title:/qu[a-z]+k/"),
+            QueryAst::Regex(RegexQuery {
+                field: "title".to_string(),
+                regex: "qu[a-z]+k".to_string(),
+                max_expansions: DEFAULT_AUTOMATON_MAX_EXPANSIONS,
+            })
+        );
+        assert_eq!(
+            parse_user_query("qu*ck"),
+            QueryAst::Wildcard(WildcardQuery {
+                field: "body".to_string(),
+                value: "qu*ck".to_string(),
+                max_expansions: DEFAULT_AUTOMATON_MAX_EXPANSIONS,
+            })
+        );
+        assert_eq!(
+            parse_user_query("title:quikc~1"),
+            QueryAst::Fuzzy(FuzzyQuery {
+                field: "title".to_string(),
+                value: "quikc".to_string(),
+                distance: 1,
+                transposition_cost_one: true,
+                prefix: false,
+                max_expansions: DEFAULT_AUTOMATON_MAX_EXPANSIONS,
+            })
+        );
+        let QueryAst::Bool(BoolQuery { must, .. }) =
+            parse_user_query("-title:/a b/^2 AND (qu?ck OR quikc~)")
+        else {
+            panic!()
+        };
+        assert_eq!(must.len(), 1);
+        let QueryAst::Bool(BoolQuery { should, .. }) = &must[0] else {
+            panic!()
+        };
+        assert!(matches!(should[0], QueryAst::Wildcard(_)));
+        let QueryAst::Fuzzy(fuzzy_query) = &should[1] else {
+            panic!()
+        };
+        assert_eq!(fuzzy_query.distance, 2);
+    }
+
+    #[test]
+    fn test_user_input_query_term_patterns_ignored() {
+        let parse_user_query = |user_text: &str| {
+            UserInputQuery {
+                user_text: user_text.to_string(),
+                default_fields: None,
+                default_operator: BooleanOperand::And,
+            }
+            .parse_user_query(&["body".to_string()])
+            .unwrap()
+        };
+        assert!(matches!(
+            parse_user_query("quick*"),
+            QueryAst::PhrasePrefix(_)
+        ));
+        assert!(matches!(
+            parse_user_query("body:\"qu*ck\""),
+            QueryAst::FullText(_)
+        ));
+        assert!(matches!(
+            parse_user_query("body:\"quick fox\"~1"),
+            QueryAst::FullText(_)
+        ));
+        assert!(matches!(
+            parse_user_query("path:/var/log"),
+            QueryAst::FullText(_)
+        ));
+        assert!(matches!(parse_user_query("qu\\*ck"), QueryAst::FullText(_)));
+    }
+
+    #[test]
+    fn test_user_input_query_term_patterns_escaped() {
+        let parse_user_query = |user_text: &str| {
+            UserInputQuery {
+                user_text: user_text.to_string(),
+                default_fields: None,
+                default_operator: BooleanOperand::And,
+            }
+            .parse_user_query(&["body".to_string()])
+            .unwrap()
+        };
+        let QueryAst::FullText(full_text_query) = parse_user_query("qu\\*ck") else {
+            panic!()
+        };
+        assert_eq!(full_text_query.text, "qu*ck");
+
+        let QueryAst::FullText(full_text_query) = parse_user_query("title:quick\\~1") else {
+            panic!()
+        };
+        assert_eq!(full_text_query.field, "title");
+        assert_eq!(full_text_query.text, "quick~1");
+
+        let QueryAst::PhrasePrefix(phrase_prefix_query) = parse_user_query("qu\\*i*") else {
+            panic!()
+        };
+        assert_eq!(phrase_prefix_query.phrase, "qu*i");
+
+        let QueryAst::Regex(regex_query) = parse_user_query("path:/var\\/log/") else {
+            panic!()
+        };
+        assert_eq!(regex_query.regex, "var/log");
+
+        let QueryAst::Wildcard(wildcard_query) = parse_user_query("qu*ck\\?") else {
+            panic!()
+        };
+        assert_eq!(wildcard_query.value, "qu*ck\\?");
+
+        let QueryAst::FullText(full_text_query) = parse_user_query("\u{E000}0") else {
+            panic!()
+        };
+        assert_eq!(full_text_query.text, "\u{E000}0");
+
+        let QueryAst::FullText(full_text_query) = parse_user_query("\"qu*ck quikc~1\"") else {
+            panic!()
+        };
+        assert_eq!(full_text_query.text, "qu*ck quikc~1");
+    }
 }
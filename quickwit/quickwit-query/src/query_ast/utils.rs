@@ -67,6 +67,82 @@ pub fn find_field_or_hit_dynamic<'a>(
     Ok((field, field_entry, path))
 }
 
+/// Returns the untokenized term of `text` in the text or JSON field `full_path`, as matched by
+/// prefix, wildcard, regex and fuzzy queries.
+///
+/// For a JSON field, the term is prefixed by the path and value type, like all the text values
+/// of the path in the term dictionary.
+pub(crate) fn untokenized_text_term(
+    full_path: &str,
+    schema: &TantivySchema,
+    query_name: &str,
+    text: &str,
+) -> Result<Term, InvalidQuery> {
+    let (field, field_entry, json_path) = find_field_or_hit_dynamic(full_path, schema)?;
+    match field_entry.field_type() {
+        FieldType::Str(text_options) => {
+            if text_options.get_indexing_options().is_none() {
+                return Err(InvalidQuery::SchemaError(format!(
+                    "field {} is not full-text searchable",
+                    field_entry.name()
+                )));
+            }
+            Ok(Term::from_field_text(field, text))
+        }
+        FieldType::JsonObject(json_options) => {
+            if json_options.get_text_indexing_options().is_none() {
+                return Err(InvalidQuery::SchemaError(format!(
+                    "field {} is not full-text searchable",
+                    field_entry.name()
+                )));
+            }
+            let mut term = Term::with_capacity(json_path.len() + text.len() + 8);
+            let mut json_term_writer = JsonTermWriter::from_field_and_json_path(
+                field,
+                json_path,
+                json_options.is_expand_dots_enabled(),
+                &mut term,
+            );
+            json_term_writer.set_str(text);
+            Ok(json_term_writer.term().clone())
+        }
+        _ => Err(InvalidQuery::SchemaError(format!(
+            "trying to run a {query_name} query on a non-text field"
+        ))),
+    }
+}
+
+/// Escapes the characters of `text` that have a special meaning in a regular expression.
+pub(crate) fn escape_regex(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(
+            c,
+            '\\' | '.'
+                | '+'
+                | '*'
+                | '?'
+                | '('
+                | ')'
+                | '|'
+                | '['
+                | ']'
+                | '{'
+                | '}'
+                | '^'
+                | '$'
+                | '#'
+                | '&'
+                | '-'
+                | '~'
+        ) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
 /// Creates a full text query.
 ///
 /// If tokenize is set to true, the text will be tokenized.
@@ -21,7 +21,8 @@ use crate::not_nan_f32::NotNaNf32;
 use crate::query_ast::field_presence::FieldPresenceQuery;
 use crate::query_ast::user_input_query::UserInputQuery;
 use crate::query_ast::{
-    BoolQuery, FullTextQuery, PhrasePrefixQuery, QueryAst, RangeQuery, TermQuery, TermSetQuery,
+    BoolQuery, FullTextQuery, FuzzyQuery, PhrasePrefixQuery, PrefixQuery, QueryAst, RangeQuery,
+    RegexQuery, TermQuery, TermSetQuery, WildcardQuery,
 };
 
 /// Simple trait to implement a Visitor over the QueryAst.
@@ -37,6 +38,10 @@ pub trait QueryAstVisitor<'a> {
             QueryAst::PhrasePrefix(phrase_prefix_query) => {
                 self.visit_phrase_prefix(phrase_prefix_query)
             }
+            QueryAst::Prefix(prefix_query) => self.visit_prefix(prefix_query),
+            QueryAst::Wildcard(wildcard_query) => self.visit_wildcard(wildcard_query),
+            QueryAst::Regex(regex_query) => self.visit_regex(regex_query),
+            QueryAst::Fuzzy(fuzzy_query) => self.visit_fuzzy(fuzzy_query),
             QueryAst::Range(range_query) => self.visit_range(range_query),
             QueryAst::MatchAll => self.visit_match_all(),
             QueryAst::MatchNone => self.visit_match_none(),
@@ -78,6 +83,22 @@ pub trait QueryAstVisitor<'a> {
         Ok(())
     }
 
+    fn visit_prefix(&mut self, _prefix_query: &'a PrefixQuery) -> Result<(), Self::Err> {
+        Ok(())
+    }
+
+    fn visit_wildcard(&mut self, _wildcard_query: &'a WildcardQuery) -> Result<(), Self::Err> {
+        Ok(())
+    }
+
+    fn visit_regex(&mut self, _regex_query: &'a RegexQuery) -> Result<(), Self::Err> {
+        Ok(())
+    }
+
+    fn visit_fuzzy(&mut self, _fuzzy_query: &'a FuzzyQuery) -> Result<(), Self::Err> {
+        Ok(())
+    }
+
     fn visit_match_all(&mut self) -> Result<(), Self::Err> {
         Ok(())
     }
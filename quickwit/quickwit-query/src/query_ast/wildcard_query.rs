@@ -0,0 +1,120 @@
+// Copyright (C) 2023 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use serde::{Deserialize, Serialize};
+use tantivy::schema::Schema as TantivySchema;
+use tantivy::tokenizer::TokenizerManager;
+
+use crate::query_ast::automaton_query::{
+    default_automaton_max_expansions, AutomatonQuery, TermExpansion, TermPattern,
+};
+use crate::query_ast::tantivy_query_ast::TantivyQueryAst;
+use crate::query_ast::utils::{escape_regex, untokenized_text_term};
+use crate::query_ast::{BuildTantivyAst, QueryAst};
+use crate::InvalidQuery;
+
+/// Matches documents containing a term matching the wildcard pattern `value`.
+///
+/// `*` matches any sequence of characters, including the empty one, and `?` matches exactly
+/// one character. Both can be escaped with a backslash. The pattern is not tokenized.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct WildcardQuery {
+    pub field: String,
+    pub value: String,
+    /// Maximum number of terms the query expands to in each segment.
+    #[serde(default = "default_automaton_max_expansions")]
+    pub max_expansions: u32,
+}
+
+impl From<WildcardQuery> for QueryAst {
+    fn from(wildcard_query: WildcardQuery) -> Self {
+        QueryAst::Wildcard(wildcard_query)
+    }
+}
+
+impl WildcardQuery {
+    pub(crate) fn to_regex(&self) -> String {
+        let mut regex = String::with_capacity(self.value.len() + 4);
+        // Wildcards also match line breaks.
+        regex.push_str("(?s)");
+        let mut chars = self.value.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '*' => regex.push_str(".*"),
+                '?' => regex.push('.'),
+                '\\' => {
+                    let escaped_char = chars.next().unwrap_or('\\');
+                    regex.push_str(&escape_regex(escaped_char.encode_utf8(&mut [0u8; 4])));
+                }
+                _ => regex.push_str(&escape_regex(c.encode_utf8(&mut [0u8; 4]))),
+            }
+        }
+        regex
+    }
+
+    /// Returns the terms the query expands to in each segment.
+    pub fn term_expansion(&self, schema: &TantivySchema) -> Result<TermExpansion, InvalidQuery> {
+        let term = untokenized_text_term(&self.field, schema, "wildcard", "")?;
+        Ok(TermExpansion {
+            field: term.field(),
+            pattern: TermPattern::Regex {
+                term_prefix: term.serialized_value_bytes().to_vec(),
+                regex: self.to_regex(),
+            },
+            max_expansions: self.max_expansions,
+        })
+    }
+}
+
+impl BuildTantivyAst for WildcardQuery {
+    fn build_tantivy_ast_impl(
+        &self,
+        schema: &TantivySchema,
+        _tokenizer_manager: &TokenizerManager,
+        _search_fields: &[String],
+        _with_validation: bool,
+    ) -> Result<TantivyQueryAst, InvalidQuery> {
+        let term_expansion = self.term_expansion(schema)?;
+        Ok(AutomatonQuery::new(term_expansion)?.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WildcardQuery;
+    use crate::query_ast::DEFAULT_AUTOMATON_MAX_EXPANSIONS;
+
+    fn wildcard_to_regex(value: &str) -> String {
+        WildcardQuery {
+            field: "body".to_string(),
+            value: value.to_string(),
+            max_expansions: DEFAULT_AUTOMATON_MAX_EXPANSIONS,
+        }
+        .to_regex()
+    }
+
+    #[test]
+    fn test_wildcard_query_to_regex() {
+        assert_eq!(wildcard_to_regex("quick*"), "(?s)quick.*");
+        assert_eq!(wildcard_to_regex("qu?ck"), "(?s)qu.ck");
+        assert_eq!(wildcard_to_regex("quick.wit"), r"(?s)quick\.wit");
+        assert_eq!(wildcard_to_regex(r"what\?*"), r"(?s)what\?.*");
+        assert_eq!(wildcard_to_regex(r"trailing\"), r"(?s)trailing\\");
+    }
+}
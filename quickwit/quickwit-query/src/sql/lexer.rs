@@ -0,0 +1,260 @@
+// Copyright (C) 2023 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::fmt;
+
+use super::SqlParseError;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Token {
+    /// Unquoted word: either a keyword or an identifier.
+    Word(String),
+    /// Identifier quoted with double quotes or backticks.
+    QuotedIdent(String),
+    /// String literal quoted with single quotes.
+    String(String),
+    Number(String),
+    Star,
+    Comma,
+    LeftParen,
+    RightParen,
+    Eq,
+    NotEq,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Semicolon,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Token::Word(word) => write!(f, "{word}"),
+            Token::QuotedIdent(ident) => write!(f, "\"{ident}\""),
+            Token::String(string) => write!(f, "'{string}'"),
+            Token::Number(number) => write!(f, "{number}"),
+            Token::Star => write!(f, "*"),
+            Token::Comma => write!(f, ","),
+            Token::LeftParen => write!(f, "("),
+            Token::RightParen => write!(f, ")"),
+            Token::Eq => write!(f, "="),
+            Token::NotEq => write!(f, "!="),
+            Token::Lt => write!(f, "<"),
+            Token::Le => write!(f, "<="),
+            Token::Gt => write!(f, ">"),
+            Token::Ge => write!(f, ">="),
+            Token::Semicolon => write!(f, ";"),
+        }
+    }
+}
+
+fn is_word_start(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_' || c == '@' || c == '$'
+}
+
+/// Besides the usual identifier characters, words may contain `-`, `.` and `*` so that index ID
+/// patterns (`logs-*`) and field paths (`resource.service`) do not need to be quoted.
+fn is_word_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '_' | '@' | '$' | '-' | '.' | '*')
+}
+
+pub(crate) fn tokenize(sql: &str) -> Result<Vec<Token>, SqlParseError> {
+    let mut tokens = Vec::new();
+    let mut chars = sql.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        let token = match c {
+            '*' => {
+                chars.next();
+                Token::Star
+            }
+            ',' => {
+                chars.next();
+                Token::Comma
+            }
+            '(' => {
+                chars.next();
+                Token::LeftParen
+            }
+            ')' => {
+                chars.next();
+                Token::RightParen
+            }
+            ';' => {
+                chars.next();
+                Token::Semicolon
+            }
+            '=' => {
+                chars.next();
+                Token::Eq
+            }
+            '!' => {
+                chars.next();
+                if chars.next_if_eq(&'=').is_none() {
+                    return Err(SqlParseError("unexpected character `!`".to_string()));
+                }
+                Token::NotEq
+            }
+            '<' => {
+                chars.next();
+                if chars.next_if_eq(&'=').is_some() {
+                    Token::Le
+                } else if chars.next_if_eq(&'>').is_some() {
+                    Token::NotEq
+                } else {
+                    Token::Lt
+                }
+            }
+            '>' => {
+                chars.next();
+                if chars.next_if_eq(&'=').is_some() {
+                    Token::Ge
+                } else {
+                    Token::Gt
+                }
+            }
+            '\'' => {
+                chars.next();
+                Token::String(read_quoted(&mut chars, '\'')?)
+            }
+            '"' | '`' => {
+                chars.next();
+                Token::QuotedIdent(read_quoted(&mut chars, c)?)
+            }
+            '-' | '0'..='9' => {
+                let mut number = String::new();
+                number.push(c);
+                chars.next();
+                while let Some(c) =
+                    chars.next_if(|c| c.is_ascii_digit() || matches!(*c, '.' | 'e' | 'E'))
+                {
+                    number.push(c);
+                    if matches!(c, 'e' | 'E') {
+                        if let Some(sign) = chars.next_if(|c| matches!(*c, '-' | '+')) {
+                            number.push(sign);
+                        }
+                    }
+                }
+                if number == "-" || number.parse::<f64>().is_err() {
+                    return Err(SqlParseError(format!("invalid number `{number}`")));
+                }
+                Token::Number(number)
+            }
+            c if is_word_start(c) => {
+                let mut word = String::new();
+                while let Some(c) = chars.next_if(|c| is_word_char(*c)) {
+                    word.push(c);
+                }
+                Token::Word(word)
+            }
+            _ => {
+                return Err(SqlParseError(format!("unexpected character `{c}`")));
+            }
+        };
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+/// Reads the remainder of a quoted string or identifier. The quote character is escaped by
+/// doubling it.
+fn read_quoted(
+    chars: &mut std::iter::Peekable<std::str::Chars>,
+    quote: char,
+) -> Result<String, SqlParseError> {
+    let mut value = String::new();
+    loop {
+        match chars.next() {
+            Some(c) if c == quote => {
+                if chars.next_if_eq(&quote).is_some() {
+                    value.push(quote);
+                } else {
+                    return Ok(value);
+                }
+            }
+            Some(c) => value.push(c),
+            None => {
+                return Err(SqlParseError(format!(
+                    "unterminated quoted string `{quote}{value}`"
+                )));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize() {
+        let tokens = tokenize(
+            "SELECT count(*), \"my field\" FROM logs-* WHERE a.b >= -1.5e3 AND c <> 'it''s';",
+        )
+        .unwrap();
+        assert_eq!(
+            tokens,
+            [
+                Token::Word("SELECT".to_string()),
+                Token::Word("count".to_string()),
+                Token::LeftParen,
+                Token::Star,
+                Token::RightParen,
+                Token::Comma,
+                Token::QuotedIdent("my field".to_string()),
+                Token::Word("FROM".to_string()),
+                Token::Word("logs-*".to_string()),
+                Token::Word("WHERE".to_string()),
+                Token::Word("a.b".to_string()),
+                Token::Ge,
+                Token::Number("-1.5e3".to_string()),
+                Token::Word("AND".to_string()),
+                Token::Word("c".to_string()),
+                Token::NotEq,
+                Token::String("it's".to_string()),
+                Token::Semicolon,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_errors() {
+        assert_eq!(
+            tokenize("a = 'foo").unwrap_err().to_string(),
+            "invalid SQL query: unterminated quoted string `'foo`"
+        );
+        assert_eq!(
+            tokenize("a ! b").unwrap_err().to_string(),
+            "invalid SQL query: unexpected character `!`"
+        );
+        assert_eq!(
+            tokenize("a = 1.2.3").unwrap_err().to_string(),
+            "invalid SQL query: invalid number `1.2.3`"
+        );
+        assert_eq!(
+            tokenize("a = #").unwrap_err().to_string(),
+            "invalid SQL query: unexpected character `#`"
+        );
+    }
+}
@@ -0,0 +1,149 @@
+// Copyright (C) 2023 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Subset of SQL used to express searches and aggregations:
+//!
+//! ```sql
+//! SELECT <projection> [AS <alias>], ...
+//! FROM <index_id_pattern>, ...
+//! [WHERE <predicate>]
+//! [GROUP BY <field or date bucket>, ...]
+//! [ORDER BY <column> [ASC | DESC], ...]
+//! [LIMIT <n> [OFFSET <m>]]
+//! ```
+//!
+//! The `WHERE` clause is translated into a [`QueryAst`]. Projections, groups, and ordering are
+//! left to the caller, which compiles them into a search request.
+
+use std::fmt;
+
+use thiserror::Error;
+
+use crate::query_ast::QueryAst;
+
+mod lexer;
+mod parser;
+
+pub use parser::parse_sql;
+
+/// Error returned when a SQL query cannot be parsed or uses unsupported constructs.
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+#[error("invalid SQL query: {0}")]
+pub struct SqlParseError(pub String);
+
+/// A `SELECT` statement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SqlSelect {
+    pub projections: Vec<SqlProjection>,
+    pub index_id_patterns: Vec<String>,
+    /// Query derived from the `WHERE` clause. Matches all documents if the clause is absent.
+    pub query_ast: QueryAst,
+    pub group_by: Vec<SqlExpr>,
+    pub order_by: Vec<SqlOrderBy>,
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SqlProjection {
+    pub expr: SqlExpr,
+    pub alias: Option<String>,
+}
+
+impl SqlProjection {
+    /// Returns the name of the column holding the projection in the results.
+    pub fn column_name(&self) -> String {
+        if let Some(alias) = &self.alias {
+            return alias.clone();
+        }
+        self.expr.to_string()
+    }
+}
+
+/// Expression that can be selected, grouped by, or ordered by.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SqlExpr {
+    /// `*`
+    Wildcard,
+    Field(String),
+    /// Aggregate function over a field. `COUNT(*)` has no field.
+    Aggregate {
+        function: SqlAggregateFunction,
+        field: Option<String>,
+    },
+    /// `DATE_HISTOGRAM(field, '1h')` or `DATE_TRUNC('hour', field)`: buckets of a date field.
+    DateHistogram {
+        field: String,
+        fixed_interval: String,
+    },
+}
+
+impl SqlExpr {
+    pub fn is_aggregate(&self) -> bool {
+        matches!(self, SqlExpr::Aggregate { .. })
+    }
+}
+
+impl fmt::Display for SqlExpr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SqlExpr::Wildcard => write!(f, "*"),
+            SqlExpr::Field(field) => write!(f, "{field}"),
+            SqlExpr::Aggregate {
+                function,
+                field: Some(field),
+            } => write!(f, "{}({field})", function.name()),
+            SqlExpr::Aggregate {
+                function,
+                field: None,
+            } => write!(f, "{}(*)", function.name()),
+            SqlExpr::DateHistogram {
+                field,
+                fixed_interval,
+            } => write!(f, "date_histogram({field}, '{fixed_interval}')"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SqlAggregateFunction {
+    Count,
+    Sum,
+    Avg,
+    Min,
+    Max,
+}
+
+impl SqlAggregateFunction {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SqlAggregateFunction::Count => "count",
+            SqlAggregateFunction::Sum => "sum",
+            SqlAggregateFunction::Avg => "avg",
+            SqlAggregateFunction::Min => "min",
+            SqlAggregateFunction::Max => "max",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SqlOrderBy {
+    pub expr: SqlExpr,
+    pub descending: bool,
+}
@@ -0,0 +1,823 @@
+// Copyright (C) 2023 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::{BTreeSet, HashMap};
+use std::ops::Bound;
+
+use super::lexer::{tokenize, Token};
+use super::{SqlAggregateFunction, SqlExpr, SqlOrderBy, SqlParseError, SqlProjection, SqlSelect};
+use crate::query_ast::{
+    BoolQuery, FieldPresenceQuery, FullTextParams, FullTextQuery, QueryAst, RangeQuery, TermQuery,
+    TermSetQuery, UserInputQuery, WildcardQuery, DEFAULT_AUTOMATON_MAX_EXPANSIONS,
+};
+use crate::{BooleanOperand, JsonLiteral, MatchAllOrNone};
+
+const RESERVED_KEYWORDS: &[&str] = &[
+    "AND", "AS", "ASC", "BETWEEN", "BY", "DESC", "FALSE", "FROM", "GROUP", "IN", "IS", "LIKE",
+    "LIMIT", "NOT", "NULL", "OFFSET", "OR", "ORDER", "SELECT", "TRUE", "WHERE",
+];
+
+/// Parses a SQL `SELECT` statement.
+pub fn parse_sql(sql: &str) -> Result<SqlSelect, SqlParseError> {
+    let tokens = tokenize(sql)?;
+    let mut parser = Parser { tokens, pos: 0 };
+    let select = parser.parse_select()?;
+    parser.consume_token(&Token::Semicolon);
+
+    if parser.peek().is_some() {
+        return Err(parser.unexpected("end of query"));
+    }
+    Ok(select)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn peek_nth(&self, n: usize) -> Option<&Token> {
+        self.tokens.get(self.pos + n)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token_opt = self.tokens.get(self.pos).cloned();
+        if token_opt.is_some() {
+            self.pos += 1;
+        }
+        token_opt
+    }
+
+    fn unexpected(&self, expected: &str) -> SqlParseError {
+        match self.peek() {
+            Some(token) => SqlParseError(format!("expected {expected}, found `{token}`")),
+            None => SqlParseError(format!("expected {expected}, found end of query")),
+        }
+    }
+
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Word(word)) if word.eq_ignore_ascii_case(keyword))
+    }
+
+    fn consume_keyword(&mut self, keyword: &str) -> bool {
+        if self.peek_keyword(keyword) {
+            self.pos += 1;
+            return true;
+        }
+        false
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), SqlParseError> {
+        if self.consume_keyword(keyword) {
+            return Ok(());
+        }
+        Err(self.unexpected(&format!("`{keyword}`")))
+    }
+
+    fn consume_token(&mut self, token: &Token) -> bool {
+        if self.peek() == Some(token) {
+            self.pos += 1;
+            return true;
+        }
+        false
+    }
+
+    fn expect_token(&mut self, token: Token) -> Result<(), SqlParseError> {
+        if self.consume_token(&token) {
+            return Ok(());
+        }
+        Err(self.unexpected(&format!("`{token}`")))
+    }
+
+    fn parse_identifier(&mut self) -> Result<String, SqlParseError> {
+        match self.peek() {
+            Some(Token::Word(word))
+                if !RESERVED_KEYWORDS
+                    .iter()
+                    .any(|keyword| word.eq_ignore_ascii_case(keyword)) =>
+            {
+                let word = word.clone();
+                self.pos += 1;
+                Ok(word)
+            }
+            Some(Token::QuotedIdent(ident)) => {
+                let ident = ident.clone();
+                self.pos += 1;
+                Ok(ident)
+            }
+            _ => Err(self.unexpected("an identifier")),
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<String, SqlParseError> {
+        if let Some(Token::String(string)) = self.peek() {
+            let string = string.clone();
+            self.pos += 1;
+            return Ok(string);
+        }
+        Err(self.unexpected("a string literal"))
+    }
+
+    fn parse_u64(&mut self) -> Result<u64, SqlParseError> {
+        if let Some(Token::Number(number)) = self.peek() {
+            if let Ok(value) = number.parse::<u64>() {
+                self.pos += 1;
+                return Ok(value);
+            }
+        }
+        Err(self.unexpected("a positive integer"))
+    }
+
+    fn parse_literal(&mut self) -> Result<JsonLiteral, SqlParseError> {
+        let literal = match self.peek() {
+            Some(Token::String(string)) => JsonLiteral::String(string.clone()),
+            Some(Token::Number(number)) => {
+                let number = serde_json::from_str(number)
+                    .map_err(|_| SqlParseError(format!("invalid number `{number}`")))?;
+                JsonLiteral::Number(number)
+            }
+            Some(Token::Word(word)) if word.eq_ignore_ascii_case("TRUE") => JsonLiteral::Bool(true),
+            Some(Token::Word(word)) if word.eq_ignore_ascii_case("FALSE") => {
+                JsonLiteral::Bool(false)
+            }
+            _ => return Err(self.unexpected("a literal")),
+        };
+        self.pos += 1;
+        Ok(literal)
+    }
+
+    fn parse_comma_separated<T>(
+        &mut self,
+        mut parse_item: impl FnMut(&mut Self) -> Result<T, SqlParseError>,
+    ) -> Result<Vec<T>, SqlParseError> {
+        let mut items = vec![parse_item(self)?];
+        while self.consume_token(&Token::Comma) {
+            items.push(parse_item(self)?);
+        }
+        Ok(items)
+    }
+
+    fn parse_select(&mut self) -> Result<SqlSelect, SqlParseError> {
+        self.expect_keyword("SELECT")?;
+        let projections = self.parse_comma_separated(Self::parse_projection)?;
+
+        self.expect_keyword("FROM")?;
+        let index_id_patterns = self.parse_comma_separated(Self::parse_identifier)?;
+
+        let query_ast = if self.consume_keyword("WHERE") {
+            self.parse_or()?
+        } else {
+            QueryAst::MatchAll
+        };
+        let mut group_by = Vec::new();
+
+        if self.consume_keyword("GROUP") {
+            self.expect_keyword("BY")?;
+            group_by = self.parse_comma_separated(Self::parse_group_by)?;
+        }
+        let mut order_by = Vec::new();
+
+        if self.consume_keyword("ORDER") {
+            self.expect_keyword("BY")?;
+            order_by = self.parse_comma_separated(Self::parse_order_by)?;
+        }
+        let mut limit = None;
+        let mut offset = None;
+
+        if self.consume_keyword("LIMIT") {
+            limit = Some(self.parse_u64()?);
+        }
+        if self.consume_keyword("OFFSET") {
+            offset = Some(self.parse_u64()?);
+        }
+        Ok(SqlSelect {
+            projections,
+            index_id_patterns,
+            query_ast,
+            group_by,
+            order_by,
+            limit,
+            offset,
+        })
+    }
+
+    fn parse_projection(&mut self) -> Result<SqlProjection, SqlParseError> {
+        let expr = self.parse_expr()?;
+        let alias = if self.consume_keyword("AS") {
+            Some(self.parse_identifier()?)
+        } else {
+            None
+        };
+        Ok(SqlProjection { expr, alias })
+    }
+
+    fn parse_group_by(&mut self) -> Result<SqlExpr, SqlParseError> {
+        let expr = self.parse_expr()?;
+
+        if !matches!(expr, SqlExpr::Field(_) | SqlExpr::DateHistogram { .. }) {
+            return Err(SqlParseError(format!("cannot group by `{expr}`")));
+        }
+        Ok(expr)
+    }
+
+    fn parse_order_by(&mut self) -> Result<SqlOrderBy, SqlParseError> {
+        let expr = self.parse_expr()?;
+
+        if expr == SqlExpr::Wildcard {
+            return Err(SqlParseError("cannot order by `*`".to_string()));
+        }
+        let descending = if self.consume_keyword("DESC") {
+            true
+        } else {
+            self.consume_keyword("ASC");
+            false
+        };
+        Ok(SqlOrderBy { expr, descending })
+    }
+
+    /// Parses a field, `*`, or a function call.
+    fn parse_expr(&mut self) -> Result<SqlExpr, SqlParseError> {
+        if self.consume_token(&Token::Star) {
+            return Ok(SqlExpr::Wildcard);
+        }
+        let function_name = match (self.peek(), self.peek_nth(1)) {
+            (Some(Token::Word(word)), Some(Token::LeftParen)) => word.to_ascii_lowercase(),
+            _ => return Ok(SqlExpr::Field(self.parse_identifier()?)),
+        };
+        self.pos += 2;
+
+        let expr = match function_name.as_str() {
+            "count" if self.consume_token(&Token::Star) => SqlExpr::Aggregate {
+                function: SqlAggregateFunction::Count,
+                field: None,
+            },
+            "count" | "sum" | "avg" | "min" | "max" => {
+                let function = match function_name.as_str() {
+                    "count" => SqlAggregateFunction::Count,
+                    "sum" => SqlAggregateFunction::Sum,
+                    "avg" => SqlAggregateFunction::Avg,
+                    "min" => SqlAggregateFunction::Min,
+                    _ => SqlAggregateFunction::Max,
+                };
+                let field = self.parse_identifier()?;
+                SqlExpr::Aggregate {
+                    function,
+                    field: Some(field),
+                }
+            }
+            "date_histogram" => {
+                let field = self.parse_identifier()?;
+                self.expect_token(Token::Comma)?;
+                let fixed_interval = self.parse_string()?;
+                SqlExpr::DateHistogram {
+                    field,
+                    fixed_interval,
+                }
+            }
+            "date_trunc" => {
+                let unit = self.parse_string()?;
+                let fixed_interval = match unit.to_ascii_lowercase().as_str() {
+                    "second" => "1s",
+                    "minute" => "1m",
+                    "hour" => "1h",
+                    "day" => "1d",
+                    _ => {
+                        return Err(SqlParseError(format!(
+                            "unsupported `DATE_TRUNC` unit `{unit}`, expected `second`, `minute`, \
+                             `hour`, or `day`"
+                        )));
+                    }
+                };
+                self.expect_token(Token::Comma)?;
+                let field = self.parse_identifier()?;
+                SqlExpr::DateHistogram {
+                    field,
+                    fixed_interval: fixed_interval.to_string(),
+                }
+            }
+            _ => {
+                return Err(SqlParseError(format!(
+                    "unsupported function `{function_name}`"
+                )));
+            }
+        };
+        self.expect_token(Token::RightParen)?;
+        Ok(expr)
+    }
+
+    fn parse_or(&mut self) -> Result<QueryAst, SqlParseError> {
+        let mut operands = vec![self.parse_and()?];
+
+        while self.consume_keyword("OR") {
+            operands.push(self.parse_and()?);
+        }
+        if operands.len() == 1 {
+            return Ok(operands.pop().unwrap());
+        }
+        Ok(BoolQuery {
+            should: operands,
+            ..Default::default()
+        }
+        .into())
+    }
+
+    fn parse_and(&mut self) -> Result<QueryAst, SqlParseError> {
+        let mut operands = vec![self.parse_not()?];
+
+        while self.consume_keyword("AND") {
+            operands.push(self.parse_not()?);
+        }
+        if operands.len() == 1 {
+            return Ok(operands.pop().unwrap());
+        }
+        Ok(BoolQuery {
+            must: operands,
+            ..Default::default()
+        }
+        .into())
+    }
+
+    fn parse_not(&mut self) -> Result<QueryAst, SqlParseError> {
+        if self.consume_keyword("NOT") {
+            let operand = self.parse_not()?;
+            return Ok(negate(operand));
+        }
+        self.parse_predicate()
+    }
+
+    fn parse_predicate(&mut self) -> Result<QueryAst, SqlParseError> {
+        if self.consume_token(&Token::LeftParen) {
+            let query_ast = self.parse_or()?;
+            self.expect_token(Token::RightParen)?;
+            return Ok(query_ast);
+        }
+        if let (Some(Token::Word(word)), Some(Token::LeftParen)) = (self.peek(), self.peek_nth(1)) {
+            let function_name = word.to_ascii_lowercase();
+            self.pos += 2;
+
+            let query_ast = match function_name.as_str() {
+                "match" => {
+                    let field = self.parse_identifier()?;
+                    self.expect_token(Token::Comma)?;
+                    let text = self.parse_string()?;
+                    FullTextQuery {
+                        field,
+                        text,
+                        params: FullTextParams {
+                            tokenizer: None,
+                            mode: BooleanOperand::Or.into(),
+                            zero_terms_query: MatchAllOrNone::MatchNone,
+                        },
+                    }
+                    .into()
+                }
+                "query" => {
+                    let user_text = self.parse_string()?;
+                    UserInputQuery {
+                        user_text,
+                        default_fields: None,
+                        default_operator: BooleanOperand::And,
+                    }
+                    .into()
+                }
+                _ => {
+                    return Err(SqlParseError(format!(
+                        "unsupported function `{function_name}` in `WHERE` clause"
+                    )));
+                }
+            };
+            self.expect_token(Token::RightParen)?;
+            return Ok(query_ast);
+        }
+        let field = self.parse_identifier()?;
+
+        if self.consume_keyword("IS") {
+            let negated = self.consume_keyword("NOT");
+            self.expect_keyword("NULL")?;
+            let field_presence_query: QueryAst = FieldPresenceQuery { field }.into();
+
+            if negated {
+                return Ok(field_presence_query);
+            }
+            return Ok(negate(field_presence_query));
+        }
+        let negated = self.consume_keyword("NOT");
+
+        let query_ast = if self.consume_keyword("BETWEEN") {
+            let lower_bound = self.parse_literal()?;
+            self.expect_keyword("AND")?;
+            let upper_bound = self.parse_literal()?;
+            RangeQuery {
+                field,
+                lower_bound: Bound::Included(lower_bound),
+                upper_bound: Bound::Included(upper_bound),
+            }
+            .into()
+        } else if self.consume_keyword("IN") {
+            self.expect_token(Token::LeftParen)?;
+            let terms: BTreeSet<String> = self
+                .parse_comma_separated(Self::parse_literal)?
+                .into_iter()
+                .map(literal_to_term)
+                .collect();
+            self.expect_token(Token::RightParen)?;
+            let terms_per_field = HashMap::from([(field, terms)]);
+            TermSetQuery { terms_per_field }.into()
+        } else if self.consume_keyword("LIKE") {
+            let pattern = self.parse_string()?;
+            WildcardQuery {
+                field,
+                value: like_pattern_to_wildcard(&pattern),
+                max_expansions: DEFAULT_AUTOMATON_MAX_EXPANSIONS,
+            }
+            .into()
+        } else if negated {
+            return Err(self.unexpected("`BETWEEN`, `IN`, or `LIKE`"));
+        } else {
+            self.parse_comparison(field)?
+        };
+        if negated {
+            return Ok(negate(query_ast));
+        }
+        Ok(query_ast)
+    }
+
+    fn parse_comparison(&mut self, field: String) -> Result<QueryAst, SqlParseError> {
+        let Some(operator) = self.next() else {
+            return Err(self.unexpected("a comparison operator"));
+        };
+        if !matches!(
+            operator,
+            Token::Eq | Token::NotEq | Token::Lt | Token::Le | Token::Gt | Token::Ge
+        ) {
+            self.pos -= 1;
+            return Err(self.unexpected("a comparison operator"));
+        }
+        let literal = self.parse_literal()?;
+
+        let query_ast = match operator {
+            Token::Eq => TermQuery {
+                field,
+                value: literal_to_term(literal),
+            }
+            .into(),
+            Token::NotEq => negate(
+                TermQuery {
+                    field,
+                    value: literal_to_term(literal),
+                }
+                .into(),
+            ),
+            _ => {
+                let (lower_bound, upper_bound) = match operator {
+                    Token::Lt => (Bound::Unbounded, Bound::Excluded(literal)),
+                    Token::Le => (Bound::Unbounded, Bound::Included(literal)),
+                    Token::Gt => (Bound::Excluded(literal), Bound::Unbounded),
+                    _ => (Bound::Included(literal), Bound::Unbounded),
+                };
+                RangeQuery {
+                    field,
+                    lower_bound,
+                    upper_bound,
+                }
+                .into()
+            }
+        };
+        Ok(query_ast)
+    }
+}
+
+fn negate(query_ast: QueryAst) -> QueryAst {
+    BoolQuery {
+        must_not: vec![query_ast],
+        ..Default::default()
+    }
+    .into()
+}
+
+fn literal_to_term(literal: JsonLiteral) -> String {
+    match literal {
+        JsonLiteral::Number(number) => number.to_string(),
+        JsonLiteral::String(string) => string,
+        JsonLiteral::Bool(boolean) => boolean.to_string(),
+    }
+}
+
+/// Converts a `LIKE` pattern into a wildcard pattern: `%` matches any sequence of characters and
+/// `_` matches exactly one character.
+fn like_pattern_to_wildcard(pattern: &str) -> String {
+    let mut wildcard = String::with_capacity(pattern.len());
+
+    for c in pattern.chars() {
+        match c {
+            '%' => wildcard.push('*'),
+            '_' => wildcard.push('?'),
+            '*' | '?' | '\\' => {
+                wildcard.push('\\');
+                wildcard.push(c);
+            }
+            _ => wildcard.push(c),
+        }
+    }
+    wildcard
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn term(field: &str, value: &str) -> QueryAst {
+        TermQuery {
+            field: field.to_string(),
+            value: value.to_string(),
+        }
+        .into()
+    }
+
+    fn parse_where(predicate: &str) -> QueryAst {
+        parse_sql(&format!("SELECT * FROM my-index WHERE {predicate}"))
+            .unwrap()
+            .query_ast
+    }
+
+    #[test]
+    fn test_parse_sql_select() {
+        let select = parse_sql(
+            "select service, date_trunc('hour', timestamp) as hour, count(*), avg(latency) from \
+             logs-*, \"other index\" where status = 200 group by service, hour order by count(*) \
+             desc, service limit 10 offset 5;",
+        )
+        .unwrap();
+        assert_eq!(
+            select.projections,
+            [
+                SqlProjection {
+                    expr: SqlExpr::Field("service".to_string()),
+                    alias: None,
+                },
+                SqlProjection {
+                    expr: SqlExpr::DateHistogram {
+                        field: "timestamp".to_string(),
+                        fixed_interval: "1h".to_string(),
+                    },
+                    alias: Some("hour".to_string()),
+                },
+                SqlProjection {
+                    expr: SqlExpr::Aggregate {
+                        function: SqlAggregateFunction::Count,
+                        field: None,
+                    },
+                    alias: None,
+                },
+                SqlProjection {
+                    expr: SqlExpr::Aggregate {
+                        function: SqlAggregateFunction::Avg,
+                        field: Some("latency".to_string()),
+                    },
+                    alias: None,
+                },
+            ]
+        );
+        assert_eq!(select.projections[1].column_name(), "hour");
+        assert_eq!(select.projections[2].column_name(), "count(*)");
+        assert_eq!(select.projections[3].column_name(), "avg(latency)");
+        assert_eq!(select.index_id_patterns, ["logs-*", "other index"]);
+        assert_eq!(select.query_ast, term("status", "200"));
+        assert_eq!(
+            select.group_by,
+            [
+                SqlExpr::Field("service".to_string()),
+                SqlExpr::Field("hour".to_string())
+            ]
+        );
+        assert_eq!(
+            select.order_by,
+            [
+                SqlOrderBy {
+                    expr: SqlExpr::Aggregate {
+                        function: SqlAggregateFunction::Count,
+                        field: None,
+                    },
+                    descending: true,
+                },
+                SqlOrderBy {
+                    expr: SqlExpr::Field("service".to_string()),
+                    descending: false,
+                },
+            ]
+        );
+        assert_eq!(select.limit, Some(10));
+        assert_eq!(select.offset, Some(5));
+    }
+
+    #[test]
+    fn test_parse_sql_select_without_where() {
+        let select = parse_sql("SELECT * FROM my-index").unwrap();
+        assert_eq!(
+            select.projections,
+            [SqlProjection {
+                expr: SqlExpr::Wildcard,
+                alias: None,
+            }]
+        );
+        assert_eq!(select.query_ast, QueryAst::MatchAll);
+        assert!(select.group_by.is_empty());
+        assert!(select.order_by.is_empty());
+        assert!(select.limit.is_none());
+        assert!(select.offset.is_none());
+    }
+
+    #[test]
+    fn test_parse_sql_where_boolean() {
+        assert_eq!(
+            parse_where("a = 'x' OR b = 'y' AND NOT c = 'z'"),
+            BoolQuery {
+                should: vec![
+                    term("a", "x"),
+                    BoolQuery {
+                        must: vec![term("b", "y"), negate(term("c", "z"))],
+                        ..Default::default()
+                    }
+                    .into()
+                ],
+                ..Default::default()
+            }
+            .into()
+        );
+        assert_eq!(
+            parse_where("(a = 'x' OR b = 'y') AND c != true"),
+            BoolQuery {
+                must: vec![
+                    BoolQuery {
+                        should: vec![term("a", "x"), term("b", "y")],
+                        ..Default::default()
+                    }
+                    .into(),
+                    negate(term("c", "true")),
+                ],
+                ..Default::default()
+            }
+            .into()
+        );
+    }
+
+    #[test]
+    fn test_parse_sql_where_range() {
+        assert_eq!(
+            parse_where("latency >= 1.5"),
+            RangeQuery {
+                field: "latency".to_string(),
+                lower_bound: Bound::Included(JsonLiteral::Number(
+                    serde_json::Number::from_f64(1.5).unwrap()
+                )),
+                upper_bound: Bound::Unbounded,
+            }
+            .into()
+        );
+        assert_eq!(
+            parse_where("timestamp < '2023-01-01T00:00:00Z'"),
+            RangeQuery {
+                field: "timestamp".to_string(),
+                lower_bound: Bound::Unbounded,
+                upper_bound: Bound::Excluded(JsonLiteral::String(
+                    "2023-01-01T00:00:00Z".to_string()
+                )),
+            }
+            .into()
+        );
+        assert_eq!(
+            parse_where("status NOT BETWEEN 200 AND 299"),
+            negate(
+                RangeQuery {
+                    field: "status".to_string(),
+                    lower_bound: Bound::Included(JsonLiteral::Number(200.into())),
+                    upper_bound: Bound::Included(JsonLiteral::Number(299.into())),
+                }
+                .into()
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_sql_where_match_and_terms() {
+        assert_eq!(
+            parse_where("MATCH(body, 'connection reset')"),
+            FullTextQuery {
+                field: "body".to_string(),
+                text: "connection reset".to_string(),
+                params: FullTextParams {
+                    tokenizer: None,
+                    mode: BooleanOperand::Or.into(),
+                    zero_terms_query: MatchAllOrNone::MatchNone,
+                },
+            }
+            .into()
+        );
+        assert_eq!(
+            parse_where("QUERY('body:error AND severity:WARN')"),
+            UserInputQuery {
+                user_text: "body:error AND severity:WARN".to_string(),
+                default_fields: None,
+                default_operator: BooleanOperand::And,
+            }
+            .into()
+        );
+        assert_eq!(
+            parse_where("service IN ('api', 'db')"),
+            TermSetQuery {
+                terms_per_field: HashMap::from([(
+                    "service".to_string(),
+                    BTreeSet::from(["api".to_string(), "db".to_string()])
+                )]),
+            }
+            .into()
+        );
+        assert_eq!(
+            parse_where("host LIKE 'web-%_1*'"),
+            WildcardQuery {
+                field: "host".to_string(),
+                value: "web-*?1\\*".to_string(),
+                max_expansions: DEFAULT_AUTOMATON_MAX_EXPANSIONS,
+            }
+            .into()
+        );
+        assert_eq!(
+            parse_where("trace_id IS NOT NULL"),
+            FieldPresenceQuery {
+                field: "trace_id".to_string(),
+            }
+            .into()
+        );
+        assert_eq!(
+            parse_where("trace_id IS NULL"),
+            negate(
+                FieldPresenceQuery {
+                    field: "trace_id".to_string(),
+                }
+                .into()
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_sql_errors() {
+        let parse_error = |sql: &str| parse_sql(sql).unwrap_err().to_string();
+        assert_eq!(
+            parse_error("SELECT * my-index"),
+            "invalid SQL query: expected `FROM`, found `my-index`"
+        );
+        assert_eq!(
+            parse_error("SELECT * FROM"),
+            "invalid SQL query: expected an identifier, found end of query"
+        );
+        assert_eq!(
+            parse_error("SELECT * FROM my-index WHERE a ="),
+            "invalid SQL query: expected a literal, found end of query"
+        );
+        assert_eq!(
+            parse_error("SELECT * FROM my-index WHERE a"),
+            "invalid SQL query: expected a comparison operator, found end of query"
+        );
+        assert_eq!(
+            parse_error("SELECT median(a) FROM my-index"),
+            "invalid SQL query: unsupported function `median`"
+        );
+        assert_eq!(
+            parse_error("SELECT * FROM my-index GROUP BY count(*)"),
+            "invalid SQL query: cannot group by `count(*)`"
+        );
+        assert_eq!(
+            parse_error("SELECT * FROM my-index LIMIT -1"),
+            "invalid SQL query: expected a positive integer, found `-1`"
+        );
+        assert_eq!(
+            parse_error("SELECT * FROM my-index LIMIT 1 foo"),
+            "invalid SQL query: expected end of query, found `foo`"
+        );
+        assert_eq!(
+            parse_error("SELECT date_trunc('month', ts) FROM my-index"),
+            "invalid SQL query: unsupported `DATE_TRUNC` unit `month`, expected `second`, \
+             `minute`, `hour`, or `day`"
+        );
+    }
+}
@@ -26,7 +26,10 @@ use quickwit_indexing::actors::IndexingServiceCounters;
 pub use quickwit_ingest::CommitType;
 use quickwit_metastore::{IndexMetadata, Split, SplitInfo};
 use quickwit_search::SearchResponseRest;
-use quickwit_serve::{ListSplitsQueryParams, ListSplitsResponse, SearchRequestQueryString};
+use quickwit_serve::{
+    DeadLetterReplayResponse, DeadLetterSearchResponse, ListSplitsQueryParams, ListSplitsResponse,
+    SearchRequestQueryString,
+};
 use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
 use reqwest::{Client, ClientBuilder, Method, StatusCode, Url};
 use serde::Serialize;
@@ -344,6 +347,29 @@ impl<'a> IndexClient<'a> {
         Ok(index_metadata)
     }
 
+    pub async fn update(
+        &self,
+        index_id: &str,
+        body: Bytes,
+        config_format: ConfigFormat,
+    ) -> Result<IndexMetadata, Error> {
+        let header_map = header_from_config_format(config_format);
+        let path = format!("indexes/{index_id}");
+        let response = self
+            .transport
+            .send::<()>(
+                Method::PUT,
+                &path,
+                Some(header_map),
+                None,
+                Some(body),
+                self.timeout,
+            )
+            .await?;
+        let index_metadata = response.deserialize().await?;
+        Ok(index_metadata)
+    }
+
     pub async fn list(&self) -> Result<Vec<IndexMetadata>, Error> {
         let response = self
             .transport
@@ -523,6 +549,42 @@ impl<'a, 'b> SourceClient<'a, 'b> {
         Ok(())
     }
 
+    pub async fn dead_letter_docs(
+        &self,
+        source_id: &str,
+        max_hits: u64,
+        start_offset: u64,
+    ) -> Result<DeadLetterSearchResponse, Error> {
+        let path = format!("{}/{source_id}/dead-letter", self.sources_root_url());
+        let query_params = [("max_hits", max_hits), ("start_offset", start_offset)];
+        let response = self
+            .transport
+            .send(
+                Method::GET,
+                &path,
+                None,
+                Some(&query_params),
+                None,
+                self.timeout,
+            )
+            .await?;
+        let dead_letter_search_response = response.deserialize().await?;
+        Ok(dead_letter_search_response)
+    }
+
+    pub async fn replay_dead_letter(
+        &self,
+        source_id: &str,
+    ) -> Result<DeadLetterReplayResponse, Error> {
+        let path = format!("{}/{source_id}/dead-letter/replay", self.sources_root_url());
+        let response = self
+            .transport
+            .send::<()>(Method::POST, &path, None, None, None, self.timeout)
+            .await?;
+        let dead_letter_replay_response = response.deserialize().await?;
+        Ok(dead_letter_replay_response)
+    }
+
     pub async fn list(&self) -> Result<Vec<SourceConfig>, Error> {
         let response = self
             .transport
@@ -694,6 +756,8 @@ mod test {
             aggregations: None,
             elapsed_time_micros: 100,
             errors: Vec::new(),
+            timed_out: false,
+            profile: None,
         };
         Mock::given(method("POST"))
             .and(path("/api/v1/my-index/search"))
@@ -910,6 +974,26 @@ mod test {
             index_metadata
         );
 
+        // PUT update index
+        Mock::given(method("PUT"))
+            .and(path("/api/v1/indexes/test-index"))
+            .and(body_json(index_config_to_create.clone()))
+            .respond_with(
+                ResponseTemplate::new(StatusCode::OK).set_body_json(index_metadata.clone()),
+            )
+            .up_to_n_times(1)
+            .mount(&mock_server)
+            .await;
+        let put_body = Bytes::from(serde_json::to_vec(&index_config_to_create).unwrap());
+        assert_eq!(
+            qw_client
+                .indexes()
+                .update("test-index", put_body, ConfigFormat::Json)
+                .await
+                .unwrap(),
+            index_metadata
+        );
+
         // PUT clear index
         Mock::given(method("PUT"))
             .and(path("/api/v1/indexes/my-index/clear"))
@@ -1117,6 +1201,46 @@ mod test {
             .await
             .unwrap_err();
 
+        // GET dead-letter docs
+        Mock::given(method("GET"))
+            .and(path(
+                "/api/v1/indexes/my-index/sources/my-source/dead-letter",
+            ))
+            .and(query_param("max_hits", "10"))
+            .and(query_param("start_offset", "20"))
+            .respond_with(
+                ResponseTemplate::new(StatusCode::OK)
+                    .set_body_json(json!({"num_hits": 0, "docs": []})),
+            )
+            .up_to_n_times(1)
+            .mount(&mock_server)
+            .await;
+        let dead_letter_search_response = qw_client
+            .sources("my-index")
+            .dead_letter_docs("my-source", 10, 20)
+            .await
+            .unwrap();
+        assert_eq!(dead_letter_search_response.num_hits, 0);
+
+        // POST replay dead-letter docs
+        Mock::given(method("POST"))
+            .and(path(
+                "/api/v1/indexes/my-index/sources/my-source/dead-letter/replay",
+            ))
+            .respond_with(
+                ResponseTemplate::new(StatusCode::OK)
+                    .set_body_json(json!({"num_replayed_docs": 3})),
+            )
+            .up_to_n_times(1)
+            .mount(&mock_server)
+            .await;
+        let dead_letter_replay_response = qw_client
+            .sources("my-index")
+            .replay_dead_letter("my-source")
+            .await
+            .unwrap();
+        assert_eq!(dead_letter_replay_response.num_replayed_docs, 3);
+
         // DELETE source
         Mock::given(method("DELETE"))
             .and(path("/api/v1/indexes/my-index/sources/my-source"))
@@ -0,0 +1,1049 @@
+// Copyright (C) 2023 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use anyhow::Context;
+use quickwit_proto::metastore::MetastoreServiceClient;
+use quickwit_proto::search::{LeafSearchResponse, SearchRequest, SearchResponse};
+use serde::{Deserialize, Serialize};
+use tantivy::collector::Collector;
+use tantivy::TantivyError;
+use tokio::sync::watch;
+use tracing::{error, warn};
+use ulid::Ulid;
+
+use crate::collector::make_merge_collector;
+use crate::root::{finalize_aggregation_if_any, root_search_with_progress};
+use crate::service::SearcherContext;
+use crate::{ClusterClient, SearchError};
+
+/// Default time an asynchronous search and its results are kept.
+pub const DEFAULT_ASYNC_SEARCH_KEEP_ALIVE: Duration = Duration::from_secs(60 * 60);
+
+/// Maximum accepted keep alive of an asynchronous search.
+///
+/// Asynchronous searches are kept in memory, in the async search store of the searchers.
+const MAX_ASYNC_SEARCH_KEEP_ALIVE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Maximum total size of the entries of the async search store of a searcher. Each asynchronous
+/// search uses two entries: its status and its cancellation flag.
+const ASYNC_SEARCH_STORE_CAPACITY_NUM_BYTES: usize = 256 * 1024 * 1024;
+
+/// Maximum size of an entry of the async search store. The response of an asynchronous search
+/// is dropped from its status if it does not fit.
+const MAX_ASYNC_SEARCH_ENTRY_NUM_BYTES: usize = 10 * 1024 * 1024;
+
+const ASYNC_SEARCH_KEY_PREFIX: &[u8] = b"async_search:";
+
+/// Default time the submission of an asynchronous search waits for the search to complete.
+pub const DEFAULT_WAIT_FOR_COMPLETION_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Interval at which a running asynchronous search checks whether it was cancelled.
+const ASYNC_SEARCH_CANCELLATION_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Minimum interval between two updates of the stored status of a running asynchronous search.
+/// The status is only updated if the search made progress in the meantime.
+const ASYNC_SEARCH_STATUS_UPDATE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Request to run a search in the background.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SubmitAsyncSearchRequest {
+    /// The search to run.
+    pub search_request: SearchRequest,
+    /// Time to wait for the search to complete before returning its status.
+    pub wait_for_completion_timeout: Duration,
+    /// Time during which the search and its results are kept, starting from the submission.
+    pub keep_alive: Duration,
+}
+
+/// State of an asynchronous search.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AsyncSearchState {
+    /// The search is running. Its response, if any, only covers the splits searched so far.
+    Running,
+    /// The search completed. Its response is final.
+    Completed,
+    /// The search failed.
+    Failed,
+    /// The search was cancelled. Its response is dropped.
+    Cancelled,
+}
+
+/// Status of an asynchronous search, as stored in the KV store.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AsyncSearchStatus {
+    /// ID of the asynchronous search.
+    pub id: String,
+    /// State of the search.
+    pub state: AsyncSearchState,
+    /// Submission time, in milliseconds since the Unix epoch.
+    pub start_time_millis: i64,
+    /// Time after which the search and its results are dropped, in milliseconds since the Unix
+    /// epoch.
+    pub expiration_time_millis: i64,
+    /// Time at which the search completed, failed or was cancelled, in milliseconds since the Unix
+    /// epoch.
+    pub completion_time_millis: Option<i64>,
+    /// Number of splits to search. It is zero until the relevant splits are listed.
+    pub num_splits_total: u64,
+    /// Number of splits searched so far.
+    pub num_splits_done: u64,
+    /// While the search is running, the number of hits and the aggregations of the splits
+    /// searched so far, without any hit. Once the search completed, its final response.
+    pub response: Option<SearchResponse>,
+    /// Error of the search, if it failed.
+    pub error: Option<String>,
+}
+
+impl AsyncSearchStatus {
+    /// Returns true if the search is still running.
+    pub fn is_running(&self) -> bool {
+        self.state == AsyncSearchState::Running
+    }
+
+    /// Returns true if the response, if any, is not the final response of the search.
+    pub fn is_partial(&self) -> bool {
+        self.state != AsyncSearchState::Completed
+    }
+
+    fn complete(&mut self, root_search_result: crate::Result<SearchResponse>) {
+        match root_search_result {
+            Ok(search_response) => {
+                self.state = AsyncSearchState::Completed;
+                self.num_splits_done = self.num_splits_total;
+                self.response = Some(search_response);
+            }
+            Err(search_error) => {
+                self.state = AsyncSearchState::Failed;
+                self.response = None;
+                self.error = Some(search_error.to_string());
+            }
+        }
+        self.completion_time_millis = Some(now_millis());
+    }
+
+    fn cancel(&mut self) {
+        self.state = AsyncSearchState::Cancelled;
+        self.response = None;
+        self.completion_time_millis.get_or_insert_with(now_millis);
+    }
+
+    /// Drops a response too large to be stored. A completed search then fails, since its final
+    /// response is lost.
+    fn drop_too_large_response(&mut self) {
+        if self.state == AsyncSearchState::Completed {
+            self.state = AsyncSearchState::Failed;
+            self.error = Some(format!(
+                "the response of the async search exceeds the maximum size of \
+                 {MAX_ASYNC_SEARCH_ENTRY_NUM_BYTES} bytes"
+            ));
+        }
+        self.response = None;
+    }
+
+    /// Time left before the search expires. Entries of the KV store are written with this TTL.
+    fn time_to_live(&self) -> Duration {
+        let time_to_live_millis = (self.expiration_time_millis - now_millis()).max(1_000);
+        Duration::from_millis(time_to_live_millis as u64)
+    }
+}
+
+/// In-memory KV store holding the statuses and cancellation flags of asynchronous searches,
+/// bounded by the total size of its entries.
+///
+/// Entries larger than `max_entry_num_bytes` are refused. When the store is full, the expired
+/// entries are evicted first, then the entries closest to their expiration. A search whose
+/// cancellation flag is evicted is considered cancelled.
+#[derive(Clone)]
+pub(crate) struct AsyncSearchStore {
+    inner: Arc<Mutex<AsyncSearchStoreInner>>,
+    capacity_num_bytes: usize,
+    max_entry_num_bytes: usize,
+}
+
+#[derive(Default)]
+struct AsyncSearchStoreInner {
+    entries: HashMap<Vec<u8>, (Vec<u8>, Instant)>,
+    num_bytes: usize,
+}
+
+impl AsyncSearchStoreInner {
+    fn remove(&mut self, key: &[u8]) {
+        if let Some((payload, _)) = self.entries.remove(key) {
+            self.num_bytes -= key.len() + payload.len();
+        }
+    }
+
+    fn evict_expired(&mut self, now: Instant) {
+        let expired_keys: Vec<Vec<u8>> = self
+            .entries
+            .iter()
+            .filter(|(_, (_, expiration))| *expiration <= now)
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in expired_keys {
+            self.remove(&key);
+        }
+    }
+
+    /// Evicts the entries closest to their expiration until `num_bytes` bytes are available.
+    fn evict_until_available(&mut self, capacity_num_bytes: usize, num_bytes: usize) {
+        let mut keys_by_expiration: Vec<(Instant, Vec<u8>)> = self
+            .entries
+            .iter()
+            .map(|(key, (_, expiration))| (*expiration, key.clone()))
+            .collect();
+        keys_by_expiration.sort_unstable();
+
+        for (_, key) in keys_by_expiration {
+            if self.num_bytes + num_bytes <= capacity_num_bytes {
+                break;
+            }
+            self.remove(&key);
+        }
+    }
+}
+
+impl Default for AsyncSearchStore {
+    fn default() -> AsyncSearchStore {
+        AsyncSearchStore::with_capacity(
+            ASYNC_SEARCH_STORE_CAPACITY_NUM_BYTES,
+            MAX_ASYNC_SEARCH_ENTRY_NUM_BYTES,
+        )
+    }
+}
+
+impl AsyncSearchStore {
+    fn with_capacity(capacity_num_bytes: usize, max_entry_num_bytes: usize) -> AsyncSearchStore {
+        AsyncSearchStore {
+            inner: Arc::default(),
+            capacity_num_bytes,
+            max_entry_num_bytes,
+        }
+    }
+
+    /// Stores an entry, evicting other entries if the store is full. Entries larger than the
+    /// maximum entry size are refused.
+    pub fn put(&self, key: Vec<u8>, payload: Vec<u8>, ttl: Duration) {
+        let entry_num_bytes = key.len() + payload.len();
+
+        if entry_num_bytes > self.max_entry_num_bytes {
+            warn!(
+                num_bytes = entry_num_bytes,
+                max_num_bytes = self.max_entry_num_bytes,
+                "async search store entry is too large"
+            );
+            return;
+        }
+        let now = Instant::now();
+        let mut inner = self.inner.lock().unwrap();
+        inner.remove(&key);
+
+        if inner.num_bytes + entry_num_bytes > self.capacity_num_bytes {
+            inner.evict_expired(now);
+        }
+        if inner.num_bytes + entry_num_bytes > self.capacity_num_bytes {
+            warn!(
+                capacity_num_bytes = self.capacity_num_bytes,
+                "async search store is full, evicting entries"
+            );
+            inner.evict_until_available(self.capacity_num_bytes, entry_num_bytes);
+        }
+        inner.num_bytes += entry_num_bytes;
+        inner.entries.insert(key, (payload, now + ttl));
+    }
+
+    pub fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        let inner = self.inner.lock().unwrap();
+        let (payload, expiration) = inner.entries.get(key)?;
+
+        if *expiration <= Instant::now() {
+            return None;
+        }
+        Some(payload.clone())
+    }
+}
+
+/// Returns true if the key belongs to the async search store rather than to the KV store of the
+/// scroll contexts.
+pub(crate) fn is_async_search_key(key: &[u8]) -> bool {
+    key.starts_with(ASYNC_SEARCH_KEY_PREFIX)
+}
+
+/// Progress of a root search, updated as its leaf searches complete.
+#[derive(Debug, Default)]
+pub(crate) struct SearchProgress {
+    inner: Mutex<SearchProgressInner>,
+}
+
+#[derive(Debug, Default)]
+struct SearchProgressInner {
+    num_splits_total: usize,
+    num_splits_done: usize,
+    /// Leaf responses received since the last merge, stripped of their partial hits.
+    leaf_search_responses: Vec<LeafSearchResponse>,
+}
+
+impl SearchProgress {
+    pub fn set_num_splits_total(&self, num_splits_total: usize) {
+        self.inner.lock().unwrap().num_splits_total = num_splits_total;
+    }
+
+    pub fn record_leaf_search_response(
+        &self,
+        num_splits: usize,
+        leaf_search_response: &LeafSearchResponse,
+    ) {
+        // Only the number of hits and the aggregations are reported while the search is running.
+        let leaf_search_response = LeafSearchResponse {
+            num_hits: leaf_search_response.num_hits,
+            intermediate_aggregation_result: leaf_search_response
+                .intermediate_aggregation_result
+                .clone(),
+            ..Default::default()
+        };
+        let mut inner = self.inner.lock().unwrap();
+        inner.num_splits_done += num_splits;
+        inner.leaf_search_responses.push(leaf_search_response);
+    }
+
+    /// Returns the total number of splits and the number of splits searched so far.
+    fn num_splits(&self) -> (usize, usize) {
+        let inner = self.inner.lock().unwrap();
+        (inner.num_splits_total, inner.num_splits_done)
+    }
+
+    fn take_leaf_search_responses(&self) -> Vec<LeafSearchResponse> {
+        std::mem::take(&mut self.inner.lock().unwrap().leaf_search_responses)
+    }
+
+    fn push_leaf_search_response(&self, leaf_search_response: LeafSearchResponse) {
+        self.inner
+            .lock()
+            .unwrap()
+            .leaf_search_responses
+            .push(leaf_search_response);
+    }
+}
+
+/// Merges the leaf responses received so far into a response holding the number of hits and the
+/// aggregations of the splits searched so far.
+async fn merge_partial_search_response(
+    search_progress: &SearchProgress,
+    search_request: &SearchRequest,
+    searcher_context: &SearcherContext,
+) -> crate::Result<Option<SearchResponse>> {
+    let leaf_search_responses = search_progress.take_leaf_search_responses();
+
+    if leaf_search_responses.is_empty() {
+        return Ok(None);
+    }
+    let merge_collector =
+        make_merge_collector(search_request, &searcher_context.get_aggregation_limits())?;
+    let leaf_search_responses: Vec<tantivy::Result<LeafSearchResponse>> =
+        leaf_search_responses.into_iter().map(Ok).collect();
+    let merged_leaf_search_response =
+        crate::run_cpu_intensive(move || merge_collector.merge_fruits(leaf_search_responses))
+            .await
+            .context("failed to merge leaf search responses")?
+            .map_err(|error: TantivyError| SearchError::Internal(error.to_string()))?;
+
+    // The merged response replaces the responses it was made of, so that the next merge only
+    // merges it with the responses received in the meantime.
+    search_progress.push_leaf_search_response(merged_leaf_search_response.clone());
+
+    let aggregation = finalize_aggregation_if_any(
+        search_request,
+        merged_leaf_search_response.intermediate_aggregation_result,
+        searcher_context,
+    )?;
+    let search_response = SearchResponse {
+        num_hits: merged_leaf_search_response.num_hits,
+        aggregation,
+        ..Default::default()
+    };
+    Ok(Some(search_response))
+}
+
+/// Submits a search running in the background on this node.
+///
+/// The status of the search is stored in the KV store of the cluster, so that any searcher can
+/// return it or cancel the search. The status returned is the final one if the search completes
+/// before `wait_for_completion_timeout`, the one of the running search otherwise.
+pub(crate) async fn submit_async_search(
+    submit_request: SubmitAsyncSearchRequest,
+    searcher_context: Arc<SearcherContext>,
+    metastore: MetastoreServiceClient,
+    cluster_client: ClusterClient,
+) -> crate::Result<AsyncSearchStatus> {
+    let SubmitAsyncSearchRequest {
+        search_request,
+        wait_for_completion_timeout,
+        keep_alive,
+    } = submit_request;
+
+    if keep_alive > MAX_ASYNC_SEARCH_KEEP_ALIVE {
+        return Err(SearchError::InvalidArgument(format!(
+            "Quickwit only supports async search keep alive period up to {} secs",
+            MAX_ASYNC_SEARCH_KEEP_ALIVE.as_secs()
+        )));
+    }
+    if search_request.scroll_ttl_secs.is_some() {
+        return Err(SearchError::InvalidArgument(
+            "async search does not support scroll".to_string(),
+        ));
+    }
+    let start_time_millis = now_millis();
+    let mut status = AsyncSearchStatus {
+        id: Ulid::new().to_string(),
+        state: AsyncSearchState::Running,
+        start_time_millis,
+        expiration_time_millis: start_time_millis + keep_alive.as_millis() as i64,
+        completion_time_millis: None,
+        num_splits_total: 0,
+        num_splits_done: 0,
+        response: None,
+        error: None,
+    };
+    // A missing cancellation flag means the search was cancelled or expired, so it is stored
+    // before the search starts.
+    put_cancellation_flag(&cluster_client, &status, false).await;
+    put_status(&cluster_client, &mut status).await;
+
+    // The entries are lost if no searcher could store them.
+    if cluster_client
+        .get_kv(&cancellation_flag_key(&status.id))
+        .await
+        .is_none()
+        || cluster_client
+            .get_kv(&status_key(&status.id))
+            .await
+            .is_none()
+    {
+        return Err(SearchError::Internal(
+            "failed to store the status of the async search".to_string(),
+        ));
+    }
+
+    let (status_tx, mut status_rx) = watch::channel(status.clone());
+    tokio::spawn(run_async_search(
+        status,
+        search_request,
+        searcher_context,
+        metastore,
+        cluster_client,
+        status_tx,
+    ));
+    let wait_for_completion_fut = async {
+        loop {
+            let is_running = status_rx.borrow().is_running();
+
+            if !is_running || status_rx.changed().await.is_err() {
+                break;
+            }
+        }
+    };
+    let _ = tokio::time::timeout(wait_for_completion_timeout, wait_for_completion_fut).await;
+    let status = status_rx.borrow().clone();
+    Ok(status)
+}
+
+/// Runs the search, storing its status at regular intervals until it completes, fails or is
+/// cancelled.
+async fn run_async_search(
+    mut status: AsyncSearchStatus,
+    search_request: SearchRequest,
+    searcher_context: Arc<SearcherContext>,
+    metastore: MetastoreServiceClient,
+    cluster_client: ClusterClient,
+    status_tx: watch::Sender<AsyncSearchStatus>,
+) {
+    let search_progress = Arc::new(SearchProgress::default());
+    let mut root_search_handle = {
+        let search_request = search_request.clone();
+        let searcher_context = searcher_context.clone();
+        let cluster_client = cluster_client.clone();
+        let search_progress = search_progress.clone();
+        tokio::spawn(async move {
+            root_search_with_progress(
+                &searcher_context,
+                search_request,
+                metastore,
+                &cluster_client,
+                Some(search_progress.as_ref()),
+            )
+            .await
+        })
+    };
+    let mut cancellation_check_interval =
+        tokio::time::interval(ASYNC_SEARCH_CANCELLATION_CHECK_INTERVAL);
+    // The first tick completes immediately.
+    cancellation_check_interval.tick().await;
+    let mut last_status_update = Instant::now();
+
+    loop {
+        tokio::select! {
+            root_search_join_result = &mut root_search_handle => {
+                let (num_splits_total, _) = search_progress.num_splits();
+                status.num_splits_total = num_splits_total as u64;
+                let root_search_result = match root_search_join_result {
+                    Ok(root_search_result) => root_search_result,
+                    Err(join_error) => Err(SearchError::from(join_error)),
+                };
+                status.complete(root_search_result);
+            }
+            _ = cancellation_check_interval.tick() => {
+                if is_cancelled(&cluster_client, &status.id).await {
+                    root_search_handle.abort();
+                    status.cancel();
+                } else {
+                    let (num_splits_total, num_splits_done) = search_progress.num_splits();
+                    let made_progress = num_splits_total as u64 != status.num_splits_total
+                        || num_splits_done as u64 != status.num_splits_done;
+
+                    // The status is replicated to several searchers, so the status of a running
+                    // search is only updated once in a while.
+                    if !made_progress
+                        || last_status_update.elapsed() < ASYNC_SEARCH_STATUS_UPDATE_INTERVAL
+                    {
+                        continue;
+                    }
+                    status.num_splits_total = num_splits_total as u64;
+                    status.num_splits_done = num_splits_done as u64;
+
+                    match merge_partial_search_response(
+                        &search_progress,
+                        &search_request,
+                        &searcher_context,
+                    )
+                    .await
+                    {
+                        Ok(Some(search_response)) => status.response = Some(search_response),
+                        Ok(None) => {}
+                        Err(search_error) => {
+                            warn!(async_search_id=%status.id, error=?search_error, "failed to merge partial search response");
+                        }
+                    }
+                }
+            }
+        }
+        put_status(&cluster_client, &mut status).await;
+        last_status_update = Instant::now();
+        status_tx.send_replace(status.clone());
+
+        if !status.is_running() {
+            break;
+        }
+    }
+    if let Some(error) = &status.error {
+        error!(async_search_id=%status.id, error=%error, "async search failed");
+    }
+}
+
+/// Returns the status of an asynchronous search.
+pub(crate) async fn get_async_search(
+    async_search_id: &str,
+    cluster_client: &ClusterClient,
+) -> crate::Result<AsyncSearchStatus> {
+    validate_async_search_id(async_search_id)?;
+    let payload = cluster_client
+        .get_kv(&status_key(async_search_id))
+        .await
+        .ok_or_else(|| SearchError::AsyncSearchNotFound {
+            async_search_id: async_search_id.to_string(),
+        })?;
+    let mut status: AsyncSearchStatus = serde_json::from_slice(&payload)?;
+
+    // The status of a running search is only updated at regular intervals.
+    if status.state != AsyncSearchState::Cancelled
+        && is_cancelled(cluster_client, async_search_id).await
+    {
+        status.cancel();
+    }
+    Ok(status)
+}
+
+/// Cancels an asynchronous search and drops its results.
+///
+/// The search is aborted by the node running it the next time it checks whether it was cancelled.
+pub(crate) async fn cancel_async_search(
+    async_search_id: &str,
+    cluster_client: &ClusterClient,
+) -> crate::Result<AsyncSearchStatus> {
+    let mut status = get_async_search(async_search_id, cluster_client).await?;
+
+    if status.state == AsyncSearchState::Cancelled {
+        return Ok(status);
+    }
+    put_cancellation_flag(cluster_client, &status, true).await;
+
+    if !status.is_running() {
+        // The search will not update its status anymore: we drop its results ourselves.
+        status.cancel();
+        put_status(cluster_client, &mut status).await;
+    } else {
+        status.cancel();
+    }
+    Ok(status)
+}
+
+fn validate_async_search_id(async_search_id: &str) -> crate::Result<()> {
+    Ulid::from_str(async_search_id).map_err(|_| {
+        SearchError::InvalidArgument(format!("invalid async search ID `{async_search_id}`"))
+    })?;
+    Ok(())
+}
+
+fn status_key(async_search_id: &str) -> Vec<u8> {
+    format!("async_search:{async_search_id}:status").into_bytes()
+}
+
+fn cancellation_flag_key(async_search_id: &str) -> Vec<u8> {
+    format!("async_search:{async_search_id}:cancelled").into_bytes()
+}
+
+/// Stores the status. Its response is dropped if it does not fit in an entry of the async search
+/// store.
+async fn put_status(cluster_client: &ClusterClient, status: &mut AsyncSearchStatus) {
+    let mut payload =
+        serde_json::to_vec(status).expect("async search status should be JSON serializable");
+
+    if payload.len() > MAX_ASYNC_SEARCH_ENTRY_NUM_BYTES {
+        warn!(
+            async_search_id=%status.id,
+            num_bytes=payload.len(),
+            "async search response is too large"
+        );
+        status.drop_too_large_response();
+        payload =
+            serde_json::to_vec(status).expect("async search status should be JSON serializable");
+    }
+    cluster_client
+        .put_kv(&status_key(&status.id), &payload, status.time_to_live())
+        .await;
+}
+
+async fn put_cancellation_flag(
+    cluster_client: &ClusterClient,
+    status: &AsyncSearchStatus,
+    cancelled: bool,
+) {
+    let payload = [cancelled as u8];
+    cluster_client
+        .put_kv(
+            &cancellation_flag_key(&status.id),
+            &payload,
+            status.time_to_live(),
+        )
+        .await;
+}
+
+/// Returns true if the search was cancelled. A missing flag means that the search expired or that
+/// its entry was lost, in which case it is considered cancelled as well.
+async fn is_cancelled(cluster_client: &ClusterClient, async_search_id: &str) -> bool {
+    let payload_opt = cluster_client
+        .get_kv(&cancellation_flag_key(async_search_id))
+        .await;
+    payload_opt.as_deref() != Some(&[0u8][..])
+}
+
+fn now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as i64)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::RwLock;
+
+    use quickwit_indexing::MockSplitBuilder;
+    use quickwit_metastore::{
+        IndexMetadata, ListIndexesMetadataResponseExt, ListSplitsResponseExt,
+    };
+    use quickwit_proto::metastore::{ListIndexesMetadataResponse, ListSplitsResponse};
+    use quickwit_query::query_ast::qast_json_helper;
+
+    use super::*;
+    use crate::{searcher_pool_for_test, MockSearchService, SearchJobPlacer};
+
+    fn mock_search_service_with_kv() -> MockSearchService {
+        let kv: Arc<RwLock<HashMap<Vec<u8>, Vec<u8>>>> = Default::default();
+        let kv_clone = kv.clone();
+        let mut mock_search_service = MockSearchService::new();
+        mock_search_service
+            .expect_put_kv()
+            .returning(move |put_kv_req| {
+                kv_clone
+                    .write()
+                    .unwrap()
+                    .insert(put_kv_req.key, put_kv_req.payload);
+            });
+        mock_search_service
+            .expect_get_kv()
+            .returning(move |get_kv_req| kv.read().unwrap().get(&get_kv_req.key).cloned());
+        mock_search_service
+    }
+
+    fn metastore_for_test() -> MetastoreServiceClient {
+        let mut metastore = MetastoreServiceClient::mock();
+        let index_metadata = IndexMetadata::for_test("test-index", "ram:///test-index");
+        let index_uid = index_metadata.index_uid.clone();
+        metastore
+            .expect_list_indexes_metadata()
+            .returning(move |_index_ids_query| {
+                Ok(ListIndexesMetadataResponse::try_from_indexes_metadata(vec![
+                    index_metadata.clone()
+                ])
+                .unwrap())
+            });
+        metastore.expect_list_splits().returning(move |_filter| {
+            let splits = vec![
+                MockSplitBuilder::new("split1")
+                    .with_index_uid(&index_uid)
+                    .build(),
+                MockSplitBuilder::new("split2")
+                    .with_index_uid(&index_uid)
+                    .build(),
+            ];
+            Ok(ListSplitsResponse::try_from_splits(splits).unwrap())
+        });
+        MetastoreServiceClient::from(metastore)
+    }
+
+    #[tokio::test]
+    async fn test_search_progress() {
+        let search_progress = SearchProgress::default();
+        search_progress.set_num_splits_total(3);
+        assert_eq!(search_progress.num_splits(), (3, 0));
+
+        let leaf_search_response = LeafSearchResponse {
+            num_hits: 10,
+            ..Default::default()
+        };
+        search_progress.record_leaf_search_response(2, &leaf_search_response);
+        search_progress.record_leaf_search_response(1, &leaf_search_response);
+        assert_eq!(search_progress.num_splits(), (3, 3));
+
+        let search_request = SearchRequest {
+            max_hits: 10,
+            ..Default::default()
+        };
+        let searcher_context = SearcherContext::for_test();
+        let search_response =
+            merge_partial_search_response(&search_progress, &search_request, &searcher_context)
+                .await
+                .unwrap()
+                .unwrap();
+        assert_eq!(search_response.num_hits, 20);
+        assert!(search_response.hits.is_empty());
+
+        search_progress.record_leaf_search_response(1, &leaf_search_response);
+        let search_response =
+            merge_partial_search_response(&search_progress, &search_request, &searcher_context)
+                .await
+                .unwrap()
+                .unwrap();
+        assert_eq!(search_response.num_hits, 30);
+    }
+
+    #[tokio::test]
+    async fn test_async_search_completes() {
+        let mut mock_search_service = mock_search_service_with_kv();
+        mock_search_service.expect_leaf_search().returning(
+            |leaf_search_req: quickwit_proto::search::LeafSearchRequest| {
+                Ok(LeafSearchResponse {
+                    num_hits: 3 * leaf_search_req.split_offsets.len() as u64,
+                    num_attempted_splits: leaf_search_req.split_offsets.len() as u64,
+                    ..Default::default()
+                })
+            },
+        );
+        let searcher_pool = searcher_pool_for_test([("127.0.0.1:1001", mock_search_service)]);
+        let cluster_client = ClusterClient::new(SearchJobPlacer::new(searcher_pool));
+        let submit_request = SubmitAsyncSearchRequest {
+            search_request: SearchRequest {
+                index_id_patterns: vec!["test-index".to_string()],
+                query_ast: qast_json_helper("test", &["body"]),
+                max_hits: 0,
+                ..Default::default()
+            },
+            wait_for_completion_timeout: Duration::from_secs(10),
+            keep_alive: Duration::from_secs(60),
+        };
+        let status = submit_async_search(
+            submit_request,
+            Arc::new(SearcherContext::for_test()),
+            metastore_for_test(),
+            cluster_client.clone(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(status.state, AsyncSearchState::Completed);
+        assert!(!status.is_partial());
+        assert_eq!(status.num_splits_total, 2);
+        assert_eq!(status.num_splits_done, 2);
+        assert_eq!(status.response.as_ref().unwrap().num_hits, 6);
+        assert!(status.completion_time_millis.is_some());
+        assert_eq!(
+            status.expiration_time_millis - status.start_time_millis,
+            60_000
+        );
+
+        let stored_status = get_async_search(&status.id, &cluster_client).await.unwrap();
+        assert_eq!(stored_status, status);
+
+        let cancelled_status = cancel_async_search(&status.id, &cluster_client)
+            .await
+            .unwrap();
+        assert_eq!(cancelled_status.state, AsyncSearchState::Cancelled);
+        assert!(cancelled_status.response.is_none());
+
+        let stored_status = get_async_search(&status.id, &cluster_client).await.unwrap();
+        assert_eq!(stored_status.state, AsyncSearchState::Cancelled);
+        assert!(stored_status.response.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_async_search_fails() {
+        let mut mock_search_service = mock_search_service_with_kv();
+        mock_search_service
+            .expect_leaf_search()
+            .returning(|_| Err(SearchError::Internal("something bad happened".to_string())));
+        let searcher_pool = searcher_pool_for_test([("127.0.0.1:1001", mock_search_service)]);
+        let cluster_client = ClusterClient::new(SearchJobPlacer::new(searcher_pool));
+        let submit_request = SubmitAsyncSearchRequest {
+            search_request: SearchRequest {
+                index_id_patterns: vec!["test-index".to_string()],
+                query_ast: qast_json_helper("test", &["body"]),
+                max_hits: 10,
+                ..Default::default()
+            },
+            wait_for_completion_timeout: Duration::from_secs(10),
+            keep_alive: Duration::from_secs(60),
+        };
+        let status = submit_async_search(
+            submit_request,
+            Arc::new(SearcherContext::for_test()),
+            metastore_for_test(),
+            cluster_client.clone(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(status.state, AsyncSearchState::Failed);
+        assert!(status.response.is_none());
+        assert!(status.error.unwrap().contains("something bad happened"));
+    }
+
+    #[tokio::test]
+    async fn test_async_search_invalid_requests() {
+        let searcher_pool =
+            searcher_pool_for_test([("127.0.0.1:1001", mock_search_service_with_kv())]);
+        let cluster_client = ClusterClient::new(SearchJobPlacer::new(searcher_pool));
+        let submit_request = SubmitAsyncSearchRequest {
+            search_request: SearchRequest::default(),
+            wait_for_completion_timeout: DEFAULT_WAIT_FOR_COMPLETION_TIMEOUT,
+            keep_alive: Duration::from_secs(10 * 24 * 60 * 60),
+        };
+        let search_error = submit_async_search(
+            submit_request,
+            Arc::new(SearcherContext::for_test()),
+            MetastoreServiceClient::from(MetastoreServiceClient::mock()),
+            cluster_client.clone(),
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(search_error, SearchError::InvalidArgument(_)));
+
+        let search_error = get_async_search("not-an-id", &cluster_client)
+            .await
+            .unwrap_err();
+        assert!(matches!(search_error, SearchError::InvalidArgument(_)));
+
+        let unknown_async_search_id = Ulid::new().to_string();
+        let search_error = get_async_search(&unknown_async_search_id, &cluster_client)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            search_error,
+            SearchError::AsyncSearchNotFound { .. }
+        ));
+        let search_error = cancel_async_search(&unknown_async_search_id, &cluster_client)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            search_error,
+            SearchError::AsyncSearchNotFound { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_async_search_store() {
+        // Entries are made of a 4-byte key and an 8-byte payload.
+        let async_search_store = AsyncSearchStore::with_capacity(24, 16);
+        async_search_store.put(
+            b"key1".to_vec(),
+            b"payload1".to_vec(),
+            Duration::from_secs(60),
+        );
+        async_search_store.put(
+            b"key2".to_vec(),
+            b"payload2".to_vec(),
+            Duration::from_millis(10),
+        );
+        // Updating an entry does not require more room.
+        async_search_store.put(
+            b"key1".to_vec(),
+            b"payload4".to_vec(),
+            Duration::from_secs(60),
+        );
+        assert_eq!(async_search_store.get(b"key1").unwrap(), b"payload4");
+        assert_eq!(async_search_store.get(b"key2").unwrap(), b"payload2");
+
+        // Entries larger than the maximum entry size are refused.
+        async_search_store.put(
+            b"key3".to_vec(),
+            b"large-payload".to_vec(),
+            Duration::from_secs(60),
+        );
+        assert!(async_search_store.get(b"key3").is_none());
+
+        // The store is full: expired entries are evicted first.
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        async_search_store.put(
+            b"key3".to_vec(),
+            b"payload3".to_vec(),
+            Duration::from_secs(60),
+        );
+        assert_eq!(async_search_store.get(b"key3").unwrap(), b"payload3");
+        assert_eq!(async_search_store.get(b"key1").unwrap(), b"payload4");
+
+        // Then the entries closest to their expiration.
+        async_search_store.put(
+            b"key4".to_vec(),
+            b"payload5".to_vec(),
+            Duration::from_secs(60),
+        );
+        assert!(async_search_store.get(b"key1").is_none());
+        assert_eq!(async_search_store.get(b"key3").unwrap(), b"payload3");
+        assert_eq!(async_search_store.get(b"key4").unwrap(), b"payload5");
+        assert_eq!(async_search_store.inner.lock().unwrap().num_bytes, 24);
+    }
+
+    #[test]
+    fn test_async_search_status_drop_too_large_response() {
+        let start_time_millis = now_millis();
+        let mut status = AsyncSearchStatus {
+            id: Ulid::new().to_string(),
+            state: AsyncSearchState::Running,
+            start_time_millis,
+            expiration_time_millis: start_time_millis + 60_000,
+            completion_time_millis: None,
+            num_splits_total: 2,
+            num_splits_done: 1,
+            response: Some(SearchResponse::default()),
+            error: None,
+        };
+        status.drop_too_large_response();
+        assert_eq!(status.state, AsyncSearchState::Running);
+        assert!(status.response.is_none());
+        assert!(status.error.is_none());
+
+        status.complete(Ok(SearchResponse::default()));
+        status.drop_too_large_response();
+        assert_eq!(status.state, AsyncSearchState::Failed);
+        assert!(status.response.is_none());
+        assert!(status.error.unwrap().contains("maximum size"));
+    }
+
+    #[test]
+    fn test_is_async_search_key() {
+        let async_search_id = Ulid::new().to_string();
+        assert!(is_async_search_key(&status_key(&async_search_id)));
+        assert!(is_async_search_key(&cancellation_flag_key(
+            &async_search_id
+        )));
+        assert!(!is_async_search_key(b"01HAV29D4XY3D462FS3D8K5Q2H"));
+    }
+
+    #[tokio::test]
+    async fn test_async_search_missing_cancellation_flag() {
+        let searcher_pool =
+            searcher_pool_for_test([("127.0.0.1:1001", mock_search_service_with_kv())]);
+        let cluster_client = ClusterClient::new(SearchJobPlacer::new(searcher_pool));
+        let start_time_millis = now_millis();
+        let mut status = AsyncSearchStatus {
+            id: Ulid::new().to_string(),
+            state: AsyncSearchState::Running,
+            start_time_millis,
+            expiration_time_millis: start_time_millis + 60_000,
+            completion_time_millis: None,
+            num_splits_total: 0,
+            num_splits_done: 0,
+            response: None,
+            error: None,
+        };
+        put_status(&cluster_client, &mut status).await;
+        assert!(is_cancelled(&cluster_client, &status.id).await);
+
+        let stored_status = get_async_search(&status.id, &cluster_client).await.unwrap();
+        assert_eq!(stored_status.state, AsyncSearchState::Cancelled);
+
+        put_cancellation_flag(&cluster_client, &status, false).await;
+        assert!(!is_cancelled(&cluster_client, &status.id).await);
+    }
+
+    #[tokio::test]
+    async fn test_async_search_not_stored() {
+        let mut mock_search_service = MockSearchService::new();
+        // The searcher fails to store the entries of the search.
+        mock_search_service.expect_put_kv().returning(|_| {});
+        mock_search_service.expect_get_kv().returning(|_| None);
+        mock_search_service.expect_leaf_search().never();
+        let searcher_pool = searcher_pool_for_test([("127.0.0.1:1001", mock_search_service)]);
+        let cluster_client = ClusterClient::new(SearchJobPlacer::new(searcher_pool));
+        let submit_request = SubmitAsyncSearchRequest {
+            search_request: SearchRequest {
+                index_id_patterns: vec!["test-index".to_string()],
+                query_ast: qast_json_helper("test", &["body"]),
+                max_hits: 10,
+                ..Default::default()
+            },
+            wait_for_completion_timeout: Duration::from_secs(10),
+            keep_alive: Duration::from_secs(60),
+        };
+        let search_error = submit_async_search(
+            submit_request,
+            Arc::new(SearcherContext::for_test()),
+            MetastoreServiceClient::from(MetastoreServiceClient::mock()),
+            cluster_client,
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(search_error, SearchError::Internal(_)));
+    }
+}
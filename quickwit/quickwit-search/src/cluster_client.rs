@@ -23,9 +23,9 @@ use base64::Engine;
 use futures::future::ready;
 use futures::{Future, StreamExt};
 use quickwit_proto::search::{
-    FetchDocsRequest, FetchDocsResponse, GetKvRequest, LeafListTermsRequest, LeafListTermsResponse,
-    LeafSearchRequest, LeafSearchResponse, LeafSearchStreamRequest, LeafSearchStreamResponse,
-    PutKvRequest,
+    FetchDocsRequest, FetchDocsResponse, GetKvRequest, LeafListFieldsRequest,
+    LeafListFieldsResponse, LeafListTermsRequest, LeafListTermsResponse, LeafSearchRequest,
+    LeafSearchResponse, LeafSearchStreamRequest, LeafSearchStreamResponse, PutKvRequest,
 };
 use tantivy::aggregation::intermediate_agg_result::IntermediateAggregationResults;
 use tokio::sync::mpsc::error::SendError;
@@ -172,6 +172,15 @@ impl ClusterClient {
         client.leaf_list_terms(request.clone()).await
     }
 
+    /// Leaf list fields.
+    pub async fn leaf_list_fields(
+        &self,
+        request: LeafListFieldsRequest,
+        mut client: SearchServiceClient,
+    ) -> crate::Result<LeafListFieldsResponse> {
+        client.leaf_list_fields(request).await
+    }
+
     /// Attempts to store a given search context within the cluster.
     ///
     /// This function may fail silently, if no clients was available.
@@ -272,6 +281,9 @@ fn merge_leaf_search_response(
     left_response
         .partial_hits
         .extend(right_response.partial_hits);
+    left_response
+        .split_profiles
+        .extend(right_response.split_profiles);
     let intermediate_aggregation_result: Option<Vec<u8>> = match (
         left_response.intermediate_aggregation_result,
         right_response.intermediate_aggregation_result,
@@ -292,6 +304,7 @@ fn merge_leaf_search_response(
             + right_response.num_attempted_splits,
         failed_splits: right_response.failed_splits,
         partial_hits: left_response.partial_hits,
+        split_profiles: left_response.split_profiles,
     })
 }
 
@@ -355,6 +368,7 @@ mod tests {
         PartialHit {
             sort_value: Some(SortValue::U64(sort_value).into()),
             sort_value2: None,
+            extra_sort_values: Vec::new(),
             split_id: split_id.to_string(),
             segment_ord: 1,
             doc_id,
@@ -414,6 +428,7 @@ mod tests {
             start_timestamp: None,
             end_timestamp: None,
             fast_field: "fast".to_string(),
+            fast_fields: Vec::new(),
             output_format: 0,
             partition_by_field: None,
         };
@@ -554,6 +569,7 @@ mod tests {
                         error: "mock_error".to_string(),
                         split_id: "split_2".to_string(),
                         retryable_error: true,
+                        timed_out: false,
                     }],
                     num_attempted_splits: 1,
                     ..Default::default()
@@ -570,6 +586,7 @@ mod tests {
                         error: "mock_error".to_string(),
                         split_id: "split_3".to_string(),
                         retryable_error: true,
+                        timed_out: false,
                     }],
                     num_attempted_splits: 1,
                     ..Default::default()
@@ -593,6 +610,7 @@ mod tests {
             error: "error".to_string(),
             split_id: "split_2".to_string(),
             retryable_error: true,
+            timed_out: false,
         };
         let leaf_response = LeafSearchResponse {
             num_hits: 1,
@@ -623,6 +641,7 @@ mod tests {
             error: "error".to_string(),
             split_id: "split_2".to_string(),
             retryable_error: true,
+            timed_out: false,
         };
         let leaf_response = LeafSearchResponse {
             num_hits: 1,
@@ -19,20 +19,21 @@
 
 use std::cmp::Ordering;
 use std::collections::HashSet;
+use std::sync::Arc;
 
 use itertools::Itertools;
 use quickwit_common::binary_heap::{SortKeyMapper, TopK};
 use quickwit_doc_mapper::{DocMapper, WarmupInfo};
 use quickwit_proto::search::{
     LeafSearchResponse, PartialHit, SearchRequest, SortByValue, SortOrder, SortValue,
-    SplitSearchError,
+    SplitSearchError, SplitSearchProfile,
 };
 use serde::Deserialize;
 use tantivy::aggregation::agg_req::{get_fast_field_names, Aggregations};
 use tantivy::aggregation::intermediate_agg_result::IntermediateAggregationResults;
 use tantivy::aggregation::{AggregationLimits, AggregationSegmentCollector};
 use tantivy::collector::{Collector, SegmentCollector};
-use tantivy::columnar::{ColumnType, MonotonicallyMappableToU64};
+use tantivy::columnar::{ColumnType, MonotonicallyMappableToU64, StrColumn};
 use tantivy::fastfield::Column;
 use tantivy::{DocId, Score, SegmentOrdinal, SegmentReader, TantivyError};
 
@@ -53,28 +54,24 @@ pub(crate) enum SortByComponent {
         order: SortOrder,
     },
 }
-impl From<SortByComponent> for SortByPair {
-    fn from(value: SortByComponent) -> Self {
-        Self {
-            first: value,
-            second: None,
-        }
-    }
-}
+/// The criteria used to sort the hits, by decreasing priority.
+///
+/// Without any criterion, hits are sorted by descending doc id.
 #[derive(Clone)]
-pub(crate) struct SortByPair {
-    first: SortByComponent,
-    second: Option<SortByComponent>,
+pub(crate) struct SortBy {
+    components: Vec<SortByComponent>,
 }
-impl SortByPair {
-    pub fn sort_orders(&self) -> (SortOrder, SortOrder) {
-        (
-            self.first.sort_order(),
-            self.second
-                .as_ref()
-                .map(|sort_by| sort_by.sort_order())
-                .unwrap_or(SortOrder::Desc),
-        )
+impl SortBy {
+    pub fn sort_orders(&self) -> Vec<SortOrder> {
+        self.components
+            .iter()
+            .map(|sort_by| sort_by.sort_order())
+            .collect()
+    }
+    pub fn requires_scoring(&self) -> bool {
+        self.components
+            .iter()
+            .any(|sort_by| sort_by.requires_scoring())
     }
 }
 impl SortByComponent {
@@ -85,18 +82,20 @@ impl SortByComponent {
         match self {
             SortByComponent::DocId { .. } => Ok(SortingFieldExtractorComponent::DocId),
             SortByComponent::FastField { field_name, .. } => {
-                let sort_column_opt: Option<(Column<u64>, ColumnType)> =
-                    segment_reader.fast_fields().u64_lenient(field_name)?;
-                let (sort_column, column_type) = sort_column_opt.unwrap_or_else(|| {
-                    (
-                        Column::build_empty_column(segment_reader.max_doc()),
-                        ColumnType::U64,
-                    )
-                });
-                let sort_field_type = SortFieldType::try_from(column_type)?;
+                let fast_fields = segment_reader.fast_fields();
+                if let Some((sort_column, column_type)) = fast_fields.u64_lenient(field_name)? {
+                    let sort_field_type = SortFieldType::try_from(column_type)?;
+                    return Ok(SortingFieldExtractorComponent::FastField {
+                        sort_column,
+                        sort_field_type,
+                    });
+                }
+                if let Some(str_column) = fast_fields.str(field_name)? {
+                    return Ok(SortingFieldExtractorComponent::StrFastField { str_column });
+                }
                 Ok(SortingFieldExtractorComponent::FastField {
-                    sort_column,
-                    sort_field_type,
+                    sort_column: Column::build_empty_column(segment_reader.max_doc()),
+                    sort_field_type: SortFieldType::U64,
                 })
             }
             SortByComponent::Score { .. } => Ok(SortingFieldExtractorComponent::Score),
@@ -145,9 +144,23 @@ enum SortingFieldExtractorComponent {
         sort_column: Column<u64>,
         sort_field_type: SortFieldType,
     },
+    /// Text fast field. Within a segment, terms are sorted through their ordinal in the
+    /// dictionary, which follows their lexicographic order. See [`str_sort_value_from_term_ord`].
+    StrFastField {
+        str_column: StrColumn,
+    },
     Score,
 }
 
+/// Maps a term ordinal to the sort value used within a segment.
+///
+/// The ordinal is mapped to an odd number, so that a string missing from the dictionary can
+/// be mapped to the even number sitting between its two neighbor terms. This is what
+/// `search_after` needs.
+fn str_sort_value_from_term_ord(term_ord: u64) -> SortValue {
+    SortValue::U64(term_ord * 2 + 1)
+}
+
 impl SortingFieldExtractorComponent {
     /// Returns the sort value for the given element
     ///
@@ -171,41 +184,99 @@ impl SortingFieldExtractorComponent {
             } => sort_column
                 .first(doc_id)
                 .map(|field_val| map_fast_field_to_value(field_val, *sort_field_type)),
+            SortingFieldExtractorComponent::StrFastField { str_column } => str_column
+                .ords()
+                .first(doc_id)
+                .map(str_sort_value_from_term_ord),
             SortingFieldExtractorComponent::Score { .. } => Some(SortValue::F64(score as f64)),
         }
     }
-}
 
-impl From<SortingFieldExtractorComponent> for SortingFieldExtractorPair {
-    fn from(value: SortingFieldExtractorComponent) -> Self {
-        Self {
-            first: value,
-            second: None,
+    /// Converts a sort value, as found in a `PartialHit`, into the sort value used within the
+    /// segment.
+    fn to_segment_sort_value(
+        &self,
+        sort_value_opt: Option<SortValue>,
+    ) -> tantivy::Result<Option<SortValue>> {
+        let (
+            SortingFieldExtractorComponent::StrFastField { str_column },
+            Some(SortValue::Str(text)),
+        ) = (self, &sort_value_opt)
+        else {
+            return Ok(sort_value_opt);
+        };
+        // Binary search of the first term greater than or equal to `text`.
+        let mut term = String::new();
+        let mut lower_term_ord = 0u64;
+        let mut upper_term_ord = str_column.dictionary().num_terms() as u64;
+        while lower_term_ord < upper_term_ord {
+            let middle_term_ord = lower_term_ord + (upper_term_ord - lower_term_ord) / 2;
+            term.clear();
+            str_column.ord_to_str(middle_term_ord, &mut term)?;
+            if term.as_str() < text.as_str() {
+                lower_term_ord = middle_term_ord + 1;
+            } else {
+                upper_term_ord = middle_term_ord;
+            }
         }
+        term.clear();
+        if str_column.ord_to_str(lower_term_ord, &mut term)? && term == *text {
+            return Ok(Some(str_sort_value_from_term_ord(lower_term_ord)));
+        }
+        Ok(Some(SortValue::U64(lower_term_ord * 2)))
+    }
+
+    /// Converts a sort value used within the segment into the sort value exposed in
+    /// `PartialHit`s, which can be compared across segments and splits.
+    fn to_partial_hit_sort_value(
+        &self,
+        sort_value_opt: Option<SortValue>,
+    ) -> tantivy::Result<Option<SortValue>> {
+        let (
+            SortingFieldExtractorComponent::StrFastField { str_column },
+            Some(SortValue::U64(value)),
+        ) = (self, &sort_value_opt)
+        else {
+            return Ok(sort_value_opt);
+        };
+        let mut term = String::new();
+        if !str_column.ord_to_str(value / 2, &mut term)? {
+            return Ok(None);
+        }
+        Ok(Some(SortValue::Str(term)))
     }
 }
 
-pub(crate) struct SortingFieldExtractorPair {
-    first: SortingFieldExtractorComponent,
-    second: Option<SortingFieldExtractorComponent>,
+pub(crate) struct SortingFieldExtractor {
+    components: Vec<SortingFieldExtractorComponent>,
 }
 
-impl SortingFieldExtractorPair {
+impl SortingFieldExtractor {
     /// Returns the list of sort values for the given element
     ///
     /// See also [`SortingFieldExtractorComponent::extract_typed_sort_value_opt`] for more
     /// information.
-    fn extract_typed_sort_value(
-        &self,
-        doc_id: DocId,
-        score: Score,
-    ) -> (Option<SortValue>, Option<SortValue>) {
-        let first = self.first.extract_typed_sort_value_opt(doc_id, score);
-        let second = self
-            .second
-            .as_ref()
-            .and_then(|second| second.extract_typed_sort_value_opt(doc_id, score));
-        (first, second)
+    fn extract_typed_sort_values(&self, doc_id: DocId, score: Score) -> SortValues {
+        self.components
+            .iter()
+            .map(|component| component.extract_typed_sort_value_opt(doc_id, score))
+            .collect()
+    }
+
+    fn to_segment_sort_values(&self, sort_values: SortValues) -> tantivy::Result<SortValues> {
+        self.components
+            .iter()
+            .zip(sort_values.into_values())
+            .map(|(component, sort_value_opt)| component.to_segment_sort_value(sort_value_opt))
+            .collect()
+    }
+
+    fn to_partial_hit_sort_values(&self, sort_values: SortValues) -> tantivy::Result<SortValues> {
+        self.components
+            .iter()
+            .zip(sort_values.into_values())
+            .map(|(component, sort_value_opt)| component.to_partial_hit_sort_value(sort_value_opt))
+            .collect()
     }
 }
 
@@ -228,21 +299,103 @@ impl TryFrom<ColumnType> for SortFieldType {
 }
 
 /// Takes a user-defined sorting criteria and resolves it to a
-/// segment specific `SortingFieldExtractorPair`.
+/// segment specific `SortingFieldExtractor`.
 fn get_score_extractor(
-    sort_by: &SortByPair,
+    sort_by: &SortBy,
     segment_reader: &SegmentReader,
-) -> tantivy::Result<SortingFieldExtractorPair> {
-    Ok(SortingFieldExtractorPair {
-        first: sort_by
-            .first
-            .to_sorting_field_extractor_component(segment_reader)?,
-        second: sort_by
-            .second
-            .as_ref()
-            .map(|first| first.to_sorting_field_extractor_component(segment_reader))
-            .transpose()?,
-    })
+) -> tantivy::Result<SortingFieldExtractor> {
+    let components = sort_by
+        .components
+        .iter()
+        .map(|sort_by| sort_by.to_sorting_field_extractor_component(segment_reader))
+        .collect::<tantivy::Result<Vec<_>>>()?;
+    Ok(SortingFieldExtractor { components })
+}
+
+/// Values of the sort keys of a hit, by decreasing priority.
+///
+/// The first two values are stored inline, so that sorting by up to two keys does not
+/// allocate.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct SortValues {
+    sort_value: Option<SortValue>,
+    sort_value2: Option<SortValue>,
+    extra_sort_values: Vec<Option<SortValue>>,
+}
+
+impl SortValues {
+    fn from_partial_hit(partial_hit: &PartialHit) -> SortValues {
+        let to_sort_value_opt =
+            |sort_by_value: &SortByValue| -> Option<SortValue> { sort_by_value.sort_value.clone() };
+        SortValues {
+            sort_value: partial_hit.sort_value.as_ref().and_then(to_sort_value_opt),
+            sort_value2: partial_hit.sort_value2.as_ref().and_then(to_sort_value_opt),
+            extra_sort_values: partial_hit
+                .extra_sort_values
+                .iter()
+                .map(to_sort_value_opt)
+                .collect(),
+        }
+    }
+
+    fn extra_sort_value(&self, idx: usize) -> Option<&SortValue> {
+        self.extra_sort_values.get(idx).and_then(Option::as_ref)
+    }
+
+    fn into_values(self) -> impl Iterator<Item = Option<SortValue>> {
+        [self.sort_value, self.sort_value2]
+            .into_iter()
+            .chain(self.extra_sort_values)
+    }
+}
+
+impl FromIterator<Option<SortValue>> for SortValues {
+    fn from_iter<I: IntoIterator<Item = Option<SortValue>>>(iter: I) -> Self {
+        let mut sort_values_iter = iter.into_iter();
+        SortValues {
+            sort_value: sort_values_iter.next().flatten(),
+            sort_value2: sort_values_iter.next().flatten(),
+            extra_sort_values: sort_values_iter.collect(),
+        }
+    }
+}
+
+/// Sort orders of the sort keys, by decreasing priority.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct SortOrders {
+    order1: SortOrder,
+    order2: SortOrder,
+    extra_orders: Arc<[SortOrder]>,
+}
+
+impl SortOrders {
+    fn new(sort_orders: &[SortOrder]) -> SortOrders {
+        SortOrders {
+            order1: sort_orders.first().copied().unwrap_or(SortOrder::Desc),
+            order2: sort_orders.get(1).copied().unwrap_or(SortOrder::Desc),
+            extra_orders: sort_orders.iter().skip(2).copied().collect(),
+        }
+    }
+
+    /// Compares two lists of sort values, key by key.
+    fn compare(&self, left: &SortValues, right: &SortValues) -> Ordering {
+        self.order1
+            .compare_opt(&left.sort_value, &right.sort_value)
+            .then_with(|| {
+                self.order2
+                    .compare_opt(&left.sort_value2, &right.sort_value2)
+            })
+            .then_with(|| {
+                self.extra_orders
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, order)| {
+                        order.compare_opt(&left.extra_sort_value(idx), &right.extra_sort_value(idx))
+                    })
+                    .find(|ordering| ordering.is_ne())
+                    .unwrap_or(Ordering::Equal)
+            })
+    }
 }
 
 /// PartialHitHeapItem order is the inverse of the natural order
@@ -296,38 +449,37 @@ enum AggregationSegmentCollectors {
 pub struct QuickwitSegmentCollector {
     num_hits: u64,
     split_id: String,
-    score_extractor: SortingFieldExtractorPair,
+    score_extractor: SortingFieldExtractor,
     // PartialHits in this heap don't contain a split_id yet.
     top_k_hits: TopK<SegmentPartialHit, SegmentPartialHitSortingKey, HitSortingMapper>,
     segment_ord: u32,
     timestamp_filter_opt: Option<TimestampFilter>,
     aggregation: Option<AggregationSegmentCollectors>,
-    search_after: Option<PartialHit>,
+    search_after: Option<SegmentSearchAfter>,
     split_search_after_order: Ordering,
 }
 
+/// The `search_after` hit, with its sort values converted to the segment sort values.
+struct SegmentSearchAfter {
+    sort_values: SortValues,
+    partial_hit: PartialHit,
+}
+
 impl QuickwitSegmentCollector {
     #[inline]
     fn collect_top_k(&mut self, doc_id: DocId, score: Score) {
-        let (sort_value, sort_value2) =
-            self.score_extractor.extract_typed_sort_value(doc_id, score);
+        let sort_values = self
+            .score_extractor
+            .extract_typed_sort_values(doc_id, score);
 
         if let Some(search_after) = &self.search_after {
-            let search_after_value1 = search_after.sort_value.and_then(|v| v.sort_value);
-            let search_after_value2 = search_after.sort_value2.and_then(|v| v.sort_value);
-            let orders = &self.top_k_hits.sort_key_mapper;
-            let mut cmp_result = orders
-                .order1
-                .compare_opt(&sort_value, &search_after_value1)
-                .then_with(|| {
-                    orders
-                        .order2
-                        .compare_opt(&sort_value2, &search_after_value2)
-                });
+            let sort_orders = &self.top_k_hits.sort_key_mapper.sort_orders;
+            let mut cmp_result = sort_orders.compare(&sort_values, &search_after.sort_values);
+            let search_after = &search_after.partial_hit;
             if !search_after.split_id.is_empty() {
                 // TODO actually it's not first, it should be what's in _shard_doc then first then
                 // default
-                let order = orders.order1;
+                let order = sort_orders.order1;
                 cmp_result = cmp_result
                     .then(self.split_search_after_order)
                     .then_with(|| order.compare(&self.segment_ord, &search_after.segment_ord))
@@ -340,8 +492,7 @@ impl QuickwitSegmentCollector {
         }
 
         let hit = SegmentPartialHit {
-            sort_value: sort_value.map(Into::into),
-            sort_value2: sort_value2.map(Into::into),
+            sort_values,
             doc_id,
         };
         self.top_k_hits.add_entry(hit);
@@ -356,26 +507,33 @@ impl QuickwitSegmentCollector {
     }
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Clone, Debug)]
 struct SegmentPartialHit {
-    sort_value: Option<SortValue>,
-    sort_value2: Option<SortValue>,
+    sort_values: SortValues,
     doc_id: DocId,
 }
 
 impl SegmentPartialHit {
-    fn into_partial_hit(self, split_id: String, segment_ord: SegmentOrdinal) -> PartialHit {
-        PartialHit {
-            sort_value: self.sort_value.map(|sort_value| SortByValue {
-                sort_value: Some(sort_value),
-            }),
-            sort_value2: self.sort_value2.map(|sort_value| SortByValue {
-                sort_value: Some(sort_value),
-            }),
+    /// Converts the segment hit into a `PartialHit`, with one sort value per sort key.
+    fn into_partial_hit(
+        self,
+        split_id: String,
+        segment_ord: SegmentOrdinal,
+        num_sort_values: usize,
+    ) -> PartialHit {
+        let mut partial_hit = PartialHit {
             doc_id: self.doc_id,
             split_id,
             segment_ord,
-        }
+            ..Default::default()
+        };
+        partial_hit.set_sort_by_values(
+            self.sort_values
+                .into_values()
+                .take(num_sort_values)
+                .map(|sort_value| SortByValue { sort_value }),
+        );
+        partial_hit
     }
 }
 
@@ -403,14 +561,22 @@ impl SegmentCollector for QuickwitSegmentCollector {
     }
 
     fn harvest(self) -> Self::Fruit {
+        let num_sort_values = self.score_extractor.components.len();
         let partial_hits: Vec<PartialHit> = self
             .top_k_hits
             .finalize()
             .into_iter()
-            .map(|segment_partial_hit: SegmentPartialHit| {
-                segment_partial_hit.into_partial_hit(self.split_id.clone(), self.segment_ord)
+            .map(|mut segment_partial_hit: SegmentPartialHit| {
+                segment_partial_hit.sort_values = self
+                    .score_extractor
+                    .to_partial_hit_sort_values(segment_partial_hit.sort_values)?;
+                Ok(segment_partial_hit.into_partial_hit(
+                    self.split_id.clone(),
+                    self.segment_ord,
+                    num_sort_values,
+                ))
             })
-            .collect();
+            .collect::<tantivy::Result<_>>()?;
 
         let intermediate_aggregation_result = match self.aggregation {
             Some(AggregationSegmentCollectors::FindTraceIdsSegmentCollector(collector)) => {
@@ -432,6 +598,7 @@ impl SegmentCollector for QuickwitSegmentCollector {
             partial_hits,
             failed_splits: Vec::new(),
             num_attempted_splits: 1,
+            split_profiles: Vec::new(),
         })
     }
 }
@@ -469,7 +636,7 @@ pub(crate) struct QuickwitCollector {
     pub split_id: String,
     pub start_offset: usize,
     pub max_hits: usize,
-    pub sort_by: SortByPair,
+    pub sort_by: SortBy,
     timestamp_filter_builder_opt: Option<TimestampFilterBuilder>,
     pub aggregation: Option<QuickwitAggregations>,
     pub aggregation_limits: AggregationLimits,
@@ -479,9 +646,8 @@ pub(crate) struct QuickwitCollector {
 impl QuickwitCollector {
     pub fn fast_field_names(&self) -> HashSet<String> {
         let mut fast_field_names = HashSet::default();
-        self.sort_by.first.add_fast_field(&mut fast_field_names);
-        if let Some(sort_by_second) = &self.sort_by.second {
-            sort_by_second.add_fast_field(&mut fast_field_names);
+        for sort_by in &self.sort_by.components {
+            sort_by.add_fast_field(&mut fast_field_names);
         }
         if let Some(aggregations) = &self.aggregation {
             fast_field_names.extend(aggregations.fast_field_names());
@@ -536,8 +702,8 @@ impl Collector for QuickwitCollector {
             None => None,
         };
         let score_extractor = get_score_extractor(&self.sort_by, segment_reader)?;
-        let (order1, order2) = self.sort_by.sort_orders();
-        let sort_key_mapper = HitSortingMapper { order1, order2 };
+        let sort_key_mapper = HitSortingMapper::new(&self.sort_by.sort_orders());
+        let order1 = sort_key_mapper.sort_orders.order1;
         let split_search_after_order = if let Some(search_after) = &self.search_after {
             if !search_after.split_id.is_empty() {
                 order1.compare(&self.split_id, &search_after.split_id)
@@ -550,6 +716,14 @@ impl Collector for QuickwitCollector {
             // this value isn't actually used.
             Ordering::Equal
         };
+        let search_after = match &self.search_after {
+            Some(search_after) => Some(SegmentSearchAfter {
+                sort_values: score_extractor
+                    .to_segment_sort_values(SortValues::from_partial_hit(search_after))?,
+                partial_hit: search_after.clone(),
+            }),
+            None => None,
+        };
         Ok(QuickwitSegmentCollector {
             num_hits: 0u64,
             split_id: self.split_id.clone(),
@@ -558,7 +732,7 @@ impl Collector for QuickwitCollector {
             segment_ord,
             timestamp_filter_opt,
             aggregation,
-            search_after: self.search_after.clone(),
+            search_after,
             split_search_after_order,
         })
     }
@@ -567,13 +741,7 @@ impl Collector for QuickwitCollector {
         // We do not need BM25 scoring in Quickwit if it is not opted-in.
         // By returning false, we inform tantivy that it does not need to decompress
         // term frequencies.
-        self.sort_by.first.requires_scoring()
-            || self
-                .sort_by
-                .second
-                .as_ref()
-                .map(|sort_by| sort_by.requires_scoring())
-                .unwrap_or(false)
+        self.sort_by.requires_scoring()
     }
 
     fn merge_fruits(
@@ -586,14 +754,9 @@ impl Collector for QuickwitCollector {
         // All leaves will return their top [0..start_offset + max_hits) documents.
         // We compute the overall [0..start_offset + max_hits) documents ...
         let num_hits = self.start_offset + self.max_hits;
-        let (sort_order1, sort_order2) = self.sort_by.sort_orders();
-        let mut merged_leaf_response = merge_leaf_responses(
-            &self.aggregation,
-            segment_fruits?,
-            sort_order1,
-            sort_order2,
-            num_hits,
-        )?;
+        let sort_orders = self.sort_by.sort_orders();
+        let mut merged_leaf_response =
+            merge_leaf_responses(&self.aggregation, segment_fruits?, &sort_orders, num_hits)?;
         // ... and drop the first [..start_offsets) hits.
         // note that self.start_offset is 0 when merging from leaf_search, and is only set when
         // merging from root_search, so as to remove the firsts elements only once.
@@ -661,8 +824,7 @@ fn merge_intermediate_aggregation_result<'a>(
 fn merge_leaf_responses(
     aggregations_opt: &Option<QuickwitAggregations>,
     mut leaf_responses: Vec<LeafSearchResponse>,
-    sort_order1: SortOrder,
-    sort_order2: SortOrder,
+    sort_orders: &[SortOrder],
     max_hits: usize,
 ) -> tantivy::Result<LeafSearchResponse> {
     // Optimization: No merging needed if there is only one result.
@@ -690,22 +852,21 @@ fn merge_leaf_responses(
         .flat_map(|leaf_response| leaf_response.failed_splits.iter())
         .cloned()
         .collect_vec();
-    let all_partial_hits: Vec<PartialHit> = leaf_responses
-        .into_iter()
-        .flat_map(|leaf_response| leaf_response.partial_hits)
-        .collect();
-    let top_k_partial_hits: Vec<PartialHit> = top_k_partial_hits(
-        all_partial_hits.into_iter(),
-        sort_order1,
-        sort_order2,
-        max_hits,
-    );
+    let mut split_profiles: Vec<SplitSearchProfile> = Vec::new();
+    let mut all_partial_hits: Vec<PartialHit> = Vec::new();
+    for leaf_response in leaf_responses {
+        split_profiles.extend(leaf_response.split_profiles);
+        all_partial_hits.extend(leaf_response.partial_hits);
+    }
+    let top_k_partial_hits: Vec<PartialHit> =
+        top_k_partial_hits(all_partial_hits.into_iter(), sort_orders, max_hits);
     Ok(LeafSearchResponse {
         intermediate_aggregation_result: merged_intermediate_aggregation_result,
         num_hits,
         partial_hits: top_k_partial_hits,
         failed_splits,
         num_attempted_splits,
+        split_profiles,
     })
 }
 
@@ -715,11 +876,10 @@ fn merge_leaf_responses(
 /// TODO we could possibly optimize the sort away (but I doubt it matters).
 fn top_k_partial_hits(
     partial_hits: impl Iterator<Item = PartialHit>,
-    order1: SortOrder,
-    order2: SortOrder,
+    sort_orders: &[SortOrder],
     num_hits: usize,
 ) -> Vec<PartialHit> {
-    let sort_key_mapper = HitSortingMapper { order1, order2 };
+    let sort_key_mapper = HitSortingMapper::new(sort_orders);
     let mut top_k_hits = TopK::new(num_hits, sort_key_mapper);
 
     partial_hits.for_each(|hit| top_k_hits.add_entry(hit));
@@ -727,7 +887,7 @@ fn top_k_partial_hits(
     top_k_hits.finalize()
 }
 
-pub(crate) fn sort_by_from_request(search_request: &SearchRequest) -> SortByPair {
+pub(crate) fn sort_by_from_request(search_request: &SearchRequest) -> SortBy {
     let to_sort_by_component = |field_name: &str, order| {
         if field_name == "_score" {
             SortByComponent::Score { order }
@@ -741,28 +901,17 @@ pub(crate) fn sort_by_from_request(search_request: &SearchRequest) -> SortByPair
         }
     };
 
-    let num_sort_fields = search_request.sort_fields.len();
-    if num_sort_fields == 0 {
-        SortByComponent::DocId {
-            order: SortOrder::Desc,
-        }
-        .into()
-    } else if num_sort_fields == 1 {
-        let sort_field = &search_request.sort_fields[0];
-        let order = SortOrder::from_i32(sort_field.sort_order).unwrap_or(SortOrder::Desc);
-        to_sort_by_component(&sort_field.field_name, order).into()
-    } else if num_sort_fields == 2 {
-        let sort_field1 = &search_request.sort_fields[0];
-        let order1 = SortOrder::from_i32(sort_field1.sort_order).unwrap_or(SortOrder::Desc);
-        let sort_field2 = &search_request.sort_fields[1];
-        let order2 = SortOrder::from_i32(sort_field2.sort_order).unwrap_or(SortOrder::Desc);
-        SortByPair {
-            first: to_sort_by_component(&sort_field1.field_name, order1),
-            second: Some(to_sort_by_component(&sort_field2.field_name, order2)),
-        }
-    } else {
-        panic!("Sort by more than 2 fields is not supported yet.")
-    }
+    // Without sort fields, hits are sorted by descending doc id, which is the tie-breaker of
+    // any sort.
+    let components = search_request
+        .sort_fields
+        .iter()
+        .map(|sort_field| {
+            let order = SortOrder::from_i32(sort_field.sort_order).unwrap_or(SortOrder::Desc);
+            to_sort_by_component(&sort_field.field_name, order)
+        })
+        .collect();
+    SortBy { components }
 }
 
 /// Builds the QuickwitCollector, in function of the information that was requested by the user.
@@ -816,35 +965,25 @@ pub(crate) fn make_merge_collector(
     })
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 struct SegmentPartialHitSortingKey {
-    sort_value: Option<SortValue>,
-    sort_value2: Option<SortValue>,
+    sort_values: SortValues,
     doc_id: DocId,
     // TODO This should not be there.
-    sort_order: SortOrder,
-    // TODO This should not be there.
-    sort_order2: SortOrder,
+    sort_orders: SortOrders,
 }
 
 impl Ord for SegmentPartialHitSortingKey {
     fn cmp(&self, other: &SegmentPartialHitSortingKey) -> Ordering {
         debug_assert_eq!(
-            self.sort_order, other.sort_order,
-            "comparing two PartialHitSortingKey of different ordering"
-        );
-        debug_assert_eq!(
-            self.sort_order2, other.sort_order2,
+            self.sort_orders, other.sort_orders,
             "comparing two PartialHitSortingKey of different ordering"
         );
         let order = self
-            .sort_order
-            .compare_opt(&self.sort_value, &other.sort_value);
-        let order2 = self
-            .sort_order2
-            .compare_opt(&self.sort_value2, &other.sort_value2);
-        let order_addr = self.sort_order.compare(&self.doc_id, &other.doc_id);
-        order.then(order2).then(order_addr)
+            .sort_orders
+            .compare(&self.sort_values, &other.sort_values);
+        let order_addr = self.sort_orders.order1.compare(&self.doc_id, &other.doc_id);
+        order.then(order_addr)
     }
 }
 
@@ -856,36 +995,29 @@ impl PartialOrd for SegmentPartialHitSortingKey {
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 struct PartialHitSortingKey {
-    sort_value: Option<SortValue>,
-    sort_value2: Option<SortValue>,
+    sort_values: SortValues,
     address: GlobalDocAddress,
     // TODO remove this
-    sort_order: SortOrder,
-    sort_order2: SortOrder,
+    sort_orders: SortOrders,
 }
 
 impl Ord for PartialHitSortingKey {
     fn cmp(&self, other: &PartialHitSortingKey) -> Ordering {
         assert_eq!(
-            self.sort_order, other.sort_order,
-            "comparing two PartialHitSortingKey of different ordering"
-        );
-        assert_eq!(
-            self.sort_order2, other.sort_order2,
+            self.sort_orders, other.sort_orders,
             "comparing two PartialHitSortingKey of different ordering"
         );
 
         let order = self
-            .sort_order
-            .compare_opt(&self.sort_value, &other.sort_value);
-
-        let order2 = self
-            .sort_order2
-            .compare_opt(&self.sort_value2, &other.sort_value2);
+            .sort_orders
+            .compare(&self.sort_values, &other.sort_values);
 
-        let order_addr = self.sort_order.compare(&self.address, &other.address);
+        let order_addr = self
+            .sort_orders
+            .order1
+            .compare(&self.address, &other.address);
 
-        order.then(order2).then(order_addr)
+        order.then(order_addr)
     }
 }
 
@@ -897,19 +1029,24 @@ impl PartialOrd for PartialHitSortingKey {
 
 #[derive(Clone)]
 struct HitSortingMapper {
-    order1: SortOrder,
-    order2: SortOrder,
+    sort_orders: SortOrders,
+}
+
+impl HitSortingMapper {
+    fn new(sort_orders: &[SortOrder]) -> HitSortingMapper {
+        HitSortingMapper {
+            sort_orders: SortOrders::new(sort_orders),
+        }
+    }
 }
 
 impl SortKeyMapper<PartialHit> for HitSortingMapper {
     type Key = PartialHitSortingKey;
     fn get_sort_key(&self, partial_hit: &PartialHit) -> PartialHitSortingKey {
         PartialHitSortingKey {
-            sort_value: partial_hit.sort_value.and_then(|v| v.sort_value),
-            sort_value2: partial_hit.sort_value2.and_then(|v| v.sort_value),
+            sort_values: SortValues::from_partial_hit(partial_hit),
             address: GlobalDocAddress::from_partial_hit(partial_hit),
-            sort_order: self.order1,
-            sort_order2: self.order2,
+            sort_orders: self.sort_orders.clone(),
         }
     }
 }
@@ -918,11 +1055,9 @@ impl SortKeyMapper<SegmentPartialHit> for HitSortingMapper {
     type Key = SegmentPartialHitSortingKey;
     fn get_sort_key(&self, partial_hit: &SegmentPartialHit) -> SegmentPartialHitSortingKey {
         SegmentPartialHitSortingKey {
-            sort_value: partial_hit.sort_value,
-            sort_value2: partial_hit.sort_value2,
+            sort_values: partial_hit.sort_values.clone(),
             doc_id: partial_hit.doc_id,
-            sort_order: self.order1,
-            sort_order2: self.order2,
+            sort_orders: self.sort_orders.clone(),
         }
     }
 }
@@ -936,13 +1071,13 @@ pub(crate) struct IncrementalCollector {
     num_hits: u64,
     failed_splits: Vec<SplitSearchError>,
     num_attempted_splits: u64,
+    split_profiles: Vec<SplitSearchProfile>,
 }
 
 impl IncrementalCollector {
     /// Create a new incremental collector
     pub(crate) fn new(inner: QuickwitCollector) -> Self {
-        let (order1, order2) = inner.sort_by.sort_orders();
-        let sort_key_mapper = HitSortingMapper { order1, order2 };
+        let sort_key_mapper = HitSortingMapper::new(&inner.sort_by.sort_orders());
         IncrementalCollector {
             top_k_hits: TopK::new(inner.max_hits + inner.start_offset, sort_key_mapper),
             inner,
@@ -950,6 +1085,7 @@ impl IncrementalCollector {
             num_hits: 0,
             failed_splits: Vec::new(),
             num_attempted_splits: 0,
+            split_profiles: Vec::new(),
         }
     }
 
@@ -961,12 +1097,14 @@ impl IncrementalCollector {
             failed_splits,
             num_attempted_splits,
             intermediate_aggregation_result,
+            split_profiles,
         } = leaf_response;
 
         self.num_hits += num_hits;
         self.top_k_hits.add_entries(partial_hits.into_iter());
         self.failed_splits.extend(failed_splits);
         self.num_attempted_splits += num_attempted_splits;
+        self.split_profiles.extend(split_profiles);
         if let Some(intermediate_aggregation_result) = intermediate_aggregation_result {
             self.intermediate_aggregation_results
                 .push(intermediate_aggregation_result);
@@ -1007,6 +1145,7 @@ impl IncrementalCollector {
             failed_splits: self.failed_splits,
             num_attempted_splits: self.num_attempted_splits,
             intermediate_aggregation_result,
+            split_profiles: self.split_profiles,
         })
     }
 }
@@ -1073,6 +1212,7 @@ mod tests {
         let make_doc = |sort_value: u64| PartialHit {
             sort_value: Some(SortValue::U64(sort_value).into()),
             sort_value2: None,
+            extra_sort_values: Vec::new(),
             split_id: "split1".to_string(),
             segment_ord: 0u32,
             doc_id: 0u32,
@@ -1080,8 +1220,7 @@ mod tests {
         assert_eq!(
             top_k_partial_hits(
                 vec![make_doc(1u64), make_doc(3u64), make_doc(2u64),].into_iter(),
-                SortOrder::Asc,
-                SortOrder::Asc,
+                &[SortOrder::Asc, SortOrder::Asc],
                 2
             ),
             vec![make_doc(1), make_doc(2)]
@@ -1093,6 +1232,7 @@ mod tests {
         let make_hit_given_split_id = |split_id: u64| PartialHit {
             sort_value: Some(SortValue::U64(0u64).into()),
             sort_value2: None,
+            extra_sort_values: Vec::new(),
             split_id: format!("split_{split_id}"),
             segment_ord: 0u32,
             doc_id: 0u32,
@@ -1105,8 +1245,7 @@ mod tests {
                     make_hit_given_split_id(2u64),
                 ]
                 .into_iter(),
-                SortOrder::Desc,
-                SortOrder::Desc,
+                &[SortOrder::Desc, SortOrder::Desc],
                 2
             ),
             &[make_hit_given_split_id(3), make_hit_given_split_id(2)]
@@ -1119,8 +1258,7 @@ mod tests {
                     make_hit_given_split_id(2u64),
                 ]
                 .into_iter(),
-                SortOrder::Asc,
-                SortOrder::Asc,
+                &[SortOrder::Asc, SortOrder::Asc],
                 2
             ),
             &[make_hit_given_split_id(1), make_hit_given_split_id(2)]
@@ -1432,6 +1570,7 @@ mod tests {
                 sort_value2: Some(SortByValue {
                     sort_value: val2.map(SortValue::U64),
                 }),
+                extra_sort_values: Vec::new(),
             })
             .collect::<Vec<_>>();
         // we eliminte based on sort value
@@ -1478,6 +1617,7 @@ mod tests {
                 doc_id: 5,
                 sort_value: None,
                 sort_value2: None,
+                extra_sort_values: Vec::new(),
             };
             let request = SearchRequest {
                 max_hits: 1000,
@@ -1576,10 +1716,12 @@ mod tests {
                     doc_id: 123,
                     sort_value: Some(SortValue::I64(1234).into()),
                     sort_value2: None,
+                    extra_sort_values: Vec::new(),
                 }],
                 failed_splits: Vec::new(),
                 num_attempted_splits: 3,
                 intermediate_aggregation_result: None,
+                split_profiles: Vec::new(),
             }],
         );
 
@@ -1593,10 +1735,12 @@ mod tests {
                     doc_id: 123,
                     sort_value: Some(SortValue::I64(1234).into()),
                     sort_value2: None,
+                    extra_sort_values: Vec::new(),
                 }],
                 failed_splits: Vec::new(),
                 num_attempted_splits: 3,
-                intermediate_aggregation_result: None
+                intermediate_aggregation_result: None,
+                split_profiles: Vec::new(),
             }
         );
 
@@ -1621,6 +1765,7 @@ mod tests {
                             doc_id: 123,
                             sort_value: Some(SortValue::I64(1234).into()),
                             sort_value2: None,
+                            extra_sort_values: Vec::new(),
                         },
                         PartialHit {
                             split_id: "1".to_string(),
@@ -1628,11 +1773,13 @@ mod tests {
                             doc_id: 125,
                             sort_value: Some(SortValue::I64(1236).into()),
                             sort_value2: None,
+                            extra_sort_values: Vec::new(),
                         },
                     ],
                     failed_splits: Vec::new(),
                     num_attempted_splits: 3,
                     intermediate_aggregation_result: None,
+                    split_profiles: Vec::new(),
                 },
                 LeafSearchResponse {
                     num_hits: 10,
@@ -1642,14 +1789,17 @@ mod tests {
                         doc_id: 3,
                         sort_value: Some(SortValue::I64(1235).into()),
                         sort_value2: None,
+                        extra_sort_values: Vec::new(),
                     }],
                     failed_splits: vec![SplitSearchError {
                         error: "fake error".to_string(),
                         split_id: "3".to_string(),
                         retryable_error: true,
+                        timed_out: false,
                     }],
                     num_attempted_splits: 2,
                     intermediate_aggregation_result: None,
+                    split_profiles: Vec::new(),
                 },
             ],
         );
@@ -1665,6 +1815,7 @@ mod tests {
                         doc_id: 125,
                         sort_value: Some(SortValue::I64(1236).into()),
                         sort_value2: None,
+                        extra_sort_values: Vec::new(),
                     },
                     PartialHit {
                         split_id: "2".to_string(),
@@ -1672,15 +1823,18 @@ mod tests {
                         doc_id: 3,
                         sort_value: Some(SortValue::I64(1235).into()),
                         sort_value2: None,
+                        extra_sort_values: Vec::new(),
                     },
                 ],
                 failed_splits: vec![SplitSearchError {
                     error: "fake error".to_string(),
                     split_id: "3".to_string(),
                     retryable_error: true,
+                    timed_out: false,
                 }],
                 num_attempted_splits: 5,
-                intermediate_aggregation_result: None
+                intermediate_aggregation_result: None,
+                split_profiles: Vec::new(),
             }
         );
 
@@ -1706,6 +1860,7 @@ mod tests {
                             doc_id: 123,
                             sort_value: Some(SortValue::I64(1234).into()),
                             sort_value2: None,
+                            extra_sort_values: Vec::new(),
                         },
                         PartialHit {
                             split_id: "1".to_string(),
@@ -1713,11 +1868,13 @@ mod tests {
                             doc_id: 125,
                             sort_value: Some(SortValue::I64(1236).into()),
                             sort_value2: None,
+                            extra_sort_values: Vec::new(),
                         },
                     ],
                     failed_splits: Vec::new(),
                     num_attempted_splits: 3,
                     intermediate_aggregation_result: None,
+                    split_profiles: Vec::new(),
                 },
                 LeafSearchResponse {
                     num_hits: 10,
@@ -1727,14 +1884,17 @@ mod tests {
                         doc_id: 3,
                         sort_value: Some(SortValue::I64(1235).into()),
                         sort_value2: None,
+                        extra_sort_values: Vec::new(),
                     }],
                     failed_splits: vec![SplitSearchError {
                         error: "fake error".to_string(),
                         split_id: "3".to_string(),
                         retryable_error: true,
+                        timed_out: false,
                     }],
                     num_attempted_splits: 2,
                     intermediate_aggregation_result: None,
+                    split_profiles: Vec::new(),
                 },
             ],
         );
@@ -1750,6 +1910,7 @@ mod tests {
                         doc_id: 123,
                         sort_value: Some(SortValue::I64(1234).into()),
                         sort_value2: None,
+                        extra_sort_values: Vec::new(),
                     },
                     PartialHit {
                         split_id: "2".to_string(),
@@ -1757,15 +1918,18 @@ mod tests {
                         doc_id: 3,
                         sort_value: Some(SortValue::I64(1235).into()),
                         sort_value2: None,
+                        extra_sort_values: Vec::new(),
                     },
                 ],
                 failed_splits: vec![SplitSearchError {
                     error: "fake error".to_string(),
                     split_id: "3".to_string(),
                     retryable_error: true,
+                    timed_out: false,
                 }],
                 num_attempted_splits: 5,
-                intermediate_aggregation_result: None
+                intermediate_aggregation_result: None,
+                split_profiles: Vec::new(),
             }
         );
         // TODO would be nice to test aggregation too.
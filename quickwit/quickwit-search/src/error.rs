@@ -30,6 +30,8 @@ use tokio::task::JoinError;
 #[allow(missing_docs)]
 #[derive(Error, Debug, Serialize, Deserialize, Clone)]
 pub enum SearchError {
+    #[error("could not find async search `{async_search_id}`")]
+    AsyncSearchNotFound { async_search_id: String },
     #[error("could not find indexes matching the IDs `{index_ids:?}`")]
     IndexesNotFound { index_ids: Vec<String> },
     #[error("internal error: `{0}`")]
@@ -47,6 +49,7 @@ pub enum SearchError {
 impl ServiceError for SearchError {
     fn error_code(&self) -> ServiceErrorCode {
         match self {
+            SearchError::AsyncSearchNotFound { .. } => ServiceErrorCode::NotFound,
             SearchError::IndexesNotFound { .. } => ServiceErrorCode::NotFound,
             SearchError::Internal(_) => ServiceErrorCode::Internal,
             SearchError::InvalidAggregationRequest(_) => ServiceErrorCode::BadRequest,
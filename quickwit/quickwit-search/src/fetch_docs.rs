@@ -17,7 +17,9 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
+use std::cmp::Ordering;
 use std::collections::{BTreeMap, HashMap};
+use std::ops::Range;
 use std::sync::Arc;
 
 use anyhow::{Context, Ok};
@@ -25,19 +27,26 @@ use futures::{StreamExt, TryStreamExt};
 use itertools::Itertools;
 use quickwit_doc_mapper::DocMapper;
 use quickwit_proto::search::{
-    FetchDocsResponse, PartialHit, SnippetRequest, SplitIdAndFooterOffsets,
+    FetchDocsResponse, PartialHit, SnippetConfig, SnippetRequest, SplitIdAndFooterOffsets,
 };
+use quickwit_query::find_field_or_hit_dynamic;
 use quickwit_storage::Storage;
+use serde_json::Value as JsonValue;
+use tantivy::json_utils::JsonTermWriter;
 use tantivy::query::Query;
-use tantivy::schema::{Document as DocumentTrait, Field, OwnedValue, TantivyDocument, Value};
-use tantivy::{ReloadPolicy, Score, Searcher, SnippetGenerator, Term};
+use tantivy::schema::{Document as DocumentTrait, Field, FieldType, TantivyDocument, Value};
+use tantivy::tokenizer::{TextAnalyzer, TokenStream};
+use tantivy::{ReloadPolicy, Score, Searcher, Term};
 use tracing::{error, Instrument};
 
 use crate::leaf::open_index_with_caches;
 use crate::service::SearcherContext;
 use crate::{convert_document_to_json_string, GlobalDocAddress};
 
-const SNIPPET_MAX_NUM_CHARS: usize = 150;
+const DEFAULT_SNIPPET_FRAGMENT_SIZE: usize = 150;
+const DEFAULT_SNIPPET_NUM_FRAGMENTS: usize = 1;
+const DEFAULT_SNIPPET_PRE_TAG: &str = "<b>";
+const DEFAULT_SNIPPET_POST_TAG: &str = "</b>";
 
 /// Given a list of global doc address, fetches all the documents and
 /// returns them as a hashmap.
@@ -48,6 +57,7 @@ async fn fetch_docs_to_map(
     splits: &[SplitIdAndFooterOffsets],
     doc_mapper: Arc<dyn DocMapper>,
     snippet_request_opt: Option<&SnippetRequest>,
+    fetch_fields: &[String],
 ) -> anyhow::Result<HashMap<GlobalDocAddress, Document>> {
     let mut split_fetch_docs_futures = Vec::new();
 
@@ -75,6 +85,7 @@ async fn fetch_docs_to_map(
             split_and_offset,
             doc_mapper.clone(),
             snippet_request_opt,
+            fetch_fields,
         ));
     }
 
@@ -108,6 +119,8 @@ async fn fetch_docs_to_map(
 /// This function takes a list of partial hits (possibly from different splits)
 /// and the storage associated to an index, fetches the document from
 /// the split document stores, and returns the full hits.
+///
+/// If `fetch_fields` is not empty, the hits only hold these top-level fields.
 pub async fn fetch_docs(
     searcher_context: Arc<SearcherContext>,
     partial_hits: Vec<PartialHit>,
@@ -115,6 +128,7 @@ pub async fn fetch_docs(
     splits: &[SplitIdAndFooterOffsets],
     doc_mapper: Arc<dyn DocMapper>,
     snippet_request_opt: Option<&SnippetRequest>,
+    fetch_fields: &[String],
 ) -> anyhow::Result<FetchDocsResponse> {
     let global_doc_addrs: Vec<GlobalDocAddress> = partial_hits
         .iter()
@@ -128,6 +142,7 @@ pub async fn fetch_docs(
         splits,
         doc_mapper,
         snippet_request_opt,
+        fetch_fields,
     )
     .await?;
 
@@ -168,6 +183,7 @@ async fn fetch_docs_in_split(
     split: &SplitIdAndFooterOffsets,
     doc_mapper: Arc<dyn DocMapper>,
     snippet_request_opt: Option<&SnippetRequest>,
+    fetch_fields: &[String],
 ) -> anyhow::Result<Vec<(GlobalDocAddress, Document)>> {
     global_doc_addrs.sort_by_key(|doc| doc.doc_addr);
     // Opens the index without the ephemeral unbounded cache, this cache is indeed not useful
@@ -204,7 +220,13 @@ async fn fetch_docs_in_split(
                 .await
                 .context("searcher-doc-async")?;
 
-            let named_field_doc = doc.to_named_doc(moved_searcher.schema());
+            let mut named_field_doc = doc.to_named_doc(moved_searcher.schema());
+
+            if !fetch_fields.is_empty() {
+                named_field_doc
+                    .0
+                    .retain(|field_name, _| fetch_fields.contains(field_name));
+            }
             let content_json =
                 convert_document_to_json_string(named_field_doc, &*moved_doc_mapper)?;
             if fields_snippet_generator_opt_clone.is_none() {
@@ -228,15 +250,7 @@ async fn fetch_docs_in_split(
                 ));
             }
 
-            let mut snippets = HashMap::new();
-            for (field, field_values) in doc.get_sorted_field_values() {
-                let field_name = moved_searcher.schema().get_field_name(field);
-                if let Some(values) = fields_snippet_generator_clone
-                    .snippets_from_field_values(field_name, field_values)
-                {
-                    snippets.insert(field_name, values);
-                }
-            }
+            let snippets = fields_snippet_generator_clone.snippets_from_doc(&doc);
             let snippet_json = serde_json::to_string(&snippets)?;
             Ok((
                 global_doc_addr,
@@ -259,33 +273,27 @@ async fn fetch_docs_in_split(
 // the snippet fields from a search request.
 #[derive(Clone)]
 struct FieldsSnippetGenerator {
-    field_generators: Arc<HashMap<String, SnippetGenerator>>,
+    field_generators: Arc<HashMap<String, FieldSnippetGenerator>>,
 }
 
 impl FieldsSnippetGenerator {
-    // Returns the  snippets from fields values.
-    fn snippets_from_field_values(
-        &self,
-        field_name: &str,
-        field_values: Vec<&OwnedValue>,
-    ) -> Option<Vec<String>> {
-        if let Some(snippet_generator) = self.field_generators.get(field_name) {
-            let values = field_values
+    // Returns the snippets of the document, per snippet field.
+    //
+    // Fields without any value in the document are omitted.
+    fn snippets_from_doc(&self, doc: &TantivyDocument) -> HashMap<&str, Vec<String>> {
+        let mut snippets = HashMap::new();
+        for (field_name, field_generator) in self.field_generators.iter() {
+            let texts = field_generator.texts_from_doc(doc);
+            if texts.is_empty() {
+                continue;
+            }
+            let values = texts
                 .into_iter()
-                .filter_map(|value| {
-                    value.as_str().and_then(|text| {
-                        let snippet = snippet_generator.snippet(text);
-                        match snippet.is_empty() {
-                            false => Some(snippet.to_html()),
-                            _ => None,
-                        }
-                    })
-                })
+                .flat_map(|text| field_generator.snippets(&text))
                 .collect();
-            Some(values)
-        } else {
-            None
+            snippets.insert(field_name.as_str(), values);
         }
+        snippets
     }
 
     fn is_empty(&self) -> bool {
@@ -293,6 +301,208 @@ impl FieldsSnippetGenerator {
     }
 }
 
+/// Snippet settings, with the defaults applied.
+#[derive(Clone, Debug)]
+struct SnippetSettings {
+    fragment_size: usize,
+    // If 0, the whole field value is returned.
+    num_fragments: usize,
+    pre_tag: String,
+    post_tag: String,
+}
+
+impl From<Option<&SnippetConfig>> for SnippetSettings {
+    fn from(snippet_config_opt: Option<&SnippetConfig>) -> Self {
+        let snippet_config = snippet_config_opt.cloned().unwrap_or_default();
+        SnippetSettings {
+            fragment_size: snippet_config
+                .fragment_size
+                .map(|fragment_size| fragment_size as usize)
+                .unwrap_or(DEFAULT_SNIPPET_FRAGMENT_SIZE),
+            num_fragments: snippet_config
+                .num_fragments
+                .map(|num_fragments| num_fragments as usize)
+                .unwrap_or(DEFAULT_SNIPPET_NUM_FRAGMENTS),
+            pre_tag: snippet_config
+                .pre_tag
+                .unwrap_or_else(|| DEFAULT_SNIPPET_PRE_TAG.to_string()),
+            post_tag: snippet_config
+                .post_tag
+                .unwrap_or_else(|| DEFAULT_SNIPPET_POST_TAG.to_string()),
+        }
+    }
+}
+
+/// Candidate fragment of a text, with the byte ranges of its highlighted terms.
+struct Fragment {
+    start_offset: usize,
+    stop_offset: usize,
+    score: Score,
+    highlighted: Vec<Range<usize>>,
+}
+
+impl Fragment {
+    fn new(start_offset: usize) -> Fragment {
+        Fragment {
+            start_offset,
+            stop_offset: start_offset,
+            score: 0.0,
+            highlighted: Vec::new(),
+        }
+    }
+}
+
+// Generates the snippets of a text field or of a path within a JSON field.
+struct FieldSnippetGenerator {
+    field: Field,
+    // Path of the snippet field within the JSON field, empty for a text field.
+    json_path: String,
+    // Highlighted terms, with their scores.
+    terms: BTreeMap<String, Score>,
+    tokenizer: TextAnalyzer,
+    settings: SnippetSettings,
+}
+
+impl FieldSnippetGenerator {
+    // Returns the texts of the snippet field in the document.
+    fn texts_from_doc(&self, doc: &TantivyDocument) -> Vec<String> {
+        let mut texts = Vec::new();
+        for value in doc.get_all(self.field) {
+            if self.json_path.is_empty() {
+                if let Some(text) = value.as_str() {
+                    texts.push(text.to_string());
+                }
+            } else if let Ok(json_value) = serde_json::to_value(value) {
+                collect_json_texts(&json_value, &self.json_path, &mut texts);
+            }
+        }
+        texts
+    }
+
+    // Returns the highlighted fragments of a text, in the order they appear in the text.
+    fn snippets(&self, text: &str) -> Vec<String> {
+        let mut tokenizer = self.tokenizer.clone();
+        let mut token_stream = tokenizer.token_stream(text);
+        let mut fragments: Vec<Fragment> = Vec::new();
+        let mut fragment = Fragment::new(0);
+
+        while let Some(token) = token_stream.next() {
+            if self.settings.num_fragments > 0
+                && token.offset_to - fragment.start_offset > self.settings.fragment_size
+            {
+                if fragment.score > 0.0 {
+                    fragments.push(fragment);
+                }
+                fragment = Fragment::new(token.offset_from);
+            }
+            fragment.stop_offset = token.offset_to;
+            if let Some(score) = self.terms.get(&token.text) {
+                fragment.score += score;
+                fragment
+                    .highlighted
+                    .push(token.offset_from..token.offset_to);
+            }
+        }
+        if fragment.score > 0.0 {
+            fragments.push(fragment);
+        }
+        if self.settings.num_fragments == 0 {
+            // The whole field value is highlighted.
+            return fragments
+                .into_iter()
+                .map(|mut fragment| {
+                    fragment.start_offset = 0;
+                    fragment.stop_offset = text.len();
+                    self.render_fragment(text, &fragment)
+                })
+                .collect();
+        }
+        // The sort is stable: on equal scores, the first fragments are preferred.
+        fragments.sort_by(|left, right| {
+            right
+                .score
+                .partial_cmp(&left.score)
+                .unwrap_or(Ordering::Equal)
+        });
+        fragments.truncate(self.settings.num_fragments);
+        fragments.sort_by_key(|fragment| fragment.start_offset);
+        fragments
+            .iter()
+            .map(|fragment| self.render_fragment(text, fragment))
+            .collect()
+    }
+
+    fn render_fragment(&self, text: &str, fragment: &Fragment) -> String {
+        let mut snippet = String::with_capacity(fragment.stop_offset - fragment.start_offset);
+        let mut start_offset = fragment.start_offset;
+        for highlighted in collapse_overlapping_ranges(&fragment.highlighted) {
+            snippet.push_str(&escape_html(&text[start_offset..highlighted.start]));
+            snippet.push_str(&self.settings.pre_tag);
+            snippet.push_str(&escape_html(&text[highlighted.clone()]));
+            snippet.push_str(&self.settings.post_tag);
+            start_offset = highlighted.end;
+        }
+        snippet.push_str(&escape_html(&text[start_offset..fragment.stop_offset]));
+        snippet
+    }
+}
+
+// Collects the strings found at `json_path` in a JSON value. The keys of the JSON objects may
+// contain dots.
+fn collect_json_texts(json_value: &JsonValue, json_path: &str, texts: &mut Vec<String>) {
+    match json_value {
+        JsonValue::String(text) if json_path.is_empty() => texts.push(text.clone()),
+        JsonValue::Array(json_values) => {
+            for json_value in json_values {
+                collect_json_texts(json_value, json_path, texts);
+            }
+        }
+        JsonValue::Object(json_object) if !json_path.is_empty() => {
+            for (key, json_value) in json_object {
+                let Some(path_suffix) = json_path.strip_prefix(key.as_str()) else {
+                    continue;
+                };
+                if path_suffix.is_empty() {
+                    collect_json_texts(json_value, "", texts);
+                } else if let Some(sub_path) = path_suffix.strip_prefix('.') {
+                    collect_json_texts(json_value, sub_path, texts);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+// Merges the ranges overlapping each other. The ranges are expected to be sorted by start.
+fn collapse_overlapping_ranges(ranges: &[Range<usize>]) -> Vec<Range<usize>> {
+    let mut collapsed_ranges: Vec<Range<usize>> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        if let Some(last_range) = collapsed_ranges.last_mut() {
+            if range.start < last_range.end {
+                last_range.end = last_range.end.max(range.end);
+                continue;
+            }
+        }
+        collapsed_ranges.push(range.clone());
+    }
+    collapsed_ranges
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#x27;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
 // Creates FieldsSnippetGenerator.
 async fn create_fields_snippet_generator(
     searcher: &Searcher,
@@ -303,10 +513,12 @@ async fn create_fields_snippet_generator(
     let query_ast_resolved = serde_json::from_str(&snippet_request.query_ast_resolved)
         .context("failed to deserialize QueryAst")?;
     let (query, _) = doc_mapper.query(schema.clone(), &query_ast_resolved, false)?;
+    let settings = SnippetSettings::from(snippet_request.snippet_config.as_ref());
     let mut snippet_generators = HashMap::new();
     for field_name in &snippet_request.snippet_fields {
-        let field = schema.get_field(field_name)?;
-        let snippet_generator = create_snippet_generator(searcher, &query, field).await?;
+        let (field, _, json_path) = find_field_or_hit_dynamic(field_name, schema)?;
+        let snippet_generator =
+            create_snippet_generator(searcher, &query, field, json_path, settings.clone()).await?;
         snippet_generators.insert(field_name.clone(), snippet_generator);
     }
 
@@ -315,12 +527,29 @@ async fn create_fields_snippet_generator(
     })
 }
 
-// Creates a snippet generator associated to a field.
+// Creates a snippet generator associated to a field, or to a path within a JSON field.
 async fn create_snippet_generator(
     searcher: &Searcher,
     query: &dyn Query,
     field: Field,
-) -> anyhow::Result<SnippetGenerator> {
+    json_path: &str,
+    settings: SnippetSettings,
+) -> anyhow::Result<FieldSnippetGenerator> {
+    // The value of the terms of a JSON path are prefixed by the path and the value type.
+    let term_value_prefix: Vec<u8> = match searcher.schema().get_field_entry(field).field_type() {
+        FieldType::JsonObject(json_options) if !json_path.is_empty() => {
+            let mut term = Term::with_capacity(json_path.len() + 8);
+            let mut json_term_writer = JsonTermWriter::from_field_and_json_path(
+                field,
+                json_path,
+                json_options.is_expand_dots_enabled(),
+                &mut term,
+            );
+            json_term_writer.set_str("");
+            json_term_writer.term().serialized_value_bytes().to_vec()
+        }
+        _ => Vec::new(),
+    };
     let mut terms: Vec<&Term> = Vec::new();
     // TODO ok with termset?
     query.query_terms(&mut |term, _need_position| {
@@ -330,8 +559,13 @@ async fn create_snippet_generator(
     });
     let mut terms_text: BTreeMap<String, f32> = BTreeMap::default();
     for term in terms {
-        let value = term.value();
-        let Some(term_str) = value.as_str() else {
+        let Some(term_value) = term
+            .serialized_value_bytes()
+            .strip_prefix(term_value_prefix.as_slice())
+        else {
+            continue;
+        };
+        let Ok(term_str) = std::str::from_utf8(term_value) else {
             continue;
         };
         let doc_freq = searcher.doc_freq_async(term).await?;
@@ -341,10 +575,11 @@ async fn create_snippet_generator(
         }
     }
     let tokenizer = searcher.index().tokenizer_for_field(field)?;
-    Ok(SnippetGenerator::new(
-        terms_text,
-        tokenizer,
+    Ok(FieldSnippetGenerator {
         field,
-        SNIPPET_MAX_NUM_CHARS,
-    ))
+        json_path: json_path.replace("\\.", "."),
+        terms: terms_text,
+        tokenizer,
+        settings,
+    })
 }
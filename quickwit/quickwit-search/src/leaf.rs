@@ -20,7 +20,9 @@
 use std::collections::{HashMap, HashSet};
 use std::ops::Bound;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use anyhow::Context;
 use futures::future::try_join_all;
@@ -31,16 +33,20 @@ use quickwit_doc_mapper::{DocMapper, TermRange, WarmupInfo};
 use quickwit_proto::search::{
     CountHits, LeafListTermsResponse, LeafSearchResponse, ListTermsRequest, PartialHit,
     SearchRequest, SortOrder, SortValue, SplitIdAndFooterOffsets, SplitSearchError,
+    SplitSearchProfile,
 };
-use quickwit_query::query_ast::QueryAst;
+use quickwit_query::query_ast::{QueryAst, TermExpansion};
 use quickwit_storage::{
-    wrap_storage_with_cache, BundleStorage, MemorySizedCache, OwnedBytes, SplitCache, Storage,
+    wrap_storage_with_cache, wrap_storage_with_read_stats, BundleStorage, MemorySizedCache,
+    OwnedBytes, ReadStats, SplitCache, Storage,
 };
 use tantivy::directory::FileSlice;
 use tantivy::fastfield::FastFieldReaders;
 use tantivy::schema::{Field, FieldType};
 use tantivy::tokenizer::TokenizerManager;
 use tantivy::{Index, ReloadPolicy, Searcher, Term};
+use tokio::task::AbortHandle;
+use tokio::time::{timeout_at, Instant};
 use tracing::*;
 
 use crate::collector::{make_collector_for_split, make_merge_collector, IncrementalCollector};
@@ -52,10 +58,16 @@ async fn get_split_footer_from_cache_or_fetch(
     index_storage: Arc<dyn Storage>,
     split_and_footer_offsets: &SplitIdAndFooterOffsets,
     footer_cache: &MemorySizedCache<String>,
+    split_read_stats_opt: Option<&SplitReadStats>,
 ) -> anyhow::Result<OwnedBytes> {
     {
         let possible_val = footer_cache.get(&split_and_footer_offsets.split_id);
         if let Some(footer_data) = possible_val {
+            if let Some(split_read_stats) = split_read_stats_opt {
+                split_read_stats
+                    .footer_cache_hit
+                    .store(true, Ordering::Relaxed);
+            }
             return Ok(footer_data);
         }
     }
@@ -83,44 +95,112 @@ async fn get_split_footer_from_cache_or_fetch(
     Ok(footer_data_opt)
 }
 
+/// Reads of a split through its cache layers, recorded to profile a split search.
+#[derive(Default)]
+struct SplitReadStats {
+    footer_cache_hit: AtomicBool,
+    // Reads of the split directory, in front of the fast fields cache.
+    directory_read_stats: Arc<ReadStats>,
+    // Reads of the split file, in front of the split cache.
+    split_file_read_stats: Arc<ReadStats>,
+    // Reads of the split file hitting the storage.
+    storage_read_stats: Arc<ReadStats>,
+}
+
+impl SplitReadStats {
+    // The cache hits are approximated by the difference between the reads in front of and behind
+    // a cache. A read missing a cache may be split into several reads or merged with others behind
+    // it, so these counts are not exact.
+    fn num_fast_field_cache_hits(&self) -> u64 {
+        self.directory_read_stats
+            .num_reads()
+            .saturating_sub(self.split_file_read_stats.num_reads())
+    }
+
+    fn num_split_cache_hits(&self) -> u64 {
+        self.split_file_read_stats
+            .num_reads()
+            .saturating_sub(self.storage_read_stats.num_reads())
+    }
+}
+
 /// Opens a `tantivy::Index` for the given split with several cache layers:
 /// - A split footer cache given by `SearcherContext.split_footer_cache`.
 /// - A fast fields cache given by `SearcherContext.storage_long_term_cache`.
 /// - An ephemeral unbounded cache directory whose lifetime is tied to the returned `Index`.
-#[instrument(skip_all, fields(split_footer_start=split_and_footer_offsets.split_footer_start, split_footer_end=split_and_footer_offsets.split_footer_end))]
 pub(crate) async fn open_index_with_caches(
     searcher_context: &SearcherContext,
     index_storage: Arc<dyn Storage>,
     split_and_footer_offsets: &SplitIdAndFooterOffsets,
     tokenizer_manager: Option<&TokenizerManager>,
     ephemeral_unbounded_cache: bool,
+) -> anyhow::Result<Index> {
+    open_index_with_caches_and_read_stats(
+        searcher_context,
+        index_storage,
+        split_and_footer_offsets,
+        tokenizer_manager,
+        ephemeral_unbounded_cache,
+        None,
+    )
+    .await
+}
+
+/// Opens a `tantivy::Index` like [`open_index_with_caches`], recording the reads of the split
+/// through each cache layer if `split_read_stats_opt` is set.
+#[instrument(skip_all, fields(split_footer_start=split_and_footer_offsets.split_footer_start, split_footer_end=split_and_footer_offsets.split_footer_end))]
+async fn open_index_with_caches_and_read_stats(
+    searcher_context: &SearcherContext,
+    index_storage: Arc<dyn Storage>,
+    split_and_footer_offsets: &SplitIdAndFooterOffsets,
+    tokenizer_manager: Option<&TokenizerManager>,
+    ephemeral_unbounded_cache: bool,
+    split_read_stats_opt: Option<&SplitReadStats>,
 ) -> anyhow::Result<Index> {
     let split_file = PathBuf::from(format!("{}.split", split_and_footer_offsets.split_id));
     let footer_data = get_split_footer_from_cache_or_fetch(
         index_storage.clone(),
         split_and_footer_offsets,
         &searcher_context.split_footer_cache,
+        split_read_stats_opt,
     )
     .await?;
 
+    let index_storage = if let Some(split_read_stats) = split_read_stats_opt {
+        wrap_storage_with_read_stats(index_storage, split_read_stats.storage_read_stats.clone())
+    } else {
+        index_storage
+    };
     // We wrap the top-level storage with the split cache.
     // This is before the bundle storage: at this point, this storage is reading `.split` files.
-    let index_storage_with_split_cache =
+    let mut index_storage_with_split_cache =
         if let Some(split_cache) = searcher_context.split_cache_opt.as_ref() {
             SplitCache::wrap_storage(split_cache.clone(), index_storage.clone())
         } else {
             index_storage.clone()
         };
+    if let Some(split_read_stats) = split_read_stats_opt {
+        index_storage_with_split_cache = wrap_storage_with_read_stats(
+            index_storage_with_split_cache,
+            split_read_stats.split_file_read_stats.clone(),
+        );
+    }
 
     let (hotcache_bytes, bundle_storage) = BundleStorage::open_from_split_data(
         index_storage_with_split_cache,
         split_file,
         FileSlice::new(Arc::new(footer_data)),
     )?;
-    let bundle_storage_with_cache = wrap_storage_with_cache(
+    let mut bundle_storage_with_cache = wrap_storage_with_cache(
         searcher_context.fast_fields_cache.clone(),
         Arc::new(bundle_storage),
     );
+    if let Some(split_read_stats) = split_read_stats_opt {
+        bundle_storage_with_cache = wrap_storage_with_read_stats(
+            bundle_storage_with_cache,
+            split_read_stats.directory_read_stats.clone(),
+        );
+    }
     let directory = StorageDirectory::new(bundle_storage_with_cache);
     let hot_directory = if ephemeral_unbounded_cache {
         let caching_directory = CachingDirectory::new_unbounded(Arc::new(directory));
@@ -170,6 +250,9 @@ pub(crate) async fn warmup(searcher: &Searcher, warmup_info: &WarmupInfo) -> any
         .instrument(debug_span!("warm_up_fieldnorms"));
     let warm_up_postings_future = warm_up_postings(searcher, &warmup_info.posting_field_names)
         .instrument(debug_span!("warm_up_postings"));
+    let warm_up_term_expansions_future =
+        warm_up_term_expansions(searcher, &warmup_info.term_expansions)
+            .instrument(debug_span!("warm_up_term_expansions"));
 
     tokio::try_join!(
         warm_up_terms_future,
@@ -178,6 +261,7 @@ pub(crate) async fn warmup(searcher: &Searcher, warmup_info: &WarmupInfo) -> any
         warm_up_term_dict_future,
         warm_up_fieldnorms_future,
         warm_up_postings_future,
+        warm_up_term_expansions_future,
     )?;
 
     Ok(())
@@ -316,6 +400,31 @@ async fn warm_up_term_ranges(
     Ok(())
 }
 
+/// Warms up the term dictionary of the fields targeted by wildcard, regex and fuzzy queries,
+/// then the postings of the terms they expand to.
+async fn warm_up_term_expansions(
+    searcher: &Searcher,
+    term_expansions: &HashSet<TermExpansion>,
+) -> anyhow::Result<()> {
+    let mut warm_up_futures = Vec::new();
+    for term_expansion in term_expansions {
+        for segment_reader in searcher.segment_readers() {
+            let inverted_index = segment_reader.inverted_index(term_expansion.field)?;
+            warm_up_futures.push(async move {
+                inverted_index.terms().warm_up_dictionary().await?;
+                let terms = term_expansion.expand(&inverted_index)?;
+                let warm_up_postings_futures = terms
+                    .iter()
+                    .map(|term| inverted_index.warm_postings(term, false));
+                try_join_all(warm_up_postings_futures).await?;
+                std::io::Result::Ok(())
+            });
+        }
+    }
+    try_join_all(warm_up_futures).await?;
+    Ok(())
+}
+
 async fn warm_up_fieldnorms(searcher: &Searcher, requires_scoring: bool) -> anyhow::Result<()> {
     if !requires_scoring {
         return Ok(());
@@ -344,20 +453,32 @@ async fn leaf_search_single_split(
     doc_mapper: Arc<dyn DocMapper>,
 ) -> crate::Result<LeafSearchResponse> {
     rewrite_request(&mut search_request, &split);
-    if let Some(cached_answer) = searcher_context
+    // The profile flag is removed from the request, so that it is not part of the cache key.
+    let profile = std::mem::take(&mut search_request.profile);
+    if let Some(mut cached_answer) = searcher_context
         .leaf_search_cache
         .get(split.clone(), search_request.clone())
     {
+        if profile {
+            cached_answer.split_profiles = vec![SplitSearchProfile {
+                split_id: split.split_id.clone(),
+                partial_request_cache_hit: true,
+                num_docs_matched: cached_answer.num_hits,
+                ..Default::default()
+            }];
+        }
         return Ok(cached_answer);
     }
 
     let split_id = split.split_id.to_string();
-    let index = open_index_with_caches(
+    let split_read_stats_opt: Option<SplitReadStats> = profile.then(SplitReadStats::default);
+    let index = open_index_with_caches_and_read_stats(
         searcher_context,
         storage,
         &split,
         Some(doc_mapper.tokenizer_manager()),
         true,
+        split_read_stats_opt.as_ref(),
     )
     .await?;
     let split_schema = index.schema();
@@ -380,9 +501,17 @@ async fn leaf_search_single_split(
     let collector_warmup_info = quickwit_collector.warmup_info();
     warmup_info.merge(collector_warmup_info);
 
+    let warmup_start = Instant::now();
+    let num_bytes_fetched_before_warmup = split_read_stats_opt
+        .as_ref()
+        .map(|split_read_stats| split_read_stats.storage_read_stats.num_bytes())
+        .unwrap_or_default();
     warmup(&searcher, &warmup_info).await?;
+    let warmup_micros = warmup_start.elapsed().as_micros() as u64;
+
+    let collection_start = Instant::now();
     let span = info_span!("tantivy_search");
-    let leaf_search_response = crate::run_cpu_intensive(move || {
+    let mut leaf_search_response = crate::run_cpu_intensive(move || {
         let _span_guard = span.enter();
         searcher.search(&query, &quickwit_collector)
     })
@@ -390,10 +519,28 @@ async fn leaf_search_single_split(
     .map_err(|_| {
         crate::SearchError::Internal(format!("leaf search panicked. split={split_id}"))
     })??;
+    let collection_micros = collection_start.elapsed().as_micros() as u64;
 
     searcher_context
         .leaf_search_cache
         .put(split, search_request, leaf_search_response.clone());
+
+    if let Some(split_read_stats) = split_read_stats_opt {
+        leaf_search_response.split_profiles = vec![SplitSearchProfile {
+            split_id,
+            partial_request_cache_hit: false,
+            footer_cache_hit: split_read_stats.footer_cache_hit.load(Ordering::Relaxed),
+            num_fast_field_cache_hits: split_read_stats.num_fast_field_cache_hits(),
+            num_split_cache_hits: split_read_stats.num_split_cache_hits(),
+            warmup_micros,
+            warmup_num_bytes_fetched: split_read_stats
+                .storage_read_stats
+                .num_bytes()
+                .saturating_sub(num_bytes_fetched_before_warmup),
+            num_docs_matched: leaf_search_response.num_hits,
+            collection_micros,
+        }];
+    }
     Ok(leaf_search_response)
 }
 
@@ -563,13 +710,32 @@ pub async fn leaf_search(
     let split_filter = Arc::new(Mutex::new(split_filter));
     let incremental_merge_collector = Arc::new(Mutex::new(incremental_merge_collector));
 
+    let deadline_opt: Option<Instant> = request
+        .timeout_millis
+        .map(|timeout_millis| Instant::now() + Duration::from_millis(timeout_millis));
+
     let mut leaf_search_single_split_futures: Vec<_> = Vec::with_capacity(splits.len());
+    let mut abort_split_searches_on_drop = AbortSplitSearchesOnDrop::default();
+    let mut timed_out_splits: Vec<SplitSearchError> = Vec::new();
 
     for split in splits {
-        let leaf_split_search_permit = searcher_context.leaf_search_split_semaphore
+        let leaf_split_search_permit_fut = searcher_context
+            .leaf_search_split_semaphore
             .clone()
-            .acquire_owned()
-            .await
+            .acquire_owned();
+        let leaf_split_search_permit_res_opt = match deadline_opt {
+            Some(deadline) if Instant::now() >= deadline => None,
+            Some(deadline) => timeout_at(deadline, leaf_split_search_permit_fut)
+                .await
+                .ok(),
+            None => Some(leaf_split_search_permit_fut.await),
+        };
+        let Some(leaf_split_search_permit_res) = leaf_split_search_permit_res_opt else {
+            // The search timed out before this split could be searched.
+            timed_out_splits.push(SplitSearchError::for_timed_out_split(split.split_id));
+            continue;
+        };
+        let leaf_split_search_permit = leaf_split_search_permit_res
             .expect("Failed to acquire permit. This should never happen! Please, report on https://github.com/quickwit-oss/quickwit/issues.");
 
         let mut request = (*request).clone();
@@ -583,7 +749,7 @@ pub async fn leaf_search(
             request.sort_fields.clear();
         }
 
-        leaf_search_single_split_futures.push(tokio::spawn(
+        let leaf_search_single_split_join_handle = tokio::spawn(
             leaf_search_single_split_wrapper(
                 request,
                 searcher_context.clone(),
@@ -593,9 +759,14 @@ pub async fn leaf_search(
                 split_filter.clone(),
                 incremental_merge_collector.clone(),
                 leaf_split_search_permit,
+                deadline_opt,
             )
             .in_current_span(),
-        ));
+        );
+        abort_split_searches_on_drop
+            .abort_handles
+            .push(leaf_search_single_split_join_handle.abort_handle());
+        leaf_search_single_split_futures.push(leaf_search_single_split_join_handle);
     }
 
     // TODO we could cancel running splits when !run_all_splits and the running split can no longer
@@ -609,6 +780,10 @@ pub async fn leaf_search(
         Err(filter_merger) => filter_merger.lock().unwrap().clone(),
     };
 
+    for timed_out_split in timed_out_splits {
+        incremental_merge_collector.add_failed_split(timed_out_split);
+    }
+
     for result in split_search_results {
         // splits that did not panic were already added to the collector
         if let Err(e) = result {
@@ -618,6 +793,7 @@ pub async fn leaf_search(
                 split_id: "unknown".to_string(),
                 error: format!("{}", SearchError::from(e)),
                 retryable_error: true,
+                timed_out: false,
             })
         }
     }
@@ -628,6 +804,23 @@ pub async fn leaf_search(
         .context("failed to merge split search responses")?
 }
 
+/// Aborts the split search tasks when dropped.
+///
+/// Split searches run in their own tasks: this cancels them when the leaf search is dropped,
+/// for instance when the client of the leaf search request disconnects.
+#[derive(Default)]
+struct AbortSplitSearchesOnDrop {
+    abort_handles: Vec<AbortHandle>,
+}
+
+impl Drop for AbortSplitSearchesOnDrop {
+    fn drop(&mut self) {
+        for abort_handle in &self.abort_handles {
+            abort_handle.abort();
+        }
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 async fn leaf_search_single_split_wrapper(
     request: SearchRequest,
@@ -638,23 +831,38 @@ async fn leaf_search_single_split_wrapper(
     split_filter: Arc<Mutex<CanSplitDoBetter>>,
     incremental_merge_collector: Arc<Mutex<IncrementalCollector>>,
     leaf_split_search_permit: tokio::sync::OwnedSemaphorePermit,
+    deadline_opt: Option<Instant>,
 ) {
     crate::SEARCH_METRICS.leaf_searches_splits_total.inc();
     let timer = crate::SEARCH_METRICS
         .leaf_search_split_duration_secs
         .start_timer();
-    let leaf_search_single_split_res = leaf_search_single_split(
+    let leaf_search_single_split_fut = leaf_search_single_split(
         &searcher_context,
         request,
         index_storage,
         split.clone(),
         doc_mapper,
-    )
-    .await;
+    );
+    // On timeout, the split search future is dropped, which cancels the split search.
+    let leaf_search_single_split_res_opt = match deadline_opt {
+        Some(deadline) => timeout_at(deadline, leaf_search_single_split_fut)
+            .await
+            .ok(),
+        None => Some(leaf_search_single_split_fut.await),
+    };
 
     // We explicitly drop it, to highlight it to the reader
     std::mem::drop(leaf_split_search_permit);
 
+    let Some(leaf_search_single_split_res) = leaf_search_single_split_res_opt else {
+        incremental_merge_collector
+            .lock()
+            .unwrap()
+            .add_failed_split(SplitSearchError::for_timed_out_split(split.split_id));
+        return;
+    };
+
     if leaf_search_single_split_res.is_ok() {
         timer.observe_duration();
     }
@@ -666,6 +874,7 @@ async fn leaf_search_single_split_wrapper(
             split_id: split.split_id.clone(),
             error: format!("{err}"),
             retryable_error: true,
+            timed_out: false,
         }),
     }
     if let Some(last_hit) = locked_incremental_merge_collector.peek_worst_hit() {
@@ -845,6 +1054,7 @@ pub async fn leaf_list_terms(
             split_id,
             error: err.to_string(),
             retryable_error: true,
+            timed_out: false,
         })
         .collect();
     let merged_search_response = LeafListTermsResponse {
@@ -21,7 +21,8 @@ use std::ops::Bound;
 
 use prost::Message;
 use quickwit_proto::search::{
-    CountHits, LeafSearchResponse, SearchRequest, SplitIdAndFooterOffsets,
+    CountHits, LeafListFieldsResponse, LeafSearchResponse, ListFieldsEntry, SearchRequest,
+    SplitIdAndFooterOffsets,
 };
 use quickwit_storage::{MemorySizedCache, OwnedBytes};
 
@@ -72,6 +73,40 @@ impl LeafSearchCache {
     }
 }
 
+/// A cache to memoize the fields listed by `leaf_list_fields_single_split`. Splits are immutable,
+/// so their fields are keyed by split ID and never invalidated.
+pub struct ListFieldsCache {
+    content: MemorySizedCache<String>,
+}
+
+impl ListFieldsCache {
+    pub fn new(capacity: usize) -> ListFieldsCache {
+        ListFieldsCache {
+            content: MemorySizedCache::with_capacity_in_bytes(
+                capacity,
+                &quickwit_storage::STORAGE_METRICS.list_fields_cache,
+            ),
+        }
+    }
+
+    pub fn get(&self, split_id: &str) -> Option<Vec<ListFieldsEntry>> {
+        let encoded_fields = self.content.get(split_id)?;
+        // this should never fail
+        LeafListFieldsResponse::decode(&*encoded_fields)
+            .ok()
+            .map(|response| response.fields)
+    }
+
+    pub fn put(&self, split_id: String, fields: Vec<ListFieldsEntry>) {
+        let response = LeafListFieldsResponse {
+            fields,
+            ..Default::default()
+        };
+        let encoded_fields = response.encode_to_vec();
+        self.content.put(split_id, OwnedBytes::new(encoded_fields));
+    }
+}
+
 /// A key inside a [`LeafSearchCache`].
 #[derive(Debug, Hash, PartialEq, Eq)]
 struct CacheKey {
@@ -98,6 +133,8 @@ impl CacheKey {
         // it doesn't matter whether or not we count all hits at the scale of a
         // single split: either we did process it and got everything, or we didn't.
         search_request.count_hits = CountHits::CountAll.into();
+        // the timeout does not change the result of a split search that completed.
+        search_request.timeout_millis = None;
 
         CacheKey {
             split_id: split_info.split_id,
@@ -185,10 +222,11 @@ impl std::ops::RangeBounds<i64> for Range {
 #[cfg(test)]
 mod tests {
     use quickwit_proto::search::{
-        LeafSearchResponse, PartialHit, SearchRequest, SortValue, SplitIdAndFooterOffsets,
+        LeafSearchResponse, ListFieldType, ListFieldsEntry, PartialHit, SearchRequest, SortValue,
+        SplitIdAndFooterOffsets,
     };
 
-    use super::LeafSearchCache;
+    use super::{LeafSearchCache, ListFieldsCache};
 
     #[test]
     fn test_leaf_search_cache_no_timestamp() {
@@ -240,8 +278,10 @@ mod tests {
                 segment_ord: 0,
                 sort_value: Some(SortValue::U64(0u64).into()),
                 sort_value2: None,
+                extra_sort_values: Vec::new(),
                 split_id: "split_1".to_string(),
             }],
+            split_profiles: Vec::new(),
         };
 
         assert!(cache.get(split_1.clone(), query_1.clone()).is_none());
@@ -326,8 +366,10 @@ mod tests {
                 segment_ord: 0,
                 sort_value: Some(SortValue::U64(0).into()),
                 sort_value2: None,
+                extra_sort_values: Vec::new(),
                 split_id: "split_1".to_string(),
             }],
+            split_profiles: Vec::new(),
         };
 
         // for split_1, 1 and 1bis cover different timestamp ranges
@@ -355,4 +397,25 @@ mod tests {
         assert!(cache.get(split_3.clone(), query_2).is_none());
         assert!(cache.get(split_3, query_2bis).is_some());
     }
+
+    #[test]
+    fn test_list_fields_cache() {
+        let cache = ListFieldsCache::new(64_000_000);
+        assert!(cache.get("split_1").is_none());
+
+        let fields = vec![ListFieldsEntry {
+            field_name: "attributes.host".to_string(),
+            field_type: ListFieldType::Str as i32,
+            searchable: true,
+            aggregatable: false,
+            index_ids: Vec::new(),
+        }];
+        cache.put("split_1".to_string(), fields.clone());
+        assert_eq!(cache.get("split_1").unwrap(), fields);
+        assert!(cache.get("split_2").is_none());
+
+        // Splits without JSON fields are cached too.
+        cache.put("split_2".to_string(), Vec::new());
+        assert_eq!(cache.get("split_2").unwrap(), Vec::new());
+    }
 }
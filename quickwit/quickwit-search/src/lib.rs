@@ -22,6 +22,7 @@
 #![allow(clippy::bool_assert_comparison)]
 #![deny(clippy::disallowed_methods)]
 
+mod async_search;
 mod client;
 mod cluster_client;
 mod collector;
@@ -31,6 +32,7 @@ mod filters;
 mod find_trace_ids_collector;
 mod leaf;
 mod leaf_cache;
+mod list_fields;
 mod retry;
 mod root;
 mod scroll_context;
@@ -38,6 +40,7 @@ mod search_job_placer;
 mod search_response_rest;
 mod search_stream;
 mod service;
+mod sql;
 mod thread_pool;
 
 mod metrics;
@@ -70,6 +73,10 @@ use quickwit_storage::StorageResolver;
 pub use service::SearcherContext;
 use tantivy::DocAddress;
 
+pub use crate::async_search::{
+    AsyncSearchState, AsyncSearchStatus, SubmitAsyncSearchRequest, DEFAULT_ASYNC_SEARCH_KEEP_ALIVE,
+    DEFAULT_WAIT_FOR_COMPLETION_TIMEOUT,
+};
 pub use crate::client::{
     create_search_client_from_channel, create_search_client_from_grpc_addr, SearchServiceClient,
 };
@@ -77,13 +84,17 @@ pub use crate::cluster_client::ClusterClient;
 pub use crate::error::{parse_grpc_error, SearchError};
 use crate::fetch_docs::fetch_docs;
 use crate::leaf::{leaf_list_terms, leaf_search};
+use crate::list_fields::leaf_list_fields;
+pub use crate::list_fields::root_list_fields;
 pub use crate::root::{
-    jobs_to_leaf_requests, root_list_terms, root_search, IndexMetasForLeafSearch, SearchJob,
+    check_all_index_metadata_found, jobs_to_leaf_requests, root_list_terms, root_search,
+    IndexMetasForLeafSearch, SearchJob,
 };
 pub use crate::search_job_placer::{Job, SearchJobPlacer};
-pub use crate::search_response_rest::SearchResponseRest;
+pub use crate::search_response_rest::{AsyncSearchResponseRest, SearchResponseRest};
 pub use crate::search_stream::root_search_stream;
 pub use crate::service::{MockSearchService, SearchService, SearchServiceImpl};
+pub use crate::sql::{SqlColumn, SqlPlan, SqlResponse, DEFAULT_SQL_LIMIT};
 use crate::thread_pool::run_cpu_intensive;
 
 /// A pool of searcher clients identified by their gRPC socket address.
@@ -0,0 +1,405 @@
+// Copyright (C) 2023 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::Context;
+use futures::future::try_join_all;
+use itertools::{Either, Itertools};
+use quickwit_common::shared_consts::FIELD_PRESENCE_FIELD_NAME;
+use quickwit_common::PrettySample;
+use quickwit_config::build_doc_mapper;
+use quickwit_doc_mapper::split_fields::{SplitJsonField, SPLIT_FIELDS_FILE_NAME};
+use quickwit_doc_mapper::{DYNAMIC_FIELD_NAME, SOURCE_FIELD_NAME};
+use quickwit_metastore::{IndexMetadata, ListIndexesMetadataResponseExt};
+use quickwit_proto::metastore::{
+    ListIndexesMetadataRequest, MetastoreService, MetastoreServiceClient,
+};
+use quickwit_proto::search::{
+    LeafListFieldsRequest, LeafListFieldsResponse, ListFieldType, ListFieldsEntry,
+    ListFieldsRequest, ListFieldsResponse, SplitIdAndFooterOffsets, SplitSearchError,
+};
+use quickwit_storage::Storage;
+use tantivy::schema::{FieldEntry, FieldType, Schema, Type};
+use tantivy::Directory;
+use tracing::{error, info, instrument};
+
+use crate::leaf::open_index_with_caches;
+use crate::service::SearcherContext;
+use crate::{
+    check_all_index_metadata_found, list_relevant_splits, ClusterClient, SearchError, SearchJob,
+};
+
+/// Fields that are not part of the documents, and therefore never listed.
+const INTERNAL_FIELD_NAMES: [&str; 3] = [
+    SOURCE_FIELD_NAME,
+    DYNAMIC_FIELD_NAME,
+    FIELD_PRESENCE_FIELD_NAME,
+];
+
+fn list_field_type(value_type: Type) -> ListFieldType {
+    match value_type {
+        Type::Str => ListFieldType::Str,
+        Type::U64 => ListFieldType::U64,
+        Type::I64 => ListFieldType::I64,
+        Type::F64 => ListFieldType::F64,
+        Type::Bool => ListFieldType::Bool,
+        Type::Date => ListFieldType::Date,
+        Type::Facet => ListFieldType::Facet,
+        Type::Bytes => ListFieldType::Bytes,
+        Type::Json => ListFieldType::Json,
+        Type::IpAddr => ListFieldType::IpAddr,
+    }
+}
+
+fn list_fields_entry(
+    field_name: String,
+    field_type: ListFieldType,
+    field_entry: &FieldEntry,
+) -> ListFieldsEntry {
+    ListFieldsEntry {
+        field_name,
+        field_type: field_type as i32,
+        searchable: field_entry.is_indexed(),
+        aggregatable: field_entry.is_fast(),
+        index_ids: Vec::new(),
+    }
+}
+
+/// Lists the fields declared in the schema, i.e. the fields of the doc mapping.
+fn list_schema_fields(schema: &Schema) -> Vec<ListFieldsEntry> {
+    schema
+        .fields()
+        .filter(|(_, field_entry)| !INTERNAL_FIELD_NAMES.contains(&field_entry.name()))
+        .map(|(_, field_entry)| {
+            let field_type = list_field_type(field_entry.field_type().value_type());
+            list_fields_entry(field_entry.name().to_string(), field_type, field_entry)
+        })
+        .collect()
+}
+
+/// Lists the fields of the JSON objects indexed in a single split. They are read from the split
+/// fields file written when the split is packaged, which is stored in the hotcache of the split, so
+/// the term dictionaries are not loaded. The fields are cached: splits are immutable.
+#[instrument(skip_all, fields(split_id = split.split_id))]
+async fn leaf_list_fields_single_split(
+    searcher_context: &SearcherContext,
+    storage: Arc<dyn Storage>,
+    split: SplitIdAndFooterOffsets,
+) -> crate::Result<Vec<ListFieldsEntry>> {
+    if let Some(fields) = searcher_context.list_fields_cache.get(&split.split_id) {
+        return Ok(fields);
+    }
+    let index = open_index_with_caches(searcher_context, storage, &split, None, false).await?;
+    let split_schema = index.schema();
+    let split_fields_path = Path::new(SPLIT_FIELDS_FILE_NAME);
+
+    let mut fields = BTreeMap::new();
+    // Splits packaged before the split fields file was introduced do not list the fields of their
+    // JSON objects.
+    if index
+        .directory()
+        .exists(split_fields_path)
+        .context("failed to check split fields file")?
+    {
+        let split_fields_json = index
+            .directory()
+            .atomic_read(split_fields_path)
+            .context("failed to read split fields file")?;
+        let split_json_fields: Vec<SplitJsonField> = serde_json::from_slice(&split_fields_json)
+            .context("failed to deserialize split fields file")?;
+
+        for split_json_field in split_json_fields {
+            let field = split_schema
+                .get_field(&split_json_field.json_field_name)
+                .context("failed to find JSON field in split schema")?;
+            let field_entry = split_schema.get_field_entry(field);
+            let value_type = split_json_field.value_type().with_context(|| {
+                format!(
+                    "unknown JSON term type code `{}`",
+                    split_json_field.type_code
+                )
+            })?;
+            let field_type = list_field_type(value_type);
+            let field_name = split_json_field.field_name;
+            fields
+                .entry((field_name.clone(), field_type as i32))
+                .or_insert_with(|| list_fields_entry(field_name, field_type, field_entry));
+        }
+    }
+    let fields: Vec<ListFieldsEntry> = fields.into_values().collect();
+    searcher_context
+        .list_fields_cache
+        .put(split.split_id, fields.clone());
+    Ok(fields)
+}
+
+/// `leaf` step of list fields.
+#[instrument(skip_all, fields(index = %request.index_id))]
+pub async fn leaf_list_fields(
+    searcher_context: Arc<SearcherContext>,
+    request: LeafListFieldsRequest,
+    index_storage: Arc<dyn Storage>,
+) -> crate::Result<LeafListFieldsResponse> {
+    info!(split_offsets = ?PrettySample::new(&request.split_offsets, 5));
+    let leaf_list_fields_single_split_futures: Vec<_> = request
+        .split_offsets
+        .iter()
+        .map(|split| {
+            let index_storage_clone = index_storage.clone();
+            let searcher_context_clone = searcher_context.clone();
+            async move {
+                let _leaf_split_search_permit = searcher_context_clone.leaf_search_split_semaphore.clone()
+                    .acquire_owned()
+                    .await
+                    .expect("Failed to acquire permit. This should never happen! Please, report on https://github.com/quickwit-oss/quickwit/issues.");
+                leaf_list_fields_single_split(
+                    &searcher_context_clone,
+                    index_storage_clone,
+                    split.clone(),
+                )
+                .await
+                .map_err(|error| (split.split_id.clone(), error))
+            }
+        })
+        .collect();
+
+    let split_results = futures::future::join_all(leaf_list_fields_single_split_futures).await;
+
+    let (split_fields, errors): (Vec<Vec<ListFieldsEntry>>, Vec<(String, SearchError)>) =
+        split_results
+            .into_iter()
+            .partition_map(|split_result| match split_result {
+                Ok(split_fields) => Either::Left(split_fields),
+                Err(error) => Either::Right(error),
+            });
+
+    let fields: Vec<ListFieldsEntry> = split_fields
+        .into_iter()
+        .kmerge_by(|left, right| {
+            (&left.field_name, left.field_type) < (&right.field_name, right.field_type)
+        })
+        .dedup_by(|left, right| {
+            left.field_name == right.field_name && left.field_type == right.field_type
+        })
+        .collect();
+
+    let failed_splits = errors
+        .into_iter()
+        .map(|(split_id, error)| SplitSearchError {
+            split_id,
+            error: error.to_string(),
+            retryable_error: true,
+            timed_out: false,
+        })
+        .collect();
+    Ok(LeafListFieldsResponse {
+        fields,
+        failed_splits,
+        num_attempted_splits: request.split_offsets.len() as u64,
+    })
+}
+
+/// Merges the fields of an index into the fields of all the indexes. A field shared by several
+/// indexes is only searchable (resp. aggregatable) if it is searchable (resp. aggregatable) in all
+/// of them.
+fn merge_index_fields(
+    fields: &mut BTreeMap<(String, i32), ListFieldsEntry>,
+    index_id: &str,
+    index_fields: impl IntoIterator<Item = ListFieldsEntry>,
+) {
+    for index_field in index_fields {
+        let key = (index_field.field_name.clone(), index_field.field_type);
+        let field = fields.entry(key).or_insert_with(|| ListFieldsEntry {
+            searchable: true,
+            aggregatable: true,
+            ..index_field.clone()
+        });
+        field.searchable &= index_field.searchable;
+        field.aggregatable &= index_field.aggregatable;
+
+        if field.index_ids.last().map(String::as_str) != Some(index_id) {
+            field.index_ids.push(index_id.to_string());
+        }
+    }
+}
+
+/// Performs a distributed list fields.
+/// 1. Lists the fields of the doc mapping of each index.
+/// 2. Sends leaf requests over gRPC to multiple leaf nodes to list the fields of the JSON objects
+/// (including the dynamic fields) seen in the published splits.
+/// 3. Merges the fields and returns them.
+#[instrument(skip(metastore, cluster_client))]
+pub async fn root_list_fields(
+    list_fields_request: ListFieldsRequest,
+    mut metastore: MetastoreServiceClient,
+    cluster_client: &ClusterClient,
+) -> crate::Result<ListFieldsResponse> {
+    let list_indexes_metadata_request = ListIndexesMetadataRequest {
+        index_id_patterns: list_fields_request.index_id_patterns.clone(),
+    };
+    let indexes_metadata: Vec<IndexMetadata> = metastore
+        .list_indexes_metadata(list_indexes_metadata_request)
+        .await?
+        .deserialize_indexes_metadata()?;
+    check_all_index_metadata_found(
+        &indexes_metadata[..],
+        &list_fields_request.index_id_patterns[..],
+    )?;
+
+    let mut fields_per_index: Vec<(String, Vec<ListFieldsEntry>)> = Vec::new();
+    let mut leaf_request_futures = Vec::new();
+
+    for index_metadata in indexes_metadata {
+        let index_id = index_metadata.index_id().to_string();
+        let index_uid = index_metadata.index_uid.clone();
+        let index_config = index_metadata.into_index_config();
+        let doc_mapper = build_doc_mapper(&index_config.doc_mapping, &index_config.search_settings)
+            .map_err(|err| {
+                SearchError::Internal(format!("failed to build doc mapper. cause: {err}"))
+            })?;
+        let schema = doc_mapper.schema();
+        fields_per_index.push((index_id.clone(), list_schema_fields(&schema)));
+
+        let has_json_fields = schema.fields().any(|(_, field_entry)| {
+            matches!(field_entry.field_type(), FieldType::JsonObject(_)) && field_entry.is_indexed()
+        });
+        if !has_json_fields {
+            continue;
+        }
+        let split_metadatas =
+            list_relevant_splits(vec![index_uid], None, None, None, &mut metastore).await?;
+        let jobs: Vec<SearchJob> = split_metadatas.iter().map(SearchJob::from).collect();
+        let assigned_leaf_jobs = cluster_client
+            .search_job_placer
+            .assign_jobs(jobs, &Default::default())
+            .await?;
+
+        for (client, client_jobs) in assigned_leaf_jobs {
+            let leaf_request = LeafListFieldsRequest {
+                index_id: index_id.clone(),
+                index_uri: index_config.index_uri.to_string(),
+                split_offsets: client_jobs.into_iter().map(Into::into).collect(),
+            };
+            let index_id = index_id.clone();
+            leaf_request_futures.push(async move {
+                let leaf_response = cluster_client
+                    .leaf_list_fields(leaf_request, client)
+                    .await?;
+                Ok::<_, SearchError>((index_id, leaf_response))
+            });
+        }
+    }
+    let leaf_responses = try_join_all(leaf_request_futures).await?;
+
+    let failed_splits: Vec<&SplitSearchError> = leaf_responses
+        .iter()
+        .flat_map(|(_, leaf_response)| &leaf_response.failed_splits)
+        .collect();
+
+    if !failed_splits.is_empty() {
+        error!(failed_splits = ?failed_splits, "leaf list fields response contains at least one failed split");
+        let errors: String = failed_splits
+            .iter()
+            .map(|split_search_error| split_search_error.to_string())
+            .join(", ");
+        return Err(SearchError::Internal(errors));
+    }
+    for (index_id, leaf_response) in leaf_responses {
+        fields_per_index.push((index_id, leaf_response.fields));
+    }
+    // Sorting by index ID groups the fields of each index together, which lets
+    // `merge_index_fields` deduplicate the index IDs of the fields cheaply.
+    fields_per_index.sort_by(|(left, _), (right, _)| left.cmp(right));
+
+    let mut fields = BTreeMap::new();
+    for (index_id, index_fields) in fields_per_index {
+        merge_index_fields(&mut fields, &index_id, index_fields);
+    }
+    Ok(ListFieldsResponse {
+        fields: fields.into_values().collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use tantivy::schema::{SchemaBuilder, FAST, INDEXED, STORED, STRING, TEXT};
+
+    use super::*;
+
+    #[test]
+    fn test_list_schema_fields() {
+        let mut schema_builder = SchemaBuilder::new();
+        schema_builder.add_text_field("body", TEXT);
+        schema_builder.add_text_field("severity", STRING | FAST);
+        schema_builder.add_u64_field("status", INDEXED | FAST);
+        schema_builder.add_text_field(SOURCE_FIELD_NAME, STORED);
+        schema_builder.add_json_field(DYNAMIC_FIELD_NAME, TEXT | FAST);
+        let schema = schema_builder.build();
+
+        let fields = list_schema_fields(&schema);
+        assert_eq!(fields.len(), 3);
+
+        assert_eq!(fields[0].field_name, "body");
+        assert_eq!(fields[0].field_type(), ListFieldType::Str);
+        assert!(fields[0].searchable);
+        assert!(!fields[0].aggregatable);
+
+        assert_eq!(fields[1].field_name, "severity");
+        assert!(fields[1].searchable);
+        assert!(fields[1].aggregatable);
+
+        assert_eq!(fields[2].field_name, "status");
+        assert_eq!(fields[2].field_type(), ListFieldType::U64);
+    }
+
+    #[test]
+    fn test_merge_index_fields() {
+        let field = |field_name: &str, aggregatable: bool| ListFieldsEntry {
+            field_name: field_name.to_string(),
+            field_type: ListFieldType::Str as i32,
+            searchable: true,
+            aggregatable,
+            index_ids: Vec::new(),
+        };
+        let mut fields = BTreeMap::new();
+        merge_index_fields(
+            &mut fields,
+            "index-1",
+            [field("host", true), field("message", false)],
+        );
+        merge_index_fields(&mut fields, "index-1", [field("tenant", true)]);
+        merge_index_fields(&mut fields, "index-2", [field("host", false)]);
+
+        let fields: Vec<ListFieldsEntry> = fields.into_values().collect();
+        assert_eq!(fields.len(), 3);
+
+        assert_eq!(fields[0].field_name, "host");
+        assert!(!fields[0].aggregatable);
+        assert_eq!(fields[0].index_ids, ["index-1", "index-2"]);
+
+        assert_eq!(fields[1].field_name, "message");
+        assert_eq!(fields[1].index_ids, ["index-1"]);
+
+        assert_eq!(fields[2].field_name, "tenant");
+        assert!(fields[2].aggregatable);
+    }
+}
@@ -18,6 +18,8 @@
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
 use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::ops::RangeInclusive;
 use std::time::Duration;
 
 use anyhow::Context;
@@ -27,20 +29,21 @@ use quickwit_common::shared_consts::{DELETION_GRACE_PERIOD, SCROLL_BATCH_LEN};
 use quickwit_common::uri::Uri;
 use quickwit_common::PrettySample;
 use quickwit_config::{build_doc_mapper, IndexConfig};
-use quickwit_doc_mapper::tag_pruning::extract_tags_from_query;
+use quickwit_doc_mapper::tag_pruning::{extract_tags_from_query, TagFilterAst};
 use quickwit_doc_mapper::{DocMapper, DYNAMIC_FIELD_NAME};
 use quickwit_metastore::{
-    IndexMetadata, IndexMetadataResponseExt, ListIndexesMetadataResponseExt, ListSplitsRequestExt,
-    ListSplitsResponseExt, SplitMetadata,
+    IndexMetadata, IndexMetadataResponseExt, ListIndexesMetadataResponseExt, ListSplitsQuery,
+    ListSplitsRequestExt, ListSplitsResponseExt, SplitMetadata, SplitState,
 };
 use quickwit_proto::metastore::{
     IndexMetadataRequest, ListIndexesMetadataRequest, ListSplitsRequest, MetastoreService,
     MetastoreServiceClient,
 };
 use quickwit_proto::search::{
-    FetchDocsRequest, FetchDocsResponse, Hit, LeafHit, LeafListTermsRequest, LeafListTermsResponse,
-    LeafSearchRequest, LeafSearchResponse, ListTermsRequest, ListTermsResponse, PartialHit,
-    SearchRequest, SearchResponse, SnippetRequest, SortField, SplitIdAndFooterOffsets,
+    FetchDocsRequest, FetchDocsResponse, Hit, IndexQueryProfile, LeafHit, LeafListTermsRequest,
+    LeafListTermsResponse, LeafSearchRequest, LeafSearchResponse, ListTermsRequest,
+    ListTermsResponse, PartialHit, PrunedSplit, SearchProfile, SearchRequest, SearchResponse,
+    SnippetRequest, SortField, SplitIdAndFooterOffsets, SplitSearchError,
 };
 use quickwit_proto::types::{IndexUid, SplitId};
 use quickwit_query::query_ast::{
@@ -52,8 +55,10 @@ use tantivy::aggregation::intermediate_agg_result::IntermediateAggregationResult
 use tantivy::collector::Collector;
 use tantivy::schema::{FieldType, Schema};
 use tantivy::TantivyError;
+use tokio::time::Instant;
 use tracing::{debug, error, info, info_span, instrument};
 
+use crate::async_search::SearchProgress;
 use crate::cluster_client::ClusterClient;
 use crate::collector::{make_merge_collector, QuickwitAggregations};
 use crate::find_trace_ids_collector::Span;
@@ -68,6 +73,9 @@ use crate::{
 /// Maximum accepted scroll TTL.
 const MAX_SCROLL_TTL: Duration = Duration::from_secs(DELETION_GRACE_PERIOD.as_secs() - 60 * 2);
 
+/// Maximum time reserved, within the search timeout, for the leaf responses to reach the root.
+const MAX_LEAF_SEARCH_TIMEOUT_MARGIN: Duration = Duration::from_millis(100);
+
 /// SearchJob to be assigned to search clients by the [`SearchJobPlacer`].
 #[derive(Debug, Clone, PartialEq)]
 pub struct SearchJob {
@@ -96,16 +104,28 @@ impl From<SearchJob> for SplitIdAndFooterOffsets {
     }
 }
 
-impl<'a> From<&'a SplitMetadata> for SearchJob {
-    fn from(split_metadata: &'a SplitMetadata) -> Self {
+impl SearchJob {
+    /// Creates a search job for a split, whose cost only accounts for the documents within the
+    /// `[start_timestamp, end_timestamp)` time range targeted by the search.
+    pub(crate) fn for_time_range(
+        split_metadata: &SplitMetadata,
+        start_timestamp: Option<i64>,
+        end_timestamp: Option<i64>,
+    ) -> Self {
         SearchJob {
             index_uid: split_metadata.index_uid.clone(),
-            cost: compute_split_cost(split_metadata),
+            cost: compute_split_cost(split_metadata, start_timestamp, end_timestamp),
             offsets: extract_split_and_footer_offsets(split_metadata),
         }
     }
 }
 
+impl<'a> From<&'a SplitMetadata> for SearchJob {
+    fn from(split_metadata: &'a SplitMetadata) -> Self {
+        SearchJob::for_time_range(split_metadata, None, None)
+    }
+}
+
 impl Job for SearchJob {
     fn split_id(&self) -> &str {
         &self.offsets.split_id
@@ -145,27 +165,53 @@ pub struct IndexMetasForLeafSearch {
     pub index_uri: Uri,
     /// Doc mapper json string.
     pub doc_mapper_str: String,
+    /// Query AST resolved against the doc mapper of the index, as a JSON string.
+    /// If `None`, the query AST of the search request is used.
+    #[serde(default)]
+    pub query_ast_resolved: Option<String>,
+    /// Start timestamp refined from the query on the timestamp field of the index.
+    /// If `None`, the start timestamp of the search request is used.
+    #[serde(default)]
+    pub start_timestamp: Option<i64>,
+    /// End timestamp refined from the query on the timestamp field of the index.
+    /// If `None`, the end timestamp of the search request is used.
+    #[serde(default)]
+    pub end_timestamp: Option<i64>,
 }
 
 pub(crate) type IndexesMetasForLeafSearch = HashMap<IndexUid, IndexMetasForLeafSearch>;
-type TimestampFieldOpt = Option<String>;
-
-/// Validates request against each index's doc mapper and ensures that:
-/// - timestamp fields (if any) are equal across indexes.
-/// - resolved query ASTs are the same across indexes.
-/// Returns the timestamp field, the resolved query AST and the indexes metadatas
-/// needed for leaf search requests.
-/// Note: the requirements on timestamp fields and resolved query ASTs can be lifted
-/// but it adds complexity that does not seem needed right now.
+
+/// Filter used to list the splits of an index relevant to a search request.
+///
+/// It depends on the index, as the query is resolved against the doc mapper of each index.
+#[derive(Clone, Debug, Default, PartialEq)]
+struct SplitsFilter {
+    start_timestamp: Option<i64>,
+    end_timestamp: Option<i64>,
+    tag_filter_ast: Option<TagFilterAst>,
+}
+
+/// Validates request against each index's doc mapper, and resolves the query for each index.
+///
+/// Indexes can have different default search fields and timestamp fields: the query AST is
+/// resolved and the time range is refined for each index separately.
+/// Returns the resolved query AST if it is the same across indexes, the splits filter of each
+/// index and the indexes metadatas needed for leaf search requests.
+/// If a search profile is given, the query lowered for each index is added to it.
 fn validate_request_and_build_metadatas(
     indexes_metadata: &[IndexMetadata],
     search_request: &SearchRequest,
-) -> crate::Result<(TimestampFieldOpt, QueryAst, IndexesMetasForLeafSearch)> {
+    mut search_profile_opt: Option<&mut SearchProfile>,
+) -> crate::Result<(
+    Option<QueryAst>,
+    Vec<(IndexUid, SplitsFilter)>,
+    IndexesMetasForLeafSearch,
+)> {
     let mut metadatas_for_leaf: HashMap<IndexUid, IndexMetasForLeafSearch> = HashMap::new();
+    let mut splits_filters: Vec<(IndexUid, SplitsFilter)> = Vec::new();
     let query_ast: QueryAst = serde_json::from_str(&search_request.query_ast)
         .map_err(|err| SearchError::InvalidQuery(err.to_string()))?;
-    let mut query_ast_resolved_opt: Option<QueryAst> = None;
-    let mut timestamp_field_opt: Option<String> = None;
+    let mut query_asts_resolved: Vec<QueryAst> = Vec::new();
 
     for index_metadata in indexes_metadata {
         let doc_mapper = build_doc_mapper(
@@ -181,58 +227,60 @@ fn validate_request_and_build_metadatas(
             // We convert the error to return a 400 to the user (and not a 500).
             .map_err(|err| SearchError::InvalidQuery(err.to_string()))?;
 
-        // Validate uniqueness of resolved query AST.
-        if let Some(query_ast_resolved) = &query_ast_resolved_opt {
-            if query_ast_resolved != &query_ast_resolved_for_index {
-                return Err(SearchError::InvalidQuery(
-                    "resolved query ASTs must be the same across indexes. resolving queries with \
-                     different default fields are different between indexes is not supported"
-                        .to_string(),
-                ));
-            }
-        } else {
-            query_ast_resolved_opt = Some(query_ast_resolved_for_index.clone());
+        validate_request(&*doc_mapper, search_request)?;
+        // Validates the query by effectively building it against the current schema.
+        let (lowered_query, _) =
+            doc_mapper.query(doc_mapper.schema(), &query_ast_resolved_for_index, true)?;
+        if let Some(search_profile) = search_profile_opt.as_deref_mut() {
+            search_profile.queries.push(IndexQueryProfile {
+                index_id: index_metadata.index_id().to_string(),
+                lowered_query: format!("{lowered_query:?}"),
+            });
         }
 
-        // Validate uniqueness of timestamp field if any.
-        if let Some(timestamp_field_for_index) = doc_mapper.timestamp_field_name() {
-            match timestamp_field_opt {
-                Some(timestamp_field) if timestamp_field != timestamp_field_for_index => {
-                    return Err(SearchError::InvalidQuery(
-                        "the timestamp field (if present) must be the same for all indexes"
-                            .to_string(),
-                    ));
-                }
-                None => {
-                    timestamp_field_opt = Some(timestamp_field_for_index.to_string());
-                }
-                _ => {}
-            }
+        let mut start_timestamp = search_request.start_timestamp;
+        let mut end_timestamp = search_request.end_timestamp;
+        if let Some(timestamp_field) = doc_mapper.timestamp_field_name() {
+            refine_start_end_timestamp_from_ast(
+                &query_ast_resolved_for_index,
+                timestamp_field,
+                &mut start_timestamp,
+                &mut end_timestamp,
+            );
         }
-
-        validate_request(&*doc_mapper, search_request)?;
-        // Validates the query by effectively building it against the current schema.
-        doc_mapper.query(doc_mapper.schema(), &query_ast_resolved_for_index, true)?;
+        let splits_filter = SplitsFilter {
+            start_timestamp,
+            end_timestamp,
+            tag_filter_ast: extract_tags_from_query(query_ast_resolved_for_index.clone()),
+        };
+        splits_filters.push((index_metadata.index_uid.clone(), splits_filter));
 
         let index_metadata_for_leaf_search = IndexMetasForLeafSearch {
             index_uri: index_metadata.index_uri().clone(),
             doc_mapper_str: serde_json::to_string(&doc_mapper).map_err(|err| {
                 SearchError::Internal(format!("failed to serialize doc mapper. cause: {err}"))
             })?,
+            query_ast_resolved: Some(serde_json::to_string(&query_ast_resolved_for_index)?),
+            start_timestamp,
+            end_timestamp,
         };
         metadatas_for_leaf.insert(
             index_metadata.index_uid.clone(),
             index_metadata_for_leaf_search,
         );
+        query_asts_resolved.push(query_ast_resolved_for_index);
     }
 
-    let query_ast_resolved = query_ast_resolved_opt.ok_or_else(|| {
-        SearchError::Internal(
-            "resolved query AST must be present. this should never happen".to_string(),
-        )
-    })?;
-
-    Ok((timestamp_field_opt, query_ast_resolved, metadatas_for_leaf))
+    let common_query_ast_resolved_opt = if query_asts_resolved.iter().all_equal() {
+        query_asts_resolved.into_iter().next()
+    } else {
+        None
+    };
+    Ok((
+        common_query_ast_resolved_opt,
+        splits_filters,
+        metadatas_for_leaf,
+    ))
 }
 
 fn validate_requested_snippet_fields(
@@ -240,11 +288,17 @@ fn validate_requested_snippet_fields(
     snippet_fields: &[String],
 ) -> anyhow::Result<()> {
     for field_name in snippet_fields {
-        let field_entry = schema
-            .get_field(field_name)
-            .map(|field| schema.get_field_entry(field))?;
+        // Snippet fields can also target a path within a JSON field.
+        let (field, json_path) = match schema.find_field(field_name) {
+            Some(field_and_json_path) => field_and_json_path,
+            None => match schema.get_field(DYNAMIC_FIELD_NAME) {
+                Ok(dynamic_field) => (dynamic_field, field_name.as_str()),
+                Err(_) => return Err(TantivyError::FieldNotFound(field_name.clone()).into()),
+            },
+        };
+        let field_entry = schema.get_field_entry(field);
         match field_entry.field_type() {
-            FieldType::Str(text_options) => {
+            FieldType::Str(text_options) if json_path.is_empty() => {
                 if !text_options.is_stored() {
                     return Err(anyhow::anyhow!(
                         "the snippet field `{}` must be stored",
@@ -252,6 +306,20 @@ fn validate_requested_snippet_fields(
                     ));
                 }
             }
+            FieldType::JsonObject(json_options) if !json_path.is_empty() => {
+                if !json_options.is_stored() {
+                    return Err(anyhow::anyhow!(
+                        "the snippet field `{}` must be stored",
+                        field_name
+                    ));
+                }
+                if json_options.get_text_indexing_options().is_none() {
+                    return Err(anyhow::anyhow!(
+                        "the snippet field `{}` must be indexed",
+                        field_name
+                    ));
+                }
+            }
             other => {
                 return Err(anyhow::anyhow!(
                     "the snippet field `{}` must be of type `Str`, got `{}`",
@@ -265,15 +333,6 @@ fn validate_requested_snippet_fields(
 }
 
 fn validate_sort_by_fields(sort_fields: &[SortField], schema: &Schema) -> crate::Result<()> {
-    if sort_fields.is_empty() {
-        return Ok(());
-    }
-    if sort_fields.len() > 2 {
-        return Err(SearchError::InvalidArgument(format!(
-            "sort by field must be up to 2 fields {:?}",
-            sort_fields
-        )));
-    }
     for sort in sort_fields {
         validate_sort_by_field(&sort.field_name, schema)?;
     }
@@ -302,15 +361,21 @@ fn simplify_search_request_for_scroll_api(req: &SearchRequest) -> crate::Result<
         aggregation_request: None,
         // We remove the snippet fields. This feature is not supported for scroll requests.
         snippet_fields: Vec::new(),
+        snippet_config: None,
         // We remove the scroll ttl parameter. It is irrelevant to process later request
         scroll_ttl_secs: None,
         search_after: None,
         count_hits: req.count_hits,
+        // The timeout only applies to the initial search request.
+        timeout_millis: None,
+        // The profile only applies to the initial search request.
+        profile: false,
+        fetch_fields: req.fetch_fields.clone(),
     })
 }
 
 fn validate_sort_by_field(field_name: &str, schema: &Schema) -> crate::Result<()> {
-    if field_name == "_score" {
+    if ["_score", "_shard_doc", "_doc"].contains(&field_name) {
         return Ok(());
     }
     let dynamic_field_opt = schema.get_field(DYNAMIC_FIELD_NAME).ok();
@@ -320,9 +385,15 @@ fn validate_sort_by_field(field_name: &str, schema: &Schema) -> crate::Result<()
             SearchError::InvalidArgument(format!("unknown field used in `sort by`: {field_name}"))
         })?;
     let sort_by_field_entry = schema.get_field_entry(sort_by_field);
-    if matches!(sort_by_field_entry.field_type(), FieldType::Str(_)) {
+    let unsupported_type_name = match sort_by_field_entry.field_type() {
+        FieldType::Bytes(_) => Some("bytes"),
+        FieldType::Facet(_) => Some("facet"),
+        FieldType::IpAddr(_) => Some("ip"),
+        _ => None,
+    };
+    if let Some(type_name) = unsupported_type_name {
         return Err(SearchError::InvalidArgument(format!(
-            "sort by field on type text is currently not supported `{field_name}`"
+            "sort by field on type {type_name} is currently not supported `{field_name}`"
         )));
     }
     if !sort_by_field_entry.is_fast() {
@@ -399,6 +470,7 @@ async fn search_partial_hits_phase_with_scroll(
     mut search_request: SearchRequest,
     split_metadatas: &[SplitMetadata],
     cluster_client: &ClusterClient,
+    search_progress_opt: Option<&SearchProgress>,
 ) -> crate::Result<(LeafSearchResponse, Option<ScrollKeyAndStartOffset>)> {
     let scroll_ttl_opt = get_scroll_ttl_duration(&search_request)?;
 
@@ -415,6 +487,7 @@ async fn search_partial_hits_phase_with_scroll(
             &search_request,
             split_metadatas,
             cluster_client,
+            search_progress_opt,
         )
         .await?;
         let cached_partial_hits = leaf_search_resp.partial_hits.clone();
@@ -451,6 +524,7 @@ async fn search_partial_hits_phase_with_scroll(
             &search_request,
             split_metadatas,
             cluster_client,
+            search_progress_opt,
         )
         .await?;
         Ok((leaf_search_resp, None))
@@ -464,18 +538,59 @@ pub(crate) async fn search_partial_hits_phase(
     search_request: &SearchRequest,
     split_metadatas: &[SplitMetadata],
     cluster_client: &ClusterClient,
+    search_progress_opt: Option<&SearchProgress>,
 ) -> crate::Result<LeafSearchResponse> {
-    let jobs: Vec<SearchJob> = split_metadatas.iter().map(SearchJob::from).collect();
+    let deadline_opt: Option<Instant> = search_request
+        .timeout_millis
+        .map(|timeout_millis| Instant::now() + Duration::from_millis(timeout_millis));
+    let jobs: Vec<SearchJob> = split_metadatas
+        .iter()
+        .map(|split_metadata| {
+            let index_metas_opt = indexes_metas_for_leaf_search.get(&split_metadata.index_uid);
+            let start_timestamp = index_metas_opt
+                .and_then(|index_metas| index_metas.start_timestamp)
+                .or(search_request.start_timestamp);
+            let end_timestamp = index_metas_opt
+                .and_then(|index_metas| index_metas.end_timestamp)
+                .or(search_request.end_timestamp);
+            SearchJob::for_time_range(split_metadata, start_timestamp, end_timestamp)
+        })
+        .collect();
     let assigned_leaf_search_jobs = cluster_client
         .search_job_placer
         .assign_jobs(jobs, &HashSet::default())
         .await?;
+    if let Some(search_progress) = search_progress_opt {
+        search_progress.set_num_splits_total(split_metadatas.len());
+    }
+    let mut search_request_for_leaves = search_request.clone();
+    search_request_for_leaves.timeout_millis = search_request
+        .timeout_millis
+        .map(leaf_search_timeout_millis);
     let mut leaf_request_tasks = Vec::new();
     for (client, client_jobs) in assigned_leaf_search_jobs {
-        let leaf_requests =
-            jobs_to_leaf_requests(search_request, indexes_metas_for_leaf_search, client_jobs)?;
+        let leaf_requests = jobs_to_leaf_requests(
+            &search_request_for_leaves,
+            indexes_metas_for_leaf_search,
+            client_jobs,
+        )?;
         for leaf_request in leaf_requests {
-            leaf_request_tasks.push(cluster_client.leaf_search(leaf_request, client.clone()));
+            let split_ids: Vec<String> = leaf_request
+                .split_offsets
+                .iter()
+                .map(|split_offsets| split_offsets.split_id.clone())
+                .collect();
+            let num_splits = split_ids.len();
+            let leaf_search_fut = leaf_search_until_deadline(
+                cluster_client.leaf_search(leaf_request, client.clone()),
+                split_ids,
+                deadline_opt,
+            );
+            leaf_request_tasks.push(leaf_search_with_progress(
+                leaf_search_fut,
+                num_splits,
+                search_progress_opt,
+            ));
         }
     }
     let leaf_search_responses: Vec<LeafSearchResponse> = try_join_all(leaf_request_tasks).await?;
@@ -500,14 +615,69 @@ pub(crate) async fn search_partial_hits_phase(
     .map_err(|error: TantivyError| crate::SearchError::Internal(error.to_string()))?;
     debug!(leaf_search_response = ?leaf_search_response, "Merged leaf search response.");
 
-    if !leaf_search_response.failed_splits.is_empty() {
-        error!(failed_splits = ?leaf_search_response.failed_splits, "Leaf search response contains at least one failed split.");
-        let errors: String = leaf_search_response.failed_splits.iter().join(", ");
+    // The splits skipped because the search timed out are kept in the response: the hits gathered
+    // so far are returned.
+    let failed_splits: Vec<&SplitSearchError> = leaf_search_response
+        .failed_splits
+        .iter()
+        .filter(|failed_split| !failed_split.timed_out)
+        .collect();
+    if !failed_splits.is_empty() {
+        error!(failed_splits = ?failed_splits, "Leaf search response contains at least one failed split.");
+        let errors: String = failed_splits.iter().join(", ");
         return Err(SearchError::Internal(errors));
     }
     Ok(leaf_search_response)
 }
 
+/// Returns the timeout of the leaf searches, given the timeout of the root search.
+///
+/// Leaf searches time out a bit earlier than the root search, so that the hits they gathered
+/// reach the root before its own deadline.
+fn leaf_search_timeout_millis(timeout_millis: u64) -> u64 {
+    let margin_millis =
+        (timeout_millis / 10).min(MAX_LEAF_SEARCH_TIMEOUT_MARGIN.as_millis() as u64);
+    timeout_millis - margin_millis
+}
+
+/// Awaits a leaf search until the deadline, if any.
+///
+/// When the deadline is reached, the leaf search is cancelled and its splits are reported as
+/// timed out.
+async fn leaf_search_until_deadline(
+    leaf_search_fut: impl Future<Output = crate::Result<LeafSearchResponse>>,
+    split_ids: Vec<String>,
+    deadline_opt: Option<Instant>,
+) -> crate::Result<LeafSearchResponse> {
+    let Some(deadline) = deadline_opt else {
+        return leaf_search_fut.await;
+    };
+    if let Ok(leaf_search_res) = tokio::time::timeout_at(deadline, leaf_search_fut).await {
+        return leaf_search_res;
+    }
+    let failed_splits = split_ids
+        .into_iter()
+        .map(SplitSearchError::for_timed_out_split)
+        .collect();
+    Ok(LeafSearchResponse {
+        failed_splits,
+        ..Default::default()
+    })
+}
+
+/// Awaits a leaf search, reporting its completion to the search progress, if any.
+async fn leaf_search_with_progress(
+    leaf_search_fut: impl Future<Output = crate::Result<LeafSearchResponse>>,
+    num_splits: usize,
+    search_progress_opt: Option<&SearchProgress>,
+) -> crate::Result<LeafSearchResponse> {
+    let leaf_search_response = leaf_search_fut.await?;
+    if let Some(search_progress) = search_progress_opt {
+        search_progress.record_leaf_search_response(num_splits, &leaf_search_response);
+    }
+    Ok(leaf_search_response)
+}
+
 pub(crate) fn get_snippet_request(search_request: &SearchRequest) -> Option<SnippetRequest> {
     if search_request.snippet_fields.is_empty() {
         return None;
@@ -515,6 +685,7 @@ pub(crate) fn get_snippet_request(search_request: &SearchRequest) -> Option<Snip
     Some(SnippetRequest {
         snippet_fields: search_request.snippet_fields.clone(),
         query_ast_resolved: search_request.query_ast.clone(),
+        snippet_config: search_request.snippet_config.clone(),
     })
 }
 
@@ -524,6 +695,7 @@ pub(crate) async fn fetch_docs_phase(
     partial_hits: &[PartialHit],
     split_metadatas: &[SplitMetadata],
     snippet_request_opt: Option<SnippetRequest>,
+    fetch_fields: &[String],
     cluster_client: &ClusterClient,
 ) -> crate::Result<Vec<Hit>> {
     let hit_order: HashMap<(String, u32, u32), usize> = partial_hits
@@ -550,6 +722,7 @@ pub(crate) async fn fetch_docs_phase(
     for (client, client_jobs) in assigned_fetch_docs_jobs {
         let fetch_jobs_requests = jobs_to_fetch_docs_requests(
             snippet_request_opt.clone(),
+            fetch_fields,
             indexes_metas_for_leaf_search,
             client_jobs,
         )?;
@@ -621,6 +794,7 @@ async fn root_search_aux(
     search_request: SearchRequest,
     split_metadatas: Vec<SplitMetadata>,
     cluster_client: &ClusterClient,
+    search_progress_opt: Option<&SearchProgress>,
 ) -> crate::Result<SearchResponse> {
     debug!(split_metadatas = ?PrettySample::new(&split_metadatas, 5));
     let (first_phase_result, scroll_key_and_start_offset_opt): (
@@ -632,6 +806,7 @@ async fn root_search_aux(
         search_request.clone(),
         &split_metadatas[..],
         cluster_client,
+        search_progress_opt,
     )
     .await?;
 
@@ -641,6 +816,7 @@ async fn root_search_aux(
         &first_phase_result.partial_hits,
         &split_metadatas[..],
         snippet_request,
+        &search_request.fetch_fields,
         cluster_client,
     )
     .await?;
@@ -651,15 +827,33 @@ async fn root_search_aux(
         searcher_context,
     )?;
 
+    // At this point, the only failed splits are the ones skipped because the search timed out.
+    let timed_out = !first_phase_result.failed_splits.is_empty();
+    let errors: Vec<String> = first_phase_result
+        .failed_splits
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    let profile_opt = if search_request.profile {
+        Some(SearchProfile {
+            split_profiles: first_phase_result.split_profiles,
+            ..Default::default()
+        })
+    } else {
+        None
+    };
+
     Ok(SearchResponse {
         aggregation: aggregation_result_json_opt,
         num_hits: first_phase_result.num_hits,
         hits,
         elapsed_time_micros: 0u64,
-        errors: Vec::new(),
+        errors,
         scroll_id: scroll_key_and_start_offset_opt
             .as_ref()
             .map(ToString::to_string),
+        timed_out,
+        profile: profile_opt,
     })
 }
 
@@ -685,7 +879,7 @@ fn finalize_aggregation(
     Ok(merge_aggregation_result)
 }
 
-fn finalize_aggregation_if_any(
+pub(crate) fn finalize_aggregation_if_any(
     search_request: &SearchRequest,
     intermediate_aggregation_result_bytes_opt: Option<Vec<u8>>,
     searcher_context: &SearcherContext,
@@ -713,7 +907,7 @@ fn finalize_aggregation_if_any(
 /// We put this check here and not in the metastore to make sure the logic is independent
 /// of the metastore implementation, and some different use cases could require different
 /// behaviors. This specification was principally motivated by #4042.
-fn check_all_index_metadata_found(
+pub fn check_all_index_metadata_found(
     index_metadatas: &[IndexMetadata],
     index_id_patterns: &[String],
 ) -> crate::Result<()> {
@@ -752,10 +946,29 @@ fn check_all_index_metadata_found(
 /// 4. Builds the response with docs and returns.
 #[instrument(skip_all)]
 pub async fn root_search(
+    searcher_context: &SearcherContext,
+    search_request: SearchRequest,
+    metastore: MetastoreServiceClient,
+    cluster_client: &ClusterClient,
+) -> crate::Result<SearchResponse> {
+    root_search_with_progress(
+        searcher_context,
+        search_request,
+        metastore,
+        cluster_client,
+        None,
+    )
+    .await
+}
+
+/// Performs a distributed search, like [`root_search`], reporting the splits searched and
+/// their results as they complete to the search progress, if any.
+pub(crate) async fn root_search_with_progress(
     searcher_context: &SearcherContext,
     mut search_request: SearchRequest,
     mut metastore: MetastoreServiceClient,
     cluster_client: &ClusterClient,
+    search_progress_opt: Option<&SearchProgress>,
 ) -> crate::Result<SearchResponse> {
     info!(searcher_context = ?searcher_context, search_request = ?search_request);
     let start_instant = tokio::time::Instant::now();
@@ -779,54 +992,179 @@ pub async fn root_search(
             search_request,
             Vec::new(),
             cluster_client,
+            search_progress_opt,
         )
         .await?;
         search_response.elapsed_time_micros = start_instant.elapsed().as_micros() as u64;
         return Ok(search_response);
     }
 
-    let index_uids = indexes_metadata
-        .iter()
-        .map(|index_metadata| index_metadata.index_uid.clone())
-        .collect_vec();
-    let (timestamp_field_opt, query_ast_resolved, indexes_metas_for_leaf_search) =
-        validate_request_and_build_metadatas(&indexes_metadata, &search_request)?;
-    search_request.query_ast = serde_json::to_string(&query_ast_resolved)?;
-
-    if let Some(timestamp_field) = &timestamp_field_opt {
-        refine_start_end_timestamp_from_ast(
-            &query_ast_resolved,
-            timestamp_field,
-            &mut search_request.start_timestamp,
-            &mut search_request.end_timestamp,
-        );
+    let mut search_profile_opt: Option<SearchProfile> =
+        search_request.profile.then(SearchProfile::default);
+    let (query_ast_resolved_opt, splits_filters, indexes_metas_for_leaf_search) =
+        validate_request_and_build_metadatas(
+            &indexes_metadata,
+            &search_request,
+            search_profile_opt.as_mut(),
+        )?;
+    // When the resolved query differs between indexes, each leaf request gets the query
+    // resolved for its index from `indexes_metas_for_leaf_search`.
+    if let Some(query_ast_resolved) = query_ast_resolved_opt {
+        search_request.query_ast = serde_json::to_string(&query_ast_resolved)?;
     }
-    let tag_filter_ast = extract_tags_from_query(query_ast_resolved);
 
     // TODO if search after is set, we sort by timestamp and we don't want to count all results,
     // we can refine more here. Same if we sort by _shard_doc
-    let split_metadatas: Vec<SplitMetadata> = list_relevant_splits(
-        index_uids,
-        search_request.start_timestamp,
-        search_request.end_timestamp,
-        tag_filter_ast,
+    let split_metadatas: Vec<SplitMetadata> = list_relevant_splits_for_indexes(
+        splits_filters,
         &mut metastore,
+        search_profile_opt.as_mut(),
     )
     .await?;
 
+    // The metastore requests are part of the time budget of the search.
+    if let Some(timeout_millis) = search_request.timeout_millis {
+        let elapsed_millis = start_instant.elapsed().as_millis() as u64;
+        search_request.timeout_millis = Some(timeout_millis.saturating_sub(elapsed_millis));
+    }
+
     let mut search_response = root_search_aux(
         searcher_context,
         &indexes_metas_for_leaf_search,
         search_request,
         split_metadatas,
         cluster_client,
+        search_progress_opt,
     )
     .await?;
 
+    if let Some(mut search_profile) = search_profile_opt {
+        if let Some(leaf_search_profile) = search_response.profile.take() {
+            search_profile.split_profiles = leaf_search_profile.split_profiles;
+        }
+        search_response.profile = Some(search_profile);
+    }
     search_response.elapsed_time_micros = start_instant.elapsed().as_micros() as u64;
     Ok(search_response)
 }
 
+/// Maximum number of pruned splits reported in a search profile, for each splits filter.
+const MAX_NUM_PRUNED_SPLITS_PER_PROFILE: usize = 1_000;
+
+/// Lists the splits relevant to a search request.
+///
+/// Indexes sharing the same splits filter are listed with a single metastore request.
+/// If a search profile is given, the splits pruned by the filters are added to it.
+async fn list_relevant_splits_for_indexes(
+    splits_filters: Vec<(IndexUid, SplitsFilter)>,
+    metastore: &mut MetastoreServiceClient,
+    mut search_profile_opt: Option<&mut SearchProfile>,
+) -> crate::Result<Vec<SplitMetadata>> {
+    let mut index_uids_per_splits_filter: Vec<(SplitsFilter, Vec<IndexUid>)> = Vec::new();
+    for (index_uid, splits_filter) in splits_filters {
+        if let Some((_, index_uids)) = index_uids_per_splits_filter
+            .iter_mut()
+            .find(|(other_splits_filter, _)| *other_splits_filter == splits_filter)
+        {
+            index_uids.push(index_uid);
+        } else {
+            index_uids_per_splits_filter.push((splits_filter, vec![index_uid]));
+        }
+    }
+    let mut split_metadatas = Vec::new();
+    for (splits_filter, index_uids) in index_uids_per_splits_filter {
+        let split_metadatas_for_filter = list_relevant_splits(
+            index_uids.clone(),
+            splits_filter.start_timestamp,
+            splits_filter.end_timestamp,
+            splits_filter.tag_filter_ast.clone(),
+            metastore,
+        )
+        .await?;
+        if let Some(search_profile) = search_profile_opt.as_deref_mut() {
+            if splits_filter != SplitsFilter::default() {
+                // The pruned splits are the ones missing from the relevant splits. Listing the
+                // splits of the indexes without filter is only done when profiling, and is capped
+                // so that the number of splits listed stays close to the number of relevant ones.
+                let limit = split_metadatas_for_filter.len() + MAX_NUM_PRUNED_SPLITS_PER_PROFILE;
+                let list_splits_query = ListSplitsQuery::try_from_index_uids(index_uids)?
+                    .with_split_state(SplitState::Published)
+                    .with_limit(limit);
+                let list_splits_request =
+                    ListSplitsRequest::try_from_list_splits_query(list_splits_query)?;
+                let all_split_metadatas: Vec<SplitMetadata> = metastore
+                    .list_splits(list_splits_request)
+                    .await?
+                    .deserialize_splits()?
+                    .into_iter()
+                    .map(|split| split.split_metadata)
+                    .collect();
+
+                if all_split_metadatas.len() == limit {
+                    search_profile.pruned_splits_truncated = true;
+                }
+                record_pruned_splits(
+                    search_profile,
+                    &splits_filter,
+                    all_split_metadatas,
+                    &split_metadatas_for_filter,
+                );
+            }
+        }
+        split_metadatas.extend(split_metadatas_for_filter);
+    }
+    Ok(split_metadatas)
+}
+
+/// Adds to the search profile the splits that were pruned by the splits filter, that is, the
+/// splits missing from the relevant splits.
+fn record_pruned_splits(
+    search_profile: &mut SearchProfile,
+    splits_filter: &SplitsFilter,
+    all_split_metadatas: Vec<SplitMetadata>,
+    relevant_split_metadatas: &[SplitMetadata],
+) {
+    let relevant_split_ids: HashSet<&str> = relevant_split_metadatas
+        .iter()
+        .map(|split_metadata| split_metadata.split_id())
+        .collect();
+    for split_metadata in all_split_metadatas {
+        if relevant_split_ids.contains(split_metadata.split_id()) {
+            continue;
+        }
+        let pruned_by_time_range = is_split_pruned_by_time_range(&split_metadata, splits_filter);
+        let pruned_split = PrunedSplit {
+            index_id: split_metadata.index_uid.index_id().to_string(),
+            split_id: split_metadata.split_id,
+        };
+        if pruned_by_time_range {
+            search_profile
+                .splits_pruned_by_time_range
+                .push(pruned_split);
+        } else {
+            search_profile.splits_pruned_by_tags.push(pruned_split);
+        }
+    }
+}
+
+/// Returns true if the time range of the split does not overlap the
+/// `[start_timestamp, end_timestamp)` time range of the splits filter.
+fn is_split_pruned_by_time_range(
+    split_metadata: &SplitMetadata,
+    splits_filter: &SplitsFilter,
+) -> bool {
+    let Some(time_range) = split_metadata.time_range.as_ref() else {
+        return false;
+    };
+    let ends_before_start = splits_filter
+        .start_timestamp
+        .map_or(false, |start_timestamp| *time_range.end() < start_timestamp);
+    let starts_after_end = splits_filter
+        .end_timestamp
+        .map_or(false, |end_timestamp| *time_range.start() >= end_timestamp);
+    ends_before_start || starts_after_end
+}
+
 pub(crate) fn refine_start_end_timestamp_from_ast(
     query_ast: &QueryAst,
     timestamp_field: &str,
@@ -1015,7 +1353,16 @@ pub async fn root_list_terms(
 
     let index_uri = &index_config.index_uri;
 
-    let jobs: Vec<SearchJob> = split_metadatas.iter().map(SearchJob::from).collect();
+    let jobs: Vec<SearchJob> = split_metadatas
+        .iter()
+        .map(|split_metadata| {
+            SearchJob::for_time_range(
+                split_metadata,
+                list_terms_request.start_timestamp,
+                list_terms_request.end_timestamp,
+            )
+        })
+        .collect();
     let assigned_leaf_search_jobs = cluster_client
         .search_job_placer
         .assign_jobs(jobs, &HashSet::default())
@@ -1126,10 +1473,62 @@ async fn assign_client_fetch_docs_jobs(
     Ok(assigned_jobs)
 }
 
+/// Fixed cost of searching a split, whatever its size: opening it, fetching its hotcache...
+const SPLIT_FIXED_COST: usize = 5;
+
+/// Number of documents accounting for one unit of cost.
+const NUM_DOCS_PER_COST_UNIT: usize = 100_000;
+
+/// Number of bytes of uncompressed documents accounting for one unit of cost.
+const NUM_BYTES_PER_COST_UNIT: u64 = 100_000_000;
+
 // Measure the cost associated to searching in a given split metadata.
-fn compute_split_cost(_split_metadata: &SplitMetadata) -> usize {
-    // TODO: Have a smarter cost, by smoothing the number of docs.
-    1
+//
+// The cost is made of a fixed part and of a part proportional to the number and size of the
+// documents in the split, scaled by the fraction of the split time range overlapping the
+// `[start_timestamp, end_timestamp)` time range of the search.
+fn compute_split_cost(
+    split_metadata: &SplitMetadata,
+    start_timestamp: Option<i64>,
+    end_timestamp: Option<i64>,
+) -> usize {
+    let num_docs_cost = split_metadata.num_docs as f64 / NUM_DOCS_PER_COST_UNIT as f64;
+    let num_bytes_cost =
+        split_metadata.uncompressed_docs_size_in_bytes as f64 / NUM_BYTES_PER_COST_UNIT as f64;
+    let time_range_overlap_ratio = split_metadata
+        .time_range
+        .as_ref()
+        .map(|time_range| {
+            compute_time_range_overlap_ratio(time_range, start_timestamp, end_timestamp)
+        })
+        .unwrap_or(1.0);
+    let variable_cost = (num_docs_cost + num_bytes_cost) * time_range_overlap_ratio;
+    SPLIT_FIXED_COST + variable_cost.round() as usize
+}
+
+/// Returns the fraction of the split time range `[split_start, split_end]` overlapping the
+/// search time range `[start_timestamp, end_timestamp)`, assuming documents are evenly spread
+/// over time.
+fn compute_time_range_overlap_ratio(
+    split_time_range: &RangeInclusive<i64>,
+    start_timestamp: Option<i64>,
+    end_timestamp: Option<i64>,
+) -> f64 {
+    let split_start = *split_time_range.start();
+    let split_end = *split_time_range.end();
+    let overlap_start = start_timestamp.map_or(split_start, |start_timestamp| {
+        split_start.max(start_timestamp)
+    });
+    let overlap_end = end_timestamp.map_or(split_end, |end_timestamp| {
+        split_end.min(end_timestamp.saturating_sub(1))
+    });
+    if overlap_end < overlap_start {
+        return 0.0;
+    }
+    // Timestamps are in seconds and both ends are included.
+    let overlap_duration = overlap_end as f64 - overlap_start as f64 + 1.0;
+    let split_duration = split_end as f64 - split_start as f64 + 1.0;
+    (overlap_duration / split_duration).min(1.0)
 }
 
 /// Builds a list of [`LeafSearchRequest`], one per index, from a list of [`SearchJob`].
@@ -1149,8 +1548,18 @@ pub fn jobs_to_leaf_requests(
                 "received search job for an unknown index {index_uid}. it should never happen"
             ))
         })?;
+        let mut search_request_for_index = search_request_for_leaf.clone();
+        if let Some(query_ast_resolved) = &search_index_meta.query_ast_resolved {
+            search_request_for_index.query_ast = query_ast_resolved.clone();
+        }
+        search_request_for_index.start_timestamp = search_index_meta
+            .start_timestamp
+            .or(search_request_for_leaf.start_timestamp);
+        search_request_for_index.end_timestamp = search_index_meta
+            .end_timestamp
+            .or(search_request_for_leaf.end_timestamp);
         let leaf_search_request = LeafSearchRequest {
-            search_request: Some(search_request_for_leaf.clone()),
+            search_request: Some(search_request_for_index),
             split_offsets: job_group.into_iter().map(|job| job.offsets).collect(),
             doc_mapper: search_index_meta.doc_mapper_str.clone(),
             index_uri: search_index_meta.index_uri.to_string(),
@@ -1163,6 +1572,7 @@ pub fn jobs_to_leaf_requests(
 /// Builds a list of [`FetchDocsRequest`], one per index, from a list of [`FetchDocsJob`].
 pub fn jobs_to_fetch_docs_requests(
     snippet_request_opt: Option<SnippetRequest>,
+    fetch_fields: &[String],
     indexes_metas_for_leaf_search: &IndexesMetasForLeafSearch,
     jobs: Vec<FetchDocsJob>,
 ) -> crate::Result<Vec<FetchDocsRequest>> {
@@ -1185,12 +1595,20 @@ pub fn jobs_to_fetch_docs_requests(
             .into_iter()
             .map(|fetch_doc_job| fetch_doc_job.into())
             .collect();
+        let mut snippet_request_for_index = snippet_request_opt.clone();
+        if let (Some(snippet_request), Some(query_ast_resolved)) = (
+            snippet_request_for_index.as_mut(),
+            &index_meta.query_ast_resolved,
+        ) {
+            snippet_request.query_ast_resolved = query_ast_resolved.clone();
+        }
         let fetch_docs_req = FetchDocsRequest {
             partial_hits,
             split_offsets,
             index_uri: index_meta.index_uri.to_string(),
-            snippet_request: snippet_request_opt.clone(),
+            snippet_request: snippet_request_for_index,
             doc_mapper: index_meta.doc_mapper_str.clone(),
+            fetch_fields: fetch_fields.to_vec(),
         };
         fetch_docs_requests.push(fetch_docs_req);
     }
@@ -1221,6 +1639,7 @@ mod tests {
         schema_builder.add_text_field("title", TEXT);
         schema_builder.add_text_field("desc", TEXT | STORED);
         schema_builder.add_ip_addr_field("ip", FAST | STORED);
+        schema_builder.add_json_field("attributes", TEXT | STORED);
         let schema = schema_builder.build();
         validate_requested_snippet_fields(&schema, snippet_fields)
     }
@@ -1246,6 +1665,13 @@ mod tests {
             field_is_not_text_err.to_string(),
             "the snippet field `ip` must be of type `Str`, got `IpAddr`"
         );
+        check_snippet_fields_validation(&["attributes.message".to_string()]).unwrap();
+        let field_is_json_root_err =
+            check_snippet_fields_validation(&["attributes".to_string()]).unwrap_err();
+        assert_eq!(
+            field_is_json_root_err.to_string(),
+            "the snippet field `attributes` must be of type `Str`, got `JsonObject`"
+        );
     }
 
     fn index_metadata_for_multi_indexes_test(index_id: &str, index_uri: &str) -> IndexMetadata {
@@ -1301,7 +1727,8 @@ mod tests {
             .index_config
             .doc_mapping
             .timestamp_field = None;
-        let (timestamp_field, query_ast, indexes_metas_for_leaf_req) =
+        let mut search_profile = SearchProfile::default();
+        let (query_ast, splits_filters, indexes_metas_for_leaf_req) =
             validate_request_and_build_metadatas(
                 &[
                     index_metadata,
@@ -1309,18 +1736,39 @@ mod tests {
                     index_metadata_no_timestamp,
                 ],
                 &search_request,
+                Some(&mut search_profile),
             )
             .unwrap();
-        assert_eq!(timestamp_field, Some("timestamp".to_string()));
-        assert_eq!(query_ast, request_query_ast);
+        assert_eq!(query_ast, Some(request_query_ast));
+        assert_eq!(splits_filters.len(), 3);
         assert_eq!(indexes_metas_for_leaf_req.len(), 3);
+        let profiled_index_ids: Vec<&str> = search_profile
+            .queries
+            .iter()
+            .map(|query_profile| query_profile.index_id.as_str())
+            .collect();
+        assert_eq!(
+            profiled_index_ids,
+            ["test-index-1", "test-index-2", "test-index-3"]
+        );
+        assert!(search_profile.queries[0].lowered_query.contains("test"));
     }
 
     #[test]
-    fn test_validate_request_and_build_metadatas_fail_with_different_timestamps() {
+    fn test_validate_request_and_build_metadatas_with_different_timestamps() {
+        use std::ops::Bound;
+
+        use quickwit_query::JsonLiteral;
+
+        let query_ast: QueryAst = quickwit_query::query_ast::RangeQuery {
+            field: "timestamp".to_string(),
+            lower_bound: Bound::Included(JsonLiteral::String("2021-04-13T22:45:41Z".to_owned())),
+            upper_bound: Bound::Excluded(JsonLiteral::String("2021-05-06T06:51:19Z".to_owned())),
+        }
+        .into();
         let search_request = quickwit_proto::search::SearchRequest {
             index_id_patterns: vec!["test-index".to_string()],
-            query_ast: qast_json_helper("test", &["body"]),
+            query_ast: serde_json::to_string(&query_ast).unwrap(),
             max_hits: 10,
             start_offset: 10,
             ..Default::default()
@@ -1330,6 +1778,11 @@ mod tests {
         let doc_mapping_json_2 = r#"{
             "mode": "lenient",
             "field_mappings": [
+                {
+                    "name": "timestamp",
+                    "type": "datetime",
+                    "fast": true
+                },
                 {
                     "name": "timestamp-2",
                     "type": "datetime",
@@ -1349,19 +1802,47 @@ mod tests {
             .index_config
             .search_settings
             .default_search_fields = Vec::new();
-        let timestamp_field_different = validate_request_and_build_metadatas(
-            &[index_metadata_1, index_metadata_2],
-            &search_request,
-        )
-        .unwrap_err();
+        let index_uid_1 = index_metadata_1.index_uid.clone();
+        let index_uid_2 = index_metadata_2.index_uid.clone();
+        let (query_ast_resolved, splits_filters, indexes_metas_for_leaf_req) =
+            validate_request_and_build_metadatas(
+                &[index_metadata_1, index_metadata_2],
+                &search_request,
+                None,
+            )
+            .unwrap();
+        assert_eq!(query_ast_resolved, Some(query_ast));
         assert_eq!(
-            timestamp_field_different.to_string(),
-            "the timestamp field (if present) must be the same for all indexes"
+            splits_filters,
+            vec![
+                (
+                    index_uid_1.clone(),
+                    SplitsFilter {
+                        start_timestamp: Some(1618353941),
+                        end_timestamp: Some(1620283879),
+                        tag_filter_ast: None,
+                    }
+                ),
+                (
+                    index_uid_2.clone(),
+                    SplitsFilter {
+                        start_timestamp: None,
+                        end_timestamp: None,
+                        tag_filter_ast: None,
+                    }
+                ),
+            ]
         );
+        let index_metas_1 = &indexes_metas_for_leaf_req[&index_uid_1];
+        assert_eq!(index_metas_1.start_timestamp, Some(1618353941));
+        assert_eq!(index_metas_1.end_timestamp, Some(1620283879));
+        let index_metas_2 = &indexes_metas_for_leaf_req[&index_uid_2];
+        assert_eq!(index_metas_2.start_timestamp, None);
+        assert_eq!(index_metas_2.end_timestamp, None);
     }
 
     #[test]
-    fn test_validate_request_and_build_metadatas_fail_with_different_resolved_qast() {
+    fn test_validate_request_and_build_metadatas_with_different_resolved_qast() {
         let qast = query_ast_from_user_text("test", None);
         let search_request = quickwit_proto::search::SearchRequest {
             index_id_patterns: vec!["test-index".to_string()],
@@ -1376,16 +1857,27 @@ mod tests {
             .index_config
             .search_settings
             .default_search_fields = vec!["owner".to_string()];
-        let timestamp_field_different = validate_request_and_build_metadatas(
-            &[index_metadata_1, index_metadata_2],
-            &search_request,
-        )
-        .unwrap_err();
-        assert_eq!(
-            timestamp_field_different.to_string(),
-            "resolved query ASTs must be the same across indexes. resolving queries with \
-             different default fields are different between indexes is not supported"
-        );
+        let index_uid_1 = index_metadata_1.index_uid.clone();
+        let index_uid_2 = index_metadata_2.index_uid.clone();
+        let (query_ast_resolved, splits_filters, indexes_metas_for_leaf_req) =
+            validate_request_and_build_metadatas(
+                &[index_metadata_1, index_metadata_2],
+                &search_request,
+                None,
+            )
+            .unwrap();
+        assert!(query_ast_resolved.is_none());
+        assert_eq!(splits_filters.len(), 2);
+        let query_ast_resolved_1 = indexes_metas_for_leaf_req[&index_uid_1]
+            .query_ast_resolved
+            .clone()
+            .unwrap();
+        let query_ast_resolved_2 = indexes_metas_for_leaf_req[&index_uid_2]
+            .query_ast_resolved
+            .clone()
+            .unwrap();
+        assert_eq!(query_ast_resolved_2, qast_json_helper("test", &["owner"]));
+        assert_ne!(query_ast_resolved_1, query_ast_resolved_2);
     }
 
     fn mock_partial_hit(
@@ -1396,6 +1888,7 @@ mod tests {
         quickwit_proto::search::PartialHit {
             sort_value: Some(SortValue::U64(sort_value).into()),
             sort_value2: None,
+            extra_sort_values: Vec::new(),
             split_id: split_id.to_string(),
             segment_ord: 1,
             doc_id,
@@ -1410,6 +1903,7 @@ mod tests {
         quickwit_proto::search::PartialHit {
             sort_value: sort_value.map(|sort_value| SortValue::U64(sort_value).into()),
             sort_value2: None,
+            extra_sort_values: Vec::new(),
             split_id: split_id.to_string(),
             segment_ord: 1,
             doc_id,
@@ -1735,6 +2229,7 @@ mod tests {
                         quickwit_proto::search::PartialHit {
                             sort_value: Some(SortValue::U64(2u64).into()),
                             sort_value2: None,
+                            extra_sort_values: Vec::new(),
                             split_id: "split1".to_string(),
                             segment_ord: 0,
                             doc_id: 0,
@@ -1742,6 +2237,7 @@ mod tests {
                         quickwit_proto::search::PartialHit {
                             sort_value: None,
                             sort_value2: None,
+                            extra_sort_values: Vec::new(),
                             split_id: "split1".to_string(),
                             segment_ord: 0,
                             doc_id: 1,
@@ -1769,6 +2265,7 @@ mod tests {
                         quickwit_proto::search::PartialHit {
                             sort_value: Some(SortValue::I64(-1i64).into()),
                             sort_value2: None,
+                            extra_sort_values: Vec::new(),
                             split_id: "split2".to_string(),
                             segment_ord: 0,
                             doc_id: 1,
@@ -1776,6 +2273,7 @@ mod tests {
                         quickwit_proto::search::PartialHit {
                             sort_value: Some(SortValue::I64(1i64).into()),
                             sort_value2: None,
+                            extra_sort_values: Vec::new(),
                             split_id: "split2".to_string(),
                             segment_ord: 0,
                             doc_id: 0,
@@ -1783,6 +2281,7 @@ mod tests {
                         quickwit_proto::search::PartialHit {
                             sort_value: None,
                             sort_value2: None,
+                            extra_sort_values: Vec::new(),
                             split_id: "split2".to_string(),
                             segment_ord: 0,
                             doc_id: 2,
@@ -1825,6 +2324,7 @@ mod tests {
                 doc_id: 1,
                 sort_value: Some(SortValue::I64(-1i64).into()),
                 sort_value2: None,
+                extra_sort_values: Vec::new(),
             }
         );
         assert_eq!(
@@ -1835,6 +2335,7 @@ mod tests {
                 doc_id: 0,
                 sort_value: Some(SortValue::I64(1i64).into()),
                 sort_value2: None,
+                extra_sort_values: Vec::new(),
             }
         );
         assert_eq!(
@@ -1845,6 +2346,7 @@ mod tests {
                 doc_id: 0,
                 sort_value: Some(SortValue::U64(2u64).into()),
                 sort_value2: None,
+                extra_sort_values: Vec::new(),
             }
         );
         assert_eq!(
@@ -1855,6 +2357,7 @@ mod tests {
                 doc_id: 1,
                 sort_value: None,
                 sort_value2: None,
+                extra_sort_values: Vec::new(),
             }
         );
         assert_eq!(
@@ -1865,6 +2368,7 @@ mod tests {
                 doc_id: 2,
                 sort_value: None,
                 sort_value2: None,
+                extra_sort_values: Vec::new(),
             }
         );
         Ok(())
@@ -1914,6 +2418,7 @@ mod tests {
                         quickwit_proto::search::PartialHit {
                             sort_value: Some(SortValue::U64(2u64).into()),
                             sort_value2: None,
+                            extra_sort_values: Vec::new(),
                             split_id: "split1".to_string(),
                             segment_ord: 0,
                             doc_id: 0,
@@ -1921,6 +2426,7 @@ mod tests {
                         quickwit_proto::search::PartialHit {
                             sort_value: None,
                             sort_value2: None,
+                            extra_sort_values: Vec::new(),
                             split_id: "split1".to_string(),
                             segment_ord: 0,
                             doc_id: 1,
@@ -1948,6 +2454,7 @@ mod tests {
                         quickwit_proto::search::PartialHit {
                             sort_value: Some(SortValue::I64(1i64).into()),
                             sort_value2: None,
+                            extra_sort_values: Vec::new(),
                             split_id: "split2".to_string(),
                             segment_ord: 0,
                             doc_id: 0,
@@ -1955,6 +2462,7 @@ mod tests {
                         quickwit_proto::search::PartialHit {
                             sort_value: Some(SortValue::I64(-1i64).into()),
                             sort_value2: None,
+                            extra_sort_values: Vec::new(),
                             split_id: "split2".to_string(),
                             segment_ord: 0,
                             doc_id: 1,
@@ -1962,6 +2470,7 @@ mod tests {
                         quickwit_proto::search::PartialHit {
                             sort_value: None,
                             sort_value2: None,
+                            extra_sort_values: Vec::new(),
                             split_id: "split2".to_string(),
                             segment_ord: 0,
                             doc_id: 2,
@@ -2004,6 +2513,7 @@ mod tests {
                 doc_id: 0,
                 sort_value: Some(SortValue::U64(2u64).into()),
                 sort_value2: None,
+                extra_sort_values: Vec::new(),
             }
         );
         assert_eq!(
@@ -2014,6 +2524,7 @@ mod tests {
                 doc_id: 0,
                 sort_value: Some(SortValue::I64(1i64).into()),
                 sort_value2: None,
+                extra_sort_values: Vec::new(),
             }
         );
         assert_eq!(
@@ -2024,6 +2535,7 @@ mod tests {
                 doc_id: 1,
                 sort_value: Some(SortValue::I64(-1i64).into()),
                 sort_value2: None,
+                extra_sort_values: Vec::new(),
             }
         );
         assert_eq!(
@@ -2034,6 +2546,7 @@ mod tests {
                 doc_id: 2,
                 sort_value: None,
                 sort_value2: None,
+                extra_sort_values: Vec::new(),
             }
         );
         assert_eq!(
@@ -2044,6 +2557,7 @@ mod tests {
                 doc_id: 1,
                 sort_value: None,
                 sort_value2: None,
+                extra_sort_values: Vec::new(),
             }
         );
         Ok(())
@@ -2094,6 +2608,7 @@ mod tests {
                             error: "mock_error".to_string(),
                             split_id: "split2".to_string(),
                             retryable_error: true,
+                            timed_out: false,
                         }],
                         num_attempted_splits: 1,
                         ..Default::default()
@@ -2213,6 +2728,7 @@ mod tests {
                         error: "mock_error".to_string(),
                         split_id: "split2".to_string(),
                         retryable_error: true,
+                        timed_out: false,
                     }],
                     num_attempted_splits: 1,
                     ..Default::default()
@@ -2268,6 +2784,7 @@ mod tests {
                         error: "mock_error".to_string(),
                         split_id: "split1".to_string(),
                         retryable_error: true,
+                        timed_out: false,
                     }],
                     num_attempted_splits: 1,
                     ..Default::default()
@@ -2340,6 +2857,7 @@ mod tests {
                             error: "mock_error".to_string(),
                             split_id: "split1".to_string(),
                             retryable_error: true,
+                            timed_out: false,
                         }],
                         num_attempted_splits: 1,
                         ..Default::default()
@@ -2414,6 +2932,7 @@ mod tests {
                         error: "mock_error".to_string(),
                         split_id: "split1".to_string(),
                         retryable_error: true,
+                        timed_out: false,
                     }],
                     num_attempted_splits: 1,
                     ..Default::default()
@@ -2497,6 +3016,7 @@ mod tests {
                         error: "mock_error".to_string(),
                         split_id: "split1".to_string(),
                         retryable_error: true,
+                        timed_out: false,
                     }],
                     num_attempted_splits: 1,
                     ..Default::default()
@@ -2797,6 +3317,97 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_leaf_search_timeout_millis() {
+        assert_eq!(leaf_search_timeout_millis(0), 0);
+        assert_eq!(leaf_search_timeout_millis(500), 450);
+        assert_eq!(leaf_search_timeout_millis(10_000), 9_900);
+    }
+
+    #[test]
+    fn test_compute_split_cost() {
+        let small_split_metadata = SplitMetadata {
+            num_docs: 5_000,
+            uncompressed_docs_size_in_bytes: 1_000_000,
+            time_range: Some(0..=99),
+            ..Default::default()
+        };
+        assert_eq!(compute_split_cost(&small_split_metadata, None, None), 5);
+
+        let large_split_metadata = SplitMetadata {
+            num_docs: 10_000_000,
+            uncompressed_docs_size_in_bytes: 1_000_000_000,
+            time_range: Some(0..=99),
+            ..Default::default()
+        };
+        assert_eq!(compute_split_cost(&large_split_metadata, None, None), 115);
+        assert_eq!(
+            compute_split_cost(&large_split_metadata, Some(50), None),
+            60
+        );
+        assert_eq!(
+            compute_split_cost(&large_split_metadata, None, Some(10)),
+            16
+        );
+        assert_eq!(
+            compute_split_cost(&large_split_metadata, Some(0), Some(100)),
+            115
+        );
+        assert_eq!(
+            compute_split_cost(&large_split_metadata, Some(200), None),
+            5
+        );
+
+        let large_split_without_time_range_metadata = SplitMetadata {
+            time_range: None,
+            ..large_split_metadata
+        };
+        assert_eq!(
+            compute_split_cost(&large_split_without_time_range_metadata, Some(50), None),
+            115
+        );
+    }
+
+    #[test]
+    fn test_is_split_pruned_by_time_range() {
+        let split_metadata = SplitMetadata {
+            time_range: Some(10..=19),
+            ..Default::default()
+        };
+        let splits_filter =
+            |start_timestamp: Option<i64>, end_timestamp: Option<i64>| SplitsFilter {
+                start_timestamp,
+                end_timestamp,
+                tag_filter_ast: None,
+            };
+        assert!(!is_split_pruned_by_time_range(
+            &split_metadata,
+            &splits_filter(None, None)
+        ));
+        assert!(!is_split_pruned_by_time_range(
+            &split_metadata,
+            &splits_filter(Some(19), Some(20))
+        ));
+        assert!(is_split_pruned_by_time_range(
+            &split_metadata,
+            &splits_filter(Some(20), None)
+        ));
+        assert!(!is_split_pruned_by_time_range(
+            &split_metadata,
+            &splits_filter(None, Some(11))
+        ));
+        assert!(is_split_pruned_by_time_range(
+            &split_metadata,
+            &splits_filter(None, Some(10))
+        ));
+
+        let split_metadata_without_time_range = SplitMetadata::default();
+        assert!(!is_split_pruned_by_time_range(
+            &split_metadata_without_time_range,
+            &splits_filter(Some(20), Some(30))
+        ));
+    }
+
     #[test]
     fn test_extract_timestamp_range_from_ast() {
         use std::ops::Bound;
@@ -3229,4 +3840,102 @@ mod tests {
         );
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_root_search_multi_indices_with_different_default_search_fields(
+    ) -> anyhow::Result<()> {
+        let search_request = quickwit_proto::search::SearchRequest {
+            index_id_patterns: vec!["test-index-*".to_string()],
+            query_ast: serde_json::to_string(&query_ast_from_user_text("test", None)).unwrap(),
+            max_hits: 10,
+            ..Default::default()
+        };
+        let mut metastore = MetastoreServiceClient::mock();
+        let index_metadata_1 = IndexMetadata::for_test("test-index-1", "ram:///test-index-1");
+        let index_uid_1 = index_metadata_1.index_uid.clone();
+        let index_metadata_2 =
+            index_metadata_for_multi_indexes_test("test-index-2", "ram:///test-index-2");
+        let index_uid_2 = index_metadata_2.index_uid.clone();
+        metastore.expect_list_indexes_metadata().return_once(
+            move |_list_indexes_metadata_request: ListIndexesMetadataRequest| {
+                Ok(ListIndexesMetadataResponse::try_from_indexes_metadata(vec![
+                    index_metadata_1,
+                    index_metadata_2,
+                ])
+                .unwrap())
+            },
+        );
+        metastore
+            .expect_list_splits()
+            .return_once(move |list_splits_request| {
+                let list_splits_query =
+                    list_splits_request.deserialize_list_splits_query().unwrap();
+                assert_eq!(
+                    list_splits_query.index_uids,
+                    vec![index_uid_1.clone(), index_uid_2.clone()]
+                );
+                let splits = vec![
+                    MockSplitBuilder::new("index-1-split-1")
+                        .with_index_uid(&index_uid_1)
+                        .build(),
+                    MockSplitBuilder::new("index-2-split-1")
+                        .with_index_uid(&index_uid_2)
+                        .build(),
+                ];
+                Ok(ListSplitsResponse::try_from_splits(splits).unwrap())
+            });
+        let mut mock_search_service = MockSearchService::new();
+        mock_search_service
+            .expect_leaf_search()
+            .times(2)
+            .withf(|leaf_search_req| {
+                let query_ast = &leaf_search_req.search_request.as_ref().unwrap().query_ast;
+                (leaf_search_req.index_uri == "ram:///test-index-1"
+                    && *query_ast
+                        == qast_json_helper(
+                            "test",
+                            &["body", "attributes.server", r"attributes.server\.status"],
+                        ))
+                    || (leaf_search_req.index_uri == "ram:///test-index-2"
+                        && *query_ast == qast_json_helper("test", &["body"]))
+            })
+            .returning(
+                |leaf_search_req: quickwit_proto::search::LeafSearchRequest| {
+                    let partial_hits = leaf_search_req
+                        .split_offsets
+                        .iter()
+                        .map(|split_offset| mock_partial_hit(&split_offset.split_id, 3, 1))
+                        .collect_vec();
+                    Ok(quickwit_proto::search::LeafSearchResponse {
+                        num_hits: leaf_search_req.split_offsets.len() as u64,
+                        partial_hits,
+                        failed_splits: Vec::new(),
+                        num_attempted_splits: 1,
+                        ..Default::default()
+                    })
+                },
+            );
+        mock_search_service
+            .expect_fetch_docs()
+            .times(2)
+            .returning(|fetch_docs_req| {
+                Ok(quickwit_proto::search::FetchDocsResponse {
+                    hits: get_doc_for_fetch_req(fetch_docs_req),
+                })
+            });
+        let searcher_pool = searcher_pool_for_test([("127.0.0.1:1001", mock_search_service)]);
+        let search_job_placer = SearchJobPlacer::new(searcher_pool);
+        let cluster_client = ClusterClient::new(search_job_placer.clone());
+        let search_response = root_search(
+            &SearcherContext::for_test(),
+            search_request,
+            MetastoreServiceClient::from(metastore),
+            &cluster_client,
+        )
+        .await
+        .unwrap();
+        assert_eq!(search_response.num_hits, 2);
+        assert_eq!(search_response.hits.len(), 2);
+        Ok(())
+    }
 }
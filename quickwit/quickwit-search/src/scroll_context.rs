@@ -115,6 +115,7 @@ impl ScrollContext {
             &self.search_request,
             &self.split_metadatas[..],
             cluster_client,
+            None,
         )
         .await?;
         self.cached_partial_hits_start_offset = start_offset;
@@ -174,17 +174,35 @@ impl SearchJobPlacer {
         }
         jobs.sort_unstable_by(Job::compare_cost);
 
+        let total_load: usize = jobs.iter().map(Job::cost).sum();
+        let max_node_load = compute_max_node_load(total_load, candidate_nodes.len());
+
         let mut job_assignments: HashMap<SocketAddr, (SearchServiceClient, Vec<J>)> =
             HashMap::with_capacity(num_nodes);
 
         for job in jobs {
             sort_by_rendez_vous_hash(&mut candidate_nodes, job.split_id());
-            // Select the least loaded node.
-            let chosen_node_idx = if candidate_nodes.len() >= 2 {
+            // Select the least loaded of the two nodes with the highest affinity, unless it would
+            // end up with more than its share of the total load. In that case, pick the node with
+            // the highest affinity that can still take the job, or the least loaded node.
+            let mut chosen_node_idx = if candidate_nodes.len() >= 2 {
                 usize::from(candidate_nodes[0].load > candidate_nodes[1].load)
             } else {
                 0
             };
+            if candidate_nodes[chosen_node_idx].load + job.cost() > max_node_load {
+                chosen_node_idx = candidate_nodes
+                    .iter()
+                    .position(|candidate_node| candidate_node.load + job.cost() <= max_node_load)
+                    .unwrap_or_else(|| {
+                        candidate_nodes
+                            .iter()
+                            .enumerate()
+                            .min_by_key(|(_, candidate_node)| candidate_node.load)
+                            .map(|(candidate_node_idx, _)| candidate_node_idx)
+                            .expect("`candidate_nodes` should not be empty.")
+                    });
+            }
             let chosen_node = &mut candidate_nodes[chosen_node_idx];
             chosen_node.load += job.cost();
 
@@ -213,6 +231,17 @@ impl SearchJobPlacer {
     }
 }
 
+/// Factor by which the load of a node may exceed an even share of the total load.
+///
+/// The bound is loose on purpose: jobs only spill over to nodes with a lower affinity, and
+/// therefore a colder cache, for their split when a node is clearly overloaded.
+const MAX_LOAD_FACTOR: usize = 2;
+
+/// Returns the maximum load a node should be assigned, given the total load of the jobs to place.
+fn compute_max_node_load(total_load: usize, num_nodes: usize) -> usize {
+    (total_load * MAX_LOAD_FACTOR + num_nodes - 1) / num_nodes
+}
+
 #[derive(Debug, Clone)]
 struct CandidateNodes {
     pub grpc_addr: SocketAddr,
@@ -322,4 +351,82 @@ mod tests {
             assert_eq!(assigned_jobs, expected_assigned_jobs);
         }
     }
+
+    #[tokio::test]
+    async fn test_search_job_placer_bounds_node_load() {
+        let searcher_pool = searcher_pool_for_test([
+            ("127.0.0.1:1001", MockSearchService::new()),
+            ("127.0.0.1:1002", MockSearchService::new()),
+            ("127.0.0.1:1003", MockSearchService::new()),
+            ("127.0.0.1:1004", MockSearchService::new()),
+        ]);
+        let search_job_placer = SearchJobPlacer::new(searcher_pool);
+        let jobs: Vec<SearchJob> = (0..100)
+            .map(|split_idx| {
+                let cost = if split_idx < 10 {
+                    50
+                } else {
+                    split_idx % 7 + 1
+                };
+                SearchJob::for_test(&format!("split{split_idx}"), cost)
+            })
+            .collect();
+        let total_load: usize = jobs.iter().map(Job::cost).sum();
+        let max_node_load = compute_max_node_load(total_load, 4);
+
+        let assigned_jobs: Vec<(SocketAddr, Vec<SearchJob>)> = search_job_placer
+            .assign_jobs(jobs, &HashSet::default())
+            .await
+            .unwrap()
+            .map(|(client, jobs)| (client.grpc_addr(), jobs))
+            .collect();
+
+        let num_assigned_jobs: usize = assigned_jobs.iter().map(|(_, jobs)| jobs.len()).sum();
+        assert_eq!(num_assigned_jobs, 100);
+
+        for (_, jobs) in &assigned_jobs {
+            let node_load: usize = jobs.iter().map(Job::cost).sum();
+            assert!(node_load <= max_node_load);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_search_job_placer_oversized_job() {
+        let searcher_pool = searcher_pool_for_test([
+            ("127.0.0.1:1001", MockSearchService::new()),
+            ("127.0.0.1:1002", MockSearchService::new()),
+            ("127.0.0.1:1003", MockSearchService::new()),
+        ]);
+        let search_job_placer = SearchJobPlacer::new(searcher_pool);
+        let jobs = vec![
+            SearchJob::for_test("split1", 1_000),
+            SearchJob::for_test("split2", 1),
+            SearchJob::for_test("split3", 1),
+        ];
+        let assigned_jobs: Vec<(SocketAddr, Vec<SearchJob>)> = search_job_placer
+            .assign_jobs(jobs, &HashSet::default())
+            .await
+            .unwrap()
+            .map(|(client, jobs)| (client.grpc_addr(), jobs))
+            .collect();
+
+        // The job exceeding the maximum node load is still assigned, and no other job is
+        // assigned to the same node.
+        let oversized_job_assignment = assigned_jobs
+            .iter()
+            .find(|(_, jobs)| jobs.contains(&SearchJob::for_test("split1", 1_000)))
+            .unwrap();
+        assert_eq!(oversized_job_assignment.1.len(), 1);
+
+        let num_assigned_jobs: usize = assigned_jobs.iter().map(|(_, jobs)| jobs.len()).sum();
+        assert_eq!(num_assigned_jobs, 3);
+    }
+
+    #[test]
+    fn test_compute_max_node_load() {
+        assert_eq!(compute_max_node_load(0, 1), 0);
+        assert_eq!(compute_max_node_load(100, 1), 200);
+        assert_eq!(compute_max_node_load(21, 2), 21);
+        assert_eq!(compute_max_node_load(1_002, 3), 668);
+    }
 }
@@ -19,11 +19,12 @@
 
 use std::convert::TryFrom;
 
-use quickwit_common::truncate_str;
-use quickwit_proto::search::SearchResponse;
+use quickwit_common::{is_false, truncate_str};
+use quickwit_proto::search::{SearchProfile, SearchResponse};
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 
+use crate::async_search::{AsyncSearchState, AsyncSearchStatus};
 use crate::error::SearchError;
 
 /// SearchResponseRest represents the response returned by the REST search API
@@ -47,6 +48,14 @@ pub struct SearchResponseRest {
     #[schema(value_type = Object)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub aggregations: Option<JsonValue>,
+    /// Whether the search timed out. If so, the hits are those gathered before the timeout.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_false")]
+    pub timed_out: bool,
+    /// Profile of the search, only returned if it was requested.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<SearchProfile>,
 }
 
 impl TryFrom<SearchResponse> for SearchResponseRest {
@@ -97,6 +106,60 @@ impl TryFrom<SearchResponse> for SearchResponseRest {
             elapsed_time_micros: search_response.elapsed_time_micros,
             errors: search_response.errors,
             aggregations: aggregations_opt,
+            timed_out: search_response.timed_out,
+            profile: search_response.profile,
+        })
+    }
+}
+
+/// AsyncSearchResponseRest represents the status of an asynchronous search returned by the REST
+/// API and is meant to be serialized into JSON.
+#[derive(Serialize, Deserialize, PartialEq, Debug, utoipa::ToSchema)]
+pub struct AsyncSearchResponseRest {
+    /// ID of the asynchronous search.
+    pub id: String,
+    /// State of the search.
+    pub state: AsyncSearchState,
+    /// Submission time, in milliseconds since the Unix epoch.
+    pub start_time_millis: i64,
+    /// Time after which the search and its results are dropped, in milliseconds since the Unix
+    /// epoch.
+    pub expiration_time_millis: i64,
+    /// Time at which the search completed, failed or was cancelled, in milliseconds since the Unix
+    /// epoch.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completion_time_millis: Option<i64>,
+    /// Number of splits to search.
+    pub num_splits_total: u64,
+    /// Number of splits searched so far.
+    pub num_splits_done: u64,
+    /// Response of the search. While the search is running, it holds the number of hits and the
+    /// aggregations of the splits searched so far, without any hit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response: Option<SearchResponseRest>,
+    /// Error of the search, if it failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl TryFrom<AsyncSearchStatus> for AsyncSearchResponseRest {
+    type Error = SearchError;
+
+    fn try_from(async_search_status: AsyncSearchStatus) -> Result<Self, Self::Error> {
+        let response = async_search_status
+            .response
+            .map(SearchResponseRest::try_from)
+            .transpose()?;
+        Ok(AsyncSearchResponseRest {
+            id: async_search_status.id,
+            state: async_search_status.state,
+            start_time_millis: async_search_status.start_time_millis,
+            expiration_time_millis: async_search_status.expiration_time_millis,
+            completion_time_millis: async_search_status.completion_time_millis,
+            num_splits_total: async_search_status.num_splits_total,
+            num_splits_done: async_search_status.num_splits_done,
+            response,
+            error: async_search_status.error,
         })
     }
 }
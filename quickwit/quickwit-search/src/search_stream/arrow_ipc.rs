@@ -0,0 +1,176 @@
+// Copyright (C) 2023 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Encoding of the search stream results in the Arrow IPC streaming format.
+//!
+//! An Arrow IPC stream is made of a schema message, followed by record batch messages, and
+//! terminated by an end-of-stream marker. Each split yields one record batch: the leaves only
+//! encode record batch messages, while the root emits the schema message and the end-of-stream
+//! marker around them.
+
+use std::sync::Arc;
+
+use arrow::array::{
+    ArrayRef, BooleanArray, Float64Array, Int64Array, StringArray, TimestampMicrosecondArray,
+    UInt64Array,
+};
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use arrow::error::ArrowError;
+use arrow::ipc::writer::{write_message, DictionaryTracker, IpcDataGenerator, IpcWriteOptions};
+use arrow::record_batch::RecordBatch;
+
+use super::collector::{ColumnValues, StreamColumn, StreamColumnType};
+
+/// Marks the end of an Arrow IPC stream: a continuation marker followed by a zero length.
+pub const END_OF_STREAM: [u8; 8] = [0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00];
+
+const UTC_TIMEZONE: &str = "UTC";
+
+/// Returns the Arrow schema of the streamed columns. Datetime columns are exported as
+/// microsecond timestamps.
+pub fn arrow_schema(stream_columns: &[StreamColumn]) -> SchemaRef {
+    let fields: Vec<Field> = stream_columns
+        .iter()
+        .map(|stream_column| {
+            let data_type = match stream_column.column_type {
+                StreamColumnType::I64 => DataType::Int64,
+                StreamColumnType::U64 => DataType::UInt64,
+                StreamColumnType::F64 => DataType::Float64,
+                StreamColumnType::Bool => DataType::Boolean,
+                StreamColumnType::DateTime => {
+                    DataType::Timestamp(TimeUnit::Microsecond, Some(UTC_TIMEZONE.into()))
+                }
+                StreamColumnType::Str => DataType::Utf8,
+            };
+            Field::new(&stream_column.field_name, data_type, true)
+        })
+        .collect();
+    Arc::new(Schema::new(fields))
+}
+
+/// Encodes the schema message that starts an Arrow IPC stream.
+pub fn encode_schema(schema: &Schema) -> Result<Vec<u8>, ArrowError> {
+    let write_options = IpcWriteOptions::default();
+    let encoded_schema = IpcDataGenerator::default().schema_to_bytes(schema, &write_options);
+    let mut buffer = Vec::new();
+    write_message(&mut buffer, encoded_schema, &write_options)?;
+    Ok(buffer)
+}
+
+/// Encodes the columns as a record batch message of an Arrow IPC stream.
+pub fn encode_record_batch(
+    schema: SchemaRef,
+    columns: Vec<ColumnValues>,
+    buffer: &mut Vec<u8>,
+) -> Result<(), ArrowError> {
+    let arrays: Vec<ArrayRef> = columns.into_iter().map(into_array).collect();
+    let record_batch = RecordBatch::try_new(schema, arrays)?;
+    let write_options = IpcWriteOptions::default();
+    let mut dictionary_tracker = DictionaryTracker::new(false);
+    let (encoded_dictionaries, encoded_record_batch) = IpcDataGenerator::default().encoded_batch(
+        &record_batch,
+        &mut dictionary_tracker,
+        &write_options,
+    )?;
+    buffer.clear();
+    for encoded_dictionary in encoded_dictionaries {
+        write_message(&mut *buffer, encoded_dictionary, &write_options)?;
+    }
+    write_message(&mut *buffer, encoded_record_batch, &write_options)?;
+    Ok(())
+}
+
+fn into_array(column_values: ColumnValues) -> ArrayRef {
+    match column_values {
+        ColumnValues::I64(values) => Arc::new(Int64Array::from(values)),
+        ColumnValues::U64(values) => Arc::new(UInt64Array::from(values)),
+        ColumnValues::F64(values) => Arc::new(Float64Array::from(values)),
+        ColumnValues::Bool(values) => Arc::new(BooleanArray::from(values)),
+        ColumnValues::DateTime(values) => {
+            let timestamps_micros: Vec<Option<i64>> = values
+                .into_iter()
+                .map(|value_opt| value_opt.map(|date_time| date_time.into_timestamp_micros()))
+                .collect();
+            Arc::new(TimestampMicrosecondArray::from(timestamps_micros).with_timezone(UTC_TIMEZONE))
+        }
+        ColumnValues::Str(values) => Arc::new(StringArray::from(values)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::Array;
+    use arrow::ipc::reader::StreamReader;
+    use tantivy::DateTime;
+
+    use super::*;
+
+    #[test]
+    fn test_arrow_ipc_stream_roundtrip() {
+        let stream_columns = vec![
+            StreamColumn {
+                field_name: "ts".to_string(),
+                column_type: StreamColumnType::DateTime,
+            },
+            StreamColumn {
+                field_name: "level".to_string(),
+                column_type: StreamColumnType::Str,
+            },
+            StreamColumn {
+                field_name: "latency".to_string(),
+                column_type: StreamColumnType::F64,
+            },
+        ];
+        let schema = arrow_schema(&stream_columns);
+
+        let mut stream_bytes = encode_schema(&schema).unwrap();
+        for (timestamp_secs, level) in [(1, "info"), (2, "warn")] {
+            let columns = vec![
+                ColumnValues::DateTime(vec![Some(DateTime::from_timestamp_secs(timestamp_secs))]),
+                ColumnValues::Str(vec![Some(level.to_string())]),
+                ColumnValues::F64(vec![None]),
+            ];
+            let mut buffer = Vec::new();
+            encode_record_batch(schema.clone(), columns, &mut buffer).unwrap();
+            stream_bytes.extend(buffer);
+        }
+        stream_bytes.extend(END_OF_STREAM);
+
+        let stream_reader = StreamReader::try_new(&stream_bytes[..], None).unwrap();
+        assert_eq!(stream_reader.schema(), schema);
+        let record_batches: Vec<RecordBatch> = stream_reader
+            .collect::<Result<Vec<RecordBatch>, ArrowError>>()
+            .unwrap();
+        assert_eq!(record_batches.len(), 2);
+
+        let timestamps = record_batches[1]
+            .column(0)
+            .as_any()
+            .downcast_ref::<TimestampMicrosecondArray>()
+            .unwrap();
+        assert_eq!(timestamps.value(0), 2_000_000);
+        let levels = record_batches[1]
+            .column(1)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(levels.value(0), "warn");
+        assert!(record_batches[1].column(2).is_null(0));
+    }
+}
@@ -19,12 +19,14 @@
 
 use std::collections::HashMap;
 use std::hash::Hash;
+use std::io;
 use std::marker::PhantomData;
 
 use tantivy::collector::{Collector, SegmentCollector};
-use tantivy::columnar::{DynamicColumn, HasAssociatedColumnType};
+use tantivy::columnar::{DynamicColumn, HasAssociatedColumnType, StrColumn};
 use tantivy::fastfield::Column;
-use tantivy::{DocId, Score, SegmentOrdinal, SegmentReader};
+use tantivy::schema::Type;
+use tantivy::{DateTime, DocId, Score, SegmentOrdinal, SegmentReader};
 
 use crate::filters::{TimestampFilter, TimestampFilterBuilder};
 
@@ -244,3 +246,247 @@ impl<Item: HasAssociatedColumnType, PartitionItem: HasAssociatedColumnType + Has
         self.fast_field_values
     }
 }
+
+/// Type of the values of a fast field streamed as a column.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum StreamColumnType {
+    I64,
+    U64,
+    F64,
+    Bool,
+    DateTime,
+    Str,
+}
+
+impl StreamColumnType {
+    /// Returns the column type for a fast field of the given type, or `None` if such fast
+    /// fields cannot be streamed as a column.
+    pub fn from_field_type(field_type: Type) -> Option<Self> {
+        match field_type {
+            Type::I64 => Some(StreamColumnType::I64),
+            Type::U64 => Some(StreamColumnType::U64),
+            Type::F64 => Some(StreamColumnType::F64),
+            Type::Bool => Some(StreamColumnType::Bool),
+            Type::Date => Some(StreamColumnType::DateTime),
+            Type::Str => Some(StreamColumnType::Str),
+            Type::Facet | Type::Bytes | Type::Json | Type::IpAddr => None,
+        }
+    }
+}
+
+/// A fast field streamed as a column.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StreamColumn {
+    pub field_name: String,
+    pub column_type: StreamColumnType,
+}
+
+/// Values of a column, one per row. Documents without a value for the fast field have a `None`
+/// value, and only the first value of multivalued fast fields is kept.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ColumnValues {
+    I64(Vec<Option<i64>>),
+    U64(Vec<Option<u64>>),
+    F64(Vec<Option<f64>>),
+    Bool(Vec<Option<bool>>),
+    DateTime(Vec<Option<DateTime>>),
+    Str(Vec<Option<String>>),
+}
+
+impl ColumnValues {
+    fn empty(column_type: StreamColumnType) -> Self {
+        match column_type {
+            StreamColumnType::I64 => ColumnValues::I64(Vec::new()),
+            StreamColumnType::U64 => ColumnValues::U64(Vec::new()),
+            StreamColumnType::F64 => ColumnValues::F64(Vec::new()),
+            StreamColumnType::Bool => ColumnValues::Bool(Vec::new()),
+            StreamColumnType::DateTime => ColumnValues::DateTime(Vec::new()),
+            StreamColumnType::Str => ColumnValues::Str(Vec::new()),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            ColumnValues::I64(values) => values.len(),
+            ColumnValues::U64(values) => values.len(),
+            ColumnValues::F64(values) => values.len(),
+            ColumnValues::Bool(values) => values.len(),
+            ColumnValues::DateTime(values) => values.len(),
+            ColumnValues::Str(values) => values.len(),
+        }
+    }
+
+    fn append(&mut self, other: ColumnValues) {
+        match (self, other) {
+            (ColumnValues::I64(values), ColumnValues::I64(other_values)) => {
+                values.extend(other_values)
+            }
+            (ColumnValues::U64(values), ColumnValues::U64(other_values)) => {
+                values.extend(other_values)
+            }
+            (ColumnValues::F64(values), ColumnValues::F64(other_values)) => {
+                values.extend(other_values)
+            }
+            (ColumnValues::Bool(values), ColumnValues::Bool(other_values)) => {
+                values.extend(other_values)
+            }
+            (ColumnValues::DateTime(values), ColumnValues::DateTime(other_values)) => {
+                values.extend(other_values)
+            }
+            (ColumnValues::Str(values), ColumnValues::Str(other_values)) => {
+                values.extend(other_values)
+            }
+            _ => panic!("columns of different types cannot be appended"),
+        }
+    }
+}
+
+/// Fast field column of a segment.
+enum SegmentColumn {
+    I64(Option<Column<i64>>),
+    U64(Option<Column<u64>>),
+    F64(Option<Column<f64>>),
+    Bool(Option<Column<bool>>),
+    DateTime(Option<Column<DateTime>>),
+    Str(Option<StrColumn>),
+}
+
+impl SegmentColumn {
+    fn open(segment_reader: &SegmentReader, stream_column: &StreamColumn) -> tantivy::Result<Self> {
+        let fast_fields = segment_reader.fast_fields();
+        let field_name = stream_column.field_name.as_str();
+        let segment_column = match stream_column.column_type {
+            StreamColumnType::I64 => SegmentColumn::I64(fast_fields.column_opt(field_name)?),
+            StreamColumnType::U64 => SegmentColumn::U64(fast_fields.column_opt(field_name)?),
+            StreamColumnType::F64 => SegmentColumn::F64(fast_fields.column_opt(field_name)?),
+            StreamColumnType::Bool => SegmentColumn::Bool(fast_fields.column_opt(field_name)?),
+            StreamColumnType::DateTime => {
+                SegmentColumn::DateTime(fast_fields.column_opt(field_name)?)
+            }
+            StreamColumnType::Str => SegmentColumn::Str(fast_fields.str(field_name)?),
+        };
+        Ok(segment_column)
+    }
+
+    fn values(&self, doc_ids: &[DocId]) -> io::Result<ColumnValues> {
+        fn first_values<Item: HasAssociatedColumnType>(
+            column_opt: &Option<Column<Item>>,
+            doc_ids: &[DocId],
+        ) -> Vec<Option<Item>> {
+            match column_opt {
+                Some(column) => doc_ids.iter().map(|doc_id| column.first(*doc_id)).collect(),
+                None => doc_ids.iter().map(|_| None).collect(),
+            }
+        }
+        let column_values = match self {
+            SegmentColumn::I64(column_opt) => ColumnValues::I64(first_values(column_opt, doc_ids)),
+            SegmentColumn::U64(column_opt) => ColumnValues::U64(first_values(column_opt, doc_ids)),
+            SegmentColumn::F64(column_opt) => ColumnValues::F64(first_values(column_opt, doc_ids)),
+            SegmentColumn::Bool(column_opt) => {
+                ColumnValues::Bool(first_values(column_opt, doc_ids))
+            }
+            SegmentColumn::DateTime(column_opt) => {
+                ColumnValues::DateTime(first_values(column_opt, doc_ids))
+            }
+            SegmentColumn::Str(None) => ColumnValues::Str(vec![None; doc_ids.len()]),
+            SegmentColumn::Str(Some(str_column)) => {
+                let mut values = Vec::with_capacity(doc_ids.len());
+                let mut term = String::new();
+                for doc_id in doc_ids {
+                    let value_opt = match str_column.term_ords(*doc_id).next() {
+                        Some(term_ord) if str_column.ord_to_str(term_ord, &mut term)? => {
+                            Some(term.clone())
+                        }
+                        _ => None,
+                    };
+                    values.push(value_opt);
+                }
+                ColumnValues::Str(values)
+            }
+        };
+        Ok(column_values)
+    }
+}
+
+/// Collects the values of several fast fields, row by row.
+#[derive(Clone)]
+pub struct FastFieldRowsCollector {
+    pub columns_to_collect: Vec<StreamColumn>,
+    pub timestamp_filter_builder_opt: Option<TimestampFilterBuilder>,
+}
+
+impl Collector for FastFieldRowsCollector {
+    type Child = FastFieldRowsSegmentCollector;
+    type Fruit = Vec<ColumnValues>;
+
+    fn for_segment(
+        &self,
+        _segment_ord: SegmentOrdinal,
+        segment_reader: &SegmentReader,
+    ) -> tantivy::Result<Self::Child> {
+        let timestamp_filter_opt =
+            if let Some(timestamp_filter_builder) = &self.timestamp_filter_builder_opt {
+                timestamp_filter_builder.build(segment_reader)?
+            } else {
+                None
+            };
+        let segment_columns = self
+            .columns_to_collect
+            .iter()
+            .map(|stream_column| SegmentColumn::open(segment_reader, stream_column))
+            .collect::<tantivy::Result<Vec<_>>>()?;
+        Ok(FastFieldRowsSegmentCollector {
+            doc_ids: Vec::new(),
+            segment_columns,
+            timestamp_filter_opt,
+        })
+    }
+
+    fn requires_scoring(&self) -> bool {
+        // We do not need BM25 scoring in Quickwit.
+        false
+    }
+
+    fn merge_fruits(
+        &self,
+        segment_fruits: Vec<io::Result<Vec<ColumnValues>>>,
+    ) -> tantivy::Result<Self::Fruit> {
+        let mut columns: Vec<ColumnValues> = self
+            .columns_to_collect
+            .iter()
+            .map(|stream_column| ColumnValues::empty(stream_column.column_type))
+            .collect();
+        for segment_fruit in segment_fruits {
+            for (column, segment_column) in columns.iter_mut().zip(segment_fruit?) {
+                column.append(segment_column);
+            }
+        }
+        Ok(columns)
+    }
+}
+
+pub struct FastFieldRowsSegmentCollector {
+    doc_ids: Vec<DocId>,
+    segment_columns: Vec<SegmentColumn>,
+    timestamp_filter_opt: Option<TimestampFilter>,
+}
+
+impl SegmentCollector for FastFieldRowsSegmentCollector {
+    type Fruit = io::Result<Vec<ColumnValues>>;
+
+    fn collect(&mut self, doc_id: DocId, _score: Score) {
+        if let Some(ref timestamp_filter) = self.timestamp_filter_opt {
+            if !timestamp_filter.is_within_range(doc_id) {
+                return;
+            }
+        }
+        self.doc_ids.push(doc_id);
+    }
+
+    fn harvest(self) -> Self::Fruit {
+        self.segment_columns
+            .iter()
+            .map(|segment_column| segment_column.values(&self.doc_ids))
+            .collect()
+    }
+}
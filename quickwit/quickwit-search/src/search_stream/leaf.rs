@@ -38,7 +38,10 @@ use tantivy::{DateTime, ReloadPolicy, Searcher};
 use tokio_stream::wrappers::UnboundedReceiverStream;
 use tracing::*;
 
-use super::collector::{PartionnedFastFieldCollector, PartitionValues};
+use super::collector::{
+    ColumnValues, FastFieldRowsCollector, PartionnedFastFieldCollector, PartitionValues,
+    StreamColumn,
+};
 use super::FastFieldCollector;
 use crate::filters::{create_timestamp_filter_builder, TimestampFilterBuilder};
 use crate::leaf::{open_index_with_caches, rewrite_start_end_time_bounds, warmup};
@@ -156,6 +159,19 @@ async fn leaf_search_stream_single_split(
         ));
     }
 
+    // Single integer or datetime fast fields keep being streamed as a plain list of values in
+    // the CSV and ClickHouse formats. Anything else is streamed row by row.
+    let stream_columns_opt: Option<Vec<StreamColumn>> =
+        if request_fields.is_streamed_as_rows(output_format) {
+            let fast_field_names = super::fast_field_names(&stream_request);
+            Some(super::stream_columns(
+                &doc_mapper.schema(),
+                &fast_field_names,
+            )?)
+        } else {
+            None
+        };
+
     let search_request = Arc::new(SearchRequest::try_from(stream_request.clone())?);
     let query_ast = serde_json::from_str(&search_request.query_ast)
         .map_err(|err| SearchError::InvalidQuery(err.to_string()))?;
@@ -197,6 +213,21 @@ async fn leaf_search_stream_single_split(
     let m_request_fields = request_fields.clone();
     let collect_handle = crate::run_cpu_intensive(move || {
         let mut buffer = Vec::new();
+        if let Some(stream_columns) = stream_columns_opt {
+            let collected_columns = collect_rows(
+                &stream_columns,
+                timestamp_filter_builder_opt,
+                &searcher,
+                &query,
+            )?;
+            super::serialize_rows(
+                &stream_columns,
+                collected_columns,
+                &mut buffer,
+                output_format,
+            )?;
+            return Result::<Vec<u8>>::Ok(buffer);
+        }
         match m_request_fields.fast_field_types() {
             (Type::I64, None) => {
                 let collected_values = collect_values::<i64>(
@@ -311,6 +342,20 @@ where
     Ok(result)
 }
 
+fn collect_rows(
+    stream_columns: &[StreamColumn],
+    timestamp_filter_builder_opt: Option<TimestampFilterBuilder>,
+    searcher: &Searcher,
+    query: &dyn Query,
+) -> crate::Result<Vec<ColumnValues>> {
+    let collector = FastFieldRowsCollector {
+        columns_to_collect: stream_columns.to_vec(),
+        timestamp_filter_builder_opt,
+    };
+    let result = searcher.search(query, &collector)?;
+    Ok(result)
+}
+
 fn collect_partitioned_values<
     Item: HasAssociatedColumnType,
     TPartitionValue: HasAssociatedColumnType + Eq + Hash,
@@ -339,7 +384,7 @@ where
 #[derive(Debug)]
 // TODO move to owned values, implement Send + Sync
 struct SearchStreamRequestFields {
-    fast_field: Field,
+    fast_fields: Vec<Field>,
     partition_by_fast_field: Option<Field>,
     timestamp_field_name: Option<String>,
     schema: Schema,
@@ -347,7 +392,7 @@ struct SearchStreamRequestFields {
 
 impl std::fmt::Display for SearchStreamRequestFields {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "fast_field: {},", self.fast_field_name())?;
+        write!(f, "fast_fields: {},", self.fast_field_names().join(","))?;
         write!(
             f,
             "timestamp_field: {},",
@@ -367,13 +412,16 @@ impl<'a> SearchStreamRequestFields {
         schema: &'a Schema,
         doc_mapper: &dyn DocMapper,
     ) -> crate::Result<SearchStreamRequestFields> {
-        let fast_field = schema.get_field(&stream_request.fast_field)?;
+        let mut fast_fields = Vec::new();
+        for fast_field_name in super::fast_field_names(stream_request) {
+            let fast_field = schema.get_field(fast_field_name)?;
 
-        if !Self::is_fast_field(schema, &fast_field) {
-            return Err(SearchError::InvalidQuery(format!(
-                "field `{}` is not a fast field",
-                &stream_request.fast_field
-            )));
+            if !Self::is_fast_field(schema, &fast_field) {
+                return Err(SearchError::InvalidQuery(format!(
+                    "field `{fast_field_name}` is not a fast field"
+                )));
+            }
+            fast_fields.push(fast_field);
         }
 
         let timestamp_field_name = doc_mapper.timestamp_field_name().map(ToString::to_string);
@@ -391,9 +439,16 @@ impl<'a> SearchStreamRequestFields {
             )));
         }
 
+        if partition_by_fast_field.is_some() && fast_fields.len() > 1 {
+            return Err(SearchError::InvalidQuery(
+                "partitioning by a field is only supported when extracting a single fast field"
+                    .to_string(),
+            ));
+        }
+
         Ok(SearchStreamRequestFields {
             schema: schema.to_owned(),
-            fast_field,
+            fast_fields,
             partition_by_fast_field,
             timestamp_field_name,
         })
@@ -402,7 +457,7 @@ impl<'a> SearchStreamRequestFields {
     pub fn fast_field_types(&self) -> (Type, Option<Type>) {
         (
             self.schema
-                .get_field_entry(self.fast_field)
+                .get_field_entry(self.fast_fields[0])
                 .field_type()
                 .value_type(),
             self.partition_by_fast_field
@@ -415,7 +470,7 @@ impl<'a> SearchStreamRequestFields {
         timestamp_filter_builder_opt: Option<&TimestampFilterBuilder>,
     ) -> HashSet<String> {
         let mut set = HashSet::new();
-        set.insert(self.fast_field_name().to_string());
+        set.extend(self.fast_field_names().into_iter().map(ToString::to_string));
         if let Some(timestamp_filter_builder) = timestamp_filter_builder_opt {
             set.insert(timestamp_filter_builder.timestamp_field_name.clone());
         }
@@ -430,7 +485,34 @@ impl<'a> SearchStreamRequestFields {
     }
 
     pub fn fast_field_name(&self) -> &str {
-        self.schema.get_field_name(self.fast_field)
+        self.schema.get_field_name(self.fast_fields[0])
+    }
+
+    pub fn fast_field_names(&self) -> Vec<&str> {
+        self.fast_fields
+            .iter()
+            .map(|fast_field| self.schema.get_field_name(*fast_field))
+            .collect()
+    }
+
+    /// Returns true if the fast fields should be streamed row by row, rather than as the plain
+    /// list of values of a single integer or datetime fast field.
+    fn is_streamed_as_rows(&self, output_format: OutputFormat) -> bool {
+        if self.partition_by_fast_field.is_some() {
+            return false;
+        }
+        if self.fast_fields.len() > 1 {
+            return true;
+        }
+        let is_plain_list_format = matches!(
+            output_format,
+            OutputFormat::Csv | OutputFormat::ClickHouseRowBinary
+        );
+        let is_plain_list_type = matches!(
+            self.fast_field_types().0,
+            Type::I64 | Type::U64 | Type::Date
+        );
+        !is_plain_list_format || !is_plain_list_type
     }
 
     pub fn partition_by_fast_field_name(&self) -> Option<&str> {
@@ -495,6 +577,7 @@ mod tests {
             start_timestamp: None,
             end_timestamp: Some(end_timestamp),
             fast_field: "ts".to_string(),
+            fast_fields: Vec::new(),
             output_format: 0,
             partition_by_field: None,
         };
@@ -572,6 +655,7 @@ mod tests {
             start_timestamp: None,
             end_timestamp: Some(end_timestamp),
             fast_field: "ts".to_string(),
+            fast_fields: Vec::new(),
             output_format: 0,
             partition_by_field: None,
         };
@@ -603,22 +687,21 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_leaf_search_stream_with_string_fast_field_should_return_proper_error(
+    async fn test_leaf_search_stream_with_bytes_fast_field_should_return_proper_error(
     ) -> anyhow::Result<()> {
-        let index_id = "single-node-simple-string-fast-field";
+        let index_id = "single-node-simple-bytes-fast-field";
         let doc_mapping_yaml = r#"
             field_mappings:
               - name: body
                 type: text
-              - name: app
-                type: text
-                tokenizer: raw
+              - name: payload
+                type: bytes
                 fast: true
         "#;
         let test_sandbox = TestSandbox::create(index_id, doc_mapping_yaml, "{}", &["body"]).await?;
 
         test_sandbox
-            .add_documents(vec![json!({"body": "body", "app": "my-app"})])
+            .add_documents(vec![json!({"body": "info", "payload": "aGVsbG8="})])
             .await?;
 
         let request = SearchStreamRequest {
@@ -627,7 +710,8 @@ mod tests {
             snippet_fields: Vec::new(),
             start_timestamp: None,
             end_timestamp: None,
-            fast_field: "app".to_string(),
+            fast_field: "payload".to_string(),
+            fast_fields: Vec::new(),
             output_format: 0,
             partition_by_field: None,
         };
@@ -651,7 +735,96 @@ mod tests {
         .await;
         let res = single_node_stream.next().await.expect("no leaf result");
         let error_message = res.unwrap_err().to_string();
-        assert!(error_message.contains("search stream does not support fast field of type `Str`"),);
+        assert!(
+            error_message.contains("search stream does not support fast field of type `Bytes`"),
+        );
+        test_sandbox.assert_quit().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_leaf_search_stream_multiple_fast_fields() -> anyhow::Result<()> {
+        let index_id = "single-node-multiple-fast-fields";
+        let doc_mapping_yaml = r#"
+            field_mappings:
+              - name: body
+                type: text
+              - name: ts
+                type: datetime
+                fast: true
+              - name: app
+                type: text
+                tokenizer: raw
+                fast: true
+              - name: latency
+                type: f64
+                fast: true
+              - name: is_error
+                type: bool
+                fast: true
+            timestamp_field: ts
+        "#;
+        let test_sandbox = TestSandbox::create(index_id, doc_mapping_yaml, "{}", &["body"]).await?;
+        test_sandbox
+            .add_documents(vec![
+                json!({"body": "info", "ts": 1, "app": "my-app", "latency": 1.5, "is_error": false}),
+                json!({"body": "info", "ts": 2, "app": "my, \"quoted\" app", "is_error": true}),
+                json!({"body": "debug", "ts": 3, "app": "my-app", "latency": 2.5, "is_error": false}),
+            ])
+            .await?;
+        let splits = test_sandbox
+            .metastore()
+            .list_splits(ListSplitsRequest::try_from_index_uid(test_sandbox.index_uid()).unwrap())
+            .await?
+            .deserialize_splits()?;
+        let splits_offsets: Vec<SplitIdAndFooterOffsets> = splits
+            .into_iter()
+            .map(|split| extract_split_and_footer_offsets(&split.split_metadata))
+            .collect();
+        let searcher_context = Arc::new(SearcherContext::for_test());
+
+        let mut outputs = Vec::new();
+        for output_format in [OutputFormat::Csv, OutputFormat::Ndjson] {
+            let request = SearchStreamRequest {
+                index_id: index_id.to_string(),
+                query_ast: qast_json_helper("info", &["body"]),
+                snippet_fields: Vec::new(),
+                start_timestamp: None,
+                end_timestamp: None,
+                fast_field: String::new(),
+                fast_fields: vec![
+                    "ts".to_string(),
+                    "app".to_string(),
+                    "latency".to_string(),
+                    "is_error".to_string(),
+                ],
+                output_format: output_format as i32,
+                partition_by_field: None,
+            };
+            let mut single_node_stream = leaf_search_stream(
+                searcher_context.clone(),
+                request,
+                test_sandbox.storage(),
+                splits_offsets.clone(),
+                test_sandbox.doc_mapper(),
+            )
+            .await;
+            let res = single_node_stream.next().await.expect("no leaf result")?;
+            let output = String::from_utf8(res.data)?;
+            outputs.push(output.lines().sorted().join("\n"));
+        }
+        assert_eq!(
+            outputs[0],
+            "1000000,my-app,1.5,false\n2000000,\"my, \"\"quoted\"\" app\",,true"
+        );
+        assert_eq!(
+            outputs[1],
+            concat!(
+                r#"{"app":"my, \"quoted\" app","is_error":true,"latency":null,"ts":"1970-01-01T00:00:02Z"}"#,
+                "\n",
+                r#"{"app":"my-app","is_error":false,"latency":1.5,"ts":"1970-01-01T00:00:01Z"}"#,
+            )
+        );
         test_sandbox.assert_quit().await;
         Ok(())
     }
@@ -717,6 +890,7 @@ mod tests {
             start_timestamp: None,
             end_timestamp: Some(end_timestamp),
             fast_field: "fast_field".to_string(),
+            fast_fields: Vec::new(),
             output_format: 1,
             partition_by_field: Some(String::from("partition_by_fast_field")),
         };
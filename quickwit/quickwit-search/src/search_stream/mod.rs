@@ -17,6 +17,7 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
+mod arrow_ipc;
 mod collector;
 mod leaf;
 mod root;
@@ -27,11 +28,56 @@ use std::io::Write;
 
 pub use collector::FastFieldCollector;
 pub use leaf::leaf_search_stream;
-use quickwit_proto::search::OutputFormat;
+use quickwit_proto::search::{OutputFormat, SearchStreamRequest};
 pub use root::root_search_stream;
+use serde_json::{Map as JsonMap, Value as JsonValue};
 use tantivy::columnar::MonotonicallyMappableToU64;
+use tantivy::schema::Schema;
+use tantivy::time::format_description::well_known::Rfc3339;
 
-use self::collector::PartitionValues;
+use self::collector::{ColumnValues, PartitionValues, StreamColumn, StreamColumnType};
+use crate::SearchError;
+
+/// Returns the names of the fast fields to extract for a search stream request.
+pub(crate) fn fast_field_names(stream_request: &SearchStreamRequest) -> Vec<&str> {
+    if stream_request.fast_fields.is_empty() {
+        vec![stream_request.fast_field.as_str()]
+    } else {
+        stream_request
+            .fast_fields
+            .iter()
+            .map(String::as_str)
+            .collect()
+    }
+}
+
+/// Resolves the columns to stream, checking that the fast fields exist and can be streamed.
+pub(crate) fn stream_columns(
+    schema: &Schema,
+    fast_field_names: &[&str],
+) -> crate::Result<Vec<StreamColumn>> {
+    let mut stream_columns = Vec::with_capacity(fast_field_names.len());
+    for fast_field_name in fast_field_names {
+        let field = schema.get_field(fast_field_name)?;
+        let field_entry = schema.get_field_entry(field);
+        if !field_entry.is_fast() {
+            return Err(SearchError::InvalidQuery(format!(
+                "field `{fast_field_name}` is not a fast field"
+            )));
+        }
+        let field_type = field_entry.field_type().value_type();
+        let column_type = StreamColumnType::from_field_type(field_type).ok_or_else(|| {
+            SearchError::InvalidQuery(format!(
+                "search stream does not support fast field of type `{field_type:?}`"
+            ))
+        })?;
+        stream_columns.push(StreamColumn {
+            field_name: fast_field_name.to_string(),
+            column_type,
+        });
+    }
+    Ok(stream_columns)
+}
 
 pub trait ToLittleEndian {
     fn to_le_bytes(&self) -> [u8; 8];
@@ -66,6 +112,10 @@ pub fn serialize<T: ToLittleEndian + Display>(
     match format {
         OutputFormat::Csv => serialize_csv(values, buffer),
         OutputFormat::ClickHouseRowBinary => serialize_click_house_row_binary(values, buffer),
+        OutputFormat::Ndjson | OutputFormat::ArrowIpc => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "output format requires serializing rows",
+        )),
     }
 }
 
@@ -92,6 +142,191 @@ pub fn serialize_partitions<
     Ok(())
 }
 
+/// Serialize the rows made of the values of the `columns` into the `buffer` as bytes.
+///
+/// Please note that the `buffer` is always cleared.
+pub(crate) fn serialize_rows(
+    stream_columns: &[StreamColumn],
+    columns: Vec<ColumnValues>,
+    buffer: &mut Vec<u8>,
+    format: OutputFormat,
+) -> crate::Result<()> {
+    buffer.clear();
+    let num_rows = columns.first().map(ColumnValues::len).unwrap_or(0);
+    match format {
+        OutputFormat::Csv => serialize_rows_csv(&columns, num_rows, buffer)
+            .map_err(|_| SearchError::Internal("error when serializing CSV rows".to_string())),
+        OutputFormat::ClickHouseRowBinary => {
+            serialize_rows_click_house_row_binary(&columns, num_rows, buffer);
+            Ok(())
+        }
+        OutputFormat::Ndjson => serialize_rows_ndjson(stream_columns, &columns, num_rows, buffer),
+        OutputFormat::ArrowIpc => {
+            let schema = arrow_ipc::arrow_schema(stream_columns);
+            arrow_ipc::encode_record_batch(schema, columns, buffer).map_err(|error| {
+                SearchError::Internal(format!(
+                    "error when serializing Arrow record batch: {error}"
+                ))
+            })
+        }
+    }
+}
+
+/// Serializes rows as CSV. Missing values are serialized as empty fields and datetimes as
+/// microsecond timestamps.
+fn serialize_rows_csv(
+    columns: &[ColumnValues],
+    num_rows: usize,
+    buffer: &mut Vec<u8>,
+) -> io::Result<()> {
+    for row in 0..num_rows {
+        for (column_ord, column) in columns.iter().enumerate() {
+            if column_ord > 0 {
+                buffer.push(b',');
+            }
+            match column {
+                ColumnValues::I64(values) => write_opt(&values[row], buffer)?,
+                ColumnValues::U64(values) => write_opt(&values[row], buffer)?,
+                ColumnValues::F64(values) => write_opt(&values[row], buffer)?,
+                ColumnValues::Bool(values) => write_opt(&values[row], buffer)?,
+                ColumnValues::DateTime(values) => write_opt(
+                    &values[row].map(|date_time| date_time.into_timestamp_micros()),
+                    buffer,
+                )?,
+                ColumnValues::Str(values) => {
+                    if let Some(value) = &values[row] {
+                        write_csv_string(value, buffer);
+                    }
+                }
+            }
+        }
+        buffer.push(b'\n');
+    }
+    Ok(())
+}
+
+fn write_opt<T: Display>(value_opt: &Option<T>, buffer: &mut Vec<u8>) -> io::Result<()> {
+    if let Some(value) = value_opt {
+        write!(buffer, "{value}")?;
+    }
+    Ok(())
+}
+
+/// Writes a CSV field, quoting it if necessary as specified in RFC 4180.
+fn write_csv_string(value: &str, buffer: &mut Vec<u8>) {
+    if !value.contains([',', '"', '\n', '\r']) {
+        buffer.extend_from_slice(value.as_bytes());
+        return;
+    }
+    buffer.push(b'"');
+    for byte in value.bytes() {
+        if byte == b'"' {
+            buffer.push(b'"');
+        }
+        buffer.push(byte);
+    }
+    buffer.push(b'"');
+}
+
+/// Serializes rows in ClickHouse binary format. Each column is serialized as `Nullable`:
+/// a byte flags missing values, and present values follow it. Datetimes are serialized as
+/// `DateTime64(6)` and texts as `String`.
+fn serialize_rows_click_house_row_binary(
+    columns: &[ColumnValues],
+    num_rows: usize,
+    buffer: &mut Vec<u8>,
+) {
+    fn write_nullable<'a, T>(value_opt: &'a Option<T>, buffer: &mut Vec<u8>) -> Option<&'a T> {
+        buffer.push(u8::from(value_opt.is_none()));
+        value_opt.as_ref()
+    }
+    for row in 0..num_rows {
+        for column in columns {
+            match column {
+                ColumnValues::I64(values) => {
+                    if let Some(value) = write_nullable(&values[row], buffer) {
+                        buffer.extend(value.to_le_bytes());
+                    }
+                }
+                ColumnValues::U64(values) => {
+                    if let Some(value) = write_nullable(&values[row], buffer) {
+                        buffer.extend(value.to_le_bytes());
+                    }
+                }
+                ColumnValues::F64(values) => {
+                    if let Some(value) = write_nullable(&values[row], buffer) {
+                        buffer.extend(value.to_le_bytes());
+                    }
+                }
+                ColumnValues::Bool(values) => {
+                    if let Some(value) = write_nullable(&values[row], buffer) {
+                        buffer.push(u8::from(*value));
+                    }
+                }
+                ColumnValues::DateTime(values) => {
+                    if let Some(value) = write_nullable(&values[row], buffer) {
+                        buffer.extend(value.into_timestamp_micros().to_le_bytes());
+                    }
+                }
+                ColumnValues::Str(values) => {
+                    if let Some(value) = write_nullable(&values[row], buffer) {
+                        write_leb128(value.len() as u64, buffer);
+                        buffer.extend_from_slice(value.as_bytes());
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn write_leb128(mut value: u64, buffer: &mut Vec<u8>) {
+    loop {
+        let byte = (value & 0x7F) as u8;
+        value >>= 7;
+        if value == 0 {
+            buffer.push(byte);
+            return;
+        }
+        buffer.push(byte | 0x80);
+    }
+}
+
+/// Serializes rows as newline delimited JSON objects. Missing values are serialized as `null`
+/// and datetimes as RFC 3339 strings.
+fn serialize_rows_ndjson(
+    stream_columns: &[StreamColumn],
+    columns: &[ColumnValues],
+    num_rows: usize,
+    buffer: &mut Vec<u8>,
+) -> crate::Result<()> {
+    for row in 0..num_rows {
+        let mut json_row = JsonMap::with_capacity(columns.len());
+        for (stream_column, column) in stream_columns.iter().zip(columns) {
+            let json_value = match column {
+                ColumnValues::I64(values) => values[row].map(JsonValue::from),
+                ColumnValues::U64(values) => values[row].map(JsonValue::from),
+                ColumnValues::F64(values) => values[row].map(JsonValue::from),
+                ColumnValues::Bool(values) => values[row].map(JsonValue::from),
+                ColumnValues::DateTime(values) => values[row]
+                    .map(|date_time| date_time.into_utc().format(&Rfc3339))
+                    .transpose()
+                    .map_err(|error| {
+                        SearchError::Internal(format!("failed to format datetime: {error}"))
+                    })?
+                    .map(JsonValue::String),
+                ColumnValues::Str(values) => values[row].clone().map(JsonValue::String),
+            };
+            json_row.insert(
+                stream_column.field_name.clone(),
+                json_value.unwrap_or(JsonValue::Null),
+            );
+        }
+        serde_json::to_writer(&mut *buffer, &json_row)?;
+        buffer.push(b'\n');
+    }
+    Ok(())
+}
+
 fn serialize_csv<T: Display>(values: &[T], buffer: &mut Vec<u8>) -> io::Result<()> {
     buffer.clear();
     for value in values {
@@ -138,8 +373,42 @@ mod helpers {
 
 #[cfg(test)]
 mod tests {
-    use crate::search_stream::collector::PartitionValues;
-    use crate::search_stream::{serialize_click_house_row_binary, serialize_csv};
+    use quickwit_proto::search::OutputFormat;
+    use tantivy::DateTime;
+
+    use crate::search_stream::collector::{
+        ColumnValues, PartitionValues, StreamColumn, StreamColumnType,
+    };
+    use crate::search_stream::{serialize_click_house_row_binary, serialize_csv, serialize_rows};
+
+    fn test_rows() -> (Vec<StreamColumn>, Vec<ColumnValues>) {
+        let stream_columns = vec![
+            StreamColumn {
+                field_name: "ts".to_string(),
+                column_type: StreamColumnType::DateTime,
+            },
+            StreamColumn {
+                field_name: "app".to_string(),
+                column_type: StreamColumnType::Str,
+            },
+            StreamColumn {
+                field_name: "count".to_string(),
+                column_type: StreamColumnType::U64,
+            },
+        ];
+        let columns = vec![
+            ColumnValues::DateTime(vec![
+                Some(DateTime::from_timestamp_secs(1)),
+                Some(DateTime::from_timestamp_secs(2)),
+            ]),
+            ColumnValues::Str(vec![
+                Some("my-app".to_string()),
+                Some("a,\"b\"".to_string()),
+            ]),
+            ColumnValues::U64(vec![Some(3), None]),
+        ];
+        (stream_columns, columns)
+    }
 
     #[test]
     fn test_serialize_row_binary() {
@@ -159,6 +428,72 @@ mod tests {
         assert_eq!(buffer, "-10\n".as_bytes());
     }
 
+    #[test]
+    fn test_serialize_rows_csv() {
+        let (stream_columns, columns) = test_rows();
+        let mut buffer = Vec::new();
+        serialize_rows(&stream_columns, columns, &mut buffer, OutputFormat::Csv).unwrap();
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "1000000,my-app,3\n2000000,\"a,\"\"b\"\"\",\n"
+        );
+    }
+
+    #[test]
+    fn test_serialize_rows_ndjson() {
+        let (stream_columns, columns) = test_rows();
+        let mut buffer = Vec::new();
+        serialize_rows(&stream_columns, columns, &mut buffer, OutputFormat::Ndjson).unwrap();
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            concat!(
+                r#"{"app":"my-app","count":3,"ts":"1970-01-01T00:00:01Z"}"#,
+                "\n",
+                r#"{"app":"a,\"b\"","count":null,"ts":"1970-01-01T00:00:02Z"}"#,
+                "\n"
+            )
+        );
+    }
+
+    #[test]
+    fn test_serialize_rows_click_house_row_binary() {
+        let (stream_columns, columns) = test_rows();
+        let mut buffer = Vec::new();
+        serialize_rows(
+            &stream_columns,
+            columns,
+            &mut buffer,
+            OutputFormat::ClickHouseRowBinary,
+        )
+        .unwrap();
+        let expected_buffer: Vec<u8> = [
+            &[0u8][..],
+            &1_000_000i64.to_le_bytes(),
+            &[0u8, 6u8],
+            b"my-app",
+            &[0u8],
+            &3u64.to_le_bytes(),
+            &[0u8],
+            &2_000_000i64.to_le_bytes(),
+            &[0u8, 5u8],
+            b"a,\"b\"",
+            &[1u8],
+        ]
+        .concat();
+        assert_eq!(buffer, expected_buffer);
+    }
+
+    #[test]
+    fn test_write_leb128() {
+        let mut buffer = Vec::new();
+        super::write_leb128(127, &mut buffer);
+        assert_eq!(buffer, [0x7F]);
+
+        let mut buffer = Vec::new();
+        super::write_leb128(300, &mut buffer);
+        assert_eq!(buffer, [0xAC, 0x02]);
+    }
+
     #[test]
     fn test_serialize_partitions() {
         let mut buffer = Vec::new();
@@ -26,11 +26,14 @@ use quickwit_config::build_doc_mapper;
 use quickwit_doc_mapper::tag_pruning::extract_tags_from_query;
 use quickwit_metastore::IndexMetadataResponseExt;
 use quickwit_proto::metastore::{IndexMetadataRequest, MetastoreService, MetastoreServiceClient};
-use quickwit_proto::search::{LeafSearchStreamRequest, SearchRequest, SearchStreamRequest};
+use quickwit_proto::search::{
+    LeafSearchStreamRequest, OutputFormat, SearchRequest, SearchStreamRequest,
+};
 use quickwit_query::query_ast::QueryAst;
 use tokio_stream::StreamMap;
 use tracing::*;
 
+use super::arrow_ipc;
 use crate::cluster_client::ClusterClient;
 use crate::root::{refine_start_end_timestamp_from_ast, SearchJob};
 use crate::{list_relevant_splits, SearchError};
@@ -76,6 +79,26 @@ pub async fn root_search_stream(
     doc_mapper.query(doc_mapper.schema(), &query_ast_resolved, true)?;
     search_stream_request.query_ast = serde_json::to_string(&query_ast_resolved)?;
 
+    // The leaves only stream record batches: the Arrow IPC stream schema and end-of-stream
+    // marker are emitted here.
+    let (arrow_ipc_header_opt, arrow_ipc_footer_opt): (
+        Option<crate::Result<Bytes>>,
+        Option<crate::Result<Bytes>>,
+    ) = if search_stream_request.output_format == OutputFormat::ArrowIpc as i32 {
+        let fast_field_names = super::fast_field_names(&search_stream_request);
+        let stream_columns = super::stream_columns(&doc_mapper.schema(), &fast_field_names)?;
+        let arrow_schema = arrow_ipc::arrow_schema(&stream_columns);
+        let arrow_ipc_header = arrow_ipc::encode_schema(&arrow_schema).map_err(|error| {
+            SearchError::Internal(format!("failed to serialize Arrow schema: {error}"))
+        })?;
+        (
+            Some(Ok(Bytes::from(arrow_ipc_header))),
+            Some(Ok(Bytes::from_static(&arrow_ipc::END_OF_STREAM))),
+        )
+    } else {
+        (None, None)
+    };
+
     let search_request = SearchRequest::try_from(search_stream_request.clone())?;
     let split_metadatas = list_relevant_splits(
         vec![index_uid],
@@ -110,9 +133,12 @@ pub async fn root_search_stream(
             .await;
         stream_map.insert(leaf_ord, leaf_stream);
     }
-    Ok(stream_map
+    let leaf_stream = stream_map
         .map(|(_leaf_ord, result)| result)
-        .map_ok(|leaf_response| Bytes::from(leaf_response.data)))
+        .map_ok(|leaf_response| Bytes::from(leaf_response.data));
+    Ok(futures::stream::iter(arrow_ipc_header_opt)
+        .chain(leaf_stream)
+        .chain(futures::stream::iter(arrow_ipc_footer_opt)))
 }
 
 fn jobs_to_leaf_request(
@@ -140,6 +166,7 @@ mod tests {
     use tokio_stream::wrappers::UnboundedReceiverStream;
 
     use super::*;
+    use crate::search_stream::collector::{StreamColumn, StreamColumnType};
     use crate::{searcher_pool_for_test, MockSearchService, SearchJobPlacer};
 
     #[tokio::test]
@@ -198,6 +225,73 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_root_search_stream_arrow_ipc() -> anyhow::Result<()> {
+        let request = quickwit_proto::search::SearchStreamRequest {
+            index_id: "test-index".to_string(),
+            query_ast: qast_json_helper("test", &["body"]),
+            fast_fields: vec!["timestamp".to_string(), "response_time".to_string()],
+            output_format: OutputFormat::ArrowIpc as i32,
+            ..Default::default()
+        };
+        let mut mock_metastore = MetastoreServiceClient::mock();
+        let index_metadata = IndexMetadata::for_test("test-index", "ram:///test-index");
+        let index_uid = index_metadata.index_uid.clone();
+        mock_metastore.expect_index_metadata().returning(move |_| {
+            Ok(IndexMetadataResponse::try_from_index_metadata(index_metadata.clone()).unwrap())
+        });
+        mock_metastore.expect_list_splits().returning(move |_| {
+            let splits = vec![MockSplitBuilder::new("split1")
+                .with_index_uid(&index_uid)
+                .build()];
+            Ok(ListSplitsResponse::try_from_splits(splits).unwrap())
+        });
+        let mut mock_search_service = MockSearchService::new();
+        let (result_sender, result_receiver) = tokio::sync::mpsc::unbounded_channel();
+        result_sender.send(Ok(quickwit_proto::search::LeafSearchStreamResponse {
+            data: b"record batch".to_vec(),
+            split_id: "split_1".to_string(),
+        }))?;
+        mock_search_service.expect_leaf_search_stream().return_once(
+            |_leaf_search_req: quickwit_proto::search::LeafSearchStreamRequest| {
+                Ok(UnboundedReceiverStream::new(result_receiver))
+            },
+        );
+        // The test will hang on indefinitely if we don't drop the receiver.
+        drop(result_sender);
+
+        let searcher_pool = searcher_pool_for_test([("127.0.0.1:1001", mock_search_service)]);
+        let search_job_placer = SearchJobPlacer::new(searcher_pool);
+        let cluster_client = ClusterClient::new(search_job_placer.clone());
+        let result: Vec<Bytes> = root_search_stream(
+            request,
+            MetastoreServiceClient::from(mock_metastore),
+            cluster_client,
+        )
+        .await?
+        .try_collect()
+        .await?;
+        assert_eq!(result.len(), 3);
+
+        let expected_arrow_schema = arrow_ipc::arrow_schema(&[
+            StreamColumn {
+                field_name: "timestamp".to_string(),
+                column_type: StreamColumnType::DateTime,
+            },
+            StreamColumn {
+                field_name: "response_time".to_string(),
+                column_type: StreamColumnType::F64,
+            },
+        ]);
+        assert_eq!(
+            &result[0],
+            &arrow_ipc::encode_schema(&expected_arrow_schema)?[..]
+        );
+        assert_eq!(&result[1], &b"record batch"[..]);
+        assert_eq!(&result[2], &arrow_ipc::END_OF_STREAM[..]);
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_root_search_stream_single_split_partitionned() -> anyhow::Result<()> {
         let request = quickwit_proto::search::SearchStreamRequest {
@@ -29,11 +29,12 @@ use quickwit_config::SearcherConfig;
 use quickwit_doc_mapper::DocMapper;
 use quickwit_proto::metastore::MetastoreServiceClient;
 use quickwit_proto::search::{
-    FetchDocsRequest, FetchDocsResponse, GetKvRequest, Hit, LeafListTermsRequest,
-    LeafListTermsResponse, LeafSearchRequest, LeafSearchResponse, LeafSearchStreamRequest,
-    LeafSearchStreamResponse, ListTermsRequest, ListTermsResponse, PutKvRequest,
-    ReportSplitsRequest, ReportSplitsResponse, ScrollRequest, SearchRequest, SearchResponse,
-    SearchStreamRequest, SnippetRequest,
+    FetchDocsRequest, FetchDocsResponse, GetKvRequest, Hit, LeafListFieldsRequest,
+    LeafListFieldsResponse, LeafListTermsRequest, LeafListTermsResponse, LeafSearchRequest,
+    LeafSearchResponse, LeafSearchStreamRequest, LeafSearchStreamResponse, ListFieldsRequest,
+    ListFieldsResponse, ListTermsRequest, ListTermsResponse, PutKvRequest, ReportSplitsRequest,
+    ReportSplitsResponse, ScrollRequest, SearchRequest, SearchResponse, SearchStreamRequest,
+    SnippetRequest,
 };
 use quickwit_storage::{
     MemorySizedCache, QuickwitCache, SplitCache, StorageCache, StorageResolver,
@@ -42,15 +43,23 @@ use tantivy::aggregation::AggregationLimits;
 use tokio::sync::Semaphore;
 use tokio_stream::wrappers::UnboundedReceiverStream;
 
-use crate::leaf_cache::LeafSearchCache;
+use crate::async_search::{
+    cancel_async_search, get_async_search, is_async_search_key, submit_async_search,
+    AsyncSearchStatus, AsyncSearchStore, SubmitAsyncSearchRequest,
+};
+use crate::leaf_cache::{LeafSearchCache, ListFieldsCache};
 use crate::root::{fetch_docs_phase, get_snippet_request};
 use crate::scroll_context::{MiniKV, ScrollContext, ScrollKeyAndStartOffset};
 use crate::search_stream::{leaf_search_stream, root_search_stream};
 use crate::{
-    fetch_docs, leaf_list_terms, leaf_search, root_list_terms, root_search, ClusterClient,
-    SearchError,
+    fetch_docs, leaf_list_fields, leaf_list_terms, leaf_search, root_list_fields, root_list_terms,
+    root_search, ClusterClient, SearchError,
 };
 
+/// Capacity of the cache of the fields listed per split. Split fields lists are small, so this is
+/// enough to cache the fields of tens of thousands of splits.
+const LIST_FIELDS_CACHE_CAPACITY: usize = 16 * 1024 * 1024;
+
 #[derive(Clone)]
 /// The search service implementation.
 pub struct SearchServiceImpl {
@@ -59,6 +68,7 @@ pub struct SearchServiceImpl {
     cluster_client: ClusterClient,
     searcher_context: Arc<SearcherContext>,
     search_after_cache: MiniKV,
+    async_search_store: AsyncSearchStore,
 }
 
 /// Trait representing a search service.
@@ -121,6 +131,20 @@ pub trait SearchService: 'static + Send + Sync {
         request: LeafListTermsRequest,
     ) -> crate::Result<LeafListTermsResponse>;
 
+    /// Root list fields API.
+    /// This RPC lists the fields of the doc mappings of the targeted indexes, and dispatches the
+    /// multiple calls to `LeafListFields` to discover the fields of JSON objects.
+    async fn root_list_fields(
+        &self,
+        request: ListFieldsRequest,
+    ) -> crate::Result<ListFieldsResponse>;
+
+    /// Lists the fields of the JSON objects indexed in a given set of splits.
+    async fn leaf_list_fields(
+        &self,
+        request: LeafListFieldsRequest,
+    ) -> crate::Result<LeafListFieldsResponse>;
+
     /// Performs a scroll request.
     async fn scroll(&self, scroll_request: ScrollRequest) -> crate::Result<SearchResponse>;
 
@@ -133,6 +157,24 @@ pub trait SearchService: 'static + Send + Sync {
     /// See also `put_kv(..)`.
     async fn get_kv(&self, get_kv: GetKvRequest) -> Option<Vec<u8>>;
 
+    /// Submits a root search running in the background on this node.
+    ///
+    /// Returns the status of the search once it completes, or once the wait for completion
+    /// timeout is reached.
+    async fn submit_async_search(
+        &self,
+        submit_request: SubmitAsyncSearchRequest,
+    ) -> crate::Result<AsyncSearchStatus>;
+
+    /// Returns the status of an asynchronous search, possibly submitted to another node.
+    async fn get_async_search(&self, async_search_id: String) -> crate::Result<AsyncSearchStatus>;
+
+    /// Cancels an asynchronous search, possibly submitted to another node, and drops its results.
+    async fn cancel_async_search(
+        &self,
+        async_search_id: String,
+    ) -> crate::Result<AsyncSearchStatus>;
+
     /// Indexers call report_splits to inform searchers node about the presence of a split, which
     /// would then be considered as a candidate for the searcher split cache.
     async fn report_splits(&self, report_splits: ReportSplitsRequest) -> ReportSplitsResponse;
@@ -152,6 +194,7 @@ impl SearchServiceImpl {
             cluster_client,
             searcher_context,
             search_after_cache: MiniKV::default(),
+            async_search_store: AsyncSearchStore::default(),
         }
     }
 }
@@ -216,6 +259,7 @@ impl SearchService for SearchServiceImpl {
             &fetch_docs_request.split_offsets,
             doc_mapper,
             snippet_request_opt,
+            &fetch_docs_request.fetch_fields,
         )
         .await?;
 
@@ -292,22 +336,81 @@ impl SearchService for SearchServiceImpl {
         Ok(leaf_search_response)
     }
 
+    async fn root_list_fields(
+        &self,
+        list_fields_request: ListFieldsRequest,
+    ) -> crate::Result<ListFieldsResponse> {
+        root_list_fields(
+            list_fields_request,
+            self.metastore.clone(),
+            &self.cluster_client,
+        )
+        .await
+    }
+
+    async fn leaf_list_fields(
+        &self,
+        leaf_list_fields_request: LeafListFieldsRequest,
+    ) -> crate::Result<LeafListFieldsResponse> {
+        let index_uri = Uri::from_str(&leaf_list_fields_request.index_uri)?;
+        let storage = self.storage_resolver.resolve(&index_uri).await?;
+        leaf_list_fields(
+            self.searcher_context.clone(),
+            leaf_list_fields_request,
+            storage,
+        )
+        .await
+    }
+
     async fn scroll(&self, scroll_request: ScrollRequest) -> crate::Result<SearchResponse> {
         scroll(scroll_request, &self.cluster_client, &self.searcher_context).await
     }
 
     async fn put_kv(&self, put_request: PutKvRequest) {
         let ttl = Duration::from_secs(put_request.ttl_secs as u64);
+
+        if is_async_search_key(&put_request.key) {
+            self.async_search_store
+                .put(put_request.key, put_request.payload, ttl);
+            return;
+        }
         self.search_after_cache
             .put(put_request.key, put_request.payload, ttl)
             .await;
     }
 
     async fn get_kv(&self, get_request: GetKvRequest) -> Option<Vec<u8>> {
+        if is_async_search_key(&get_request.key) {
+            return self.async_search_store.get(&get_request.key);
+        }
         let payload: Vec<u8> = self.search_after_cache.get(&get_request.key).await?;
         Some(payload)
     }
 
+    async fn submit_async_search(
+        &self,
+        submit_request: SubmitAsyncSearchRequest,
+    ) -> crate::Result<AsyncSearchStatus> {
+        submit_async_search(
+            submit_request,
+            self.searcher_context.clone(),
+            self.metastore.clone(),
+            self.cluster_client.clone(),
+        )
+        .await
+    }
+
+    async fn get_async_search(&self, async_search_id: String) -> crate::Result<AsyncSearchStatus> {
+        get_async_search(&async_search_id, &self.cluster_client).await
+    }
+
+    async fn cancel_async_search(
+        &self,
+        async_search_id: String,
+    ) -> crate::Result<AsyncSearchStatus> {
+        cancel_async_search(&async_search_id, &self.cluster_client).await
+    }
+
     async fn report_splits(&self, report_splits: ReportSplitsRequest) -> ReportSplitsResponse {
         if let Some(split_cache) = self.searcher_context.split_cache_opt.as_ref() {
             split_cache.report_splits(report_splits.report_splits);
@@ -374,6 +477,7 @@ pub(crate) async fn scroll(
         &partial_hits[..],
         &scroll_context.split_metadatas[..],
         snippet_request,
+        &scroll_context.search_request.fetch_fields,
         cluster_client,
     )
     .await?;
@@ -387,6 +491,8 @@ pub(crate) async fn scroll(
         scroll_id: next_scroll_id.as_ref().map(ToString::to_string),
         errors: Vec::new(),
         aggregation: None,
+        timed_out: false,
+        profile: None,
     })
 }
 /// [`SearcherContext`] provides a common set of variables
@@ -405,6 +511,8 @@ pub struct SearcherContext {
     pub split_stream_semaphore: Semaphore,
     /// Recent sub-query cache.
     pub leaf_search_cache: LeafSearchCache,
+    /// Fields of the JSON objects indexed in each split.
+    pub list_fields_cache: ListFieldsCache,
     /// Search split cache. `None` if no split cache is configured.
     pub split_cache_opt: Option<Arc<SplitCache>>,
 }
@@ -447,6 +555,7 @@ impl SearcherContext {
         let leaf_search_cache = LeafSearchCache::new(
             searcher_config.partial_request_cache_capacity.get_bytes() as usize,
         );
+        let list_fields_cache = ListFieldsCache::new(LIST_FIELDS_CACHE_CAPACITY);
 
         Self {
             searcher_config,
@@ -455,6 +564,7 @@ impl SearcherContext {
             split_footer_cache: global_split_footer_cache,
             split_stream_semaphore,
             leaf_search_cache,
+            list_fields_cache,
             split_cache_opt,
         }
     }
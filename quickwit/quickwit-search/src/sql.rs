@@ -0,0 +1,1013 @@
+// Copyright (C) 2023 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Compiles SQL queries into search requests and shapes their responses into tables.
+
+use std::cmp::Ordering;
+use std::collections::HashSet;
+
+use quickwit_proto::search::{SearchRequest, SearchResponse, SortField, SortOrder};
+use quickwit_query::sql::{parse_sql, SqlAggregateFunction, SqlExpr, SqlProjection, SqlSelect};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map as JsonMap, Value as JsonValue};
+
+use crate::{Result, SearchError};
+
+/// Number of rows returned by a SQL query without a `LIMIT` clause.
+pub const DEFAULT_SQL_LIMIT: u64 = 100;
+
+/// Maximum number of buckets returned for each grouping key.
+const MAX_NUM_GROUPS_PER_KEY: u32 = 10_000;
+
+/// Column of a [`SqlResponse`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, utoipa::ToSchema)]
+pub struct SqlColumn {
+    /// Name of the column: its alias, or the selected expression.
+    pub name: String,
+}
+
+/// Tabular result of a SQL query.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, utoipa::ToSchema)]
+pub struct SqlResponse {
+    /// Columns of the table.
+    pub columns: Vec<SqlColumn>,
+    /// Rows of the table, holding one value per column.
+    #[schema(value_type = Vec<Vec<Object>>)]
+    pub rows: Vec<Vec<JsonValue>>,
+}
+
+impl SqlResponse {
+    /// Formats the table as CSV, with a header row holding the column names.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::new();
+        let header = self
+            .columns
+            .iter()
+            .map(|column| escape_csv_field(&column.name))
+            .collect::<Vec<_>>()
+            .join(",");
+        csv.push_str(&header);
+        csv.push('\n');
+
+        for row in &self.rows {
+            let line = row
+                .iter()
+                .map(|value| {
+                    let field = match value {
+                        JsonValue::Null => String::new(),
+                        JsonValue::String(string) => string.clone(),
+                        _ => value.to_string(),
+                    };
+                    escape_csv_field(&field)
+                })
+                .collect::<Vec<_>>()
+                .join(",");
+            csv.push_str(&line);
+            csv.push('\n');
+        }
+        csv
+    }
+}
+
+fn escape_csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        return format!("\"{}\"", field.replace('"', "\"\""));
+    }
+    field.to_string()
+}
+
+/// Where the values of a column come from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ColumnSource {
+    /// All the top-level fields of the documents, expanded into as many columns.
+    Wildcard,
+    /// Field of the documents.
+    Field(String),
+    /// Key of the buckets of the n-th grouping key.
+    GroupKey(usize),
+    /// Number of documents in the group, or overall if there is no grouping key.
+    DocCount,
+    /// Value of the metric aggregation with the given name.
+    Metric(String),
+}
+
+/// A SQL query compiled into a search request, along with what is needed to turn the search
+/// response into a table.
+///
+/// Queries without aggregates or `GROUP BY` clause return the matching documents, ordered with
+/// the search request's sort fields. Others run a tantivy aggregation: grouping keys become
+/// nested `terms` or `date_histogram` aggregations, and aggregate functions metric
+/// aggregations. Groups are ordered and limited once the aggregation results are collected,
+/// except for a single `terms` grouping key, whose aggregation directly returns the top groups.
+#[derive(Debug)]
+pub struct SqlPlan {
+    search_request: SearchRequest,
+    column_names: Vec<String>,
+    column_sources: Vec<ColumnSource>,
+    is_aggregation: bool,
+    group_keys: Vec<SqlExpr>,
+    /// Columns groups are ordered by, and whether the order is descending.
+    group_order_by: Vec<(usize, bool)>,
+    /// Whether the order and the limit of the groups are pushed down to the `terms` aggregation
+    /// of the single grouping key, which then leaves out the other groups on purpose.
+    is_group_limit_pushed_down: bool,
+    limit: usize,
+    offset: usize,
+}
+
+impl SqlPlan {
+    /// Parses a SQL query and compiles it into a search request.
+    pub fn parse(sql: &str) -> Result<SqlPlan> {
+        let select =
+            parse_sql(sql).map_err(|error| SearchError::InvalidQuery(error.to_string()))?;
+        SqlPlan::build(select)
+    }
+
+    fn build(select: SqlSelect) -> Result<SqlPlan> {
+        let SqlSelect {
+            projections,
+            index_id_patterns,
+            query_ast,
+            group_by,
+            order_by,
+            limit,
+            offset,
+        } = select;
+
+        let group_keys = group_by
+            .into_iter()
+            .map(|expr| resolve_alias(expr, &projections))
+            .collect::<Result<Vec<SqlExpr>>>()?;
+        let is_aggregation = !group_keys.is_empty()
+            || projections
+                .iter()
+                .any(|projection| projection.expr.is_aggregate());
+
+        let mut column_names = Vec::with_capacity(projections.len());
+        let mut column_sources = Vec::with_capacity(projections.len());
+        let mut metric_aggregations = JsonMap::new();
+
+        for projection in &projections {
+            let column_source = match &projection.expr {
+                SqlExpr::Wildcard if is_aggregation => {
+                    return Err(invalid_sql(
+                        "`*` cannot be selected along with aggregates or a `GROUP BY` clause",
+                    ));
+                }
+                SqlExpr::Wildcard => ColumnSource::Wildcard,
+                SqlExpr::Aggregate {
+                    function: SqlAggregateFunction::Count,
+                    field: None,
+                } => ColumnSource::DocCount,
+                SqlExpr::Aggregate {
+                    function,
+                    field: Some(field),
+                } => {
+                    let aggregation_name = format!("metric_{}", metric_aggregations.len());
+                    let aggregation_type = match function {
+                        SqlAggregateFunction::Count => "value_count",
+                        _ => function.name(),
+                    };
+                    metric_aggregations.insert(
+                        aggregation_name.clone(),
+                        json!({ aggregation_type: { "field": field } }),
+                    );
+                    ColumnSource::Metric(aggregation_name)
+                }
+                SqlExpr::Aggregate { field: None, .. } => {
+                    return Err(invalid_sql(format!(
+                        "`{}` requires a field",
+                        projection.expr
+                    )));
+                }
+                expr if is_aggregation => {
+                    let Some(group_key_ord) =
+                        group_keys.iter().position(|group_key| group_key == expr)
+                    else {
+                        return Err(invalid_sql(format!(
+                            "`{expr}` must appear in the `GROUP BY` clause"
+                        )));
+                    };
+                    ColumnSource::GroupKey(group_key_ord)
+                }
+                SqlExpr::Field(field) => ColumnSource::Field(field.clone()),
+                expr @ SqlExpr::DateHistogram { .. } => {
+                    return Err(invalid_sql(format!(
+                        "`{expr}` can only be selected along with a `GROUP BY` clause"
+                    )));
+                }
+            };
+            column_names.push(projection.column_name());
+            column_sources.push(column_source);
+        }
+        let query_ast_json = serde_json::to_string(&query_ast)?;
+        let limit = limit.unwrap_or(DEFAULT_SQL_LIMIT);
+        let offset = offset.unwrap_or(0);
+
+        let mut search_request = SearchRequest {
+            index_id_patterns,
+            query_ast: query_ast_json,
+            ..Default::default()
+        };
+        let mut group_order_by = Vec::new();
+        let mut is_group_limit_pushed_down = false;
+
+        if is_aggregation {
+            for order_by in order_by {
+                let Some(column_ord) = find_column(&order_by.expr, &projections) else {
+                    return Err(invalid_sql(format!(
+                        "`ORDER BY {}` must refer to a selected column",
+                        order_by.expr
+                    )));
+                };
+                group_order_by.push((column_ord, order_by.descending));
+            }
+            let terms_order_and_size = terms_order_and_size(
+                &group_keys,
+                &group_order_by,
+                &column_sources,
+                offset + limit,
+            );
+            is_group_limit_pushed_down = terms_order_and_size.is_some();
+            let aggregations =
+                build_aggregations(&group_keys, metric_aggregations, terms_order_and_size);
+
+            if !aggregations.is_empty() {
+                search_request.aggregation_request = Some(serde_json::to_string(&aggregations)?);
+            }
+        } else {
+            for order_by in order_by {
+                let SqlExpr::Field(field) = resolve_alias(order_by.expr, &projections)? else {
+                    return Err(invalid_sql(
+                        "documents can only be ordered by fields, use a `GROUP BY` clause to \
+                         order by aggregates",
+                    ));
+                };
+                let sort_order = if order_by.descending {
+                    SortOrder::Desc
+                } else {
+                    SortOrder::Asc
+                };
+                search_request.sort_fields.push(SortField {
+                    field_name: field,
+                    sort_order: sort_order as i32,
+                });
+            }
+            search_request.max_hits = limit;
+            search_request.start_offset = offset;
+        }
+        Ok(SqlPlan {
+            search_request,
+            column_names,
+            column_sources,
+            is_aggregation,
+            group_keys,
+            group_order_by,
+            is_group_limit_pushed_down,
+            limit: limit as usize,
+            offset: offset as usize,
+        })
+    }
+
+    /// Returns the search request to execute.
+    pub fn search_request(&self) -> &SearchRequest {
+        &self.search_request
+    }
+
+    /// Shapes the response of the search request into a table.
+    pub fn into_sql_response(self, search_response: SearchResponse) -> Result<SqlResponse> {
+        if self.is_aggregation {
+            self.aggregation_response(search_response)
+        } else {
+            self.documents_response(search_response)
+        }
+    }
+
+    fn documents_response(self, search_response: SearchResponse) -> Result<SqlResponse> {
+        let docs = search_response
+            .hits
+            .iter()
+            .map(|hit| serde_json::from_str::<JsonValue>(&hit.json))
+            .collect::<serde_json::Result<Vec<JsonValue>>>()?;
+
+        let mut columns = Vec::new();
+        let mut column_fields: Vec<String> = Vec::new();
+
+        for (column_name, column_source) in self.column_names.into_iter().zip(self.column_sources) {
+            match column_source {
+                ColumnSource::Wildcard => {
+                    let mut seen_fields = HashSet::new();
+
+                    for doc in &docs {
+                        let Some(doc_object) = doc.as_object() else {
+                            continue;
+                        };
+                        for field in doc_object.keys() {
+                            if seen_fields.insert(field.clone()) {
+                                columns.push(SqlColumn {
+                                    name: field.clone(),
+                                });
+                                column_fields.push(field.clone());
+                            }
+                        }
+                    }
+                }
+                ColumnSource::Field(field) => {
+                    columns.push(SqlColumn { name: column_name });
+                    column_fields.push(field);
+                }
+                _ => unreachable!("documents are only projected on fields"),
+            }
+        }
+        let rows = docs
+            .iter()
+            .map(|doc| {
+                column_fields
+                    .iter()
+                    .map(|field| get_field_value(doc, field))
+                    .collect()
+            })
+            .collect();
+        Ok(SqlResponse { columns, rows })
+    }
+
+    fn aggregation_response(self, search_response: SearchResponse) -> Result<SqlResponse> {
+        let aggregations: JsonValue = match &search_response.aggregation {
+            Some(aggregation_json) => serde_json::from_str(aggregation_json)?,
+            None => JsonValue::Object(JsonMap::new()),
+        };
+        let mut rows = Vec::new();
+
+        if self.group_keys.is_empty() {
+            let row = self.build_row(&[], &aggregations, search_response.num_hits);
+            rows.push(row);
+        } else {
+            self.collect_group_rows(&aggregations, &mut Vec::new(), &mut rows)?;
+        }
+        if !self.group_order_by.is_empty() {
+            rows.sort_by(|left_row, right_row| {
+                for &(column_ord, descending) in &self.group_order_by {
+                    let mut ordering =
+                        compare_json_values(&left_row[column_ord], &right_row[column_ord]);
+                    if descending {
+                        ordering = ordering.reverse();
+                    }
+                    if ordering != Ordering::Equal {
+                        return ordering;
+                    }
+                }
+                Ordering::Equal
+            });
+        }
+        let rows = rows
+            .into_iter()
+            .skip(self.offset)
+            .take(self.limit)
+            .collect();
+        let columns = self
+            .column_names
+            .into_iter()
+            .map(|name| SqlColumn { name })
+            .collect();
+        Ok(SqlResponse { columns, rows })
+    }
+
+    /// Walks down the nested grouping aggregations, emitting a row for each innermost bucket.
+    ///
+    /// Fails if a `terms` aggregation left out some groups, as ordering and limiting the rows
+    /// would then silently yield wrong results.
+    fn collect_group_rows(
+        &self,
+        bucket: &JsonValue,
+        group_key_values: &mut Vec<JsonValue>,
+        rows: &mut Vec<Vec<JsonValue>>,
+    ) -> Result<()> {
+        let depth = group_key_values.len();
+
+        if depth == self.group_keys.len() {
+            let doc_count = bucket["doc_count"].as_u64().unwrap_or(0);
+            let row = self.build_row(group_key_values, bucket, doc_count);
+            rows.push(row);
+            return Ok(());
+        }
+        let group_aggregation = &bucket[group_aggregation_name(depth)];
+        let sum_other_doc_count = group_aggregation["sum_other_doc_count"]
+            .as_u64()
+            .unwrap_or(0);
+
+        if sum_other_doc_count > 0 && !self.is_group_limit_pushed_down {
+            return Err(invalid_sql(format!(
+                "`GROUP BY {}` yields more than {MAX_NUM_GROUPS_PER_KEY} groups, narrow down the \
+                 query",
+                self.group_keys[depth]
+            )));
+        }
+        let Some(sub_buckets) = group_aggregation["buckets"].as_array() else {
+            return Ok(());
+        };
+        for sub_bucket in sub_buckets {
+            let key = match sub_bucket.get("key_as_string") {
+                Some(key_as_string) => key_as_string.clone(),
+                None => sub_bucket["key"].clone(),
+            };
+            group_key_values.push(key);
+            self.collect_group_rows(sub_bucket, group_key_values, rows)?;
+            group_key_values.pop();
+        }
+        Ok(())
+    }
+
+    fn build_row(
+        &self,
+        group_key_values: &[JsonValue],
+        bucket: &JsonValue,
+        doc_count: u64,
+    ) -> Vec<JsonValue> {
+        self.column_sources
+            .iter()
+            .map(|column_source| match column_source {
+                ColumnSource::GroupKey(group_key_ord) => group_key_values[*group_key_ord].clone(),
+                ColumnSource::DocCount => JsonValue::from(doc_count),
+                ColumnSource::Metric(aggregation_name) => bucket[aggregation_name]["value"].clone(),
+                ColumnSource::Wildcard | ColumnSource::Field(_) => {
+                    unreachable!("groups are only projected on keys and aggregates")
+                }
+            })
+            .collect()
+    }
+}
+
+fn invalid_sql(message: impl std::fmt::Display) -> SearchError {
+    SearchError::InvalidQuery(format!("invalid SQL query: {message}"))
+}
+
+/// Substitutes a field named after the alias of a selected expression with that expression.
+fn resolve_alias(expr: SqlExpr, projections: &[SqlProjection]) -> Result<SqlExpr> {
+    let SqlExpr::Field(name) = &expr else {
+        return Ok(expr);
+    };
+    let Some(projection) = projections
+        .iter()
+        .find(|projection| projection.alias.as_ref() == Some(name))
+    else {
+        return Ok(expr);
+    };
+    if projection.expr.is_aggregate() {
+        return Err(invalid_sql(format!(
+            "`{name}` refers to the aggregate `{}`",
+            projection.expr
+        )));
+    }
+    Ok(projection.expr.clone())
+}
+
+/// Returns the position of the selected column matching an expression or named after it.
+fn find_column(expr: &SqlExpr, projections: &[SqlProjection]) -> Option<usize> {
+    projections.iter().position(|projection| {
+        if &projection.expr == expr {
+            return true;
+        }
+        matches!(expr, SqlExpr::Field(name) if projection.alias.as_ref() == Some(name))
+    })
+}
+
+fn group_aggregation_name(depth: usize) -> String {
+    format!("group_{depth}")
+}
+
+/// Returns the order and the size of the `terms` aggregation of a single grouping key when it
+/// can return the groups to keep by itself: the groups must be ordered by at most one column,
+/// and the requested rows must fit in [`MAX_NUM_GROUPS_PER_KEY`] groups.
+///
+/// Like for Elasticsearch's `terms` aggregation, the top groups are then approximate when the
+/// index spans several splits.
+fn terms_order_and_size(
+    group_keys: &[SqlExpr],
+    group_order_by: &[(usize, bool)],
+    column_sources: &[ColumnSource],
+    num_rows: u64,
+) -> Option<(JsonValue, u64)> {
+    if !matches!(group_keys, [SqlExpr::Field(_)])
+        || num_rows == 0
+        || num_rows > MAX_NUM_GROUPS_PER_KEY as u64
+    {
+        return None;
+    }
+    let terms_order = match group_order_by {
+        [] => json!({"_count": "desc"}),
+        [(column_ord, descending)] => {
+            let order_target = match &column_sources[*column_ord] {
+                ColumnSource::GroupKey(_) => "_key",
+                ColumnSource::DocCount => "_count",
+                ColumnSource::Metric(aggregation_name) => aggregation_name.as_str(),
+                ColumnSource::Wildcard | ColumnSource::Field(_) => return None,
+            };
+            let direction = if *descending { "desc" } else { "asc" };
+            json!({ order_target: direction })
+        }
+        _ => return None,
+    };
+    Some((terms_order, num_rows))
+}
+
+/// Nests one bucket aggregation per grouping key, the metric aggregations being computed in the
+/// innermost buckets.
+fn build_aggregations(
+    group_keys: &[SqlExpr],
+    metric_aggregations: JsonMap<String, JsonValue>,
+    terms_order_and_size: Option<(JsonValue, u64)>,
+) -> JsonMap<String, JsonValue> {
+    let mut aggregations = metric_aggregations;
+
+    for (depth, group_key) in group_keys.iter().enumerate().rev() {
+        let mut bucket_aggregation = match group_key {
+            SqlExpr::Field(field) => match &terms_order_and_size {
+                Some((terms_order, terms_size)) => json!({
+                    "terms": {
+                        "field": field,
+                        "size": terms_size,
+                        "order": terms_order,
+                    }
+                }),
+                None => json!({
+                    "terms": {
+                        "field": field,
+                        "size": MAX_NUM_GROUPS_PER_KEY,
+                    }
+                }),
+            },
+            SqlExpr::DateHistogram {
+                field,
+                fixed_interval,
+            } => json!({
+                "date_histogram": {
+                    "field": field,
+                    "fixed_interval": fixed_interval,
+                    "min_doc_count": 1,
+                }
+            }),
+            _ => unreachable!("the parser only accepts fields and date buckets as grouping keys"),
+        };
+        if !aggregations.is_empty() {
+            bucket_aggregation["aggs"] = JsonValue::Object(aggregations);
+        }
+        aggregations = JsonMap::new();
+        aggregations.insert(group_aggregation_name(depth), bucket_aggregation);
+    }
+    aggregations
+}
+
+/// Returns the value of a field of a document. Dots in the field path are first matched against
+/// the document's keys, then interpreted as object nesting.
+fn get_field_value(doc: &JsonValue, field_path: &str) -> JsonValue {
+    if let Some(value) = doc.get(field_path) {
+        return value.clone();
+    }
+    let mut value = doc;
+
+    for field_name in field_path.split('.') {
+        match value.get(field_name) {
+            Some(child_value) => value = child_value,
+            None => return JsonValue::Null,
+        }
+    }
+    value.clone()
+}
+
+/// Orders JSON values of different types as null < booleans < numbers < strings < others.
+fn compare_json_values(left: &JsonValue, right: &JsonValue) -> Ordering {
+    fn type_rank(value: &JsonValue) -> u8 {
+        match value {
+            JsonValue::Null => 0,
+            JsonValue::Bool(_) => 1,
+            JsonValue::Number(_) => 2,
+            JsonValue::String(_) => 3,
+            JsonValue::Array(_) => 4,
+            JsonValue::Object(_) => 5,
+        }
+    }
+    match (left, right) {
+        (JsonValue::Bool(left), JsonValue::Bool(right)) => left.cmp(right),
+        (JsonValue::Number(left), JsonValue::Number(right)) => left
+            .as_f64()
+            .partial_cmp(&right.as_f64())
+            .unwrap_or(Ordering::Equal),
+        (JsonValue::String(left), JsonValue::String(right)) => left.cmp(right),
+        _ => type_rank(left).cmp(&type_rank(right)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quickwit_proto::search::Hit;
+    use quickwit_query::query_ast::{QueryAst, TermQuery};
+
+    use super::*;
+
+    #[test]
+    fn test_sql_plan_documents() {
+        let plan = SqlPlan::parse(
+            "SELECT service, latency AS l FROM logs WHERE service = 'api' ORDER BY l DESC LIMIT 2 \
+             OFFSET 1",
+        )
+        .unwrap();
+        let search_request = plan.search_request();
+        assert_eq!(search_request.index_id_patterns, ["logs"]);
+        let query_ast: QueryAst = serde_json::from_str(&search_request.query_ast).unwrap();
+        assert_eq!(
+            query_ast,
+            TermQuery::from_field_value("service", "api").into()
+        );
+        assert_eq!(
+            search_request.sort_fields,
+            [SortField {
+                field_name: "latency".to_string(),
+                sort_order: SortOrder::Desc as i32,
+            }]
+        );
+        assert_eq!(search_request.max_hits, 2);
+        assert_eq!(search_request.start_offset, 1);
+        assert!(search_request.aggregation_request.is_none());
+
+        let search_response = SearchResponse {
+            num_hits: 3,
+            hits: vec![
+                Hit {
+                    json: r#"{"service": "api", "latency": 12}"#.to_string(),
+                    ..Default::default()
+                },
+                Hit {
+                    json: r#"{"service": "api"}"#.to_string(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let sql_response = plan.into_sql_response(search_response).unwrap();
+        assert_eq!(
+            sql_response,
+            SqlResponse {
+                columns: vec![
+                    SqlColumn {
+                        name: "service".to_string()
+                    },
+                    SqlColumn {
+                        name: "l".to_string()
+                    },
+                ],
+                rows: vec![
+                    vec![json!("api"), json!(12)],
+                    vec![json!("api"), JsonValue::Null],
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn test_sql_plan_documents_wildcard() {
+        let plan = SqlPlan::parse("SELECT *, resource.host FROM logs").unwrap();
+        assert_eq!(plan.search_request().max_hits, DEFAULT_SQL_LIMIT);
+
+        let search_response = SearchResponse {
+            num_hits: 2,
+            hits: vec![
+                Hit {
+                    json: r#"{"body": "foo", "resource": {"host": "a"}}"#.to_string(),
+                    ..Default::default()
+                },
+                Hit {
+                    json: r#"{"body": "bar", "severity": "WARN"}"#.to_string(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let sql_response = plan.into_sql_response(search_response).unwrap();
+        let column_names: Vec<&str> = sql_response
+            .columns
+            .iter()
+            .map(|column| column.name.as_str())
+            .collect();
+        assert_eq!(
+            column_names,
+            ["body", "resource", "severity", "resource.host"]
+        );
+        assert_eq!(
+            sql_response.rows,
+            [
+                vec![
+                    json!("foo"),
+                    json!({"host": "a"}),
+                    JsonValue::Null,
+                    json!("a")
+                ],
+                vec![
+                    json!("bar"),
+                    JsonValue::Null,
+                    json!("WARN"),
+                    JsonValue::Null
+                ],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sql_plan_group_by() {
+        let plan = SqlPlan::parse(
+            "SELECT service, DATE_TRUNC('hour', timestamp) AS hour, COUNT(*) AS hits, \
+             AVG(latency) FROM logs GROUP BY service, hour ORDER BY hits DESC, service LIMIT 2",
+        )
+        .unwrap();
+        let search_request = plan.search_request();
+        assert_eq!(search_request.max_hits, 0);
+        assert!(search_request.sort_fields.is_empty());
+        let aggregations: JsonValue =
+            serde_json::from_str(search_request.aggregation_request.as_ref().unwrap()).unwrap();
+        assert_eq!(
+            aggregations,
+            json!({
+                "group_0": {
+                    "terms": {"field": "service", "size": 10_000},
+                    "aggs": {
+                        "group_1": {
+                            "date_histogram": {
+                                "field": "timestamp",
+                                "fixed_interval": "1h",
+                                "min_doc_count": 1,
+                            },
+                            "aggs": {
+                                "metric_0": {"avg": {"field": "latency"}},
+                            }
+                        }
+                    }
+                }
+            })
+        );
+        let aggregation_results = json!({
+            "group_0": {
+                "buckets": [
+                    {
+                        "key": "api",
+                        "doc_count": 3,
+                        "group_1": {
+                            "buckets": [
+                                {
+                                    "key": 0.0,
+                                    "key_as_string": "1970-01-01T00:00:00Z",
+                                    "doc_count": 1,
+                                    "metric_0": {"value": 10.0},
+                                },
+                                {
+                                    "key": 3600000.0,
+                                    "key_as_string": "1970-01-01T01:00:00Z",
+                                    "doc_count": 2,
+                                    "metric_0": {"value": 20.0},
+                                },
+                            ]
+                        }
+                    },
+                    {
+                        "key": "db",
+                        "doc_count": 2,
+                        "group_1": {
+                            "buckets": [
+                                {
+                                    "key": 0.0,
+                                    "key_as_string": "1970-01-01T00:00:00Z",
+                                    "doc_count": 2,
+                                    "metric_0": {"value": null},
+                                },
+                            ]
+                        }
+                    },
+                ]
+            }
+        });
+        let search_response = SearchResponse {
+            num_hits: 5,
+            aggregation: Some(aggregation_results.to_string()),
+            ..Default::default()
+        };
+        let sql_response = plan.into_sql_response(search_response).unwrap();
+        let column_names: Vec<&str> = sql_response
+            .columns
+            .iter()
+            .map(|column| column.name.as_str())
+            .collect();
+        assert_eq!(column_names, ["service", "hour", "hits", "avg(latency)"]);
+        assert_eq!(
+            sql_response.rows,
+            [
+                vec![
+                    json!("api"),
+                    json!("1970-01-01T01:00:00Z"),
+                    json!(2),
+                    json!(20.0)
+                ],
+                vec![
+                    json!("db"),
+                    json!("1970-01-01T00:00:00Z"),
+                    json!(2),
+                    JsonValue::Null
+                ],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sql_plan_group_by_pushes_down_limit() {
+        let plan = SqlPlan::parse(
+            "SELECT service, MAX(latency) AS max_latency FROM logs GROUP BY service ORDER BY \
+             max_latency DESC LIMIT 3 OFFSET 2",
+        )
+        .unwrap();
+        let aggregations: JsonValue =
+            serde_json::from_str(plan.search_request().aggregation_request.as_ref().unwrap())
+                .unwrap();
+        assert_eq!(
+            aggregations,
+            json!({
+                "group_0": {
+                    "terms": {"field": "service", "size": 5, "order": {"metric_0": "desc"}},
+                    "aggs": {
+                        "metric_0": {"max": {"field": "latency"}},
+                    }
+                }
+            })
+        );
+        let aggregation_results = json!({
+            "group_0": {
+                "sum_other_doc_count": 10,
+                "buckets": [
+                    {"key": "api", "doc_count": 3, "metric_0": {"value": 30.0}},
+                    {"key": "db", "doc_count": 2, "metric_0": {"value": 20.0}},
+                    {"key": "web", "doc_count": 1, "metric_0": {"value": 10.0}},
+                ]
+            }
+        });
+        let search_response = SearchResponse {
+            num_hits: 16,
+            aggregation: Some(aggregation_results.to_string()),
+            ..Default::default()
+        };
+        let sql_response = plan.into_sql_response(search_response).unwrap();
+        assert_eq!(sql_response.rows, [vec![json!("web"), json!(10.0)]]);
+
+        let plan = SqlPlan::parse("SELECT service FROM logs GROUP BY service").unwrap();
+        let aggregations: JsonValue =
+            serde_json::from_str(plan.search_request().aggregation_request.as_ref().unwrap())
+                .unwrap();
+        assert_eq!(
+            aggregations,
+            json!({
+                "group_0": {
+                    "terms": {"field": "service", "size": 100, "order": {"_count": "desc"}},
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn test_sql_plan_group_by_too_many_groups() {
+        let plan = SqlPlan::parse(
+            "SELECT service, host, COUNT(*) FROM logs GROUP BY service, host ORDER BY host",
+        )
+        .unwrap();
+        let aggregation_results = json!({
+            "group_0": {
+                "sum_other_doc_count": 0,
+                "buckets": [
+                    {
+                        "key": "api",
+                        "doc_count": 3,
+                        "group_1": {
+                            "sum_other_doc_count": 1,
+                            "buckets": [{"key": "host-1", "doc_count": 2}],
+                        }
+                    },
+                ]
+            }
+        });
+        let search_response = SearchResponse {
+            num_hits: 3,
+            aggregation: Some(aggregation_results.to_string()),
+            ..Default::default()
+        };
+        let error = plan.into_sql_response(search_response).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid SQL query: `GROUP BY host` yields more than 10000 groups, narrow down the \
+             query"
+        );
+    }
+
+    #[test]
+    fn test_sql_plan_aggregates_without_group_by() {
+        let plan =
+            SqlPlan::parse("SELECT COUNT(*), MAX(latency), COUNT(trace_id) FROM logs").unwrap();
+        let aggregations: JsonValue =
+            serde_json::from_str(plan.search_request().aggregation_request.as_ref().unwrap())
+                .unwrap();
+        assert_eq!(
+            aggregations,
+            json!({
+                "metric_0": {"max": {"field": "latency"}},
+                "metric_1": {"value_count": {"field": "trace_id"}},
+            })
+        );
+        let search_response = SearchResponse {
+            num_hits: 42,
+            aggregation: Some(
+                json!({"metric_0": {"value": 120.0}, "metric_1": {"value": 40.0}}).to_string(),
+            ),
+            ..Default::default()
+        };
+        let sql_response = plan.into_sql_response(search_response).unwrap();
+        assert_eq!(
+            sql_response.rows,
+            [vec![json!(42), json!(120.0), json!(40.0)]]
+        );
+
+        let plan = SqlPlan::parse("SELECT COUNT(*) FROM logs WHERE status >= 500").unwrap();
+        assert!(plan.search_request().aggregation_request.is_none());
+        assert_eq!(plan.search_request().max_hits, 0);
+    }
+
+    #[test]
+    fn test_sql_plan_errors() {
+        let plan_error = |sql: &str| SqlPlan::parse(sql).unwrap_err().to_string();
+        assert_eq!(
+            plan_error("SELECT * FROM logs GROUP BY service"),
+            "invalid SQL query: `*` cannot be selected along with aggregates or a `GROUP BY` \
+             clause"
+        );
+        assert_eq!(
+            plan_error("SELECT service, host, COUNT(*) FROM logs GROUP BY service"),
+            "invalid SQL query: `host` must appear in the `GROUP BY` clause"
+        );
+        assert_eq!(
+            plan_error("SELECT DATE_HISTOGRAM(timestamp, '1m') FROM logs"),
+            "invalid SQL query: `date_histogram(timestamp, '1m')` can only be selected along with \
+             a `GROUP BY` clause"
+        );
+        assert_eq!(
+            plan_error("SELECT service FROM logs ORDER BY COUNT(*)"),
+            "invalid SQL query: documents can only be ordered by fields, use a `GROUP BY` clause \
+             to order by aggregates"
+        );
+        assert_eq!(
+            plan_error("SELECT service FROM logs GROUP BY service ORDER BY host"),
+            "invalid SQL query: `ORDER BY host` must refer to a selected column"
+        );
+        assert_eq!(
+            plan_error("SELECT COUNT(*) AS hits FROM logs GROUP BY hits"),
+            "invalid SQL query: `hits` refers to the aggregate `count(*)`"
+        );
+        assert_eq!(
+            plan_error("SELECT * FROM"),
+            "invalid SQL query: expected an identifier, found end of query"
+        );
+    }
+
+    #[test]
+    fn test_sql_response_to_csv() {
+        let sql_response = SqlResponse {
+            columns: vec![
+                SqlColumn {
+                    name: "service".to_string(),
+                },
+                SqlColumn {
+                    name: "count(*)".to_string(),
+                },
+                SqlColumn {
+                    name: "attributes".to_string(),
+                },
+            ],
+            rows: vec![
+                vec![json!("api"), json!(3), json!({"a": 1, "b": 2})],
+                vec![json!("say \"hi\""), json!(1.5), JsonValue::Null],
+            ],
+        };
+        assert_eq!(
+            sql_response.to_csv(),
+            "service,count(*),attributes\napi,3,\"{\"\"a\"\":1,\"\"b\"\":2}\"\n\"say \
+             \"\"hi\"\"\",1.5,\n"
+        );
+    }
+}
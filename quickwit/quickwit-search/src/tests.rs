@@ -26,8 +26,8 @@ use quickwit_doc_mapper::DefaultDocMapper;
 use quickwit_indexing::TestSandbox;
 use quickwit_opentelemetry::otlp::TraceId;
 use quickwit_proto::search::{
-    LeafListTermsResponse, ListTermsRequest, SearchRequest, SortByValue, SortField, SortOrder,
-    SortValue,
+    LeafListTermsResponse, ListTermsRequest, PartialHit, SearchRequest, SnippetConfig, SortByValue,
+    SortField, SortOrder, SortValue,
 };
 use quickwit_query::query_ast::{
     qast_helper, qast_json_helper, query_ast_from_user_text, QueryAst,
@@ -131,6 +131,43 @@ async fn test_single_node_termset() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_single_node_fetch_fields() -> anyhow::Result<()> {
+    let index_id = "single-node-fetch-fields";
+    let doc_mapping_yaml = r#"
+            field_mappings:
+              - name: title
+                type: text
+              - name: body
+                type: text
+              - name: url
+                type: text
+        "#;
+    let test_sandbox = TestSandbox::create(index_id, doc_mapping_yaml, "{}", &["body"]).await?;
+    let docs = vec![
+        json!({"title": "snoopy", "body": "Snoopy is an anthropomorphic beagle", "url": "http://snoopy"}),
+    ];
+    test_sandbox.add_documents(docs).await?;
+    let search_request = SearchRequest {
+        index_id_patterns: vec![index_id.to_string()],
+        query_ast: qast_json_helper("anthropomorphic", &["body"]),
+        max_hits: 1,
+        fetch_fields: vec!["title".to_string(), "url".to_string()],
+        ..Default::default()
+    };
+    let single_node_result = single_node_search(
+        search_request,
+        test_sandbox.metastore(),
+        test_sandbox.storage_resolver(),
+    )
+    .await?;
+    assert_eq!(single_node_result.hits.len(), 1);
+    let hit_json: JsonValue = serde_json::from_str(&single_node_result.hits[0].json)?;
+    assert_eq!(hit_json, json!({"title": "snoopy", "url": "http://snoopy"}));
+    test_sandbox.assert_quit().await;
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_single_search_with_snippet() -> anyhow::Result<()> {
     let index_id = "single-node-with-snippet";
@@ -181,6 +218,56 @@ async fn test_single_search_with_snippet() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_single_search_with_snippet_config() -> anyhow::Result<()> {
+    let index_id = "single-node-with-snippet-config";
+    let doc_mapping_yaml = r#"
+            field_mappings:
+              - name: body
+                type: text
+              - name: attributes
+                type: json
+                tokenizer: default
+        "#;
+    let test_sandbox = TestSandbox::create(index_id, doc_mapping_yaml, "{}", &["body"]).await?;
+    let docs = vec![json!({
+        "body": "A beagle is a hound. Dogs of many breeds are kept as pets. Another beagle.",
+        "attributes": {"message": "<beagle> & cat"}
+    })];
+    test_sandbox.add_documents(docs).await?;
+    let search_request = SearchRequest {
+        index_id_patterns: vec![index_id.to_string()],
+        query_ast: qast_json_helper("beagle", &["body", "attributes.message"]),
+        snippet_fields: vec!["body".to_string(), "attributes.message".to_string()],
+        snippet_config: Some(SnippetConfig {
+            fragment_size: Some(20),
+            num_fragments: Some(2),
+            pre_tag: Some("<mark>".to_string()),
+            post_tag: Some("</mark>".to_string()),
+        }),
+        max_hits: 1,
+        ..Default::default()
+    };
+    let single_node_result = single_node_search(
+        search_request,
+        test_sandbox.metastore(),
+        test_sandbox.storage_resolver(),
+    )
+    .await?;
+    assert_eq!(single_node_result.num_hits, 1);
+
+    let highlight_json: JsonValue =
+        serde_json::from_str(single_node_result.hits[0].snippet.as_ref().unwrap())?;
+    let expected_json: JsonValue = json!({
+        "body": ["A <mark>beagle</mark> is a hound", "Another <mark>beagle</mark>"],
+        "attributes.message": ["&lt;<mark>beagle</mark>&gt; &amp; cat"]
+    });
+    assert_json_eq!(highlight_json, expected_json);
+
+    test_sandbox.assert_quit().await;
+    Ok(())
+}
+
 async fn slop_search_and_check(
     test_sandbox: &TestSandbox,
     index_id: &str,
@@ -327,7 +414,7 @@ async fn test_single_node_several_splits() -> anyhow::Result<()> {
     assert!(is_sorted(single_node_result.hits.iter().flat_map(|hit| {
         hit.partial_hit.as_ref().map(|partial_hit| {
             (
-                partial_hit.sort_value,
+                partial_hit.sort_value.clone(),
                 partial_hit.split_id.as_str(),
                 partial_hit.doc_id,
             )
@@ -910,7 +997,6 @@ async fn test_single_node_invalid_sorting_with_query() {
             field_mappings:
               - name: description
                 type: text
-                fast: true
               - name: temperature
                 type: i64
         "#;
@@ -945,8 +1031,343 @@ async fn test_single_node_invalid_sorting_with_query() {
     let error_msg = single_node_response.unwrap_err().to_string();
     assert_eq!(
         error_msg,
-        "Invalid argument: sort by field on type text is currently not supported `description`"
+        "Invalid argument: sort by field must be a fast field, please add the fast property to \
+         your field `description`"
+    );
+    test_sandbox.assert_quit().await;
+}
+
+#[tokio::test]
+async fn test_single_node_search_timeout() -> anyhow::Result<()> {
+    let index_id = "single-node-search-timeout";
+    let doc_mapping_yaml = r#"
+            field_mappings:
+              - name: body
+                type: text
+        "#;
+    let test_sandbox = TestSandbox::create(index_id, doc_mapping_yaml, "{}", &["body"]).await?;
+    let docs = vec![
+        json!({"body": "one"}),
+        json!({"body": "two"}),
+        json!({"body": "three"}),
+    ];
+    test_sandbox.add_documents(docs).await?;
+    {
+        // The deadline is reached before any split could be searched.
+        let search_request = SearchRequest {
+            index_id_patterns: vec![index_id.to_string()],
+            query_ast: serde_json::to_string(&QueryAst::MatchAll).unwrap(),
+            max_hits: 10,
+            timeout_millis: Some(0),
+            ..Default::default()
+        };
+        let search_response = single_node_search(
+            search_request,
+            test_sandbox.metastore(),
+            test_sandbox.storage_resolver(),
+        )
+        .await?;
+        assert!(search_response.timed_out);
+        assert!(search_response.hits.is_empty());
+        assert_eq!(search_response.errors.len(), 1);
+        assert!(search_response.errors[0].contains("search timed out"));
+    }
+    {
+        let search_request = SearchRequest {
+            index_id_patterns: vec![index_id.to_string()],
+            query_ast: serde_json::to_string(&QueryAst::MatchAll).unwrap(),
+            max_hits: 10,
+            timeout_millis: Some(60_000),
+            ..Default::default()
+        };
+        let search_response = single_node_search(
+            search_request,
+            test_sandbox.metastore(),
+            test_sandbox.storage_resolver(),
+        )
+        .await?;
+        assert!(!search_response.timed_out);
+        assert_eq!(search_response.num_hits, 3);
+        assert_eq!(search_response.hits.len(), 3);
+        assert!(search_response.errors.is_empty());
+    }
+    test_sandbox.assert_quit().await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_single_node_search_profile() -> anyhow::Result<()> {
+    let index_id = "single-node-search-profile";
+    let doc_mapping_yaml = r#"
+            tag_fields:
+              - owner
+            field_mappings:
+              - name: body
+                type: text
+              - name: ts
+                type: datetime
+                input_formats:
+                    - "unix_timestamp"
+                fast: true
+              - name: owner
+                type: text
+                tokenizer: raw
+            timestamp_field: ts
+        "#;
+    let test_sandbox = TestSandbox::create(index_id, doc_mapping_yaml, "{}", &["body"]).await?;
+    // One split per batch: the first one is pruned by time range, the second one by tags.
+    for (owner, ts) in [("paul", 10), ("adrien", 100), ("paul", 100)] {
+        let docs = vec![
+            json!({"body": "info", "ts": ts, "owner": owner}),
+            json!({"body": "error", "ts": ts + 1, "owner": owner}),
+        ];
+        test_sandbox.add_documents(docs).await?;
+    }
+    let search_request = SearchRequest {
+        index_id_patterns: vec![index_id.to_string()],
+        query_ast: qast_json_helper("owner:paul AND info", &["body"]),
+        start_timestamp: Some(50),
+        max_hits: 10,
+        profile: true,
+        ..Default::default()
+    };
+    let search_response = single_node_search(
+        search_request,
+        test_sandbox.metastore(),
+        test_sandbox.storage_resolver(),
+    )
+    .await?;
+    assert_eq!(search_response.num_hits, 1);
+    let search_profile = search_response.profile.unwrap();
+
+    assert_eq!(search_profile.queries.len(), 1);
+    assert_eq!(search_profile.queries[0].index_id, index_id);
+    assert!(search_profile.queries[0].lowered_query.contains("info"));
+
+    assert_eq!(search_profile.splits_pruned_by_time_range.len(), 1);
+    assert_eq!(search_profile.splits_pruned_by_tags.len(), 1);
+    assert!(!search_profile.pruned_splits_truncated);
+    assert_eq!(search_profile.split_profiles.len(), 1);
+    let split_profile = &search_profile.split_profiles[0];
+    assert_ne!(
+        split_profile.split_id,
+        search_profile.splits_pruned_by_time_range[0].split_id
+    );
+    assert_ne!(
+        split_profile.split_id,
+        search_profile.splits_pruned_by_tags[0].split_id
+    );
+    assert!(!split_profile.partial_request_cache_hit);
+    assert_eq!(split_profile.num_docs_matched, 1);
+
+    // Without the flag, no profile is returned.
+    let search_request = SearchRequest {
+        index_id_patterns: vec![index_id.to_string()],
+        query_ast: qast_json_helper("owner:paul AND info", &["body"]),
+        max_hits: 10,
+        ..Default::default()
+    };
+    let search_response = single_node_search(
+        search_request,
+        test_sandbox.metastore(),
+        test_sandbox.storage_resolver(),
+    )
+    .await?;
+    assert_eq!(search_response.num_hits, 2);
+    assert!(search_response.profile.is_none());
+    test_sandbox.assert_quit().await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_single_node_sql() -> anyhow::Result<()> {
+    let index_id = "single-node-sql";
+    let doc_mapping_yaml = r#"
+            field_mappings:
+              - name: body
+                type: text
+              - name: service
+                type: text
+                tokenizer: raw
+                fast: true
+              - name: latency
+                type: u64
+                fast: true
+              - name: ts
+                type: datetime
+                input_formats:
+                    - "unix_timestamp"
+                fast: true
+            timestamp_field: ts
+        "#;
+    let test_sandbox = TestSandbox::create(index_id, doc_mapping_yaml, "{}", &["body"]).await?;
+    let docs = vec![
+        json!({"body": "info", "service": "api", "latency": 5, "ts": 0}),
+        json!({"body": "error", "service": "api", "latency": 20, "ts": 10}),
+        json!({"body": "error", "service": "db", "latency": 30, "ts": 3_700}),
+        json!({"body": "info", "service": "db", "latency": 50, "ts": 3_800}),
+        json!({"body": "error", "service": "web", "latency": 1, "ts": 7_300}),
+    ];
+    test_sandbox.add_documents(docs).await?;
+
+    let run_sql = |sql: &str| {
+        let sql_plan = SqlPlan::parse(sql).unwrap();
+        let search_request = sql_plan.search_request().clone();
+        let metastore = test_sandbox.metastore();
+        let storage_resolver = test_sandbox.storage_resolver();
+        async move {
+            let search_response =
+                single_node_search(search_request, metastore, storage_resolver).await?;
+            sql_plan.into_sql_response(search_response)
+        }
+    };
+    let sql_response = run_sql(
+        "SELECT service, latency FROM single-node-sql WHERE MATCH(body, 'error') ORDER BY latency \
+         DESC LIMIT 2",
+    )
+    .await?;
+    assert_eq!(
+        sql_response.rows,
+        [vec![json!("db"), json!(30)], vec![json!("api"), json!(20)]]
     );
+
+    let sql_response = run_sql(
+        "SELECT service, COUNT(*) AS hits, MAX(latency) FROM single-node-sql WHERE latency >= 10 \
+         GROUP BY service ORDER BY service",
+    )
+    .await?;
+    assert_eq!(
+        sql_response.rows,
+        [
+            vec![json!("api"), json!(1), json!(20.0)],
+            vec![json!("db"), json!(2), json!(50.0)],
+        ]
+    );
+
+    let sql_response = run_sql(
+        "SELECT DATE_TRUNC('hour', ts) AS hour, COUNT(*) FROM single-node-sql GROUP BY hour ORDER \
+         BY hour",
+    )
+    .await?;
+    let counts: Vec<&JsonValue> = sql_response.rows.iter().map(|row| &row[1]).collect();
+    assert_eq!(counts, [&json!(2), &json!(2), &json!(1)]);
+    assert!(sql_response.rows[0][0].is_string());
+
+    test_sandbox.assert_quit().await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sort_by_3_fields_with_text_field() {
+    let index_id = "sort_by_3_fields";
+    let doc_mapping_yaml = r#"
+            field_mappings:
+              - name: tenant
+                type: text
+                tokenizer: raw
+                fast: true
+              - name: severity
+                type: u64
+                fast: true
+              - name: ts
+                type: i64
+                fast: true
+            "#;
+    let test_sandbox = TestSandbox::create(index_id, doc_mapping_yaml, "{}", &[])
+        .await
+        .unwrap();
+    let docs = vec![
+        // 0
+        json!({"tenant": "beta", "severity": 2, "ts": 10}),
+        // 1
+        json!({"tenant": "alpha", "severity": 1, "ts": 30}),
+        // 2
+        json!({"tenant": "beta", "severity": 2, "ts": 5}),
+        // 3
+        json!({"tenant": "alpha", "severity": 3, "ts": 20}),
+        // 4
+        json!({"severity": 1, "ts": 1}),
+        // 5
+        json!({"tenant": "beta", "severity": 1, "ts": 2}),
+    ];
+    test_sandbox.add_documents(docs).await.unwrap();
+    let search_hits = |search_after: Option<PartialHit>| {
+        let search_request = SearchRequest {
+            index_id_patterns: vec![index_id.to_string()],
+            query_ast: serde_json::to_string(&QueryAst::MatchAll).unwrap(),
+            max_hits: 1_000,
+            sort_fields: vec![
+                SortField {
+                    field_name: "tenant".to_string(),
+                    sort_order: SortOrder::Asc as i32,
+                },
+                SortField {
+                    field_name: "severity".to_string(),
+                    sort_order: SortOrder::Desc as i32,
+                },
+                SortField {
+                    field_name: "ts".to_string(),
+                    sort_order: SortOrder::Asc as i32,
+                },
+            ],
+            search_after,
+            ..Default::default()
+        };
+        let metastore = test_sandbox.metastore();
+        let storage_resolver = test_sandbox.storage_resolver();
+        async move {
+            single_node_search(search_request, metastore, storage_resolver)
+                .await
+                .unwrap()
+                .hits
+                .into_iter()
+                .map(|hit| hit.partial_hit.unwrap())
+                .collect::<Vec<PartialHit>>()
+        }
+    };
+    let partial_hits = search_hits(None).await;
+    let ordered_docs: Vec<u32> = partial_hits
+        .iter()
+        .map(|partial_hit| partial_hit.doc_id)
+        .collect();
+    assert_eq!(&ordered_docs[..], &[3, 1, 2, 0, 5, 4]);
+    let sort_values: Vec<Option<SortValue>> = partial_hits[1]
+        .sort_by_values()
+        .map(|sort_by_value| sort_by_value.sort_value)
+        .collect();
+    assert_eq!(
+        sort_values,
+        vec![
+            Some(SortValue::Str("alpha".to_string())),
+            Some(SortValue::U64(1)),
+            Some(SortValue::I64(30)),
+        ]
+    );
+    {
+        let mut search_after = PartialHit::default();
+        search_after.set_sort_by_values(partial_hits[1].sort_by_values());
+        let ordered_docs: Vec<u32> = search_hits(Some(search_after))
+            .await
+            .into_iter()
+            .map(|partial_hit| partial_hit.doc_id)
+            .collect();
+        assert_eq!(&ordered_docs[..], &[2, 0, 5, 4]);
+    }
+    {
+        // `b` is not a term of the dictionary.
+        let mut search_after = PartialHit::default();
+        search_after.set_sort_by_values([
+            SortValue::Str("b".to_string()).into(),
+            SortByValue { sort_value: None },
+            SortByValue { sort_value: None },
+        ]);
+        let ordered_docs: Vec<u32> = search_hits(Some(search_after))
+            .await
+            .into_iter()
+            .map(|partial_hit| partial_hit.doc_id)
+            .collect();
+        assert_eq!(&ordered_docs[..], &[2, 0, 5, 4]);
+    }
     test_sandbox.assert_quit().await;
 }
 
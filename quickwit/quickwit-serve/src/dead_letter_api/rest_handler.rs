@@ -0,0 +1,483 @@
+// Copyright (C) 2023 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::sync::Arc;
+
+use quickwit_config::DeadLetterConfig;
+use quickwit_indexing::dead_letter::{check_dead_letter_replay, DeadLetterDoc};
+use quickwit_ingest::{
+    CommitType, DocBatchBuilder, IngestRequest, IngestService, IngestServiceClient,
+    IngestServiceError,
+};
+use quickwit_janitor::error::JanitorError;
+use quickwit_metastore::{IndexMetadata, IndexMetadataResponseExt};
+use quickwit_proto::metastore::{
+    EntityKind, IndexMetadataRequest, MetastoreError, MetastoreService, MetastoreServiceClient,
+};
+use quickwit_proto::search::{Hit, ScrollRequest, SearchRequest};
+use quickwit_proto::types::IndexId;
+use quickwit_proto::{ServiceError, ServiceErrorCode};
+use quickwit_query::query_ast::{BoolQuery, QueryAst, TermQuery};
+use quickwit_search::{SearchError, SearchService};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use time::OffsetDateTime;
+use warp::{Filter, Rejection};
+
+use crate::delete_task_api::create_delete_task;
+use crate::format::extract_format_from_qs;
+use crate::json_api_response::make_json_api_response;
+use crate::with_arg;
+
+/// Number of dead-letter documents replayed per ingest request.
+const REPLAY_BATCH_SIZE: u64 = 1_000;
+
+/// Time-to-live of the scroll context paging through the dead-letter documents to replay.
+const REPLAY_SCROLL_TTL_SECS: u32 = 60;
+
+#[derive(utoipa::OpenApi)]
+#[openapi(
+    paths(get_dead_letter_docs, replay_dead_letter_docs),
+    components(schemas(DeadLetterSearchResponse, DeadLetterReplayResponse))
+)]
+pub struct DeadLetterApi;
+
+#[derive(Debug, Error)]
+pub(crate) enum DeadLetterApiError {
+    #[error("internal error: {0}")]
+    Internal(String),
+    #[error("invalid request: {0}")]
+    InvalidRequest(String),
+    #[error(transparent)]
+    Ingest(#[from] IngestServiceError),
+    #[error(transparent)]
+    Janitor(#[from] JanitorError),
+    #[error(transparent)]
+    Metastore(#[from] MetastoreError),
+    #[error(transparent)]
+    Search(#[from] SearchError),
+}
+
+impl ServiceError for DeadLetterApiError {
+    fn error_code(&self) -> ServiceErrorCode {
+        match self {
+            Self::Internal(_) => ServiceErrorCode::Internal,
+            Self::InvalidRequest(_) => ServiceErrorCode::BadRequest,
+            Self::Ingest(error) => error.error_code(),
+            Self::Janitor(error) => error.error_code(),
+            Self::Metastore(error) => error.error_code(),
+            Self::Search(error) => error.error_code(),
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(deny_unknown_fields)]
+struct DeadLetterSearchParams {
+    /// Maximum number of dead-letter documents returned. Defaults to 20.
+    #[serde(default = "default_max_hits")]
+    max_hits: u64,
+    /// Offset of the first dead-letter document returned. Defaults to 0.
+    #[serde(default)]
+    start_offset: u64,
+}
+
+fn default_max_hits() -> u64 {
+    20
+}
+
+#[derive(Debug, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct DeadLetterSearchResponse {
+    /// Number of dead-letter documents recorded for the source.
+    pub num_hits: u64,
+    #[schema(value_type = Vec<Object>)]
+    pub docs: Vec<DeadLetterDoc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct DeadLetterReplayResponse {
+    /// Number of dead-letter documents ingested back into the index.
+    pub num_replayed_docs: u64,
+}
+
+pub(crate) fn dead_letter_api_handlers(
+    metastore: MetastoreServiceClient,
+    search_service: Arc<dyn SearchService>,
+    ingest_service: IngestServiceClient,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    get_dead_letter_docs_handler(metastore.clone(), search_service.clone()).or(
+        replay_dead_letter_docs_handler(metastore, search_service, ingest_service),
+    )
+}
+
+fn get_dead_letter_docs_handler(
+    metastore: MetastoreServiceClient,
+    search_service: Arc<dyn SearchService>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    warp::path!("indexes" / String / "sources" / String / "dead-letter")
+        .and(warp::get())
+        .and(serde_qs::warp::query(serde_qs::Config::default()))
+        .and(with_arg(metastore))
+        .and(with_arg(search_service))
+        .then(get_dead_letter_docs)
+        .and(extract_format_from_qs())
+        .map(make_json_api_response)
+}
+
+fn replay_dead_letter_docs_handler(
+    metastore: MetastoreServiceClient,
+    search_service: Arc<dyn SearchService>,
+    ingest_service: IngestServiceClient,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    warp::path!("indexes" / String / "sources" / String / "dead-letter" / "replay")
+        .and(warp::post())
+        .and(with_arg(metastore))
+        .and(with_arg(search_service))
+        .and(with_arg(ingest_service))
+        .then(replay_dead_letter_docs)
+        .and(extract_format_from_qs())
+        .map(make_json_api_response)
+}
+
+#[utoipa::path(
+    get,
+    tag = "Sources",
+    path = "/indexes/{index_id}/sources/{source_id}/dead-letter",
+    responses(
+        (status = 200, description = "Successfully fetched the dead-letter documents of the source.", body = DeadLetterSearchResponse)
+    ),
+    params(
+        ("index_id" = String, Path, description = "The index ID of the source."),
+        ("source_id" = String, Path, description = "The source ID whose dead-letter documents are fetched."),
+        DeadLetterSearchParams,
+    )
+)]
+/// Returns the documents rejected by the doc processor of a source.
+async fn get_dead_letter_docs(
+    index_id: IndexId,
+    source_id: String,
+    search_params: DeadLetterSearchParams,
+    mut metastore: MetastoreServiceClient,
+    search_service: Arc<dyn SearchService>,
+) -> Result<DeadLetterSearchResponse, DeadLetterApiError> {
+    let index_metadata = load_index_metadata(&mut metastore, &index_id).await?;
+    let dead_letter_index_id = dead_letter_index_id(&index_metadata, &source_id)?;
+    let search_request = SearchRequest {
+        index_id_patterns: vec![dead_letter_index_id],
+        query_ast: dead_letter_query_ast_json(&index_id, &source_id),
+        max_hits: search_params.max_hits,
+        start_offset: search_params.start_offset,
+        ..Default::default()
+    };
+    let search_response = search_service.root_search(search_request).await?;
+    let docs = search_response
+        .hits
+        .iter()
+        .map(parse_dead_letter_doc)
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(DeadLetterSearchResponse {
+        num_hits: search_response.num_hits,
+        docs,
+    })
+}
+
+#[utoipa::path(
+    post,
+    tag = "Sources",
+    path = "/indexes/{index_id}/sources/{source_id}/dead-letter/replay",
+    responses(
+        (status = 200, description = "Successfully replayed the dead-letter documents of the source.", body = DeadLetterReplayResponse)
+    ),
+    params(
+        ("index_id" = String, Path, description = "The index ID of the source."),
+        ("source_id" = String, Path, description = "The source ID whose dead-letter documents are replayed."),
+    )
+)]
+/// Ingests the documents rejected by the doc processor of a source back into the index via the
+/// ingest API, then deletes them from the dead-letter index. Only the sources parsing and
+/// transforming their documents like the ingest API source of the index can be replayed.
+async fn replay_dead_letter_docs(
+    index_id: IndexId,
+    source_id: String,
+    mut metastore: MetastoreServiceClient,
+    search_service: Arc<dyn SearchService>,
+    mut ingest_service: IngestServiceClient,
+) -> Result<DeadLetterReplayResponse, DeadLetterApiError> {
+    let index_metadata = load_index_metadata(&mut metastore, &index_id).await?;
+    let dead_letter_index_id = dead_letter_index_id(&index_metadata, &source_id)?;
+    check_dead_letter_replay(&index_metadata, &source_id)
+        .map_err(|error| DeadLetterApiError::InvalidRequest(error.to_string()))?;
+    let dead_letter_index_metadata =
+        load_index_metadata(&mut metastore, &dead_letter_index_id).await?;
+    // The documents rejected during the last commit timeout of the dead-letter index may not be
+    // searchable yet: they are left for a later replay rather than deleted without being
+    // replayed.
+    let commit_timeout_secs = dead_letter_index_metadata
+        .index_config
+        .indexing_settings
+        .commit_timeout_secs as i64;
+    let end_timestamp = OffsetDateTime::now_utc().unix_timestamp() - commit_timeout_secs;
+
+    let search_request = SearchRequest {
+        index_id_patterns: vec![dead_letter_index_id.clone()],
+        query_ast: dead_letter_query_ast_json(&index_id, &source_id),
+        end_timestamp: Some(end_timestamp),
+        max_hits: REPLAY_BATCH_SIZE,
+        scroll_ttl_secs: Some(REPLAY_SCROLL_TTL_SECS),
+        ..Default::default()
+    };
+    let mut search_response = search_service.root_search(search_request).await?;
+    let mut num_replayed_docs = 0;
+
+    while !search_response.hits.is_empty() {
+        let mut doc_batch_builder = DocBatchBuilder::new(index_id.clone());
+        for hit in &search_response.hits {
+            let raw_doc = parse_dead_letter_doc(hit)?
+                .raw_doc()
+                .map_err(|error| DeadLetterApiError::Internal(error.to_string()))?;
+            doc_batch_builder.ingest_doc(raw_doc);
+        }
+        let ingest_request = IngestRequest {
+            doc_batches: vec![doc_batch_builder.build()],
+            commit: CommitType::Auto.into(),
+        };
+        ingest_service.ingest(ingest_request).await?;
+        num_replayed_docs += search_response.hits.len() as u64;
+
+        let Some(scroll_id) = search_response.scroll_id else {
+            break;
+        };
+        let scroll_request = ScrollRequest {
+            scroll_id,
+            scroll_ttl_secs: Some(REPLAY_SCROLL_TTL_SECS),
+        };
+        search_response = search_service.scroll(scroll_request).await?;
+    }
+    if num_replayed_docs > 0 {
+        create_delete_task(
+            dead_letter_index_id,
+            dead_letter_query_ast(&index_id, &source_id),
+            None,
+            Some(end_timestamp),
+            metastore,
+        )
+        .await?;
+    }
+    Ok(DeadLetterReplayResponse { num_replayed_docs })
+}
+
+async fn load_index_metadata(
+    metastore: &mut MetastoreServiceClient,
+    index_id: &str,
+) -> Result<IndexMetadata, DeadLetterApiError> {
+    let index_metadata_request = IndexMetadataRequest::for_index_id(index_id.to_string());
+    let index_metadata = metastore
+        .index_metadata(index_metadata_request)
+        .await?
+        .deserialize_index_metadata()?;
+    Ok(index_metadata)
+}
+
+/// Returns the ID of the index the documents rejected by a source are recorded into.
+fn dead_letter_index_id(
+    index_metadata: &IndexMetadata,
+    source_id: &str,
+) -> Result<IndexId, DeadLetterApiError> {
+    let source_config = index_metadata.sources.get(source_id).ok_or_else(|| {
+        MetastoreError::NotFound(EntityKind::Source {
+            index_id: index_metadata.index_id().to_string(),
+            source_id: source_id.to_string(),
+        })
+    })?;
+    match &source_config.dead_letter_opt {
+        Some(DeadLetterConfig::Index { index_id }) => Ok(index_id.clone()),
+        Some(DeadLetterConfig::Storage) => Err(DeadLetterApiError::InvalidRequest(format!(
+            "source `{source_id}` records its dead-letter documents as NDJSON files under \
+             `{}/dead-letter/{source_id}`: replay them with `quickwit source replay-dead-letter \
+             --input-path <file>`",
+            index_metadata.index_uri()
+        ))),
+        None => Err(DeadLetterApiError::InvalidRequest(format!(
+            "source `{source_id}` has no dead-letter destination"
+        ))),
+    }
+}
+
+/// Matches the dead-letter documents of a source. A dead-letter index may be shared by the sources
+/// of several indexes.
+fn dead_letter_query_ast(index_id: &str, source_id: &str) -> QueryAst {
+    let index_id_query: QueryAst = TermQuery {
+        field: "index_id".to_string(),
+        value: index_id.to_string(),
+    }
+    .into();
+    let source_id_query: QueryAst = TermQuery {
+        field: "source_id".to_string(),
+        value: source_id.to_string(),
+    }
+    .into();
+    BoolQuery {
+        must: vec![index_id_query, source_id_query],
+        ..Default::default()
+    }
+    .into()
+}
+
+fn dead_letter_query_ast_json(index_id: &str, source_id: &str) -> String {
+    serde_json::to_string(&dead_letter_query_ast(index_id, source_id))
+        .expect("Failed to serialize QueryAst")
+}
+
+fn parse_dead_letter_doc(hit: &Hit) -> Result<DeadLetterDoc, DeadLetterApiError> {
+    serde_json::from_str(&hit.json).map_err(|error| {
+        DeadLetterApiError::Internal(format!(
+            "failed to deserialize dead-letter document: {error}"
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use quickwit_config::{SourceConfig, TransformConfig};
+    use quickwit_indexing::dead_letter::DeadLetterErrorKind;
+    use quickwit_metastore::IndexMetadata;
+    use quickwit_proto::metastore::IndexMetadataResponse;
+    use quickwit_search::MockSearchService;
+
+    use super::*;
+    use crate::recover_fn;
+
+    fn mock_metastore(dead_letter_opt: Option<DeadLetterConfig>) -> MetastoreServiceClient {
+        let mut source_config = SourceConfig::ingest_api_default();
+        source_config.source_id = "my-source".to_string();
+        source_config.dead_letter_opt = dead_letter_opt;
+        mock_metastore_with_source(source_config)
+    }
+
+    fn mock_metastore_with_source(source_config: SourceConfig) -> MetastoreServiceClient {
+        let mut index_metadata = IndexMetadata::for_test("my-logs", "ram:///indexes/my-logs");
+        index_metadata.add_source(source_config).unwrap();
+
+        let mut mock_metastore = MetastoreServiceClient::mock();
+        mock_metastore.expect_index_metadata().returning(move |_| {
+            Ok(IndexMetadataResponse::try_from_index_metadata(index_metadata.clone()).unwrap())
+        });
+        MetastoreServiceClient::from(mock_metastore)
+    }
+
+    fn dead_letter_doc_for_test() -> DeadLetterDoc {
+        DeadLetterDoc::new(
+            "my-logs".to_string(),
+            "my-source".to_string(),
+            None,
+            DeadLetterErrorKind::Parse,
+            "EOF while parsing an object".to_string(),
+            b"{",
+        )
+    }
+
+    #[tokio::test]
+    async fn test_get_dead_letter_docs() {
+        let mut mock_search_service = MockSearchService::new();
+        mock_search_service
+            .expect_root_search()
+            .returning(|search_request| {
+                assert_eq!(
+                    search_request.index_id_patterns,
+                    vec!["my-logs-dead-letter".to_string()]
+                );
+                assert_eq!(search_request.max_hits, 5);
+                let query_ast: QueryAst = serde_json::from_str(&search_request.query_ast).unwrap();
+                assert_eq!(query_ast, dead_letter_query_ast("my-logs", "my-source"));
+                Ok(quickwit_proto::search::SearchResponse {
+                    num_hits: 1,
+                    hits: vec![Hit {
+                        json: serde_json::to_string(&dead_letter_doc_for_test()).unwrap(),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                })
+            });
+        let dead_letter_handlers = dead_letter_api_handlers(
+            mock_metastore(Some(DeadLetterConfig::Index {
+                index_id: "my-logs-dead-letter".to_string(),
+            })),
+            Arc::new(mock_search_service),
+            IngestServiceClient::from(IngestServiceClient::mock()),
+        )
+        .recover(recover_fn);
+        let resp = warp::test::request()
+            .path("/indexes/my-logs/sources/my-source/dead-letter?max_hits=5")
+            .reply(&dead_letter_handlers)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let search_response: DeadLetterSearchResponse =
+            serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(search_response.num_hits, 1);
+        assert_eq!(search_response.docs[0].payload.as_deref(), Some("{"));
+    }
+
+    #[tokio::test]
+    async fn test_get_dead_letter_docs_requires_dead_letter_index() {
+        for dead_letter_opt in [None, Some(DeadLetterConfig::Storage)] {
+            let dead_letter_handlers = dead_letter_api_handlers(
+                mock_metastore(dead_letter_opt),
+                Arc::new(MockSearchService::new()),
+                IngestServiceClient::from(IngestServiceClient::mock()),
+            )
+            .recover(recover_fn);
+            let resp = warp::test::request()
+                .path("/indexes/my-logs/sources/my-source/dead-letter")
+                .reply(&dead_letter_handlers)
+                .await;
+            assert_eq!(resp.status(), 400);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_replay_dead_letter_docs_requires_ingest_api_processing() {
+        let mut source_config = SourceConfig::ingest_api_default();
+        source_config.source_id = "my-source".to_string();
+        source_config.dead_letter_opt = Some(DeadLetterConfig::Index {
+            index_id: "my-logs-dead-letter".to_string(),
+        });
+        source_config.transform_config = Some(TransformConfig::new(
+            ".message = downcase(string!(.message))".to_string(),
+            None,
+        ));
+        let dead_letter_handlers = dead_letter_api_handlers(
+            mock_metastore_with_source(source_config),
+            Arc::new(MockSearchService::new()),
+            IngestServiceClient::from(IngestServiceClient::mock()),
+        )
+        .recover(recover_fn);
+        let resp = warp::test::request()
+            .path("/indexes/my-logs/sources/my-source/dead-letter/replay")
+            .method("POST")
+            .reply(&dead_letter_handlers)
+            .await;
+        assert_eq!(resp.status(), 400);
+        let resp_json: serde_json::Value = serde_json::from_slice(resp.body()).unwrap();
+        assert!(resp_json["message"]
+            .as_str()
+            .unwrap()
+            .contains("different transforms"));
+    }
+}
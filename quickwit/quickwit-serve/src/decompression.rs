@@ -0,0 +1,253 @@
+// Copyright (C) 2023 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::io::Read;
+
+use bytes::Bytes;
+use flate2::read::{MultiGzDecoder, ZlibDecoder};
+use hyper::header::CONTENT_ENCODING;
+use thiserror::Error;
+use warp::{Filter, Rejection};
+
+use crate::SERVE_METRICS;
+
+#[derive(Debug, Error)]
+pub(crate) enum DecompressionError {
+    #[error(
+        "unsupported content encoding `{0}`, supported encodings are `gzip`, `zstd`, and `deflate`"
+    )]
+    UnsupportedEncoding(String),
+    #[error("failed to decompress `{encoding}` request body: {message}")]
+    InvalidBody { encoding: String, message: String },
+    #[error("decompressed request body exceeds the limit of {limit} bytes")]
+    PayloadTooLarge { limit: u64 },
+    #[error("internal error: {0}")]
+    Internal(String),
+}
+
+impl warp::reject::Reject for DecompressionError {}
+
+/// Extracts the request body, decompressed according to its `Content-Encoding` header.
+///
+/// `content_length_limit` applies to the size of the body before and after decompression, so
+/// that small compressed payloads cannot expand into arbitrarily large ones. Bodies are
+/// decompressed on a blocking thread, so as not to stall the runtime.
+pub(crate) fn get_body_bytes(
+    content_length_limit: u64,
+) -> impl Filter<Extract = (Bytes,), Error = Rejection> + Clone {
+    warp::header::optional::<String>(CONTENT_ENCODING.as_str())
+        .and(warp::body::content_length_limit(content_length_limit))
+        .and(warp::body::bytes())
+        .and_then(
+            move |content_encoding_opt: Option<String>, body: Bytes| async move {
+                let Some(content_encoding) = content_encoding_opt else {
+                    return Ok(body);
+                };
+                let decompressed_body = tokio::task::spawn_blocking(move || {
+                    decompress_body(&content_encoding, body, content_length_limit)
+                })
+                .await
+                .map_err(|join_error| {
+                    warp::reject::custom(DecompressionError::Internal(join_error.to_string()))
+                })?
+                .map_err(warp::reject::custom)?;
+                Ok(decompressed_body)
+            },
+        )
+}
+
+fn decompress_body(
+    content_encoding: &str,
+    mut body: Bytes,
+    content_length_limit: u64,
+) -> Result<Bytes, DecompressionError> {
+    // Encodings are listed in the order in which they were applied.
+    for encoding in content_encoding.rsplit(',') {
+        let encoding = encoding.trim().to_ascii_lowercase();
+        let decompressed_body = match encoding.as_str() {
+            "identity" => continue,
+            "gzip" | "x-gzip" => decompress(
+                MultiGzDecoder::new(body.as_ref()),
+                &encoding,
+                content_length_limit,
+            )?,
+            "zstd" => {
+                let decoder = zstd::stream::read::Decoder::with_buffer(body.as_ref())
+                    .map_err(|error| invalid_body(&encoding, error))?;
+                decompress(decoder, &encoding, content_length_limit)?
+            }
+            "deflate" => decompress(
+                ZlibDecoder::new(body.as_ref()),
+                &encoding,
+                content_length_limit,
+            )?,
+            _ => return Err(DecompressionError::UnsupportedEncoding(encoding)),
+        };
+        SERVE_METRICS
+            .request_body_compressed_bytes_total
+            .with_label_values([&encoding])
+            .inc_by(body.len() as u64);
+        SERVE_METRICS
+            .request_body_decompressed_bytes_total
+            .with_label_values([&encoding])
+            .inc_by(decompressed_body.len() as u64);
+        body = decompressed_body;
+    }
+    Ok(body)
+}
+
+fn decompress(
+    decoder: impl Read,
+    encoding: &str,
+    content_length_limit: u64,
+) -> Result<Bytes, DecompressionError> {
+    let mut decompressed_body = Vec::new();
+    // Reading one byte past the limit is enough to tell that the body is too large, without
+    // decompressing the rest of it.
+    decoder
+        .take(content_length_limit + 1)
+        .read_to_end(&mut decompressed_body)
+        .map_err(|error| invalid_body(encoding, error))?;
+
+    if decompressed_body.len() as u64 > content_length_limit {
+        return Err(DecompressionError::PayloadTooLarge {
+            limit: content_length_limit,
+        });
+    }
+    Ok(Bytes::from(decompressed_body))
+}
+
+fn invalid_body(encoding: &str, error: std::io::Error) -> DecompressionError {
+    DecompressionError::InvalidBody {
+        encoding: encoding.to_string(),
+        message: error.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::write::{GzEncoder, ZlibEncoder};
+    use flate2::Compression;
+
+    use super::*;
+
+    const PAYLOAD: &[u8] = b"{\"body\": \"foo\"}\n{\"body\": \"bar\"}\n";
+
+    fn gzip(payload: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(payload).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn deflate(payload: &[u8]) -> Vec<u8> {
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(payload).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    async fn extract_body(
+        content_encoding_opt: Option<&str>,
+        body: Vec<u8>,
+        content_length_limit: u64,
+    ) -> Result<Bytes, Rejection> {
+        let mut request = warp::test::request().method("POST").body(body);
+        if let Some(content_encoding) = content_encoding_opt {
+            request = request.header("content-encoding", content_encoding);
+        }
+        request.filter(&get_body_bytes(content_length_limit)).await
+    }
+
+    #[tokio::test]
+    async fn test_get_body_bytes_uncompressed() {
+        let body = extract_body(None, PAYLOAD.to_vec(), 1024).await.unwrap();
+        assert_eq!(body, PAYLOAD);
+
+        let body = extract_body(Some("identity"), PAYLOAD.to_vec(), 1024)
+            .await
+            .unwrap();
+        assert_eq!(body, PAYLOAD);
+    }
+
+    #[tokio::test]
+    async fn test_get_body_bytes_compressed() {
+        let body = extract_body(Some("gzip"), gzip(PAYLOAD), 1024)
+            .await
+            .unwrap();
+        assert_eq!(body, PAYLOAD);
+
+        let body = extract_body(Some("GZIP"), gzip(PAYLOAD), 1024)
+            .await
+            .unwrap();
+        assert_eq!(body, PAYLOAD);
+
+        let body = extract_body(Some("zstd"), zstd::encode_all(PAYLOAD, 0).unwrap(), 1024)
+            .await
+            .unwrap();
+        assert_eq!(body, PAYLOAD);
+
+        let body = extract_body(Some("deflate"), deflate(PAYLOAD), 1024)
+            .await
+            .unwrap();
+        assert_eq!(body, PAYLOAD);
+
+        let body = extract_body(Some("deflate, gzip"), gzip(&deflate(PAYLOAD)), 1024)
+            .await
+            .unwrap();
+        assert_eq!(body, PAYLOAD);
+    }
+
+    #[tokio::test]
+    async fn test_get_body_bytes_errors() {
+        let rejection = extract_body(Some("br"), PAYLOAD.to_vec(), 1024)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            rejection.find::<DecompressionError>().unwrap(),
+            DecompressionError::UnsupportedEncoding(encoding) if encoding == "br"
+        ));
+
+        let rejection = extract_body(Some("gzip"), PAYLOAD.to_vec(), 1024)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            rejection.find::<DecompressionError>().unwrap(),
+            DecompressionError::InvalidBody { .. }
+        ));
+
+        // The limit applies to the decompressed body, even if the compressed body fits in it.
+        let large_payload = vec![b'a'; 10_000];
+        let compressed_payload = gzip(&large_payload);
+        assert!(compressed_payload.len() < 1024);
+
+        let rejection = extract_body(Some("gzip"), compressed_payload.clone(), 1024)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            rejection.find::<DecompressionError>().unwrap(),
+            DecompressionError::PayloadTooLarge { limit: 1024 }
+        ));
+
+        let body = extract_body(Some("gzip"), compressed_payload, 10_000)
+            .await
+            .unwrap();
+        assert_eq!(body.len(), 10_000);
+    }
+}
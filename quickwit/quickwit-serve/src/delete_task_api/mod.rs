@@ -19,4 +19,5 @@
 
 mod handler;
 
+pub(crate) use handler::create_delete_task;
 pub use handler::{delete_task_api_handlers, DeleteTaskApi};
@@ -135,6 +135,7 @@ mod tests {
     use quickwit_ingest::{
         FetchRequest, IngestResponse, IngestServiceClient, SuggestTruncateRequest,
     };
+    use quickwit_proto::metastore::MetastoreServiceClient;
     use quickwit_search::MockSearchService;
 
     use crate::elastic_search_api::elastic_api_handlers;
@@ -146,7 +147,12 @@ mod tests {
         let search_service = Arc::new(MockSearchService::new());
         let (universe, _temp_dir, ingest_service, _) =
             setup_ingest_service(&["my-index"], &IngestApiConfig::default()).await;
-        let elastic_api_handlers = elastic_api_handlers(config, search_service, ingest_service);
+        let elastic_api_handlers = elastic_api_handlers(
+            config,
+            MetastoreServiceClient::from(MetastoreServiceClient::mock()),
+            search_service,
+            ingest_service,
+        );
         let payload = r#"
             { "create" : { "_index" : "my-index", "_id" : "1"} }
             {"id": 1, "message": "push"}
@@ -168,7 +174,12 @@ mod tests {
         let search_service = Arc::new(MockSearchService::new());
         let (universe, _temp_dir, ingest_service, _) =
             setup_ingest_service(&["my-index-1", "my-index-2"], &IngestApiConfig::default()).await;
-        let elastic_api_handlers = elastic_api_handlers(config, search_service, ingest_service);
+        let elastic_api_handlers = elastic_api_handlers(
+            config,
+            MetastoreServiceClient::from(MetastoreServiceClient::mock()),
+            search_service,
+            ingest_service,
+        );
         let payload = r#"
             { "create" : { "_index" : "my-index-1", "_id" : "1"} }
             {"id": 1, "message": "push"}
@@ -194,7 +205,12 @@ mod tests {
         let search_service = Arc::new(MockSearchService::new());
         let (universe, _temp_dir, ingest_service, _) =
             setup_ingest_service(&["my-index-1", "my-index-2"], &IngestApiConfig::default()).await;
-        let elastic_api_handlers = elastic_api_handlers(config, search_service, ingest_service);
+        let elastic_api_handlers = elastic_api_handlers(
+            config,
+            MetastoreServiceClient::from(MetastoreServiceClient::mock()),
+            search_service,
+            ingest_service,
+        );
         let payload = r#"
             { "create" : { "_index" : "my-index-1", "_id" : "1"} }
             {"id": 1, "message": "push"}
@@ -220,7 +236,12 @@ mod tests {
         let search_service = Arc::new(MockSearchService::new());
         let (universe, _temp_dir, ingest_service, ingest_service_mailbox) =
             setup_ingest_service(&["my-index-1", "my-index-2"], &IngestApiConfig::default()).await;
-        let elastic_api_handlers = elastic_api_handlers(config, search_service, ingest_service);
+        let elastic_api_handlers = elastic_api_handlers(
+            config,
+            MetastoreServiceClient::from(MetastoreServiceClient::mock()),
+            search_service,
+            ingest_service,
+        );
         let payload = r#"
             { "create" : { "_index" : "my-index-1", "_id" : "1"} }
             {"id": 1, "message": "push"}
@@ -297,7 +318,12 @@ mod tests {
         let search_service = Arc::new(MockSearchService::new());
         let (universe, _temp_dir, ingest_service, ingest_service_mailbox) =
             setup_ingest_service(&["my-index-1", "my-index-2"], &IngestApiConfig::default()).await;
-        let elastic_api_handlers = elastic_api_handlers(config, search_service, ingest_service);
+        let elastic_api_handlers = elastic_api_handlers(
+            config,
+            MetastoreServiceClient::from(MetastoreServiceClient::mock()),
+            search_service,
+            ingest_service,
+        );
         let payload = r#"
             { "create" : { "_index" : "my-index-1", "_id" : "1"} }
             {"id": 1, "message": "push"}
@@ -372,7 +398,12 @@ mod tests {
         let config = Arc::new(NodeConfig::for_test());
         let search_service = Arc::new(MockSearchService::new());
         let ingest_service = IngestServiceClient::from(IngestServiceClient::mock());
-        let elastic_api_handlers = elastic_api_handlers(config, search_service, ingest_service);
+        let elastic_api_handlers = elastic_api_handlers(
+            config,
+            MetastoreServiceClient::from(MetastoreServiceClient::mock()),
+            search_service,
+            ingest_service,
+        );
         let payload = r#"
             {"create": {"_index": "my-index", "_id": "1"},}
             {"id": 1, "message": "my-doc"}"#;
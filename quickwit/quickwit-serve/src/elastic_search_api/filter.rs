@@ -24,8 +24,10 @@ use warp::reject::LengthRequired;
 use warp::{Filter, Rejection};
 
 use super::model::MultiSearchQueryParams;
+use crate::decompression::get_body_bytes;
 use crate::elastic_search_api::model::{
-    ElasticIngestOptions, ScrollQueryParams, SearchBody, SearchQueryParams,
+    DeleteByQueryBody, ElasticIngestOptions, FieldCapabilityQueryParams, ScrollQueryParams,
+    SearchBody, SearchQueryParams,
 };
 use crate::search_api::extract_index_id_patterns;
 
@@ -70,10 +72,7 @@ pub(crate) fn elastic_bulk_filter(
 ) -> impl Filter<Extract = (Bytes, ElasticIngestOptions), Error = Rejection> + Clone {
     warp::path!("_elastic" / "_bulk")
         .and(warp::post())
-        .and(warp::body::content_length_limit(
-            CONTENT_LENGTH_LIMIT.get_bytes(),
-        ))
-        .and(warp::body::bytes())
+        .and(get_body_bytes(CONTENT_LENGTH_LIMIT.get_bytes()))
         .and(serde_qs::warp::query(serde_qs::Config::default()))
 }
 
@@ -111,6 +110,85 @@ pub(crate) fn elastic_index_search_filter(
         .and(json_or_empty())
 }
 
+#[utoipa::path(post, tag = "Search", path = "/{index}/_async_search")]
+pub(crate) fn elastic_index_async_search_filter(
+) -> impl Filter<Extract = (Vec<String>, SearchQueryParams, SearchBody), Error = Rejection> + Clone
+{
+    warp::path!("_elastic" / String / "_async_search")
+        .and_then(extract_index_id_patterns)
+        .and(warp::post())
+        .and(serde_qs::warp::query(serde_qs::Config::default()))
+        .and(json_or_empty())
+}
+
+#[utoipa::path(get, tag = "Search", path = "/_async_search/{id}")]
+pub(crate) fn elastic_get_async_search_filter(
+) -> impl Filter<Extract = (String,), Error = Rejection> + Clone {
+    warp::path!("_elastic" / "_async_search" / String).and(warp::get())
+}
+
+#[utoipa::path(delete, tag = "Search", path = "/_async_search/{id}")]
+pub(crate) fn elastic_delete_async_search_filter(
+) -> impl Filter<Extract = (String,), Error = Rejection> + Clone {
+    warp::path!("_elastic" / "_async_search" / String).and(warp::delete())
+}
+
+#[utoipa::path(get, tag = "Search", path = "/{index}/_count")]
+pub(crate) fn elastic_index_count_filter(
+) -> impl Filter<Extract = (Vec<String>, SearchQueryParams, SearchBody), Error = Rejection> + Clone
+{
+    warp::path!("_elastic" / String / "_count")
+        .and_then(extract_index_id_patterns)
+        .and(warp::get().or(warp::post()).unify())
+        .and(serde_qs::warp::query(serde_qs::Config::default()))
+        .and(json_or_empty())
+}
+
+#[utoipa::path(get, tag = "Metadata", path = "/_cat/indices/{index}")]
+pub(crate) fn elastic_cat_indices_filter(
+) -> impl Filter<Extract = (Vec<String>,), Error = Rejection> + Clone {
+    let all_indexes = warp::path!("_elastic" / "_cat" / "indices").map(|| vec!["*".to_string()]);
+    let some_indexes =
+        warp::path!("_elastic" / "_cat" / "indices" / String).and_then(extract_index_id_patterns);
+    all_indexes.or(some_indexes).unify().and(warp::get())
+}
+
+#[utoipa::path(get, tag = "Metadata", path = "/_resolve/index/{index}")]
+pub(crate) fn elastic_resolve_index_filter(
+) -> impl Filter<Extract = (Vec<String>,), Error = Rejection> + Clone {
+    warp::path!("_elastic" / "_resolve" / "index" / String)
+        .and_then(extract_index_id_patterns)
+        .and(warp::get())
+}
+
+#[utoipa::path(post, tag = "Delete Tasks", path = "/{index}/_delete_by_query")]
+pub(crate) fn elastic_delete_by_query_filter(
+) -> impl Filter<Extract = (String, DeleteByQueryBody), Error = Rejection> + Clone {
+    warp::path!("_elastic" / String / "_delete_by_query")
+        .and(warp::post())
+        .and(warp::body::content_length_limit(
+            BODY_LENGTH_LIMIT.get_bytes(),
+        ))
+        .and(warp::body::json())
+}
+
+#[utoipa::path(get, tag = "Metadata", path = "/{index}/_field_caps")]
+pub(crate) fn elastic_index_field_capabilities_filter(
+) -> impl Filter<Extract = (Vec<String>, FieldCapabilityQueryParams), Error = Rejection> + Clone {
+    warp::path!("_elastic" / String / "_field_caps")
+        .and_then(extract_index_id_patterns)
+        .and(warp::get().or(warp::post()).unify())
+        .and(serde_qs::warp::query(serde_qs::Config::default()))
+}
+
+#[utoipa::path(get, tag = "Metadata", path = "/{index}/_mapping")]
+pub(crate) fn elastic_index_mapping_filter(
+) -> impl Filter<Extract = (Vec<String>,), Error = Rejection> + Clone {
+    warp::path!("_elastic" / String / "_mapping")
+        .and_then(extract_index_id_patterns)
+        .and(warp::get())
+}
+
 #[utoipa::path(
     post,
     tag = "Ingest",
@@ -127,10 +205,7 @@ pub(crate) fn elastic_index_bulk_filter(
 ) -> impl Filter<Extract = (String, Bytes, ElasticIngestOptions), Error = Rejection> + Clone {
     warp::path!("_elastic" / String / "_bulk")
         .and(warp::post())
-        .and(warp::body::content_length_limit(
-            CONTENT_LENGTH_LIMIT.get_bytes(),
-        ))
-        .and(warp::body::bytes())
+        .and(get_body_bytes(CONTENT_LENGTH_LIMIT.get_bytes()))
         .and(serde_qs::warp::query::<ElasticIngestOptions>(
             serde_qs::Config::default(),
         ))
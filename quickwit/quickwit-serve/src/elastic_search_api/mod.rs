@@ -28,10 +28,16 @@ use bulk::{es_compat_bulk_handler, es_compat_index_bulk_handler};
 pub use filter::ElasticCompatibleApi;
 use quickwit_config::NodeConfig;
 use quickwit_ingest::IngestServiceClient;
+use quickwit_proto::metastore::MetastoreServiceClient;
 use quickwit_search::SearchService;
 use rest_handler::{
-    es_compat_cluster_info_handler, es_compat_index_multi_search_handler,
-    es_compat_index_search_handler, es_compat_scroll_handler, es_compat_search_handler,
+    es_compat_cat_indices_handler, es_compat_cluster_info_handler,
+    es_compat_delete_async_search_handler, es_compat_delete_by_query_handler,
+    es_compat_get_async_search_handler, es_compat_index_async_search_handler,
+    es_compat_index_count_handler, es_compat_index_field_capabilities_handler,
+    es_compat_index_mapping_handler, es_compat_index_multi_search_handler,
+    es_compat_index_search_handler, es_compat_resolve_index_handler, es_compat_scroll_handler,
+    es_compat_search_handler,
 };
 use serde::{Deserialize, Serialize};
 use warp::{Filter, Rejection};
@@ -44,14 +50,26 @@ use crate::BuildInfo;
 /// should be registered.
 pub fn elastic_api_handlers(
     node_config: Arc<NodeConfig>,
+    metastore: MetastoreServiceClient,
     search_service: Arc<dyn SearchService>,
     ingest_service: IngestServiceClient,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
     es_compat_cluster_info_handler(node_config, BuildInfo::get())
         .or(es_compat_search_handler(search_service.clone()))
         .or(es_compat_index_search_handler(search_service.clone()))
+        .or(es_compat_index_count_handler(search_service.clone()))
+        .or(es_compat_cat_indices_handler(metastore.clone()))
+        .or(es_compat_resolve_index_handler(metastore.clone()))
+        .or(es_compat_delete_by_query_handler(metastore))
         .or(es_compat_scroll_handler(search_service.clone()))
-        .or(es_compat_index_multi_search_handler(search_service))
+        .or(es_compat_index_field_capabilities_handler(
+            search_service.clone(),
+        ))
+        .or(es_compat_index_mapping_handler(search_service.clone()))
+        .or(es_compat_index_multi_search_handler(search_service.clone()))
+        .or(es_compat_index_async_search_handler(search_service.clone()))
+        .or(es_compat_get_async_search_handler(search_service.clone()))
+        .or(es_compat_delete_async_search_handler(search_service))
         .or(es_compat_bulk_handler(ingest_service.clone()))
         .or(es_compat_index_bulk_handler(ingest_service))
     // Register newly created handlers here.
@@ -93,8 +111,16 @@ mod tests {
     use assert_json_diff::assert_json_include;
     use mockall::predicate;
     use quickwit_config::NodeConfig;
+    use quickwit_indexing::{MockSplitBuilder, TestSandbox};
     use quickwit_ingest::{IngestApiService, IngestServiceClient};
-    use quickwit_search::MockSearchService;
+    use quickwit_metastore::{
+        IndexMetadata, ListIndexesMetadataResponseExt, ListSplitsResponseExt,
+    };
+    use quickwit_proto::metastore::{
+        ListDeleteTasksRequest, ListIndexesMetadataResponse, ListSplitsResponse, MetastoreService,
+        MetastoreServiceClient,
+    };
+    use quickwit_search::{AsyncSearchState, AsyncSearchStatus, MockSearchService};
     use serde_json::Value as JsonValue;
     use warp::Filter;
 
@@ -104,6 +130,10 @@ mod tests {
     use crate::rest::recover_fn;
     use crate::BuildInfo;
 
+    fn metastore_client() -> MetastoreServiceClient {
+        MetastoreServiceClient::from(MetastoreServiceClient::mock())
+    }
+
     fn ingest_service_client() -> IngestServiceClient {
         let universe = quickwit_actors::Universe::new();
         let (ingest_service_mailbox, _) = universe.create_test_mailbox::<IngestApiService>();
@@ -129,6 +159,7 @@ mod tests {
             .returning(|_| Ok(Default::default()));
         let es_search_api_handler = super::elastic_api_handlers(
             config,
+            metastore_client(),
             Arc::new(mock_search_service),
             ingest_service_client(),
         );
@@ -175,6 +206,7 @@ mod tests {
             });
         let es_search_api_handler = super::elastic_api_handlers(
             config,
+            metastore_client(),
             Arc::new(mock_search_service),
             ingest_service_client(),
         );
@@ -210,6 +242,7 @@ mod tests {
         let mock_search_service = MockSearchService::new();
         let es_search_api_handler = super::elastic_api_handlers(
             config,
+            metastore_client(),
             Arc::new(mock_search_service),
             ingest_service_client(),
         );
@@ -238,6 +271,7 @@ mod tests {
         let mock_search_service = MockSearchService::new();
         let es_search_api_handler = super::elastic_api_handlers(
             config,
+            metastore_client(),
             Arc::new(mock_search_service),
             ingest_service_client(),
         );
@@ -266,6 +300,7 @@ mod tests {
         let mock_search_service = MockSearchService::new();
         let es_search_api_handler = super::elastic_api_handlers(
             config,
+            metastore_client(),
             Arc::new(mock_search_service),
             ingest_service_client(),
         );
@@ -293,6 +328,7 @@ mod tests {
         let mock_search_service = MockSearchService::new();
         let es_search_api_handler = super::elastic_api_handlers(
             config,
+            metastore_client(),
             Arc::new(mock_search_service),
             ingest_service_client(),
         );
@@ -333,6 +369,7 @@ mod tests {
             });
         let es_search_api_handler = super::elastic_api_handlers(
             config,
+            metastore_client(),
             Arc::new(mock_search_service),
             ingest_service_client(),
         );
@@ -349,6 +386,246 @@ mod tests {
         assert_eq!(resp.status(), 200);
     }
 
+    #[tokio::test]
+    async fn test_field_capabilities_and_mapping_api() {
+        let config = Arc::new(NodeConfig::for_test());
+        let mut mock_search_service = MockSearchService::new();
+        mock_search_service
+            .expect_root_list_fields()
+            .with(predicate::function(
+                |list_fields_request: &quickwit_proto::search::ListFieldsRequest| {
+                    list_fields_request.index_id_patterns == vec!["logs-*".to_string()]
+                },
+            ))
+            .times(2)
+            .returning(|_| {
+                Ok(quickwit_proto::search::ListFieldsResponse {
+                    fields: vec![quickwit_proto::search::ListFieldsEntry {
+                        field_name: "severity".to_string(),
+                        field_type: quickwit_proto::search::ListFieldType::Str as i32,
+                        searchable: true,
+                        aggregatable: true,
+                        index_ids: vec!["logs-1".to_string()],
+                    }],
+                })
+            });
+        let es_search_api_handler = super::elastic_api_handlers(
+            config,
+            metastore_client(),
+            Arc::new(mock_search_service),
+            ingest_service_client(),
+        );
+        let resp = warp::test::request()
+            .path("/_elastic/logs-*/_field_caps?fields=sev*")
+            .reply(&es_search_api_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let resp_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        let expected_response_json = serde_json::json!({
+            "indices": ["logs-1"],
+            "fields": {
+                "severity": {
+                    "keyword": {
+                        "type": "keyword",
+                        "metadata_field": false,
+                        "searchable": true,
+                        "aggregatable": true,
+                    }
+                }
+            }
+        });
+        assert_eq!(resp_json, expected_response_json);
+
+        let resp = warp::test::request()
+            .path("/_elastic/logs-*/_mapping")
+            .reply(&es_search_api_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let resp_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        let expected_response_json = serde_json::json!({
+            "logs-1": {
+                "mappings": {
+                    "properties": {
+                        "severity": {"type": "keyword"}
+                    }
+                }
+            }
+        });
+        assert_eq!(resp_json, expected_response_json);
+    }
+
+    #[tokio::test]
+    async fn test_count_api() {
+        let config = Arc::new(NodeConfig::for_test());
+        let mut mock_search_service = MockSearchService::new();
+        mock_search_service
+            .expect_root_search()
+            .with(predicate::function(
+                |search_request: &quickwit_proto::search::SearchRequest| {
+                    search_request.index_id_patterns == vec!["index-1".to_string()]
+                        && search_request.max_hits == 0
+                        && search_request.count_hits()
+                            == quickwit_proto::search::CountHits::CountAll
+                        && search_request.aggregation_request.is_none()
+                },
+            ))
+            .returning(|_| {
+                Ok(quickwit_proto::search::SearchResponse {
+                    num_hits: 42,
+                    ..Default::default()
+                })
+            });
+        let es_search_api_handler = super::elastic_api_handlers(
+            config,
+            metastore_client(),
+            Arc::new(mock_search_service),
+            ingest_service_client(),
+        );
+        let resp = warp::test::request()
+            .path("/_elastic/index-1/_count")
+            .method("POST")
+            .body(r#"{"query": {"term": {"severity": "ERROR"}}}"#)
+            .reply(&es_search_api_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let resp_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(resp_json, serde_json::json!({"count": 42}));
+    }
+
+    #[tokio::test]
+    async fn test_cat_indices_and_resolve_index_api() {
+        let config = Arc::new(NodeConfig::for_test());
+        let index_metadata_1 = IndexMetadata::for_test("logs-1", "ram:///indexes/logs-1");
+        let index_metadata_2 = IndexMetadata::for_test("logs-2", "ram:///indexes/logs-2");
+        let index_uid_1 = index_metadata_1.index_uid.clone();
+        let incarnation_id_1 = index_uid_1.incarnation_id().to_string();
+        let mut mock_metastore = MetastoreServiceClient::mock();
+        mock_metastore.expect_list_indexes_metadata().returning(
+            move |list_indexes_metadata_request| {
+                assert_eq!(
+                    list_indexes_metadata_request.index_id_patterns,
+                    vec!["logs-*".to_string()]
+                );
+                Ok(ListIndexesMetadataResponse::try_from_indexes_metadata(vec![
+                    index_metadata_2.clone(),
+                    index_metadata_1.clone(),
+                ])
+                .unwrap())
+            },
+        );
+        mock_metastore.expect_list_splits().return_once(move |_| {
+            let splits = vec![
+                MockSplitBuilder::new("split-1")
+                    .with_index_uid(&index_uid_1)
+                    .build(),
+                MockSplitBuilder::new("split-2")
+                    .with_index_uid(&index_uid_1)
+                    .build(),
+            ];
+            Ok(ListSplitsResponse::try_from_splits(splits).unwrap())
+        });
+        let es_search_api_handler = super::elastic_api_handlers(
+            config,
+            MetastoreServiceClient::from(mock_metastore),
+            Arc::new(MockSearchService::new()),
+            ingest_service_client(),
+        );
+        let resp = warp::test::request()
+            .path("/_elastic/_cat/indices/logs-*")
+            .reply(&es_search_api_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let resp_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        let expected_response_json = serde_json::json!([
+            {
+                "health": "green",
+                "status": "open",
+                "index": "logs-1",
+                "uuid": incarnation_id_1,
+                "docs.count": "20",
+                "store.size": "1600",
+            },
+            {
+                "index": "logs-2",
+                "docs.count": "0",
+                "store.size": "0",
+            }
+        ]);
+        assert_json_include!(actual: resp_json, expected: expected_response_json);
+
+        let resp = warp::test::request()
+            .path("/_elastic/_resolve/index/logs-*")
+            .reply(&es_search_api_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let resp_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        let expected_response_json = serde_json::json!({
+            "indices": [
+                {"name": "logs-1", "attributes": ["open"]},
+                {"name": "logs-2", "attributes": ["open"]},
+            ],
+            "aliases": [],
+            "data_streams": [],
+        });
+        assert_eq!(resp_json, expected_response_json);
+    }
+
+    #[tokio::test]
+    async fn test_delete_by_query_api() {
+        let index_id = "test-es-delete-by-query";
+        let doc_mapping_yaml = r#"
+            field_mappings:
+              - name: body
+                type: text
+              - name: user_id
+                type: u64
+            mode: lenient
+        "#;
+        let test_sandbox = TestSandbox::create(index_id, doc_mapping_yaml, "{}", &["body"])
+            .await
+            .unwrap();
+        let es_search_api_handler = super::elastic_api_handlers(
+            Arc::new(NodeConfig::for_test()),
+            test_sandbox.metastore(),
+            Arc::new(MockSearchService::new()),
+            ingest_service_client(),
+        );
+        let resp = warp::test::request()
+            .path("/_elastic/test-es-delete-by-query/_delete_by_query")
+            .method("POST")
+            .body(r#"{"query": {"term": {"user_id": {"value": "42"}}}}"#)
+            .reply(&es_search_api_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let resp_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(
+            resp_json,
+            serde_json::json!({"task": "test-es-delete-by-query:1"})
+        );
+        let delete_tasks = test_sandbox
+            .metastore()
+            .list_delete_tasks(ListDeleteTasksRequest::new(test_sandbox.index_uid(), 0))
+            .await
+            .unwrap()
+            .delete_tasks;
+        assert_eq!(delete_tasks.len(), 1);
+        let delete_query = delete_tasks[0].delete_query.as_ref().unwrap();
+        assert_eq!(
+            delete_query.query_ast,
+            r#"{"type":"term","field":"user_id","value":"42"}"#
+        );
+
+        // The query is validated against the doc mapping.
+        let resp = warp::test::request()
+            .path("/_elastic/test-es-delete-by-query/_delete_by_query")
+            .method("POST")
+            .body(r#"{"query": {"term": {"unknown_field": {"value": "42"}}}}"#)
+            .reply(&es_search_api_handler)
+            .await;
+        assert_eq!(resp.status(), 400);
+        test_sandbox.assert_quit().await;
+    }
+
     #[tokio::test]
     async fn test_es_compat_cluster_info_handler() {
         let build_info = BuildInfo::get();
@@ -387,4 +664,100 @@ mod tests {
             .await;
         assert_eq!(resp.status(), 200);
     }
+
+    #[tokio::test]
+    async fn test_es_compat_async_search_api() {
+        let config = Arc::new(NodeConfig::for_test());
+        let mut mock_search_service = MockSearchService::new();
+        mock_search_service
+            .expect_submit_async_search()
+            .with(predicate::function(
+                |submit_request: &quickwit_search::SubmitAsyncSearchRequest| {
+                    submit_request.search_request.index_id_patterns == vec!["index-1".to_string()]
+                        && submit_request.wait_for_completion_timeout
+                            == std::time::Duration::from_secs(2)
+                        && submit_request.keep_alive == std::time::Duration::from_secs(600)
+                },
+            ))
+            .returning(|_| {
+                Ok(AsyncSearchStatus {
+                    id: "01HAV29D4XY3D462FS3D8K5Q2H".to_string(),
+                    state: AsyncSearchState::Running,
+                    start_time_millis: 1_000,
+                    expiration_time_millis: 601_000,
+                    completion_time_millis: None,
+                    num_splits_total: 4,
+                    num_splits_done: 1,
+                    response: Some(quickwit_proto::search::SearchResponse {
+                        num_hits: 10,
+                        ..Default::default()
+                    }),
+                    error: None,
+                })
+            });
+        mock_search_service
+            .expect_get_async_search()
+            .returning(|async_search_id| {
+                Ok(AsyncSearchStatus {
+                    id: async_search_id,
+                    state: AsyncSearchState::Cancelled,
+                    start_time_millis: 1_000,
+                    expiration_time_millis: 601_000,
+                    completion_time_millis: Some(2_000),
+                    num_splits_total: 4,
+                    num_splits_done: 1,
+                    response: None,
+                    error: None,
+                })
+            });
+        mock_search_service
+            .expect_cancel_async_search()
+            .returning(|async_search_id| {
+                Err(quickwit_search::SearchError::AsyncSearchNotFound { async_search_id })
+            });
+        let es_search_api_handler = super::elastic_api_handlers(
+            config,
+            metastore_client(),
+            Arc::new(mock_search_service),
+            ingest_service_client(),
+        );
+        let resp = warp::test::request()
+            .path("/_elastic/index-1/_async_search?wait_for_completion_timeout=2s&keep_alive=10m")
+            .method("POST")
+            .body(r#"{"query":{"query_string":{"query":"test"}}}"#)
+            .reply(&es_search_api_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let resp_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        let expected_response_json = serde_json::json!({
+            "id": "01HAV29D4XY3D462FS3D8K5Q2H",
+            "is_partial": true,
+            "is_running": true,
+            "start_time_in_millis": 1_000,
+            "expiration_time_in_millis": 601_000,
+            "response": {
+                "hits": {
+                    "total": {
+                        "value": 10,
+                        "relation": "eq"
+                    }
+                }
+            }
+        });
+        assert_json_include!(actual: resp_json, expected: expected_response_json);
+
+        // A cancelled async search is not found, like a deleted Elasticsearch async search.
+        let resp = warp::test::request()
+            .path("/_elastic/_async_search/01HAV29D4XY3D462FS3D8K5Q2H")
+            .reply(&es_search_api_handler)
+            .await;
+        assert_eq!(resp.status(), 404);
+
+        let resp = warp::test::request()
+            .path("/_elastic/_async_search/01HAV29D4XY3D462FS3D8K5Q2H")
+            .method("DELETE")
+            .reply(&es_search_api_handler)
+            .await;
+        assert_eq!(resp.status(), 404);
+    }
 }
@@ -0,0 +1,39 @@
+// Copyright (C) 2023 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use elasticsearch_dsl::search::SearchResponse as ElasticSearchResponse;
+use serde::Serialize;
+
+/// Response of the `_async_search` endpoints.
+#[derive(Debug, Serialize)]
+pub struct ElasticAsyncSearchResponse {
+    pub id: String,
+    pub is_partial: bool,
+    pub is_running: bool,
+    pub start_time_in_millis: i64,
+    pub expiration_time_in_millis: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completion_time_in_millis: Option<i64>,
+    pub response: ElasticSearchResponse,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ElasticAsyncSearchDeleteResponse {
+    pub acknowledged: bool,
+}
@@ -0,0 +1,57 @@
+// Copyright (C) 2023 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use serde::{Deserialize, Serialize};
+
+/// Entry of the `_cat/indices` response. Like Elasticsearch, the numbers are reported as strings.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ElasticsearchCatIndexResponse {
+    pub health: String,
+    pub status: String,
+    pub index: String,
+    pub uuid: String,
+    pub pri: String,
+    pub rep: String,
+    #[serde(rename = "docs.count")]
+    pub docs_count: String,
+    #[serde(rename = "docs.deleted")]
+    pub docs_deleted: String,
+    /// Size of the published splits in bytes.
+    #[serde(rename = "store.size")]
+    pub store_size: String,
+    #[serde(rename = "pri.store.size")]
+    pub pri_store_size: String,
+}
+
+impl ElasticsearchCatIndexResponse {
+    pub fn new(index_id: String, uuid: String, num_docs: u64, num_bytes: u64) -> Self {
+        Self {
+            health: "green".to_string(),
+            status: "open".to_string(),
+            index: index_id,
+            uuid,
+            pri: "1".to_string(),
+            rep: "1".to_string(),
+            docs_count: num_docs.to_string(),
+            docs_deleted: "0".to_string(),
+            store_size: num_bytes.to_string(),
+            pri_store_size: num_bytes.to_string(),
+        }
+    }
+}
@@ -19,6 +19,7 @@
 
 use elasticsearch_dsl::search::ErrorCause;
 use hyper::StatusCode;
+use quickwit_janitor::error::JanitorError;
 use quickwit_proto::ServiceError;
 use quickwit_search::SearchError;
 use serde::{Deserialize, Serialize};
@@ -30,12 +31,12 @@ pub struct ElasticSearchError {
     pub error: ErrorCause,
 }
 
-impl From<SearchError> for ElasticSearchError {
-    fn from(search_error: SearchError) -> Self {
-        let status = search_error.error_code().to_http_status_code();
+impl ElasticSearchError {
+    fn from_service_error(service_error: impl ServiceError) -> Self {
+        let status = service_error.error_code().to_http_status_code();
         // Fill only reason field to keep it simple.
         let reason = ErrorCause {
-            reason: Some(search_error.to_string()),
+            reason: Some(service_error.to_string()),
             caused_by: None,
             root_cause: vec![],
             stack_trace: None,
@@ -49,3 +50,15 @@ impl From<SearchError> for ElasticSearchError {
         }
     }
 }
+
+impl From<SearchError> for ElasticSearchError {
+    fn from(search_error: SearchError) -> Self {
+        ElasticSearchError::from_service_error(search_error)
+    }
+}
+
+impl From<JanitorError> for ElasticSearchError {
+    fn from(janitor_error: JanitorError) -> Self {
+        ElasticSearchError::from_service_error(janitor_error)
+    }
+}
@@ -0,0 +1,333 @@
+// Copyright (C) 2023 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use quickwit_proto::search::{ListFieldType, ListFieldsEntry, ListFieldsResponse};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map as JsonMap, Value as JsonValue};
+
+use crate::simple_list::{from_simple_list, to_simple_list};
+
+#[serde_with::skip_serializing_none]
+#[derive(Default, Debug, Serialize, Deserialize)]
+pub struct FieldCapabilityQueryParams {
+    #[serde(serialize_with = "to_simple_list")]
+    #[serde(deserialize_with = "from_simple_list")]
+    #[serde(default)]
+    pub fields: Option<Vec<String>>,
+    #[serde(default)]
+    pub allow_no_indices: Option<bool>,
+    #[serde(default)]
+    pub ignore_unavailable: Option<bool>,
+    #[serde(default)]
+    pub include_unmapped: Option<bool>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct FieldCapabilityResponse {
+    pub indices: Vec<String>,
+    /// Capabilities per field name and Elasticsearch field type.
+    pub fields: BTreeMap<String, BTreeMap<String, FieldCapabilityEntry>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FieldCapabilityEntry {
+    #[serde(rename = "type")]
+    pub field_type: String,
+    pub metadata_field: bool,
+    pub searchable: bool,
+    pub aggregatable: bool,
+    /// Indexes in which the field has this type. Only set when the field does not have the same
+    /// type in all the indexes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub indices: Option<Vec<String>>,
+}
+
+/// Maps a Quickwit field type to the closest Elasticsearch field type. Aggregatable strings are
+/// reported as `keyword` and the other ones as `text`.
+fn es_field_type(field: &ListFieldsEntry) -> &'static str {
+    match field.field_type() {
+        ListFieldType::Str if field.aggregatable => "keyword",
+        ListFieldType::Str => "text",
+        ListFieldType::U64 => "unsigned_long",
+        ListFieldType::I64 => "long",
+        ListFieldType::F64 => "double",
+        ListFieldType::Bool => "boolean",
+        ListFieldType::Date => "date_nanos",
+        ListFieldType::Facet => "keyword",
+        ListFieldType::Bytes => "binary",
+        ListFieldType::IpAddr => "ip",
+        ListFieldType::Json => "object",
+    }
+}
+
+/// Matches a field name against an Elasticsearch field pattern, in which `*` matches any sequence
+/// of characters.
+fn field_matches_pattern(field_name: &str, pattern: &str) -> bool {
+    let Some((prefix, suffix)) = pattern.split_once('*') else {
+        return field_name == pattern;
+    };
+    let Some(remaining) = field_name.strip_prefix(prefix) else {
+        return false;
+    };
+    remaining
+        .char_indices()
+        .map(|(pos, _)| pos)
+        .chain([remaining.len()])
+        .any(|pos| field_matches_pattern(&remaining[pos..], suffix))
+}
+
+fn field_matches_patterns(field_name: &str, patterns_opt: Option<&[String]>) -> bool {
+    let Some(patterns) = patterns_opt else {
+        return true;
+    };
+    patterns
+        .iter()
+        .any(|pattern| field_matches_pattern(field_name, pattern))
+}
+
+pub fn convert_to_es_field_capabilities_response(
+    list_fields_response: ListFieldsResponse,
+    field_patterns_opt: Option<&[String]>,
+) -> FieldCapabilityResponse {
+    let indices: BTreeSet<&String> = list_fields_response
+        .fields
+        .iter()
+        .flat_map(|field| &field.index_ids)
+        .collect();
+    let num_indices = indices.len();
+    let mut response = FieldCapabilityResponse {
+        indices: indices.into_iter().cloned().collect(),
+        fields: BTreeMap::new(),
+    };
+    for field in &list_fields_response.fields {
+        if !field_matches_patterns(&field.field_name, field_patterns_opt) {
+            continue;
+        }
+        let field_type = es_field_type(field);
+        let field_capabilities = response.fields.entry(field.field_name.clone()).or_default();
+
+        if let Some(entry) = field_capabilities.get_mut(field_type) {
+            // Several Quickwit types can map to the same Elasticsearch type.
+            entry.searchable &= field.searchable;
+            entry.aggregatable &= field.aggregatable;
+            let mut indices: BTreeSet<String> = entry.indices.take().unwrap_or_default();
+            indices.extend(field.index_ids.iter().cloned());
+            entry.indices = Some(indices.into_iter().collect());
+        } else {
+            let entry = FieldCapabilityEntry {
+                field_type: field_type.to_string(),
+                metadata_field: false,
+                searchable: field.searchable,
+                aggregatable: field.aggregatable,
+                indices: Some(field.index_ids.clone()),
+            };
+            field_capabilities.insert(field_type.to_string(), entry);
+        }
+    }
+    for entry in response.fields.values_mut().flat_map(BTreeMap::values_mut) {
+        if entry.indices.as_ref().map(Vec::len) == Some(num_indices) {
+            entry.indices = None;
+        }
+    }
+    response
+}
+
+/// Splits a field name into its path segments, unescaping the dots.
+fn split_field_name(field_name: &str) -> Vec<String> {
+    let mut segments = Vec::new();
+    let mut segment = String::new();
+    let mut chars = field_name.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => segment.extend(chars.next()),
+            '.' => segments.push(std::mem::take(&mut segment)),
+            _ => segment.push(c),
+        }
+    }
+    segments.push(segment);
+    segments
+}
+
+/// Inserts a field in the `properties` of an Elasticsearch mapping, creating the intermediate
+/// objects. When a field has several types, the first one wins.
+fn insert_mapping_property(properties: &mut JsonMap<String, JsonValue>, field: &ListFieldsEntry) {
+    let segments = split_field_name(&field.field_name);
+    let (leaf_name, parent_names) = segments.split_last().expect("segments should not be empty");
+    let mut properties = properties;
+
+    for parent_name in parent_names {
+        let parent = properties
+            .entry(parent_name.clone())
+            .or_insert_with(|| json!({}));
+        // A parent may have been reported as a leaf object.
+        if let Some(parent_object) = parent.as_object_mut() {
+            parent_object.remove("type");
+        }
+        properties = parent
+            .as_object_mut()
+            .expect("mapping properties should be objects")
+            .entry("properties")
+            .or_insert_with(|| json!({}))
+            .as_object_mut()
+            .expect("mapping properties should be objects");
+    }
+    if properties.contains_key(leaf_name) {
+        return;
+    }
+    let field_mapping = if field.field_type() == ListFieldType::Json {
+        json!({"type": "object"})
+    } else {
+        json!({"type": es_field_type(field)})
+    };
+    properties.insert(leaf_name.clone(), field_mapping);
+}
+
+pub fn convert_to_es_mapping_response(list_fields_response: ListFieldsResponse) -> JsonValue {
+    let mut properties_per_index: BTreeMap<&str, JsonMap<String, JsonValue>> = BTreeMap::new();
+
+    for field in &list_fields_response.fields {
+        for index_id in &field.index_ids {
+            let properties = properties_per_index.entry(index_id.as_str()).or_default();
+            insert_mapping_property(properties, field);
+        }
+    }
+    let response: JsonMap<String, JsonValue> = properties_per_index
+        .into_iter()
+        .map(|(index_id, properties)| {
+            let mapping = json!({"mappings": {"properties": properties}});
+            (index_id.to_string(), mapping)
+        })
+        .collect();
+    JsonValue::Object(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list_fields_entry(
+        field_name: &str,
+        field_type: ListFieldType,
+        aggregatable: bool,
+        index_ids: &[&str],
+    ) -> ListFieldsEntry {
+        ListFieldsEntry {
+            field_name: field_name.to_string(),
+            field_type: field_type as i32,
+            searchable: true,
+            aggregatable,
+            index_ids: index_ids
+                .iter()
+                .map(|index_id| index_id.to_string())
+                .collect(),
+        }
+    }
+
+    fn list_fields_response() -> ListFieldsResponse {
+        ListFieldsResponse {
+            fields: vec![
+                list_fields_entry("attributes", ListFieldType::Json, true, &["logs-1"]),
+                list_fields_entry(
+                    r"attributes.http\.method",
+                    ListFieldType::Str,
+                    true,
+                    &["logs-1"],
+                ),
+                list_fields_entry("body", ListFieldType::Str, false, &["logs-1", "logs-2"]),
+                list_fields_entry("status", ListFieldType::I64, true, &["logs-1"]),
+                list_fields_entry("status", ListFieldType::Str, true, &["logs-2"]),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_field_matches_pattern() {
+        assert!(field_matches_pattern("body", "body"));
+        assert!(!field_matches_pattern("body", "bod"));
+        assert!(field_matches_pattern("body", "*"));
+        assert!(field_matches_pattern("attributes.host", "attributes.*"));
+        assert!(field_matches_pattern("attributes.host", "*.host"));
+        assert!(field_matches_pattern("attributes.host.name", "a*.*.name"));
+        assert!(!field_matches_pattern("attributes.host", "resource.*"));
+    }
+
+    #[test]
+    fn test_convert_to_es_field_capabilities_response() {
+        let response = convert_to_es_field_capabilities_response(list_fields_response(), None);
+        assert_eq!(response.indices, ["logs-1", "logs-2"]);
+        assert_eq!(
+            serde_json::to_value(&response.fields).unwrap(),
+            json!({
+                "attributes": {
+                    "object": {"type": "object", "metadata_field": false, "searchable": true, "aggregatable": true, "indices": ["logs-1"]}
+                },
+                r"attributes.http\.method": {
+                    "keyword": {"type": "keyword", "metadata_field": false, "searchable": true, "aggregatable": true, "indices": ["logs-1"]}
+                },
+                "body": {
+                    "text": {"type": "text", "metadata_field": false, "searchable": true, "aggregatable": false}
+                },
+                "status": {
+                    "long": {"type": "long", "metadata_field": false, "searchable": true, "aggregatable": true, "indices": ["logs-1"]},
+                    "keyword": {"type": "keyword", "metadata_field": false, "searchable": true, "aggregatable": true, "indices": ["logs-2"]}
+                },
+            })
+        );
+        let field_patterns = vec!["attr*".to_string()];
+        let response = convert_to_es_field_capabilities_response(
+            list_fields_response(),
+            Some(&field_patterns),
+        );
+        assert_eq!(response.fields.len(), 2);
+    }
+
+    #[test]
+    fn test_convert_to_es_mapping_response() {
+        let response = convert_to_es_mapping_response(list_fields_response());
+        assert_eq!(
+            response,
+            json!({
+                "logs-1": {
+                    "mappings": {
+                        "properties": {
+                            "attributes": {
+                                "properties": {
+                                    "http.method": {"type": "keyword"}
+                                }
+                            },
+                            "body": {"type": "text"},
+                            "status": {"type": "long"}
+                        }
+                    }
+                },
+                "logs-2": {
+                    "mappings": {
+                        "properties": {
+                            "body": {"type": "text"},
+                            "status": {"type": "keyword"}
+                        }
+                    }
+                }
+            })
+        );
+    }
+}
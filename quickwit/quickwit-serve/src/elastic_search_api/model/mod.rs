@@ -17,21 +17,36 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
+mod async_search;
 mod bulk_body;
 mod bulk_query_params;
+mod cat_indices;
+mod count;
+mod delete_by_query;
 mod error;
+mod field_capability;
 mod multi_search;
+mod resolve_index;
 mod scroll;
 mod search_body;
 mod search_query_params;
 
+pub use async_search::{ElasticAsyncSearchDeleteResponse, ElasticAsyncSearchResponse};
 pub use bulk_body::{BulkAction, BulkActionMeta};
 pub use bulk_query_params::{ElasticIngestOptions, ElasticRefresh};
+pub use cat_indices::ElasticsearchCatIndexResponse;
+pub use count::ElasticCountResponse;
+pub use delete_by_query::{DeleteByQueryBody, ElasticDeleteByQueryResponse};
 pub use error::ElasticSearchError;
+pub use field_capability::{
+    convert_to_es_field_capabilities_response, convert_to_es_mapping_response,
+    FieldCapabilityQueryParams, FieldCapabilityResponse,
+};
 pub use multi_search::{
     MultiSearchHeader, MultiSearchQueryParams, MultiSearchResponse, MultiSearchSingleResponse,
 };
 use quickwit_proto::search::SortOrder;
+pub use resolve_index::{ElasticsearchResolveIndexEntry, ElasticsearchResolveIndexResponse};
 pub use scroll::ScrollQueryParams;
 pub use search_body::SearchBody;
 pub use search_query_params::SearchQueryParams;
@@ -0,0 +1,43 @@
+// Copyright (C) 2023 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ElasticsearchResolveIndexResponse {
+    pub indices: Vec<ElasticsearchResolveIndexEntry>,
+    /// Quickwit has no aliases nor data streams, these lists are always empty.
+    pub aliases: Vec<serde_json::Value>,
+    pub data_streams: Vec<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ElasticsearchResolveIndexEntry {
+    pub name: String,
+    pub attributes: Vec<String>,
+}
+
+impl ElasticsearchResolveIndexEntry {
+    pub fn new(index_id: String) -> Self {
+        Self {
+            name: index_id,
+            attributes: vec!["open".to_string()],
+        }
+    }
+}
@@ -20,7 +20,7 @@
 use std::collections::BTreeSet;
 use std::fmt;
 
-use quickwit_proto::search::SortOrder;
+use quickwit_proto::search::{SnippetConfig, SortOrder};
 use quickwit_query::{ElasticQueryDsl, OneFieldMap};
 use serde::de::{MapAccess, Visitor};
 use serde::{Deserialize, Deserializer, Serialize};
@@ -71,6 +71,85 @@ pub struct SearchBody {
     pub stored_fields: Option<BTreeSet<String>>,
     #[serde(default)]
     pub search_after: Vec<serde_json::Value>,
+    #[serde(default)]
+    pub highlight: Option<Highlight>,
+    #[serde(default)]
+    pub timeout: Option<String>,
+}
+
+const DEFAULT_HIGHLIGHT_PRE_TAG: &str = "<em>";
+const DEFAULT_HIGHLIGHT_POST_TAG: &str = "</em>";
+
+/// The `highlight` section of a search request.
+///
+/// The settings apply to all fields: per-field settings are ignored. Only the first pre and post
+/// tags are used.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+pub struct Highlight {
+    #[serde(default)]
+    #[serde(deserialize_with = "deserialize_highlight_fields")]
+    pub fields: Vec<String>,
+    #[serde(default)]
+    pub pre_tags: Vec<String>,
+    #[serde(default)]
+    pub post_tags: Vec<String>,
+    #[serde(default)]
+    pub fragment_size: Option<u32>,
+    #[serde(default)]
+    pub number_of_fragments: Option<u32>,
+}
+
+impl Highlight {
+    /// Returns the fields to highlight. Field names with wildcards are skipped, as they cannot be
+    /// resolved against the doc mapping.
+    pub fn snippet_fields(&self) -> Vec<String> {
+        self.fields
+            .iter()
+            .filter(|field_name| !field_name.contains('*'))
+            .cloned()
+            .collect()
+    }
+
+    pub fn snippet_config(&self) -> SnippetConfig {
+        let pre_tag = self
+            .pre_tags
+            .first()
+            .map(String::as_str)
+            .unwrap_or(DEFAULT_HIGHLIGHT_PRE_TAG);
+        let post_tag = self
+            .post_tags
+            .first()
+            .map(String::as_str)
+            .unwrap_or(DEFAULT_HIGHLIGHT_POST_TAG);
+        SnippetConfig {
+            fragment_size: self.fragment_size,
+            num_fragments: self.number_of_fragments,
+            pre_tag: Some(pre_tag.to_string()),
+            post_tag: Some(post_tag.to_string()),
+        }
+    }
+}
+
+/// ES accepts the highlighted fields either as an object or as an array of single-field objects.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum HighlightFieldsForDeser {
+    Object(serde_json::Map<String, serde_json::Value>),
+    Array(Vec<serde_json::Map<String, serde_json::Value>>),
+}
+
+fn deserialize_highlight_fields<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where D: Deserializer<'de> {
+    let field_names = match HighlightFieldsForDeser::deserialize(deserializer)? {
+        HighlightFieldsForDeser::Object(fields) => {
+            fields.into_iter().map(|(name, _)| name).collect()
+        }
+        HighlightFieldsForDeser::Array(fields_array) => fields_array
+            .into_iter()
+            .flat_map(|fields| fields.into_iter().map(|(name, _)| name))
+            .collect(),
+    };
+    Ok(field_names)
 }
 
 struct FieldSortVecVisitor;
@@ -222,4 +301,60 @@ mod tests {
         assert_eq!(field_sorts[3].field, "_doc");
         assert_eq!(field_sorts[3].order, SortOrder::Asc);
     }
+
+    #[test]
+    fn test_highlight() {
+        let json = r#"
+        {
+            "highlight": {
+                "pre_tags": ["<mark>"],
+                "post_tags": ["</mark>"],
+                "number_of_fragments": 3,
+                "fields": {
+                    "body": {},
+                    "attributes.message": { "type": "plain" },
+                    "*": {}
+                }
+            }
+        }
+        "#;
+        let search_body: super::SearchBody = serde_json::from_str(json).unwrap();
+        let highlight = search_body.highlight.unwrap();
+        let mut snippet_fields = highlight.snippet_fields();
+        snippet_fields.sort();
+        assert_eq!(snippet_fields, ["attributes.message", "body"]);
+        assert_eq!(
+            highlight.snippet_config(),
+            SnippetConfig {
+                fragment_size: None,
+                num_fragments: Some(3),
+                pre_tag: Some("<mark>".to_string()),
+                post_tag: Some("</mark>".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_highlight_fields_array() {
+        let json = r#"
+        {
+            "highlight": {
+                "fragment_size": 50,
+                "fields": [{ "title": {} }, { "body": {} }]
+            }
+        }
+        "#;
+        let search_body: super::SearchBody = serde_json::from_str(json).unwrap();
+        let highlight = search_body.highlight.unwrap();
+        assert_eq!(highlight.snippet_fields(), ["title", "body"]);
+        assert_eq!(
+            highlight.snippet_config(),
+            SnippetConfig {
+                fragment_size: Some(50),
+                num_fragments: None,
+                pre_tag: Some("<em>".to_string()),
+                post_tag: Some("</em>".to_string()),
+            }
+        );
+    }
 }
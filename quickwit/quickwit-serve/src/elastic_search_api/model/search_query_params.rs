@@ -88,6 +88,8 @@ pub struct SearchQueryParams {
     #[serde(default)]
     pub ignore_unavailable: Option<bool>,
     #[serde(default)]
+    pub keep_alive: Option<String>,
+    #[serde(default)]
     pub lenient: Option<bool>,
     #[serde(default)]
     pub max_concurrent_shard_requests: Option<u64>,
@@ -149,6 +151,8 @@ pub struct SearchQueryParams {
     pub typed_keys: Option<bool>,
     #[serde(default)]
     pub version: Option<bool>,
+    #[serde(default)]
+    pub wait_for_completion_timeout: Option<String>,
 }
 
 // Parse a single sort field parameter from ES sort query string parameter.
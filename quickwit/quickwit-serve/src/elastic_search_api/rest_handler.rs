@@ -17,7 +17,7 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 use std::str::from_utf8;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
@@ -30,25 +30,49 @@ use hyper::StatusCode;
 use itertools::Itertools;
 use quickwit_common::truncate_str;
 use quickwit_config::{validate_index_id_pattern, NodeConfig};
-use quickwit_proto::search::{CountHits, PartialHit, ScrollRequest, SearchResponse, SortByValue};
+use quickwit_metastore::{
+    IndexMetadata, ListIndexesMetadataResponseExt, ListSplitsQuery, ListSplitsRequestExt,
+    ListSplitsResponseExt, SplitState,
+};
+use quickwit_proto::metastore::{
+    ListIndexesMetadataRequest, ListSplitsRequest, MetastoreService, MetastoreServiceClient,
+};
+use quickwit_proto::search::{
+    CountHits, ListFieldsRequest, PartialHit, ScrollRequest, SearchResponse, SortByValue,
+};
+use quickwit_proto::types::IndexUid;
 use quickwit_proto::ServiceErrorCode;
 use quickwit_query::query_ast::{QueryAst, UserInputQuery};
 use quickwit_query::BooleanOperand;
-use quickwit_search::{SearchError, SearchService};
+use quickwit_search::{
+    check_all_index_metadata_found, AsyncSearchState, AsyncSearchStatus, SearchError, SearchService,
+};
 use serde_json::json;
 use warp::{Filter, Rejection};
 
 use super::filter::{
-    elastic_cluster_info_filter, elastic_index_search_filter, elastic_multi_search_filter,
+    elastic_cat_indices_filter, elastic_cluster_info_filter, elastic_delete_async_search_filter,
+    elastic_delete_by_query_filter, elastic_get_async_search_filter,
+    elastic_index_async_search_filter, elastic_index_count_filter,
+    elastic_index_field_capabilities_filter, elastic_index_mapping_filter,
+    elastic_index_search_filter, elastic_multi_search_filter, elastic_resolve_index_filter,
     elastic_scroll_filter, elastic_search_filter,
 };
 use super::model::{
-    ElasticSearchError, MultiSearchHeader, MultiSearchQueryParams, MultiSearchResponse,
+    convert_to_es_field_capabilities_response, convert_to_es_mapping_response, DeleteByQueryBody,
+    ElasticAsyncSearchDeleteResponse, ElasticAsyncSearchResponse, ElasticCountResponse,
+    ElasticDeleteByQueryResponse, ElasticSearchError, ElasticsearchCatIndexResponse,
+    ElasticsearchResolveIndexEntry, ElasticsearchResolveIndexResponse, FieldCapabilityQueryParams,
+    FieldCapabilityResponse, MultiSearchHeader, MultiSearchQueryParams, MultiSearchResponse,
     MultiSearchSingleResponse, ScrollQueryParams, SearchBody, SearchQueryParams,
 };
 use super::TrackTotalHits;
+use crate::delete_task_api::create_delete_task;
 use crate::format::BodyFormat;
 use crate::json_api_response::{make_json_api_response, ApiError, JsonApiResponse};
+use crate::search_api::{
+    parse_search_timeout, submit_async_search_request_from_api_request, AsyncSearchQueryString,
+};
 use crate::{with_arg, BuildInfo};
 
 /// Elastic compatible cluster info handler.
@@ -101,6 +125,66 @@ pub fn es_compat_index_search_handler(
         .map(make_elastic_api_response)
 }
 
+/// GET or POST _elastic/{index}/_count
+pub fn es_compat_index_count_handler(
+    search_service: Arc<dyn SearchService>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    elastic_index_count_filter()
+        .and(with_arg(search_service))
+        .then(es_compat_index_count)
+        .map(make_elastic_api_response)
+}
+
+/// GET _elastic/_cat/indices
+pub fn es_compat_cat_indices_handler(
+    metastore: MetastoreServiceClient,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    elastic_cat_indices_filter()
+        .and(with_arg(metastore))
+        .then(es_compat_cat_indices)
+        .map(make_elastic_api_response)
+}
+
+/// GET _elastic/_resolve/index/{index}
+pub fn es_compat_resolve_index_handler(
+    metastore: MetastoreServiceClient,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    elastic_resolve_index_filter()
+        .and(with_arg(metastore))
+        .then(es_compat_resolve_index)
+        .map(make_elastic_api_response)
+}
+
+/// POST _elastic/{index}/_delete_by_query
+pub fn es_compat_delete_by_query_handler(
+    metastore: MetastoreServiceClient,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    elastic_delete_by_query_filter()
+        .and(with_arg(metastore))
+        .then(es_compat_delete_by_query)
+        .map(make_elastic_api_response)
+}
+
+/// GET or POST _elastic/{index}/_field_caps
+pub fn es_compat_index_field_capabilities_handler(
+    search_service: Arc<dyn SearchService>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    elastic_index_field_capabilities_filter()
+        .and(with_arg(search_service))
+        .then(es_compat_index_field_capabilities)
+        .map(make_elastic_api_response)
+}
+
+/// GET _elastic/{index}/_mapping
+pub fn es_compat_index_mapping_handler(
+    search_service: Arc<dyn SearchService>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    elastic_index_mapping_filter()
+        .and(with_arg(search_service))
+        .then(es_compat_index_mapping)
+        .map(make_elastic_api_response)
+}
+
 /// GET or POST _elastic/_search/scroll
 pub fn es_compat_scroll_handler(
     search_service: Arc<dyn SearchService>,
@@ -111,6 +195,36 @@ pub fn es_compat_scroll_handler(
         .map(make_elastic_api_response)
 }
 
+/// POST _elastic/{index}/_async_search
+pub fn es_compat_index_async_search_handler(
+    search_service: Arc<dyn SearchService>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    elastic_index_async_search_filter()
+        .and(with_arg(search_service))
+        .then(es_compat_index_async_search)
+        .map(make_elastic_api_response)
+}
+
+/// GET _elastic/_async_search/{id}
+pub fn es_compat_get_async_search_handler(
+    search_service: Arc<dyn SearchService>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    elastic_get_async_search_filter()
+        .and(with_arg(search_service))
+        .then(es_compat_get_async_search)
+        .map(make_elastic_api_response)
+}
+
+/// DELETE _elastic/_async_search/{id}
+pub fn es_compat_delete_async_search_handler(
+    search_service: Arc<dyn SearchService>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    elastic_delete_async_search_filter()
+        .and(with_arg(search_service))
+        .then(es_compat_delete_async_search)
+        .map(make_elastic_api_response)
+}
+
 /// POST _elastic/_search
 pub fn es_compat_index_multi_search_handler(
     search_service: Arc<dyn SearchService>,
@@ -176,11 +290,6 @@ fn build_request_for_es_api(
         })
         .take_while_inclusive(|sort_field| !is_doc_field(sort_field))
         .collect();
-    if sort_fields.len() >= 3 {
-        return Err(ElasticSearchError::from(SearchError::InvalidArgument(
-            format!("only up to two sort fields supported at the moment. got {sort_fields:?}"),
-        )));
-    }
 
     let scroll_duration: Option<Duration> = search_params.parse_scroll_ttl()?;
     let scroll_ttl_secs: Option<u32> = scroll_duration.map(|duration| duration.as_secs() as u32);
@@ -188,6 +297,18 @@ fn build_request_for_es_api(
     let has_doc_id_field = sort_fields.iter().any(is_doc_field);
     let search_after = partial_hit_from_search_after_param(search_body.search_after, &sort_fields)?;
 
+    let timeout_millis = search_params
+        .timeout
+        .as_deref()
+        .or(search_body.timeout.as_deref())
+        .map(parse_search_timeout)
+        .transpose()?;
+
+    let (snippet_fields, snippet_config) = match &search_body.highlight {
+        Some(highlight) => (highlight.snippet_fields(), Some(highlight.snippet_config())),
+        None => (Vec::new(), None),
+    };
+
     Ok((
         quickwit_proto::search::SearchRequest {
             index_id_patterns,
@@ -198,10 +319,14 @@ fn build_request_for_es_api(
             sort_fields,
             start_timestamp: None,
             end_timestamp: None,
-            snippet_fields: Vec::new(),
+            snippet_fields,
+            snippet_config,
             scroll_ttl_secs,
             search_after,
             count_hits,
+            timeout_millis,
+            profile: false,
+            fetch_fields: Vec::new(),
         },
         has_doc_id_field,
     ))
@@ -234,6 +359,7 @@ fn partial_hit_from_search_after_param(
     }
 
     let mut parsed_search_after = PartialHit::default();
+    let mut sort_by_values: Vec<SortByValue> = Vec::with_capacity(search_after.len());
     for (value, field) in search_after.into_iter().zip(sort_order) {
         if is_doc_field(field) {
             if let Some(value_str) = value.as_str() {
@@ -253,6 +379,7 @@ fn partial_hit_from_search_after_param(
                 parsed_search_after.split_id = address.split;
                 parsed_search_after.segment_ord = address.doc_addr.segment_ord;
                 parsed_search_after.doc_id = address.doc_addr.doc_id;
+                parsed_search_after.set_sort_by_values(sort_by_values);
                 return Ok(Some(parsed_search_after));
             } else {
                 todo!();
@@ -270,14 +397,10 @@ fn partial_hit_from_search_after_param(
                     additional_details: Default::default(),
                 },
             })?;
-            // TODO make cleaner once we support Vec
-            if parsed_search_after.sort_value.is_none() {
-                parsed_search_after.sort_value = Some(value);
-            } else {
-                parsed_search_after.sort_value2 = Some(value);
-            }
+            sort_by_values.push(value);
         }
     }
+    parsed_search_after.set_sort_by_values(sort_by_values);
     Ok(Some(parsed_search_after))
 }
 
@@ -298,24 +421,180 @@ async fn es_compat_index_search(
     Ok(search_response_rest)
 }
 
+async fn es_compat_index_count(
+    index_id_patterns: Vec<String>,
+    search_params: SearchQueryParams,
+    search_body: SearchBody,
+    search_service: Arc<dyn SearchService>,
+) -> Result<ElasticCountResponse, ElasticSearchError> {
+    let (mut search_request, _append_shard_doc) =
+        build_request_for_es_api(index_id_patterns, search_params, search_body)?;
+    search_request.max_hits = 0;
+    search_request.start_offset = 0;
+    search_request.count_hits = CountHits::CountAll.into();
+    search_request.aggregation_request = None;
+    search_request.sort_fields.clear();
+
+    let search_response: SearchResponse = search_service.root_search(search_request).await?;
+    let count_response = ElasticCountResponse {
+        count: search_response.num_hits,
+    };
+    Ok(count_response)
+}
+
+/// Resolves index ID patterns the same way `root_search` does: patterns without wildcard must
+/// match an existing index.
+async fn resolve_index_id_patterns(
+    index_id_patterns: Vec<String>,
+    metastore: &mut MetastoreServiceClient,
+) -> Result<Vec<IndexMetadata>, ElasticSearchError> {
+    let list_indexes_metadata_request = ListIndexesMetadataRequest {
+        index_id_patterns: index_id_patterns.clone(),
+    };
+    let mut indexes_metadata = metastore
+        .list_indexes_metadata(list_indexes_metadata_request)
+        .await
+        .and_then(|response| response.deserialize_indexes_metadata())
+        .map_err(SearchError::from)?;
+    check_all_index_metadata_found(&indexes_metadata[..], &index_id_patterns[..])?;
+    indexes_metadata.sort_by(|left, right| left.index_id().cmp(right.index_id()));
+    Ok(indexes_metadata)
+}
+
+async fn es_compat_cat_indices(
+    index_id_patterns: Vec<String>,
+    mut metastore: MetastoreServiceClient,
+) -> Result<Vec<ElasticsearchCatIndexResponse>, ElasticSearchError> {
+    let indexes_metadata = resolve_index_id_patterns(index_id_patterns, &mut metastore).await?;
+
+    if indexes_metadata.is_empty() {
+        return Ok(Vec::new());
+    }
+    let index_uids: Vec<IndexUid> = indexes_metadata
+        .iter()
+        .map(|index_metadata| index_metadata.index_uid.clone())
+        .collect();
+    let list_splits_query = ListSplitsQuery::try_from_index_uids(index_uids)
+        .map_err(SearchError::from)?
+        .with_split_state(SplitState::Published);
+    let list_splits_request = ListSplitsRequest::try_from_list_splits_query(list_splits_query)
+        .map_err(SearchError::from)?;
+    let splits = metastore
+        .list_splits(list_splits_request)
+        .await
+        .and_then(|response| response.deserialize_splits())
+        .map_err(SearchError::from)?;
+
+    // Number of docs and bytes of the published splits per index.
+    let mut index_stats: HashMap<IndexUid, (u64, u64)> = HashMap::new();
+    for split in splits {
+        let split_metadata = split.split_metadata;
+        let (num_docs, num_bytes) = index_stats.entry(split_metadata.index_uid).or_default();
+        *num_docs += split_metadata.num_docs as u64;
+        *num_bytes += split_metadata.footer_offsets.end;
+    }
+    let cat_indices_response = indexes_metadata
+        .into_iter()
+        .map(|index_metadata| {
+            let (num_docs, num_bytes) = index_stats
+                .get(&index_metadata.index_uid)
+                .copied()
+                .unwrap_or_default();
+            ElasticsearchCatIndexResponse::new(
+                index_metadata.index_id().to_string(),
+                index_metadata.index_uid.incarnation_id().to_string(),
+                num_docs,
+                num_bytes,
+            )
+        })
+        .collect();
+    Ok(cat_indices_response)
+}
+
+async fn es_compat_resolve_index(
+    index_id_patterns: Vec<String>,
+    mut metastore: MetastoreServiceClient,
+) -> Result<ElasticsearchResolveIndexResponse, ElasticSearchError> {
+    let indexes_metadata = resolve_index_id_patterns(index_id_patterns, &mut metastore).await?;
+    let indices = indexes_metadata
+        .into_iter()
+        .map(|index_metadata| {
+            ElasticsearchResolveIndexEntry::new(index_metadata.index_id().to_string())
+        })
+        .collect();
+    Ok(ElasticsearchResolveIndexResponse {
+        indices,
+        ..Default::default()
+    })
+}
+
+async fn es_compat_delete_by_query(
+    index_id: String,
+    delete_by_query_body: DeleteByQueryBody,
+    metastore: MetastoreServiceClient,
+) -> Result<ElasticDeleteByQueryResponse, ElasticSearchError> {
+    let query_ast: QueryAst = delete_by_query_body
+        .query
+        .try_into()
+        .map_err(|err: anyhow::Error| SearchError::InvalidQuery(err.to_string()))?;
+    let delete_task =
+        create_delete_task(index_id.clone(), query_ast, None, None, metastore).await?;
+    let delete_by_query_response = ElasticDeleteByQueryResponse {
+        task: format!("{index_id}:{}", delete_task.opstamp),
+    };
+    Ok(delete_by_query_response)
+}
+
+async fn es_compat_index_field_capabilities(
+    index_id_patterns: Vec<String>,
+    field_capability_params: FieldCapabilityQueryParams,
+    search_service: Arc<dyn SearchService>,
+) -> Result<FieldCapabilityResponse, ElasticSearchError> {
+    let list_fields_request = ListFieldsRequest { index_id_patterns };
+    let list_fields_response = search_service.root_list_fields(list_fields_request).await?;
+    let field_capability_response = convert_to_es_field_capabilities_response(
+        list_fields_response,
+        field_capability_params.fields.as_deref(),
+    );
+    Ok(field_capability_response)
+}
+
+async fn es_compat_index_mapping(
+    index_id_patterns: Vec<String>,
+    search_service: Arc<dyn SearchService>,
+) -> Result<serde_json::Value, ElasticSearchError> {
+    let list_fields_request = ListFieldsRequest { index_id_patterns };
+    let list_fields_response = search_service.root_list_fields(list_fields_request).await?;
+    Ok(convert_to_es_mapping_response(list_fields_response))
+}
+
 fn convert_hit(hit: quickwit_proto::search::Hit, append_shard_doc: bool) -> ElasticHit {
     let fields: BTreeMap<String, serde_json::Value> =
         serde_json::from_str(&hit.json).unwrap_or_default();
     let mut sort = Vec::new();
     if let Some(partial_hit) = hit.partial_hit {
-        if let Some(sort_value) = partial_hit.sort_value {
-            sort.push(sort_value.into_json());
-        }
-        if let Some(sort_value2) = partial_hit.sort_value2 {
-            sort.push(sort_value2.into_json());
-        }
+        // Sort values are positional: a document missing a sort field gets a `null` value.
+        sort = partial_hit
+            .sort_by_values()
+            .map(|sort_by_value| sort_by_value.into_json())
+            .collect();
         if append_shard_doc {
+            // The last sort field is the doc field, which has no value of its own.
+            sort.pop();
             sort.push(serde_json::Value::String(
                 quickwit_search::GlobalDocAddress::from_partial_hit(&partial_hit).to_string(),
             ));
         }
     }
 
+    let mut highlight: BTreeMap<String, Vec<String>> = hit
+        .snippet
+        .as_deref()
+        .and_then(|snippet_json| serde_json::from_str(snippet_json).ok())
+        .unwrap_or_default();
+    // Like Elasticsearch, we omit the fields without any highlighted fragment.
+    highlight.retain(|_, fragments| !fragments.is_empty());
+
     ElasticHit {
         fields,
         explanation: None,
@@ -325,7 +604,7 @@ fn convert_hit(hit: quickwit_proto::search::Hit, append_shard_doc: bool) -> Elas
         nested: None,
         source: Source::from_string(hit.json)
             .unwrap_or_else(|_| Source::from_string("{}".to_string()).unwrap()),
-        highlight: Default::default(),
+        highlight,
         inner_hits: Default::default(),
         matched_queries: Vec::default(),
         sort,
@@ -444,6 +723,78 @@ async fn es_scroll(
     Ok(search_response_rest)
 }
 
+async fn es_compat_index_async_search(
+    index_id_patterns: Vec<String>,
+    search_params: SearchQueryParams,
+    search_body: SearchBody,
+    search_service: Arc<dyn SearchService>,
+) -> Result<ElasticAsyncSearchResponse, ElasticSearchError> {
+    let async_search_params = AsyncSearchQueryString {
+        wait_for_completion_timeout: search_params.wait_for_completion_timeout.clone(),
+        keep_alive: search_params.keep_alive.clone(),
+    };
+    let (search_request, append_shard_doc) =
+        build_request_for_es_api(index_id_patterns, search_params, search_body)?;
+    let submit_request =
+        submit_async_search_request_from_api_request(search_request, async_search_params)?;
+    let async_search_status = search_service.submit_async_search(submit_request).await?;
+    convert_to_es_async_search_response(async_search_status, append_shard_doc)
+}
+
+async fn es_compat_get_async_search(
+    async_search_id: String,
+    search_service: Arc<dyn SearchService>,
+) -> Result<ElasticAsyncSearchResponse, ElasticSearchError> {
+    let async_search_status = search_service.get_async_search(async_search_id).await?;
+    // TODO append_shard_doc depends on the initial request, but we don't have access to it
+    convert_to_es_async_search_response(async_search_status, false)
+}
+
+async fn es_compat_delete_async_search(
+    async_search_id: String,
+    search_service: Arc<dyn SearchService>,
+) -> Result<ElasticAsyncSearchDeleteResponse, ElasticSearchError> {
+    search_service.cancel_async_search(async_search_id).await?;
+    Ok(ElasticAsyncSearchDeleteResponse { acknowledged: true })
+}
+
+fn convert_to_es_async_search_response(
+    async_search_status: AsyncSearchStatus,
+    append_shard_doc: bool,
+) -> Result<ElasticAsyncSearchResponse, ElasticSearchError> {
+    match async_search_status.state {
+        // Like Elasticsearch, a deleted async search cannot be found anymore.
+        AsyncSearchState::Cancelled => {
+            return Err(SearchError::AsyncSearchNotFound {
+                async_search_id: async_search_status.id,
+            }
+            .into());
+        }
+        AsyncSearchState::Failed => {
+            let error = async_search_status.error.unwrap_or_default();
+            return Err(SearchError::Internal(error).into());
+        }
+        AsyncSearchState::Running | AsyncSearchState::Completed => {}
+    }
+    let is_running = async_search_status.is_running();
+    let mut is_partial = async_search_status.is_partial();
+    let search_response = async_search_status.response.unwrap_or_default();
+    is_partial |= search_response.timed_out;
+    let took_millis = (search_response.elapsed_time_micros / 1_000) as u32;
+    let mut search_response_rest: ElasticSearchResponse =
+        convert_to_es_search_response(search_response, append_shard_doc);
+    search_response_rest.took = took_millis;
+    Ok(ElasticAsyncSearchResponse {
+        id: async_search_status.id,
+        is_partial,
+        is_running,
+        start_time_in_millis: async_search_status.start_time_millis,
+        expiration_time_in_millis: async_search_status.expiration_time_millis,
+        completion_time_in_millis: async_search_status.completion_time_millis,
+        response: search_response_rest,
+    })
+}
+
 fn convert_to_es_search_response(
     resp: SearchResponse,
     append_shard_doc: bool,
@@ -459,7 +810,7 @@ fn convert_to_es_search_response(
         None
     };
     ElasticSearchResponse {
-        timed_out: false,
+        timed_out: resp.timed_out,
         hits: HitsMetadata {
             total: Some(TotalHits {
                 value: resp.num_hits,
@@ -474,8 +825,8 @@ fn convert_to_es_search_response(
     }
 }
 
-fn make_elastic_api_response(
-    elasticsearch_result: Result<ElasticSearchResponse, ElasticSearchError>,
+fn make_elastic_api_response<T: serde::Serialize>(
+    elasticsearch_result: Result<T, ElasticSearchError>,
 ) -> JsonApiResponse {
     let status_code = match &elasticsearch_result {
         Ok(_) => StatusCode::OK,
@@ -24,10 +24,11 @@ use std::sync::Arc;
 use quickwit_common::tower::BoxFutureInfaillible;
 use quickwit_config::service::QuickwitService;
 use quickwit_jaeger::JaegerService;
-use quickwit_opentelemetry::otlp::{OtlpGrpcLogsService, OtlpGrpcTracesService};
 use quickwit_proto::indexing::IndexingServiceClient;
+use quickwit_proto::jaeger::storage::v1::dependencies_reader_plugin_server::DependenciesReaderPluginServer;
 use quickwit_proto::jaeger::storage::v1::span_reader_plugin_server::SpanReaderPluginServer;
 use quickwit_proto::opentelemetry::proto::collector::logs::v1::logs_service_server::LogsServiceServer;
+use quickwit_proto::opentelemetry::proto::collector::metrics::v1::metrics_service_server::MetricsServiceServer;
 use quickwit_proto::opentelemetry::proto::collector::trace::v1::trace_service_server::TraceServiceServer;
 use quickwit_proto::search::search_service_server::SearchServiceServer;
 use quickwit_proto::tonic::codegen::CompressionEncoding;
@@ -111,36 +112,33 @@ pub(crate) async fn start_grpc_server(
         None
     };
     // Mount gRPC OpenTelemetry OTLP trace service if `QuickwitService::Indexer` is enabled on node.
-    let enable_opentelemetry_otlp_grpc_service =
-        services.node_config.indexer_config.enable_otlp_endpoint;
-    let otlp_trace_grpc_service = if enable_opentelemetry_otlp_grpc_service
-        && services
-            .node_config
-            .is_service_enabled(QuickwitService::Indexer)
-    {
-        enabled_grpc_services.insert("otlp-trace");
-        let ingest_service = services.ingest_service.clone();
-        let commit_type_opt = None;
-        let trace_service =
-            TraceServiceServer::new(OtlpGrpcTracesService::new(ingest_service, commit_type_opt))
+    let otlp_trace_grpc_service =
+        if let Some(otlp_traces_service) = services.otlp_traces_service_opt.clone() {
+            enabled_grpc_services.insert("otlp-trace");
+            let trace_service = TraceServiceServer::new(otlp_traces_service)
                 .accept_compressed(CompressionEncoding::Gzip);
-        Some(trace_service)
-    } else {
-        None
-    };
-    let otlp_log_grpc_service = if enable_opentelemetry_otlp_grpc_service
-        && services
-            .node_config
-            .is_service_enabled(QuickwitService::Indexer)
-    {
-        enabled_grpc_services.insert("otlp-logs");
-        let ingest_service = services.ingest_service.clone();
-        let logs_service = LogsServiceServer::new(OtlpGrpcLogsService::new(ingest_service))
-            .accept_compressed(CompressionEncoding::Gzip);
-        Some(logs_service)
-    } else {
-        None
-    };
+            Some(trace_service)
+        } else {
+            None
+        };
+    let otlp_log_grpc_service =
+        if let Some(otlp_logs_service) = services.otlp_logs_service_opt.clone() {
+            enabled_grpc_services.insert("otlp-logs");
+            let logs_service = LogsServiceServer::new(otlp_logs_service)
+                .accept_compressed(CompressionEncoding::Gzip);
+            Some(logs_service)
+        } else {
+            None
+        };
+    let otlp_metrics_grpc_service =
+        if let Some(otlp_metrics_service) = services.otlp_metrics_service_opt.clone() {
+            enabled_grpc_services.insert("otlp-metrics");
+            let metrics_service = MetricsServiceServer::new(otlp_metrics_service)
+                .accept_compressed(CompressionEncoding::Gzip);
+            Some(metrics_service)
+        } else {
+            None
+        };
     // Mount gRPC search service if `QuickwitService::Searcher` is enabled on node.
     let search_grpc_service = if services
         .node_config
@@ -154,19 +152,23 @@ pub(crate) async fn start_grpc_server(
         None
     };
     let enable_jaeger_endpoint = services.node_config.jaeger_config.enable_endpoint;
-    let jaeger_grpc_service = if enable_jaeger_endpoint
+    let (jaeger_grpc_service, jaeger_dependencies_grpc_service) = if enable_jaeger_endpoint
         && services
             .node_config
             .is_service_enabled(QuickwitService::Searcher)
     {
         enabled_grpc_services.insert("jaeger");
         let search_service = services.search_service.clone();
-        Some(SpanReaderPluginServer::new(JaegerService::new(
+        let jaeger_service = Arc::new(JaegerService::new(
             services.node_config.jaeger_config.clone(),
             search_service,
-        )))
+        ));
+        (
+            Some(SpanReaderPluginServer::from_arc(jaeger_service.clone())),
+            Some(DependenciesReaderPluginServer::from_arc(jaeger_service)),
+        )
     } else {
-        None
+        (None, None)
     };
     let server_router = server
         .add_optional_service(control_plane_grpc_service)
@@ -175,8 +177,10 @@ pub(crate) async fn start_grpc_server(
         .add_optional_service(ingest_router_grpc_service)
         .add_optional_service(ingester_grpc_service)
         .add_optional_service(jaeger_grpc_service)
+        .add_optional_service(jaeger_dependencies_grpc_service)
         .add_optional_service(metastore_grpc_service)
         .add_optional_service(otlp_log_grpc_service)
+        .add_optional_service(otlp_metrics_grpc_service)
         .add_optional_service(otlp_trace_grpc_service)
         .add_optional_service(search_grpc_service);
 
@@ -53,6 +53,7 @@ use crate::with_arg;
 #[openapi(
     paths(
         create_index,
+        update_index,
         clear_index,
         delete_index,
         get_indexes_metadatas,
@@ -76,6 +77,7 @@ pub fn index_management_handlers(
     get_index_metadata_handler(index_service.metastore())
         .or(get_indexes_metadatas_handler(index_service.metastore()))
         .or(create_index_handler(index_service.clone(), node_config))
+        .or(update_index_handler(index_service.clone()))
         .or(clear_index_handler(index_service.clone()))
         .or(delete_index_handler(index_service.clone()))
         // Splits handlers
@@ -503,6 +505,66 @@ async fn create_index(
         .await
 }
 
+fn update_index_handler(
+    index_service: IndexService,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    warp::path!("indexes" / String)
+        .and(warp::put())
+        .and(config_format_filter())
+        .and(warp::body::content_length_limit(1024 * 1024))
+        .and(warp::filters::body::bytes())
+        .and(with_arg(index_service))
+        .then(update_index)
+        .and(extract_format_from_qs())
+        .map(make_json_api_response)
+}
+
+#[utoipa::path(
+    put,
+    tag = "Indexes",
+    path = "/indexes/{index_id}",
+    request_body = VersionedIndexConfig,
+    responses(
+        // We return `VersionedIndexMetadata` as it's the serialized model view.
+        (status = 200, description = "Successfully updated index.", body = VersionedIndexMetadata)
+    ),
+    params(
+        ("index_id" = String, Path, description = "The index ID to update."),
+    )
+)]
+/// Updates index config.
+///
+/// The doc mapping can only be extended with new fields. The indexing settings, search settings,
+/// and retention policy can be modified freely. If the config does not specify `index_uri`, the
+/// current index URI is kept.
+async fn update_index(
+    index_id: String,
+    config_format: ConfigFormat,
+    index_config_bytes: Bytes,
+    mut index_service: IndexService,
+) -> Result<IndexMetadata, IndexServiceError> {
+    let index_metadata_request = IndexMetadataRequest::for_index_id(index_id.clone());
+    let current_index_metadata = index_service
+        .metastore()
+        .index_metadata(index_metadata_request)
+        .await?
+        .deserialize_index_metadata()?;
+    let index_config = quickwit_config::load_index_config_update(
+        config_format,
+        &index_config_bytes,
+        &current_index_metadata.index_config,
+    )
+    .map_err(IndexServiceError::InvalidConfig)?;
+    if index_config.index_id != index_id {
+        return Err(IndexServiceError::InvalidConfig(anyhow::anyhow!(
+            "index ID in the config `{}` does not match the index ID in the path `{index_id}`",
+            index_config.index_id
+        )));
+    }
+    info!(index_id = %index_id, "update-index");
+    index_service.update_index(index_config).await
+}
+
 fn clear_index_handler(
     index_service: IndexService,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
@@ -1375,6 +1437,136 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_update_index() {
+        let metastore = metastore_for_test();
+        let index_service = IndexService::new(metastore.clone(), StorageResolver::unconfigured());
+        let mut node_config = NodeConfig::for_test();
+        node_config.default_index_root_uri = Uri::for_test("file:///default-index-root-uri");
+        let index_management_handler =
+            super::index_management_handlers(index_service, Arc::new(node_config))
+                .recover(recover_fn);
+        let resp = warp::test::request()
+            .path("/indexes")
+            .method("POST")
+            .json(&true)
+            .body(r#"{"version": "0.6", "index_id": "hdfs-logs", "doc_mapping": {"field_mappings":[{"name": "timestamp", "type": "i64", "fast": true, "indexed": true}]}}"#)
+            .reply(&index_management_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+        {
+            let resp = warp::test::request()
+                .path("/indexes/hdfs-logs")
+                .method("PUT")
+                .json(&true)
+                .body(r#"{"version": "0.6", "index_id": "hdfs-logs", "doc_mapping": {"field_mappings":[{"name": "timestamp", "type": "i64", "fast": true, "indexed": true}, {"name": "body", "type": "text"}]}, "search_settings": {"default_search_fields": ["body"]}, "indexing_settings": {"commit_timeout_secs": 5}}"#)
+                .reply(&index_management_handler)
+                .await;
+            assert_eq!(resp.status(), 200);
+            let resp_json: serde_json::Value = serde_json::from_slice(resp.body()).unwrap();
+            let expected_response_json = serde_json::json!({
+                "index_config": {
+                    "index_id": "hdfs-logs",
+                    "index_uri": "file:///default-index-root-uri/hdfs-logs",
+                    "search_settings": {
+                        "default_search_fields": ["body"]
+                    },
+                    "indexing_settings": {
+                        "commit_timeout_secs": 5
+                    }
+                }
+            });
+            assert_json_include!(actual: resp_json, expected: expected_response_json);
+        }
+        {
+            // Removing a field is not allowed.
+            let resp = warp::test::request()
+                .path("/indexes/hdfs-logs")
+                .method("PUT")
+                .json(&true)
+                .body(r#"{"version": "0.6", "index_id": "hdfs-logs", "doc_mapping": {"field_mappings":[{"name": "body", "type": "text"}]}}"#)
+                .reply(&index_management_handler)
+                .await;
+            assert_eq!(resp.status(), 400);
+            let body = std::str::from_utf8(resp.body()).unwrap();
+            assert!(body.contains("field `timestamp` cannot be removed"));
+        }
+        {
+            let resp = warp::test::request()
+                .path("/indexes/hdfs-logs")
+                .method("PUT")
+                .json(&true)
+                .body(r#"{"version": "0.6", "index_id": "other-index", "doc_mapping": {"field_mappings":[{"name": "timestamp", "type": "i64", "fast": true, "indexed": true}]}}"#)
+                .reply(&index_management_handler)
+                .await;
+            assert_eq!(resp.status(), 400);
+        }
+        {
+            let resp = warp::test::request()
+                .path("/indexes/other-index")
+                .method("PUT")
+                .json(&true)
+                .body(r#"{"version": "0.6", "index_id": "other-index", "doc_mapping": {"field_mappings":[{"name": "timestamp", "type": "i64", "fast": true, "indexed": true}]}}"#)
+                .reply(&index_management_handler)
+                .await;
+            assert_eq!(resp.status(), 404);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_update_index_with_custom_index_uri() {
+        let metastore = metastore_for_test();
+        let index_service = IndexService::new(metastore.clone(), StorageResolver::unconfigured());
+        let mut node_config = NodeConfig::for_test();
+        node_config.default_index_root_uri = Uri::for_test("file:///default-index-root-uri");
+        let index_management_handler =
+            super::index_management_handlers(index_service, Arc::new(node_config))
+                .recover(recover_fn);
+        let resp = warp::test::request()
+            .path("/indexes")
+            .method("POST")
+            .json(&true)
+            .body(r#"{"version": "0.6", "index_id": "hdfs-logs", "index_uri": "file:///custom-index-root-uri/hdfs-logs", "doc_mapping": {"field_mappings":[{"name": "timestamp", "type": "i64", "fast": true, "indexed": true}]}}"#)
+            .reply(&index_management_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+        {
+            // The update does not specify `index_uri`: the custom index URI is kept.
+            let resp = warp::test::request()
+                .path("/indexes/hdfs-logs")
+                .method("PUT")
+                .json(&true)
+                .body(r#"{"version": "0.6", "index_id": "hdfs-logs", "doc_mapping": {"field_mappings":[{"name": "timestamp", "type": "i64", "fast": true, "indexed": true}]}, "indexing_settings": {"commit_timeout_secs": 5}}"#)
+                .reply(&index_management_handler)
+                .await;
+            assert_eq!(resp.status(), 200);
+            let resp_json: serde_json::Value = serde_json::from_slice(resp.body()).unwrap();
+            let expected_response_json = serde_json::json!({
+                "index_config": {
+                    "index_id": "hdfs-logs",
+                    "index_uri": "file:///custom-index-root-uri/hdfs-logs",
+                    "indexing_settings": {
+                        "commit_timeout_secs": 5
+                    }
+                }
+            });
+            assert_json_include!(actual: resp_json, expected: expected_response_json);
+        }
+        {
+            // The index URI cannot be updated.
+            let resp = warp::test::request()
+                .path("/indexes/hdfs-logs")
+                .method("PUT")
+                .json(&true)
+                .body(r#"{"version": "0.6", "index_id": "hdfs-logs", "index_uri": "file:///default-index-root-uri/hdfs-logs", "doc_mapping": {"field_mappings":[{"name": "timestamp", "type": "i64", "fast": true, "indexed": true}]}}"#)
+                .reply(&index_management_handler)
+                .await;
+            assert_eq!(resp.status(), 400);
+            let body = std::str::from_utf8(resp.body()).unwrap();
+            assert!(body.contains("index URI cannot be updated"));
+        }
+    }
+
     #[tokio::test]
     async fn test_create_delete_index_and_source() {
         let mut metastore = metastore_for_test();
@@ -33,6 +33,7 @@ use serde::Deserialize;
 use thiserror::Error;
 use warp::{Filter, Rejection};
 
+use crate::decompression::get_body_bytes;
 use crate::format::extract_format_from_qs;
 use crate::json_api_response::make_json_api_response;
 use crate::{with_arg, BodyFormat};
@@ -78,10 +79,7 @@ fn ingest_filter(
 ) -> impl Filter<Extract = (String, Bytes, IngestOptions), Error = Rejection> + Clone {
     warp::path!(String / "ingest")
         .and(warp::post())
-        .and(warp::body::content_length_limit(
-            config.content_length_limit,
-        ))
-        .and(warp::body::bytes())
+        .and(get_body_bytes(config.content_length_limit))
         .and(serde_qs::warp::query::<IngestOptions>(
             serde_qs::Config::default(),
         ))
@@ -102,10 +100,7 @@ fn ingest_v2_filter(
 ) -> impl Filter<Extract = (String, Bytes, IngestOptions), Error = Rejection> + Clone {
     warp::path!(String / "ingest-v2")
         .and(warp::post())
-        .and(warp::body::content_length_limit(
-            config.content_length_limit,
-        ))
-        .and(warp::body::bytes())
+        .and(get_body_bytes(config.content_length_limit))
         .and(serde_qs::warp::query::<IngestOptions>(
             serde_qs::Config::default(),
         ))
@@ -228,9 +223,12 @@ pub(crate) fn lines(body: &Bytes) -> impl Iterator<Item = &[u8]> {
 
 #[cfg(test)]
 pub(crate) mod tests {
+    use std::io::Write;
     use std::time::Duration;
 
     use byte_unit::Byte;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
     use quickwit_actors::{Mailbox, Universe};
     use quickwit_config::IngestApiConfig;
     use quickwit_ingest::{
@@ -330,6 +328,32 @@ pub(crate) mod tests {
         universe.assert_quit().await;
     }
 
+    #[tokio::test]
+    async fn test_ingest_api_returns_200_when_ingest_gzip_ndjson() {
+        let (universe, _temp_dir, ingest_service, _) =
+            setup_ingest_service(&["my-index"], &IngestApiConfig::default()).await;
+        let ingest_router = IngestRouterServiceClient::mock().into();
+        let ingest_api_handlers =
+            ingest_api_handlers(ingest_router, ingest_service, IngestApiConfig::default());
+        let payload = r#"
+            {"id": 1, "message": "push"}
+            {"id": 2, "message": "push"}"#;
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(payload.as_bytes()).unwrap();
+        let resp = warp::test::request()
+            .path("/my-index/ingest")
+            .method("POST")
+            .header("content-encoding", "gzip")
+            .body(encoder.finish().unwrap())
+            .reply(&ingest_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let ingest_response: IngestResponse = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(ingest_response.num_docs_for_processing, 2);
+
+        universe.assert_quit().await;
+    }
+
     #[tokio::test]
     async fn test_ingest_api_return_429_if_above_limits() {
         let config = IngestApiConfig {
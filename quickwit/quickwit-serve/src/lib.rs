@@ -19,6 +19,8 @@
 
 mod build_info;
 mod cluster_api;
+mod dead_letter_api;
+mod decompression;
 mod delete_task_api;
 mod elastic_search_api;
 mod format;
@@ -28,14 +30,18 @@ mod index_api;
 mod indexing_api;
 mod ingest_api;
 mod json_api_response;
+mod loki_api;
 mod metrics;
 mod metrics_api;
 mod node_info_handler;
 mod openapi;
+mod otlp_api;
+mod prometheus_api;
 mod rate_modulator;
 mod rest;
 mod search_api;
 pub(crate) mod simple_list;
+mod sql_api;
 mod ui_handler;
 
 use std::collections::{HashMap, HashSet};
@@ -71,11 +77,14 @@ use quickwit_ingest::{
     start_ingest_api_service, GetMemoryCapacity, IngestApiService, IngestRequest, IngestRouter,
     IngestServiceClient, Ingester, IngesterPool,
 };
+use quickwit_jaeger::{dependencies_index_config, dependencies_summary_task};
 use quickwit_janitor::{start_janitor_service, JanitorService};
 use quickwit_metastore::{
     ControlPlaneMetastore, ListIndexesMetadataResponseExt, MetastoreResolver,
 };
-use quickwit_opentelemetry::otlp::{OtlpGrpcLogsService, OtlpGrpcTracesService};
+use quickwit_opentelemetry::otlp::{
+    OtlpGrpcLogsService, OtlpGrpcMetricsService, OtlpGrpcTracesService,
+};
 use quickwit_proto::control_plane::ControlPlaneServiceClient;
 use quickwit_proto::indexing::IndexingServiceClient;
 use quickwit_proto::ingest::ingester::IngesterServiceClient;
@@ -98,8 +107,10 @@ use tracing::{debug, error, info, warn};
 use warp::{Filter, Rejection};
 
 pub use crate::build_info::{BuildInfo, RuntimeInfo};
+pub use crate::dead_letter_api::{DeadLetterReplayResponse, DeadLetterSearchResponse};
 pub use crate::index_api::{ListSplitsQueryParams, ListSplitsResponse};
 pub use crate::metrics::SERVE_METRICS;
+use crate::prometheus_api::prometheus_index_config;
 use crate::rate_modulator::RateModulator;
 #[cfg(test)]
 use crate::rest::recover_fn;
@@ -125,6 +136,9 @@ struct QuickwitServices {
     pub ingest_router_service: IngestRouterServiceClient,
     pub ingester_service_opt: Option<IngesterServiceClient>,
     pub janitor_service_opt: Option<Mailbox<JanitorService>>,
+    pub otlp_logs_service_opt: Option<OtlpGrpcLogsService>,
+    pub otlp_metrics_service_opt: Option<OtlpGrpcMetricsService>,
+    pub otlp_traces_service_opt: Option<OtlpGrpcTracesService>,
     /// We do have a search service even on nodes that are not running `search`.
     /// It is only used to serve the rest API calls and will only execute
     /// the root requests.
@@ -381,10 +395,16 @@ pub async fn serve_quickwit(
         {
             let otel_logs_index_config =
                 OtlpGrpcLogsService::index_config(&node_config.default_index_root_uri)?;
+            let otel_metrics_index_config =
+                OtlpGrpcMetricsService::index_config(&node_config.default_index_root_uri)?;
             let otel_traces_index_config =
                 OtlpGrpcTracesService::index_config(&node_config.default_index_root_uri)?;
 
-            for index_config in [otel_logs_index_config, otel_traces_index_config] {
+            for index_config in [
+                otel_logs_index_config,
+                otel_metrics_index_config,
+                otel_traces_index_config,
+            ] {
                 match index_manager.create_index(index_config, false).await {
                     Ok(_)
                     | Err(IndexServiceError::Metastore(MetastoreError::AlreadyExists(
@@ -395,6 +415,40 @@ pub async fn serve_quickwit(
             }
         }
     }
+    if node_config.is_service_enabled(QuickwitService::Indexer)
+        && node_config.indexer_config.enable_prometheus_endpoint
+    {
+        let prometheus_index_config = prometheus_index_config(&node_config.default_index_root_uri)?;
+
+        match index_manager
+            .create_index(prometheus_index_config, false)
+            .await
+        {
+            Ok(_)
+            | Err(IndexServiceError::Metastore(MetastoreError::AlreadyExists(
+                EntityKind::Index { .. },
+            ))) => Ok(()),
+            Err(error) => Err(error),
+        }?;
+    }
+    // The OTLP services are exposed over gRPC and HTTP if `QuickwitService::Indexer` is enabled
+    // on node.
+    let (otlp_logs_service_opt, otlp_metrics_service_opt, otlp_traces_service_opt) = if node_config
+        .is_service_enabled(QuickwitService::Indexer)
+        && node_config.indexer_config.enable_otlp_endpoint
+    {
+        let commit_type_opt = None;
+        (
+            Some(OtlpGrpcLogsService::new(ingest_service.clone())),
+            Some(OtlpGrpcMetricsService::new(ingest_service.clone())),
+            Some(OtlpGrpcTracesService::new(
+                ingest_service.clone(),
+                commit_type_opt,
+            )),
+        )
+    } else {
+        (None, None, None)
+    };
 
     let cluster_change_stream = cluster.ready_nodes_change_stream().await;
 
@@ -450,6 +504,33 @@ pub async fn serve_quickwit(
     } else {
         None
     };
+    // The Jaeger dependency graph is summarized by the janitor, of which there is a single instance
+    // in the cluster.
+    let mut dependencies_summary_handle_opt = None;
+
+    if node_config.is_service_enabled(QuickwitService::Janitor)
+        && node_config.jaeger_config.enable_dependencies_summary
+    {
+        let dependencies_index_config =
+            dependencies_index_config(&node_config.default_index_root_uri)?;
+
+        match index_manager
+            .create_index(dependencies_index_config, false)
+            .await
+        {
+            Ok(_)
+            | Err(IndexServiceError::Metastore(MetastoreError::AlreadyExists(
+                EntityKind::Index { .. },
+            ))) => Ok(()),
+            Err(error) => Err(error),
+        }?;
+        let dependencies_summary_handle = tokio::spawn(dependencies_summary_task(
+            node_config.jaeger_config.clone(),
+            search_service.clone(),
+            ingest_service.clone(),
+        ));
+        dependencies_summary_handle_opt = Some(dependencies_summary_handle);
+    }
 
     let grpc_listen_addr = node_config.grpc_listen_addr;
     let rest_listen_addr = node_config.rest_listen_addr;
@@ -466,6 +547,9 @@ pub async fn serve_quickwit(
         ingest_service,
         ingester_service_opt,
         janitor_service_opt,
+        otlp_logs_service_opt,
+        otlp_metrics_service_opt,
+        otlp_traces_service_opt,
         search_service,
     });
     // Setup and start gRPC server.
@@ -518,6 +602,12 @@ pub async fn serve_quickwit(
     let shutdown_handle = tokio::spawn(async move {
         shutdown_signal.await;
 
+        // The summary task resumes from the last window stored in the summary index when the node
+        // restarts, so it can be stopped at any point.
+        if let Some(dependencies_summary_handle) = dependencies_summary_handle_opt {
+            dependencies_summary_handle.abort();
+        }
+
         if grpc_shutdown_trigger_tx.send(()).is_err() {
             debug!("gRPC server shutdown signal receiver was dropped.");
         }
@@ -0,0 +1,638 @@
+// Copyright (C) 2023 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Parser for the subset of LogQL supported by the Loki API:
+//!
+//! - stream selectors: `{service="api", level=~"warn|error", env!="dev"}`;
+//! - line filters: `|= "timeout"` and `!= "debug"`;
+//! - range aggregations: `count_over_time(<log query> [5m])` and `rate(<log query> [5m])`;
+//! - the `sum` vector aggregation, optionally grouped `by` labels.
+
+use std::time::Duration;
+
+use quickwit_query::query_ast::{
+    BoolQuery, FieldPresenceQuery, FullTextMode, FullTextParams, FullTextQuery, QueryAst,
+    RegexQuery, TermQuery, DEFAULT_AUTOMATON_MAX_EXPANSIONS,
+};
+use quickwit_query::MatchAllOrNone;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum MatchOperator {
+    Eq,
+    NotEq,
+    Regex,
+    NotRegex,
+}
+
+/// Label matcher of a stream selector.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct LabelMatcher {
+    pub label: String,
+    pub operator: MatchOperator,
+    pub value: String,
+}
+
+/// Line filter: `|= "text"` keeps the lines containing the text, `!= "text"` drops them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct LineFilter {
+    pub text: String,
+    pub negated: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct LogQuery {
+    pub matchers: Vec<LabelMatcher>,
+    pub line_filters: Vec<LineFilter>,
+}
+
+impl LogQuery {
+    /// Converts the stream selector into term queries over the label fields, and the line
+    /// filters into full-text queries over the default search fields.
+    pub fn to_query_ast(&self, default_search_fields: &[String]) -> Result<QueryAst, String> {
+        let mut bool_query = BoolQuery::default();
+
+        for matcher in &self.matchers {
+            let field = matcher.label.clone();
+            // In Loki, matching the empty string matches the streams without the label.
+            let (clause, negated) = match matcher.operator {
+                MatchOperator::Eq | MatchOperator::NotEq if matcher.value.is_empty() => (
+                    FieldPresenceQuery { field }.into(),
+                    matcher.operator == MatchOperator::Eq,
+                ),
+                MatchOperator::Eq | MatchOperator::NotEq => (
+                    TermQuery {
+                        field,
+                        value: matcher.value.clone(),
+                    }
+                    .into(),
+                    matcher.operator == MatchOperator::NotEq,
+                ),
+                MatchOperator::Regex | MatchOperator::NotRegex => (
+                    RegexQuery {
+                        field,
+                        regex: matcher.value.clone(),
+                        max_expansions: DEFAULT_AUTOMATON_MAX_EXPANSIONS,
+                    }
+                    .into(),
+                    matcher.operator == MatchOperator::NotRegex,
+                ),
+            };
+            if negated {
+                bool_query.must_not.push(clause);
+            } else {
+                bool_query.must.push(clause);
+            }
+        }
+        for line_filter in &self.line_filters {
+            if default_search_fields.is_empty() {
+                return Err(
+                    "line filters require the index to define default search fields".to_string(),
+                );
+            }
+            let mut field_queries: Vec<QueryAst> = default_search_fields
+                .iter()
+                .map(|field| {
+                    FullTextQuery {
+                        field: field.clone(),
+                        text: line_filter.text.clone(),
+                        params: FullTextParams {
+                            tokenizer: None,
+                            mode: FullTextMode::PhraseFallbackToIntersection,
+                            zero_terms_query: MatchAllOrNone::MatchAll,
+                        },
+                    }
+                    .into()
+                })
+                .collect();
+            let clause = if field_queries.len() == 1 {
+                field_queries.pop().unwrap()
+            } else {
+                BoolQuery {
+                    should: field_queries,
+                    ..Default::default()
+                }
+                .into()
+            };
+            if line_filter.negated {
+                bool_query.must_not.push(clause);
+            } else {
+                bool_query.must.push(clause);
+            }
+        }
+        if bool_query == BoolQuery::default() {
+            return Ok(QueryAst::MatchAll);
+        }
+        Ok(bool_query.into())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RangeFunction {
+    CountOverTime,
+    Rate,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct MetricQuery {
+    pub function: RangeFunction,
+    pub log_query: LogQuery,
+    pub range: Duration,
+    /// Labels the series are grouped by. Without `sum` aggregation, the series are those of the
+    /// labels of the stream selector.
+    pub group_by: Vec<String>,
+    /// Whether the grouping labels were set by a `sum` aggregation rather than taken from the
+    /// stream selector.
+    pub explicit_group_by: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum LogqlQuery {
+    Log(LogQuery),
+    Metric(MetricQuery),
+}
+
+pub(crate) fn parse_logql(query: &str) -> Result<LogqlQuery, String> {
+    let mut parser = Parser {
+        input: query,
+        pos: 0,
+    };
+    let logql_query = parser.parse_query()?;
+    parser.skip_whitespaces();
+
+    if parser.pos < parser.input.len() {
+        return Err(parser.unexpected("end of query"));
+    }
+    Ok(logql_query)
+}
+
+/// Parses a stream selector, such as the `match[]` parameters of the series endpoint.
+pub(crate) fn parse_stream_selector(selector: &str) -> Result<LogQuery, String> {
+    let mut parser = Parser {
+        input: selector,
+        pos: 0,
+    };
+    let matchers = parser.parse_selector()?;
+    parser.skip_whitespaces();
+
+    if parser.pos < parser.input.len() {
+        return Err(parser.unexpected("end of selector"));
+    }
+    Ok(LogQuery {
+        matchers,
+        line_filters: Vec::new(),
+    })
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    fn skip_whitespaces(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn unexpected(&self, expected: &str) -> String {
+        let rest = self.rest();
+        if rest.is_empty() {
+            return format!("expected {expected}, found end of query");
+        }
+        let found: String = rest.chars().take(10).collect();
+        format!("expected {expected}, found `{found}`")
+    }
+
+    fn consume(&mut self, token: &str) -> bool {
+        self.skip_whitespaces();
+
+        if self.rest().starts_with(token) {
+            self.pos += token.len();
+            return true;
+        }
+        false
+    }
+
+    fn expect(&mut self, token: &str) -> Result<(), String> {
+        if self.consume(token) {
+            return Ok(());
+        }
+        Err(self.unexpected(&format!("`{token}`")))
+    }
+
+    fn parse_identifier(&mut self) -> Result<String, String> {
+        self.skip_whitespaces();
+        let rest = self.rest();
+        let len = rest
+            .char_indices()
+            .find(|&(idx, c)| {
+                !(c.is_ascii_alphabetic()
+                    || c == '_'
+                    || (idx > 0 && (c.is_ascii_digit() || c == '.')))
+            })
+            .map(|(idx, _)| idx)
+            .unwrap_or(rest.len());
+        if len == 0 {
+            return Err(self.unexpected("an identifier"));
+        }
+        self.pos += len;
+        Ok(rest[..len].to_string())
+    }
+
+    /// Parses a string quoted with double quotes, where backslashes escape characters, or with
+    /// backticks, where they don't.
+    fn parse_string(&mut self) -> Result<String, String> {
+        self.skip_whitespaces();
+        let mut chars = self.rest().char_indices();
+
+        let quote = match chars.next() {
+            Some((_, quote @ ('"' | '`'))) => quote,
+            _ => return Err(self.unexpected("a string")),
+        };
+        let mut value = String::new();
+
+        while let Some((idx, c)) = chars.next() {
+            if c == quote {
+                self.pos += idx + 1;
+                return Ok(value);
+            }
+            if c == '\\' && quote == '"' {
+                let escaped_char = match chars.next() {
+                    Some((_, 'n')) => '\n',
+                    Some((_, 't')) => '\t',
+                    Some((_, 'r')) => '\r',
+                    Some((_, escaped_char)) => escaped_char,
+                    None => break,
+                };
+                value.push(escaped_char);
+            } else {
+                value.push(c);
+            }
+        }
+        Err("unterminated string".to_string())
+    }
+
+    fn parse_query(&mut self) -> Result<LogqlQuery, String> {
+        self.skip_whitespaces();
+
+        if self.rest().starts_with('{') {
+            let log_query = self.parse_log_query()?;
+            return Ok(LogqlQuery::Log(log_query));
+        }
+        let function_name = self.parse_identifier()?;
+
+        if function_name == "sum" {
+            let mut group_by = self.parse_by_clause()?;
+            self.expect("(")?;
+            let function_name = self.parse_identifier()?;
+            let mut metric_query = self.parse_range_aggregation(&function_name)?;
+            self.expect(")")?;
+
+            if group_by.is_none() {
+                group_by = self.parse_by_clause()?;
+            }
+            metric_query.group_by = group_by.unwrap_or_default();
+            metric_query.explicit_group_by = true;
+            return Ok(LogqlQuery::Metric(metric_query));
+        }
+        let metric_query = self.parse_range_aggregation(&function_name)?;
+        Ok(LogqlQuery::Metric(metric_query))
+    }
+
+    fn parse_by_clause(&mut self) -> Result<Option<Vec<String>>, String> {
+        let rest_before = self.pos;
+        self.skip_whitespaces();
+
+        if !self.rest().starts_with("by") {
+            self.pos = rest_before;
+            return Ok(None);
+        }
+        if self.parse_identifier()? != "by" {
+            return Err("only the `by` grouping clause is supported".to_string());
+        }
+        self.expect("(")?;
+        let mut labels = vec![self.parse_identifier()?];
+
+        while self.consume(",") {
+            labels.push(self.parse_identifier()?);
+        }
+        self.expect(")")?;
+        Ok(Some(labels))
+    }
+
+    fn parse_range_aggregation(&mut self, function_name: &str) -> Result<MetricQuery, String> {
+        let function = match function_name {
+            "count_over_time" => RangeFunction::CountOverTime,
+            "rate" => RangeFunction::Rate,
+            _ => {
+                return Err(format!(
+                    "unsupported function `{function_name}`, expected `count_over_time`, `rate`, \
+                     or `sum`"
+                ));
+            }
+        };
+        self.expect("(")?;
+        let log_query = self.parse_log_query()?;
+        self.expect("[")?;
+
+        let Some(range_len) = self.rest().find(']') else {
+            return Err(self.unexpected("`]`"));
+        };
+        let range_str = self.rest()[..range_len].trim().to_string();
+        let range = humantime::parse_duration(&range_str)
+            .map_err(|_| format!("invalid range `{range_str}`"))?;
+
+        if range.as_millis() == 0 {
+            return Err(format!("invalid range `{range_str}`"));
+        }
+        self.pos += range_len + 1;
+        self.expect(")")?;
+
+        // Unless the series are grouped, they are those of the stream selector's labels.
+        let mut group_by: Vec<String> = Vec::new();
+        for matcher in &log_query.matchers {
+            if !group_by.contains(&matcher.label) {
+                group_by.push(matcher.label.clone());
+            }
+        }
+        Ok(MetricQuery {
+            function,
+            log_query,
+            range,
+            group_by,
+            explicit_group_by: false,
+        })
+    }
+
+    fn parse_log_query(&mut self) -> Result<LogQuery, String> {
+        let matchers = self.parse_selector()?;
+        let mut line_filters = Vec::new();
+
+        loop {
+            let negated = if self.consume("|=") {
+                false
+            } else if self.consume("!=") {
+                true
+            } else if self.consume("|~") || self.consume("!~") {
+                return Err("regular expression line filters are not supported".to_string());
+            } else if self.consume("|") {
+                return Err("parser and formatting pipeline stages are not supported".to_string());
+            } else {
+                break;
+            };
+            let text = self.parse_string()?;
+            line_filters.push(LineFilter { text, negated });
+        }
+        Ok(LogQuery {
+            matchers,
+            line_filters,
+        })
+    }
+
+    fn parse_selector(&mut self) -> Result<Vec<LabelMatcher>, String> {
+        self.expect("{")?;
+        let mut matchers = Vec::new();
+
+        if self.consume("}") {
+            return Ok(matchers);
+        }
+        loop {
+            let label = self.parse_identifier()?;
+            let operator = if self.consume("=~") {
+                MatchOperator::Regex
+            } else if self.consume("!~") {
+                MatchOperator::NotRegex
+            } else if self.consume("!=") {
+                MatchOperator::NotEq
+            } else if self.consume("=") {
+                MatchOperator::Eq
+            } else {
+                return Err(self.unexpected("`=`, `!=`, `=~`, or `!~`"));
+            };
+            let value = self.parse_string()?;
+            matchers.push(LabelMatcher {
+                label,
+                operator,
+                value,
+            });
+            if self.consume("}") {
+                return Ok(matchers);
+            }
+            self.expect(",")?;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matcher(label: &str, operator: MatchOperator, value: &str) -> LabelMatcher {
+        LabelMatcher {
+            label: label.to_string(),
+            operator,
+            value: value.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_logql_log_query() {
+        let logql_query =
+            parse_logql(r#"{service="api", level=~"warn|error", env!=""} |= "time\"out" != `a\b`"#)
+                .unwrap();
+        assert_eq!(
+            logql_query,
+            LogqlQuery::Log(LogQuery {
+                matchers: vec![
+                    matcher("service", MatchOperator::Eq, "api"),
+                    matcher("level", MatchOperator::Regex, "warn|error"),
+                    matcher("env", MatchOperator::NotEq, ""),
+                ],
+                line_filters: vec![
+                    LineFilter {
+                        text: "time\"out".to_string(),
+                        negated: false,
+                    },
+                    LineFilter {
+                        text: "a\\b".to_string(),
+                        negated: true,
+                    },
+                ],
+            })
+        );
+        assert_eq!(
+            parse_logql("{}").unwrap(),
+            LogqlQuery::Log(LogQuery {
+                matchers: Vec::new(),
+                line_filters: Vec::new(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_logql_metric_query() {
+        let logql_query =
+            parse_logql(r#"rate({service="api", host!~"db-.*"} |= "error" [5m])"#).unwrap();
+        assert_eq!(
+            logql_query,
+            LogqlQuery::Metric(MetricQuery {
+                function: RangeFunction::Rate,
+                log_query: LogQuery {
+                    matchers: vec![
+                        matcher("service", MatchOperator::Eq, "api"),
+                        matcher("host", MatchOperator::NotRegex, "db-.*"),
+                    ],
+                    line_filters: vec![LineFilter {
+                        text: "error".to_string(),
+                        negated: false,
+                    }],
+                },
+                range: Duration::from_secs(300),
+                group_by: vec!["service".to_string(), "host".to_string()],
+                explicit_group_by: false,
+            })
+        );
+        let LogqlQuery::Metric(metric_query) =
+            parse_logql(r#"sum by (level, resource.host) (count_over_time({service="api"}[1m]))"#)
+                .unwrap()
+        else {
+            panic!("expected a metric query");
+        };
+        assert_eq!(metric_query.function, RangeFunction::CountOverTime);
+        assert_eq!(metric_query.range, Duration::from_secs(60));
+        assert_eq!(metric_query.group_by, ["level", "resource.host"]);
+        assert!(metric_query.explicit_group_by);
+
+        let LogqlQuery::Metric(metric_query) =
+            parse_logql(r#"sum(count_over_time({service="api"}[30s])) by (level)"#).unwrap()
+        else {
+            panic!("expected a metric query");
+        };
+        assert_eq!(metric_query.group_by, ["level"]);
+
+        let LogqlQuery::Metric(metric_query) =
+            parse_logql(r#"sum(count_over_time({service="api"}[30s]))"#).unwrap()
+        else {
+            panic!("expected a metric query");
+        };
+        assert!(metric_query.group_by.is_empty());
+        assert!(metric_query.explicit_group_by);
+    }
+
+    #[test]
+    fn test_parse_logql_errors() {
+        assert_eq!(
+            parse_logql(r#"{service="api""#).unwrap_err(),
+            "expected `,`, found end of query"
+        );
+        assert_eq!(
+            parse_logql(r#"{service~"api"}"#).unwrap_err(),
+            "expected `=`, `!=`, `=~`, or `!~`, found `~\"api\"}`"
+        );
+        assert_eq!(
+            parse_logql(r#"{service="api"} | json"#).unwrap_err(),
+            "parser and formatting pipeline stages are not supported"
+        );
+        assert_eq!(
+            parse_logql(r#"{service="api"} |~ "err.*""#).unwrap_err(),
+            "regular expression line filters are not supported"
+        );
+        assert_eq!(
+            parse_logql(r#"bytes_over_time({service="api"}[1m])"#).unwrap_err(),
+            "unsupported function `bytes_over_time`, expected `count_over_time`, `rate`, or `sum`"
+        );
+        assert_eq!(
+            parse_logql(r#"rate({service="api"}[foo])"#).unwrap_err(),
+            "invalid range `foo`"
+        );
+        assert_eq!(
+            parse_logql(r#"{service="api"} foo"#).unwrap_err(),
+            "expected end of query, found `foo`"
+        );
+        assert_eq!(
+            parse_logql(r#"{service="api} "#).unwrap_err(),
+            "unterminated string"
+        );
+    }
+
+    #[test]
+    fn test_log_query_to_query_ast() {
+        let LogqlQuery::Log(log_query) =
+            parse_logql(r#"{service="api", host=~"db-.*", env=""} != "debug""#).unwrap()
+        else {
+            panic!("expected a log query");
+        };
+        let query_ast = log_query
+            .to_query_ast(&["body".to_string(), "attributes".to_string()])
+            .unwrap();
+        let full_text_query = |field: &str| -> QueryAst {
+            FullTextQuery {
+                field: field.to_string(),
+                text: "debug".to_string(),
+                params: FullTextParams {
+                    tokenizer: None,
+                    mode: FullTextMode::PhraseFallbackToIntersection,
+                    zero_terms_query: MatchAllOrNone::MatchAll,
+                },
+            }
+            .into()
+        };
+        assert_eq!(
+            query_ast,
+            BoolQuery {
+                must: vec![
+                    TermQuery {
+                        field: "service".to_string(),
+                        value: "api".to_string(),
+                    }
+                    .into(),
+                    RegexQuery {
+                        field: "host".to_string(),
+                        regex: "db-.*".to_string(),
+                        max_expansions: DEFAULT_AUTOMATON_MAX_EXPANSIONS,
+                    }
+                    .into(),
+                ],
+                must_not: vec![
+                    FieldPresenceQuery {
+                        field: "env".to_string(),
+                    }
+                    .into(),
+                    BoolQuery {
+                        should: vec![full_text_query("body"), full_text_query("attributes")],
+                        ..Default::default()
+                    }
+                    .into(),
+                ],
+                ..Default::default()
+            }
+            .into()
+        );
+        assert_eq!(
+            log_query.to_query_ast(&[]).unwrap_err(),
+            "line filters require the index to define default search fields"
+        );
+        let selector = parse_stream_selector(r#"{}"#).unwrap();
+        assert_eq!(selector.to_query_ast(&[]).unwrap(), QueryAst::MatchAll);
+    }
+}
@@ -0,0 +1,1403 @@
+// Copyright (C) 2023 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use quickwit_config::build_doc_mapper;
+use quickwit_datetime::{parse_date_time_str, DateTimeInputFormat};
+use quickwit_metastore::IndexMetadataResponseExt;
+use quickwit_proto::metastore::{
+    IndexMetadataRequest, MetastoreError, MetastoreService, MetastoreServiceClient,
+};
+use quickwit_proto::search::{
+    Hit, ListTermsRequest, SearchRequest, SortField, SortOrder, SortValue,
+};
+use quickwit_proto::types::IndexId;
+use quickwit_proto::{ServiceError, ServiceErrorCode};
+use quickwit_search::{SearchError, SearchService};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map as JsonMap, Value as JsonValue};
+use tantivy::schema::FieldType;
+use thiserror::Error;
+use warp::{Filter, Rejection};
+
+use super::logql::{
+    parse_logql, parse_stream_selector, LogQuery, LogqlQuery, MetricQuery, RangeFunction,
+};
+use crate::json_api_response::make_json_api_response;
+use crate::{with_arg, BodyFormat};
+
+/// Number of log lines returned by a log query when the request does not set a limit.
+const DEFAULT_LIMIT: u64 = 100;
+
+/// Maximum number of values of each grouping label a metric query returns series for.
+const MAX_NUM_SERIES: u64 = 1_000;
+
+/// Maximum number of values returned by the label values endpoint.
+const MAX_NUM_LABEL_VALUES: u64 = 1_000;
+
+/// Number of most recent log lines the series endpoint collects label sets from.
+const MAX_SERIES_HITS: u64 = 1_000;
+
+/// Maximum number of samples, and of `date_histogram` buckets, per series of a metric query,
+/// like the maximum resolution of Loki.
+const MAX_NUM_POINTS: i64 = 11_000;
+
+/// Number of samples per series of a metric query when the request does not set a step.
+const DEFAULT_NUM_SAMPLES: i64 = 250;
+
+const NANOS_PER_SEC: i64 = 1_000_000_000;
+
+const NANOS_PER_MILLI: i64 = 1_000_000;
+
+/// Time range queried when the request does not set a start, in nanoseconds.
+const DEFAULT_LOOKBACK_NANOS: i64 = 3_600 * NANOS_PER_SEC;
+
+#[derive(utoipa::OpenApi)]
+#[openapi(
+    paths(loki_query_range, loki_labels, loki_label_values, loki_series,),
+    components(schemas(LokiDirection))
+)]
+pub struct LokiApi;
+
+#[derive(Debug, Error)]
+pub(crate) enum LokiApiError {
+    #[error("internal error: {0}")]
+    Internal(String),
+    #[error("invalid request: {0}")]
+    InvalidRequest(String),
+    #[error(transparent)]
+    Metastore(#[from] MetastoreError),
+    #[error(transparent)]
+    Search(#[from] SearchError),
+}
+
+impl ServiceError for LokiApiError {
+    fn error_code(&self) -> ServiceErrorCode {
+        match self {
+            Self::Internal(_) => ServiceErrorCode::Internal,
+            Self::InvalidRequest(_) => ServiceErrorCode::BadRequest,
+            Self::Metastore(error) => error.error_code(),
+            Self::Search(error) => error.error_code(),
+        }
+    }
+}
+
+/// Order in which log lines are returned.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LokiDirection {
+    /// Most recent log lines first.
+    #[default]
+    Backward,
+    /// Oldest log lines first.
+    Forward,
+}
+
+impl LokiDirection {
+    fn sort_order(self) -> SortOrder {
+        match self {
+            Self::Backward => SortOrder::Desc,
+            Self::Forward => SortOrder::Asc,
+        }
+    }
+}
+
+#[derive(Debug, Default, Eq, PartialEq, Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+struct LokiQueryRangeParams {
+    /// The LogQL query.
+    query: String,
+    /// Start of the time range, as a Unix timestamp in seconds or nanoseconds, or as a RFC 3339
+    /// datetime. Defaults to one hour before the end.
+    start: Option<String>,
+    /// End of the time range. Defaults to now.
+    end: Option<String>,
+    /// Maximum number of log lines returned by a log query. Defaults to 100.
+    limit: Option<u64>,
+    /// Order of the log lines returned by a log query. Defaults to `backward`.
+    #[serde(default)]
+    direction: LokiDirection,
+    /// Interval between the samples of a metric query, as a duration (e.g. `30s`) or a number of
+    /// seconds. Defaults to the time range divided by 250, and at least one second.
+    step: Option<String>,
+}
+
+#[derive(Debug, Default, Eq, PartialEq, Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+struct LokiTimeRangeParams {
+    /// Start of the time range. Defaults to one hour before the end.
+    start: Option<String>,
+    /// End of the time range. Defaults to now.
+    end: Option<String>,
+}
+
+#[derive(Debug, Default, Eq, PartialEq, Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+struct LokiSeriesParams {
+    /// Stream selectors, passed as `match[]`, the series of which are returned.
+    #[serde(rename = "match", default)]
+    matches: Vec<String>,
+    /// Start of the time range. Defaults to one hour before the end.
+    start: Option<String>,
+    /// End of the time range. Defaults to now.
+    end: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct LokiResponse<T> {
+    status: &'static str,
+    data: T,
+}
+
+impl<T> LokiResponse<T> {
+    fn success(data: T) -> Self {
+        Self {
+            status: "success",
+            data,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(tag = "resultType", content = "result", rename_all = "snake_case")]
+enum LokiQueryResult {
+    Streams(Vec<LokiStream>),
+    Matrix(Vec<LokiSeries>),
+}
+
+/// Log lines sharing the same labels, as `[<timestamp in nanoseconds>, <line>]` pairs.
+#[derive(Debug, PartialEq, Serialize)]
+struct LokiStream {
+    stream: BTreeMap<String, String>,
+    values: Vec<[String; 2]>,
+}
+
+/// Samples of a metric series, as `[<timestamp in seconds>, <value>]` pairs.
+#[derive(Debug, PartialEq, Serialize)]
+struct LokiSeries {
+    metric: BTreeMap<String, String>,
+    values: Vec<(f64, String)>,
+}
+
+/// Time range of a Loki request, in nanoseconds since epoch, both ends included.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+struct TimeRange {
+    start_nanos: i64,
+    end_nanos: i64,
+}
+
+impl TimeRange {
+    fn parse(start_opt: Option<&str>, end_opt: Option<&str>) -> Result<Self, LokiApiError> {
+        let end_nanos = match end_opt {
+            Some(end) => parse_loki_timestamp(end)?,
+            None => SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_nanos() as i64)
+                .unwrap_or_default(),
+        };
+        let start_nanos = match start_opt {
+            Some(start) => parse_loki_timestamp(start)?,
+            None => end_nanos - DEFAULT_LOOKBACK_NANOS,
+        };
+        if start_nanos > end_nanos {
+            return Err(LokiApiError::InvalidRequest(
+                "the start of the time range must not be after its end".to_string(),
+            ));
+        }
+        Ok(Self {
+            start_nanos,
+            end_nanos,
+        })
+    }
+
+    /// Start of the search time filter, in seconds, included.
+    fn start_timestamp(&self) -> i64 {
+        self.start_nanos.div_euclid(NANOS_PER_SEC)
+    }
+
+    /// End of the search time filter, in seconds, excluded.
+    fn end_timestamp(&self) -> i64 {
+        self.end_nanos.div_euclid(NANOS_PER_SEC) + 1
+    }
+}
+
+/// Parses the step of a metric query, as a number of seconds or a duration. Returns
+/// milliseconds.
+fn parse_loki_step(step_str: &str) -> Result<i64, LokiApiError> {
+    let step_millis = match step_str.parse::<f64>() {
+        Ok(step_secs) if step_secs.is_finite() => (step_secs * 1_000.0) as i64,
+        _ => humantime::parse_duration(step_str)
+            .map(|step| step.as_millis() as i64)
+            .map_err(|_| LokiApiError::InvalidRequest(format!("invalid step `{step_str}`")))?,
+    };
+    if step_millis <= 0 {
+        return Err(LokiApiError::InvalidRequest(format!(
+            "invalid step `{step_str}`, it must be positive"
+        )));
+    }
+    Ok(step_millis)
+}
+
+/// Parses a Loki timestamp: a Unix timestamp in seconds or nanoseconds, a Unix timestamp in
+/// seconds with a fractional part, or a RFC 3339 datetime. Returns nanoseconds since epoch.
+fn parse_loki_timestamp(timestamp_str: &str) -> Result<i64, LokiApiError> {
+    if let Ok(timestamp) = timestamp_str.parse::<i64>() {
+        // Like Loki, integers of up to 10 digits are interpreted as seconds.
+        if timestamp_str.trim_start_matches('-').len() <= 10 {
+            return Ok(timestamp.saturating_mul(NANOS_PER_SEC));
+        }
+        return Ok(timestamp);
+    }
+    if let Ok(timestamp_secs) = timestamp_str.parse::<f64>() {
+        if timestamp_secs.is_finite() {
+            return Ok((timestamp_secs * NANOS_PER_SEC as f64) as i64);
+        }
+    }
+    parse_date_time_str(timestamp_str, &[DateTimeInputFormat::Rfc3339])
+        .map(|date_time| date_time.into_timestamp_nanos())
+        .map_err(|_| LokiApiError::InvalidRequest(format!("invalid timestamp `{timestamp_str}`")))
+}
+
+/// Fields of an index Loki queries rely on.
+#[derive(Debug)]
+struct LokiIndexContext {
+    timestamp_field: String,
+    /// Tag fields and fast text fields, exposed as stream labels.
+    label_fields: Vec<String>,
+    /// Label fields backed by a fast field, which metric series can be grouped by.
+    fast_label_fields: Vec<String>,
+    /// Fields searched by line filters.
+    default_search_fields: Vec<String>,
+}
+
+async fn load_index_context(
+    index_id: &str,
+    mut metastore: MetastoreServiceClient,
+) -> Result<LokiIndexContext, LokiApiError> {
+    let index_metadata_request = IndexMetadataRequest::for_index_id(index_id.to_string());
+    let index_config = metastore
+        .index_metadata(index_metadata_request)
+        .await?
+        .deserialize_index_metadata()?
+        .into_index_config();
+    let doc_mapper = build_doc_mapper(&index_config.doc_mapping, &index_config.search_settings)
+        .map_err(|error| LokiApiError::Internal(error.to_string()))?;
+
+    let Some(timestamp_field) = doc_mapper.timestamp_field_name() else {
+        return Err(LokiApiError::InvalidRequest(format!(
+            "index `{index_id}` has no timestamp field"
+        )));
+    };
+    let fast_label_fields: BTreeSet<String> = doc_mapper
+        .schema()
+        .fields()
+        .filter(|(_, field_entry)| {
+            matches!(field_entry.field_type(), FieldType::Str(_)) && field_entry.is_fast()
+        })
+        .map(|(_, field_entry)| field_entry.name().to_string())
+        .collect();
+    let mut label_fields = doc_mapper.tag_field_names();
+    label_fields.extend(fast_label_fields.iter().cloned());
+
+    Ok(LokiIndexContext {
+        timestamp_field: timestamp_field.to_string(),
+        label_fields: label_fields.into_iter().collect(),
+        fast_label_fields: fast_label_fields.into_iter().collect(),
+        default_search_fields: doc_mapper.default_search_fields().to_vec(),
+    })
+}
+
+/// Loki API handlers, mounted under `/<index_id>/loki/api/v1`, so that the URL of a Grafana Loki
+/// data source querying an index is `http://<host>:7280/api/v1/<index_id>`.
+pub(crate) fn loki_api_handlers(
+    search_service: Arc<dyn SearchService>,
+    metastore: MetastoreServiceClient,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    loki_query_range_handler(search_service.clone(), metastore.clone())
+        .or(loki_labels_handler(metastore.clone()))
+        .or(loki_label_values_handler(
+            search_service.clone(),
+            metastore.clone(),
+        ))
+        .or(loki_series_handler(search_service, metastore))
+}
+
+fn loki_query_range_filter(
+) -> impl Filter<Extract = (IndexId, LokiQueryRangeParams), Error = Rejection> + Clone {
+    warp::path!(String / "loki" / "api" / "v1" / "query_range")
+        .and(warp::get())
+        .and(serde_qs::warp::query(serde_qs::Config::default()))
+}
+
+fn loki_query_range_handler(
+    search_service: Arc<dyn SearchService>,
+    metastore: MetastoreServiceClient,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    loki_query_range_filter()
+        .and(with_arg(search_service))
+        .and(with_arg(metastore))
+        .then(loki_query_range)
+        .map(|result| make_json_api_response(result, BodyFormat::default()))
+}
+
+fn loki_labels_filter() -> impl Filter<Extract = (IndexId,), Error = Rejection> + Clone {
+    warp::path!(String / "loki" / "api" / "v1" / "labels").and(warp::get())
+}
+
+fn loki_labels_handler(
+    metastore: MetastoreServiceClient,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    loki_labels_filter()
+        .and(with_arg(metastore))
+        .then(loki_labels)
+        .map(|result| make_json_api_response(result, BodyFormat::default()))
+}
+
+fn loki_label_values_filter(
+) -> impl Filter<Extract = (IndexId, String, LokiTimeRangeParams), Error = Rejection> + Clone {
+    warp::path!(String / "loki" / "api" / "v1" / "label" / String / "values")
+        .and(warp::get())
+        .and(serde_qs::warp::query(serde_qs::Config::default()))
+}
+
+fn loki_label_values_handler(
+    search_service: Arc<dyn SearchService>,
+    metastore: MetastoreServiceClient,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    loki_label_values_filter()
+        .and(with_arg(search_service))
+        .and(with_arg(metastore))
+        .then(loki_label_values)
+        .map(|result| make_json_api_response(result, BodyFormat::default()))
+}
+
+fn loki_series_filter(
+) -> impl Filter<Extract = (IndexId, LokiSeriesParams), Error = Rejection> + Clone {
+    // The stream selectors are passed as `match[]`, which is percent-encoded by most clients, so
+    // the query string is parsed in non-strict mode.
+    warp::path!(String / "loki" / "api" / "v1" / "series")
+        .and(warp::get())
+        .and(serde_qs::warp::query(serde_qs::Config::new(5, false)))
+}
+
+fn loki_series_handler(
+    search_service: Arc<dyn SearchService>,
+    metastore: MetastoreServiceClient,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    loki_series_filter()
+        .and(with_arg(search_service))
+        .and(with_arg(metastore))
+        .then(loki_series)
+        .map(|result| make_json_api_response(result, BodyFormat::default()))
+}
+
+#[utoipa::path(
+    get,
+    tag = "Search",
+    path = "/{index_id}/loki/api/v1/query_range",
+    responses(
+        (status = 200, description = "Successfully executed the LogQL query.")
+    ),
+    params(
+        ("index_id" = String, Path, description = "The index ID to query."),
+        LokiQueryRangeParams,
+    )
+)]
+/// Execute LogQL Query
+///
+/// Log queries return the matching log lines grouped in streams by label values. Metric queries
+/// (`count_over_time`, `rate`) return a series of samples per combination of values of the
+/// grouping labels.
+async fn loki_query_range(
+    index_id: IndexId,
+    params: LokiQueryRangeParams,
+    search_service: Arc<dyn SearchService>,
+    metastore: MetastoreServiceClient,
+) -> Result<LokiResponse<LokiQueryResult>, LokiApiError> {
+    let logql_query = parse_logql(&params.query).map_err(LokiApiError::InvalidRequest)?;
+    let time_range = TimeRange::parse(params.start.as_deref(), params.end.as_deref())?;
+    let index_context = load_index_context(&index_id, metastore).await?;
+
+    let query_result = match logql_query {
+        LogqlQuery::Log(log_query) => {
+            let search_request = log_search_request(
+                index_id,
+                &log_query,
+                time_range,
+                &index_context,
+                params.limit.unwrap_or(DEFAULT_LIMIT),
+                params.direction.sort_order(),
+            )?;
+            let search_response = search_service.root_search(search_request).await?;
+            collect_streams(search_response.hits, &index_context)?
+        }
+        LogqlQuery::Metric(metric_query) => {
+            let step_millis = match params.step.as_deref() {
+                Some(step_str) => parse_loki_step(step_str)?,
+                None => default_step_millis(time_range),
+            };
+            execute_metric_query(
+                index_id,
+                metric_query,
+                time_range,
+                step_millis,
+                &index_context,
+                &*search_service,
+            )
+            .await?
+        }
+    };
+    Ok(LokiResponse::success(query_result))
+}
+
+#[utoipa::path(
+    get,
+    tag = "Search",
+    path = "/{index_id}/loki/api/v1/labels",
+    responses(
+        (status = 200, description = "Successfully listed the labels.")
+    ),
+    params(
+        ("index_id" = String, Path, description = "The index ID to list the labels of."),
+    )
+)]
+/// List Labels
+///
+/// Returns the fields exposed as stream labels: the tag fields and the fast text fields.
+async fn loki_labels(
+    index_id: IndexId,
+    metastore: MetastoreServiceClient,
+) -> Result<LokiResponse<Vec<String>>, LokiApiError> {
+    let index_context = load_index_context(&index_id, metastore).await?;
+    Ok(LokiResponse::success(index_context.label_fields))
+}
+
+#[utoipa::path(
+    get,
+    tag = "Search",
+    path = "/{index_id}/loki/api/v1/label/{label}/values",
+    responses(
+        (status = 200, description = "Successfully listed the label values.")
+    ),
+    params(
+        ("index_id" = String, Path, description = "The index ID to list the label values of."),
+        ("label" = String, Path, description = "The label to list the values of."),
+        LokiTimeRangeParams,
+    )
+)]
+/// List Label Values
+///
+/// Returns the values of a label over the time range, up to 1000 values.
+async fn loki_label_values(
+    index_id: IndexId,
+    label: String,
+    params: LokiTimeRangeParams,
+    search_service: Arc<dyn SearchService>,
+    metastore: MetastoreServiceClient,
+) -> Result<LokiResponse<Vec<String>>, LokiApiError> {
+    let time_range = TimeRange::parse(params.start.as_deref(), params.end.as_deref())?;
+    let index_context = load_index_context(&index_id, metastore).await?;
+
+    if !index_context.label_fields.contains(&label) {
+        return Ok(LokiResponse::success(Vec::new()));
+    }
+    let list_terms_request = ListTermsRequest {
+        index_id,
+        field: label,
+        start_timestamp: Some(time_range.start_timestamp()),
+        end_timestamp: Some(time_range.end_timestamp()),
+        max_hits: Some(MAX_NUM_LABEL_VALUES),
+        start_key: None,
+        end_key: None,
+    };
+    let list_terms_response = search_service.root_list_terms(list_terms_request).await?;
+    let label_values: BTreeSet<String> = list_terms_response
+        .terms
+        .iter()
+        .filter_map(|term_bytes| {
+            tantivy::Term::wrap(term_bytes)
+                .value()
+                .as_str()
+                .map(ToString::to_string)
+        })
+        .collect();
+    Ok(LokiResponse::success(label_values.into_iter().collect()))
+}
+
+#[utoipa::path(
+    get,
+    tag = "Search",
+    path = "/{index_id}/loki/api/v1/series",
+    responses(
+        (status = 200, description = "Successfully listed the series.")
+    ),
+    params(
+        ("index_id" = String, Path, description = "The index ID to list the series of."),
+        LokiSeriesParams,
+    )
+)]
+/// List Series
+///
+/// Returns the label sets of the streams matching the stream selectors, collected from the most
+/// recent log lines of the time range.
+async fn loki_series(
+    index_id: IndexId,
+    params: LokiSeriesParams,
+    search_service: Arc<dyn SearchService>,
+    metastore: MetastoreServiceClient,
+) -> Result<LokiResponse<Vec<BTreeMap<String, String>>>, LokiApiError> {
+    if params.matches.is_empty() {
+        return Err(LokiApiError::InvalidRequest(
+            "at least one `match[]` stream selector is required".to_string(),
+        ));
+    }
+    let log_queries = params
+        .matches
+        .iter()
+        .map(|selector| parse_stream_selector(selector))
+        .collect::<Result<Vec<LogQuery>, String>>()
+        .map_err(LokiApiError::InvalidRequest)?;
+    let time_range = TimeRange::parse(params.start.as_deref(), params.end.as_deref())?;
+    let index_context = load_index_context(&index_id, metastore).await?;
+    let mut label_sets: BTreeSet<BTreeMap<String, String>> = BTreeSet::new();
+
+    for log_query in &log_queries {
+        let search_request = log_search_request(
+            index_id.clone(),
+            log_query,
+            time_range,
+            &index_context,
+            MAX_SERIES_HITS,
+            SortOrder::Desc,
+        )?;
+        let search_response = search_service.root_search(search_request).await?;
+
+        for hit in &search_response.hits {
+            let labels = extract_labels(&parse_hit_doc(hit)?, &index_context.label_fields);
+            if !labels.is_empty() {
+                label_sets.insert(labels);
+            }
+        }
+    }
+    Ok(LokiResponse::success(label_sets.into_iter().collect()))
+}
+
+fn log_search_request(
+    index_id: IndexId,
+    log_query: &LogQuery,
+    time_range: TimeRange,
+    index_context: &LokiIndexContext,
+    max_hits: u64,
+    sort_order: SortOrder,
+) -> Result<SearchRequest, LokiApiError> {
+    let query_ast = log_query
+        .to_query_ast(&index_context.default_search_fields)
+        .map_err(LokiApiError::InvalidRequest)?;
+    let search_request = SearchRequest {
+        index_id_patterns: vec![index_id],
+        query_ast: serde_json::to_string(&query_ast).expect("Failed to serialize QueryAst"),
+        start_timestamp: Some(time_range.start_timestamp()),
+        end_timestamp: Some(time_range.end_timestamp()),
+        max_hits,
+        sort_fields: vec![SortField {
+            field_name: index_context.timestamp_field.clone(),
+            sort_order: sort_order as i32,
+        }],
+        ..Default::default()
+    };
+    Ok(search_request)
+}
+
+fn parse_hit_doc(hit: &Hit) -> Result<JsonValue, LokiApiError> {
+    serde_json::from_str(&hit.json)
+        .map_err(|error| LokiApiError::Internal(format!("failed to deserialize document: {error}")))
+}
+
+/// Extracts the values of the label fields from a document. Fields of objects are looked up by
+/// their dotted path.
+fn extract_labels(doc: &JsonValue, label_fields: &[String]) -> BTreeMap<String, String> {
+    let mut labels = BTreeMap::new();
+
+    for label_field in label_fields {
+        let value = label_field.split('.').fold(doc, |value, key| &value[key]);
+        let label_value = match value {
+            JsonValue::String(text) => text.clone(),
+            JsonValue::Number(number) => number.to_string(),
+            JsonValue::Bool(boolean) => boolean.to_string(),
+            _ => continue,
+        };
+        labels.insert(label_field.clone(), label_value);
+    }
+    labels
+}
+
+/// Groups the hits of a log query in streams. The hits being sorted by timestamp, so are the
+/// lines of each stream.
+fn collect_streams(
+    hits: Vec<Hit>,
+    index_context: &LokiIndexContext,
+) -> Result<LokiQueryResult, LokiApiError> {
+    let mut streams: BTreeMap<BTreeMap<String, String>, Vec<[String; 2]>> = BTreeMap::new();
+
+    for hit in hits {
+        let timestamp_nanos = match hit
+            .partial_hit
+            .as_ref()
+            .and_then(|partial_hit| partial_hit.sort_value.as_ref())
+            .and_then(|sort_by_value| sort_by_value.sort_value.as_ref())
+        {
+            Some(SortValue::I64(timestamp_nanos)) => *timestamp_nanos,
+            _ => 0,
+        };
+        let labels = extract_labels(&parse_hit_doc(&hit)?, &index_context.label_fields);
+        streams
+            .entry(labels)
+            .or_default()
+            .push([timestamp_nanos.to_string(), hit.json]);
+    }
+    let streams = streams
+        .into_iter()
+        .map(|(stream, values)| LokiStream { stream, values })
+        .collect();
+    Ok(LokiQueryResult::Streams(streams))
+}
+
+fn group_aggregation_name(depth: usize) -> String {
+    format!("group_{depth}")
+}
+
+/// Like Loki, the default step splits the time range in 250 samples, rounded up to a second.
+fn default_step_millis(time_range: TimeRange) -> i64 {
+    let range_secs = (time_range.end_nanos - time_range.start_nanos) as f64 / NANOS_PER_SEC as f64;
+    (range_secs / DEFAULT_NUM_SAMPLES as f64).ceil().max(1.0) as i64 * 1_000
+}
+
+fn greatest_common_divisor(mut left: i64, mut right: i64) -> i64 {
+    while right != 0 {
+        (left, right) = (right, left % right);
+    }
+    left
+}
+
+/// Runs a metric query as a `date_histogram` aggregation nested in a `terms` aggregation per
+/// grouping label.
+///
+/// Samples are taken every `step` at multiples of the step since epoch, each counting the log lines
+/// of the range ending at the sample. The buckets are as wide as the largest duration that both
+/// the range and the step are multiples of, so that each sample sums the buckets of its range.
+async fn execute_metric_query(
+    index_id: IndexId,
+    metric_query: MetricQuery,
+    time_range: TimeRange,
+    step_millis: i64,
+    index_context: &LokiIndexContext,
+    search_service: &dyn SearchService,
+) -> Result<LokiQueryResult, LokiApiError> {
+    let query_ast = metric_query
+        .log_query
+        .to_query_ast(&index_context.default_search_fields)
+        .map_err(LokiApiError::InvalidRequest)?;
+    // Series can only be grouped by the labels backed by a fast field. The labels of the stream
+    // selector are skipped, but the labels of a `by` clause must be fast.
+    let mut group_by: Vec<String> = Vec::new();
+
+    for label in metric_query.group_by {
+        if !index_context.fast_label_fields.contains(&label) {
+            if metric_query.explicit_group_by {
+                return Err(LokiApiError::InvalidRequest(format!(
+                    "cannot group by label `{label}`: series can only be grouped by labels backed \
+                     by a fast field"
+                )));
+            }
+            continue;
+        }
+        if !group_by.contains(&label) {
+            group_by.push(label);
+        }
+    }
+    let range_millis = metric_query.range.as_millis() as i64;
+    let start_millis = time_range.start_nanos.div_euclid(NANOS_PER_MILLI);
+    let end_millis = time_range.end_nanos.div_euclid(NANOS_PER_MILLI);
+    let first_sample_millis =
+        (start_millis + step_millis - 1).div_euclid(step_millis) * step_millis;
+    let last_sample_millis = end_millis.div_euclid(step_millis) * step_millis;
+
+    if first_sample_millis > last_sample_millis {
+        return Ok(LokiQueryResult::Matrix(Vec::new()));
+    }
+    let num_samples = (last_sample_millis - first_sample_millis) / step_millis + 1;
+
+    if num_samples > MAX_NUM_POINTS {
+        return Err(LokiApiError::InvalidRequest(format!(
+            "the query would return {num_samples} samples per series, more than the maximum of \
+             {MAX_NUM_POINTS}: increase the step"
+        )));
+    }
+    let interval_millis = greatest_common_divisor(range_millis, step_millis);
+    let num_buckets = (last_sample_millis - first_sample_millis + range_millis) / interval_millis;
+
+    if num_buckets > MAX_NUM_POINTS {
+        return Err(LokiApiError::InvalidRequest(format!(
+            "the range and the step of the query would require {num_buckets} buckets per series, \
+             more than the maximum of {MAX_NUM_POINTS}: use a range and a step that are multiples \
+             of a larger duration, or a shorter time range"
+        )));
+    }
+    let mut aggregations = json!({
+        "timeline": {
+            "date_histogram": {
+                "field": index_context.timestamp_field,
+                "fixed_interval": format!("{interval_millis}ms"),
+                "min_doc_count": 1,
+            }
+        }
+    });
+    for (depth, label) in group_by.iter().enumerate().rev() {
+        let mut group_aggregation = JsonMap::new();
+        group_aggregation.insert(
+            group_aggregation_name(depth),
+            json!({
+                "terms": {
+                    "field": label,
+                    "size": MAX_NUM_SERIES,
+                },
+                "aggs": aggregations,
+            }),
+        );
+        aggregations = JsonValue::Object(group_aggregation);
+    }
+    // The range of the first sample starts before the time range of the request.
+    let search_request = SearchRequest {
+        index_id_patterns: vec![index_id],
+        query_ast: serde_json::to_string(&query_ast).expect("Failed to serialize QueryAst"),
+        start_timestamp: Some((first_sample_millis - range_millis).div_euclid(1_000)),
+        end_timestamp: Some(last_sample_millis.div_euclid(1_000) + 1),
+        max_hits: 0,
+        aggregation_request: Some(aggregations.to_string()),
+        ..Default::default()
+    };
+    let search_response = search_service.root_search(search_request).await?;
+
+    let aggregations: JsonValue = match &search_response.aggregation {
+        Some(aggregation_json) => serde_json::from_str(aggregation_json).map_err(|error| {
+            LokiApiError::Internal(format!("failed to deserialize aggregations: {error}"))
+        })?,
+        None => JsonValue::Null,
+    };
+    let sample_builder = SampleBuilder {
+        function: metric_query.function,
+        range_millis,
+        step_millis,
+        first_sample_millis,
+        last_sample_millis,
+    };
+    let mut series = Vec::new();
+    collect_series(
+        &aggregations,
+        &group_by,
+        0,
+        &sample_builder,
+        &mut BTreeMap::new(),
+        &mut series,
+    );
+    Ok(LokiQueryResult::Matrix(series))
+}
+
+/// Converts the `date_histogram` buckets of a series into the samples of a metric query.
+struct SampleBuilder {
+    function: RangeFunction,
+    range_millis: i64,
+    step_millis: i64,
+    first_sample_millis: i64,
+    last_sample_millis: i64,
+}
+
+impl SampleBuilder {
+    /// Each sample counts the log lines of the range ending at the sample timestamp, excluded.
+    /// Samples without any log line are skipped.
+    fn samples(&self, buckets: &[JsonValue]) -> Vec<(f64, String)> {
+        let mut bucket_counts: Vec<(i64, u64)> = buckets
+            .iter()
+            .filter_map(|bucket| {
+                let key_millis = bucket["key"].as_f64()? as i64;
+                let doc_count = bucket["doc_count"].as_u64()?;
+                Some((key_millis, doc_count))
+            })
+            .collect();
+        bucket_counts.sort_unstable();
+
+        let mut samples = Vec::new();
+        // The buckets in `bucket_counts[window_start..window_end]` are those of the range of the
+        // current sample.
+        let mut window_start = 0;
+        let mut window_end = 0;
+        let mut window_count = 0;
+
+        for sample_millis in
+            (self.first_sample_millis..=self.last_sample_millis).step_by(self.step_millis as usize)
+        {
+            while window_end < bucket_counts.len() && bucket_counts[window_end].0 < sample_millis {
+                window_count += bucket_counts[window_end].1;
+                window_end += 1;
+            }
+            while window_start < window_end
+                && bucket_counts[window_start].0 < sample_millis - self.range_millis
+            {
+                window_count -= bucket_counts[window_start].1;
+                window_start += 1;
+            }
+            if window_count == 0 {
+                continue;
+            }
+            let value = match self.function {
+                RangeFunction::CountOverTime => window_count.to_string(),
+                RangeFunction::Rate => {
+                    (window_count as f64 * 1_000.0 / self.range_millis as f64).to_string()
+                }
+            };
+            samples.push((sample_millis as f64 / 1_000.0, value));
+        }
+        samples
+    }
+}
+
+/// Walks down the nested grouping aggregations, emitting a series for each innermost bucket.
+fn collect_series(
+    aggregations: &JsonValue,
+    group_by: &[String],
+    depth: usize,
+    sample_builder: &SampleBuilder,
+    labels: &mut BTreeMap<String, String>,
+    series: &mut Vec<LokiSeries>,
+) {
+    if depth == group_by.len() {
+        let buckets = aggregations["timeline"]["buckets"]
+            .as_array()
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let values = sample_builder.samples(buckets);
+        if !values.is_empty() {
+            series.push(LokiSeries {
+                metric: labels.clone(),
+                values,
+            });
+        }
+        return;
+    }
+    let buckets = aggregations[group_aggregation_name(depth)]["buckets"].as_array();
+
+    for bucket in buckets.into_iter().flatten() {
+        let label_value = match &bucket["key"] {
+            JsonValue::String(text) => text.clone(),
+            key => key.to_string(),
+        };
+        labels.insert(group_by[depth].clone(), label_value);
+        collect_series(bucket, group_by, depth + 1, sample_builder, labels, series);
+        labels.remove(&group_by[depth]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mockall::predicate;
+    use quickwit_metastore::IndexMetadata;
+    use quickwit_proto::metastore::IndexMetadataResponse;
+    use quickwit_proto::search::{ListTermsResponse, PartialHit, SearchResponse, SortByValue};
+    use quickwit_search::{encode_term_for_test, MockSearchService};
+
+    use super::*;
+    use crate::recover_fn;
+
+    fn mock_metastore() -> MetastoreServiceClient {
+        let mut index_metadata = IndexMetadata::for_test("my-logs", "ram:///indexes/my-logs");
+        index_metadata.index_config.doc_mapping = serde_json::from_str(
+            r#"{
+                "field_mappings": [
+                    {"name": "timestamp", "type": "datetime", "fast": true},
+                    {"name": "service", "type": "text", "tokenizer": "raw", "fast": true},
+                    {"name": "host", "type": "text", "tokenizer": "raw"},
+                    {"name": "body", "type": "text"}
+                ],
+                "timestamp_field": "timestamp",
+                "tag_fields": ["host"]
+            }"#,
+        )
+        .unwrap();
+        index_metadata
+            .index_config
+            .search_settings
+            .default_search_fields = vec!["body".to_string()];
+
+        let mut mock_metastore = MetastoreServiceClient::mock();
+        mock_metastore.expect_index_metadata().returning(move |_| {
+            Ok(IndexMetadataResponse::try_from_index_metadata(index_metadata.clone()).unwrap())
+        });
+        MetastoreServiceClient::from(mock_metastore)
+    }
+
+    fn loki_api_handlers_with_mock(
+        mock_search_service: MockSearchService,
+    ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+        loki_api_handlers(Arc::new(mock_search_service), mock_metastore()).recover(recover_fn)
+    }
+
+    fn hit(timestamp_nanos: i64, json: &str) -> Hit {
+        Hit {
+            json: json.to_string(),
+            partial_hit: Some(PartialHit {
+                sort_value: Some(SortByValue {
+                    sort_value: Some(SortValue::I64(timestamp_nanos)),
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_loki_timestamp() {
+        assert_eq!(
+            parse_loki_timestamp("1700000000").unwrap(),
+            1_700_000_000_000_000_000
+        );
+        assert_eq!(
+            parse_loki_timestamp("1700000000123456789").unwrap(),
+            1_700_000_000_123_456_789
+        );
+        assert_eq!(
+            parse_loki_timestamp("1700000000.5").unwrap(),
+            1_700_000_000_500_000_000
+        );
+        assert_eq!(
+            parse_loki_timestamp("2023-11-14T22:13:20Z").unwrap(),
+            1_700_000_000_000_000_000
+        );
+        parse_loki_timestamp("yesterday").unwrap_err();
+
+        let time_range = TimeRange::parse(Some("1700000000.5"), Some("1700003600")).unwrap();
+        assert_eq!(time_range.start_timestamp(), 1_700_000_000);
+        assert_eq!(time_range.end_timestamp(), 1_700_003_601);
+
+        let time_range = TimeRange::parse(None, Some("1700003600")).unwrap();
+        assert_eq!(time_range.start_timestamp(), 1_700_000_000);
+
+        TimeRange::parse(Some("1700003600"), Some("1700000000")).unwrap_err();
+    }
+
+    #[test]
+    fn test_parse_loki_step() {
+        assert_eq!(parse_loki_step("60").unwrap(), 60_000);
+        assert_eq!(parse_loki_step("0.5").unwrap(), 500);
+        assert_eq!(parse_loki_step("5m").unwrap(), 300_000);
+        parse_loki_step("0").unwrap_err();
+        parse_loki_step("-1").unwrap_err();
+        parse_loki_step("often").unwrap_err();
+
+        let time_range = TimeRange::parse(Some("1700000000"), Some("1700003600")).unwrap();
+        assert_eq!(default_step_millis(time_range), 15_000);
+
+        let time_range = TimeRange::parse(Some("1700000000"), Some("1700000010")).unwrap();
+        assert_eq!(default_step_millis(time_range), 1_000);
+    }
+
+    #[test]
+    fn test_sample_builder() {
+        let buckets = [
+            json!({"key": 1700000000000.0, "doc_count": 1}),
+            json!({"key": 1700000010000.0, "doc_count": 2}),
+            json!({"key": 1700000040000.0, "doc_count": 4}),
+        ];
+        // The ranges of the samples overlap.
+        let sample_builder = SampleBuilder {
+            function: RangeFunction::CountOverTime,
+            range_millis: 30_000,
+            step_millis: 10_000,
+            first_sample_millis: 1_700_000_000_000,
+            last_sample_millis: 1_700_000_080_000,
+        };
+        assert_eq!(
+            sample_builder.samples(&buckets),
+            [
+                (1700000010.0, "1".to_string()),
+                (1700000020.0, "3".to_string()),
+                (1700000030.0, "3".to_string()),
+                (1700000040.0, "2".to_string()),
+                (1700000050.0, "4".to_string()),
+                (1700000060.0, "4".to_string()),
+                (1700000070.0, "4".to_string()),
+            ]
+        );
+        // The ranges of the samples do not cover the whole time range.
+        let sample_builder = SampleBuilder {
+            function: RangeFunction::Rate,
+            range_millis: 10_000,
+            step_millis: 20_000,
+            first_sample_millis: 1_700_000_000_000,
+            last_sample_millis: 1_700_000_060_000,
+        };
+        assert_eq!(
+            sample_builder.samples(&buckets),
+            [(1700000020.0, "0.2".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_extract_labels() {
+        let doc = json!({
+            "service": "api",
+            "resource": {"host": "host-1", "pid": 42},
+            "tags": ["a", "b"],
+        });
+        let label_fields = [
+            "service".to_string(),
+            "resource.host".to_string(),
+            "resource.pid".to_string(),
+            "tags".to_string(),
+            "missing".to_string(),
+        ];
+        let labels = extract_labels(&doc, &label_fields);
+        assert_eq!(
+            labels,
+            BTreeMap::from([
+                ("resource.host".to_string(), "host-1".to_string()),
+                ("resource.pid".to_string(), "42".to_string()),
+                ("service".to_string(), "api".to_string()),
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_loki_query_range_streams() {
+        let mut mock_search_service = MockSearchService::new();
+        mock_search_service
+            .expect_root_search()
+            .with(predicate::function(|search_request: &SearchRequest| {
+                search_request.index_id_patterns == ["my-logs"]
+                    && search_request.max_hits == 10
+                    && search_request.start_timestamp == Some(1_700_000_000)
+                    && search_request.end_timestamp == Some(1_700_003_601)
+                    && search_request.sort_fields
+                        == [SortField {
+                            field_name: "timestamp".to_string(),
+                            sort_order: SortOrder::Desc as i32,
+                        }]
+                    && search_request.query_ast.contains(r#""value":"api""#)
+                    && search_request.query_ast.contains(r#""text":"timeout""#)
+            }))
+            .returning(|_| {
+                Ok(SearchResponse {
+                    num_hits: 3,
+                    hits: vec![
+                        hit(
+                            3_000_000_000,
+                            r#"{"service":"api","host":"host-2","body":"timeout"}"#,
+                        ),
+                        hit(
+                            2_000_000_000,
+                            r#"{"service":"api","host":"host-1","body":"timeout"}"#,
+                        ),
+                        hit(
+                            1_000_000_000,
+                            r#"{"service":"api","host":"host-2","body":"timeout"}"#,
+                        ),
+                    ],
+                    ..Default::default()
+                })
+            });
+        let loki_api_handlers = loki_api_handlers_with_mock(mock_search_service);
+        let resp = warp::test::request()
+            .path(
+                "/my-logs/loki/api/v1/query_range?query=%7Bservice%3D%22api%22%7D%20%7C%3D%20%\
+                 22timeout%22&start=1700000000&end=1700003600&limit=10",
+            )
+            .reply(&loki_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let resp_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(
+            resp_json,
+            json!({
+                "status": "success",
+                "data": {
+                    "resultType": "streams",
+                    "result": [
+                        {
+                            "stream": {"host": "host-1", "service": "api"},
+                            "values": [
+                                ["2000000000", r#"{"service":"api","host":"host-1","body":"timeout"}"#],
+                            ],
+                        },
+                        {
+                            "stream": {"host": "host-2", "service": "api"},
+                            "values": [
+                                ["3000000000", r#"{"service":"api","host":"host-2","body":"timeout"}"#],
+                                ["1000000000", r#"{"service":"api","host":"host-2","body":"timeout"}"#],
+                            ],
+                        },
+                    ],
+                },
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_loki_query_range_matrix() {
+        let mut mock_search_service = MockSearchService::new();
+        mock_search_service
+            .expect_root_search()
+            .with(predicate::function(|search_request: &SearchRequest| {
+                let aggregations: JsonValue =
+                    serde_json::from_str(search_request.aggregation_request.as_ref().unwrap())
+                        .unwrap();
+                search_request.max_hits == 0
+                    && search_request.start_timestamp == Some(1_699_999_980)
+                    && search_request.end_timestamp == Some(1_700_003_581)
+                    && aggregations
+                        == json!({
+                            "group_0": {
+                                "terms": {"field": "service", "size": 1000},
+                                "aggs": {
+                                    "timeline": {
+                                        "date_histogram": {
+                                            "field": "timestamp",
+                                            "fixed_interval": "60000ms",
+                                            "min_doc_count": 1,
+                                        }
+                                    }
+                                }
+                            }
+                        })
+            }))
+            .returning(|_| {
+                let aggregations = json!({
+                    "group_0": {
+                        "buckets": [
+                            {
+                                "key": "api",
+                                "doc_count": 9,
+                                "timeline": {
+                                    "buckets": [
+                                        {"key": 1699999980000.0, "doc_count": 6},
+                                        {"key": 1700000040000.0, "doc_count": 3},
+                                    ]
+                                }
+                            },
+                        ]
+                    }
+                });
+                Ok(SearchResponse {
+                    num_hits: 9,
+                    aggregation: Some(aggregations.to_string()),
+                    ..Default::default()
+                })
+            });
+        let loki_api_handlers = loki_api_handlers_with_mock(mock_search_service);
+        let resp = warp::test::request()
+            .path(
+                "/my-logs/loki/api/v1/query_range?query=sum%20by%20(service)%20(rate(%7Bservice%\
+                 3D~%22.%2B%22%7D%5B1m%5D))&start=1700000000&end=1700003600&step=60",
+            )
+            .reply(&loki_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let resp_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(
+            resp_json,
+            json!({
+                "status": "success",
+                "data": {
+                    "resultType": "matrix",
+                    "result": [
+                        {
+                            "metric": {"service": "api"},
+                            "values": [[1700000040.0, "0.1"], [1700000100.0, "0.05"]],
+                        },
+                    ],
+                },
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_loki_query_range_invalid_query() {
+        let loki_api_handlers = loki_api_handlers_with_mock(MockSearchService::new());
+        let resp = warp::test::request()
+            .path("/my-logs/loki/api/v1/query_range?query=%7Bservice%3D%22api%22%7D%20%7C%20json")
+            .reply(&loki_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 400);
+        let resp_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(
+            resp_json["message"],
+            "invalid request: parser and formatting pipeline stages are not supported"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_loki_query_range_invalid_metric_query() {
+        let loki_api_handlers = loki_api_handlers_with_mock(MockSearchService::new());
+        // `host` is not a fast field.
+        let resp = warp::test::request()
+            .path(
+                "/my-logs/loki/api/v1/query_range?query=sum%20by%20(host)%20(count_over_time(%\
+                 7Bservice%3D%22api%22%7D%5B1m%5D))&start=1700000000&end=1700003600",
+            )
+            .reply(&loki_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 400);
+        let resp_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(
+            resp_json["message"],
+            "invalid request: cannot group by label `host`: series can only be grouped by labels \
+             backed by a fast field"
+        );
+        // A 1s range over 30 days.
+        let resp = warp::test::request()
+            .path(
+                "/my-logs/loki/api/v1/query_range?query=count_over_time(%7Bservice%3D%22api%22%7D%\
+                 5B1s%5D)&start=1700000000&end=1702592000",
+            )
+            .reply(&loki_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 400);
+        let resp_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        assert!(resp_json["message"]
+            .as_str()
+            .unwrap()
+            .contains("more than the maximum of 11000"));
+
+        let resp = warp::test::request()
+            .path(
+                "/my-logs/loki/api/v1/query_range?query=count_over_time(%7Bservice%3D%22api%22%7D%\
+                 5B1m%5D)&start=1700000000&end=1702592000&step=1",
+            )
+            .reply(&loki_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 400);
+        let resp_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        assert!(resp_json["message"]
+            .as_str()
+            .unwrap()
+            .contains("increase the step"));
+    }
+
+    #[tokio::test]
+    async fn test_loki_labels_and_label_values() {
+        let mut mock_search_service = MockSearchService::new();
+        mock_search_service
+            .expect_root_list_terms()
+            .with(predicate::function(
+                |list_terms_request: &ListTermsRequest| {
+                    list_terms_request.index_id == "my-logs"
+                        && list_terms_request.field == "service"
+                        && list_terms_request.max_hits == Some(1_000)
+                },
+            ))
+            .returning(|_| {
+                Ok(ListTermsResponse {
+                    num_hits: 2,
+                    terms: vec![encode_term_for_test!("db"), encode_term_for_test!("api")],
+                    elapsed_time_micros: 0,
+                    errors: Vec::new(),
+                })
+            });
+        let loki_api_handlers = loki_api_handlers_with_mock(mock_search_service);
+
+        let resp = warp::test::request()
+            .path("/my-logs/loki/api/v1/labels")
+            .reply(&loki_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let resp_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(
+            resp_json,
+            json!({"status": "success", "data": ["host", "service"]})
+        );
+
+        let resp = warp::test::request()
+            .path("/my-logs/loki/api/v1/label/service/values?start=1700000000&end=1700003600")
+            .reply(&loki_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let resp_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(
+            resp_json,
+            json!({"status": "success", "data": ["api", "db"]})
+        );
+
+        let resp = warp::test::request()
+            .path("/my-logs/loki/api/v1/label/body/values")
+            .reply(&loki_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let resp_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(resp_json, json!({"status": "success", "data": []}));
+    }
+
+    #[tokio::test]
+    async fn test_loki_series() {
+        let mut mock_search_service = MockSearchService::new();
+        mock_search_service
+            .expect_root_search()
+            .with(predicate::function(|search_request: &SearchRequest| {
+                search_request.max_hits == 1_000
+            }))
+            .times(2)
+            .returning(|_| {
+                Ok(SearchResponse {
+                    num_hits: 3,
+                    hits: vec![
+                        hit(3, r#"{"service":"api","host":"host-1"}"#),
+                        hit(2, r#"{"service":"api","host":"host-1"}"#),
+                        hit(1, r#"{"service":"db"}"#),
+                    ],
+                    ..Default::default()
+                })
+            });
+        let loki_api_handlers = loki_api_handlers_with_mock(mock_search_service);
+        let resp = warp::test::request()
+            .path(
+                "/my-logs/loki/api/v1/series?match%5B%5D=%7Bservice%3D%22api%22%7D&match%5B%5D=%\
+                 7Bservice%3D%22db%22%7D",
+            )
+            .reply(&loki_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let resp_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(
+            resp_json,
+            json!({
+                "status": "success",
+                "data": [
+                    {"host": "host-1", "service": "api"},
+                    {"service": "db"},
+                ],
+            })
+        );
+
+        let resp = warp::test::request()
+            .path("/my-logs/loki/api/v1/series")
+            .reply(&loki_api_handlers_with_mock(MockSearchService::new()))
+            .await;
+        assert_eq!(resp.status(), 400);
+    }
+}
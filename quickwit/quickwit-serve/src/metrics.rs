@@ -18,10 +18,12 @@
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
 use once_cell::sync::Lazy;
-use quickwit_common::metrics::{new_counter, IntCounter};
+use quickwit_common::metrics::{new_counter, new_counter_vec, IntCounter, IntCounterVec};
 
 pub struct RestMetrics {
     pub http_requests_total: IntCounter,
+    pub request_body_compressed_bytes_total: IntCounterVec<1>,
+    pub request_body_decompressed_bytes_total: IntCounterVec<1>,
 }
 
 impl Default for RestMetrics {
@@ -32,6 +34,20 @@ impl Default for RestMetrics {
                 "Total number of HTTP requests received",
                 "quickwit",
             ),
+            request_body_compressed_bytes_total: new_counter_vec(
+                "request_body_compressed_bytes_total",
+                "Total size in bytes of the compressed request bodies received, before \
+                 decompression",
+                "quickwit",
+                ["encoding"],
+            ),
+            request_body_decompressed_bytes_total: new_counter_vec(
+                "request_body_decompressed_bytes_total",
+                "Total size in bytes of the compressed request bodies received, after \
+                 decompression",
+                "quickwit",
+                ["encoding"],
+            ),
         }
     }
 }
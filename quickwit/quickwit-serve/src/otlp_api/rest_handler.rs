@@ -0,0 +1,818 @@
+// Copyright (C) 2023 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::sync::Arc;
+
+use bytes::Bytes;
+use hyper::header::CONTENT_TYPE;
+use hyper::http::HeaderValue;
+use prost::Message;
+use quickwit_common::uri::Uri;
+use quickwit_config::DocMapping;
+use quickwit_metastore::IndexMetadataResponseExt;
+use quickwit_opentelemetry::otlp::{
+    decode_otlp_json, OtlpEncoding, OtlpGrpcLogsService, OtlpGrpcMetricsService,
+    OtlpGrpcTracesService, OTEL_LOGS_INDEX_ID, OTEL_METRICS_INDEX_ID, OTEL_TRACES_INDEX_ID,
+};
+use quickwit_proto::metastore::{
+    IndexMetadataRequest, MetastoreError, MetastoreService, MetastoreServiceClient,
+};
+use quickwit_proto::opentelemetry::proto::collector::logs::v1::{
+    ExportLogsServiceRequest, ExportLogsServiceResponse,
+};
+use quickwit_proto::opentelemetry::proto::collector::metrics::v1::{
+    ExportMetricsServiceRequest, ExportMetricsServiceResponse,
+};
+use quickwit_proto::opentelemetry::proto::collector::trace::v1::{
+    ExportTraceServiceRequest, ExportTraceServiceResponse,
+};
+use quickwit_proto::types::IndexId;
+use quickwit_proto::{tonic, ServiceError, ServiceErrorCode};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use thiserror::Error;
+use warp::reply::Response;
+use warp::{Filter, Rejection, Reply};
+
+use crate::decompression::get_body_bytes;
+use crate::json_api_response::make_json_api_response;
+use crate::{require, with_arg, BodyFormat};
+
+/// Header used to ingest log records into an index other than the default OTEL logs index.
+const OTEL_LOGS_INDEX_HEADER: &str = "qw-otel-logs-index";
+
+/// Header used to ingest metric data points into an index other than the default OTEL metrics
+/// index.
+const OTEL_METRICS_INDEX_HEADER: &str = "qw-otel-metrics-index";
+
+/// Header used to ingest spans into an index other than the default OTEL traces index.
+const OTEL_TRACES_INDEX_HEADER: &str = "qw-otel-traces-index";
+
+const PROTOBUF_CONTENT_TYPE: &str = "application/x-protobuf";
+
+#[derive(utoipa::OpenApi)]
+#[openapi(paths(otlp_ingest_logs, otlp_ingest_metrics, otlp_ingest_traces))]
+pub struct OtlpApi;
+
+#[derive(Debug, Error)]
+pub(crate) enum OtlpApiError {
+    #[error(
+        "unsupported content-type header `{0}`. choices are application/x-protobuf and \
+         application/json"
+    )]
+    UnsupportedContentType(String),
+    #[error("failed to decode OTLP request: {0}")]
+    InvalidRequest(String),
+    #[error(
+        "index `{index_id}` does not use the OTEL {signal} doc mapping: field `{field_name}` is \
+         missing or mapped differently"
+    )]
+    IncompatibleIndex {
+        index_id: IndexId,
+        signal: &'static str,
+        field_name: String,
+    },
+    #[error(transparent)]
+    Metastore(#[from] MetastoreError),
+    #[error("{}", .0.message())]
+    Export(tonic::Status),
+}
+
+impl ServiceError for OtlpApiError {
+    fn error_code(&self) -> ServiceErrorCode {
+        match self {
+            Self::UnsupportedContentType(_) => ServiceErrorCode::UnsupportedMediaType,
+            Self::InvalidRequest(_) => ServiceErrorCode::BadRequest,
+            Self::IncompatibleIndex { .. } => ServiceErrorCode::BadRequest,
+            Self::Metastore(error) => error.error_code(),
+            Self::Export(status) => match status.code() {
+                tonic::Code::InvalidArgument => ServiceErrorCode::BadRequest,
+                tonic::Code::NotFound => ServiceErrorCode::NotFound,
+                tonic::Code::ResourceExhausted => ServiceErrorCode::RateLimited,
+                tonic::Code::Unavailable => ServiceErrorCode::Unavailable,
+                tonic::Code::Unimplemented => ServiceErrorCode::NotSupportedYet,
+                _ => ServiceErrorCode::Internal,
+            },
+        }
+    }
+}
+
+/// Setup OTLP/HTTP API handlers.
+///
+/// Log records, metric data points, and spans are ingested into the default OTEL indexes unless
+/// a target index is specified with the `qw-otel-{logs,metrics,traces}-index` header, or in the
+/// path (`/{index_id}/otlp/v1/{logs,metrics,traces}`).
+pub(crate) fn otlp_ingest_api_handlers(
+    metastore: MetastoreServiceClient,
+    default_index_root_uri: &Uri,
+    otlp_logs_service_opt: Option<OtlpGrpcLogsService>,
+    otlp_metrics_service_opt: Option<OtlpGrpcMetricsService>,
+    otlp_traces_service_opt: Option<OtlpGrpcTracesService>,
+    content_length_limit: u64,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    let logs_index_validator = OtlpIndexValidator {
+        signal: "logs",
+        default_index_id: OTEL_LOGS_INDEX_ID,
+        otel_doc_mapping: Arc::new(
+            OtlpGrpcLogsService::index_config(default_index_root_uri)
+                .expect("the OTEL logs index config should be valid")
+                .doc_mapping,
+        ),
+        metastore: metastore.clone(),
+    };
+    let metrics_index_validator = OtlpIndexValidator {
+        signal: "metrics",
+        default_index_id: OTEL_METRICS_INDEX_ID,
+        otel_doc_mapping: Arc::new(
+            OtlpGrpcMetricsService::index_config(default_index_root_uri)
+                .expect("the OTEL metrics index config should be valid")
+                .doc_mapping,
+        ),
+        metastore: metastore.clone(),
+    };
+    let traces_index_validator = OtlpIndexValidator {
+        signal: "traces",
+        default_index_id: OTEL_TRACES_INDEX_ID,
+        otel_doc_mapping: Arc::new(
+            OtlpGrpcTracesService::index_config(default_index_root_uri)
+                .expect("the OTEL traces index config should be valid")
+                .doc_mapping,
+        ),
+        metastore,
+    };
+    otlp_logs_handler(
+        otlp_logs_service_opt,
+        logs_index_validator,
+        content_length_limit,
+    )
+    .or(otlp_metrics_handler(
+        otlp_metrics_service_opt,
+        metrics_index_validator,
+        content_length_limit,
+    ))
+    .or(otlp_traces_handler(
+        otlp_traces_service_opt,
+        traces_index_validator,
+        content_length_limit,
+    ))
+}
+
+/// Checks that the index targeted by an OTLP/HTTP request can hold the documents of the signal.
+#[derive(Clone)]
+struct OtlpIndexValidator {
+    signal: &'static str,
+    default_index_id: &'static str,
+    otel_doc_mapping: Arc<DocMapping>,
+    metastore: MetastoreServiceClient,
+}
+
+impl OtlpIndexValidator {
+    /// The default OTEL index is created by Quickwit with the OTEL doc mapping, so it is not
+    /// checked. Any other index must exist and map all the fields of the OTEL doc mapping the same
+    /// way.
+    async fn validate(&self, index_id: &str) -> Result<(), OtlpApiError> {
+        if index_id == self.default_index_id {
+            return Ok(());
+        }
+        let index_metadata_request = IndexMetadataRequest::for_index_id(index_id.to_string());
+        let doc_mapping = self
+            .metastore
+            .clone()
+            .index_metadata(index_metadata_request)
+            .await?
+            .deserialize_index_metadata()?
+            .into_index_config()
+            .doc_mapping;
+
+        if let Some(field_mapping) = self
+            .otel_doc_mapping
+            .field_mappings
+            .iter()
+            .find(|field_mapping| !doc_mapping.field_mappings.contains(field_mapping))
+        {
+            return Err(OtlpApiError::IncompatibleIndex {
+                index_id: index_id.to_string(),
+                signal: self.signal,
+                field_name: field_mapping.name.clone(),
+            });
+        }
+        Ok(())
+    }
+}
+
+fn otlp_filter(
+    signal: &'static str,
+    index_id_header: &'static str,
+    default_index_id: &'static str,
+    content_length_limit: u64,
+) -> impl Filter<Extract = (IndexId, Option<mime_guess::Mime>, Bytes), Error = Rejection> + Clone {
+    let default_index_path = warp::path!("otlp" / "v1" / ..)
+        .and(warp::path(signal))
+        .and(warp::path::end())
+        .and(warp::header::optional::<String>(index_id_header))
+        .map(move |index_id_opt: Option<String>| {
+            index_id_opt.unwrap_or_else(|| default_index_id.to_string())
+        });
+    let custom_index_path = warp::path!(String / "otlp" / "v1" / ..)
+        .and(warp::path(signal))
+        .and(warp::path::end());
+    default_index_path
+        .or(custom_index_path)
+        .unify()
+        .and(warp::post())
+        .and(warp::header::optional::<mime_guess::Mime>(
+            CONTENT_TYPE.as_str(),
+        ))
+        .and(get_body_bytes(content_length_limit))
+}
+
+fn otlp_logs_handler(
+    otlp_logs_service_opt: Option<OtlpGrpcLogsService>,
+    index_validator: OtlpIndexValidator,
+    content_length_limit: u64,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    otlp_filter(
+        "logs",
+        OTEL_LOGS_INDEX_HEADER,
+        OTEL_LOGS_INDEX_ID,
+        content_length_limit,
+    )
+    .and(require(otlp_logs_service_opt))
+    .and(with_arg(index_validator))
+    .then(otlp_ingest_logs)
+    .map(make_otlp_response)
+}
+
+fn otlp_metrics_handler(
+    otlp_metrics_service_opt: Option<OtlpGrpcMetricsService>,
+    index_validator: OtlpIndexValidator,
+    content_length_limit: u64,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    otlp_filter(
+        "metrics",
+        OTEL_METRICS_INDEX_HEADER,
+        OTEL_METRICS_INDEX_ID,
+        content_length_limit,
+    )
+    .and(require(otlp_metrics_service_opt))
+    .and(with_arg(index_validator))
+    .then(otlp_ingest_metrics)
+    .map(make_otlp_response)
+}
+
+fn otlp_traces_handler(
+    otlp_traces_service_opt: Option<OtlpGrpcTracesService>,
+    index_validator: OtlpIndexValidator,
+    content_length_limit: u64,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    otlp_filter(
+        "traces",
+        OTEL_TRACES_INDEX_HEADER,
+        OTEL_TRACES_INDEX_ID,
+        content_length_limit,
+    )
+    .and(require(otlp_traces_service_opt))
+    .and(with_arg(index_validator))
+    .then(otlp_ingest_traces)
+    .map(make_otlp_response)
+}
+
+#[utoipa::path(
+    post,
+    tag = "Ingest",
+    path = "/otlp/v1/logs",
+    request_body(content = String, description = "OTLP logs export request encoded in Protobuf or JSON", content_type = "application/x-protobuf"),
+    responses(
+        (status = 200, description = "Successfully ingested log records.")
+    ),
+    params(
+        ("qw-otel-logs-index" = Option<String>, Header, description = "The index ID to ingest the log records into. Defaults to the OTEL logs index."),
+    )
+)]
+/// Ingest OTLP Log Records
+async fn otlp_ingest_logs(
+    index_id: IndexId,
+    content_type_opt: Option<mime_guess::Mime>,
+    body: Bytes,
+    otlp_logs_service: OtlpGrpcLogsService,
+    index_validator: OtlpIndexValidator,
+) -> Result<(ExportLogsServiceResponse, OtlpEncoding), OtlpApiError> {
+    let encoding = parse_otlp_encoding(content_type_opt)?;
+    let export_request: ExportLogsServiceRequest = decode_otlp_request(&body, encoding)?;
+    index_validator.validate(&index_id).await?;
+    let export_response = otlp_logs_service
+        .export_http(index_id, export_request, encoding)
+        .await
+        .map_err(OtlpApiError::Export)?;
+    Ok((export_response, encoding))
+}
+
+#[utoipa::path(
+    post,
+    tag = "Ingest",
+    path = "/otlp/v1/metrics",
+    request_body(content = String, description = "OTLP metrics export request encoded in Protobuf or JSON", content_type = "application/x-protobuf"),
+    responses(
+        (status = 200, description = "Successfully ingested metric data points.")
+    ),
+    params(
+        ("qw-otel-metrics-index" = Option<String>, Header, description = "The index ID to ingest the data points into. Defaults to the OTEL metrics index."),
+    )
+)]
+/// Ingest OTLP Metrics
+async fn otlp_ingest_metrics(
+    index_id: IndexId,
+    content_type_opt: Option<mime_guess::Mime>,
+    body: Bytes,
+    otlp_metrics_service: OtlpGrpcMetricsService,
+    index_validator: OtlpIndexValidator,
+) -> Result<(ExportMetricsServiceResponse, OtlpEncoding), OtlpApiError> {
+    let encoding = parse_otlp_encoding(content_type_opt)?;
+    let export_request: ExportMetricsServiceRequest = decode_otlp_request(&body, encoding)?;
+    index_validator.validate(&index_id).await?;
+    let export_response = otlp_metrics_service
+        .export_http(index_id, export_request, encoding)
+        .await
+        .map_err(OtlpApiError::Export)?;
+    Ok((export_response, encoding))
+}
+
+#[utoipa::path(
+    post,
+    tag = "Ingest",
+    path = "/otlp/v1/traces",
+    request_body(content = String, description = "OTLP traces export request encoded in Protobuf or JSON", content_type = "application/x-protobuf"),
+    responses(
+        (status = 200, description = "Successfully ingested spans.")
+    ),
+    params(
+        ("qw-otel-traces-index" = Option<String>, Header, description = "The index ID to ingest the spans into. Defaults to the OTEL traces index."),
+    )
+)]
+/// Ingest OTLP Spans
+async fn otlp_ingest_traces(
+    index_id: IndexId,
+    content_type_opt: Option<mime_guess::Mime>,
+    body: Bytes,
+    otlp_traces_service: OtlpGrpcTracesService,
+    index_validator: OtlpIndexValidator,
+) -> Result<(ExportTraceServiceResponse, OtlpEncoding), OtlpApiError> {
+    let encoding = parse_otlp_encoding(content_type_opt)?;
+    let export_request: ExportTraceServiceRequest = decode_otlp_request(&body, encoding)?;
+    index_validator.validate(&index_id).await?;
+    let export_response = otlp_traces_service
+        .export_http(index_id, export_request, encoding)
+        .await
+        .map_err(OtlpApiError::Export)?;
+    Ok((export_response, encoding))
+}
+
+/// Returns the encoding of the request body. OTLP/HTTP clients must set the content-type header,
+/// but we fall back to Protobuf, the default OTLP encoding, when it is missing.
+fn parse_otlp_encoding(
+    content_type_opt: Option<mime_guess::Mime>,
+) -> Result<OtlpEncoding, OtlpApiError> {
+    let Some(content_type) = content_type_opt else {
+        return Ok(OtlpEncoding::Protobuf);
+    };
+    match content_type.essence_str() {
+        PROTOBUF_CONTENT_TYPE => Ok(OtlpEncoding::Protobuf),
+        "application/json" => Ok(OtlpEncoding::Json),
+        _ => Err(OtlpApiError::UnsupportedContentType(
+            content_type.to_string(),
+        )),
+    }
+}
+
+/// Decodes the request body. JSON bodies follow the proto3 JSON mapping, with hex-encoded trace and
+/// span IDs.
+fn decode_otlp_request<T: Message + Default + DeserializeOwned>(
+    body: &Bytes,
+    encoding: OtlpEncoding,
+) -> Result<T, OtlpApiError> {
+    match encoding {
+        OtlpEncoding::Json => {
+            decode_otlp_json(body).map_err(|error| OtlpApiError::InvalidRequest(error.to_string()))
+        }
+        OtlpEncoding::Protobuf => T::decode(body.as_ref())
+            .map_err(|error| OtlpApiError::InvalidRequest(error.to_string())),
+    }
+}
+
+/// The OTLP/HTTP specification requires the response to be encoded like the request.
+fn make_otlp_response<T: Message + Serialize>(
+    result: Result<(T, OtlpEncoding), OtlpApiError>,
+) -> Response {
+    match result {
+        Ok((export_response, OtlpEncoding::Protobuf)) => {
+            let mut response = Response::new(export_response.encode_to_vec().into());
+            response.headers_mut().insert(
+                CONTENT_TYPE,
+                HeaderValue::from_static(PROTOBUF_CONTENT_TYPE),
+            );
+            response
+        }
+        Ok((export_response, OtlpEncoding::Json)) => {
+            make_json_api_response::<_, OtlpApiError>(Ok(export_response), BodyFormat::default())
+                .into_response()
+        }
+        Err(error) => {
+            make_json_api_response::<(), _>(Err(error), BodyFormat::default()).into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quickwit_config::{IndexConfig, IngestApiConfig};
+    use quickwit_ingest::{IngestServiceClient, TailRequest};
+    use quickwit_metastore::IndexMetadata;
+    use quickwit_proto::metastore::{EntityKind, IndexMetadataResponse};
+    use quickwit_proto::opentelemetry::proto::logs::v1::{LogRecord, ResourceLogs, ScopeLogs};
+    use quickwit_proto::opentelemetry::proto::trace::v1::{ResourceSpans, ScopeSpans, Span};
+    use serde_json::{json, Value as JsonValue};
+
+    use super::*;
+    use crate::ingest_api::setup_ingest_service;
+
+    fn otlp_logs_request() -> ExportLogsServiceRequest {
+        let log_record = LogRecord {
+            time_unix_nano: 1_000_000_001,
+            body: None,
+            ..Default::default()
+        };
+        ExportLogsServiceRequest {
+            resource_logs: vec![ResourceLogs {
+                scope_logs: vec![ScopeLogs {
+                    log_records: vec![log_record],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+        }
+    }
+
+    fn otel_index_metadata(index_id: &str, index_config: IndexConfig) -> IndexMetadata {
+        let mut index_config = index_config;
+        index_config.index_id = index_id.to_string();
+        IndexMetadata::new(index_config)
+    }
+
+    /// Returns a metastore that knows the provided indexes.
+    fn metastore_for_test(indexes_metadata: Vec<IndexMetadata>) -> MetastoreServiceClient {
+        let mut mock_metastore = MetastoreServiceClient::mock();
+        mock_metastore
+            .expect_index_metadata()
+            .returning(move |index_metadata_request| {
+                let index_id = index_metadata_request.index_id.unwrap();
+                let Some(index_metadata) = indexes_metadata
+                    .iter()
+                    .find(|index_metadata| index_metadata.index_id() == index_id)
+                else {
+                    return Err(MetastoreError::NotFound(EntityKind::Index { index_id }));
+                };
+                Ok(IndexMetadataResponse::try_from_index_metadata(index_metadata.clone()).unwrap())
+            });
+        MetastoreServiceClient::from(mock_metastore)
+    }
+
+    fn default_index_root_uri() -> Uri {
+        Uri::for_test("ram:///indexes")
+    }
+
+    async fn num_docs_in_queue(ingest_service: &mut IngestServiceClient, index_id: &str) -> usize {
+        let tail_request = TailRequest {
+            index_id: index_id.to_string(),
+        };
+        ingest_service
+            .tail(tail_request)
+            .await
+            .unwrap()
+            .doc_batch
+            .map(|doc_batch| doc_batch.num_docs())
+            .unwrap_or(0)
+    }
+
+    #[tokio::test]
+    async fn test_otlp_ingest_logs() {
+        let (universe, _temp_dir, mut ingest_service, _) = setup_ingest_service(
+            &[OTEL_LOGS_INDEX_ID, "my-logs"],
+            &IngestApiConfig::default(),
+        )
+        .await;
+        let otlp_logs_service = OtlpGrpcLogsService::new(ingest_service.clone());
+        let logs_index_config =
+            OtlpGrpcLogsService::index_config(&default_index_root_uri()).unwrap();
+        let traces_index_config =
+            OtlpGrpcTracesService::index_config(&default_index_root_uri()).unwrap();
+        let metastore = metastore_for_test(vec![
+            otel_index_metadata("my-logs", logs_index_config),
+            otel_index_metadata(OTEL_TRACES_INDEX_ID, traces_index_config),
+            IndexMetadata::for_test("my-index", "ram:///indexes/my-index"),
+        ]);
+        let otlp_api_handlers = otlp_ingest_api_handlers(
+            metastore,
+            &default_index_root_uri(),
+            Some(otlp_logs_service),
+            None,
+            None,
+            10 * 1024 * 1024,
+        );
+
+        let resp = warp::test::request()
+            .path("/otlp/v1/logs")
+            .method("POST")
+            .header("content-type", "application/x-protobuf")
+            .body(otlp_logs_request().encode_to_vec())
+            .reply(&otlp_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 200);
+        assert_eq!(
+            resp.headers().get("content-type").unwrap(),
+            "application/x-protobuf"
+        );
+        let export_response = ExportLogsServiceResponse::decode(resp.body().as_ref()).unwrap();
+        assert_eq!(
+            export_response
+                .partial_success
+                .unwrap()
+                .rejected_log_records,
+            0
+        );
+        assert_eq!(
+            num_docs_in_queue(&mut ingest_service, OTEL_LOGS_INDEX_ID).await,
+            1
+        );
+
+        let resp = warp::test::request()
+            .path("/my-logs/otlp/v1/logs")
+            .method("POST")
+            .header("content-type", "application/json")
+            .body(
+                json!({
+                    "resourceLogs": [{
+                        "scopeLogs": [{
+                            "logRecords": [{
+                                "timeUnixNano": "1544712660300000000",
+                                "severityNumber": 10,
+                                "traceId": "5B8EFFF798038103D269B633813FC60C",
+                                "spanId": "EEE19B7EC3C1B174",
+                                "body": {"stringValue": "Example log record"},
+                                "attributes": [
+                                    {"key": "int.attribute", "value": {"intValue": "10"}}
+                                ]
+                            }]
+                        }]
+                    }]
+                })
+                .to_string(),
+            )
+            .reply(&otlp_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 200);
+        assert_eq!(
+            resp.headers().get("content-type").unwrap(),
+            "application/json"
+        );
+        let export_response: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(export_response, json!({"partialSuccess": {}}));
+
+        let resp = warp::test::request()
+            .path("/otlp/v1/logs")
+            .method("POST")
+            .header("content-type", "application/x-protobuf")
+            .header(OTEL_LOGS_INDEX_HEADER, "my-logs")
+            .body(otlp_logs_request().encode_to_vec())
+            .reply(&otlp_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 200);
+        assert_eq!(num_docs_in_queue(&mut ingest_service, "my-logs").await, 2);
+
+        // The target index does not exist.
+        let resp = warp::test::request()
+            .path("/otlp/v1/logs")
+            .method("POST")
+            .header("content-type", "application/x-protobuf")
+            .header(OTEL_LOGS_INDEX_HEADER, "does-not-exist")
+            .body(otlp_logs_request().encode_to_vec())
+            .reply(&otlp_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 404);
+
+        // The target index does not use the OTEL logs doc mapping.
+        let resp = warp::test::request()
+            .path("/my-index/otlp/v1/logs")
+            .method("POST")
+            .header("content-type", "application/x-protobuf")
+            .body(otlp_logs_request().encode_to_vec())
+            .reply(&otlp_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 400);
+
+        // The traces doc mapping is not compatible with the logs doc mapping.
+        let resp = warp::test::request()
+            .path("/otlp/v1/logs")
+            .method("POST")
+            .header("content-type", "application/x-protobuf")
+            .header(OTEL_LOGS_INDEX_HEADER, OTEL_TRACES_INDEX_ID)
+            .body(otlp_logs_request().encode_to_vec())
+            .reply(&otlp_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 400);
+
+        let resp = warp::test::request()
+            .path("/otlp/v1/logs")
+            .method("POST")
+            .header("content-type", "text/plain")
+            .body("hello")
+            .reply(&otlp_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 415);
+
+        let resp = warp::test::request()
+            .path("/otlp/v1/logs")
+            .method("POST")
+            .header("content-type", "application/json")
+            .body("{")
+            .reply(&otlp_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 400);
+
+        universe.assert_quit().await;
+    }
+
+    #[tokio::test]
+    async fn test_otlp_ingest_metrics() {
+        let (universe, _temp_dir, mut ingest_service, _) =
+            setup_ingest_service(&["my-metrics"], &IngestApiConfig::default()).await;
+        let otlp_metrics_service = OtlpGrpcMetricsService::new(ingest_service.clone());
+        let metrics_index_config =
+            OtlpGrpcMetricsService::index_config(&default_index_root_uri()).unwrap();
+        let metastore = metastore_for_test(vec![otel_index_metadata(
+            "my-metrics",
+            metrics_index_config,
+        )]);
+        let otlp_api_handlers = otlp_ingest_api_handlers(
+            metastore,
+            &default_index_root_uri(),
+            None,
+            Some(otlp_metrics_service),
+            None,
+            10 * 1024 * 1024,
+        );
+
+        let export_request = json!({
+            "resourceMetrics": [{
+                "scopeMetrics": [{
+                    "metrics": [{
+                        "name": "cpu.utilization",
+                        "gauge": {
+                            "dataPoints": [{
+                                "asDouble": 0.5,
+                                "timeUnixNano": "1000000001"
+                            }]
+                        }
+                    }]
+                }]
+            }]
+        });
+        let resp = warp::test::request()
+            .path("/otlp/v1/metrics")
+            .method("POST")
+            .header("content-type", "application/json")
+            .header(OTEL_METRICS_INDEX_HEADER, "my-metrics")
+            .body(export_request.to_string())
+            .reply(&otlp_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let export_response: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(export_response, json!({"partialSuccess": {}}));
+        assert_eq!(
+            num_docs_in_queue(&mut ingest_service, "my-metrics").await,
+            1
+        );
+
+        universe.assert_quit().await;
+    }
+
+    #[tokio::test]
+    async fn test_otlp_ingest_traces() {
+        let (universe, _temp_dir, mut ingest_service, _) =
+            setup_ingest_service(&["my-traces"], &IngestApiConfig::default()).await;
+        let otlp_traces_service = OtlpGrpcTracesService::new(ingest_service.clone(), None);
+        let traces_index_config =
+            OtlpGrpcTracesService::index_config(&default_index_root_uri()).unwrap();
+        let metastore =
+            metastore_for_test(vec![otel_index_metadata("my-traces", traces_index_config)]);
+        let otlp_api_handlers = otlp_ingest_api_handlers(
+            metastore,
+            &default_index_root_uri(),
+            None,
+            None,
+            Some(otlp_traces_service),
+            10 * 1024 * 1024,
+        );
+
+        let span = Span {
+            trace_id: vec![1; 16],
+            span_id: vec![2; 8],
+            name: "publish_split".to_string(),
+            start_time_unix_nano: 1_000_000_001,
+            end_time_unix_nano: 1_000_000_002,
+            ..Default::default()
+        };
+        let export_request = ExportTraceServiceRequest {
+            resource_spans: vec![ResourceSpans {
+                scope_spans: vec![ScopeSpans {
+                    spans: vec![span],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+        };
+        let resp = warp::test::request()
+            .path("/my-traces/otlp/v1/traces")
+            .method("POST")
+            .header("content-type", "application/x-protobuf")
+            .body(export_request.encode_to_vec())
+            .reply(&otlp_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let export_response = ExportTraceServiceResponse::decode(resp.body().as_ref()).unwrap();
+        assert_eq!(export_response.partial_success.unwrap().rejected_spans, 0);
+        assert_eq!(num_docs_in_queue(&mut ingest_service, "my-traces").await, 1);
+
+        let resp = warp::test::request()
+            .path("/my-traces/otlp/v1/traces")
+            .method("POST")
+            .header("content-type", "application/json")
+            .body(
+                json!({
+                    "resourceSpans": [{
+                        "scopeSpans": [{
+                            "spans": [{
+                                "traceId": "5B8EFFF798038103D269B633813FC60C",
+                                "spanId": "EEE19B7EC3C1B174",
+                                "parentSpanId": "EEE19B7EC3C1B173",
+                                "name": "I'm a server span",
+                                "startTimeUnixNano": "1544712660000000000",
+                                "endTimeUnixNano": "1544712661000000000",
+                                "kind": 2
+                            }]
+                        }]
+                    }]
+                })
+                .to_string(),
+            )
+            .reply(&otlp_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 200);
+        assert_eq!(num_docs_in_queue(&mut ingest_service, "my-traces").await, 2);
+
+        // Trace IDs are hex-encoded in OTLP JSON payloads.
+        let resp = warp::test::request()
+            .path("/my-traces/otlp/v1/traces")
+            .method("POST")
+            .header("content-type", "application/json")
+            .body(
+                json!({
+                    "resourceSpans": [{
+                        "scopeSpans": [{
+                            "spans": [{"traceId": "AQEBAQEBAQEBAQEBAQEBAQ==", "spanId": "EEE19B7EC3C1B174"}]
+                        }]
+                    }]
+                })
+                .to_string(),
+            )
+            .reply(&otlp_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 400);
+
+        // The logs service is not enabled.
+        let resp = warp::test::request()
+            .path("/otlp/v1/logs")
+            .method("POST")
+            .body(otlp_logs_request().encode_to_vec())
+            .reply(&otlp_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 404);
+
+        universe.assert_quit().await;
+    }
+}
@@ -0,0 +1,289 @@
+// Copyright (C) 2023 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::BTreeMap;
+
+use anyhow::bail;
+use bytes::Bytes;
+use prost::Message;
+use quickwit_common::uri::Uri;
+use quickwit_config::{load_index_config_from_user_config, ConfigFormat, IndexConfig};
+use quickwit_ingest::{DocBatch, DocBatchBuilder};
+use quickwit_proto::prometheus::WriteRequest;
+use quickwit_proto::types::IndexId;
+use serde::Serialize;
+
+pub(crate) const PROMETHEUS_METRICS_INDEX_ID: &str = "prometheus-metrics-v0_6";
+
+const PROMETHEUS_METRICS_INDEX_CONFIG: &str = r#"
+version: 0.6
+
+index_id: ${INDEX_ID}
+
+doc_mapping:
+  mode: strict
+  field_mappings:
+    - name: timestamp_millis
+      type: datetime
+      input_formats: [unix_timestamp]
+      output_format: unix_timestamp_millis
+      indexed: false
+      fast: true
+      fast_precision: milliseconds
+    - name: metric_name
+      type: text
+      tokenizer: raw
+      fast: true
+    - name: labels
+      type: json
+      tokenizer: raw
+      fast: true
+    - name: value
+      type: f64
+      indexed: false
+      fast: true
+
+  timestamp_field: timestamp_millis
+
+indexing_settings:
+  commit_timeout_secs: 5
+
+search_settings:
+  default_search_fields: [metric_name]
+"#;
+
+/// Label holding the metric name of a time series.
+const METRIC_NAME_LABEL: &str = "__name__";
+
+pub(crate) fn prometheus_index_config(default_index_root_uri: &Uri) -> anyhow::Result<IndexConfig> {
+    let index_config_str =
+        PROMETHEUS_METRICS_INDEX_CONFIG.replace("${INDEX_ID}", PROMETHEUS_METRICS_INDEX_ID);
+    let index_config = load_index_config_from_user_config(
+        ConfigFormat::Yaml,
+        index_config_str.as_bytes(),
+        default_index_root_uri,
+    )?;
+    Ok(index_config)
+}
+
+/// A Prometheus sample flattened into a document.
+#[derive(Debug, Serialize)]
+struct SampleDoc<'a> {
+    timestamp_millis: i64,
+    metric_name: &'a str,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    labels: BTreeMap<&'a str, &'a str>,
+    value: f64,
+}
+
+#[derive(Debug)]
+pub(crate) struct ParsedWriteRequest {
+    pub doc_batch: DocBatch,
+    pub num_samples: u64,
+    pub num_rejected_samples: u64,
+}
+
+/// Decodes a snappy-compressed remote-write request. The decompressed size, read from the header
+/// of the payload, is checked against `content_length_limit` before decompressing it.
+pub(crate) fn decode_write_request(
+    body: &Bytes,
+    content_length_limit: u64,
+) -> anyhow::Result<WriteRequest> {
+    let decompressed_len = snap::raw::decompress_len(body)?;
+
+    if decompressed_len as u64 > content_length_limit {
+        bail!(
+            "decompressed remote-write request of {decompressed_len} bytes exceeds the limit of \
+             {content_length_limit} bytes"
+        );
+    }
+    let decompressed_body = snap::raw::Decoder::new().decompress_vec(body)?;
+    let write_request = WriteRequest::decode(decompressed_body.as_slice())?;
+    Ok(write_request)
+}
+
+/// Turns each sample of a remote-write request into a document. NaN values, which Prometheus uses
+/// as staleness markers, and infinite values cannot be represented in JSON and are rejected.
+pub(crate) fn parse_write_request(
+    write_request: &WriteRequest,
+    index_id: IndexId,
+) -> anyhow::Result<ParsedWriteRequest> {
+    let mut doc_batch_builder = DocBatchBuilder::new(index_id).json_writer();
+    let mut num_samples = 0;
+    let mut num_rejected_samples = 0;
+
+    for time_series in &write_request.timeseries {
+        let mut metric_name = "";
+        let mut labels = BTreeMap::new();
+
+        for label in &time_series.labels {
+            if label.name == METRIC_NAME_LABEL {
+                metric_name = &label.value;
+            } else {
+                labels.insert(label.name.as_str(), label.value.as_str());
+            }
+        }
+        let mut sample_doc = SampleDoc {
+            timestamp_millis: 0,
+            metric_name,
+            labels,
+            value: 0.0,
+        };
+        for sample in &time_series.samples {
+            num_samples += 1;
+
+            if !sample.value.is_finite() {
+                num_rejected_samples += 1;
+                continue;
+            }
+            sample_doc.timestamp_millis = sample.timestamp;
+            sample_doc.value = sample.value;
+            doc_batch_builder.ingest_doc(&sample_doc)?;
+        }
+    }
+    let parsed_write_request = ParsedWriteRequest {
+        doc_batch: doc_batch_builder.build(),
+        num_samples,
+        num_rejected_samples,
+    };
+    Ok(parsed_write_request)
+}
+
+#[cfg(test)]
+mod tests {
+    use quickwit_ingest::DocCommand;
+    use quickwit_proto::prometheus::{Label, Sample, TimeSeries};
+    use serde_json::{json, Value as JsonValue};
+
+    use super::*;
+
+    fn label(name: &str, value: &str) -> Label {
+        Label {
+            name: name.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_prometheus_index_config_is_valid() {
+        let index_config = prometheus_index_config(&Uri::for_test("ram:///indexes")).unwrap();
+        assert_eq!(index_config.index_id, PROMETHEUS_METRICS_INDEX_ID);
+    }
+
+    #[test]
+    fn test_decode_write_request() {
+        let write_request = WriteRequest {
+            timeseries: vec![TimeSeries {
+                labels: vec![label("__name__", "up")],
+                samples: vec![Sample {
+                    value: 1.0,
+                    timestamp: 1_000,
+                }],
+                exemplars: Vec::new(),
+            }],
+            metadata: Vec::new(),
+        };
+        let body = snap::raw::Encoder::new()
+            .compress_vec(&write_request.encode_to_vec())
+            .unwrap();
+        let body = Bytes::from(body);
+        assert_eq!(decode_write_request(&body, 1024).unwrap(), write_request);
+
+        let error = decode_write_request(&body, 8).unwrap_err();
+        assert!(error.to_string().contains("exceeds the limit of 8 bytes"));
+
+        // The decompressed length is read from the header of the payload, so a forged header is
+        // rejected before any buffer is allocated.
+        let forged_body = Bytes::from_static(&[0xff, 0xff, 0xff, 0xff, 0x0f]);
+        let error = decode_write_request(&forged_body, 1024).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("4294967295 bytes exceeds the limit"));
+
+        decode_write_request(&Bytes::from_static(b"not snappy"), 1024).unwrap_err();
+    }
+
+    #[test]
+    fn test_parse_write_request() {
+        let write_request = WriteRequest {
+            timeseries: vec![
+                TimeSeries {
+                    labels: vec![
+                        label("__name__", "http_requests_total"),
+                        label("method", "GET"),
+                        label("job", "quickwit"),
+                    ],
+                    samples: vec![
+                        Sample {
+                            value: 3.0,
+                            timestamp: 1_000,
+                        },
+                        Sample {
+                            value: f64::NAN,
+                            timestamp: 2_000,
+                        },
+                    ],
+                    exemplars: Vec::new(),
+                },
+                TimeSeries {
+                    labels: vec![label("__name__", "up")],
+                    samples: vec![Sample {
+                        value: 1.0,
+                        timestamp: 3_000,
+                    }],
+                    exemplars: Vec::new(),
+                },
+            ],
+            metadata: Vec::new(),
+        };
+        let parsed_write_request =
+            parse_write_request(&write_request, "my-metrics".to_string()).unwrap();
+        assert_eq!(parsed_write_request.num_samples, 3);
+        assert_eq!(parsed_write_request.num_rejected_samples, 1);
+
+        let doc_batch = parsed_write_request.doc_batch;
+        assert_eq!(doc_batch.index_id, "my-metrics");
+        assert_eq!(doc_batch.num_docs(), 2);
+
+        let docs: Vec<JsonValue> = doc_batch
+            .iter()
+            .map(|doc_command| match doc_command {
+                DocCommand::Ingest { payload } => serde_json::from_slice(&payload).unwrap(),
+                DocCommand::Commit => panic!("expected ingest command"),
+            })
+            .collect();
+        assert_eq!(
+            docs[0],
+            json!({
+                "timestamp_millis": 1_000,
+                "metric_name": "http_requests_total",
+                "labels": {"job": "quickwit", "method": "GET"},
+                "value": 3.0,
+            })
+        );
+        assert_eq!(
+            docs[1],
+            json!({
+                "timestamp_millis": 3_000,
+                "metric_name": "up",
+                "value": 1.0,
+            })
+        );
+    }
+}
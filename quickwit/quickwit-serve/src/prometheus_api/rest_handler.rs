@@ -0,0 +1,262 @@
+// Copyright (C) 2023 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use bytes::Bytes;
+use quickwit_ingest::{
+    CommitType, IngestRequest, IngestService, IngestServiceClient, IngestServiceError,
+};
+use quickwit_proto::types::IndexId;
+use quickwit_proto::{ServiceError, ServiceErrorCode};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use warp::{Filter, Rejection};
+
+use super::remote_write::{decode_write_request, parse_write_request, PROMETHEUS_METRICS_INDEX_ID};
+use crate::json_api_response::make_json_api_response;
+use crate::{require, with_arg, BodyFormat};
+
+/// Header used to ingest samples into an index other than the default Prometheus metrics index.
+const PROMETHEUS_INDEX_HEADER: &str = "qw-prometheus-index";
+
+#[derive(utoipa::OpenApi)]
+#[openapi(
+    paths(prometheus_remote_write),
+    components(schemas(RemoteWriteResponse))
+)]
+pub struct PrometheusApi;
+
+#[derive(Debug, Error)]
+pub(crate) enum PrometheusApiError {
+    #[error("failed to decode remote-write request: {0}")]
+    InvalidRequest(String),
+    #[error(transparent)]
+    Ingest(#[from] IngestServiceError),
+    #[error("internal error: {0}")]
+    Internal(String),
+}
+
+impl ServiceError for PrometheusApiError {
+    fn error_code(&self) -> ServiceErrorCode {
+        match self {
+            Self::InvalidRequest(_) => ServiceErrorCode::BadRequest,
+            Self::Ingest(error) => error.error_code(),
+            Self::Internal(_) => ServiceErrorCode::Internal,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct RemoteWriteResponse {
+    /// Number of samples contained in the request.
+    pub num_samples: u64,
+    /// Number of samples that could not be ingested, such as staleness markers.
+    pub num_rejected_samples: u64,
+}
+
+/// Setup Prometheus API handlers.
+///
+/// The handlers are only mounted when the Prometheus endpoint is enabled on an indexer, otherwise
+/// `ingest_service_opt` is `None` and requests are rejected.
+pub(crate) fn prometheus_api_handlers(
+    ingest_service_opt: Option<IngestServiceClient>,
+    content_length_limit: u64,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    prometheus_remote_write_handler(ingest_service_opt, content_length_limit)
+}
+
+fn prometheus_remote_write_filter(
+    content_length_limit: u64,
+) -> impl Filter<Extract = (IndexId, Bytes, u64), Error = Rejection> + Clone {
+    warp::path!("write")
+        .and(warp::post())
+        .and(warp::header::optional::<String>(PROMETHEUS_INDEX_HEADER))
+        .map(|index_id_opt: Option<String>| {
+            index_id_opt.unwrap_or_else(|| PROMETHEUS_METRICS_INDEX_ID.to_string())
+        })
+        .and(warp::body::content_length_limit(content_length_limit))
+        .and(warp::body::bytes())
+        .and(with_arg(content_length_limit))
+}
+
+fn prometheus_remote_write_handler(
+    ingest_service_opt: Option<IngestServiceClient>,
+    content_length_limit: u64,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    prometheus_remote_write_filter(content_length_limit)
+        .and(require(ingest_service_opt))
+        .then(prometheus_remote_write)
+        .map(|result| make_json_api_response(result, BodyFormat::default()))
+}
+
+#[utoipa::path(
+    post,
+    tag = "Ingest",
+    path = "/write",
+    request_body(content = String, description = "Snappy-compressed Prometheus remote-write request", content_type = "application/x-protobuf"),
+    responses(
+        (status = 200, description = "Successfully ingested samples.", body = RemoteWriteResponse)
+    ),
+    params(
+        ("qw-prometheus-index" = Option<String>, Header, description = "The index ID to ingest the samples into. Defaults to the Prometheus metrics index."),
+    )
+)]
+/// Ingest Prometheus Remote-Write Samples
+///
+/// Each sample is indexed as a document holding the metric name, the labels of its time series,
+/// its value, and its timestamp.
+async fn prometheus_remote_write(
+    index_id: IndexId,
+    body: Bytes,
+    content_length_limit: u64,
+    mut ingest_service: IngestServiceClient,
+) -> Result<RemoteWriteResponse, PrometheusApiError> {
+    // Decompressing, decoding, and parsing the samples of a request are CPU-intensive, so they
+    // run on a blocking thread, so as not to stall the runtime.
+    let parsed_write_request = tokio::task::spawn_blocking(move || {
+        let write_request = decode_write_request(&body, content_length_limit)?;
+        parse_write_request(&write_request, index_id)
+    })
+    .await
+    .map_err(|join_error| PrometheusApiError::Internal(join_error.to_string()))?
+    .map_err(|error| PrometheusApiError::InvalidRequest(error.to_string()))?;
+
+    if parsed_write_request.doc_batch.num_docs() > 0 {
+        let ingest_request = IngestRequest {
+            doc_batches: vec![parsed_write_request.doc_batch],
+            commit: CommitType::Auto.into(),
+        };
+        ingest_service.ingest(ingest_request).await?;
+    }
+    let remote_write_response = RemoteWriteResponse {
+        num_samples: parsed_write_request.num_samples,
+        num_rejected_samples: parsed_write_request.num_rejected_samples,
+    };
+    Ok(remote_write_response)
+}
+
+#[cfg(test)]
+mod tests {
+    use prost::Message;
+    use quickwit_config::IngestApiConfig;
+    use quickwit_ingest::TailRequest;
+    use quickwit_proto::prometheus::{Label, Sample, TimeSeries, WriteRequest};
+
+    use super::*;
+    use crate::ingest_api::setup_ingest_service;
+
+    fn snappy_write_request() -> Vec<u8> {
+        let write_request = WriteRequest {
+            timeseries: vec![TimeSeries {
+                labels: vec![
+                    Label {
+                        name: "__name__".to_string(),
+                        value: "up".to_string(),
+                    },
+                    Label {
+                        name: "job".to_string(),
+                        value: "quickwit".to_string(),
+                    },
+                ],
+                samples: vec![
+                    Sample {
+                        value: 1.0,
+                        timestamp: 1_000,
+                    },
+                    Sample {
+                        value: 0.0,
+                        timestamp: 2_000,
+                    },
+                ],
+                exemplars: Vec::new(),
+            }],
+            metadata: Vec::new(),
+        };
+        snap::raw::Encoder::new()
+            .compress_vec(&write_request.encode_to_vec())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_prometheus_remote_write() {
+        let (universe, _temp_dir, mut ingest_service, _) = setup_ingest_service(
+            &[PROMETHEUS_METRICS_INDEX_ID, "my-metrics"],
+            &IngestApiConfig::default(),
+        )
+        .await;
+        let prometheus_api_handlers =
+            prometheus_api_handlers(Some(ingest_service.clone()), 10 * 1024 * 1024);
+
+        let resp = warp::test::request()
+            .path("/write")
+            .method("POST")
+            .header("content-type", "application/x-protobuf")
+            .header("content-encoding", "snappy")
+            .body(snappy_write_request())
+            .reply(&prometheus_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let remote_write_response: RemoteWriteResponse =
+            serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(remote_write_response.num_samples, 2);
+        assert_eq!(remote_write_response.num_rejected_samples, 0);
+
+        let tail_request = TailRequest {
+            index_id: PROMETHEUS_METRICS_INDEX_ID.to_string(),
+        };
+        let fetch_response = ingest_service.tail(tail_request).await.unwrap();
+        assert_eq!(fetch_response.doc_batch.unwrap().num_docs(), 2);
+
+        let resp = warp::test::request()
+            .path("/write")
+            .method("POST")
+            .header(PROMETHEUS_INDEX_HEADER, "my-metrics")
+            .body(snappy_write_request())
+            .reply(&prometheus_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 200);
+
+        let tail_request = TailRequest {
+            index_id: "my-metrics".to_string(),
+        };
+        let fetch_response = ingest_service.tail(tail_request).await.unwrap();
+        assert_eq!(fetch_response.doc_batch.unwrap().num_docs(), 2);
+
+        let resp = warp::test::request()
+            .path("/write")
+            .method("POST")
+            .body("not snappy")
+            .reply(&prometheus_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 400);
+
+        universe.assert_quit().await;
+    }
+
+    #[tokio::test]
+    async fn test_prometheus_remote_write_disabled() {
+        let prometheus_api_handlers = prometheus_api_handlers(None, 10 * 1024 * 1024);
+        let resp = warp::test::request()
+            .path("/write")
+            .method("POST")
+            .body(snappy_write_request())
+            .reply(&prometheus_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 404);
+    }
+}
@@ -23,6 +23,7 @@ use std::sync::Arc;
 use hyper::http::HeaderValue;
 use hyper::{http, Method};
 use quickwit_common::tower::BoxFutureInfaillible;
+use quickwit_config::service::QuickwitService;
 use quickwit_proto::ServiceErrorCode;
 use tower::make::Shared;
 use tower::ServiceBuilder;
@@ -33,6 +34,8 @@ use tracing::{error, info};
 use warp::{redirect, Filter, Rejection, Reply};
 
 use crate::cluster_api::cluster_handler;
+use crate::dead_letter_api::dead_letter_api_handlers;
+use crate::decompression::DecompressionError;
 use crate::delete_task_api::delete_task_api_handlers;
 use crate::elastic_search_api::elastic_api_handlers;
 use crate::health_check_api::health_check_handlers;
@@ -40,9 +43,16 @@ use crate::index_api::index_management_handlers;
 use crate::indexing_api::indexing_get_handler;
 use crate::ingest_api::ingest_api_handlers;
 use crate::json_api_response::{ApiError, JsonApiResponse};
+use crate::loki_api::loki_api_handlers;
 use crate::metrics_api::metrics_handler;
 use crate::node_info_handler::node_info_handler;
-use crate::search_api::{search_get_handler, search_post_handler, search_stream_handler};
+use crate::otlp_api::otlp_ingest_api_handlers;
+use crate::prometheus_api::prometheus_api_handlers;
+use crate::search_api::{
+    async_search_delete_handler, async_search_get_handler, async_search_submit_handler,
+    search_get_handler, search_post_handler, search_stream_handler,
+};
+use crate::sql_api::sql_handler;
 use crate::ui_handler::ui_handler;
 use crate::{BodyFormat, BuildInfo, QuickwitServices, RuntimeInfo};
 
@@ -87,6 +97,15 @@ pub(crate) async fn start_rest_server(
 
     let ingest_router = quickwit_services.ingest_router_service.clone();
     let ingest_service = quickwit_services.ingest_service.clone();
+    // The Prometheus remote-write endpoint is only exposed on indexers that enable it.
+    let prometheus_ingest_service_opt = (quickwit_services
+        .node_config
+        .is_service_enabled(QuickwitService::Indexer)
+        && quickwit_services
+            .node_config
+            .indexer_config
+            .enable_prometheus_endpoint)
+        .then(|| ingest_service.clone());
 
     // `/api/v1/*` routes.
     let api_v1_root_url = warp::path!("api" / "v1" / ..);
@@ -106,11 +125,43 @@ pub(crate) async fn start_rest_server(
         .or(search_stream_handler(
             quickwit_services.search_service.clone(),
         ))
+        .or(async_search_submit_handler(
+            quickwit_services.search_service.clone(),
+        ))
+        .or(async_search_get_handler(
+            quickwit_services.search_service.clone(),
+        ))
+        .or(async_search_delete_handler(
+            quickwit_services.search_service.clone(),
+        ))
+        .or(sql_handler(quickwit_services.search_service.clone()))
+        .or(loki_api_handlers(
+            quickwit_services.search_service.clone(),
+            quickwit_services.metastore_client.clone(),
+        ))
         .or(ingest_api_handlers(
             ingest_router,
             ingest_service.clone(),
             quickwit_services.node_config.ingest_api_config.clone(),
         ))
+        .or(otlp_ingest_api_handlers(
+            quickwit_services.metastore_client.clone(),
+            &quickwit_services.node_config.default_index_root_uri,
+            quickwit_services.otlp_logs_service_opt.clone(),
+            quickwit_services.otlp_metrics_service_opt.clone(),
+            quickwit_services.otlp_traces_service_opt.clone(),
+            quickwit_services
+                .node_config
+                .ingest_api_config
+                .content_length_limit,
+        ))
+        .or(prometheus_api_handlers(
+            prometheus_ingest_service_opt,
+            quickwit_services
+                .node_config
+                .ingest_api_config
+                .content_length_limit,
+        ))
         .or(index_management_handlers(
             quickwit_services.index_manager.clone(),
             quickwit_services.node_config.clone(),
@@ -118,8 +169,14 @@ pub(crate) async fn start_rest_server(
         .or(delete_task_api_handlers(
             quickwit_services.metastore_client.clone(),
         ))
+        .or(dead_letter_api_handlers(
+            quickwit_services.metastore_client.clone(),
+            quickwit_services.search_service.clone(),
+            ingest_service.clone(),
+        ))
         .or(elastic_api_handlers(
             quickwit_services.node_config.clone(),
+            quickwit_services.metastore_client.clone(),
             quickwit_services.search_service.clone(),
             ingest_service.clone(),
         ));
@@ -194,6 +251,18 @@ fn get_status_with_error(rejection: Rejection) -> ApiError {
             service_code: ServiceErrorCode::UnsupportedMediaType,
             message: error.to_string(),
         }
+    } else if let Some(error) = rejection.find::<DecompressionError>() {
+        let service_code = match error {
+            DecompressionError::UnsupportedEncoding(_) => ServiceErrorCode::UnsupportedMediaType,
+            DecompressionError::InvalidBody { .. } | DecompressionError::PayloadTooLarge { .. } => {
+                ServiceErrorCode::BadRequest
+            }
+            DecompressionError::Internal(_) => ServiceErrorCode::Internal,
+        };
+        ApiError {
+            service_code,
+            message: error.to_string(),
+        }
     } else if rejection.is_not_found() {
         ApiError {
             service_code: ServiceErrorCode::NotFound,
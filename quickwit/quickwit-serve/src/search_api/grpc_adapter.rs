@@ -119,6 +119,29 @@ impl grpc::SearchService for GrpcSearchAdapter {
         convert_to_grpc_result(leaf_search_result)
     }
 
+    #[instrument(skip(self, request))]
+    async fn root_list_fields(
+        &self,
+        request: tonic::Request<quickwit_proto::search::ListFieldsRequest>,
+    ) -> Result<tonic::Response<quickwit_proto::search::ListFieldsResponse>, tonic::Status> {
+        set_parent_span_from_request_metadata(request.metadata());
+        let list_fields_request = request.into_inner();
+        let list_fields_result = self.0.root_list_fields(list_fields_request).await;
+        convert_to_grpc_result(list_fields_result)
+    }
+
+    #[instrument(skip(self, request))]
+    async fn leaf_list_fields(
+        &self,
+        request: tonic::Request<quickwit_proto::search::LeafListFieldsRequest>,
+    ) -> Result<tonic::Response<quickwit_proto::search::LeafListFieldsResponse>, tonic::Status>
+    {
+        set_parent_span_from_request_metadata(request.metadata());
+        let leaf_list_fields_request = request.into_inner();
+        let leaf_list_fields_result = self.0.leaf_list_fields(leaf_list_fields_request).await;
+        convert_to_grpc_result(leaf_list_fields_result)
+    }
+
     async fn scroll(
         &self,
         request: tonic::Request<quickwit_proto::search::ScrollRequest>,
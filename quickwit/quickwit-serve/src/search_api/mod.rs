@@ -21,10 +21,14 @@ mod grpc_adapter;
 mod rest_handler;
 
 pub use self::grpc_adapter::GrpcSearchAdapter;
-pub(crate) use self::rest_handler::extract_index_id_patterns;
 pub use self::rest_handler::{
+    async_search_delete_handler, async_search_get_handler, async_search_submit_handler,
     search_get_handler, search_post_handler, search_request_from_api_request,
-    search_stream_handler, SearchApi, SearchRequestQueryString, SortBy,
+    search_stream_handler, AsyncSearchQueryString, SearchApi, SearchRequestQueryString, SortBy,
+};
+pub(crate) use self::rest_handler::{
+    extract_index_id_patterns, parse_duration_param, parse_search_timeout,
+    submit_async_search_request_from_api_request,
 };
 
 #[cfg(test)]
@@ -76,6 +80,7 @@ mod tests {
             start_timestamp: None,
             end_timestamp: None,
             fast_field: "timestamp".to_string(),
+            fast_fields: Vec::new(),
             output_format: OutputFormat::Csv as i32,
             partition_by_field: None,
         };
@@ -19,16 +19,24 @@
 
 use std::convert::TryFrom;
 use std::sync::Arc;
+use std::time::Duration;
 
 use futures::stream::StreamExt;
 use hyper::header::HeaderValue;
 use hyper::HeaderMap;
 use once_cell::sync::Lazy;
+use quickwit_common::is_false;
 use quickwit_config::validate_index_id_pattern;
-use quickwit_proto::search::{CountHits, OutputFormat, SortField, SortOrder};
+use quickwit_proto::search::{
+    CountHits, IndexQueryProfile, OutputFormat, PrunedSplit, SearchProfile, SnippetConfig,
+    SortField, SortOrder, SplitSearchProfile,
+};
 use quickwit_proto::ServiceError;
 use quickwit_query::query_ast::query_ast_from_user_text;
-use quickwit_search::{SearchError, SearchResponseRest, SearchService};
+use quickwit_search::{
+    AsyncSearchResponseRest, AsyncSearchState, SearchError, SearchResponseRest, SearchService,
+    SubmitAsyncSearchRequest, DEFAULT_ASYNC_SEARCH_KEEP_ALIVE, DEFAULT_WAIT_FOR_COMPLETION_TIMEOUT,
+};
 use regex::Regex;
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use serde_json::Value as JsonValue;
@@ -43,15 +51,28 @@ use crate::{with_arg, BodyFormat};
 
 #[derive(utoipa::OpenApi)]
 #[openapi(
-    paths(search_get_handler, search_post_handler, search_stream_handler,),
+    paths(
+        search_get_handler,
+        search_post_handler,
+        search_stream_handler,
+        async_search_submit_handler,
+        async_search_get_handler,
+        async_search_delete_handler,
+    ),
     components(schemas(
+        AsyncSearchResponseRest,
+        AsyncSearchState,
         BodyFormat,
+        IndexQueryProfile,
         OutputFormat,
+        PrunedSplit,
+        SearchProfile,
         SearchRequestQueryString,
         SearchResponseRest,
         SortBy,
         SortField,
         SortOrder,
+        SplitSearchProfile,
     ),)
 )]
 pub struct SearchApi;
@@ -189,6 +210,19 @@ pub struct SearchRequestQueryString {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(serialize_with = "to_simple_list")]
     pub snippet_fields: Option<Vec<String>>,
+    /// Maximum number of characters of a snippet fragment (by default 150).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snippet_fragment_size: Option<u32>,
+    /// Maximum number of fragments returned per snippet field value (by default 1).
+    /// If set to 0, the whole highlighted field value is returned.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snippet_num_fragments: Option<u32>,
+    /// Markup inserted before each highlighted term (by default `<b>`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snippet_pre_tag: Option<String>,
+    /// Markup inserted after each highlighted term (by default `</b>`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snippet_post_tag: Option<String>,
     /// If set, restrict search to documents with a `timestamp >= start_timestamp`.
     /// This timestamp is expressed in seconds.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -222,6 +256,16 @@ pub struct SearchRequestQueryString {
     #[serde(with = "count_hits_from_bool")]
     #[serde(default = "count_hits_from_bool::default")]
     pub count_all: CountHits,
+    /// Maximum duration of the search (e.g. `500ms`, `2s`). When it is reached, the hits
+    /// gathered so far are returned and the response is flagged as timed out.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<String>,
+    /// If set to true, the response includes a profile of the search: the lowered query, the
+    /// splits pruned by time range or tags, and the timings and cache hits of each split.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_false")]
+    pub profile: bool,
 }
 
 mod count_hits_from_bool {
@@ -252,6 +296,14 @@ mod count_hits_from_bool {
     }
 }
 
+/// Parses a search timeout expressed as a duration (e.g. `500ms`, `2s`) into milliseconds.
+pub(crate) fn parse_search_timeout(timeout_str: &str) -> Result<u64, SearchError> {
+    let timeout = humantime::parse_duration(timeout_str).map_err(|_err| {
+        SearchError::InvalidArgument(format!("invalid timeout: `{timeout_str}`"))
+    })?;
+    Ok(timeout.as_millis() as u64)
+}
+
 pub fn search_request_from_api_request(
     index_id_patterns: Vec<String>,
     search_request: SearchRequestQueryString,
@@ -261,10 +313,30 @@ pub fn search_request_from_api_request(
     // the user of the docmapper default fields (which we do not have at this point).
     let query_ast = query_ast_from_user_text(&search_request.query, search_request.search_fields);
     let query_ast_json = serde_json::to_string(&query_ast)?;
+    let snippet_config = if search_request.snippet_fragment_size.is_some()
+        || search_request.snippet_num_fragments.is_some()
+        || search_request.snippet_pre_tag.is_some()
+        || search_request.snippet_post_tag.is_some()
+    {
+        Some(SnippetConfig {
+            fragment_size: search_request.snippet_fragment_size,
+            num_fragments: search_request.snippet_num_fragments,
+            pre_tag: search_request.snippet_pre_tag,
+            post_tag: search_request.snippet_post_tag,
+        })
+    } else {
+        None
+    };
+    let timeout_millis = search_request
+        .timeout
+        .as_deref()
+        .map(parse_search_timeout)
+        .transpose()?;
     let search_request = quickwit_proto::search::SearchRequest {
         index_id_patterns,
         query_ast: query_ast_json,
         snippet_fields: search_request.snippet_fields.unwrap_or_default(),
+        snippet_config,
         start_timestamp: search_request.start_timestamp,
         end_timestamp: search_request.end_timestamp,
         max_hits: search_request.max_hits,
@@ -276,6 +348,9 @@ pub fn search_request_from_api_request(
         scroll_ttl_secs: None,
         search_after: None,
         count_hits: search_request.count_all.into(),
+        timeout_millis,
+        profile: search_request.profile,
+        fetch_fields: Vec::new(),
     };
     Ok(search_request)
 }
@@ -411,7 +486,8 @@ struct SearchStreamRequestQueryString {
     pub start_timestamp: Option<i64>,
     /// If set, restricts search to documents with a `timestamp < end_timestamp``.
     pub end_timestamp: Option<i64>,
-    /// The fast field to extract.
+    /// The fast field to extract. Several fast fields can be extracted as columns of the same
+    /// rows by separating their names with commas.
     #[serde(deserialize_with = "deserialize_non_empty_string")]
     pub fast_field: String,
     /// The requested output format.
@@ -428,6 +504,15 @@ async fn search_stream_endpoint(
 ) -> Result<hyper::Body, SearchError> {
     let query_ast = query_ast_from_user_text(&search_request.query, search_request.search_fields);
     let query_ast_json = serde_json::to_string(&query_ast)?;
+    let fast_fields: Vec<String> = if search_request.fast_field.contains(',') {
+        search_request
+            .fast_field
+            .split(',')
+            .map(|fast_field| fast_field.trim().to_string())
+            .collect()
+    } else {
+        Vec::new()
+    };
     let request = quickwit_proto::search::SearchStreamRequest {
         index_id,
         query_ast: query_ast_json,
@@ -435,6 +520,7 @@ async fn search_stream_endpoint(
         start_timestamp: search_request.start_timestamp,
         end_timestamp: search_request.end_timestamp,
         fast_field: search_request.fast_field,
+        fast_fields,
         output_format: search_request.output_format as i32,
         partition_by_field: search_request.partition_by_field,
     };
@@ -497,6 +583,8 @@ async fn search_stream(
     let content_type = match request.output_format {
         OutputFormat::ClickHouseRowBinary => "application/octet-stream",
         OutputFormat::Csv => "text/csv",
+        OutputFormat::Ndjson => "application/x-ndjson",
+        OutputFormat::ArrowIpc => "application/vnd.apache.arrow.stream",
     };
     let reply =
         make_streaming_reply(search_stream_endpoint(index_id, request, &*search_service).await);
@@ -510,12 +598,206 @@ fn search_stream_filter(
         .and(serde_qs::warp::query(serde_qs::Config::default()))
 }
 
+/// This struct represents the query string of the async search submission passed to the REST
+/// API.
+#[derive(Debug, Default, Eq, PartialEq, Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(deny_unknown_fields)]
+pub struct AsyncSearchQueryString {
+    /// Time to wait for the search to complete before returning its status (e.g. `500ms`, `2s`).
+    /// By default, 1 second.
+    #[serde(default)]
+    pub wait_for_completion_timeout: Option<String>,
+    /// Time during which the search and its results are kept, starting from the submission (e.g.
+    /// `10m`, `1h`). By default, 1 hour.
+    #[serde(default)]
+    pub keep_alive: Option<String>,
+}
+
+/// Parses a duration query parameter (e.g. `500ms`, `2s`).
+pub(crate) fn parse_duration_param(
+    param_name: &str,
+    duration_str: &str,
+) -> Result<Duration, SearchError> {
+    humantime::parse_duration(duration_str).map_err(|_err| {
+        SearchError::InvalidArgument(format!("invalid {param_name}: `{duration_str}`"))
+    })
+}
+
+pub(crate) fn submit_async_search_request_from_api_request(
+    search_request: quickwit_proto::search::SearchRequest,
+    async_search_params: AsyncSearchQueryString,
+) -> Result<SubmitAsyncSearchRequest, SearchError> {
+    let wait_for_completion_timeout = async_search_params
+        .wait_for_completion_timeout
+        .as_deref()
+        .map(|duration_str| parse_duration_param("wait_for_completion_timeout", duration_str))
+        .transpose()?
+        .unwrap_or(DEFAULT_WAIT_FOR_COMPLETION_TIMEOUT);
+    let keep_alive = async_search_params
+        .keep_alive
+        .as_deref()
+        .map(|duration_str| parse_duration_param("keep_alive", duration_str))
+        .transpose()?
+        .unwrap_or(DEFAULT_ASYNC_SEARCH_KEEP_ALIVE);
+    Ok(SubmitAsyncSearchRequest {
+        search_request,
+        wait_for_completion_timeout,
+        keep_alive,
+    })
+}
+
+async fn async_search_submit_endpoint(
+    index_id_patterns: Vec<String>,
+    async_search_params: AsyncSearchQueryString,
+    search_request: SearchRequestQueryString,
+    search_service: &dyn SearchService,
+) -> Result<AsyncSearchResponseRest, SearchError> {
+    let search_request = search_request_from_api_request(index_id_patterns, search_request)?;
+    let submit_request =
+        submit_async_search_request_from_api_request(search_request, async_search_params)?;
+    let async_search_status = search_service.submit_async_search(submit_request).await?;
+    AsyncSearchResponseRest::try_from(async_search_status)
+}
+
+async fn async_search_submit(
+    index_id_patterns: Vec<String>,
+    async_search_params: AsyncSearchQueryString,
+    search_request: SearchRequestQueryString,
+    search_service: Arc<dyn SearchService>,
+) -> impl warp::Reply {
+    info!(request =? search_request, "async_search_submit");
+    let body_format = search_request.format;
+    let result = async_search_submit_endpoint(
+        index_id_patterns,
+        async_search_params,
+        search_request,
+        &*search_service,
+    )
+    .await;
+    make_json_api_response(result, body_format)
+}
+
+fn async_search_submit_filter() -> impl Filter<
+    Extract = (
+        Vec<String>,
+        AsyncSearchQueryString,
+        SearchRequestQueryString,
+    ),
+    Error = Rejection,
+> + Clone {
+    warp::path!(String / "async-search")
+        .and_then(extract_index_id_patterns)
+        .and(warp::post())
+        .and(serde_qs::warp::query(serde_qs::Config::default()))
+        .and(warp::body::content_length_limit(1024 * 1024))
+        .and(warp::body::json())
+}
+
+#[utoipa::path(
+    post,
+    tag = "Search",
+    path = "/{index_id}/async-search",
+    request_body = SearchRequestQueryString,
+    responses(
+        (status = 200, description = "Successfully submitted search.", body = AsyncSearchResponseRest)
+    ),
+    params(
+        AsyncSearchQueryString,
+        ("index_id" = String, Path, description = "The index ID to search."),
+    )
+)]
+/// Submit Async Search
+///
+/// Runs the search parsed from the request body in the background. Returns the status of the
+/// search, holding its final response if it completed before `wait_for_completion_timeout`.
+pub fn async_search_submit_handler(
+    search_service: Arc<dyn SearchService>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    async_search_submit_filter()
+        .and(with_arg(search_service))
+        .then(async_search_submit)
+}
+
+async fn async_search_get_endpoint(
+    async_search_id: String,
+    search_service: &dyn SearchService,
+) -> Result<AsyncSearchResponseRest, SearchError> {
+    let async_search_status = search_service.get_async_search(async_search_id).await?;
+    AsyncSearchResponseRest::try_from(async_search_status)
+}
+
+#[utoipa::path(
+    get,
+    tag = "Search",
+    path = "/async-search/{async_search_id}",
+    responses(
+        (status = 200, description = "Successfully fetched search status.", body = AsyncSearchResponseRest)
+    ),
+    params(
+        ("async_search_id" = String, Path, description = "The ID of the async search."),
+    )
+)]
+/// Get Async Search
+///
+/// Returns the status of an async search: its progress and partial aggregations while it is
+/// running, its final response once it completed.
+pub fn async_search_get_handler(
+    search_service: Arc<dyn SearchService>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    warp::path!("async-search" / String)
+        .and(warp::get())
+        .and(with_arg(search_service))
+        .then(
+            |async_search_id: String, search_service: Arc<dyn SearchService>| async move {
+                let result = async_search_get_endpoint(async_search_id, &*search_service).await;
+                make_json_api_response(result, BodyFormat::default())
+            },
+        )
+}
+
+async fn async_search_delete_endpoint(
+    async_search_id: String,
+    search_service: &dyn SearchService,
+) -> Result<AsyncSearchResponseRest, SearchError> {
+    let async_search_status = search_service.cancel_async_search(async_search_id).await?;
+    AsyncSearchResponseRest::try_from(async_search_status)
+}
+
+#[utoipa::path(
+    delete,
+    tag = "Search",
+    path = "/async-search/{async_search_id}",
+    responses(
+        (status = 200, description = "Successfully cancelled search.", body = AsyncSearchResponseRest)
+    ),
+    params(
+        ("async_search_id" = String, Path, description = "The ID of the async search."),
+    )
+)]
+/// Delete Async Search
+///
+/// Cancels an async search if it is still running, and drops its results.
+pub fn async_search_delete_handler(
+    search_service: Arc<dyn SearchService>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    warp::path!("async-search" / String)
+        .and(warp::delete())
+        .and(with_arg(search_service))
+        .then(
+            |async_search_id: String, search_service: Arc<dyn SearchService>| async move {
+                let result = async_search_delete_endpoint(async_search_id, &*search_service).await;
+                make_json_api_response(result, BodyFormat::default())
+            },
+        )
+}
+
 #[cfg(test)]
 mod tests {
     use assert_json_diff::{assert_json_eq, assert_json_include};
     use bytes::Bytes;
     use mockall::predicate;
-    use quickwit_search::{MockSearchService, SearchError};
+    use quickwit_search::{AsyncSearchStatus, MockSearchService, SearchError};
     use serde_json::{json, Value as JsonValue};
 
     use super::*;
@@ -563,6 +845,8 @@ mod tests {
             elapsed_time_micros: 0u64,
             errors: Vec::new(),
             aggregations: None,
+            timed_out: false,
+            profile: None,
         };
         let search_response_json: JsonValue = serde_json::to_value(search_response)?;
         let expected_search_response_json: JsonValue = json!({
@@ -1112,6 +1396,49 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_rest_search_stream_api_multiple_fast_fields() {
+        let mut mock_search_service = MockSearchService::new();
+        mock_search_service
+            .expect_root_search_stream()
+            .with(predicate::function(
+                |search_stream_request: &quickwit_proto::search::SearchStreamRequest| {
+                    search_stream_request.fast_fields == ["ts", "level"]
+                        && search_stream_request.output_format == OutputFormat::Ndjson as i32
+                },
+            ))
+            .return_once(|_| {
+                Ok(Box::pin(futures::stream::iter(vec![Ok(Bytes::from(
+                    "{\"level\":\"info\",\"ts\":1}\n",
+                ))])))
+            });
+        let rest_search_stream_api_handler = search_handler(mock_search_service);
+        let response = warp::test::request()
+            .path("/my-index/search/stream?query=obama&fast_field=ts,level&output_format=ndjson")
+            .reply(&rest_search_stream_api_handler)
+            .await;
+        assert_eq!(response.status(), 200);
+        assert_eq!(
+            response.headers().get(CONTENT_TYPE).unwrap(),
+            "application/x-ndjson"
+        );
+        let body = String::from_utf8_lossy(response.body());
+        assert_eq!(body, "{\"level\":\"info\",\"ts\":1}\n");
+    }
+
+    #[tokio::test]
+    async fn test_rest_search_stream_api_arrow_ipc() {
+        let (_index, req) = warp::test::request()
+            .path(
+                "/my-index/search/stream?query=obama&fast_field=external_id&\
+                 output_format=arrow_ipc",
+            )
+            .filter(&super::search_stream_filter())
+            .await
+            .unwrap();
+        assert_eq!(req.output_format, OutputFormat::ArrowIpc);
+    }
+
     #[tokio::test]
     async fn test_rest_search_stream_api_error() {
         let rejection = warp::test::request()
@@ -1125,7 +1452,8 @@ mod tests {
         let parse_error = rejection.find::<serde_qs::Error>().unwrap();
         assert_eq!(
             parse_error.to_string(),
-            "unknown variant `ClickHouseRowBinary`, expected `csv` or `click_house_row_binary`"
+            "unknown variant `ClickHouseRowBinary`, expected one of `csv`, \
+             `click_house_row_binary`, `ndjson`, `arrow_ipc`"
         );
     }
 
@@ -1182,6 +1510,106 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_rest_search_api_route_snippet_config() {
+        let mut mock_search_service = MockSearchService::new();
+        mock_search_service
+            .expect_root_search()
+            .with(predicate::function(
+                |search_request: &quickwit_proto::search::SearchRequest| {
+                    search_request.snippet_config
+                        == Some(SnippetConfig {
+                            fragment_size: Some(50),
+                            num_fragments: Some(3),
+                            pre_tag: Some("<mark>".to_string()),
+                            post_tag: None,
+                        })
+                },
+            ))
+            .returning(|_| Ok(Default::default()));
+        let rest_search_api_handler = search_handler(mock_search_service);
+        let resp = warp::test::request()
+            .path(
+                "/quickwit-demo-index/search?query=bar&snippet_fields=body&\
+                 snippet_fragment_size=50&snippet_num_fragments=3&snippet_pre_tag=%3Cmark%3E",
+            )
+            .reply(&rest_search_api_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+    }
+
+    #[tokio::test]
+    async fn test_rest_search_api_route_timeout() {
+        let mut mock_search_service = MockSearchService::new();
+        mock_search_service
+            .expect_root_search()
+            .with(predicate::function(
+                |search_request: &quickwit_proto::search::SearchRequest| {
+                    search_request.timeout_millis == Some(1_500)
+                },
+            ))
+            .returning(|_| {
+                Ok(quickwit_proto::search::SearchResponse {
+                    timed_out: true,
+                    ..Default::default()
+                })
+            });
+        let rest_search_api_handler = search_handler(mock_search_service);
+        let resp = warp::test::request()
+            .path("/quickwit-demo-index/search?query=bar&timeout=1500ms")
+            .reply(&rest_search_api_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let resp_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(resp_json["timed_out"], JsonValue::Bool(true));
+
+        let rest_search_api_handler = search_handler(MockSearchService::new());
+        let resp = warp::test::request()
+            .path("/quickwit-demo-index/search?query=bar&timeout=soon")
+            .reply(&rest_search_api_handler)
+            .await;
+        assert_eq!(resp.status(), 400);
+    }
+
+    #[tokio::test]
+    async fn test_rest_search_api_route_profile() {
+        let mut mock_search_service = MockSearchService::new();
+        mock_search_service
+            .expect_root_search()
+            .with(predicate::function(
+                |search_request: &quickwit_proto::search::SearchRequest| search_request.profile,
+            ))
+            .returning(|_| {
+                Ok(quickwit_proto::search::SearchResponse {
+                    profile: Some(SearchProfile {
+                        split_profiles: vec![SplitSearchProfile {
+                            split_id: "split-1".to_string(),
+                            num_docs_matched: 3,
+                            ..Default::default()
+                        }],
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                })
+            });
+        let rest_search_api_handler = search_handler(mock_search_service);
+        let resp = warp::test::request()
+            .path("/quickwit-demo-index/search?query=bar&profile=true")
+            .reply(&rest_search_api_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let resp_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        assert_json_include!(
+            actual: resp_json,
+            expected: json!({
+                "profile": {
+                    "queries": [],
+                    "split_profiles": [{"split_id": "split-1", "num_docs_matched": 3}]
+                }
+            })
+        );
+    }
+
     #[tokio::test]
     async fn test_rest_search_api_multi_indexes() {
         {
@@ -1242,4 +1670,110 @@ mod tests {
             );
         }
     }
+
+    fn async_search_status_for_test(state: AsyncSearchState) -> AsyncSearchStatus {
+        AsyncSearchStatus {
+            id: "01HAV29D4XY3D462FS3D8K5Q2H".to_string(),
+            state,
+            start_time_millis: 1_000,
+            expiration_time_millis: 3_601_000,
+            completion_time_millis: None,
+            num_splits_total: 4,
+            num_splits_done: 1,
+            response: Some(quickwit_proto::search::SearchResponse {
+                num_hits: 10,
+                aggregation: Some(r#"{"count":{"value":10.0}}"#.to_string()),
+                ..Default::default()
+            }),
+            error: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rest_async_search_api() {
+        let mut mock_search_service = MockSearchService::new();
+        mock_search_service
+            .expect_submit_async_search()
+            .with(predicate::function(
+                |submit_request: &SubmitAsyncSearchRequest| {
+                    submit_request.search_request.index_id_patterns
+                        == vec!["quickwit-demo-index".to_string()]
+                        && submit_request.wait_for_completion_timeout == Duration::from_millis(500)
+                        && submit_request.keep_alive == DEFAULT_ASYNC_SEARCH_KEEP_ALIVE
+                },
+            ))
+            .returning(|_| Ok(async_search_status_for_test(AsyncSearchState::Running)));
+        mock_search_service
+            .expect_get_async_search()
+            .with(predicate::eq("01HAV29D4XY3D462FS3D8K5Q2H".to_string()))
+            .returning(|_| Ok(async_search_status_for_test(AsyncSearchState::Running)));
+        mock_search_service
+            .expect_cancel_async_search()
+            .with(predicate::eq("01HAV29D4XY3D462FS3D8K5Q2H".to_string()))
+            .returning(|_| {
+                let mut async_search_status =
+                    async_search_status_for_test(AsyncSearchState::Cancelled);
+                async_search_status.response = None;
+                Ok(async_search_status)
+            });
+        let mock_search_service_in_arc: Arc<dyn SearchService> = Arc::new(mock_search_service);
+        let rest_search_api_handler =
+            async_search_submit_handler(mock_search_service_in_arc.clone())
+                .or(async_search_get_handler(mock_search_service_in_arc.clone()))
+                .or(async_search_delete_handler(mock_search_service_in_arc))
+                .recover(recover_fn);
+
+        let resp = warp::test::request()
+            .method("POST")
+            .path("/quickwit-demo-index/async-search?wait_for_completion_timeout=500ms")
+            .json(&true)
+            .body(r#"{"query": "*", "max_hits": 0, "aggs": {"count": {"value_count": {"field": "id"}}}}"#)
+            .reply(&rest_search_api_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let resp_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        let expected_resp_json = json!({
+            "id": "01HAV29D4XY3D462FS3D8K5Q2H",
+            "state": "running",
+            "start_time_millis": 1_000,
+            "expiration_time_millis": 3_601_000,
+            "num_splits_total": 4,
+            "num_splits_done": 1,
+            "response": {
+                "num_hits": 10,
+                "hits": [],
+                "elapsed_time_micros": 0,
+                "errors": [],
+                "aggregations": {"count": {"value": 10.0}}
+            }
+        });
+        assert_json_eq!(resp_json, expected_resp_json);
+
+        let resp = warp::test::request()
+            .path("/async-search/01HAV29D4XY3D462FS3D8K5Q2H")
+            .reply(&rest_search_api_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let resp_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        assert_json_eq!(resp_json, expected_resp_json);
+
+        let resp = warp::test::request()
+            .method("DELETE")
+            .path("/async-search/01HAV29D4XY3D462FS3D8K5Q2H")
+            .reply(&rest_search_api_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let resp_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(resp_json["state"], JsonValue::from("cancelled"));
+        assert!(resp_json.get("response").is_none());
+
+        let resp = warp::test::request()
+            .method("POST")
+            .path("/quickwit-demo-index/async-search?keep_alive=forever")
+            .json(&true)
+            .body(r#"{"query": "*"}"#)
+            .reply(&rest_search_api_handler)
+            .await;
+        assert_eq!(resp.status(), 400);
+    }
 }
@@ -0,0 +1,244 @@
+// Copyright (C) 2023 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::sync::Arc;
+
+use hyper::header::CONTENT_TYPE;
+use quickwit_config::validate_index_id_pattern;
+use quickwit_search::{SearchError, SearchService, SqlColumn, SqlPlan, SqlResponse};
+use serde::Deserialize;
+use tracing::info;
+use warp::{reply, Filter, Rejection, Reply};
+
+use crate::json_api_response::make_json_api_response;
+use crate::{with_arg, BodyFormat};
+
+#[derive(utoipa::OpenApi)]
+#[openapi(
+    paths(sql_handler),
+    components(schemas(SqlColumn, SqlOutputFormat, SqlRequest, SqlResponse))
+)]
+pub struct SqlApi;
+
+/// Body of a SQL query request.
+#[derive(Debug, Eq, PartialEq, Deserialize, utoipa::ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SqlRequest {
+    /// The SQL query, e.g. `SELECT service, COUNT(*) FROM my-index GROUP BY service`.
+    pub query: String,
+}
+
+/// Format of the table returned by the SQL endpoint.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SqlOutputFormat {
+    /// JSON object holding the columns and the rows of the table.
+    #[default]
+    Json,
+    /// CSV with a header row.
+    Csv,
+}
+
+#[derive(Debug, Default, Eq, PartialEq, Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(deny_unknown_fields)]
+struct SqlQueryString {
+    /// The output format. By default, JSON.
+    #[serde(default)]
+    format: SqlOutputFormat,
+}
+
+#[utoipa::path(
+    post,
+    tag = "Search",
+    path = "/_sql",
+    request_body = SqlRequest,
+    responses(
+        (status = 200, description = "Successfully executed the SQL query.", body = SqlResponse)
+    ),
+    params(SqlQueryString)
+)]
+/// Execute SQL Query
+///
+/// Compiles a SQL `SELECT` statement into a search request, with a query derived from the `WHERE`
+/// clause and aggregations derived from the `GROUP BY` clause and aggregate functions, then
+/// returns its results as a table.
+pub(crate) fn sql_handler(
+    search_service: Arc<dyn SearchService>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    sql_filter().and(with_arg(search_service)).then(sql)
+}
+
+fn sql_filter() -> impl Filter<Extract = (SqlQueryString, SqlRequest), Error = Rejection> + Clone {
+    warp::path!("_sql")
+        .and(warp::post())
+        .and(serde_qs::warp::query(serde_qs::Config::default()))
+        .and(warp::body::content_length_limit(1024 * 1024))
+        .and(warp::body::json())
+}
+
+async fn sql(
+    query_string: SqlQueryString,
+    sql_request: SqlRequest,
+    search_service: Arc<dyn SearchService>,
+) -> warp::reply::Response {
+    info!(request =? sql_request, "sql");
+    let result = sql_endpoint(sql_request, &*search_service).await;
+
+    match (query_string.format, result) {
+        (SqlOutputFormat::Csv, Ok(sql_response)) => {
+            reply::with_header(sql_response.to_csv(), CONTENT_TYPE, "text/csv").into_response()
+        }
+        (_, result) => make_json_api_response(result, BodyFormat::default()).into_response(),
+    }
+}
+
+async fn sql_endpoint(
+    sql_request: SqlRequest,
+    search_service: &dyn SearchService,
+) -> Result<SqlResponse, SearchError> {
+    let sql_plan = SqlPlan::parse(&sql_request.query)?;
+
+    for index_id_pattern in &sql_plan.search_request().index_id_patterns {
+        validate_index_id_pattern(index_id_pattern)
+            .map_err(|error| SearchError::InvalidArgument(error.to_string()))?;
+    }
+    let search_response = search_service
+        .root_search(sql_plan.search_request().clone())
+        .await?;
+    sql_plan.into_sql_response(search_response)
+}
+
+#[cfg(test)]
+mod tests {
+    use mockall::predicate;
+    use quickwit_proto::search::{Hit, SearchRequest, SearchResponse};
+    use quickwit_search::MockSearchService;
+    use serde_json::{json, Value as JsonValue};
+
+    use super::*;
+    use crate::recover_fn;
+
+    fn sql_handler_with_mock(
+        mock_search_service: MockSearchService,
+    ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+        sql_handler(Arc::new(mock_search_service)).recover(recover_fn)
+    }
+
+    #[tokio::test]
+    async fn test_sql_api_documents() {
+        let mut mock_search_service = MockSearchService::new();
+        mock_search_service
+            .expect_root_search()
+            .with(predicate::function(|search_request: &SearchRequest| {
+                search_request.index_id_patterns == ["my-index"]
+                    && search_request.max_hits == 10
+                    && search_request.sort_fields.len() == 1
+            }))
+            .returning(|_| {
+                Ok(SearchResponse {
+                    num_hits: 1,
+                    hits: vec![Hit {
+                        json: r#"{"service": "api", "latency": 12}"#.to_string(),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                })
+            });
+        let sql_handler = sql_handler_with_mock(mock_search_service);
+        let resp = warp::test::request()
+            .path("/_sql")
+            .method("POST")
+            .json(&json!({
+                "query": "SELECT service, latency FROM my-index WHERE latency > 10 ORDER BY \
+                          latency DESC LIMIT 10"
+            }))
+            .reply(&sql_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let resp_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(
+            resp_json,
+            json!({
+                "columns": [{"name": "service"}, {"name": "latency"}],
+                "rows": [["api", 12]],
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sql_api_group_by_csv() {
+        let mut mock_search_service = MockSearchService::new();
+        mock_search_service
+            .expect_root_search()
+            .with(predicate::function(|search_request: &SearchRequest| {
+                search_request.max_hits == 0 && search_request.aggregation_request.is_some()
+            }))
+            .returning(|_| {
+                let aggregation = json!({
+                    "group_0": {
+                        "buckets": [
+                            {"key": "api", "doc_count": 3, "metric_0": {"value": 12.5}},
+                            {"key": "db", "doc_count": 1, "metric_0": {"value": 3.0}},
+                        ]
+                    }
+                });
+                Ok(SearchResponse {
+                    num_hits: 4,
+                    aggregation: Some(aggregation.to_string()),
+                    ..Default::default()
+                })
+            });
+        let sql_handler = sql_handler_with_mock(mock_search_service);
+        let resp = warp::test::request()
+            .path("/_sql?format=csv")
+            .method("POST")
+            .json(&json!({
+                "query": "SELECT service, COUNT(*), AVG(latency) FROM my-index GROUP BY service"
+            }))
+            .reply(&sql_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+        assert_eq!(resp.headers().get("content-type").unwrap(), "text/csv");
+        assert_eq!(
+            std::str::from_utf8(resp.body()).unwrap(),
+            "service,count(*),avg(latency)\napi,3,12.5\ndb,1,3.0\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sql_api_invalid_query() {
+        let mock_search_service = MockSearchService::new();
+        let sql_handler = sql_handler_with_mock(mock_search_service);
+        let resp = warp::test::request()
+            .path("/_sql?format=csv")
+            .method("POST")
+            .json(&json!({"query": "SELECT * FROM my-index WHERE"}))
+            .reply(&sql_handler)
+            .await;
+        assert_eq!(resp.status(), 400);
+        let resp_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(
+            resp_json,
+            json!({
+                "message": "invalid SQL query: expected an identifier, found end of query"
+            })
+        );
+    }
+}
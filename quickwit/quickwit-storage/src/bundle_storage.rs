@@ -295,6 +295,19 @@ impl Storage for BundleStorage {
         Ok(file_range.end - file_range.start)
     }
 
+    async fn list(&self, prefix: &Path) -> StorageResult<Vec<PathBuf>> {
+        let prefix_str = prefix.to_string_lossy();
+        let mut paths: Vec<PathBuf> = self
+            .metadata
+            .files
+            .keys()
+            .filter(|path| path.to_string_lossy().starts_with(prefix_str.as_ref()))
+            .cloned()
+            .collect();
+        paths.sort();
+        Ok(paths)
+    }
+
     fn uri(&self) -> &Uri {
         self.storage.uri()
     }
@@ -19,7 +19,7 @@
 
 use std::fmt;
 use std::ops::Range;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use async_trait::async_trait;
@@ -109,6 +109,10 @@ impl Storage for StorageWithCache {
         self.storage.file_num_bytes(path).await
     }
 
+    async fn list(&self, prefix: &Path) -> StorageResult<Vec<PathBuf>> {
+        self.storage.list(prefix).await
+    }
+
     fn uri(&self) -> &Uri {
         self.storage.uri()
     }
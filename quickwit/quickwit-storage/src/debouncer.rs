@@ -199,6 +199,10 @@ impl<T: Storage> Storage for DebouncedStorage<T> {
     async fn file_num_bytes(&self, path: &Path) -> StorageResult<u64> {
         self.underlying.file_num_bytes(path).await
     }
+
+    async fn list(&self, prefix: &Path) -> StorageResult<Vec<PathBuf>> {
+        self.underlying.list(prefix).await
+    }
 }
 
 #[cfg(test)]
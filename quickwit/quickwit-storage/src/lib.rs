@@ -52,6 +52,7 @@ mod split;
 mod split_cache;
 mod storage_factory;
 mod storage_resolver;
+mod storage_with_read_stats;
 mod versioned_component;
 
 use quickwit_common::uri::Uri;
@@ -79,6 +80,7 @@ pub use self::storage::MockStorage;
 pub use self::storage_factory::MockStorageFactory;
 pub use self::storage_factory::{StorageFactory, UnsupportedStorage};
 pub use self::storage_resolver::StorageResolver;
+pub use self::storage_with_read_stats::{wrap_storage_with_read_stats, ReadStats};
 #[cfg(feature = "testsuite")]
 pub use self::test_suite::{
     storage_test_multi_part_upload, storage_test_single_part_upload, storage_test_suite,
@@ -302,6 +304,29 @@ pub(crate) mod test_suite {
         Ok(())
     }
 
+    async fn test_write_and_list(storage: &mut dyn Storage) -> anyhow::Result<()> {
+        let test_paths = [
+            Path::new("list/foo/bar"),
+            Path::new("list/foo/baz/qux"),
+            Path::new("list/foobar"),
+            Path::new("list/qux"),
+        ];
+        for test_path in test_paths {
+            storage.put(test_path, Box::new(b"123".to_vec())).await?;
+        }
+        let paths = storage.list(Path::new("list/foo")).await?;
+        assert_eq!(paths, &test_paths[..3]);
+
+        let paths = storage.list(Path::new("list/")).await?;
+        assert_eq!(paths, &test_paths);
+
+        let paths = storage.list(Path::new("list/missing")).await?;
+        assert!(paths.is_empty());
+
+        storage.bulk_delete(&test_paths).await?;
+        Ok(())
+    }
+
     /// Generic test suite for a storage.
     pub async fn storage_test_suite(storage: &mut dyn Storage) -> anyhow::Result<()> {
         test_get_inexistent_file(storage)
@@ -328,6 +353,9 @@ pub(crate) mod test_suite {
             .await
             .context("write_and_delete_with_separator")?;
         test_file_size(storage).await.context("file_size")?;
+        test_write_and_list(storage)
+            .await
+            .context("write_and_list")?;
         test_delete_missing_file(storage)
             .await
             .context("delete_missing_file")?;
@@ -344,6 +344,50 @@ impl Storage for LocalFileStorage {
             }
         }
     }
+
+    async fn list(&self, prefix: &Path) -> StorageResult<Vec<PathBuf>> {
+        ensure_valid_relative_path(prefix)?;
+        let prefix_str = prefix.to_string_lossy();
+        // The prefix does not necessarily end with a directory name, so we walk its parent
+        // directory and filter the files afterwards.
+        let start_dir_path = prefix
+            .parent()
+            .map(|parent| self.root.join(parent))
+            .unwrap_or_else(|| self.root.clone());
+        let mut dir_paths = vec![start_dir_path];
+        let mut paths = Vec::new();
+
+        while let Some(dir_path) = dir_paths.pop() {
+            let mut dir_entries = match tokio::fs::read_dir(&dir_path).await {
+                Ok(dir_entries) => dir_entries,
+                Err(err) if err.kind() == ErrorKind::NotFound => continue,
+                Err(err) => return Err(err.into()),
+            };
+            while let Some(dir_entry) = dir_entries.next_entry().await? {
+                let file_type = dir_entry.file_type().await?;
+                let entry_path = dir_entry.path();
+
+                if file_type.is_dir() {
+                    dir_paths.push(entry_path);
+                    continue;
+                }
+                if !file_type.is_file() {
+                    continue;
+                }
+                let Ok(relative_path) = entry_path.strip_prefix(&self.root) else {
+                    continue;
+                };
+                if relative_path
+                    .to_string_lossy()
+                    .starts_with(prefix_str.as_ref())
+                {
+                    paths.push(relative_path.to_path_buf());
+                }
+            }
+        }
+        paths.sort();
+        Ok(paths)
+    }
 }
 
 /// A File storage resolver
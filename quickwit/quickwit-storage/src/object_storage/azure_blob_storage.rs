@@ -499,6 +499,27 @@ impl Storage for AzureBlobStorage {
         }
     }
 
+    async fn list(&self, prefix: &Path) -> StorageResult<Vec<PathBuf>> {
+        let name_prefix = self.blob_name(prefix);
+        let mut blob_pages = self
+            .container_client
+            .list_blobs()
+            .prefix(name_prefix)
+            .into_stream();
+        let mut paths = Vec::new();
+
+        while let Some(blob_page_result) = blob_pages.next().await {
+            let blob_page = blob_page_result.map_err(AzureErrorWrapper::from)?;
+
+            for blob in blob_page.blobs.blobs() {
+                if let Ok(path) = Path::new(&blob.name).strip_prefix(&self.prefix) {
+                    paths.push(path.to_path_buf());
+                }
+            }
+        }
+        Ok(paths)
+    }
+
     fn uri(&self) -> &Uri {
         &self.uri
     }
@@ -119,6 +119,21 @@ impl Storage for PrefixStorage {
     async fn file_num_bytes(&self, path: &Path) -> crate::StorageResult<u64> {
         self.storage.file_num_bytes(&self.prefix.join(path)).await
     }
+
+    async fn list(&self, prefix: &Path) -> crate::StorageResult<Vec<PathBuf>> {
+        let paths = self
+            .storage
+            .list(&self.prefix.join(prefix))
+            .await?
+            .into_iter()
+            .filter_map(|path| {
+                path.strip_prefix(&self.prefix)
+                    .ok()
+                    .map(|path| path.to_path_buf())
+            })
+            .collect();
+        Ok(paths)
+    }
 }
 
 /// Creates a [`PrefixStorage`] using an underlying storage and a prefix.
@@ -160,6 +160,20 @@ impl Storage for RamStorage {
             Err(StorageErrorKind::NotFound.with_error(err))
         }
     }
+
+    async fn list(&self, prefix: &Path) -> StorageResult<Vec<PathBuf>> {
+        let prefix_str = prefix.to_string_lossy();
+        let mut paths: Vec<PathBuf> = self
+            .files
+            .read()
+            .await
+            .keys()
+            .filter(|path| path.to_string_lossy().starts_with(prefix_str.as_ref()))
+            .cloned()
+            .collect();
+        paths.sort();
+        Ok(paths)
+    }
 }
 
 /// Builder to create a prepopulated [`RamStorage`]. This is mostly useful for tests.
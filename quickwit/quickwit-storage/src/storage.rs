@@ -138,6 +138,12 @@ pub trait Storage: fmt::Debug + Send + Sync + 'static {
     /// Returns a file size.
     async fn file_num_bytes(&self, path: &Path) -> StorageResult<u64>;
 
+    /// Lists the files whose path starts with `prefix`, in lexicographical order.
+    ///
+    /// Like object storage, the prefix is matched as a string rather than as a directory:
+    /// `foo/ba` matches both `foo/bar` and `foo/baz/qux`.
+    async fn list(&self, prefix: &Path) -> StorageResult<Vec<PathBuf>>;
+
     /// Returns an URI identifying the storage
     fn uri(&self) -> &Uri;
 }
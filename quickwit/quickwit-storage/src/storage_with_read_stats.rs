@@ -0,0 +1,186 @@
+// Copyright (C) 2023 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::fmt;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use quickwit_common::uri::Uri;
+use tokio::io::AsyncRead;
+
+use crate::storage::SendableAsync;
+use crate::{BulkDeleteError, OwnedBytes, Storage, StorageResult};
+
+/// Number of reads and number of bytes read through a [`Storage`] wrapped with
+/// [`wrap_storage_with_read_stats`].
+#[derive(Debug, Default)]
+pub struct ReadStats {
+    num_reads: AtomicU64,
+    num_bytes: AtomicU64,
+}
+
+impl ReadStats {
+    /// Returns the number of reads performed so far.
+    pub fn num_reads(&self) -> u64 {
+        self.num_reads.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of bytes read so far.
+    pub fn num_bytes(&self) -> u64 {
+        self.num_bytes.load(Ordering::Relaxed)
+    }
+
+    fn record_read(&self, num_bytes: usize) {
+        self.num_reads.fetch_add(1, Ordering::Relaxed);
+        self.num_bytes
+            .fetch_add(num_bytes as u64, Ordering::Relaxed);
+    }
+}
+
+/// This storage acts as a proxy to another storage, recording the reads
+/// going through it in a [`ReadStats`].
+struct StorageWithReadStats {
+    storage: Arc<dyn Storage>,
+    read_stats: Arc<ReadStats>,
+}
+
+impl fmt::Debug for StorageWithReadStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StorageWithReadStats")
+            .field("read_stats", &self.read_stats)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl Storage for StorageWithReadStats {
+    async fn check_connectivity(&self) -> anyhow::Result<()> {
+        self.storage.check_connectivity().await
+    }
+
+    async fn put(
+        &self,
+        path: &Path,
+        payload: Box<dyn crate::PutPayload>,
+    ) -> crate::StorageResult<()> {
+        self.storage.put(path, payload).await
+    }
+
+    async fn copy_to(&self, path: &Path, output: &mut dyn SendableAsync) -> StorageResult<()> {
+        self.storage.copy_to(path, output).await
+    }
+
+    async fn get_slice(&self, path: &Path, range: Range<usize>) -> StorageResult<OwnedBytes> {
+        let bytes = self.storage.get_slice(path, range).await?;
+        self.read_stats.record_read(bytes.len());
+        Ok(bytes)
+    }
+
+    async fn get_slice_stream(
+        &self,
+        path: &Path,
+        range: Range<usize>,
+    ) -> StorageResult<Box<dyn AsyncRead + Send + Unpin>> {
+        let num_bytes = range.len();
+        let stream = self.storage.get_slice_stream(path, range).await?;
+        self.read_stats.record_read(num_bytes);
+        Ok(stream)
+    }
+
+    async fn get_all(&self, path: &Path) -> StorageResult<OwnedBytes> {
+        let bytes = self.storage.get_all(path).await?;
+        self.read_stats.record_read(bytes.len());
+        Ok(bytes)
+    }
+
+    async fn delete(&self, path: &Path) -> StorageResult<()> {
+        self.storage.delete(path).await
+    }
+
+    async fn bulk_delete<'a>(&self, paths: &[&'a Path]) -> Result<(), BulkDeleteError> {
+        self.storage.bulk_delete(paths).await
+    }
+
+    async fn exists(&self, path: &Path) -> StorageResult<bool> {
+        self.storage.exists(path).await
+    }
+
+    async fn file_num_bytes(&self, path: &Path) -> StorageResult<u64> {
+        self.storage.file_num_bytes(path).await
+    }
+
+    async fn list(&self, prefix: &Path) -> StorageResult<Vec<PathBuf>> {
+        self.storage.list(prefix).await
+    }
+
+    fn uri(&self) -> &Uri {
+        self.storage.uri()
+    }
+}
+
+/// Wraps a storage, recording the reads going through it in the given [`ReadStats`].
+pub fn wrap_storage_with_read_stats(
+    storage: Arc<dyn Storage>,
+    read_stats: Arc<ReadStats>,
+) -> Arc<dyn Storage> {
+    Arc::new(StorageWithReadStats {
+        storage,
+        read_stats,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RamStorage;
+
+    #[tokio::test]
+    async fn test_storage_with_read_stats() {
+        let ram_storage = RamStorage::builder()
+            .put("path1", b"path1_payload")
+            .put("path2", b"path2_payload")
+            .build();
+        let read_stats = Arc::new(ReadStats::default());
+        let storage = wrap_storage_with_read_stats(Arc::new(ram_storage), read_stats.clone());
+        assert_eq!(read_stats.num_reads(), 0);
+        assert_eq!(read_stats.num_bytes(), 0);
+
+        let bytes = storage.get_slice(Path::new("path1"), 0..5).await.unwrap();
+        assert_eq!(bytes.as_slice(), b"path1");
+        assert_eq!(read_stats.num_reads(), 1);
+        assert_eq!(read_stats.num_bytes(), 5);
+
+        storage.get_all(Path::new("path2")).await.unwrap();
+        assert_eq!(read_stats.num_reads(), 2);
+        assert_eq!(read_stats.num_bytes(), 18);
+
+        // Failed reads are not recorded.
+        storage
+            .get_slice(Path::new("missing"), 0..5)
+            .await
+            .unwrap_err();
+        assert_eq!(read_stats.num_reads(), 2);
+
+        assert!(storage.exists(Path::new("path1")).await.unwrap());
+        assert_eq!(read_stats.num_reads(), 2);
+    }
+}